@@ -7,7 +7,7 @@ use std::collections::{BTreeMap, HashMap};
 
 use async_nats::Subscriber;
 use cloudevents::event::Event;
-use futures::{StreamExt, TryFutureExt};
+use futures::{FutureExt, StreamExt, TryFutureExt};
 use serde::de::DeserializeOwned;
 use tokio::sync::mpsc::Receiver;
 use tracing::{debug, error, instrument, trace};
@@ -18,6 +18,9 @@ use crate::types::ctl::{
 };
 use crate::types::host::{Host, HostInventory, HostLabel};
 use crate::types::link::Link;
+use crate::types::provider::{
+    provider_log_history_subject, provider_logs_subject, ProviderLogHistoryRequest, ProviderLogLine,
+};
 use crate::types::registry::RegistryCredential;
 use crate::types::rpc::{
     ComponentAuctionAck, ComponentAuctionRequest, DeleteInterfaceLinkDefinitionRequest,
@@ -651,6 +654,51 @@ impl Client {
         provider_id: &str,
         annotations: Option<BTreeMap<String, String>>,
         provider_configuration: Vec<String>,
+    ) -> Result<CtlResponse<()>> {
+        self.send_start_provider(
+            host_id,
+            provider_ref,
+            provider_id,
+            annotations,
+            provider_configuration,
+            None,
+        )
+        .await
+    }
+
+    /// Same as [`Client::start_provider`], but the host gives up pulling the provider after
+    /// `pull_timeout`. The host reports a pull that fails or runs out of time in the
+    /// `provider_start_failed` event with a `phase` of `pull`, and a failure to launch the pulled
+    /// provider with a `phase` of `launch`
+    #[instrument(level = "debug", skip_all)]
+    pub async fn start_provider_with_pull_timeout(
+        &self,
+        host_id: &str,
+        provider_ref: &str,
+        provider_id: &str,
+        annotations: Option<BTreeMap<String, String>>,
+        provider_configuration: Vec<String>,
+        pull_timeout: Duration,
+    ) -> Result<CtlResponse<()>> {
+        self.send_start_provider(
+            host_id,
+            provider_ref,
+            provider_id,
+            annotations,
+            provider_configuration,
+            Some(pull_timeout),
+        )
+        .await
+    }
+
+    async fn send_start_provider(
+        &self,
+        host_id: &str,
+        provider_ref: &str,
+        provider_id: &str,
+        annotations: Option<BTreeMap<String, String>>,
+        provider_configuration: Vec<String>,
+        pull_timeout: Option<Duration>,
     ) -> Result<CtlResponse<()>> {
         let host_id = IdentifierKind::is_host_id(host_id)?;
         let subject = broker::v1::commands::start_provider(
@@ -666,6 +714,9 @@ impl Client {
         if let Some(annotations) = annotations {
             cmd = cmd.annotations(annotations);
         }
+        if let Some(pull_timeout) = pull_timeout {
+            cmd = cmd.pull_timeout_ms(u64::try_from(pull_timeout.as_millis()).unwrap_or(u64::MAX));
+        }
         let cmd = cmd.config(provider_configuration).build()?;
         let bytes = json_serialize(cmd)?;
 
@@ -796,12 +847,56 @@ impl Client {
     ///
     #[allow(clippy::missing_errors_doc)] // TODO: Document errors
     pub async fn events_receiver(&self, event_types: Vec<String>) -> Result<Receiver<Event>> {
+        self.events_receiver_with_queue_group(event_types, None)
+            .await
+    }
+
+    /// The subjects (and queue group) to subscribe to for the given event types
+    fn event_subscriptions(
+        &self,
+        event_types: Vec<String>,
+        queue_group: Option<String>,
+    ) -> Vec<(String, Option<String>)> {
+        event_types
+            .into_iter()
+            .map(|event_type| {
+                (
+                    format!("wasmbus.evt.{}.{}", self.lattice, event_type),
+                    queue_group.clone(),
+                )
+            })
+            .collect()
+    }
+
+    /// Like [`Client::events_receiver`], but optionally subscribes to the events as part of a NATS
+    /// queue group.
+    ///
+    /// Without a queue group every receiver gets every event. With one, each event is delivered to
+    /// only one of the receivers subscribed with the same group name, which distributes event
+    /// handling across them. A receiver in a queue group will therefore not see every event.
+    ///
+    /// # Arguments
+    ///
+    /// * `event_types` - List of types of events to listen for
+    /// * `queue_group` - Name of the queue group to subscribe with, if any
+    ///
+    #[allow(clippy::missing_errors_doc)] // TODO: Document errors
+    pub async fn events_receiver_with_queue_group(
+        &self,
+        event_types: Vec<String>,
+        queue_group: Option<String>,
+    ) -> Result<Receiver<Event>> {
         let (sender, receiver) = tokio::sync::mpsc::channel(5000);
-        let futs = event_types.into_iter().map(|event_type| {
-            self.nc
-                .subscribe(format!("wasmbus.evt.{}.{}", self.lattice, event_type))
-                .map_err(|err| Box::new(err) as Box<dyn std::error::Error + Send + Sync>)
-        });
+        let futs = self
+            .event_subscriptions(event_types, queue_group)
+            .into_iter()
+            .map(|(subject, queue_group)| {
+                let sub = match queue_group {
+                    Some(group) => self.nc.queue_subscribe(subject, group).left_future(),
+                    None => self.nc.subscribe(subject).right_future(),
+                };
+                sub.map_err(|err| Box::new(err) as Box<dyn std::error::Error + Send + Sync>)
+            });
         let subs: Vec<Subscriber> = futures::future::join_all(futs)
             .await
             .into_iter()
@@ -821,6 +916,66 @@ impl Client {
         });
         Ok(receiver)
     }
+
+    /// Returns a receiver for the lines of output that a provider writes, either on a single host
+    /// or on every host running the provider. Only lines written after subscribing are received.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider_id` - ID of the provider to receive the output of
+    /// * `host_id` - Only receive the output of the provider on this host
+    ///
+    #[allow(clippy::missing_errors_doc)] // TODO: Document errors
+    pub async fn provider_logs_receiver(
+        &self,
+        provider_id: &str,
+        host_id: Option<&str>,
+    ) -> Result<Receiver<ProviderLogLine>> {
+        let mut sub = self
+            .nc
+            .subscribe(provider_logs_subject(&self.lattice, provider_id, host_id))
+            .await?;
+        let (sender, receiver) = tokio::sync::mpsc::channel(5000);
+        tokio::spawn(async move {
+            while let Some(msg) = sub.next().await {
+                let Ok(line) = json_deserialize::<ProviderLogLine>(&msg.payload) else {
+                    error!("Object received on provider log stream was not a log line");
+                    continue;
+                };
+                let Ok(()) = sender.send(line).await else {
+                    break;
+                };
+            }
+        });
+        Ok(receiver)
+    }
+
+    /// Retrieves the recent lines of output of a provider that a host still holds, oldest first.
+    /// Only hosts that forward provider logs hold lines, and only while they run the provider
+    ///
+    /// # Arguments
+    ///
+    /// * `provider_id` - ID of the provider to retrieve the output of
+    /// * `host_id` - The host running the provider
+    /// * `since` - Only retrieve lines the host read at most this long ago
+    ///
+    #[allow(clippy::missing_errors_doc)] // TODO: Document errors
+    #[instrument(level = "debug", skip_all)]
+    pub async fn get_provider_log_history(
+        &self,
+        provider_id: &str,
+        host_id: &str,
+        since: Option<Duration>,
+    ) -> Result<Vec<ProviderLogLine>> {
+        let subject = provider_log_history_subject(&self.lattice, provider_id, host_id);
+        debug!("get_provider_log_history:request {}", &subject);
+        let since_ms = since.map(|since| u64::try_from(since.as_millis()).unwrap_or(u64::MAX));
+        let bytes = json_serialize(ProviderLogHistoryRequest::new(since_ms))?;
+        match self.request_timeout(subject, bytes, self.timeout).await {
+            Ok(msg) => Ok(json_deserialize(&msg.payload)?),
+            Err(e) => Err(format!("Did not receive provider log history from host: {e}").into()),
+        }
+    }
 }
 
 /// Collect `T` values until timeout has elapsed
@@ -885,6 +1040,39 @@ mod tests {
         tokio::time::sleep(Duration::from_secs(120)).await;
     }
 
+    #[tokio::test]
+    async fn event_subscriptions_use_queue_group() {
+        // The client never connects, it only needs to exist to build the subjects
+        let nc = async_nats::ConnectOptions::new()
+            .retry_on_initial_connect()
+            .connect("127.0.0.1:1")
+            .await
+            .unwrap();
+        let client = ClientBuilder::new(nc).lattice("prod").build();
+        let events = vec![
+            "provider_started".to_string(),
+            "provider_start_failed".to_string(),
+        ];
+
+        assert_eq!(
+            client.event_subscriptions(events.clone(), Some("automation".to_string())),
+            vec![
+                (
+                    "wasmbus.evt.prod.provider_started".to_string(),
+                    Some("automation".to_string())
+                ),
+                (
+                    "wasmbus.evt.prod.provider_start_failed".to_string(),
+                    Some("automation".to_string())
+                ),
+            ]
+        );
+        assert!(client
+            .event_subscriptions(events, None)
+            .iter()
+            .all(|(_, group)| group.is_none()));
+    }
+
     #[test]
     fn test_check_identifier() -> Result<()> {
         assert!(IdentifierKind::is_host_id("").is_err());
@@ -264,6 +264,11 @@ pub struct StartProviderCommand {
     /// example, autonomous agents may wish to "tag" start requests as part of a given deployment
     #[serde(default, skip_serializing_if = "Option::is_none")]
     annotations: Option<BTreeMap<String, String>>,
+    /// Optional time, in milliseconds, the host may spend pulling the provider before giving up.
+    /// A pull that fails or runs out of time is reported as a failure of the pull phase rather
+    /// than of the launch. Hosts that don't support it pull without a limit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pull_timeout_ms: Option<u64>,
 }
 
 impl StartProviderCommand {
@@ -292,6 +297,11 @@ impl StartProviderCommand {
         self.annotations.as_ref()
     }
 
+    #[must_use]
+    pub fn pull_timeout_ms(&self) -> Option<u64> {
+        self.pull_timeout_ms
+    }
+
     #[must_use]
     pub fn builder() -> StartProviderCommandBuilder {
         StartProviderCommandBuilder::default()
@@ -307,6 +317,7 @@ pub struct StartProviderCommandBuilder {
     provider_ref: Option<String>,
     annotations: Option<BTreeMap<String, String>>,
     config: Option<Vec<String>>,
+    pull_timeout_ms: Option<u64>,
 }
 
 impl StartProviderCommandBuilder {
@@ -345,6 +356,12 @@ impl StartProviderCommandBuilder {
         self
     }
 
+    #[must_use]
+    pub fn pull_timeout_ms(mut self, v: u64) -> Self {
+        self.pull_timeout_ms = Some(v);
+        self
+    }
+
     pub fn build(self) -> Result<StartProviderCommand> {
         Ok(StartProviderCommand {
             provider_ref: self
@@ -358,6 +375,7 @@ impl StartProviderCommandBuilder {
                 .host_id
                 .ok_or_else(|| "host id is required for starting providers".to_string())?,
             config: self.config.unwrap_or_default(),
+            pull_timeout_ms: self.pull_timeout_ms,
         })
     }
 }
@@ -641,6 +659,7 @@ mod tests {
                 host_id: "host_id".into(),
                 config: vec!["p".into()],
                 annotations: Some(BTreeMap::from([("a".into(), "b".into())])),
+                pull_timeout_ms: Some(1000),
             },
             StartProviderCommand::builder()
                 .provider_id("provider_id")
@@ -648,6 +667,7 @@ mod tests {
                 .host_id("host_id")
                 .config(vec!["p".into()])
                 .annotations(BTreeMap::from([("a".into(), "b".into())]))
+                .pull_timeout_ms(1000)
                 .build()
                 .unwrap()
         )
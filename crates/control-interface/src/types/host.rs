@@ -51,6 +51,10 @@ pub struct Host {
     /// Current wasmCloud Host software version
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub(crate) version: Option<String>,
+
+    /// Whether the host publishes the output of the providers it runs
+    #[serde(default)]
+    pub(crate) provider_log_forwarding: bool,
 }
 
 impl Host {
@@ -104,6 +108,12 @@ impl Host {
         self.version.as_deref()
     }
 
+    /// Get whether the host publishes the output of the providers it runs. Hosts that predate
+    /// provider log forwarding report `false`
+    pub fn provider_log_forwarding(&self) -> bool {
+        self.provider_log_forwarding
+    }
+
     #[must_use]
     pub fn builder() -> HostBuilder {
         HostBuilder::default()
@@ -123,6 +133,7 @@ pub struct HostBuilder {
     uptime_human: Option<String>,
     uptime_seconds: Option<u64>,
     version: Option<String>,
+    provider_log_forwarding: bool,
 }
 
 impl HostBuilder {
@@ -186,6 +197,12 @@ impl HostBuilder {
         self
     }
 
+    #[must_use]
+    pub fn provider_log_forwarding(mut self, v: bool) -> Self {
+        self.provider_log_forwarding = v;
+        self
+    }
+
     pub fn build(self) -> Result<Host> {
         Ok(Host {
             friendly_name: self
@@ -204,10 +221,15 @@ impl HostBuilder {
                 .ok_or_else(|| "lattice is required".to_string())?,
             js_domain: self.js_domain,
             version: self.version,
+            provider_log_forwarding: self.provider_log_forwarding,
         })
     }
 }
 
+/// Feature advertised in [`HostInventory::features`] by hosts that act on the `pull_timeout_ms`
+/// of a [`StartProviderCommand`](crate::StartProviderCommand)
+pub const HOST_FEATURE_PROVIDER_PULL_TIMEOUT: &str = "provider_pull_timeout";
+
 /// Describes the known contents of a given host at the time of
 /// a query. Also used as a payload for the host heartbeat
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
@@ -243,6 +265,12 @@ pub struct HostInventory {
     /// The host uptime in seconds
     #[serde(default)]
     pub(crate) uptime_seconds: u64,
+
+    /// Optional request fields the host acts on, such as
+    /// [`HOST_FEATURE_PROVIDER_PULL_TIMEOUT`]. Hosts that predate a feature don't list it and
+    /// silently ignore its field
+    #[serde(default)]
+    pub(crate) features: Vec<String>,
 }
 
 impl HostInventory {
@@ -286,6 +314,16 @@ impl HostInventory {
         self.uptime_seconds
     }
 
+    /// Get the optional request fields the host acts on
+    pub fn features(&self) -> &[String] {
+        &self.features
+    }
+
+    /// Whether the host advertises the given feature
+    pub fn supports(&self, feature: &str) -> bool {
+        self.features.iter().any(|f| f == feature)
+    }
+
     #[must_use]
     pub fn builder() -> HostInventoryBuilder {
         HostInventoryBuilder::default()
@@ -303,6 +341,7 @@ pub struct HostInventoryBuilder {
     version: Option<String>,
     uptime_human: Option<String>,
     uptime_seconds: Option<u64>,
+    features: Option<Vec<String>>,
 }
 
 impl HostInventoryBuilder {
@@ -354,6 +393,12 @@ impl HostInventoryBuilder {
         self
     }
 
+    #[must_use]
+    pub fn features(mut self, v: Vec<String>) -> Self {
+        self.features = Some(v);
+        self
+    }
+
     pub fn build(self) -> Result<HostInventory> {
         Ok(HostInventory {
             components: self.components.unwrap_or_default(),
@@ -374,6 +419,7 @@ impl HostInventoryBuilder {
             uptime_seconds: self
                 .uptime_seconds
                 .ok_or_else(|| "uptime_seconds is required".to_string())?,
+            features: self.features.unwrap_or_default(),
         })
     }
 }
@@ -451,6 +497,7 @@ mod tests {
                 uptime_human: Some("t".into()),
                 uptime_seconds: 1,
                 version: Some("1.0.0".into()),
+                provider_log_forwarding: true,
             },
             Host::builder()
                 .rpc_host("rpc_host".into())
@@ -463,6 +510,7 @@ mod tests {
                 .uptime_human("t".into())
                 .uptime_seconds(1)
                 .version("1.0.0".into())
+                .provider_log_forwarding(true)
                 .build()
                 .unwrap()
         )
@@ -479,7 +527,8 @@ mod tests {
                 labels: BTreeMap::from([("a".into(), "b".into())]),
                 version: "1.0.0".into(),
                 uptime_human: "t".into(),
-                uptime_seconds: 1
+                uptime_seconds: 1,
+                features: vec!["feature".into()],
             },
             HostInventory::builder()
                 .components(Vec::from([ComponentDescription::default()]))
@@ -490,6 +539,7 @@ mod tests {
                 .version("1.0.0".into())
                 .uptime_human("t".into())
                 .uptime_seconds(1)
+                .features(vec!["feature".into()])
                 .build()
                 .unwrap()
         )
@@ -120,11 +120,158 @@ impl ProviderDescriptionBuilder {
     }
 }
 
+/// The output of a provider process that a line was written to
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderLogStream {
+    #[default]
+    Stdout,
+    Stderr,
+}
+
+/// A line of output written by a provider process, published by the host running the provider on
+/// [`provider_logs_subject`] when the host forwards provider logs
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct ProviderLogLine {
+    /// The host the provider runs on
+    #[serde(default)]
+    pub(crate) host_id: String,
+    /// The provider that wrote the line
+    #[serde(default)]
+    pub(crate) provider_id: String,
+    /// Whether the line was written to stdout or stderr
+    #[serde(default)]
+    pub(crate) stream: ProviderLogStream,
+    /// The line, without its trailing newline
+    #[serde(default)]
+    pub(crate) line: String,
+    /// Whether the line was cut short because it was longer than the host forwards
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) truncated: bool,
+    /// When the host read the line, in milliseconds since the Unix epoch
+    #[serde(default)]
+    pub(crate) timestamp: u64,
+    /// Position of the line among the lines of the provider on its host, starting at 1
+    #[serde(default)]
+    pub(crate) seq: u64,
+}
+
+impl ProviderLogLine {
+    #[must_use]
+    pub fn new(
+        host_id: &str,
+        provider_id: &str,
+        stream: ProviderLogStream,
+        line: impl Into<String>,
+    ) -> Self {
+        Self {
+            host_id: host_id.into(),
+            provider_id: provider_id.into(),
+            stream,
+            line: line.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Mark the line as cut short
+    #[must_use]
+    pub fn truncated(mut self, truncated: bool) -> Self {
+        self.truncated = truncated;
+        self
+    }
+
+    /// Set when the line was read and its position among the lines of the provider
+    #[must_use]
+    pub fn at(mut self, timestamp: u64, seq: u64) -> Self {
+        self.timestamp = timestamp;
+        self.seq = seq;
+        self
+    }
+
+    /// Get the ID of the host the provider runs on
+    pub fn host_id(&self) -> &str {
+        &self.host_id
+    }
+
+    /// Get the ID of the provider that wrote the line
+    pub fn provider_id(&self) -> &str {
+        &self.provider_id
+    }
+
+    /// Get the output the line was written to
+    pub fn stream(&self) -> ProviderLogStream {
+        self.stream
+    }
+
+    /// Get the line that was written
+    pub fn line(&self) -> &str {
+        &self.line
+    }
+
+    /// Get whether the line was cut short
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Get when the host read the line, in milliseconds since the Unix epoch
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    /// Get the position of the line among the lines of the provider on its host
+    pub fn seq(&self) -> u64 {
+        self.seq
+    }
+}
+
+/// A request for the recent lines of output of a provider that a host still holds, sent to
+/// [`provider_log_history_subject`]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct ProviderLogHistoryRequest {
+    /// Only return lines read at most this many milliseconds ago, as measured by the host. All
+    /// held lines are returned when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) since_ms: Option<u64>,
+}
+
+impl ProviderLogHistoryRequest {
+    #[must_use]
+    pub fn new(since_ms: Option<u64>) -> Self {
+        Self { since_ms }
+    }
+
+    /// Get how far back to return lines from, in milliseconds
+    pub fn since_ms(&self) -> Option<u64> {
+        self.since_ms
+    }
+}
+
+/// The subject a host publishes the output of a provider on. Without a `host_id`, this is a
+/// wildcard subject matching the output of the provider on every host
+#[must_use]
+pub fn provider_logs_subject(lattice: &str, provider_id: &str, host_id: Option<&str>) -> String {
+    format!(
+        "wasmbus.logs.{lattice}.provider.{provider_id}.{}",
+        host_id.unwrap_or("*")
+    )
+}
+
+/// The subject a host answers [`ProviderLogHistoryRequest`]s for a provider on
+#[must_use]
+pub fn provider_log_history_subject(lattice: &str, provider_id: &str, host_id: &str) -> String {
+    format!("wasmbus.logs.{lattice}.history.{provider_id}.{host_id}")
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeMap;
 
-    use super::ProviderDescription;
+    use super::{
+        provider_log_history_subject, provider_logs_subject, ProviderDescription, ProviderLogLine,
+        ProviderLogStream,
+    };
 
     #[test]
     fn provider_description_builder() {
@@ -146,4 +293,38 @@ mod tests {
                 .unwrap()
         )
     }
+
+    #[test]
+    fn provider_log_lines_are_published_per_host() {
+        let line = ProviderLogLine::new("NHOST", "http-server", ProviderLogStream::Stderr, "ready")
+            .at(1_714_564_800_000, 3);
+        let json = serde_json::to_value(&line).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "host_id": "NHOST",
+                "provider_id": "http-server",
+                "stream": "stderr",
+                "line": "ready",
+                "timestamp": 1_714_564_800_000_u64,
+                "seq": 3,
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<ProviderLogLine>(json).unwrap(),
+            line
+        );
+        assert_eq!(
+            provider_logs_subject("default", "http-server", Some("NHOST")),
+            "wasmbus.logs.default.provider.http-server.NHOST"
+        );
+        assert_eq!(
+            provider_logs_subject("default", "http-server", None),
+            "wasmbus.logs.default.provider.http-server.*"
+        );
+        assert_eq!(
+            provider_log_history_subject("default", "http-server", "NHOST"),
+            "wasmbus.logs.default.history.http-server.NHOST"
+        );
+    }
 }
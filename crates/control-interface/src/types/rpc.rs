@@ -24,6 +24,9 @@ pub struct ComponentAuctionAck {
     /// Constraints that were used in the auction
     #[serde(default)]
     pub(crate) constraints: BTreeMap<String, String>,
+    /// The labels of the "bidder" host, empty if the host doesn't report them
+    #[serde(default)]
+    pub(crate) labels: BTreeMap<String, String>,
 }
 
 impl ComponentAuctionAck {
@@ -39,6 +42,7 @@ impl ComponentAuctionAck {
             component_id: component_id.into(),
             host_id: host_id.into(),
             constraints: constraints.into(),
+            labels: BTreeMap::new(),
         }
     }
 
@@ -66,6 +70,12 @@ impl ComponentAuctionAck {
         &self.constraints
     }
 
+    /// Get the labels of the host that sent the auction acknowledgement
+    #[must_use]
+    pub fn labels(&self) -> &BTreeMap<String, String> {
+        &self.labels
+    }
+
     pub fn builder() -> ComponentAuctionAckBuilder {
         ComponentAuctionAckBuilder::default()
     }
@@ -77,6 +87,7 @@ pub struct ComponentAuctionAckBuilder {
     component_id: Option<String>,
     host_id: Option<String>,
     constraints: Option<BTreeMap<String, String>>,
+    labels: Option<BTreeMap<String, String>>,
 }
 
 impl ComponentAuctionAckBuilder {
@@ -104,6 +115,12 @@ impl ComponentAuctionAckBuilder {
         self
     }
 
+    #[must_use]
+    pub fn labels(mut self, v: BTreeMap<String, String>) -> Self {
+        self.labels = Some(v);
+        self
+    }
+
     pub fn build(self) -> Result<ComponentAuctionAck> {
         Ok(ComponentAuctionAck {
             component_ref: self
@@ -116,6 +133,7 @@ impl ComponentAuctionAckBuilder {
                 .host_id
                 .ok_or_else(|| "host_id is required".to_string())?,
             constraints: self.constraints.unwrap_or_default(),
+            labels: self.labels.unwrap_or_default(),
         })
     }
 }
@@ -218,6 +236,9 @@ pub struct ProviderAuctionAck {
     /// The constraints provided for the auction
     #[serde(default)]
     pub(crate) constraints: BTreeMap<String, String>,
+    /// The labels of the "bidder" host, empty if the host doesn't report them
+    #[serde(default)]
+    pub(crate) labels: BTreeMap<String, String>,
 }
 
 impl ProviderAuctionAck {
@@ -245,6 +266,12 @@ impl ProviderAuctionAck {
         &self.constraints
     }
 
+    /// Get the labels of the host that sent the provider auction acknowledgement
+    #[must_use]
+    pub fn labels(&self) -> &BTreeMap<String, String> {
+        &self.labels
+    }
+
     #[must_use]
     pub fn builder() -> ProviderAuctionAckBuilder {
         ProviderAuctionAckBuilder::default()
@@ -257,6 +284,7 @@ pub struct ProviderAuctionAckBuilder {
     provider_ref: Option<String>,
     provider_id: Option<String>,
     constraints: Option<BTreeMap<String, String>>,
+    labels: Option<BTreeMap<String, String>>,
 }
 
 impl ProviderAuctionAckBuilder {
@@ -284,6 +312,12 @@ impl ProviderAuctionAckBuilder {
         self
     }
 
+    #[must_use]
+    pub fn labels(mut self, v: BTreeMap<String, String>) -> Self {
+        self.labels = Some(v);
+        self
+    }
+
     pub fn build(self) -> Result<ProviderAuctionAck> {
         Ok(ProviderAuctionAck {
             provider_ref: self
@@ -296,6 +330,7 @@ impl ProviderAuctionAckBuilder {
                 .host_id
                 .ok_or_else(|| "host_id is required".to_string())?,
             constraints: self.constraints.unwrap_or_default(),
+            labels: self.labels.unwrap_or_default(),
         })
     }
 }
@@ -512,13 +547,15 @@ mod tests {
                 component_ref: "component_ref".into(),
                 component_id: "component_id".into(),
                 host_id: "host_id".into(),
-                constraints: BTreeMap::from([("a".into(), "b".into())])
+                constraints: BTreeMap::from([("a".into(), "b".into())]),
+                labels: BTreeMap::from([("zone".into(), "eu".into())]),
             },
             ComponentAuctionAck::builder()
                 .component_ref("component_ref".into())
                 .component_id("component_id".into())
                 .host_id("host_id".into())
                 .constraints(BTreeMap::from([("a".into(), "b".into())]))
+                .labels(BTreeMap::from([("zone".into(), "eu".into())]))
                 .build()
                 .unwrap()
         )
@@ -548,13 +585,15 @@ mod tests {
                 provider_ref: "provider_ref".into(),
                 provider_id: "provider_id".into(),
                 host_id: "host_id".into(),
-                constraints: BTreeMap::from([("a".into(), "b".into())])
+                constraints: BTreeMap::from([("a".into(), "b".into())]),
+                labels: BTreeMap::from([("zone".into(), "eu".into())]),
             },
             ProviderAuctionAck::builder()
                 .provider_ref("provider_ref".into())
                 .provider_id("provider_id".into())
                 .host_id("host_id".into())
                 .constraints(BTreeMap::from([("a".into(), "b".into())]))
+                .labels(BTreeMap::from([("zone".into(), "eu".into())]))
                 .build()
                 .unwrap()
         )
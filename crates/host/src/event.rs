@@ -289,6 +289,7 @@ pub fn provider_started(
 /// * `provider_ref` - Reference to the provider image
 /// * `provider_id` - Unique identifier for the provider
 /// * `host_id` - ID of the host where start failed
+/// * `phase` - The phase of the start that failed, either `pull` or `launch`
 /// * `error` - The error that caused the start failure
 ///
 /// # Returns
@@ -297,12 +298,14 @@ pub fn provider_start_failed(
     provider_ref: impl AsRef<str>,
     provider_id: impl AsRef<str>,
     host_id: impl AsRef<str>,
+    phase: impl AsRef<str>,
     error: &anyhow::Error,
 ) -> serde_json::Value {
     json!({
         "provider_ref": provider_ref.as_ref(),
         "provider_id": provider_id.as_ref(),
         "host_id": host_id.as_ref(),
+        "phase": phase.as_ref(),
         "error": format!("{error:#}"),
         // TODO(#1548): remove this field when we don't depend on it
         "link_name": "default",
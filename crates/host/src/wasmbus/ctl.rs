@@ -23,7 +23,8 @@ use wasmcloud_tracing::context::TraceContextInjector;
 
 use crate::registry::RegistryCredentialExt;
 use crate::wasmbus::{
-    human_friendly_uptime, injector_to_headers, Annotations, Claims, Host, Provider, StoredClaims,
+    human_friendly_uptime, injector_to_headers, provider_start_phase, Annotations, Claims, Host,
+    Provider, StoredClaims,
 };
 use crate::ResourceRef;
 
@@ -175,12 +176,14 @@ impl ControlInterfaceServer for Host {
         // This host can run the component if all constraints are satisfied and the component is not already running
         if constraints_satisfied && !component_id_running {
             Ok(Some(CtlResponse::ok(
-                ComponentAuctionAck::from_component_host_and_constraints(
-                    component_ref,
-                    component_id,
-                    &self.host_key.public_key(),
-                    constraints.clone(),
-                ),
+                ComponentAuctionAck::builder()
+                    .component_ref(component_ref.into())
+                    .component_id(component_id.into())
+                    .host_id(self.host_key.public_key())
+                    .constraints(constraints.clone())
+                    .labels(host_labels.clone())
+                    .build()
+                    .map_err(|e| anyhow!("failed to build component auction ack: {e}"))?,
             )))
         } else {
             Ok(None)
@@ -215,6 +218,7 @@ impl ControlInterfaceServer for Host {
                     .provider_ref(provider_ref.into())
                     .provider_id(provider_id.into())
                     .constraints(constraints.clone())
+                    .labels(host_labels.clone())
                     .host_id(self.host_key.public_key())
                     .build()
                     .map_err(|e| anyhow!("failed to build provider auction ack: {e}"))?,
@@ -513,6 +517,7 @@ impl ControlInterfaceServer for Host {
             let provider_id = request.provider_id();
             let provider_ref = request.provider_ref();
             let annotations = request.annotations();
+            let pull_timeout = request.pull_timeout_ms().map(Duration::from_millis);
 
             if let Err(err) = Arc::clone(&self)
                 .handle_start_provider_task(
@@ -521,6 +526,7 @@ impl ControlInterfaceServer for Host {
                     provider_ref,
                     annotations.cloned().unwrap_or_default(),
                     &host_id,
+                    pull_timeout,
                 )
                 .await
             {
@@ -533,6 +539,7 @@ impl ControlInterfaceServer for Host {
                             provider_ref,
                             provider_id,
                             host_id,
+                            provider_start_phase(&err),
                             &err,
                         ),
                     )
@@ -1002,7 +1009,8 @@ impl ControlInterfaceServer for Host {
             .version(self.host_config.version.clone())
             .ctl_host(self.host_config.rpc_nats_url.to_string())
             .rpc_host(self.host_config.rpc_nats_url.to_string())
-            .lattice(self.host_config.lattice.to_string());
+            .lattice(self.host_config.lattice.to_string())
+            .provider_log_forwarding(self.host_config.forward_provider_logs);
 
         if let Some(ref js_domain) = self.host_config.js_domain {
             host = host.js_domain(js_domain.clone());
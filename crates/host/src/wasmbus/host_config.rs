@@ -74,6 +74,10 @@ pub struct Host {
     pub enable_component_auction: bool,
     /// Whether capability provider auctions are enabled
     pub enable_provider_auction: bool,
+    /// Whether to publish each line binary providers write to stdout and stderr on the lattice,
+    /// where any subscriber can read it. Provider output may contain secrets, so this is off by
+    /// default and providers then inherit the host's own output
+    pub forward_provider_logs: bool,
 }
 
 /// Configuration for wasmCloud policy service
@@ -120,6 +124,7 @@ impl Default for Host {
             http_admin: None,
             enable_component_auction: true,
             enable_provider_auction: true,
+            forward_provider_logs: false,
         }
     }
 }
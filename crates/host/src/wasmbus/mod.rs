@@ -40,7 +40,7 @@ use wasmcloud_control_interface::{
     DeleteInterfaceLinkDefinitionRequest, HostInventory, HostLabel, HostLabelIdentifier, Link,
     ProviderAuctionAck, ProviderAuctionRequest, ProviderDescription, RegistryCredential,
     ScaleComponentCommand, StartProviderCommand, StopHostCommand, StopProviderCommand,
-    UpdateComponentCommand,
+    UpdateComponentCommand, HOST_FEATURE_PROVIDER_PULL_TIMEOUT,
 };
 use wasmcloud_core::ComponentId;
 use wasmcloud_runtime::capability::secrets::store::SecretValue;
@@ -131,6 +131,26 @@ impl TryFrom<AsyncBytesMut> for Vec<u8> {
 
 type Annotations = BTreeMap<String, String>;
 
+/// Context of an error raised while resolving or pulling a provider, as opposed to launching it
+#[derive(Debug)]
+struct ProviderPullFailed(&'static str);
+
+impl core::fmt::Display for ProviderPullFailed {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+/// The phase of a provider start that failed with `err`, as reported in the
+/// `provider_start_failed` event
+fn provider_start_phase(err: &anyhow::Error) -> &'static str {
+    if err.downcast_ref::<ProviderPullFailed>().is_some() {
+        "pull"
+    } else {
+        "launch"
+    }
+}
+
 #[derive(Debug)]
 struct Component {
     component: wasmcloud_runtime::Component<Handler>,
@@ -944,6 +964,7 @@ impl Host {
             .uptime_seconds(uptime.as_secs())
             .version(self.host_config.version.clone())
             .host_id(self.host_key.public_key())
+            .features(vec![HOST_FEATURE_PROVIDER_PULL_TIMEOUT.to_string()])
             .build()
             .expect("failed to build host inventory")
     }
@@ -1614,24 +1635,34 @@ impl Host {
         provider_ref: &str,
         annotations: BTreeMap<String, String>,
         host_id: &str,
+        pull_timeout: Option<Duration>,
     ) -> anyhow::Result<()> {
         trace!(provider_ref, provider_id, "start provider task");
 
         let registry_config = self.registry_config.read().await;
-        let provider_ref =
-            ResourceRef::try_from(provider_ref).context("failed to parse provider reference")?;
+        let provider_ref = ResourceRef::try_from(provider_ref)
+            .context(ProviderPullFailed("failed to parse provider reference"))?;
         let (path, claims_token) = match &provider_ref {
             ResourceRef::Builtin(..) => (None, None),
             _ => {
-                let (path, claims_token) = crate::fetch_provider(
+                let fetch = crate::fetch_provider(
                     &provider_ref,
                     host_id,
                     self.host_config.allow_file_load,
                     &self.host_config.oci_opts.additional_ca_paths,
                     &registry_config,
-                )
-                .await
-                .context("failed to fetch provider")?;
+                );
+                let fetched = match pull_timeout {
+                    Some(pull_timeout) => timeout(pull_timeout, fetch).await.unwrap_or_else(|_| {
+                        Err(anyhow!(
+                            "timed out after {}ms pulling provider",
+                            pull_timeout.as_millis()
+                        ))
+                    }),
+                    None => fetch.await,
+                };
+                let (path, claims_token) =
+                    fetched.context(ProviderPullFailed("failed to fetch provider"))?;
                 (Some(path), claims_token)
             }
         };
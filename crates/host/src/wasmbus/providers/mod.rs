@@ -2,13 +2,13 @@
 //!
 //! The root of this module includes functionality for running and managing provider binaries. The
 //! submodules contain builtin implementations of wasmCloud capabilities providers.
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, VecDeque};
 use std::env;
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use anyhow::{bail, Context as _};
 use async_nats::Client;
@@ -17,13 +17,17 @@ use base64::Engine;
 use bytes::Bytes;
 use futures::{stream, Future, StreamExt};
 use nkeys::XKey;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader};
 use tokio::process;
 use tokio::sync::RwLock;
 use tokio::task::JoinSet;
 use tracing::{error, instrument, trace, warn};
 use uuid::Uuid;
 use wascap::jwt::{CapabilityProvider, Token};
+use wasmcloud_control_interface::{
+    provider_log_history_subject, provider_logs_subject, ProviderLogHistoryRequest,
+    ProviderLogLine, ProviderLogStream,
+};
 use wasmcloud_core::{
     health_subject, provider_config_update_subject, HealthCheckResponse, HostData, OtelConfig,
 };
@@ -234,6 +238,28 @@ impl Host {
 
         let mut tasks = JoinSet::new();
 
+        // Only publish the provider's output when the host is configured to, since it may
+        // contain secrets
+        let output = self.host_config.forward_provider_logs.then(|| {
+            let host_id = self.host_key.public_key();
+            ProviderOutput {
+                nats: Arc::clone(&self.rpc_nats),
+                subject: provider_logs_subject(
+                    &self.host_config.lattice,
+                    provider_id,
+                    Some(&host_id),
+                ),
+                history_subject: provider_log_history_subject(
+                    &self.host_config.lattice,
+                    provider_id,
+                    &host_id,
+                ),
+                host_id,
+                provider_id: provider_id.to_string(),
+                history: Arc::default(),
+            }
+        });
+
         // Spawn a task to ensure the provider is restarted if it exits prematurely,
         // updating the configuration as needed
         tasks.spawn(
@@ -248,10 +274,16 @@ impl Host {
                     claims_token,
                     annotations,
                     shutdown.clone(),
+                    output.clone(),
                 )
                 .await?,
         );
 
+        // Spawn a task to answer requests for the recent output of the provider
+        if let Some(output) = output {
+            tasks.spawn(serve_provider_log_history(output));
+        }
+
         // Spawn a task to check the health of the provider every 30 seconds
         tasks.spawn(check_health(
             Arc::clone(&self.rpc_nats),
@@ -277,13 +309,14 @@ impl Host {
         claims_token: Option<Token<CapabilityProvider>>,
         annotations: BTreeMap<String, String>,
         shutdown: Arc<AtomicBool>,
+        output: Option<ProviderOutput>,
     ) -> anyhow::Result<impl Future<Output = ()>> {
         let host_data =
             serde_json::to_vec(&host_data).context("failed to serialize provider data")?;
 
         // If there's any issues starting the provider, we want to exit immediately
         let child = Arc::new(RwLock::new(
-            provider_command(&path, host_data)
+            provider_command(&path, host_data, output.as_ref())
                 .await
                 .context("failed to configure binary provider command")?,
         ));
@@ -363,7 +396,9 @@ impl Host {
 
                         // Restart the provider by attempting to re-execute the binary with the same
                         // host data
-                        let Ok(child_cmd) = provider_command(&path, host_data).await else {
+                        let Ok(child_cmd) =
+                            provider_command(&path, host_data, output.as_ref()).await
+                        else {
                             error!(path = ?path.display(), "failed to restart provider");
                             shutdown.store(true, Ordering::Relaxed);
                             return;
@@ -390,10 +425,198 @@ impl Host {
     }
 }
 
+/// The longest line of provider output that is published, in bytes. Longer lines are cut short
+/// so a provider writing without newlines can't grow the host's memory without bound
+const MAX_PROVIDER_LOG_LINE: usize = 16 * 1024;
+
+/// How many of the most recent lines of a provider's output are held to answer history requests
+const MAX_PROVIDER_LOG_HISTORY_LINES: usize = 1000;
+
+/// How many bytes of the most recent lines of a provider's output are held to answer history
+/// requests, keeping responses well below the default NATS max payload
+const MAX_PROVIDER_LOG_HISTORY_BYTES: usize = 256 * 1024;
+
+/// The most recent lines a provider wrote, across restarts of the provider process
+#[derive(Default)]
+struct ProviderLogHistory {
+    lines: VecDeque<ProviderLogLine>,
+    bytes: usize,
+    seq: u64,
+}
+
+impl ProviderLogHistory {
+    /// Number the line, hold on to it and drop the oldest lines over the limits
+    fn push(&mut self, line: ProviderLogLine) -> ProviderLogLine {
+        self.seq += 1;
+        let line = line.at(now_ms(), self.seq);
+        self.bytes += line.line().len();
+        self.lines.push_back(line.clone());
+        while self.lines.len() > MAX_PROVIDER_LOG_HISTORY_LINES
+            || self.bytes > MAX_PROVIDER_LOG_HISTORY_BYTES
+        {
+            let Some(oldest) = self.lines.pop_front() else {
+                break;
+            };
+            self.bytes -= oldest.line().len();
+        }
+        line
+    }
+}
+
+/// Where the output of a provider process is published when the host forwards provider logs
+#[derive(Clone)]
+struct ProviderOutput {
+    nats: Arc<Client>,
+    host_id: String,
+    provider_id: String,
+    subject: String,
+    history_subject: String,
+    history: Arc<Mutex<ProviderLogHistory>>,
+}
+
+/// Milliseconds since the Unix epoch
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
+        .unwrap_or_default()
+}
+
+/// Copy each line a provider writes to `reader` to the host's own output, as if the provider had
+/// inherited it, and publish it on the provider's log subject so it can be followed remotely
+/// (e.g. with `wash provider logs`). Output that isn't valid UTF-8 is converted lossily, lines
+/// longer than [`MAX_PROVIDER_LOG_LINE`] are published cut short, and the output is drained until
+/// the provider closes it so the provider never blocks on a full pipe
+fn forward_provider_output(
+    reader: impl AsyncRead + Unpin + Send + 'static,
+    stream: ProviderLogStream,
+    output: ProviderOutput,
+) {
+    tokio::spawn(async move {
+        let mut reader = BufReader::new(reader);
+        let mut buf = Vec::new();
+        // Whether the rest of a line that was already published cut short is being read
+        let mut skipping = false;
+        loop {
+            buf.clear();
+            match (&mut reader)
+                .take(MAX_PROVIDER_LOG_LINE as u64)
+                .read_until(b'\n', &mut buf)
+                .await
+            {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(e) => {
+                    trace!(
+                        ?e,
+                        provider_id = output.provider_id,
+                        "failed to read provider output"
+                    );
+                    break;
+                }
+            }
+            let written = match stream {
+                ProviderLogStream::Stdout => tokio::io::stdout().write_all(&buf).await,
+                ProviderLogStream::Stderr => tokio::io::stderr().write_all(&buf).await,
+            };
+            if let Err(e) = written {
+                trace!(?e, "failed to copy provider output to host output");
+            }
+            let ended = buf.ends_with(b"\n");
+            if skipping {
+                skipping = !ended;
+                continue;
+            }
+            skipping = !ended && buf.len() >= MAX_PROVIDER_LOG_LINE;
+            let line = String::from_utf8_lossy(&buf);
+            let line = ProviderLogLine::new(
+                &output.host_id,
+                &output.provider_id,
+                stream,
+                line.trim_end_matches(['\r', '\n']),
+            )
+            .truncated(skipping);
+            let line = match output.history.lock() {
+                Ok(mut history) => history.push(line),
+                Err(_) => {
+                    error!(
+                        provider_id = output.provider_id,
+                        "provider log history lock poisoned"
+                    );
+                    break;
+                }
+            };
+            match serde_json::to_vec(&line) {
+                Ok(payload) => {
+                    if let Err(e) = output
+                        .nats
+                        .publish(output.subject.clone(), payload.into())
+                        .await
+                    {
+                        trace!(?e, "failed to publish provider output");
+                    }
+                }
+                Err(e) => trace!(?e, "failed to serialize provider output"),
+            }
+        }
+    });
+}
+
+/// Answer requests for the most recent lines of output of a provider until the provider stops
+async fn serve_provider_log_history(output: ProviderOutput) {
+    let mut sub = match output.nats.subscribe(output.history_subject.clone()).await {
+        Ok(sub) => sub,
+        Err(e) => {
+            error!(
+                ?e,
+                provider_id = output.provider_id,
+                "failed to subscribe to provider log history requests"
+            );
+            return;
+        }
+    };
+    while let Some(msg) = sub.next().await {
+        let Some(reply) = msg.reply else {
+            continue;
+        };
+        let since_ms = serde_json::from_slice::<ProviderLogHistoryRequest>(&msg.payload)
+            .ok()
+            .and_then(|req| req.since_ms());
+        let cutoff = since_ms.map_or(0, |since_ms| now_ms().saturating_sub(since_ms));
+        let lines: Vec<ProviderLogLine> = match output.history.lock() {
+            Ok(history) => history
+                .lines
+                .iter()
+                .filter(|line| line.timestamp() >= cutoff)
+                .cloned()
+                .collect(),
+            Err(_) => {
+                error!(
+                    provider_id = output.provider_id,
+                    "provider log history lock poisoned"
+                );
+                return;
+            }
+        };
+        match serde_json::to_vec(&lines) {
+            Ok(payload) => {
+                if let Err(e) = output.nats.publish(reply, payload.into()).await {
+                    warn!(?e, "failed to reply to provider log history request");
+                }
+            }
+            Err(e) => error!(?e, "failed to serialize provider log history"),
+        }
+    }
+}
+
 /// Using the provided path as the provider binary, start the provider process and
 /// pass the host data to it over stdin. Returns the child process handle which
 /// has already been spawned.
-async fn provider_command(path: &Path, host_data: Vec<u8>) -> anyhow::Result<process::Child> {
+async fn provider_command(
+    path: &Path,
+    host_data: Vec<u8>,
+    output: Option<&ProviderOutput>,
+) -> anyhow::Result<process::Child> {
     let mut child_cmd = process::Command::new(path);
     // Prevent the provider from inheriting the host's environment, with the exception of
     // the following variables we manually add back
@@ -419,11 +642,23 @@ async fn provider_command(path: &Path, host_data: Vec<u8>) -> anyhow::Result<pro
         }
     }
 
+    // Without log forwarding, the provider inherits the host's stdout and stderr
+    if output.is_some() {
+        child_cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    }
     let mut child = child_cmd
         .stdin(Stdio::piped())
         .kill_on_drop(true)
         .spawn()
         .context("failed to spawn provider process")?;
+    if let Some(output) = output {
+        if let Some(stdout) = child.stdout.take() {
+            forward_provider_output(stdout, ProviderLogStream::Stdout, output.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            forward_provider_output(stderr, ProviderLogStream::Stderr, output.clone());
+        }
+    }
     let mut stdin = child.stdin.take().context("failed to take stdin")?;
     stdin
         .write_all(STANDARD.encode(host_data).as_bytes())
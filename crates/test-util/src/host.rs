@@ -93,6 +93,44 @@ impl WasmCloudTestHost {
         policy_service_config: Option<PolicyService>,
         secrets_topic_prefix: Option<String>,
         experimental_features: Option<Features>,
+    ) -> Result<Self> {
+        Self::start_with(
+            nats_url,
+            lattice_name,
+            cluster_key,
+            host_key,
+            policy_service_config,
+            secrets_topic_prefix,
+            experimental_features,
+            false,
+        )
+        .await
+    }
+
+    /// Start a test wasmCloud [`Host`] that publishes the output of the providers it runs, with
+    /// generated cluster & host keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `nats_url` - URL of the NATS instance to which we should connect (ex. "nats://localhost:4222")
+    /// * `lattice_name` - Name of the wasmCloud lattice to which we should connect (ex. "default")
+    pub async fn start_forwarding_provider_logs(
+        nats_url: impl AsRef<str>,
+        lattice_name: impl AsRef<str>,
+    ) -> Result<Self> {
+        Self::start_with(nats_url, lattice_name, None, None, None, None, None, true).await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn start_with(
+        nats_url: impl AsRef<str>,
+        lattice_name: impl AsRef<str>,
+        cluster_key: Option<KeyPair>,
+        host_key: Option<KeyPair>,
+        policy_service_config: Option<PolicyService>,
+        secrets_topic_prefix: Option<String>,
+        experimental_features: Option<Features>,
+        forward_provider_logs: bool,
     ) -> Result<Self> {
         let nats_url = Url::try_from(nats_url.as_ref()).context("failed to parse NATS URL")?;
         let lattice_name = lattice_name.as_ref();
@@ -112,6 +150,7 @@ impl WasmCloudTestHost {
             provider_shutdown_delay: Some(Duration::from_millis(300)),
             allow_file_load: true,
             experimental_features,
+            forward_provider_logs,
             ..Default::default()
         };
 
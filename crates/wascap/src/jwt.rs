@@ -7,7 +7,7 @@ use nkeys::KeyPair;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{from_str, to_string};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 const HEADER_TYPE: &str = "jwt";
@@ -75,6 +75,31 @@ pub struct Component {
     /// Indicates whether this module is a capability provider
     #[serde(rename = "prov", default = "default_as_false")]
     pub provider: bool,
+
+    /// Information about how the component was built, for tracing it back to its sources. Optional
+    #[serde(rename = "build", default, skip_serializing_if = "Option::is_none")]
+    pub build: Option<BuildMetadata>,
+}
+
+/// Information about how a component was built, embedded in its claims so that deployed
+/// components can be traced back to the sources and toolchains that produced them
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Default)]
+pub struct BuildMetadata {
+    /// Revision of the source repository the component was built from, e.g. a git commit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_revision: Option<String>,
+
+    /// Digest of the source files the component was built from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_digest: Option<String>,
+
+    /// When the component was built, in seconds since the Unix epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub built_at: Option<u64>,
+
+    /// Versions of the tools that built the component, by tool name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub toolchains: BTreeMap<String, String>,
 }
 
 /// The claims metadata corresponding to a capability provider
@@ -225,6 +250,34 @@ where
         Ok(format!("{head_and_claims}.{sig64}"))
     }
 
+    /// Encode the claims as a JWT signed by `sign`, which is given the bytes to sign and returns
+    /// their Ed25519 signature. This allows signing with keys that aren't held in memory, like
+    /// keys in a hardware module or a key management service. The issuer of the claims must be
+    /// the public key of the key `sign` signs with.
+    ///
+    /// # Errors
+    /// Will return an error if the claims can't be serialized or `sign` fails
+    pub fn encode_with<E: std::fmt::Display>(
+        &self,
+        sign: impl FnOnce(&[u8]) -> std::result::Result<Vec<u8>, E>,
+    ) -> Result<String> {
+        let header = ClaimsHeader {
+            header_type: HEADER_TYPE.to_string(),
+            algorithm: HEADER_ALGORITHM.to_string(),
+        };
+        let header = to_jwt_segment(&header)?;
+        let claims = to_jwt_segment(self)?;
+
+        let head_and_claims = format!("{header}.{claims}");
+        let sig = sign(head_and_claims.as_bytes()).map_err(|e| {
+            errors::new(errors::ErrorKind::Token(format!(
+                "failed to sign token: {e}"
+            )))
+        })?;
+        let sig64 = BASE64URL_NOPAD.encode(&sig);
+        Ok(format!("{head_and_claims}.{sig64}"))
+    }
+
     #[allow(clippy::missing_errors_doc)] // TODO: document
     pub fn decode(input: &str) -> Result<Claims<T>> {
         let segments: Vec<&str> = input.split('.').collect();
@@ -840,6 +893,7 @@ impl Component {
             rev,
             ver,
             call_alias: normalize_call_alias(call_alias),
+            build: None,
         }
     }
 }
@@ -917,7 +971,7 @@ impl Host {
 
 #[cfg(test)]
 mod test {
-    use super::{Account, Claims, Component, ErrorKind, Host, KeyPair, Operator};
+    use super::{Account, BuildMetadata, Claims, Component, ErrorKind, Host, KeyPair, Operator};
     use crate::jwt::{
         since_the_epoch, validate_token, CapabilityProvider, ClaimsBuilder, Cluster,
         WASCAP_INTERNAL_REVISION,
@@ -956,6 +1010,47 @@ mod test {
         }
     }
 
+    #[test]
+    fn build_metadata_roundtrip() {
+        let kp = KeyPair::new_account();
+        let mut component = Component::new(
+            "test".to_string(),
+            Some(vec![]),
+            false,
+            Some(1),
+            Some("0.1.0".to_string()),
+            None,
+        );
+        let claims = Claims {
+            metadata: Some(component.clone()),
+            expires: None,
+            id: nuid::next().to_string(),
+            issued_at: 0,
+            issuer: kp.public_key(),
+            subject: "test.wasm".to_string(),
+            not_before: None,
+            wascap_revision: Some(WASCAP_INTERNAL_REVISION),
+        };
+        // Claims without build metadata leave it out entirely
+        let encoded = claims.encode(&kp).unwrap();
+        let decoded = Claims::<Component>::decode(&encoded).unwrap();
+        assert!(decoded.metadata.unwrap().build.is_none());
+
+        component.build = Some(BuildMetadata {
+            source_revision: Some("5e5021c".to_string()),
+            source_digest: Some("sha256:abc".to_string()),
+            built_at: Some(1_700_000_000),
+            toolchains: [("rustc".to_string(), "1.82.0".to_string())].into(),
+        });
+        let claims = Claims {
+            metadata: Some(component.clone()),
+            ..claims
+        };
+        let encoded = claims.encode(&kp).unwrap();
+        let decoded = Claims::<Component>::decode(&encoded).unwrap();
+        assert_eq!(decoded.metadata.unwrap().build, component.build);
+    }
+
     #[test]
     fn full_validation_expires() {
         let kp = KeyPair::new_account();
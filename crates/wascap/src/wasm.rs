@@ -72,6 +72,28 @@ pub fn embed_claims(
     orig_bytecode: &[u8],
     claims: &Claims<Component>,
     kp: &KeyPair,
+) -> Result<Vec<u8>> {
+    embed_encoded_claims(orig_bytecode, claims, |claims| claims.encode(kp))
+}
+
+/// Embed a set of claims inside the bytecode of a WebAssembly module like [`embed_claims`], signing
+/// the JWT with `sign` instead of a `KeyPair` (see [`Claims::encode_with`])
+///
+/// # Errors
+/// Will return an error if the module can't be parsed or signing the claims fails
+pub fn embed_claims_with<E: std::fmt::Display>(
+    orig_bytecode: &[u8],
+    claims: &Claims<Component>,
+    sign: impl FnOnce(&[u8]) -> std::result::Result<Vec<u8>, E>,
+) -> Result<Vec<u8>> {
+    embed_encoded_claims(orig_bytecode, claims, |claims| claims.encode_with(sign))
+}
+
+/// Stamp the claims with the hash of the module, encode them as a JWT with `encode` and embed it
+fn embed_encoded_claims(
+    orig_bytecode: &[u8],
+    claims: &Claims<Component>,
+    encode: impl FnOnce(&Claims<Component>) -> Result<String>,
 ) -> Result<Vec<u8>> {
     let mut bytes = orig_bytecode.to_vec();
     bytes = strip_custom_section(&bytes)?;
@@ -84,7 +106,7 @@ pub fn embed_claims(
     });
     claims.metadata = meta;
 
-    let encoded = claims.encode(kp)?;
+    let encoded = encode(&claims)?;
     let encvec = encoded.as_bytes().to_vec();
     wasm_gen::write_custom_section(&mut bytes, SECTION_WC_JWT, &encvec);
 
@@ -302,6 +324,41 @@ mod test {
         }
     }
 
+    #[test]
+    fn claims_signed_by_a_closure_validate() {
+        let mut f = File::open("./fixtures/guest.component.wasm").unwrap();
+        let mut buffer = Vec::new();
+        f.read_to_end(&mut buffer).unwrap();
+
+        let kp = KeyPair::new_account();
+        let claims = Claims {
+            metadata: Some(Component::new(
+                "testing".to_string(),
+                Some(vec![]),
+                false,
+                Some(1),
+                Some(String::new()),
+                None,
+            )),
+            expires: None,
+            id: nuid::next().to_string(),
+            issued_at: 0,
+            issuer: kp.public_key(),
+            subject: "test.wasm".to_string(),
+            not_before: None,
+            wascap_revision: Some(WASCAP_INTERNAL_REVISION),
+        };
+        let modified_bytecode = embed_claims_with(&buffer, &claims, |data| kp.sign(data)).unwrap();
+
+        let token = extract_claims(modified_bytecode).unwrap().unwrap();
+        assert!(
+            crate::jwt::validate_token::<Component>(&token.jwt)
+                .unwrap()
+                .signature_valid
+        );
+        assert!(embed_claims_with(&buffer, &claims, |_| Err("no key")).is_err());
+    }
+
     #[test]
     fn claims_roundtrip() {
         // Serialize and de-serialize this because the module loader adds bytes to
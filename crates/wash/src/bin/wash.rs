@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
-use std::io::{stdout, BufWriter, Write};
+use std::io::{stderr, stdout, BufWriter, IsTerminal, Write};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use anyhow::{bail, Context};
 use clap::{self, Arg, ArgMatches, Command, FromArgMatches, Parser, Subcommand};
@@ -13,19 +14,25 @@ use tracing_subscriber::EnvFilter;
 use wash::lib::cli::capture::{CaptureCommand, CaptureSubcommand};
 use wash::lib::cli::claims::ClaimsCliCommand;
 use wash::lib::cli::get::GetCommand;
+use wash::lib::cli::host::HostCommand;
 use wash::lib::cli::inspect::InspectCliCommand;
 use wash::lib::cli::label::LabelHostCommand;
-use wash::lib::cli::link::LinkCommand;
+use wash::lib::cli::link::{LinkCommand, LinksDrifted};
+use wash::lib::cli::progress::{NdjsonProgress, Progress};
+use wash::lib::cli::provider::ProviderCommand;
 use wash::lib::cli::registry::{RegistryPullCommand, RegistryPushCommand};
+use wash::lib::cli::rollout::RolloutCommand;
 use wash::lib::cli::scale::ScaleCommand;
 use wash::lib::cli::spy::SpyCommand;
-use wash::lib::cli::start::StartCommand;
+use wash::lib::cli::start::{LinksRolledBack, StartCommand};
 use wash::lib::cli::stop::StopCommand;
 use wash::lib::cli::update::UpdateCommand;
-use wash::lib::cli::{CommandOutput, OutputKind};
+use wash::lib::cli::{use_color, write_text_output, CommandOutput, OutputKind, OutputStatus};
 use wash::lib::drain::Drain as DrainSelection;
+use wash::lib::failure::{Failure, FailureKind};
 use wash::lib::generate::emoji;
 use wash::lib::plugin::subcommand::{DirMapping, SubcommandRunner};
+use wash::lib::provider::ProviderStartError;
 use wash::lib::start::get_wash_versions_newer_than;
 
 use wash::cli::app::{self, AppCliCommand};
@@ -155,6 +162,10 @@ ________________________________________________________________________________
             commands: vec![
                 ("get", "Get information about different running wasmCloud resources"),
                 ("start", "Start a component or capability provider"),
+                (
+                    "rollout",
+                    "Manage progressive provider rollouts started with `wash start provider --canary`",
+                ),
                 (
                     "scale",
                     "Scale a component running in a host to a certain level of concurrency",
@@ -167,6 +178,8 @@ ________________________________________________________________________________
                 ("link", "Link one component to another on a set of interfaces"),
                 ("call", "Invoke a simple function on a component running in a wasmCloud host"),
                 ("label", "Label (or un-label) a host with a key=value label pair"),
+                ("host", "Manage hosts, e.g. drain one before maintenance"),
+                ("provider", "Inspect running capability providers, e.g. their logs"),
                 (
                     "config",
                     "Create configuration for components, capability providers and links",
@@ -200,12 +213,16 @@ ________________________________________________________________________________
             commands: vec![
                 (
                     "-o, --output <OUTPUT>",
-                    "Specify output format (text or json) [default: text]",
+                    "Specify output format (text/table, json, ndjson, yaml or wide) [default: text]",
                 ),
                 (
                     "--experimental",
                     "Whether or not to enable experimental features [default: false]",
                 ),
+                (
+                    "--progress",
+                    "Report the steps of long-running commands as JSON lines on stderr, with json or ndjson output",
+                ),
                 ("-h, --help", "Print help"),
                 ("-V, --version", "Print version"),
             ],
@@ -228,21 +245,27 @@ ________________________________________________________________________________
 /// Helper function to display the version of all the binaries wash runs
 fn version(output: OutputKind) -> String {
     match output {
-        OutputKind::Text => format!(
+        OutputKind::Text | OutputKind::Wide => format!(
             "wash          v{}\n├ nats-server {}\n├ wadm        {}\n└ wasmcloud   {}",
             clap::crate_version!(),
             NATS_SERVER_VERSION,
             WADM_VERSION,
             WASMCLOUD_HOST_VERSION
         ),
-        OutputKind::Json => {
+        OutputKind::Json | OutputKind::Ndjson | OutputKind::Yaml => {
             let versions = serde_json::json!({
                 "wash": format!("v{}", clap::crate_version!()),
                 "nats-server": NATS_SERVER_VERSION,
                 "wadm": WADM_VERSION,
                 "wasmcloud": WASMCLOUD_HOST_VERSION,
             });
-            serde_json::to_string_pretty(&versions).unwrap()
+            if output == OutputKind::Ndjson {
+                serde_json::to_string(&versions).unwrap()
+            } else if output == OutputKind::Yaml {
+                serde_yaml::to_string(&versions).unwrap()
+            } else {
+                serde_json::to_string_pretty(&versions).unwrap()
+            }
         }
     }
 }
@@ -255,7 +278,7 @@ struct Cli {
         short = 'o',
         long = "output",
         default_value = "text",
-        help = "Specify output format (text or json)",
+        help = "Specify output format (text/table, json, ndjson, yaml or wide)",
         global = true
     )]
     pub(crate) output: OutputKind,
@@ -270,6 +293,20 @@ struct Cli {
     )]
     pub(crate) experimental: bool,
 
+    #[clap(
+        long = "no-color",
+        help = "Disable colored output. Also disabled when the NO_COLOR environment variable is set",
+        global = true
+    )]
+    pub(crate) no_color: bool,
+
+    #[clap(
+        long = "progress",
+        help = "Report the steps of long-running commands, like auctioning or waiting for a start event, as JSON lines on stderr. Only applies to structured output, text output shows them on the spinner",
+        global = true
+    )]
+    pub(crate) progress: bool,
+
     #[clap(
         long = "help-markdown",
         conflicts_with = "help",
@@ -355,6 +392,9 @@ enum CliCommand {
     /// Spy on all invocations a component sends and receives
     #[clap(name = "spy")]
     Spy(SpyCommand),
+    /// Manage progressive provider rollouts started with `wash start provider --canary`
+    #[clap(name = "rollout", subcommand)]
+    Rollout(RolloutCommand),
     /// Scale a component running in a host to a certain level of concurrency
     #[clap(name = "scale", subcommand)]
     Scale(ScaleCommand),
@@ -367,6 +407,12 @@ enum CliCommand {
     /// Label (or un-label) a host with a key=value label pair
     #[clap(name = "label", alias = "tag")]
     Label(LabelHostCommand),
+    /// Manage hosts, e.g. drain one before maintenance
+    #[clap(name = "host", subcommand)]
+    Host(HostCommand),
+    /// Inspect running capability providers, e.g. their logs
+    #[clap(name = "provider", subcommand)]
+    Provider(ProviderCommand),
     /// Update a component running in a host to newer image reference
     #[clap(name = "update", subcommand)]
     Update(UpdateCommand),
@@ -492,6 +538,11 @@ async fn main() {
     }
 
     let output_kind = cli.output;
+    let progress = if cli.progress && output_kind.is_structured() {
+        Progress::new(Arc::new(NdjsonProgress::stderr()))
+    } else {
+        Progress::default()
+    };
 
     // Implements clap_markdown for markdown generation of command line documentation. Most straightforward way to invoke is probably `wash app get --help-markdown > help.md`
     if cli.help_markdown {
@@ -557,17 +608,26 @@ async fn main() {
             if !cli.experimental {
                 experimental_error_message("spy")
             } else {
-                wash::lib::cli::spy::handle_command(spy_cli).await
+                wash::lib::cli::spy::handle_command(spy_cli, output_kind).await
             }
         }
+        CliCommand::Rollout(rollout_cli) => {
+            wash::lib::cli::rollout::handle_command(rollout_cli).await
+        }
         CliCommand::Scale(scale_cli) => {
-            common::scale_cmd::handle_command(scale_cli, output_kind).await
+            common::scale_cmd::handle_command(scale_cli, output_kind, progress).await
         }
         CliCommand::Secrets(secrets_cli) => secrets::handle_command(secrets_cli, output_kind).await,
         CliCommand::Start(start_cli) => {
-            common::start_cmd::handle_command(start_cli, output_kind).await
+            common::start_cmd::handle_command(start_cli, output_kind, progress).await
         }
         CliCommand::Stop(stop_cli) => common::stop_cmd::handle_command(stop_cli, output_kind).await,
+        CliCommand::Host(host_cli) => {
+            wash::lib::cli::host::handle_command(host_cli, output_kind).await
+        }
+        CliCommand::Provider(provider_cli) => {
+            common::provider_cmd::handle_command(provider_cli, output_kind).await
+        }
         CliCommand::Label(label_cli) => {
             common::label_cmd::handle_command(label_cli, output_kind).await
         }
@@ -584,49 +644,50 @@ async fn main() {
     let mut stdout_buf = BufWriter::new(stdout().lock());
 
     let exit_code: i32 = match res {
-        Ok(out) => {
-            match output_kind {
-                OutputKind::Json => {
-                    let mut map = out.map;
-                    // When we fetch configuration, we don't want to arbitrarily insert a key into the map.
-                    // There may be other commands we do this in the future, but for now the special check is fine.
-                    if append_json_success {
-                        map.insert("success".to_string(), json!(true));
+        Ok(mut out) => {
+            // When we fetch configuration, we don't want to arbitrarily insert a key into the map.
+            // There may be other commands we do this in the future, but for now the special check is fine.
+            if output_kind.is_structured() && append_json_success {
+                out.map.insert("success".to_string(), json!(true));
+            }
+            let color = use_color(cli.no_color, stdout().is_terminal());
+            let _ = out.write_to(&mut stdout_buf, output_kind, color);
+            if output_kind.is_structured() {
+                0
+            } else {
+                // on the first non-error, non-json use of wash, print info about shell completions
+                match completions::first_run_suggestion() {
+                    Ok(Some(suggestion)) => {
+                        let _ = writeln!(stdout_buf, "\n{suggestion}");
+                        0
                     }
-                    let _ = writeln!(
-                        stdout_buf,
-                        "\n{}",
-                        serde_json::to_string_pretty(&map).unwrap()
-                    );
-                    0
-                }
-                OutputKind::Text => {
-                    let _ = writeln!(stdout_buf, "\n{}", out.text);
-                    // on the first non-error, non-json use of wash, print info about shell completions
-                    match completions::first_run_suggestion() {
-                        Ok(Some(suggestion)) => {
-                            let _ = writeln!(stdout_buf, "\n{suggestion}");
-                            0
-                        }
-                        Ok(None) => {
-                            // >1st run,  no message
-                            0
-                        }
-                        Err(e) => {
-                            // error creating first-run token file
-                            eprintln!("\nError: {e}");
-                            1
-                        }
+                    Ok(None) => {
+                        // >1st run,  no message
+                        0
+                    }
+                    Err(e) => {
+                        // error creating first-run token file
+                        eprintln!("\nError: {e}");
+                        1
                     }
                 }
             }
         }
         Err(e) => {
+            let failure_kind = FailureKind::of(&e);
             match output_kind {
-                OutputKind::Json => {
+                OutputKind::Json | OutputKind::Ndjson | OutputKind::Yaml => {
                     let mut map = HashMap::new();
                     map.insert("success".to_string(), json!(false));
                     map.insert("error".to_string(), json!(e.to_string()));
+                    if let Some(kind) = failure_kind {
+                        map.insert("error_kind".to_string(), json!(kind));
+                    }
+                    if let Some(failure) = e.chain().find_map(|e| e.downcast_ref::<Failure>()) {
+                        for (key, value) in &failure.details {
+                            map.entry(key.clone()).or_insert_with(|| value.clone());
+                        }
+                    }
 
                     let error_chain = e
                         .chain()
@@ -638,19 +699,64 @@ async fn main() {
                         map.insert("error_chain".to_string(), json!(error_chain));
                     }
 
+                    if let Some(start_err) = e
+                        .chain()
+                        .find_map(|e| e.downcast_ref::<ProviderStartError>())
+                    {
+                        map.insert("failure_phase".to_string(), json!(start_err.phase));
+                        map.insert("failure_class".to_string(), json!(start_err.class));
+                    }
+
+                    if let Some(rolled_back) =
+                        e.chain().find_map(|e| e.downcast_ref::<LinksRolledBack>())
+                    {
+                        map.insert("rolled_back_links".to_string(), json!(rolled_back.links));
+                        map.insert("restored_links".to_string(), json!(rolled_back.restored));
+                        map.insert(
+                            "failed_link_rollbacks".to_string(),
+                            json!(rolled_back.failed),
+                        );
+                    }
+
+                    if let Some(drifted) = e.chain().find_map(|e| e.downcast_ref::<LinksDrifted>())
+                    {
+                        map.insert("drift".to_string(), json!(drifted.diff));
+                    }
+
                     let backtrace = e.backtrace().to_string();
 
                     if !backtrace.is_empty() && backtrace != "disabled backtrace" {
                         map.insert("backtrace".to_string(), json!(backtrace));
                     }
 
-                    eprintln!("\n{}", serde_json::to_string_pretty(&map).unwrap());
+                    if output_kind == OutputKind::Ndjson {
+                        eprintln!("{}", serde_json::to_string(&map).unwrap());
+                    } else if output_kind == OutputKind::Yaml {
+                        eprint!("\n{}", wash::lib::cli::render::yaml(&map));
+                    } else {
+                        eprintln!("\n{}", serde_json::to_string_pretty(&map).unwrap());
+                    }
                 }
-                OutputKind::Text => {
-                    eprintln!("\n{e:?}");
+                OutputKind::Text | OutputKind::Wide => {
+                    let text = format!("{e:?}");
+                    let mut stderr = stderr().lock();
+                    let _ = writeln!(stderr);
+                    let _ = if use_color(cli.no_color, stderr.is_terminal()) {
+                        write_text_output(
+                            &mut termcolor::Ansi::new(&mut stderr),
+                            &text,
+                            OutputStatus::Failure,
+                        )
+                    } else {
+                        write_text_output(
+                            &mut termcolor::NoColor::new(&mut stderr),
+                            &text,
+                            OutputStatus::Failure,
+                        )
+                    };
                 }
             }
-            1
+            failure_kind.map_or(1, |kind| kind.exit_code())
         }
     };
 
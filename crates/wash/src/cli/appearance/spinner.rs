@@ -1,7 +1,9 @@
 use std::borrow::Cow;
+use std::sync::Arc;
 
 use anyhow::Result;
 use indicatif::{ProgressBar, ProgressStyle};
+use crate::lib::cli::progress::{Progress, ProgressEvent, ProgressSink};
 use crate::lib::cli::OutputKind;
 
 // For more spinners check out the cli-spinners project:
@@ -20,7 +22,7 @@ pub struct Spinner {
 impl Spinner {
     pub fn new(output_kind: &OutputKind) -> Result<Self> {
         match output_kind {
-            OutputKind::Text => {
+            OutputKind::Text | OutputKind::Wide => {
                 let style = ProgressStyle::default_spinner()
                     .tick_strings(DOTS_12)
                     .template("{prefix:.bold.dim} {spinner:.bold.dim} {wide_msg:.bold.dim}")?;
@@ -32,7 +34,7 @@ impl Spinner {
                     spinner: Some(spinner),
                 })
             }
-            OutputKind::Json => Ok(Self { spinner: None }),
+            OutputKind::Json | OutputKind::Ndjson | OutputKind::Yaml => Ok(Self { spinner: None }),
         }
     }
 
@@ -45,9 +47,27 @@ impl Spinner {
         }
     }
 
+    /// Progress that replaces the spinner message with each step as it is reached, if there is a
+    /// spinner
+    #[must_use]
+    pub fn progress(&self) -> Option<Progress> {
+        self.spinner
+            .clone()
+            .map(|spinner| Progress::new(Arc::new(SpinnerProgress(spinner))))
+    }
+
     pub fn finish_and_clear(&self) {
         if let Some(progress_bar) = &self.spinner {
             progress_bar.finish_and_clear();
         }
     }
 }
+
+struct SpinnerProgress(ProgressBar);
+
+impl ProgressSink for SpinnerProgress {
+    fn report(&self, event: &ProgressEvent) {
+        self.0.set_prefix(">>>");
+        self.0.set_message(format!(" {} ... ", event.message));
+    }
+}
@@ -1,16 +1,20 @@
 use std::{collections::HashMap, path::PathBuf};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use clap::Parser;
 use serde_json::json;
 
 use crate::lib::{
-    build::{build_project, sign_component_wasm, SignConfig},
-    cli::{CommandOutput, CommonPackageArgs},
-    parser::{load_config, TypeConfig},
+    build::{
+        build_project, build_workspace, clear_build_cache, collect_build_metadata,
+        sign_component_wasm, workspace_manifest, SignConfig,
+    },
+    cli::{claims::SigningBackend, CommandOutput, CommonPackageArgs},
+    parser::{load_config, load_workspace_config, ProjectConfig, TypeConfig, WorkspaceConfig},
 };
 
-/// Build (and sign) a wasmCloud component, provider, or interface
+/// Build (and sign) a wasmCloud component, provider, or interface. Building a workspace builds
+/// every member and writes a manifest deploying all of them
 #[derive(Debug, Parser, Clone)]
 #[clap(name = "build")]
 pub struct BuildCommand {
@@ -47,6 +51,22 @@ pub struct BuildCommand {
     #[clap(long = "disable-keygen")]
     pub disable_keygen: bool,
 
+    /// Where the issuer key is held: `file` for a seed given with --issuer or found in the keys
+    /// directory, `env:VAR` for a seed in the environment variable VAR, or `exec:COMMAND` for an
+    /// external signer (see `wash claims sign --help`)
+    #[clap(
+        long = "sign-with",
+        env = "WASH_SIGN_WITH",
+        default_value_t = SigningBackend::File
+    )]
+    pub sign_with: SigningBackend,
+
+    /// Embed metadata about the build into the claims of components: the git revision and digest
+    /// of the sources, the time of the build (or $SOURCE_DATE_EPOCH) and the versions of the
+    /// toolchains used. See it with `wash inspect`
+    #[clap(long = "embed-build-metadata")]
+    pub embed_build_metadata: bool,
+
     /// Skip signing the artifact and only use the native toolchain to build
     #[clap(long = "build-only", conflicts_with = "sign_only")]
     pub build_only: bool,
@@ -59,10 +79,21 @@ pub struct BuildCommand {
     /// (useful for airgapped or disconnected environments)
     #[clap(long = "skip-fetch")]
     pub skip_wit_fetch: bool,
+
+    /// Build even if nothing changed since the last build, instead of reusing its artifact
+    #[clap(long = "no-cache")]
+    pub no_cache: bool,
 }
 
 pub async fn handle_command(command: BuildCommand) -> Result<CommandOutput> {
+    if let Some(workspace) = load_workspace_config(command.config_path.clone()).await? {
+        return handle_build_workspace(command, workspace).await;
+    }
     let config = load_config(command.config_path, Some(true)).await?;
+    check_signing_backend(&config, &command.sign_with)?;
+    if command.no_cache {
+        clear_build_cache(&config).await?;
+    }
 
     match config.project_type {
         TypeConfig::Component(ref component_config) => {
@@ -77,6 +108,8 @@ pub async fn handle_command(command: BuildCommand) -> Result<CommandOutput> {
                     issuer: command.issuer,
                     subject: command.subject,
                     disable_keygen: command.disable_keygen,
+                    sign_with: command.sign_with.clone(),
+                    embed_build_metadata: command.embed_build_metadata,
                 })
             };
 
@@ -90,12 +123,17 @@ pub async fn handle_command(command: BuildCommand) -> Result<CommandOutput> {
                             .build_dir
                             .join(format!("{}.wasm", config.common.wasm_bin_name()))
                     };
+                let build_metadata = command
+                    .embed_build_metadata
+                    .then(|| collect_build_metadata(&config.common, Some(&config.language)))
+                    .transpose()?;
                 let signed_path = sign_component_wasm(
                     &config.common,
                     component_config,
                     // We prevent supplying both fields in the CLI parser, so this `context` is just a safety fallback
                     &sign_config.context("cannot supply --build-only and --sign-only")?,
                     component_wasm_path,
+                    build_metadata,
                 )?;
                 config.common.build_dir.join(signed_path)
             } else {
@@ -135,6 +173,8 @@ pub async fn handle_command(command: BuildCommand) -> Result<CommandOutput> {
                     issuer: command.issuer,
                     subject: command.subject,
                     disable_keygen: command.disable_keygen,
+                    sign_with: command.sign_with.clone(),
+                    embed_build_metadata: command.embed_build_metadata,
                 }),
                 &command.package_args,
                 command.skip_wit_fetch,
@@ -149,6 +189,104 @@ pub async fn handle_command(command: BuildCommand) -> Result<CommandOutput> {
     }
 }
 
+/// Provider archives are signed with the key pair of the issuer as they are written, so they can't
+/// be signed by an external signer
+fn check_signing_backend(config: &ProjectConfig, sign_with: &SigningBackend) -> Result<()> {
+    if let (TypeConfig::Provider(_), SigningBackend::Exec(_)) = (&config.project_type, sign_with) {
+        bail!(
+            "provider [{}] can't be signed with an external signer, use a file or environment variable key",
+            config.common.name
+        );
+    }
+    Ok(())
+}
+
+async fn handle_build_workspace(
+    command: BuildCommand,
+    workspace: WorkspaceConfig,
+) -> Result<CommandOutput> {
+    if command.sign_only {
+        bail!("--sign-only can't be used to build a workspace, build its members instead");
+    }
+    if command.subject.is_some() {
+        bail!(
+            "--subject can't be used to build a workspace, each member is signed with its own key"
+        );
+    }
+    if !command.build_only {
+        for member in &workspace.members {
+            check_signing_backend(member, &command.sign_with)?;
+        }
+    }
+    if command.no_cache {
+        for member in &workspace.members {
+            clear_build_cache(member).await?;
+        }
+    }
+
+    let signing = |member: &ProjectConfig| {
+        if command.build_only {
+            return None;
+        }
+        let member_keys = match &member.project_type {
+            TypeConfig::Component(c) => c.key_directory.clone(),
+            TypeConfig::Provider(p) => p.key_directory.clone(),
+        };
+        Some(SignConfig {
+            keys_directory: command.keys_directory.clone().or(Some(member_keys)),
+            issuer: command.issuer.clone(),
+            subject: None,
+            disable_keygen: command.disable_keygen,
+            sign_with: command.sign_with.clone(),
+            embed_build_metadata: command.embed_build_metadata,
+        })
+    };
+    let built = build_workspace(
+        &workspace,
+        signing,
+        &command.package_args,
+        command.skip_wit_fetch,
+    )
+    .await?;
+
+    let manifest_path = workspace
+        .dir
+        .join("build")
+        .join(format!("{}.wadm.yaml", workspace.name));
+    tokio::fs::create_dir_all(workspace.dir.join("build"))
+        .await
+        .context("failed to create workspace build directory")?;
+    tokio::fs::write(
+        &manifest_path,
+        serde_yaml::to_string(&workspace_manifest(&workspace, &built))?,
+    )
+    .await
+    .context("failed to write workspace manifest")?;
+
+    let mut text = format!("Built {} workspace member(s):", built.len());
+    for member in &built {
+        text.push_str(&format!(
+            "\n  {}: {}",
+            member.name,
+            member.artifact.display()
+        ));
+    }
+    text.push_str(&format!(
+        "\nA manifest deploying them can be found at {manifest_path:?}"
+    ));
+    let members = built
+        .iter()
+        .map(|m| json!({ "name": m.name, "path": m.artifact, "provider": m.provider }))
+        .collect::<Vec<_>>();
+    Ok(CommandOutput::new(
+        text,
+        HashMap::from([
+            ("members".to_string(), json!(members)),
+            ("manifest_path".to_string(), json!(manifest_path)),
+        ]),
+    ))
+}
+
 #[cfg(test)]
 mod test {
 
@@ -163,6 +301,9 @@ mod test {
         assert!(cmd.issuer.is_none());
         assert!(cmd.subject.is_none());
         assert!(cmd.keys_directory.is_none());
+        assert!(!cmd.no_cache);
+        assert_eq!(cmd.sign_with, SigningBackend::File);
+        assert!(!cmd.embed_build_metadata);
 
         let cmd: BuildCommand = Parser::try_parse_from([
             "build",
@@ -175,6 +316,10 @@ mod test {
             "/tmp/sub.nk",
             "--keys-directory",
             "/tmp",
+            "--no-cache",
+            "--sign-with",
+            "exec:kms-signer --key wasmcloud",
+            "--embed-build-metadata",
         ])
         .unwrap();
         assert_eq!(cmd.config_path, Some(PathBuf::from("/")));
@@ -182,5 +327,11 @@ mod test {
         assert_eq!(cmd.issuer, Some("/tmp/iss.nk".to_string()));
         assert_eq!(cmd.subject, Some("/tmp/sub.nk".to_string()));
         assert_eq!(cmd.keys_directory, Some(PathBuf::from("/tmp")));
+        assert!(cmd.no_cache);
+        assert!(cmd.embed_build_metadata);
+        assert_eq!(
+            cmd.sign_with,
+            SigningBackend::Exec("kms-signer --key wasmcloud".into())
+        );
     }
 }
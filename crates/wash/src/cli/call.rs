@@ -12,13 +12,16 @@ use serde_json::json;
 use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 use tracing::debug;
 
+use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
 use wasmcloud_core::parse_wit_meta_from_operation;
-use wit_bindgen_wrpc::wrpc_transport::InvokeExt as _;
+use wit_bindgen_wrpc::wrpc_transport::{Invoke as _, InvokeExt as _};
 
-use crate::lib::cli::{validate_component_id, CommandOutput};
-use crate::lib::config::DEFAULT_LATTICE;
+use crate::lib::call::{read_cbor_args, FunctionSignature};
+use crate::lib::cli::{validate_component_id, CliConnectionOpts, CommandOutput};
+use crate::lib::config::{close_ctl_client, WashConnectionOptions, DEFAULT_LATTICE};
 use crate::lib::context::fs::ContextDir;
 use crate::lib::context::ContextManager;
+use crate::lib::registry::OciPullOptions;
 use crate::util::{default_timeout_ms, extract_arg_value, msgpack_to_json_val};
 
 const DEFAULT_HTTP_SCHEME: &str = "http";
@@ -109,9 +112,15 @@ pub async fn handle_command(
         component_id,
         function,
         opts,
+        ctl_opts,
+        registry_opts,
         http_handler_invocation_opts,
         http_response_extract_json,
-        ..
+        wit,
+        args,
+        args_stdin,
+        args_format,
+        generate_payload,
     }: CallCommand,
 ) -> Result<CommandOutput> {
     ensure!(!component_id.is_empty(), "component ID may not be empty");
@@ -130,7 +139,8 @@ pub async fn handle_command(
         .await
         .context("failed to create async nats client")?;
     let wrpc_client =
-        wrpc_transport_nats::Client::new(nc, format!("{}.{component_id}", &lattice), None).await?;
+        wrpc_transport_nats::Client::new(nc.clone(), format!("{}.{component_id}", &lattice), None)
+            .await?;
 
     let (namespace, package, interface, name) = parse_wit_meta_from_operation(&function).context(
         "Invalid function supplied. Must be in the form of `namespace:package/interface.function`",
@@ -165,7 +175,59 @@ pub async fn handle_command(
             )
             .await
         }
-        // Assume the call is a function that takes no input and produces a string
+        _ if wit.is_some() || generate_payload || args_stdin || !args.is_empty() => {
+            let signature = match wit {
+                Some(wit) => FunctionSignature::load(&wit, &instance, &name)?,
+                None => {
+                    let wco: WashConnectionOptions = ctl_opts
+                        .into_cli_connection_opts(&opts, &lattice)
+                        .try_into()?;
+                    let ctl_client = wco.into_ctl_client(None).await?;
+                    let signature = FunctionSignature::fetch(
+                        &ctl_client,
+                        &component_id,
+                        &instance,
+                        &name,
+                        registry_opts.into(),
+                    )
+                    .await;
+                    close_ctl_client(&ctl_client).await;
+                    signature?
+                }
+            };
+            if generate_payload {
+                let payload = signature.payload_template();
+                return Ok(CommandOutput::new(
+                    serde_json::to_string_pretty(&payload).context("failed to print payload")?,
+                    HashMap::from([("payload".to_string(), payload)]),
+                ));
+            }
+            let args: Vec<serde_json::Value> = if args_stdin {
+                match args_format {
+                    ArgsFormat::Json => serde_json::from_reader(std::io::stdin().lock())
+                        .context("failed to read arguments from stdin as a JSON array")?,
+                    ArgsFormat::Cbor => read_cbor_args(std::io::stdin().lock())
+                        .context("failed to read arguments from stdin as a CBOR array")?,
+                }
+            } else {
+                args.iter()
+                    .map(String::as_str)
+                    .map(parse_call_arg)
+                    .collect()
+            };
+            wrpc_invoke_typed(
+                wrpc_client,
+                &lattice,
+                &component_id,
+                &instance,
+                &name,
+                opts.timeout_ms,
+                &signature,
+                &args,
+            )
+            .await
+        }
+        // Without arguments or WIT, assume the function takes no input and produces a string
         _ => {
             wrpc_invoke_simple(
                 wrpc_client,
@@ -244,11 +306,117 @@ pub struct ConnectionOpts {
     pub context_dir: Option<PathBuf>,
 }
 
+/// Control interface connection used to find the image of the component when its WIT is read
+/// from the lattice. Anything omitted comes from the context
+#[derive(Debug, Clone, Args)]
+pub struct CtlConnectionOpts {
+    /// CTL Host for connection, defaults to the host of the context
+    #[clap(long = "ctl-host", env = "WASMCLOUD_CTL_HOST")]
+    ctl_host: Option<String>,
+
+    /// CTL Port for connections, defaults to the port of the context
+    #[clap(long = "ctl-port", env = "WASMCLOUD_CTL_PORT")]
+    ctl_port: Option<String>,
+
+    /// JWT file for CTL authentication. Must be supplied with `ctl_seed`.
+    #[clap(long = "ctl-jwt", env = "WASMCLOUD_CTL_JWT", hide_env_values = true)]
+    ctl_jwt: Option<String>,
+
+    /// Seed file or literal for CTL authentication. Must be supplied with `ctl_jwt`.
+    #[clap(long = "ctl-seed", env = "WASMCLOUD_CTL_SEED", hide_env_values = true)]
+    ctl_seed: Option<String>,
+
+    /// Credsfile for CTL authentication. Combines `ctl_seed` and `ctl_jwt`.
+    /// See <https://docs.nats.io/using-nats/developer/connecting/creds> for details.
+    #[clap(long = "ctl-credsfile", env = "WASH_CTL_CREDS", hide_env_values = true)]
+    ctl_credsfile: Option<PathBuf>,
+
+    /// TLS CA file for CTL authentication. See <https://docs.nats.io/using-nats/developer/connecting/tls> for details.
+    #[clap(
+        long = "ctl-tls-ca-file",
+        env = "WASH_CTL_TLS_CA_FILE",
+        hide_env_values = true
+    )]
+    ctl_tls_ca_file: Option<PathBuf>,
+
+    /// Perform TLS handshake before expecting the server greeting.
+    #[clap(
+        long = "ctl-tls-first",
+        env = "WASH_CTL_TLS_FIRST",
+        hide_env_values = true
+    )]
+    ctl_tls_first: Option<bool>,
+}
+
+impl CtlConnectionOpts {
+    /// Connection options for the control interface of the lattice being called, sharing the
+    /// context and timeout of the RPC connection
+    fn into_cli_connection_opts(self, rpc: &ConnectionOpts, lattice: &str) -> CliConnectionOpts {
+        CliConnectionOpts {
+            ctl_host: self.ctl_host,
+            ctl_port: self.ctl_port,
+            ctl_jwt: self.ctl_jwt,
+            ctl_seed: self.ctl_seed,
+            ctl_credsfile: self.ctl_credsfile,
+            ctl_tls_ca_file: self.ctl_tls_ca_file,
+            ctl_tls_first: self.ctl_tls_first,
+            js_domain: None,
+            lattice: Some(lattice.to_string()),
+            timeout_ms: rpc.timeout_ms,
+            idle_timeout_ms: None,
+            context: rpc.context.clone(),
+        }
+    }
+}
+
+/// Registry options for pulling the component when its WIT is read from the lattice
+#[derive(Debug, Clone, Args)]
+pub struct CallRegistryOpts {
+    /// Allow pulling the component from a `latest` tag
+    #[clap(long = "allow-latest")]
+    allow_latest: bool,
+
+    /// OCI username, if omitted anonymous authentication will be used
+    #[clap(long = "user", env = "WASH_REG_USER", hide_env_values = true)]
+    user: Option<String>,
+
+    /// OCI password, if omitted anonymous authentication will be used
+    #[clap(long = "password", env = "WASH_REG_PASSWORD", hide_env_values = true)]
+    password: Option<String>,
+
+    /// Allow insecure (HTTP) registry connections
+    #[clap(long = "insecure")]
+    insecure: bool,
+
+    /// Skip checking OCI registry's certificate for validity
+    #[clap(long = "insecure-skip-tls-verify")]
+    insecure_skip_tls_verify: bool,
+}
+
+impl From<CallRegistryOpts> for OciPullOptions {
+    fn from(opts: CallRegistryOpts) -> Self {
+        Self {
+            digest: None,
+            allow_latest: opts.allow_latest,
+            user: opts.user,
+            password: opts.password,
+            insecure: opts.insecure,
+            insecure_skip_tls_verify: opts.insecure_skip_tls_verify,
+        }
+    }
+}
+
 #[derive(Args, Debug, Clone)]
 pub struct CallCommand {
     #[clap(flatten)]
     opts: ConnectionOpts,
 
+    #[clap(flatten)]
+    ctl_opts: CtlConnectionOpts,
+
+    #[clap(flatten)]
+    registry_opts: CallRegistryOpts,
+
     /// The unique component identifier of the component to invoke
     #[clap(name = "component-id", value_parser = validate_component_id)]
     pub component_id: String,
@@ -268,6 +436,56 @@ pub struct CallCommand {
     /// Customizable options related to the HTTP handler invocation (HTTP path, method, etc)
     #[clap(flatten)]
     pub http_handler_invocation_opts: HttpHandlerInvocationOpts,
+
+    /// WIT used to encode the arguments and decode the result of the function: a WIT file or
+    /// directory declaring its interface, or the built component. If omitted when arguments are
+    /// given or a payload is generated, the WIT is read from the running component, pulled from
+    /// its image reference. Otherwise the function must take no arguments and return a string
+    #[clap(long = "wit", env = "WASH_CALL_WIT")]
+    pub wit: Option<PathBuf>,
+
+    /// Argument to pass to the function, as JSON. A value that isn't valid JSON is passed as a
+    /// string. Pass once per parameter, in order
+    #[clap(long = "arg", name = "args", conflicts_with = "args_stdin")]
+    pub args: Vec<String>,
+
+    /// Read the arguments to pass to the function from stdin, as an array with one value per
+    /// parameter encoded as given by `--args-format`
+    #[clap(long = "args-stdin")]
+    pub args_stdin: bool,
+
+    /// Encoding of the arguments read with `--args-stdin`
+    #[clap(
+        long = "args-format",
+        value_enum,
+        default_value_t = ArgsFormat::Json,
+        requires = "args_stdin"
+    )]
+    pub args_format: ArgsFormat,
+
+    /// Print a JSON array with a placeholder value for each parameter of the function instead of
+    /// calling it. Fill it in and pass it back with `--args-stdin`
+    #[clap(
+        long = "generate-payload",
+        conflicts_with_all = ["args", "args_stdin"]
+    )]
+    pub generate_payload: bool,
+}
+
+/// Encoding of the arguments read with `--args-stdin`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ArgsFormat {
+    /// JSON, as printed by `--generate-payload`
+    #[default]
+    Json,
+    /// CBOR, with byte strings taken as arrays of numbers, e.g. for `list<u8>`
+    Cbor,
+}
+
+/// Parse an `--arg` value as JSON, falling back to passing it as a string so e.g. `--arg hello`
+/// doesn't need quoting
+fn parse_call_arg(arg: &str) -> serde_json::Value {
+    serde_json::from_str(arg).unwrap_or_else(|_| serde_json::Value::String(arg.to_string()))
 }
 
 /// Options that customize the HTTP request that is fed to a HTTP handler when using `wash call`
@@ -469,6 +687,66 @@ async fn wrpc_invoke_simple(
    }
 }
 
+/// Invoke a wRPC endpoint with arguments and results typed by the function's WIT
+#[allow(clippy::too_many_arguments)]
+async fn wrpc_invoke_typed(
+    client: wrpc_transport_nats::Client,
+    lattice: &str,
+    component_id: &str,
+    instance: &str,
+    function_name: &str,
+    timeout_ms: u64,
+    signature: &FunctionSignature,
+    args: &[serde_json::Value],
+) -> Result<CommandOutput> {
+    let params = signature.encode_params(args)?;
+    let invocation = async {
+        let (mut outgoing, mut incoming) = client
+            .invoke(
+                Some(gen_wash_call_headers()),
+                instance,
+                function_name,
+                params,
+                &[[]; 0],
+            )
+            .await?;
+        outgoing
+            .shutdown()
+            .await
+            .context("failed to shutdown parameter channel")?;
+        let mut results = Vec::new();
+        incoming
+            .read_to_end(&mut results)
+            .await
+            .context("failed to receive results")?;
+        anyhow::Ok(results)
+    };
+    let results = match tokio::time::timeout(Duration::from_millis(timeout_ms), invocation)
+        .await
+        .with_context(|| format!("timed out invoking component, is component [{component_id}] running in lattice [{lattice}]?"))?
+    {
+        Ok(results) => results,
+        Err(e) if e.to_string().contains("transmission failed") => bail!("No component responded to your request, ensure component {component_id} is running in lattice {lattice}"),
+        Err(e) => bail!("Error invoking component: {e}"),
+    };
+
+    let mut results = signature.decode_results(&results)?;
+    let result = match results.len() {
+        0 => serde_json::Value::Null,
+        1 => results.remove(0),
+        _ => serde_json::Value::Array(results),
+    };
+    let text = match &result {
+        serde_json::Value::Null => "Call succeeded".to_string(),
+        serde_json::Value::String(s) => s.clone(),
+        other => serde_json::to_string_pretty(other).context("failed to print result")?,
+    };
+    Ok(CommandOutput::new(
+        text,
+        HashMap::from([("result".to_string(), result)]),
+    ))
+}
+
 // Helper output functions, used to ensure consistent output between call & standalone commands
 pub fn call_output(
     response: Vec<u8>,
@@ -634,7 +912,7 @@ fn gen_wash_call_headers() -> async_nats::HeaderMap {
 
 #[cfg(test)]
 mod test {
-    use super::CallCommand;
+    use super::{parse_call_arg, ArgsFormat, CallCommand};
     use anyhow::Result;
     use clap::Parser;
 
@@ -688,6 +966,84 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn typed_arguments_are_parsed() -> Result<()> {
+        let call: Cmd = Parser::try_parse_from([
+            "call",
+            "--wit",
+            "./wit",
+            "--arg",
+            r#"{"name": "triangle"}"#,
+            "--arg",
+            "hello",
+            COMPONENT_ID,
+            "wasmcloud:test/shapes.describe",
+        ])?;
+        assert_eq!(call.command.wit, Some("./wit".into()));
+        assert_eq!(
+            call.command
+                .args
+                .iter()
+                .map(String::as_str)
+                .map(parse_call_arg)
+                .collect::<Vec<_>>(),
+            [
+                serde_json::json!({"name": "triangle"}),
+                serde_json::json!("hello")
+            ]
+        );
+        assert_eq!(parse_call_arg("3"), serde_json::json!(3));
+
+        // Without --wit, the WIT is read from the running component
+        let call: Cmd = Parser::try_parse_from([
+            "call",
+            "--arg",
+            "hello",
+            COMPONENT_ID,
+            "wasmcloud:test/shapes.describe",
+        ])?;
+        assert_eq!(call.command.wit, None);
+        assert!(Cmd::try_parse_from([
+            "call",
+            "--generate-payload",
+            "--arg",
+            "hello",
+            COMPONENT_ID,
+            "wasmcloud:test/shapes.describe",
+        ])
+        .is_err());
+        assert!(Cmd::try_parse_from([
+            "call",
+            "--wit",
+            "./wit",
+            "--arg",
+            "hello",
+            "--args-stdin",
+            COMPONENT_ID,
+            "wasmcloud:test/shapes.describe",
+        ])
+        .is_err());
+
+        let call: Cmd = Parser::try_parse_from([
+            "call",
+            "--args-stdin",
+            "--args-format",
+            "cbor",
+            COMPONENT_ID,
+            "wasmcloud:test/shapes.describe",
+        ])?;
+        assert_eq!(call.command.args_format, ArgsFormat::Cbor);
+        assert!(Cmd::try_parse_from([
+            "call",
+            "--args-format",
+            "cbor",
+            COMPONENT_ID,
+            "wasmcloud:test/shapes.describe",
+        ])
+        .is_err());
+        Ok(())
+    }
+
     /// Ensure wash call uses context
     #[test]
     fn test_use_context() -> Result<()> {
@@ -3,6 +3,7 @@ use std::path::PathBuf;
 
 use anyhow::{bail, ensure, Context as _, Result};
 use console::style;
+use sha2::{Digest, Sha256};
 use tracing::{debug, warn};
 use crate::lib::app::AppManifest;
 use crate::lib::cli::stop::stop_provider;
@@ -38,6 +39,8 @@ pub struct RunLoopState<'a> {
     pub(crate) manifest_output_dir: Option<&'a PathBuf>,
     pub(crate) previous_deps: Option<ProjectDeps>,
     pub(crate) artifact_path: Option<PathBuf>,
+    /// SHA-256 of the artifact that was last deployed, to skip reloading an unchanged component
+    pub(crate) artifact_sha256: Option<String>,
     pub(crate) component_id: Option<String>,
     pub(crate) component_ref: Option<String>,
     pub(crate) package_args: &'a CommonPackageArgs,
@@ -323,7 +326,7 @@ async fn update_secret_properties_by_spec(
 pub async fn run(state: &mut RunLoopState<'_>) -> Result<()> {
     // Build the project (equivalent to `wash build`)
     let spinner = Spinner::new(&state.output_kind).context("failed to create spinner")?;
-    if matches!(state.output_kind, OutputKind::Text) {
+    if matches!(state.output_kind, OutputKind::Text | OutputKind::Wide) {
         spinner.update_spinner_message("Building project...");
     } else {
         eprintln!(
@@ -360,6 +363,10 @@ pub async fn run(state: &mut RunLoopState<'_>) -> Result<()> {
         emoji::GREEN_CHECK,
         built_artifact_path.display()
     );
+    let artifact_sha256 = tokio::fs::read(&built_artifact_path)
+        .await
+        .map(|artifact| format!("{:x}", Sha256::digest(artifact)))
+        .context("failed to read built artifact")?;
 
     // Update the dev loop state for reuse
     state.component_id = Some(format!(
@@ -415,6 +422,17 @@ pub async fn run(state: &mut RunLoopState<'_>) -> Result<()> {
         .as_ref()
         .context("unexpectedly missing component_ref")?;
 
+    // Nothing to do if neither the component nor its dependencies changed, e.g. when only a file
+    // that doesn't go into the build was touched
+    if manifests.is_empty() && state.artifact_sha256.as_ref() == Some(&artifact_sha256) {
+        eprintln!(
+            "{} {}",
+            emoji::GREEN_CHECK,
+            style(format!("Component [{component_id}] is unchanged, skipping reload")).bold()
+        );
+        return Ok(());
+    }
+
     // If manifests are empty, let the user know we're not deploying anything, just reloading
     // the same component
     if manifests.is_empty() {
@@ -512,6 +530,7 @@ pub async fn run(state: &mut RunLoopState<'_>) -> Result<()> {
         }
     }
 
+    state.artifact_sha256 = Some(artifact_sha256);
     Ok(())
 }
 
@@ -11,10 +11,11 @@ use semver::Version;
 use session::{SessionMetadata, WashDevSession};
 use tokio::{select, sync::mpsc};
 
+use crate::lib::backoff::Backoff;
 use crate::lib::cli::{CommandOutput, CommonPackageArgs};
 use crate::lib::generate::emoji;
 use crate::lib::id::ServerId;
-use crate::lib::parser::load_config;
+use crate::lib::parser::{load_config, load_workspace_config};
 use tracing::trace;
 
 use wasmcloud_control_interface::Client;
@@ -140,6 +141,18 @@ pub async fn handle_command(
     let current_dir =
         std::env::current_dir().context("failed to get current directory for wash dev")?;
     let project_path = cmd.code_dir.unwrap_or(current_dir);
+    if let Some(workspace) = load_workspace_config(Some(project_path.clone())).await? {
+        let members = workspace
+            .members
+            .iter()
+            .map(|m| format!("  {}", m.wasmcloud_toml_dir.display()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        bail!(
+            "[{}] is a workspace, run wash dev in one of its members instead (use `wash build` to build all of them):\n{members}",
+            project_path.display()
+        );
+    }
     let mut project_cfg = load_config(Some(project_path.clone()), Some(true)).await?;
 
     let mut wash_dev_session = WashDevSession::from_sessions_file(&project_path)
@@ -249,6 +262,7 @@ pub async fn handle_command(
         manifest_output_dir: cmd.manifest_output_dir.as_ref(),
         previous_deps: None,
         artifact_path: None,
+        artifact_sha256: None,
         component_id: None,
         component_ref: None,
         package_args: &cmd.package_args,
@@ -347,6 +361,15 @@ pub async fn handle_command(
         }
     })?;
     watcher.watch(&project_path.clone(), RecursiveMode::Recursive)?;
+    // The WIT directory is usually in the project, but it can be configured to be elsewhere
+    if let (Ok(wit_dir), Ok(project_dir)) = (
+        run_loop_state.project_cfg.common.wit_dir.canonicalize(),
+        project_path.canonicalize(),
+    ) {
+        if !wit_dir.starts_with(project_dir) {
+            watcher.watch(&wit_dir, RecursiveMode::Recursive)?;
+        }
+    }
 
     // NOTE(brooksmtownsend): Yes, it would make more sense to return here. For some reason unknown to me
     // trying to return any error here will just cause the dev loop to hang infinitely and require a force quit.
@@ -373,9 +396,11 @@ pub async fn handle_command(
             // Process a file change/reload
             _ = reload_rx.recv() => {
                 pause_watch.store(true, Ordering::SeqCst);
-                devloop::run(&mut run_loop_state)
-                    .await
-                    .context("failed to run dev loop iteration")?;
+                // Like build errors, failing to reload can be fixed by changing the project (or
+                // restarting a dependency), so keep watching for changes instead of exiting
+                if let Err(e) = devloop::run(&mut run_loop_state).await {
+                    eprintln!("{} Failed to reload: {e:#}", emoji::ERROR);
+                }
                 eprintln!("\n{} Watching for file changes (press Ctrl+c to stop)...", emoji::EYES);
                 // Avoid jitter with reloads by pausing the watcher for a short time
                 tokio::time::sleep(std::time::Duration::from_millis(100)).await;
@@ -512,19 +537,11 @@ pub async fn is_host_up(
     timeout: tokio::time::Duration,
     backoff: tokio::time::Duration,
 ) -> Result<()> {
-    async fn func(
-        host_id: &str,
-        ctl_client: &Client,
-        backoff: tokio::time::Duration,
-    ) -> Result<()> {
-        loop {
-            if (ctl_client.get_host_inventory(host_id).await).is_ok() {
-                break;
-            }
-            tokio::time::sleep(backoff).await;
-        }
-        Ok(())
-    }
-
-    tokio::time::timeout(timeout, func(host_id, ctl_client, backoff)).await?
+    let backoff = Backoff::fixed(backoff);
+    tokio::time::timeout(
+        timeout,
+        backoff.retry_forever(|| ctl_client.get_host_inventory(host_id)),
+    )
+    .await?;
+    Ok(())
 }
@@ -0,0 +1,113 @@
+//! Functionality enabling the `wash link apply` subcommand
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, bail, Context as _, Result};
+use serde_json::json;
+
+use crate::appearance::spinner::Spinner;
+use crate::lib::cli::link::{
+    delete_link, get_links, load_links_snapshot, put_link, LinkApplyCommand,
+};
+use crate::lib::cli::{CommandOutput, OutputKind};
+use crate::lib::config::WashConnectionOptions;
+use crate::lib::links::Links;
+
+/// Invoke `wash link apply` subcommand
+pub async fn invoke(
+    LinkApplyCommand {
+        opts,
+        file,
+        prune,
+        dry_run,
+    }: LinkApplyCommand,
+    output_kind: OutputKind,
+) -> Result<CommandOutput> {
+    let desired = Links::builder()
+        .links(load_links_snapshot(&file)?)
+        .build()
+        .with_context(|| format!("invalid links in `{}`", file.display()))?;
+
+    let sp: Spinner = Spinner::new(&output_kind)?;
+    sp.update_spinner_message("Querying Links ... ".to_string());
+    let wco: WashConnectionOptions = opts.try_into()?;
+    let current = Links::from_iter(
+        get_links(wco.clone())
+            .await
+            .context("failed to retrieve links")?,
+    );
+    let plan = current.apply_plan(&desired, prune).map_err(|conflict| {
+        anyhow!(
+            "{conflict}. Declare the existing link in `{}` or apply with --prune to delete it",
+            file.display()
+        )
+    })?;
+
+    if !dry_run {
+        for key in &plan.delete {
+            sp.update_spinner_message(format!("Deleting link {key} ... "));
+            let ack = delete_link(
+                wco.clone(),
+                key.source_id(),
+                key.name(),
+                key.wit_namespace(),
+                key.wit_package(),
+            )
+            .await?;
+            if !ack.succeeded() {
+                bail!("Error deleting link {key}: {}", ack.message());
+            }
+        }
+        for (applied, link) in plan.put.iter().enumerate() {
+            sp.update_spinner_message(format!(
+                "Putting link {} -> {} ... ",
+                link.source_id(),
+                link.target()
+            ));
+            let ack = put_link(wco.clone(), link.clone())
+                .await
+                .with_context(|| format!("{applied} of {} link(s) were put", plan.put.len()))?;
+            if !ack.succeeded() {
+                bail!(
+                    "Error putting link {} -> {}: {}. {applied} of {} link(s) were put",
+                    link.source_id(),
+                    link.target(),
+                    ack.message(),
+                    plan.put.len()
+                );
+            }
+        }
+    }
+    sp.finish_and_clear();
+
+    let diff = &plan.diff;
+    let mut text = if plan.is_empty() {
+        format!("Links already match `{}`, nothing to do", file.display())
+    } else if dry_run {
+        format!("Would apply links from `{}`:\n{diff}", file.display())
+    } else {
+        format!("Applied links from `{}`:\n{diff}", file.display())
+    };
+    if !prune && !diff.removed.is_empty() {
+        text.push_str(&format!(
+            "\n{} link(s) not declared in the file were kept, use --prune to delete them",
+            diff.removed.len()
+        ));
+    }
+
+    let (pruned, kept) = if prune {
+        (&diff.removed[..], &[][..])
+    } else {
+        (&[][..], &diff.removed[..])
+    };
+    Ok(CommandOutput::new(
+        text,
+        HashMap::from([
+            ("dry_run".into(), json!(dry_run)),
+            ("added".into(), json!(diff.added)),
+            ("changed".into(), json!(diff.changed)),
+            ("removed".into(), json!(pruned)),
+            ("kept".into(), json!(kept)),
+        ]),
+    ))
+}
@@ -4,11 +4,14 @@ use std::collections::HashMap;
 
 use anyhow::{anyhow, bail, ensure, Context as _, Result};
 use serde_json::json;
-use crate::lib::cli::link::{delete_link, get_links, LinkDelCommand};
+use crate::lib::cli::link::{
+    delete_link, get_links, put_link, validate_interfaces, LinkDelCommand,
+};
 use crate::lib::cli::{CommandOutput, OutputKind};
 use crate::lib::config::WashConnectionOptions;
 use crate::lib::generate::interactive::prompt_for_choice;
 use crate::lib::generate::project_variables::StringEntry;
+use crate::lib::links::Links;
 
 use crate::appearance::spinner::Spinner;
 
@@ -19,12 +22,16 @@ pub async fn invoke(
         link_name,
         wit_namespace: namespace,
         wit_package: package,
+        interfaces,
         opts,
         all,
+        all_for_source,
+        dry_run,
         force,
     }: LinkDelCommand,
     output_kind: OutputKind,
 ) -> Result<CommandOutput> {
+    validate_interfaces(&interfaces).context("invalid link interfaces")?;
     let wco: WashConnectionOptions = opts.try_into()?;
 
     // If the user has chosen to delete all links, but *did not* force, then prompt
@@ -96,6 +103,10 @@ pub async fn invoke(
         }
     }
 
+    if let Some(source_id) = all_for_source {
+        return delete_links_for_source(wco, &source_id, dry_run, output_kind).await;
+    }
+
     let sp: Spinner = Spinner::new(&output_kind)?;
     let package = package.context("missing required argument package")?;
     let source_id = source_id.context("missing required argument source_id")?;
@@ -124,6 +135,21 @@ pub async fn invoke(
     };
 
     let link_name = link_name.clone().unwrap_or_else(|| "default".to_string());
+    if !interfaces.is_empty() {
+        sp.update_spinner_message(format!(
+            "Deleting {} from link for {source_id} on {namespace}:{package} ({link_name}) ... ",
+            interfaces.join(","),
+        ));
+        return delete_link_interfaces(
+            wco,
+            &source_id,
+            &link_name,
+            &namespace,
+            &package,
+            &interfaces,
+        )
+        .await;
+    }
     sp.update_spinner_message(format!(
         "Deleting link for {source_id} on {namespace}:{package} ({link_name}) ... ",
     ));
@@ -135,6 +161,171 @@ pub async fn invoke(
     link_del_output(&source_id, &link_name, &namespace, &package, failure)
 }
 
+/// Delete every link where `source_id` is the source, reporting each deleted link
+async fn delete_links_for_source(
+    wco: WashConnectionOptions,
+    source_id: &str,
+    dry_run: bool,
+    output_kind: OutputKind,
+) -> Result<CommandOutput> {
+    let sp: Spinner = Spinner::new(&output_kind)?;
+    let mut links = Links::from_iter(
+        get_links(wco.clone())
+            .await
+            .context("failed to retrieve links")?,
+    );
+    let links = links.drain_for_source(source_id);
+
+    let describe = |link: &wasmcloud_control_interface::Link| {
+        format!(
+            "{} -> {} on {}:{}/{} ({})",
+            link.source_id(),
+            link.target(),
+            link.wit_namespace(),
+            link.wit_package(),
+            link.interfaces().join(","),
+            link.name(),
+        )
+    };
+
+    if dry_run {
+        sp.finish_and_clear();
+        let mut text = format!("Would delete {} link(s) from {source_id}", links.len());
+        for link in &links {
+            text.push_str(&format!("\n  {}", describe(link)));
+        }
+        return Ok(CommandOutput::new(
+            text,
+            HashMap::from([
+                ("dry_run".into(), json!(true)),
+                ("deleted".into(), json!(links)),
+            ]),
+        ));
+    }
+
+    let mut deleted = Vec::with_capacity(links.len());
+    for link in &links {
+        sp.update_spinner_message(format!("Deleting link {} ... ", describe(link)));
+        delete_link(
+            wco.clone(),
+            link.source_id(),
+            link.name(),
+            link.wit_namespace(),
+            link.wit_package(),
+        )
+        .await
+        .with_context(|| {
+            format!(
+                "failed to delete link {}, {} link(s) were already deleted",
+                describe(link),
+                deleted.len()
+            )
+        })?;
+        deleted.push(link);
+    }
+    sp.finish_and_clear();
+
+    let mut text = format!("Deleted {} link(s) from {source_id}", deleted.len());
+    for link in &deleted {
+        text.push_str(&format!("\n  {}", describe(link)));
+    }
+    Ok(CommandOutput::new(
+        text,
+        HashMap::from([
+            ("dry_run".into(), json!(false)),
+            ("deleted".into(), json!(deleted)),
+        ]),
+    ))
+}
+
+/// Delete only the given interfaces from the link(s) under a link key. The host deletes a link key
+/// as a whole, so the key is deleted and the links keeping other interfaces are put back
+async fn delete_link_interfaces(
+    wco: WashConnectionOptions,
+    source_id: &str,
+    link_name: &str,
+    wit_namespace: &str,
+    wit_package: &str,
+    interfaces: &[String],
+) -> Result<CommandOutput> {
+    let mut links = Links::from_iter(
+        get_links(wco.clone())
+            .await
+            .context("failed to retrieve links")?,
+    );
+    let Some(key) = links
+        .iter_keys()
+        .find(|key| {
+            key.source_id() == source_id
+                && key.name() == link_name
+                && key.wit_namespace() == wit_namespace
+                && key.wit_package() == wit_package
+        })
+        .cloned()
+    else {
+        bail!("No link found for {source_id} on {wit_namespace}:{wit_package} ({link_name})");
+    };
+    let changed = links.remove_interfaces(&key, interfaces);
+    if changed.is_empty() {
+        bail!(
+            "No link for {key} covers interface(s) {}",
+            interfaces.join(",")
+        );
+    }
+    let remaining = links.get(&key).to_vec();
+
+    if remaining.is_empty() {
+        // No interface is left under the key, so the whole link goes
+        delete_link(
+            wco.clone(),
+            source_id,
+            link_name,
+            wit_namespace,
+            wit_package,
+        )
+        .await
+        .map_err(|e| anyhow!("Error deleting link: {e}"))?;
+    } else {
+        // Hosts update a link to the same target in place, so putting the link again with fewer
+        // interfaces leaves the other links under the key untouched. A link that loses every
+        // interface can't be removed on its own without deleting the whole key
+        let emptied = changed
+            .iter()
+            .filter(|link| !remaining.iter().any(|l| l.target() == link.target()))
+            .map(|link| link.target())
+            .collect::<Vec<_>>();
+        ensure!(
+            emptied.is_empty(),
+            "Deleting interface(s) {} would leave no interface on the link to {} while other links share {key}. Delete the link with `wash link del` and put the others again instead",
+            interfaces.join(","),
+            emptied.join(", ")
+        );
+        for link in remaining
+            .iter()
+            .filter(|l| changed.iter().any(|c| c.target() == l.target()))
+        {
+            put_link(wco.clone(), link.clone()).await.with_context(|| {
+                format!("failed to update the link for {key} to {}", link.target())
+            })?;
+        }
+    }
+
+    let mut map = HashMap::new();
+    map.insert("source_id".to_string(), json!(source_id));
+    map.insert("wit_namespace".to_string(), json!(wit_namespace));
+    map.insert("wit_package".to_string(), json!(wit_package));
+    map.insert("link_name".to_string(), json!(link_name));
+    map.insert("interfaces".to_string(), json!(interfaces));
+    map.insert("remaining".to_string(), json!(remaining));
+    Ok(CommandOutput::new(
+        format!(
+            "Deleted interface(s) {} from link for {source_id} on {wit_namespace}:{wit_package} ({link_name}) successfully",
+            interfaces.join(","),
+        ),
+        map,
+    ))
+}
+
 fn link_del_output(
     source_id: &str,
     link_name: &str,
@@ -0,0 +1,43 @@
+//! Functionality enabling the `wash link export` subcommand
+
+use std::collections::HashMap;
+
+use anyhow::{Context as _, Result};
+use serde_json::json;
+
+use crate::appearance::spinner::Spinner;
+use crate::lib::cli::link::{get_links, LinkExportCommand, LinksFile};
+use crate::lib::cli::{CommandOutput, OutputKind};
+use crate::lib::links::Links;
+
+/// Invoke `wash link export` subcommand
+pub async fn invoke(
+    LinkExportCommand { opts, file }: LinkExportCommand,
+    output_kind: OutputKind,
+) -> Result<CommandOutput> {
+    let sp: Spinner = Spinner::new(&output_kind)?;
+    sp.update_spinner_message("Querying Links ... ".to_string());
+    // Going through the table sorts the links by key, so exports of the same links are identical
+    let links = Links::from_iter(get_links(opts.try_into()?).await?)
+        .iter()
+        .cloned()
+        .collect::<Vec<_>>();
+    sp.finish_and_clear();
+
+    let document = serde_yaml::to_string(&LinksFile {
+        links: links.clone(),
+    })
+    .context("failed to serialize links")?;
+    let mut map = HashMap::from([("links".to_string(), json!(links))]);
+    let Some(file) = file else {
+        return Ok(CommandOutput::new(document, map));
+    };
+
+    std::fs::write(&file, document)
+        .with_context(|| format!("failed to write links to `{}`", file.display()))?;
+    map.insert("file".to_string(), json!(file));
+    Ok(CommandOutput::new(
+        format!("Exported {} link(s) to `{}`", links.len(), file.display()),
+        map,
+    ))
+}
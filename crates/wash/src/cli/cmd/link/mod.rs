@@ -4,15 +4,21 @@ use anyhow::Result;
 use crate::lib::cli::link::LinkCommand;
 use crate::lib::cli::{CommandOutput, OutputKind};
 
+mod apply;
 mod del;
+mod export;
 mod put;
 mod query;
+mod verify;
 
 /// Invoke `wash link` subcommand
 pub async fn invoke(command: LinkCommand, output_kind: OutputKind) -> Result<CommandOutput> {
     match command {
+        LinkCommand::Apply(cmd) => apply::invoke(cmd, output_kind).await,
         LinkCommand::Del(cmd) => del::invoke(cmd, output_kind).await,
+        LinkCommand::Export(cmd) => export::invoke(cmd, output_kind).await,
         LinkCommand::Put(cmd) => put::invoke(cmd, output_kind).await,
         LinkCommand::Query(cmd) => query::invoke(cmd, output_kind).await,
+        LinkCommand::Verify(cmd) => verify::invoke(cmd, output_kind).await,
     }
 }
@@ -2,10 +2,12 @@
 
 use std::collections::HashMap;
 
-use anyhow::{anyhow, bail, Result};
+use anyhow::{anyhow, bail, Context as _, Result};
 use serde_json::json;
-use crate::lib::cli::link::{put_link, LinkPutCommand};
+use crate::lib::cli::link::{get_links, put_link, validate_interfaces, LinkPutCommand};
 use crate::lib::cli::{CommandOutput, OutputKind};
+use crate::lib::config::WashConnectionOptions;
+use crate::lib::links::{LinkPutOutcome, Links};
 use wasmcloud_control_interface::Link;
 
 use crate::appearance::spinner::Spinner;
@@ -25,39 +27,53 @@ pub async fn invoke(
     }: LinkPutCommand,
     output_kind: OutputKind,
 ) -> Result<CommandOutput> {
+    validate_interfaces(&interfaces).context("invalid link interfaces")?;
     let sp: Spinner = Spinner::new(&output_kind)?;
     sp.update_spinner_message(format!("Defining link {source_id} -> {target} ... ",));
 
     let name = link_name.unwrap_or_else(|| "default".to_string());
+    let link = Link::builder()
+        .source_id(&source_id)
+        .target(&target)
+        .name(&name)
+        .wit_namespace(&wit_namespace)
+        .wit_package(&wit_package)
+        .interfaces(interfaces)
+        .source_config(source_config)
+        .target_config(target_config)
+        .build()
+        .map_err(|e| anyhow!(e).context("failed to build link"))?;
 
-    let failure = put_link(
-        opts.try_into()?,
-        Link::builder()
-            .source_id(&source_id)
-            .target(&target)
-            .name(&name)
-            .wit_namespace(&wit_namespace)
-            .wit_package(&wit_package)
-            .interfaces(interfaces)
-            .source_config(source_config)
-            .target_config(target_config)
-            .build()
-            .map_err(|e| anyhow!(e).context("failed to build link"))?,
-    )
-    .await
-    .map_or_else(
+    // Compare against the links already in the lattice, so putting an identical link again
+    // doesn't touch the hosts and reconciliation tooling can tell what changed
+    let wco: WashConnectionOptions = opts.try_into()?;
+    let current = Links::from_iter(
+        get_links(wco.clone())
+            .await
+            .context("failed to retrieve links")?,
+    );
+    let outcome = match current.put_outcome(&link) {
+        Ok(outcome) => outcome,
+        Err(conflict) => bail!("Error putting link: {conflict}"),
+    };
+    if outcome == LinkPutOutcome::Unchanged {
+        return link_put_output(&source_id, &target, outcome, None);
+    }
+
+    let failure = put_link(wco, link).await.map_or_else(
         |e| Some(format!("{e}")),
         // If the operation was unsuccessful, return the error message
         |ctl_response| (!ctl_response.succeeded()).then_some(ctl_response.message().to_string()),
     );
 
-    link_put_output(&source_id, &target, failure)
+    link_put_output(&source_id, &target, outcome, failure)
 }
 
 /// Generate output for `wash link put` command
 fn link_put_output(
     source_id: impl AsRef<str>,
     target: impl AsRef<str>,
+    outcome: LinkPutOutcome,
     failure: Option<String>,
 ) -> Result<CommandOutput> {
     let source_id = source_id.as_ref();
@@ -67,10 +83,19 @@ fn link_put_output(
             let mut map = HashMap::new();
             map.insert("source_id".to_string(), json!(source_id));
             map.insert("target".to_string(), json!(target));
-            Ok(CommandOutput::new(
-                format!("Published link ({source_id}) -> ({target}) successfully"),
-                map,
-            ))
+            map.insert("outcome".to_string(), json!(outcome));
+            let text = match outcome {
+                LinkPutOutcome::Created => {
+                    format!("Published link ({source_id}) -> ({target}) successfully")
+                }
+                LinkPutOutcome::Updated => {
+                    format!("Updated link ({source_id}) -> ({target}) successfully")
+                }
+                LinkPutOutcome::Unchanged => {
+                    format!("Link ({source_id}) -> ({target}) is already present, nothing to do")
+                }
+            };
+            Ok(CommandOutput::new(text, map))
         }
         Some(f) => bail!("Error putting link: {f}"),
     }
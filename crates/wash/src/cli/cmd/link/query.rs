@@ -9,7 +9,7 @@ use crate::lib::cli::{CommandOutput, OutputKind};
 use wasmcloud_control_interface::Link;
 
 use crate::appearance::spinner::Spinner;
-use crate::ctl::links_table;
+use crate::ctl::{links_table, links_tree};
 
 /// Generate output for the `wash link query` command
 pub fn link_query_output(list: Vec<Link>) -> CommandOutput {
@@ -17,13 +17,23 @@ pub fn link_query_output(list: Vec<Link>) -> CommandOutput {
     CommandOutput::new(links_table(list), map)
 }
 
-/// Invoke `wash link del` subcommand
+/// Generate output for the `wash link query --tree` command
+pub fn link_query_tree_output(list: Vec<Link>) -> CommandOutput {
+    let map = HashMap::from([("links".to_string(), json!(list))]);
+    CommandOutput::new(links_tree(list), map)
+}
+
+/// Invoke `wash link query` subcommand
 pub async fn invoke(
-    LinkQueryCommand { opts }: LinkQueryCommand,
+    LinkQueryCommand { opts, filter, tree }: LinkQueryCommand,
     output_kind: OutputKind,
 ) -> Result<CommandOutput> {
     let sp: Spinner = Spinner::new(&output_kind)?;
     sp.update_spinner_message("Querying Links ... ".to_string());
-    let result = get_links(opts.try_into()?).await?;
+    let mut result = get_links(opts.try_into()?).await?;
+    result.retain(|link| filter.matches(link));
+    if tree {
+        return Ok(link_query_tree_output(result));
+    }
     Ok(link_query_output(result))
 }
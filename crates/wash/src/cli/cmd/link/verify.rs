@@ -0,0 +1,39 @@
+//! Functionality enabling the `wash link verify` subcommand
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use serde_json::json;
+
+use crate::appearance::spinner::Spinner;
+use crate::lib::cli::link::{get_links, load_links_snapshot, LinkVerifyCommand, LinksDrifted};
+use crate::lib::cli::{CommandOutput, OutputKind};
+use crate::lib::links::Links;
+
+/// Invoke `wash link verify` subcommand
+pub async fn invoke(
+    LinkVerifyCommand { opts, baseline }: LinkVerifyCommand,
+    output_kind: OutputKind,
+) -> Result<CommandOutput> {
+    let expected = Links::from_iter(load_links_snapshot(&baseline)?);
+
+    let sp: Spinner = Spinner::new(&output_kind)?;
+    sp.update_spinner_message("Querying Links ... ".to_string());
+    let current = Links::from_iter(get_links(opts.try_into()?).await?);
+    sp.finish_and_clear();
+
+    let diff = expected.diff(&current);
+    if !diff.is_empty() {
+        return Err(LinksDrifted { baseline, diff }.into());
+    }
+
+    let text = format!(
+        "Links match baseline `{}` ({} link(s))",
+        baseline.display(),
+        current.len()
+    );
+    Ok(CommandOutput::new(
+        text,
+        HashMap::from([("links".to_string(), json!(current.len()))]),
+    ))
+}
@@ -46,6 +46,7 @@ use crate::config::{
     WASMCLOUD_CLUSTER_SEED, WASMCLOUD_CONFIG_SERVICE, WASMCLOUD_CTL_CREDSFILE, WASMCLOUD_CTL_HOST,
     WASMCLOUD_CTL_JWT, WASMCLOUD_CTL_PORT, WASMCLOUD_CTL_SEED, WASMCLOUD_CTL_TLS,
     WASMCLOUD_CTL_TLS_CA_FILE, WASMCLOUD_CTL_TLS_FIRST, WASMCLOUD_ENABLE_IPV6,
+    WASMCLOUD_FORWARD_PROVIDER_LOGS,
     WASMCLOUD_HOST_LOG_PATH, WASMCLOUD_HOST_PATH, WASMCLOUD_HOST_SEED, WASMCLOUD_HOST_VERSION,
     WASMCLOUD_JS_DOMAIN, WASMCLOUD_LATTICE, WASMCLOUD_LOG_LEVEL, WASMCLOUD_MAX_EXECUTION_TIME_MS,
     WASMCLOUD_OCI_ALLOWED_INSECURE, WASMCLOUD_OCI_ALLOW_LATEST, WASMCLOUD_POLICY_TOPIC,
@@ -281,6 +282,14 @@ pub struct WasmcloudOpts {
     )]
     pub enable_structured_logging: bool,
 
+    /// Publish the output of capability providers on the lattice so it can be read with `wash provider logs`.
+    /// Any lattice subscriber can read it, so only enable this when provider output holds no secrets
+    #[clap(
+        long = "forward-provider-logs",
+        env = WASMCLOUD_FORWARD_PROVIDER_LOGS
+    )]
+    pub forward_provider_logs: bool,
+
     /// A label to apply to the host, in the form of `key=value`. This flag can be repeated to supply multiple labels
     #[clap(short = 'l', long = "label", alias = "labels")]
     pub label: Option<Vec<String>>,
@@ -1075,7 +1084,7 @@ async fn run_wasmcloud_interactive(
         Result::<_, anyhow::Error>::Ok(())
     });
 
-    if output_kind != OutputKind::Json {
+    if !output_kind.is_structured() {
         println!("🏃 Running in interactive mode.");
         if let Some(ref manifest_path) = wadm_manifest {
             println!(
@@ -1,8 +1,11 @@
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
 use crate::lib::build::load_lock_file;
 use crate::lib::cli::{CommandOutput, CommonPackageArgs};
+use crate::lib::deps::{locked_versions, unlock_packages};
 use clap::Args;
+use semver::Version;
 
 use crate::lib::parser::{load_config, CommonConfig, ProjectConfig, RegistryConfig};
 use wasm_pkg_core::wit::OutputType;
@@ -27,15 +30,68 @@ pub struct DepsArgs {
     config_path: Option<PathBuf>,
 }
 
+/// Arguments to `wash wit update`
+#[derive(Debug, Args, Clone)]
+pub struct UpdateArgs {
+    /// Packages to update, as `namespace:name` (e.g. `wasi:http`). All dependencies are updated if
+    /// none are given
+    pub packages: Vec<String>,
+
+    #[clap(flatten)]
+    pub deps: DepsArgs,
+}
+
 /// Invoke `wash wit deps`
-pub async fn invoke(
+pub async fn invoke(args: DepsArgs) -> anyhow::Result<CommandOutput> {
+    fetch(args, None).await?;
+    Ok("Dependencies fetched".into())
+}
+
+/// Invoke `wash wit update`
+pub async fn invoke_update(
+    UpdateArgs { packages, deps }: UpdateArgs,
+) -> anyhow::Result<CommandOutput> {
+    let (before, after) = fetch(deps, Some(&packages)).await?;
+    let updated = after
+        .iter()
+        .filter(|(name, versions)| before.get(*name) != Some(versions))
+        .map(|(name, versions)| {
+            let versions = versions.iter().map(ToString::to_string).collect::<Vec<_>>();
+            (name.clone(), versions)
+        })
+        .collect::<HashMap<_, _>>();
+
+    let mut text = if updated.is_empty() {
+        "Dependencies are up to date".to_string()
+    } else {
+        "Dependencies updated:".to_string()
+    };
+    let mut names = updated.keys().collect::<Vec<_>>();
+    names.sort();
+    for name in names {
+        text.push_str(&format!("\n  {name} {}", updated[name].join(", ")));
+    }
+    Ok(CommandOutput::new(
+        text,
+        HashMap::from([("updated".into(), serde_json::json!(updated))]),
+    ))
+}
+
+/// Fetch the dependencies of the WIT directory and write the lock file. When `unlock` is given, the
+/// packages it names (or all packages, if it is empty) are resolved again instead of using the
+/// locked versions. Returns the locked versions from before and after fetching
+async fn fetch(
     DepsArgs {
         dir,
         common,
         config_path,
         ..
     }: DepsArgs,
-) -> anyhow::Result<CommandOutput> {
+    unlock: Option<&[String]>,
+) -> anyhow::Result<(
+    BTreeMap<String, Vec<Version>>,
+    BTreeMap<String, Vec<Version>>,
+)> {
     // Load wasmcloud.toml configuration, if present
     let project_config = match load_config(config_path.clone(), Some(true)).await {
         Ok(v) => Some(v),
@@ -55,6 +111,10 @@ pub async fn invoke(
 
     let project_cfg = load_config(config_path, Some(true)).await?;
     let mut lock_file = load_lock_file(&project_cfg.wasmcloud_toml_dir).await?;
+    let before = locked_versions(&lock_file);
+    if let Some(packages) = unlock {
+        unlock_packages(&mut lock_file, packages)?;
+    }
 
     // Start building the wkg client config
     let mut wkg = crate::lib::deps::WkgFetcher::from_common(&common, wkg_config).await?;
@@ -84,5 +144,5 @@ pub async fn invoke(
     // Now write out the lock file since everything else succeeded
     lock_file.write().await?;
 
-    Ok("Dependencies fetched".into())
+    Ok((before, locked_versions(&lock_file)))
 }
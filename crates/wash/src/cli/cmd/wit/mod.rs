@@ -26,6 +26,13 @@ pub enum WitCommand {
     #[clap(alias = "fetch")]
     Deps(deps::DepsArgs),
 
+    /// Update dependencies to the newest versions their requirements allow.
+    ///
+    /// This works like `wash wit deps`, except that the given packages (or all dependencies, if no
+    /// packages are given) are resolved again instead of using the versions in the lock file. The
+    /// lock file is then updated with the new versions.
+    Update(deps::UpdateArgs),
+
     /// Publish a WIT package to a registry.
     /// This will automatically infer the package name from the WIT package.
     Publish(publish::PublishArgs),
@@ -36,6 +43,7 @@ pub async fn handle_command(cmd: WitCommand) -> anyhow::Result<CommandOutput> {
     match cmd {
         WitCommand::Build(args) => build::invoke(args).await,
         WitCommand::Deps(args) => deps::invoke(args).await,
+        WitCommand::Update(args) => deps::invoke_update(args).await,
         WitCommand::Publish(args) => publish::invoke(args).await,
     }
 }
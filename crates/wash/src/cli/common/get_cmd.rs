@@ -1,37 +1,80 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use cloudevents::AttributesReader;
 use crossterm::{
     cursor, execute,
     terminal::{Clear, ClearType},
 };
-use std::{collections::HashMap, io::Write, time::Duration};
+use std::{collections::HashMap, future::Future, io::Write, time::Duration};
 use tokio::time::sleep;
 use crate::lib::cli::claims::get_claims;
 use crate::lib::cli::get::{
-    get_host_inventories, get_hosts, GetCommand, GetHostInventoriesCommand, GetLinksCommand,
+    get_events, get_host_inventories, get_hosts, get_providers, subscribe_events, GetCommand,
+    GetEventsCommand, GetHostInventoriesCommand, GetLinksCommand, CLAIMS_WATCH_EVENTS,
+    EVENT_TYPE_PREFIX, HOST_WATCH_EVENTS, INVENTORY_WATCH_EVENTS, LINK_WATCH_EVENTS,
 };
-use crate::lib::cli::link::{LinkCommand, LinkQueryCommand};
-use crate::lib::cli::{CommandOutput, OutputKind};
+use crate::lib::cli::link::{get_links, LinkCommand, LinkQueryCommand};
+use crate::lib::cli::{CliConnectionOpts, CommandOutput, NdjsonStream, OutputKind};
+use crate::lib::common::boxed_err_to_anyhow;
+use crate::lib::config::WashConnectionOptions;
+use crate::lib::provider::ServingProvider;
 
 use crate::appearance::spinner::Spinner;
 use crate::cmd::link::invoke as invoke_link_cmd;
 use crate::ctl::{
-    get_claims_output, get_host_inventories_output, get_hosts_output, host_inventories_table,
+    claims_table, get_claims_output, get_host_inventories_output, get_hosts_output,
+    host_inventories_table, hosts_table, links_table,
 };
 
 pub async fn handle_command(command: GetCommand, output_kind: OutputKind) -> Result<CommandOutput> {
     let out: CommandOutput = match command {
-        GetCommand::Links(GetLinksCommand { opts }) => {
-            invoke_link_cmd(LinkCommand::Query(LinkQueryCommand { opts }), output_kind).await?
+        GetCommand::Links(GetLinksCommand {
+            opts,
+            watch: Some(interval),
+        }) => {
+            let sp: Spinner = Spinner::new(&output_kind)?;
+            sp.update_spinner_message("Querying Links ... ".to_string());
+            watch_output(opts.clone(), &LINK_WATCH_EVENTS, interval, sp, "Links", || {
+                let opts = opts.clone();
+                async move { Ok(links_table(get_links(opts.try_into()?).await?)) }
+            })
+            .await?
+        }
+        GetCommand::Links(GetLinksCommand { opts, watch: None }) => {
+            invoke_link_cmd(
+                LinkCommand::Query(LinkQueryCommand {
+                    opts,
+                    filter: Default::default(),
+                    tree: false,
+                }),
+                output_kind,
+            )
+            .await?
         }
         GetCommand::Claims(cmd) => {
             let sp: Spinner = Spinner::new(&output_kind)?;
             sp.update_spinner_message("Retrieving claims ... ".to_string());
+            if let Some(interval) = cmd.watch {
+                let opts = cmd.opts.clone();
+                return watch_output(opts, &CLAIMS_WATCH_EVENTS, interval, sp, "Claims", || {
+                    let cmd = cmd.clone();
+                    async move { Ok(claims_table(get_claims(cmd).await?)) }
+                })
+                .await;
+            }
             let claims = get_claims(cmd).await?;
             get_claims_output(claims)
         }
         GetCommand::Hosts(cmd) => {
             let sp: Spinner = Spinner::new(&output_kind)?;
             sp.update_spinner_message(" Retrieving Hosts ...".to_string());
+            if let Some(interval) = cmd.watch {
+                let opts = cmd.opts.clone();
+                return watch_output(opts, &HOST_WATCH_EVENTS, interval, sp, "Hosts", || {
+                    let cmd = cmd.clone();
+                    async move { Ok(hosts_table(get_hosts(cmd).await?)) }
+                })
+                .await;
+            }
             let hosts = get_hosts(cmd).await?;
             get_hosts_output(hosts)
         }
@@ -44,66 +87,188 @@ pub async fn handle_command(command: GetCommand, output_kind: OutputKind) -> Res
             }
             get_inventory_handler(cmd, sp).await?
         }
+        GetCommand::Events(cmd) if cmd.tail => tail_events(cmd, output_kind).await?,
+        GetCommand::Events(cmd) => {
+            let sp: Spinner = Spinner::new(&output_kind)?;
+            sp.update_spinner_message(format!(
+                " Recording lattice events for {} ...",
+                humantime::format_duration(cmd.duration)
+            ));
+            let events = get_events(cmd).await?;
+            sp.finish_and_clear();
+            get_events_output(events)?
+        }
+        GetCommand::Providers(cmd) => {
+            let sp: Spinner = Spinner::new(&output_kind)?;
+            sp.update_spinner_message(" Retrieving providers ...".to_string());
+            let providers = get_providers(cmd).await?;
+            sp.finish_and_clear();
+            get_providers_output(providers)?
+        }
     };
 
     Ok(out)
 }
 
+fn get_events_output(events: Vec<cloudevents::Event>) -> Result<CommandOutput> {
+    let mut text = format!("Recorded {} event(s)", events.len());
+    for event in &events {
+        text.push('\n');
+        text.push_str(&serde_json::to_string(event)?);
+    }
+    Ok(CommandOutput::new(
+        text,
+        HashMap::from([("events".to_string(), serde_json::to_value(&events)?)]),
+    ))
+}
+
+/// Print lattice events as they arrive until interrupted with Ctrl-C, one JSON line per event
+/// with structured output
+async fn tail_events(cmd: GetEventsCommand, output_kind: OutputKind) -> Result<CommandOutput> {
+    let (mut events, filter) = subscribe_events(cmd).await?;
+    let mut stream = output_kind.is_structured().then(NdjsonStream::stdout);
+    let mut ctrlc = std::pin::pin!(tokio::signal::ctrl_c());
+    let mut received = 0;
+    loop {
+        tokio::select! {
+            event = events.recv() => {
+                let Some(event) = event else {
+                    break;
+                };
+                if !filter.matches(&event) {
+                    continue;
+                }
+                received += 1;
+                match stream.as_mut() {
+                    Some(stream) => stream.emit(&event)?,
+                    None => println!("{}", event_line(&event)),
+                }
+            }
+            res = &mut ctrlc => {
+                res?;
+                break;
+            }
+        }
+    }
+    Ok(CommandOutput::new(
+        format!("Received {received} event(s)"),
+        HashMap::from([("received".to_string(), serde_json::json!(received))]),
+    ))
+}
+
+/// A single line summary of a lattice event, e.g.
+/// `[2024-05-02T10:00:00Z] component_scaled from NHOST: {"component_id":"echo",...}`
+fn event_line(event: &cloudevents::Event) -> String {
+    let time = event
+        .time()
+        .map(|time| format!("[{}] ", time.to_rfc3339()))
+        .unwrap_or_default();
+    let ty = event.ty();
+    let data = event
+        .data()
+        .map(|data| format!(": {data}"))
+        .unwrap_or_default();
+    format!(
+        "{time}{} from {}{data}",
+        ty.strip_prefix(EVENT_TYPE_PREFIX).unwrap_or(ty),
+        event.source()
+    )
+}
+
+fn get_providers_output(providers: Vec<ServingProvider>) -> Result<CommandOutput> {
+    let mut text = format!("Found {} provider(s)", providers.len());
+    for provider in &providers {
+        text.push_str(&format!(
+            "\n  [{}] on host [{}]: {}",
+            provider.provider_id,
+            provider.host_id,
+            provider.interfaces.join(", ")
+        ));
+    }
+    Ok(CommandOutput::new(
+        text,
+        HashMap::from([("providers".to_string(), serde_json::to_value(&providers)?)]),
+    ))
+}
+
 async fn get_inventory_handler(
     cmd: GetHostInventoriesCommand,
     sp: Spinner,
 ) -> Result<CommandOutput> {
-    if cmd.watch.is_some() {
-        watch_inventory(cmd, sp).await?;
-        Ok(CommandOutput::new(
-            "Completed Watching Inventory".to_string(),
-            HashMap::new(),
-        ))
+    if let Some(interval) = cmd.watch {
+        let opts = cmd.opts.clone();
+        watch_output(opts, &INVENTORY_WATCH_EVENTS, interval, sp, "Inventory", || {
+            let cmd = cmd.clone();
+            async move { Ok(host_inventories_table(get_host_inventories(cmd).await?)) }
+        })
+        .await
     } else {
+        let filtered = !cmd.filters.is_empty();
         let invs = get_host_inventories(cmd).await?;
+        if filtered && invs.is_empty() {
+            return Ok(CommandOutput::new(
+                "No hosts are running a matching component or provider".to_string(),
+                HashMap::from([("inventories".to_string(), serde_json::json!(invs))]),
+            ));
+        }
         Ok(get_host_inventories_output(invs))
     }
 }
 
-async fn watch_inventory(cmd: GetHostInventoriesCommand, sp: Spinner) -> Result<()> {
-    let mut stdout = std::io::stdout();
-    let invs = get_host_inventories(cmd.clone()).await?;
-    sp.finish_and_clear();
-    execute!(stdout, Clear(ClearType::FromCursorUp), cursor::MoveTo(0, 0))
-        .map_err(|e| anyhow::anyhow!("Failed to clear terminal: {}", e))?;
-    let output = host_inventories_table(invs);
-    stdout
-        .write_all(output.as_bytes())
-        .map_err(|e| anyhow::anyhow!("Failed to write inventory to stdout: {}", e))?;
+/// How long to let a burst of lattice events settle before re-rendering watched output, so e.g.
+/// scaling several components causes a single refresh
+const WATCH_EVENT_SETTLE: Duration = Duration::from_millis(200);
 
-    let mut ctrlc = std::pin::pin!(tokio::signal::ctrl_c());
-    let watch_interval = cmd.watch.unwrap_or(Duration::from_millis(5000));
+/// Render the output of `render` and keep it up to date until interrupted with Ctrl-C. The output
+/// is re-rendered as soon as one of `event_types` is published on the lattice, and at least every
+/// `interval` in case an event was missed
+async fn watch_output<F, Fut>(
+    opts: CliConnectionOpts,
+    event_types: &[&str],
+    interval: Duration,
+    sp: Spinner,
+    what: &str,
+    mut render: F,
+) -> Result<CommandOutput>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<String>>,
+{
+    let wco: WashConnectionOptions = opts.try_into()?;
+    let client = wco.into_ctl_client(None).await?;
+    let mut events = client
+        .events_receiver(event_types.iter().map(ToString::to_string).collect())
+        .await
+        .map_err(boxed_err_to_anyhow)
+        .context("Failed to get lattice event channel")?;
 
+    let mut stdout = std::io::stdout();
+    let mut ctrlc = std::pin::pin!(tokio::signal::ctrl_c());
+    let mut first = true;
     loop {
-        let invs = tokio::select! {
-            res = get_host_inventories(cmd.clone()) => res?,
+        let output = tokio::select! {
+            res = render() => res?,
             res = &mut ctrlc => {
                 res?;
-                execute!(stdout, Clear(ClearType::Purge),Clear(ClearType::FromCursorUp), cursor::MoveTo(0, 0), cursor::Show)
-                    .map_err(|e| anyhow::anyhow!("Failed to execute terminal commands: {}", e))?;
-                stdout.flush()
-                    .map_err(|e| anyhow::anyhow!("Failed to flush stdout: {}", e))?;
-                return Ok(());
+                return finish_watch(&mut stdout, what);
             }
         };
 
-        execute!(stdout, Clear(ClearType::Purge), cursor::MoveTo(0, 0))
-            .map_err(|e| anyhow::anyhow!("Failed to execute terminal commands: {}", e))?;
-
-        let output = host_inventories_table(invs);
+        if first {
+            sp.finish_and_clear();
+            execute!(stdout, Clear(ClearType::FromCursorUp), cursor::MoveTo(0, 0))
+                .map_err(|e| anyhow::anyhow!("Failed to clear terminal: {}", e))?;
+            first = false;
+        } else {
+            execute!(stdout, Clear(ClearType::Purge), cursor::MoveTo(0, 0))
+                .map_err(|e| anyhow::anyhow!("Failed to execute terminal commands: {}", e))?;
+        }
         stdout
             .write_all(output.as_bytes())
-            .map_err(|e| anyhow::anyhow!("Failed to write inventory to stdout: {}", e))?;
-
+            .map_err(|e| anyhow::anyhow!("Failed to write {what} to stdout: {}", e))?;
         stdout
             .flush()
             .map_err(|e| anyhow::anyhow!("Failed to flush stdout: {}", e))?;
-
         execute!(
             stdout,
             Clear(ClearType::CurrentLine),
@@ -112,15 +277,27 @@ async fn watch_inventory(cmd: GetHostInventoriesCommand, sp: Spinner) -> Result<
         .map_err(|e| anyhow::anyhow!("Failed to clear terminal: {}", e))?;
 
         tokio::select! {
-            () = sleep(watch_interval) => continue,
+            () = sleep(interval) => {}
+            Some(_) = events.recv() => {
+                sleep(WATCH_EVENT_SETTLE).await;
+                while events.try_recv().is_ok() {}
+            }
             res = &mut ctrlc => {
                 res?;
-                execute!(stdout, Clear(ClearType::Purge),Clear(ClearType::FromCursorUp), cursor::MoveTo(0, 0), cursor::Show)
-                    .map_err(|e| anyhow::anyhow!("Failed to execute terminal commands: {}", e))?;
-                stdout.flush()
-                    .map_err(|e| anyhow::anyhow!("Failed to flush stdout: {}", e))?;
-                return Ok(());
+                return finish_watch(&mut stdout, what);
             }
         }
     }
 }
+
+/// Restore the terminal once watching is interrupted
+fn finish_watch(stdout: &mut std::io::Stdout, what: &str) -> Result<CommandOutput> {
+    execute!(stdout, Clear(ClearType::Purge), Clear(ClearType::FromCursorUp), cursor::MoveTo(0, 0), cursor::Show)
+        .map_err(|e| anyhow::anyhow!("Failed to execute terminal commands: {}", e))?;
+    stdout.flush()
+        .map_err(|e| anyhow::anyhow!("Failed to flush stdout: {}", e))?;
+    Ok(CommandOutput::new(
+        format!("Completed Watching {what}"),
+        HashMap::new(),
+    ))
+}
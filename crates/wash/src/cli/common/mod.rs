@@ -1,5 +1,6 @@
 pub mod get_cmd;
 pub mod label_cmd;
+pub mod provider_cmd;
 pub mod registry_cmd;
 pub mod scale_cmd;
 pub mod start_cmd;
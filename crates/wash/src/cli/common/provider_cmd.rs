@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use wasmcloud_control_interface::{ProviderLogLine, ProviderLogStream};
+
+use crate::lib::cli::provider::{
+    get_provider_logs, ProviderCommand, ProviderLogs, ProviderLogsCommand,
+};
+use crate::lib::cli::{CommandOutput, NdjsonStream, OutputKind};
+
+pub async fn handle_command(
+    command: ProviderCommand,
+    output_kind: OutputKind,
+) -> Result<CommandOutput> {
+    match command {
+        ProviderCommand::Logs(cmd) => show_provider_logs(cmd, output_kind).await,
+    }
+}
+
+/// Print the lines of output of a provider the hosts still hold and, when following, the lines
+/// that arrive until interrupted. With structured output, each line is printed as a JSON line
+async fn show_provider_logs(
+    cmd: ProviderLogsCommand,
+    output_kind: OutputKind,
+) -> Result<CommandOutput> {
+    let ProviderLogs { history, follow } = get_provider_logs(&cmd).await?;
+    let mut stream = output_kind.is_structured().then(NdjsonStream::stdout);
+    let mut print = |line: &ProviderLogLine| -> Result<()> {
+        match stream.as_mut() {
+            Some(stream) => stream.emit(line)?,
+            None => println!("{}", log_line(line, cmd.host_id.is_none())),
+        }
+        Ok(())
+    };
+
+    // The last line already printed for each host, so lines published while the history was
+    // being retrieved aren't printed twice
+    let mut printed: HashMap<String, u64> = HashMap::new();
+    for line in &history {
+        print(line)?;
+        let last = printed.entry(line.host_id().to_string()).or_default();
+        *last = (*last).max(line.seq());
+    }
+    let mut received = history.len();
+
+    if let Some(mut lines) = follow {
+        let mut ctrlc = std::pin::pin!(tokio::signal::ctrl_c());
+        loop {
+            tokio::select! {
+                line = lines.recv() => {
+                    let Some(line) = line else {
+                        break;
+                    };
+                    if printed.get(line.host_id()).is_some_and(|last| line.seq() <= *last) {
+                        continue;
+                    }
+                    received += 1;
+                    print(&line)?;
+                }
+                res = &mut ctrlc => {
+                    res?;
+                    break;
+                }
+            }
+        }
+    }
+    Ok(CommandOutput::new(
+        format!(
+            "Received {received} log line(s) from provider [{}]",
+            cmd.provider_id
+        ),
+        HashMap::from([
+            ("provider_id".to_string(), cmd.provider_id.into()),
+            ("received".to_string(), serde_json::json!(received)),
+        ]),
+    ))
+}
+
+/// A log line as the provider wrote it, prefixed with the host that ran it when following every
+/// host, e.g. `[NHOST] [stderr] listening on 0.0.0.0:8080`
+fn log_line(line: &ProviderLogLine, with_host: bool) -> String {
+    let host = if with_host {
+        format!("[{}] ", line.host_id())
+    } else {
+        String::new()
+    };
+    let stream = match line.stream() {
+        ProviderLogStream::Stderr => "[stderr] ",
+        ProviderLogStream::Stdout => "",
+    };
+    let truncated = if line.is_truncated() {
+        " [truncated]"
+    } else {
+        ""
+    };
+    format!("{host}{stream}{}{truncated}", line.line())
+}
@@ -1,7 +1,11 @@
 use anyhow::Result;
 
 use crate::lib::cli::{
-    scale::{handle_scale_component, ScaleCommand},
+    progress::Progress,
+    scale::{
+        handle_scale_apply, handle_scale_component, handle_scale_manifest, handle_scale_status,
+        ScaleCommand,
+    },
     CommandOutput, OutputKind,
 };
 
@@ -10,20 +14,50 @@ use crate::appearance::spinner::Spinner;
 pub async fn handle_command(
     command: ScaleCommand,
     output_kind: OutputKind,
+    progress: Progress,
 ) -> Result<CommandOutput> {
     let sp: Spinner = Spinner::new(&output_kind)?;
     let out = match command {
-        ScaleCommand::Component(cmd) => {
+        ScaleCommand::Component(mut cmd) => {
             let scale_msg = if cmd.max_instances == u32::MAX {
                 "unbounded concurrency".to_string()
             } else {
                 format!("{} max concurrent instances", cmd.max_instances)
             };
+            if cmd.host_match.interactive {
+                // The host picker needs the terminal to itself
+                sp.finish_and_clear();
+                cmd.progress = progress;
+            } else {
+                sp.update_spinner_message(format!(
+                    " Sending request to scale component {} to {scale_msg} ... ",
+                    cmd.component_ref
+                ));
+                // Steps reported by the scale replace the spinner message as they are reached
+                cmd.progress = sp.progress().unwrap_or(progress);
+            }
+            handle_scale_component(*cmd).await?
+        }
+        ScaleCommand::Apply(cmd) => {
+            sp.update_spinner_message(format!(
+                " Reconciling component counts from {} ... ",
+                cmd.file.display()
+            ));
+            handle_scale_apply(cmd, output_kind).await?
+        }
+        ScaleCommand::Manifest(cmd) => {
+            sp.update_spinner_message(format!(
+                " Sending scale commands from {} ... ",
+                cmd.file.display()
+            ));
+            handle_scale_manifest(cmd).await?
+        }
+        ScaleCommand::Status(cmd) => {
             sp.update_spinner_message(format!(
-                " Sending request to scale component {} to {scale_msg} ... ",
+                " Checking scale status of {} ... ",
                 cmd.component_ref
             ));
-            handle_scale_component(cmd.clone()).await?
+            handle_scale_status(cmd).await?
         }
     };
 
@@ -1,5 +1,6 @@
 use crate::appearance::spinner::Spinner;
 
+use crate::lib::cli::progress::Progress;
 use crate::lib::cli::start::{handle_start_component, handle_start_provider, StartCommand};
 use crate::lib::cli::{CommandOutput, OutputKind};
 use anyhow::Result;
@@ -7,22 +8,39 @@ use anyhow::Result;
 pub async fn handle_command(
     command: StartCommand,
     output_kind: OutputKind,
+    progress: Progress,
 ) -> Result<CommandOutput> {
     let sp: Spinner = Spinner::new(&output_kind)?;
     let out: CommandOutput = match command {
-        StartCommand::Component(cmd) => {
+        StartCommand::Component(mut cmd) => {
             let component_ref = &cmd.component_ref.to_string();
 
-            sp.update_spinner_message(format!(" Starting component {component_ref} ... "));
+            if cmd.host_match.interactive {
+                // The host picker needs the terminal to itself
+                sp.finish_and_clear();
+                cmd.progress = progress;
+            } else {
+                sp.update_spinner_message(format!(" Starting component {component_ref} ... "));
+                // Steps reported by the start replace the spinner message as they are reached
+                cmd.progress = sp.progress().unwrap_or(progress);
+            }
 
-            handle_start_component(cmd).await?
+            handle_start_component(*cmd).await?
         }
-        StartCommand::Provider(cmd) => {
+        StartCommand::Provider(mut cmd) => {
             let provider_ref = &cmd.provider_ref.to_string();
 
-            sp.update_spinner_message(format!(" Starting provider {provider_ref} ... "));
+            if cmd.host_match.interactive {
+                // The host picker needs the terminal to itself
+                sp.finish_and_clear();
+                cmd.progress = progress;
+            } else {
+                sp.update_spinner_message(format!(" Starting provider {provider_ref} ... "));
+                // Steps reported by the start replace the spinner message as they are reached
+                cmd.progress = sp.progress().unwrap_or(progress);
+            }
 
-            handle_start_provider(cmd).await?
+            handle_start_provider(*cmd).await?
         }
     };
 
@@ -77,15 +95,16 @@ mod test {
             "mycomponent",
         ])?;
         match start_component_all.command {
-            CtlCliCommand::Start(StartCommand::Component(StartComponentCommand {
-                opts,
-                host_id,
-                component_ref,
-                component_id,
-                constraints,
-                auction_timeout_ms,
-                ..
-            })) => {
+            CtlCliCommand::Start(StartCommand::Component(cmd)) => {
+                let StartComponentCommand {
+                    opts,
+                    host_id,
+                    component_ref,
+                    component_id,
+                    constraints,
+                    auction_timeout_ms,
+                    ..
+                } = *cmd;
                 assert_eq!(&opts.ctl_host.unwrap(), CTL_HOST);
                 assert_eq!(&opts.ctl_port.unwrap(), CTL_PORT);
                 assert_eq!(&opts.lattice.unwrap(), DEFAULT_LATTICE);
@@ -122,23 +141,25 @@ mod test {
             "providerv1",
         ])?;
         match start_provider_all.command {
-            CtlCliCommand::Start(StartCommand::Provider(StartProviderCommand {
-                opts,
-                host_id,
-                provider_ref,
-                provider_id,
-                link_name,
-                constraints,
-                auction_timeout_ms,
-                config,
-                skip_wait,
-            })) => {
+            CtlCliCommand::Start(StartCommand::Provider(cmd)) => {
+                let StartProviderCommand {
+                    opts,
+                    host_id,
+                    provider_ref,
+                    provider_id,
+                    link_names,
+                    constraints,
+                    auction_timeout_ms,
+                    config,
+                    skip_wait,
+                    ..
+                } = *cmd;
                 assert_eq!(&opts.ctl_host.unwrap(), CTL_HOST);
                 assert_eq!(&opts.ctl_port.unwrap(), CTL_PORT);
                 assert_eq!(&opts.lattice.unwrap(), DEFAULT_LATTICE);
                 assert_eq!(opts.timeout_ms, 2001);
                 assert_eq!(auction_timeout_ms, 2002);
-                assert_eq!(link_name, "default".to_string());
+                assert_eq!(link_names, vec!["default".to_string()]);
                 assert_eq!(constraints.unwrap(), vec!["arch=x86_64".to_string()]);
                 assert_eq!(host_id.unwrap(), HOST_ID.to_string());
                 assert_eq!(provider_ref, "ghcr.io/provider:v1".to_string());
@@ -1,7 +1,9 @@
 use anyhow::Result;
 
-use crate::lib::cli::stop::{handle_stop_component, handle_stop_provider, stop_host, StopCommand};
-use crate::lib::cli::{CommandOutput, OutputKind};
+use crate::lib::cli::stop::{
+    handle_stop_component, handle_stop_provider, stop_host_with_progress, StopCommand,
+};
+use crate::lib::cli::{CommandOutput, NdjsonStream, OutputKind};
 
 use crate::appearance::spinner::Spinner;
 
@@ -23,8 +25,27 @@ pub async fn handle_command(
         }
         StopCommand::Host(cmd) => {
             let host_id = &cmd.host_id.to_string();
-            sp.update_spinner_message(format!(" Stopping host {host_id} ... "));
-            stop_host(cmd).await?
+            if cmd.drain {
+                sp.update_spinner_message(format!(" Draining host {host_id} ... "));
+            } else {
+                sp.update_spinner_message(format!(" Stopping host {host_id} ... "));
+            }
+            let mut stream = NdjsonStream::for_output(output_kind);
+            stop_host_with_progress(cmd, |result| {
+                if let Some(stream) = stream.as_mut() {
+                    let _ = stream.emit(result);
+                }
+                let action = if result.error.is_some() {
+                    "failed to remove"
+                } else {
+                    "removed"
+                };
+                sp.update_spinner_message(format!(
+                    " Draining host {host_id}, {action} {} ... ",
+                    result.step.id
+                ));
+            })
+            .await?
         }
     };
 
@@ -38,6 +59,7 @@ mod test {
     use super::*;
     use clap::Parser;
     use crate::lib::cli::stop::{StopComponentCommand, StopHostCommand, StopProviderCommand};
+    use crate::lib::common::HostMatchOpts;
 
     #[derive(Parser)]
     struct Cmd {
@@ -97,14 +119,22 @@ mod test {
             CtlCliCommand::Stop(StopCommand::Component(StopComponentCommand {
                 opts,
                 host_id,
+                host_match,
                 component_id,
                 skip_wait,
+                dry_run,
+                all_hosts,
+                match_annotations,
             })) => {
                 assert_eq!(&opts.ctl_host.unwrap(), CTL_HOST);
                 assert_eq!(&opts.ctl_port.unwrap(), CTL_PORT);
                 assert_eq!(&opts.lattice.unwrap(), DEFAULT_LATTICE);
                 assert!(skip_wait);
+                assert!(!dry_run);
+                assert!(!all_hosts);
+                assert!(match_annotations.is_empty());
                 assert_eq!(host_id.unwrap(), HOST_ID);
+                assert_eq!(host_match, HostMatchOpts::default());
                 assert_eq!(component_id, COMPONENT_ID);
             }
             cmd => panic!("stop component constructed incorrect command {cmd:?}"),
@@ -149,16 +179,24 @@ mod test {
             CtlCliCommand::Stop(StopCommand::Provider(StopProviderCommand {
                 opts,
                 host_id,
+                host_match,
                 provider_id,
                 skip_wait,
+                dry_run,
+                all_hosts,
+                match_annotations,
             })) => {
                 assert_eq!(&opts.ctl_host.unwrap(), CTL_HOST);
                 assert_eq!(&opts.ctl_port.unwrap(), CTL_PORT);
                 assert_eq!(&opts.lattice.unwrap(), DEFAULT_LATTICE);
                 assert_eq!(opts.timeout_ms, 2001);
                 assert_eq!(host_id.unwrap(), HOST_ID);
+                assert_eq!(host_match, HostMatchOpts::default());
                 assert_eq!(provider_id, PROVIDER_ID);
                 assert!(skip_wait);
+                assert!(!dry_run);
+                assert!(!all_hosts);
+                assert!(match_annotations.is_empty());
             }
             cmd => panic!("stop provider constructed incorrect command {cmd:?}"),
         }
@@ -217,4 +255,44 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_stop_host_drain() -> Result<()> {
+        let drain: Cmd = Parser::try_parse_from([
+            "ctl",
+            "stop",
+            "host",
+            HOST_ID,
+            "--drain",
+            "--drain-timeout-ms",
+            "5000",
+        ])?;
+        match drain.command {
+            CtlCliCommand::Stop(StopCommand::Host(StopHostCommand {
+                drain,
+                drain_timeout_ms,
+                ..
+            })) => {
+                assert!(drain);
+                assert_eq!(drain_timeout_ms, 5000);
+            }
+            cmd => panic!("stop host constructed incorrect command {cmd:?}"),
+        }
+
+        // The deadline only applies to a drain, and a dry run can't drain
+        assert!(Cmd::try_parse_from([
+            "ctl",
+            "stop",
+            "host",
+            HOST_ID,
+            "--drain-timeout-ms",
+            "5000"
+        ])
+        .is_err());
+        assert!(
+            Cmd::try_parse_from(["ctl", "stop", "host", HOST_ID, "--drain", "--dry-run"]).is_err()
+        );
+
+        Ok(())
+    }
 }
@@ -62,6 +62,7 @@ pub const WASMCLOUD_ENABLE_IPV6: &str = "WASMCLOUD_ENABLE_IPV6";
 pub const WASMCLOUD_STRUCTURED_LOGGING_ENABLED: &str = "WASMCLOUD_STRUCTURED_LOGGING_ENABLED";
 pub const WASMCLOUD_CONFIG_SERVICE: &str = "WASMCLOUD_CONFIG_SERVICE";
 pub const WASMCLOUD_ALLOW_FILE_LOAD: &str = "WASMCLOUD_ALLOW_FILE_LOAD";
+pub const WASMCLOUD_FORWARD_PROVIDER_LOGS: &str = "WASMCLOUD_FORWARD_PROVIDER_LOGS";
 pub const DEFAULT_ALLOW_FILE_LOAD: &str = "true";
 
 /// Helper function to convert `WasmcloudOpts` to the host environment map.
@@ -186,6 +187,12 @@ pub async fn configure_host_env(wasmcloud_opts: WasmcloudOpts) -> Result<HashMap
             "true".to_string(),
         );
     }
+    if wasmcloud_opts.forward_provider_logs {
+        host_config.insert(
+            WASMCLOUD_FORWARD_PROVIDER_LOGS.to_string(),
+            "true".to_string(),
+        );
+    }
 
     let labels: Vec<(String, String)> = wasmcloud_opts
         .label
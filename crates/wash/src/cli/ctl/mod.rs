@@ -60,9 +60,11 @@ mod test {
     use crate::lib::cli::{
         get::GetHostsCommand,
         scale::ScaleComponentCommand,
+        start::Placement,
         stop::{StopComponentCommand, StopProviderCommand},
         update::UpdateComponentCommand,
     };
+    use crate::lib::common::HostMatchOpts;
 
     use super::*;
 
@@ -100,22 +102,40 @@ mod test {
             "2001",
             "--host-id",
             HOST_ID,
+            "--exact-host",
+            "--host-index",
+            "2",
             COMPONENT_ID,
         ])?;
         match stop_component_all.command {
             CtlCliCommand::Stop(StopCommand::Component(StopComponentCommand {
                 opts,
                 host_id,
+                host_match,
                 component_id,
                 skip_wait,
+                dry_run,
+                all_hosts,
+                match_annotations,
             })) => {
                 assert_eq!(&opts.ctl_host.unwrap(), CTL_HOST);
                 assert_eq!(&opts.ctl_port.unwrap(), CTL_PORT);
                 assert_eq!(&opts.lattice.unwrap(), DEFAULT_LATTICE);
                 assert_eq!(opts.timeout_ms, 2001);
                 assert_eq!(host_id, Some(HOST_ID.to_string()));
+                assert_eq!(
+                    host_match,
+                    HostMatchOpts {
+                        exact: true,
+                        index: Some(2),
+                        interactive: false,
+                    }
+                );
                 assert_eq!(component_id, COMPONENT_ID);
                 assert!(!skip_wait);
+                assert!(!dry_run);
+                assert!(!all_hosts);
+                assert!(match_annotations.is_empty());
             }
             cmd => panic!("ctl stop component constructed incorrect command {cmd:?}"),
         }
@@ -152,16 +172,24 @@ mod test {
             CtlCliCommand::Stop(StopCommand::Provider(StopProviderCommand {
                 opts,
                 host_id,
+                host_match,
                 provider_id,
                 skip_wait,
+                dry_run,
+                all_hosts,
+                match_annotations,
             })) => {
                 assert_eq!(&opts.ctl_host.unwrap(), CTL_HOST);
                 assert_eq!(&opts.ctl_port.unwrap(), CTL_PORT);
                 assert_eq!(&opts.lattice.unwrap(), DEFAULT_LATTICE);
                 assert_eq!(opts.timeout_ms, 2001);
                 assert_eq!(host_id, Some(HOST_ID.to_string()));
+                assert_eq!(host_match, HostMatchOpts::default());
                 assert_eq!(provider_id, PROVIDER_ID);
                 assert!(!skip_wait);
+                assert!(!dry_run);
+                assert!(!all_hosts);
+                assert!(match_annotations.is_empty());
             }
             cmd => panic!("ctl stop component constructed incorrect command {cmd:?}"),
         }
@@ -192,11 +220,12 @@ mod test {
             "2001",
         ])?;
         match get_hosts_all.command {
-            CtlCliCommand::Get(CtlGetCommand::Hosts(GetHostsCommand { opts })) => {
+            CtlCliCommand::Get(CtlGetCommand::Hosts(GetHostsCommand { opts, watch })) => {
                 assert_eq!(&opts.ctl_host.unwrap(), CTL_HOST);
                 assert_eq!(&opts.ctl_port.unwrap(), CTL_PORT);
                 assert_eq!(&opts.lattice.unwrap(), DEFAULT_LATTICE);
                 assert_eq!(opts.timeout_ms, 2001);
+                assert!(watch.is_none());
             }
             cmd => panic!("ctl get hosts constructed incorrect command {cmd:?}"),
         }
@@ -219,12 +248,16 @@ mod test {
                 opts,
                 host_id,
                 watch: _,
+                all,
+                filters,
             })) => {
                 assert_eq!(&opts.ctl_host.unwrap(), CTL_HOST);
                 assert_eq!(&opts.ctl_port.unwrap(), CTL_PORT);
                 assert_eq!(&opts.lattice.unwrap(), DEFAULT_LATTICE);
                 assert_eq!(opts.timeout_ms, 2001);
                 assert_eq!(host_id.unwrap(), HOST_ID.parse()?);
+                assert!(!all);
+                assert!(filters.is_empty());
             }
             cmd => panic!("ctl get inventory constructed incorrect command {cmd:?}"),
         }
@@ -244,12 +277,13 @@ mod test {
             JS_DOMAIN,
         ])?;
         match get_claims_all.command {
-            CtlCliCommand::Get(CtlGetCommand::Claims(GetClaimsCommand { opts })) => {
+            CtlCliCommand::Get(CtlGetCommand::Claims(GetClaimsCommand { opts, watch })) => {
                 assert_eq!(&opts.ctl_host.unwrap(), CTL_HOST);
                 assert_eq!(&opts.ctl_port.unwrap(), CTL_PORT);
                 assert_eq!(&opts.lattice.unwrap(), DEFAULT_LATTICE);
                 assert_eq!(opts.timeout_ms, 2001);
                 assert_eq!(opts.js_domain.unwrap(), JS_DOMAIN);
+                assert!(watch.is_none());
             }
             cmd => panic!("ctl get claims constructed incorrect command {cmd:?}"),
         }
@@ -363,29 +397,82 @@ mod test {
         ])?;
 
         match scale_component_all.command {
-            CtlCliCommand::Scale(ScaleCommand::Component(ScaleComponentCommand {
-                opts,
-                host_id,
-                component_ref,
-                component_id,
-                max_instances,
-                annotations,
-                config,
-                skip_wait,
-                wait_timeout_ms,
-            })) => {
+            CtlCliCommand::Scale(ScaleCommand::Component(mut cmd)) => {
+                cmd.take_deprecated_host_id()?;
+                let ScaleComponentCommand {
+                    opts,
+                    host_id,
+                    host_match,
+                    component_ref,
+                    component_id,
+                    legacy_component_id,
+                    max_instances,
+                    annotations,
+                    annotations_file,
+                    cap_at_host_capacity,
+                    config,
+                    constraints,
+                    placement,
+                    skip_wait,
+                    wait_timeout_ms,
+                    wait_for_ready,
+                    dry_run,
+                    owner,
+                    match_annotations,
+                    retry,
+                    receipt,
+                    progress: _,
+                } = *cmd;
                 assert_eq!(&opts.ctl_host.unwrap(), CTL_HOST);
                 assert_eq!(&opts.ctl_port.unwrap(), CTL_PORT);
                 assert_eq!(&opts.lattice.unwrap(), DEFAULT_LATTICE);
                 assert_eq!(opts.timeout_ms, 2001);
-                assert_eq!(host_id, HOST_ID);
+                assert_eq!(host_id.as_deref(), Some(HOST_ID));
+                assert_eq!(host_match, HostMatchOpts::default());
+                assert_eq!(constraints, None);
+                assert_eq!(placement, Placement::First);
                 assert_eq!(component_ref, "ghcr.io/component:v2".to_string());
                 assert_eq!(component_id, "mycomponentv2".to_string());
+                assert_eq!(legacy_component_id, None);
                 assert_eq!(max_instances, 1);
                 assert_eq!(annotations, vec!["foo=bar".to_string()]);
+                assert_eq!(annotations_file, None);
+                assert_eq!(retry.retries, 0);
+                assert!(!receipt.receipt);
+                assert!(!cap_at_host_capacity);
                 assert_eq!(config, vec!["default-port", "lang"]);
                 assert!(!skip_wait);
+                assert!(!wait_for_ready);
                 assert_eq!(wait_timeout_ms, 5000);
+                assert!(!dry_run);
+                assert_eq!(owner, None);
+                assert!(match_annotations.is_empty());
+            }
+            cmd => panic!("ctl scale component constructed incorrect command {cmd:?}"),
+        }
+
+        let scale_component_auction: Cmd = Parser::try_parse_from([
+            "ctl",
+            "scale",
+            "component",
+            "ghcr.io/component:v2",
+            "mycomponentv2",
+            "--constraint",
+            "zone=east",
+        ])?;
+        match scale_component_auction.command {
+            CtlCliCommand::Scale(ScaleCommand::Component(cmd)) => {
+                let ScaleComponentCommand {
+                    host_id,
+                    component_ref,
+                    component_id,
+                    constraints,
+                    ..
+                } = *cmd;
+                assert_eq!(host_id, None);
+                assert_eq!(component_ref, "ghcr.io/component:v2");
+                assert_eq!(component_id, "mycomponentv2");
+                assert_eq!(constraints, Some(vec!["zone=east".to_string()]));
             }
             cmd => panic!("ctl scale component constructed incorrect command {cmd:?}"),
         }
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use serde_json::json;
 use term_table::{
@@ -64,6 +64,34 @@ use crate::util::format_optional;
     table.render()
 }
 
+/// Helper function to render links as a tree grouped by source component, with each source's
+/// links sorted by target
+#[must_use] pub fn links_tree(list: Vec<Link>) -> String {
+    let mut by_source: BTreeMap<String, Vec<Link>> = BTreeMap::new();
+    for link in list {
+        by_source.entry(link.source_id().to_string()).or_default().push(link);
+    }
+
+    let mut tree = String::new();
+    for (source_id, mut links) in by_source {
+        links.sort_by(|a, b| (a.target(), a.name()).cmp(&(b.target(), b.name())));
+        tree.push_str(&source_id);
+        tree.push('\n');
+        for (idx, l) in links.iter().enumerate() {
+            let branch = if idx + 1 == links.len() { "└──" } else { "├──" };
+            tree.push_str(&format!(
+                "{branch} {} {}:{}/{} ({})\n",
+                l.target(),
+                l.wit_namespace(),
+                l.wit_package(),
+                l.interfaces().join(","),
+                l.name(),
+            ));
+        }
+    }
+    tree
+}
+
 /// Helper function to transform a Host list into a table string for printing
 #[must_use] pub fn hosts_table(mut hosts: Vec<Host>) -> String {
     // Sort hosts by uptime_seconds in descending order
@@ -315,3 +343,38 @@ use crate::util::format_optional;
 
     table.render()
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn link(source: &str, target: &str, package: &str, interfaces: &[&str]) -> Link {
+        Link::builder()
+            .source_id(source)
+            .target(target)
+            .name("default")
+            .wit_namespace("wasi")
+            .wit_package(package)
+            .interfaces(interfaces.iter().map(|i| (*i).to_string()).collect())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn links_tree_groups_by_source() {
+        let tree = links_tree(vec![
+            link("httpserver", "echo", "http", &["incoming-handler"]),
+            link("echo", "kv-redis", "keyvalue", &["store", "atomics"]),
+            link("echo", "httpclient", "http", &["outgoing-handler"]),
+        ]);
+        assert_eq!(
+            tree,
+            "echo\n\
+             ├── httpclient wasi:http/outgoing-handler (default)\n\
+             └── kv-redis wasi:keyvalue/store,atomics (default)\n\
+             httpserver\n\
+             └── echo wasi:http/incoming-handler (default)\n"
+        );
+        assert!(links_tree(Vec::new()).is_empty());
+    }
+}
@@ -59,6 +59,10 @@ pub struct NewProjectArgs {
     #[clap(short, long)]
     pub template_name: Option<String>,
 
+    /// Only offer templates written in this language, e.g. rust or go for providers
+    #[clap(long, alias = "lang", conflicts_with_all = ["git", "path"])]
+    pub language: Option<String>,
+
     /// Don't run 'git init' on the new folder
     #[clap(long)]
     pub no_git_init: bool,
@@ -78,6 +82,7 @@ impl From<NewCliCommand> for Project {
             silent: args.silent,
             favorites: args.favorites,
             template_name: args.template_name,
+            language: args.language,
             no_git_init: args.no_git_init,
             path: args.path,
             git: args.git,
@@ -3,7 +3,8 @@ use std::io::Read;
 use std::{collections::HashMap, path::PathBuf};
 
 use crate::lib::cli::par::{
-    convert_error, create_provider_archive, detect_arch, insert_provider_binary,
+    add_provider_binaries, convert_error, create_provider_archive, detect_arch,
+    insert_provider_binary, parse_provider_binary,
 };
 use crate::lib::cli::{extract_keypair, inspect, par, CommandOutput, OutputKind};
 use anyhow::{anyhow, bail, Context, Result};
@@ -86,9 +87,12 @@ pub struct CreateCommand {
     #[clap(short = 'a', long = "arch", default_value_t = detect_arch())]
     arch: String,
 
-    /// Path to provider binary for populating the archive
-    #[clap(short = 'b', long = "binary")]
-    binary: String,
+    /// Path to provider binary for populating the archive. Repeat as TARGET=PATH to add a binary
+    /// for each target, with targets in format ARCH-OS (e.g. aarch64-macos) or OS/ARCH (e.g.
+    /// linux/amd64). Binaries without a target are for --arch. Start paths containing `=` with
+    /// `./`
+    #[clap(short = 'b', long = "binary", required = true)]
+    binary: Vec<String>,
 
     /// File output destination path
     #[clap(long = "destination")]
@@ -211,6 +215,9 @@ impl From<InspectCommand> for inspect::InspectCliCommand {
             insecure: cmd.insecure,
             insecure_skip_tls_verify: cmd.insecure_skip_tls_verify,
             no_cache: cmd.no_cache,
+            interfaces: false,
+            verify_build: None,
+            check_compat: None,
         }
     }
 }
@@ -244,14 +251,22 @@ pub async fn handle_command(
 
 /// Creates a provider archive using an initial architecture target, provider, and signing keys
 pub async fn handle_create(cmd: CreateCommand, output_kind: OutputKind) -> Result<CommandOutput> {
-    let mut f = File::open(cmd.binary.clone())
-        .with_context(|| format!("failed to load binary [{}]", &cmd.binary))?;
-    let mut lib = Vec::new();
-    f.read_to_end(&mut lib)?;
+    let mut binaries = Vec::with_capacity(cmd.binary.len());
+    for value in &cmd.binary {
+        let (target, path) = parse_provider_binary(value, &cmd.arch)?;
+        let bytes = std::fs::read(&path)
+            .with_context(|| format!("failed to load binary [{}]", path.display()))?;
+        binaries.push((target, path, bytes));
+    }
+    // Keys and the default destination are named after the first binary
+    let first_binary = binaries
+        .first()
+        .map(|(_, path, _)| path.to_string_lossy().to_string())
+        .context("at least one binary is required")?;
 
     let issuer = extract_keypair(
         cmd.issuer.as_deref(),
-        Some(&cmd.binary),
+        Some(&first_binary),
         cmd.directory.clone(),
         KeyPairType::Account,
         cmd.disable_keygen,
@@ -259,7 +274,7 @@ pub async fn handle_create(cmd: CreateCommand, output_kind: OutputKind) -> Resul
     )?;
     let subject = extract_keypair(
         cmd.subject.as_deref(),
-        Some(&cmd.binary),
+        Some(&first_binary),
         cmd.directory.clone(),
         KeyPairType::Service,
         cmd.disable_keygen,
@@ -271,7 +286,7 @@ pub async fn handle_create(cmd: CreateCommand, output_kind: OutputKind) -> Resul
         Some(path) => path,
         None => format!(
             "{}{}",
-            PathBuf::from(&cmd.binary)
+            PathBuf::from(&first_binary)
                 .file_stem()
                 .unwrap()
                 .to_str()
@@ -296,8 +311,21 @@ pub async fn handle_create(cmd: CreateCommand, output_kind: OutputKind) -> Resul
     };
 
     let compress = cmd.compress;
-    let mut par = create_provider_archive(cmd.into(), &lib, wit_interface_bytes.as_deref())
-        .context("failed to create provider archive with built provider")?;
+    let mut binaries = binaries
+        .into_iter()
+        .map(|(target, _, bytes)| (target, bytes));
+    let (first_target, first_bytes) = binaries.next().context("at least one binary is required")?;
+    let mut par = create_provider_archive(
+        par::ParCreateArgs {
+            arch: first_target,
+            ..cmd.into()
+        },
+        &first_bytes,
+        wit_interface_bytes.as_deref(),
+    )
+    .context("failed to create provider archive with built provider")?;
+    add_provider_binaries(&mut par, &binaries.collect::<Vec<_>>())?;
+    let targets = par.targets();
     par.write(&outfile, &issuer, &subject, compress)
         .await
         .map_err(|e| anyhow!("{e}"))
@@ -310,6 +338,7 @@ pub async fn handle_create(cmd: CreateCommand, output_kind: OutputKind) -> Resul
 
     let mut map = HashMap::new();
     map.insert("file".to_string(), json!(outfile));
+    map.insert("targets".to_string(), json!(targets));
     Ok(CommandOutput::new(
         format!("Successfully created archive {outfile}"),
         map,
@@ -416,6 +445,8 @@ mod test {
             "--compress",
             "--wit-directory",
             "./wit",
+            "--binary",
+            "linux/arm64=./testrunner-arm.so",
         ])
         .unwrap();
         match create_long.par {
@@ -436,7 +467,10 @@ mod test {
                 wit_dir,
             }) => {
                 assert_eq!(arch, "x86_64-testrunner");
-                assert_eq!(binary, "./testrunner.so");
+                assert_eq!(
+                    binary,
+                    ["./testrunner.so", "linux/arm64=./testrunner-arm.so"]
+                );
                 assert_eq!(directory.unwrap(), PathBuf::from("./tests/fixtures"));
                 assert_eq!(issuer.unwrap(), ISSUER);
                 assert_eq!(subject.unwrap(), SUBJECT);
@@ -497,7 +531,7 @@ mod test {
                 wit_dir,
             }) => {
                 assert_eq!(arch, "x86_64-testrunner");
-                assert_eq!(binary, "./testrunner.so");
+                assert_eq!(binary, ["./testrunner.so"]);
                 assert_eq!(directory.unwrap(), PathBuf::from("./tests/fixtures"));
                 assert_eq!(issuer.unwrap(), ISSUER);
                 assert_eq!(subject.unwrap(), SUBJECT);
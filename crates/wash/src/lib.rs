@@ -1,21 +1,28 @@
 // Library modules (from wash-lib)
 pub mod lib {
     pub mod app;
+    pub mod backoff;
     pub mod build;
+    pub mod call;
     pub mod capture;
     pub mod cli;
     pub mod common;
+    pub mod compat;
     pub mod component;
     pub mod config;
     pub mod context;
     pub mod deps;
     pub mod drain;
+    pub mod failure;
     pub mod generate;
     pub mod id;
     pub mod keys;
+    pub mod links;
     pub mod parser;
     pub mod plugin;
+    pub mod provider;
     pub mod registry;
+    pub mod rollout;
     pub mod spier;
     pub mod start;
     pub mod wait;
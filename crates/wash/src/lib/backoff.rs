@@ -0,0 +1,385 @@
+//! Shared backoff strategies for operations that are retried, such as auctions or waiting for a
+//! host or server to come up. Keeping the delay calculation in one place means every retry loop
+//! in wash behaves (and can be configured) the same way.
+
+use std::future::Future;
+use std::time::Duration;
+
+use anyhow::Result;
+use async_nats::RequestErrorKind;
+use clap::Args;
+use rand::Rng;
+use tokio::time::Instant;
+use tracing::debug;
+
+/// How long to wait between attempts of a retried operation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum BackoffStrategy {
+    /// Wait the same amount of time between every attempt
+    Fixed,
+    /// Double the wait after every attempt, up to a maximum
+    #[default]
+    Exponential,
+    /// Like exponential, but wait a random amount of time between zero and the exponential delay,
+    /// so many clients retrying at once don't do so in lockstep
+    ExponentialJitter,
+}
+
+/// The outcome of a single poll of [`Backoff::poll`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Polled<T, P> {
+    /// What was polled for is ready
+    Ready(T),
+    /// Not ready yet, along with what is still outstanding
+    Pending(P),
+}
+
+/// A backoff strategy along with the delays it works with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    pub strategy: BackoffStrategy,
+    /// The delay before the first retry
+    pub base: Duration,
+    /// The longest delay between two attempts
+    pub max: Duration,
+}
+
+impl Backoff {
+    #[must_use]
+    pub fn new(strategy: BackoffStrategy, base: Duration, max: Duration) -> Self {
+        Self {
+            strategy,
+            base,
+            max: max.max(base),
+        }
+    }
+
+    /// A backoff that always waits `delay`
+    #[must_use]
+    pub fn fixed(delay: Duration) -> Self {
+        Self::new(BackoffStrategy::Fixed, delay, delay)
+    }
+
+    /// The delay before retry number `retry` (starting at 0)
+    pub fn delay(&self, retry: u32, rng: &mut impl Rng) -> Duration {
+        let exponential = || {
+            self.base
+                .checked_mul(2u32.saturating_pow(retry))
+                .map_or(self.max, |delay| delay.min(self.max))
+        };
+        match self.strategy {
+            BackoffStrategy::Fixed => self.base,
+            BackoffStrategy::Exponential => exponential(),
+            BackoffStrategy::ExponentialJitter => {
+                exponential().mul_f64(rng.random_range(0.0..=1.0))
+            }
+        }
+    }
+
+    /// An endless sequence of the delays between attempts
+    pub fn delays<'a, R: Rng>(&'a self, rng: &'a mut R) -> impl Iterator<Item = Duration> + 'a {
+        (0..).map(move |retry| self.delay(retry, rng))
+    }
+
+    /// Run `op`, retrying it up to `retries` times with this backoff between attempts. The error
+    /// of the last attempt is returned if all of them fail
+    pub async fn retry<T, F, Fut>(&self, retries: u32, mut op: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        self.retry_if(retries, |_| true, |_| op()).await
+    }
+
+    /// Run `op`, retrying it up to `retries` times with this backoff between attempts for as long
+    /// as `should_retry` accepts the error. `op` is given the attempt number (starting at 0), so a
+    /// retry can first check whether an earlier attempt took effect after all
+    pub async fn retry_if<T, F, Fut, P>(
+        &self,
+        retries: u32,
+        mut should_retry: P,
+        mut op: F,
+    ) -> Result<T>
+    where
+        F: FnMut(u32) -> Fut,
+        Fut: Future<Output = Result<T>>,
+        P: FnMut(&anyhow::Error) -> bool,
+    {
+        let mut retry = 0;
+        loop {
+            match op(retry).await {
+                Ok(value) => return Ok(value),
+                Err(e) if retry < retries && should_retry(&e) => {
+                    let delay = self.delay(retry, &mut rand::rng());
+                    debug!(
+                        ?e,
+                        ?delay,
+                        attempt = retry + 1,
+                        "operation failed, retrying"
+                    );
+                    tokio::time::sleep(delay).await;
+                    retry += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Call `poll` until it is ready, with this backoff between calls, for at most `timeout` or
+    /// forever without one. Errors of `poll` are logged and polling continues. On timeout, the
+    /// `Pending` variant carries what was outstanding at the last poll that succeeded, if any
+    pub async fn poll<T, P, F, Fut>(
+        &self,
+        timeout: Option<Duration>,
+        mut poll: F,
+    ) -> Polled<T, Option<P>>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<Polled<T, P>>>,
+    {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut outstanding = None;
+        let mut retry = 0;
+        loop {
+            match poll().await {
+                Ok(Polled::Ready(value)) => return Polled::Ready(value),
+                Ok(Polled::Pending(pending)) => outstanding = Some(pending),
+                Err(e) => debug!(?e, "failed to poll, polling again"),
+            }
+            let delay = self.delay(retry, &mut rand::rng());
+            if deadline.is_some_and(|deadline| Instant::now() + delay > deadline) {
+                return Polled::Pending(outstanding);
+            }
+            tokio::time::sleep(delay).await;
+            retry = retry.saturating_add(1);
+        }
+    }
+
+    /// Run `op` until it succeeds, with this backoff between attempts
+    pub async fn retry_forever<T, E, F, Fut>(&self, mut op: F) -> T
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = std::result::Result<T, E>>,
+    {
+        let mut retry = 0;
+        loop {
+            if let Ok(value) = op().await {
+                return value;
+            }
+            tokio::time::sleep(self.delay(retry, &mut rand::rng())).await;
+            retry = retry.saturating_add(1);
+        }
+    }
+}
+
+/// Whether an error comes from a request timing out, e.g. a control interface request that got no
+/// reply in time. Such requests may or may not have reached the host, see [`RetryOpts`]
+#[must_use]
+pub fn is_timeout(err: &anyhow::Error) -> bool {
+    err.chain().any(|e| {
+        e.downcast_ref::<std::io::Error>()
+            .is_some_and(|e| e.kind() == std::io::ErrorKind::TimedOut)
+            || e.downcast_ref::<async_nats::RequestError>()
+                .is_some_and(|e| e.kind() == RequestErrorKind::TimedOut)
+    })
+}
+
+/// Longest delay between retries of a control interface request
+const RETRY_MAX: Duration = Duration::from_secs(10);
+
+/// Options for retrying control interface requests that time out. A timed out request may still
+/// have reached the host, so commands check whether it took effect before sending it again
+#[derive(Args, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryOpts {
+    /// Number of times to send a control request again if it times out, e.g. on a congested NATS
+    /// connection. Requests that the host has acted on are not sent again
+    #[clap(long = "retries", default_value_t = 0)]
+    pub retries: u32,
+
+    /// Delay before the first retry, in milliseconds. The delay doubles with every further retry
+    #[clap(long = "retry-backoff-ms", default_value_t = 500)]
+    pub retry_backoff_ms: u64,
+}
+
+impl Default for RetryOpts {
+    fn default() -> Self {
+        Self {
+            retries: 0,
+            retry_backoff_ms: 500,
+        }
+    }
+}
+
+impl RetryOpts {
+    #[must_use]
+    pub fn backoff(&self) -> Backoff {
+        Backoff::new(
+            BackoffStrategy::Exponential,
+            Duration::from_millis(self.retry_backoff_ms),
+            RETRY_MAX,
+        )
+    }
+
+    /// Run a control request, retrying it as configured if it times out. See
+    /// [`Backoff::retry_if`] for the attempt number given to `op`
+    pub async fn retry<T, F, Fut>(&self, op: F) -> Result<T>
+    where
+        F: FnMut(u32) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        self.backoff().retry_if(self.retries, is_timeout, op).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use anyhow::anyhow;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn strategies_produce_expected_delays() {
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let fixed = Backoff::new(BackoffStrategy::Fixed, ms(100), ms(1000));
+        assert_eq!(
+            fixed.delays(&mut rng).take(4).collect::<Vec<_>>(),
+            vec![ms(100); 4]
+        );
+
+        let exponential = Backoff::new(BackoffStrategy::Exponential, ms(100), ms(1000));
+        assert_eq!(
+            exponential.delays(&mut rng).take(6).collect::<Vec<_>>(),
+            vec![ms(100), ms(200), ms(400), ms(800), ms(1000), ms(1000)]
+        );
+        // Large retry counts are capped rather than overflowing
+        assert_eq!(exponential.delay(u32::MAX, &mut rng), ms(1000));
+
+        let jitter = Backoff::new(BackoffStrategy::ExponentialJitter, ms(100), ms(1000));
+        let first = jitter
+            .delays(&mut StdRng::seed_from_u64(42))
+            .take(6)
+            .collect::<Vec<_>>();
+        let second = jitter
+            .delays(&mut StdRng::seed_from_u64(42))
+            .take(6)
+            .collect::<Vec<_>>();
+        assert_eq!(first, second, "the same seed should give the same delays");
+        for (delay, bound) in first.iter().zip(exponential.delays(&mut rng)) {
+            assert!(*delay <= bound, "{delay:?} should be at most {bound:?}");
+        }
+        assert!(
+            first
+                .iter()
+                .zip(exponential.delays(&mut rng))
+                .any(|(d, b)| *d < b),
+            "jitter should shorten at least one delay"
+        );
+    }
+
+    #[tokio::test]
+    async fn retry_stops_after_success_or_retries() {
+        let backoff = Backoff::fixed(ms(1));
+
+        let calls = AtomicU32::new(0);
+        let value = backoff
+            .retry(5, || async {
+                if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                    Err(anyhow!("not yet"))
+                } else {
+                    Ok("done")
+                }
+            })
+            .await
+            .expect("should succeed on the third attempt");
+        assert_eq!(value, "done");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let calls = AtomicU32::new(0);
+        let err = backoff
+            .retry(2, || async {
+                let attempt = calls.fetch_add(1, Ordering::SeqCst);
+                Err::<(), _>(anyhow!("attempt {attempt}"))
+            })
+            .await
+            .expect_err("should give up after the retries");
+        assert_eq!(err.to_string(), "attempt 2");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn polling_stops_when_ready_or_at_the_timeout() {
+        let backoff = Backoff::fixed(ms(1));
+
+        let calls = AtomicU32::new(0);
+        let polled = backoff
+            .poll(Some(Duration::from_secs(5)), || async {
+                match calls.fetch_add(1, Ordering::SeqCst) {
+                    0 => Err(anyhow!("host unreachable")),
+                    1 => Ok(Polled::Pending("link")),
+                    _ => Ok(Polled::Ready("done")),
+                }
+            })
+            .await;
+        assert_eq!(polled, Polled::Ready("done"));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let polled = backoff
+            .poll(Some(ms(20)), || async {
+                Ok(Polled::<(), _>::Pending("link"))
+            })
+            .await;
+        assert_eq!(polled, Polled::Pending(Some("link")));
+
+        let polled = backoff
+            .poll(Some(ms(20)), || async {
+                Err::<Polled<(), ()>, _>(anyhow!("host unreachable"))
+            })
+            .await;
+        assert_eq!(polled, Polled::Pending(None));
+    }
+
+    #[tokio::test]
+    async fn only_timeouts_are_retried() {
+        let opts = RetryOpts {
+            retries: 3,
+            retry_backoff_ms: 1,
+        };
+
+        let attempts = std::sync::Mutex::new(Vec::new());
+        let value = opts
+            .retry(|attempt| {
+                attempts.lock().unwrap().push(attempt);
+                async move {
+                    if attempt == 0 {
+                        let timeout =
+                            std::io::Error::new(std::io::ErrorKind::TimedOut, "timed out");
+                        Err(anyhow::Error::new(timeout).context("failed to start provider"))
+                    } else {
+                        Ok(attempt)
+                    }
+                }
+            })
+            .await
+            .expect("should succeed after a timeout");
+        assert_eq!(value, 1);
+        assert_eq!(*attempts.lock().unwrap(), vec![0, 1]);
+
+        let calls = AtomicU32::new(0);
+        opts.retry(|_| async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>(anyhow!("provider is not allowed on this host"))
+        })
+        .await
+        .expect_err("other errors should not be retried");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
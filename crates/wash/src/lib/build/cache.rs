@@ -0,0 +1,500 @@
+//! A content-hash based cache for [`build_project`](super::build_project), so that building a
+//! project whose sources haven't changed since the last build returns the artifact from that build
+//! instead of compiling, adapting and signing it again.
+//!
+//! The fingerprint of a build covers every file in the project directory that isn't toolchain
+//! output, including generated files ignored by git and the WIT directory with its fetched
+//! dependencies. It also covers the `wasmcloud.toml` file, the signing configuration and the
+//! public keys of the signing keys, the versions of wash and of the language toolchain, the
+//! environment variables toolchains read and, for Rust projects, the `Cargo.lock` file and the
+//! local crates the project depends on. Anything else a build depends on, like a dependency
+//! fetched by a custom build command, isn't tracked, so use `wash build --no-cache` to force a
+//! rebuild after changing it.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use ignore::WalkBuilder;
+use nkeys::{KeyPair, KeyPairType};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{debug, warn};
+
+use crate::lib::cli::claims::issuer_signer;
+use crate::lib::cli::{determine_directory, extract_keypair, OutputKind};
+use crate::lib::parser::{CommonConfig, LanguageConfig, ProjectConfig};
+
+use super::{toolchain_versions, SignConfig};
+
+/// Name of the file in the build directory that records the last build
+pub const BUILD_CACHE_FILE_NAME: &str = ".wash-build-cache.json";
+
+/// The last build of a project
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildCacheEntry {
+    /// Fingerprint of the inputs of the build
+    pub fingerprint: String,
+    /// Path to the artifact it produced
+    pub artifact: PathBuf,
+    /// SHA-256 of the artifact, so an artifact changed since is built again
+    pub artifact_sha256: String,
+}
+
+/// Path of the build cache file of a project
+#[must_use]
+pub fn build_cache_path(config: &ProjectConfig) -> PathBuf {
+    config.common.build_dir.join(BUILD_CACHE_FILE_NAME)
+}
+
+/// Forget the last build of a project, so the next one builds from scratch
+pub async fn clear_build_cache(config: &ProjectConfig) -> Result<()> {
+    match tokio::fs::remove_file(build_cache_path(config)).await {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(e).context("failed to remove build cache")
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Prefixes of the environment variables that change what language toolchains build
+const TOOLCHAIN_ENV_PREFIXES: &[&str] = &["CARGO", "RUST", "GO", "TINYGO", "WASI", "WASM"];
+
+/// Fingerprint the inputs of building a project. Files are hashed in a stable order along with
+/// their path relative to the project, so renaming or moving a file changes the fingerprint too.
+/// Hashing reads every source file and runs the toolchain to find its version, so it's done on a
+/// blocking thread
+pub async fn build_fingerprint(
+    config: &ProjectConfig,
+    signing: Option<&SignConfig>,
+) -> Result<String> {
+    let config = config.clone();
+    let signing = signing.cloned();
+    tokio::task::spawn_blocking(move || fingerprint(&config, signing.as_ref()))
+        .await
+        .context("failed to fingerprint build")?
+}
+
+fn fingerprint(config: &ProjectConfig, signing: Option<&SignConfig>) -> Result<String> {
+    let mut hasher = Sha256::new();
+    if let Some(signing) = signing {
+        hash_signing(signing, &config.common.name, &mut hasher)?;
+    }
+
+    let wasmcloud_toml = config.wasmcloud_toml_dir.join("wasmcloud.toml");
+    if let Ok(contents) = std::fs::read(&wasmcloud_toml) {
+        hasher.update(contents);
+    }
+
+    let dir = &config.common.project_dir;
+    hasher.update(format!(
+        "{:?}",
+        toolchain_versions(Some(&config.language), dir)
+    ));
+    let mut env = std::env::vars_os()
+        .filter(|(name, _)| {
+            name.to_str().is_some_and(|name| {
+                TOOLCHAIN_ENV_PREFIXES
+                    .iter()
+                    .any(|prefix| name.starts_with(prefix))
+            })
+        })
+        .collect::<Vec<_>>();
+    env.sort();
+    hasher.update(format!("{env:?}"));
+
+    // Cargo writes a missing `Cargo.lock` when it's asked for metadata, so this goes before the
+    // sources of the project
+    if let LanguageConfig::Rust(_) = config.language {
+        hash_local_crates(&config.common, &mut hasher)?;
+    }
+    for root in source_roots(&config.common) {
+        hash_files(&root, &config.common.build_dir, false, &mut hasher)?;
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Digest of the sources of a project, as `sha256:<hex>`. Covers the files of the project that
+/// aren't ignored by git, so building the same sources differently, or from a fresh checkout,
+/// gives the same digest
+pub fn source_digest(common: &CommonConfig) -> Result<String> {
+    let mut hasher = Sha256::new();
+    for root in source_roots(common) {
+        hash_files(&root, &common.build_dir, true, &mut hasher)?;
+    }
+    Ok(format!("sha256:{:x}", hasher.finalize()))
+}
+
+/// The project directory, and the WIT directory if it's outside of the project
+fn source_roots(common: &CommonConfig) -> Vec<PathBuf> {
+    let mut roots = vec![common.project_dir.clone()];
+    if !common.wit_dir.starts_with(&common.project_dir) {
+        roots.push(common.wit_dir.clone());
+    }
+    roots
+}
+
+/// Hash every file under `root` along with its path relative to it
+fn hash_files(root: &Path, build_dir: &Path, gitignore: bool, hasher: &mut Sha256) -> Result<()> {
+    for path in source_files(root, build_dir, gitignore)? {
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        hasher.update(relative.to_string_lossy().as_bytes());
+        hasher.update(
+            std::fs::read(&path).with_context(|| format!("failed to read [{}]", path.display()))?,
+        );
+    }
+    Ok(())
+}
+
+/// Hash how a build is signed: the signing options and the public keys of the issuer and subject,
+/// and of the keys in the directory keys are looked up in and generated into. Seeds never go into
+/// the fingerprint, which is stored next to the artifact, only the public keys they resolve to
+fn hash_signing(signing: &SignConfig, project_name: &str, hasher: &mut Sha256) -> Result<()> {
+    hasher.update(format!(
+        "{} {} {} {:?}",
+        signing.sign_with,
+        signing.disable_keygen,
+        signing.embed_build_metadata,
+        signing.keys_directory
+    ));
+
+    // A key that can't be resolved yet is generated by the build, which then changes the
+    // fingerprint of the next one. Keys are never generated here
+    let issuer = issuer_signer(
+        &signing.sign_with,
+        signing.issuer.as_deref(),
+        Some(project_name),
+        signing.keys_directory.clone(),
+        true,
+        OutputKind::Json,
+    )
+    .and_then(|signer| signer.public_key());
+    match issuer {
+        Ok(public_key) => hasher.update(public_key),
+        Err(e) => {
+            debug!(error = %e, "no issuer key to fingerprint yet");
+            hasher.update("no issuer");
+        }
+    }
+    if let Some(subject) = &signing.subject {
+        match KeyPair::from_public_key(subject).or_else(|_| {
+            extract_keypair(
+                Some(subject),
+                None,
+                None,
+                KeyPairType::Module,
+                true,
+                OutputKind::Json,
+            )
+        }) {
+            Ok(key) => hasher.update(key.public_key()),
+            Err(e) => {
+                debug!(error = %e, "no subject key to fingerprint yet");
+                hasher.update("no subject");
+            }
+        }
+    }
+
+    let key_dir = determine_directory(signing.keys_directory.clone())?;
+    let Ok(entries) = std::fs::read_dir(&key_dir) else {
+        return Ok(());
+    };
+    let mut keys = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "nk"))
+        .collect::<Vec<_>>();
+    keys.sort();
+    for key in keys {
+        hasher.update(key.to_string_lossy().as_bytes());
+        let seed = std::fs::read_to_string(&key)
+            .with_context(|| format!("failed to read [{}]", key.display()))?;
+        // Files that don't hold a valid seed can't sign anything, so only their path matters
+        if let Ok(key) = KeyPair::from_seed(seed.trim()) {
+            hasher.update(key.public_key());
+        }
+    }
+    Ok(())
+}
+
+/// Hash the `Cargo.lock` and root manifest of the Cargo workspace of a Rust project, and the
+/// sources of every local crate it depends on that lives outside of the project
+fn hash_local_crates(common: &CommonConfig, hasher: &mut Sha256) -> Result<()> {
+    let metadata = cargo_metadata::MetadataCommand::new()
+        .current_dir(&common.project_dir)
+        .exec()
+        .context("failed to find the crates the project depends on")?;
+    let workspace_root = metadata.workspace_root.as_std_path();
+    for file in ["Cargo.lock", "Cargo.toml"] {
+        let path = workspace_root.join(file);
+        if !path.starts_with(&common.project_dir) {
+            if let Ok(contents) = std::fs::read(&path) {
+                hasher.update(contents);
+            }
+        }
+    }
+
+    let packages = metadata
+        .packages
+        .iter()
+        .map(|package| (&package.id, package))
+        .collect::<HashMap<_, _>>();
+    // Only the crates the project depends on, or every crate of the workspace if cargo can't tell
+    // which one is the project
+    let mut dependencies = BTreeSet::new();
+    match metadata
+        .resolve
+        .as_ref()
+        .and_then(|resolve| Some((resolve, resolve.root.as_ref()?)))
+    {
+        Some((resolve, root)) => {
+            let nodes = resolve
+                .nodes
+                .iter()
+                .map(|node| (&node.id, node))
+                .collect::<HashMap<_, _>>();
+            let mut pending = vec![root];
+            while let Some(id) = pending.pop() {
+                if dependencies.insert(id) {
+                    if let Some(node) = nodes.get(id) {
+                        pending.extend(&node.dependencies);
+                    }
+                }
+            }
+        }
+        None => dependencies.extend(packages.keys().copied()),
+    }
+
+    for id in dependencies {
+        let Some(package) = packages.get(id).filter(|package| package.source.is_none()) else {
+            continue;
+        };
+        let Some(dir) = package.manifest_path.parent().map(|dir| dir.as_std_path()) else {
+            continue;
+        };
+        if !dir.starts_with(&common.project_dir) {
+            hasher.update(package.name.as_bytes());
+            hash_files(dir, &common.build_dir, true, hasher)?;
+        }
+    }
+    Ok(())
+}
+
+/// Directories that hold the output of language toolchains rather than sources, skipped even
+/// when they aren't ignored by git
+const OUTPUT_DIRS: &[&str] = &[".git", "target", "node_modules"];
+
+/// The files under `root` that go into a build, sorted. Toolchain output directories and the build
+/// directory are skipped, and so are files ignored by git if `gitignore` is set
+fn source_files(root: &Path, build_dir: &Path, gitignore: bool) -> Result<Vec<PathBuf>> {
+    let build_dir = build_dir
+        .canonicalize()
+        .unwrap_or_else(|_| build_dir.to_path_buf());
+    let mut files = Vec::new();
+    let walk = WalkBuilder::new(root)
+        .hidden(false)
+        .require_git(false)
+        .git_ignore(gitignore)
+        .git_global(gitignore)
+        .git_exclude(gitignore)
+        .ignore(gitignore)
+        .filter_entry(move |entry| {
+            if !entry.file_type().is_some_and(|t| t.is_dir()) {
+                return true;
+            }
+            let is_output = entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| OUTPUT_DIRS.contains(&name));
+            !is_output && entry.path().canonicalize().ok().as_ref() != Some(&build_dir)
+        })
+        .build();
+    for entry in walk {
+        let entry = entry.context("failed to list project files")?;
+        if entry.file_type().is_some_and(|t| t.is_file()) {
+            files.push(entry.into_path());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// The artifact of the last build of the project, if it had the same fingerprint and the artifact
+/// is still there, unchanged
+pub async fn cached_artifact(config: &ProjectConfig, fingerprint: &str) -> Option<PathBuf> {
+    let contents = tokio::fs::read(build_cache_path(config)).await.ok()?;
+    let entry = match serde_json::from_slice::<BuildCacheEntry>(&contents) {
+        Ok(entry) => entry,
+        Err(e) => {
+            warn!(?e, "ignoring unreadable build cache");
+            return None;
+        }
+    };
+    if entry.fingerprint != fingerprint {
+        debug!("project changed since the last build");
+        return None;
+    }
+    let artifact = tokio::fs::read(&entry.artifact).await.ok()?;
+    (format!("{:x}", Sha256::digest(artifact)) == entry.artifact_sha256).then_some(entry.artifact)
+}
+
+/// Record the artifact a build with the given fingerprint produced
+pub async fn record_build(
+    config: &ProjectConfig,
+    fingerprint: String,
+    artifact: &Path,
+) -> Result<()> {
+    let contents = tokio::fs::read(artifact)
+        .await
+        .with_context(|| format!("failed to read built artifact [{}]", artifact.display()))?;
+    let entry = BuildCacheEntry {
+        fingerprint,
+        artifact: artifact.to_path_buf(),
+        artifact_sha256: format!("{:x}", Sha256::digest(contents)),
+    };
+    tokio::fs::create_dir_all(&config.common.build_dir)
+        .await
+        .context("failed to create build directory")?;
+    tokio::fs::write(build_cache_path(config), serde_json::to_vec_pretty(&entry)?)
+        .await
+        .context("failed to write build cache")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::lib::cli::claims::SigningBackend;
+    use crate::lib::parser::load_config;
+
+    const WASMCLOUD_TOML: &str = r#"
+name = "cached"
+language = "rust"
+type = "component"
+version = "0.1.0"
+"#;
+
+    const CARGO_TOML: &str = r#"
+[package]
+name = "cached"
+version = "0.1.0"
+edition = "2021"
+
+[workspace]
+"#;
+
+    #[tokio::test]
+    async fn builds_are_reused_until_sources_change() -> Result<()> {
+        let project_dir = tempfile::tempdir()?;
+        let dir = project_dir.path();
+        std::fs::write(dir.join("wasmcloud.toml"), WASMCLOUD_TOML)?;
+        std::fs::write(dir.join("Cargo.toml"), CARGO_TOML)?;
+        std::fs::create_dir_all(dir.join("src"))?;
+        std::fs::write(dir.join("src/lib.rs"), "fn main() {}")?;
+        let config = load_config(Some(dir.to_path_buf()), Some(true)).await?;
+
+        let fingerprint = build_fingerprint(&config, None).await?;
+        let digest = source_digest(&config.common)?;
+        assert!(digest.starts_with("sha256:"));
+        assert!(cached_artifact(&config, &fingerprint).await.is_none());
+
+        let artifact = config.common.build_dir.join("cached_s.wasm");
+        std::fs::create_dir_all(&config.common.build_dir)?;
+        std::fs::write(&artifact, b"signed component")?;
+        record_build(&config, fingerprint.clone(), &artifact).await?;
+        assert_eq!(
+            cached_artifact(&config, &fingerprint).await,
+            Some(artifact.clone())
+        );
+
+        // Toolchain output and the build directory don't go into the fingerprint
+        std::fs::create_dir_all(dir.join("target"))?;
+        std::fs::write(dir.join("target/cached.wasm"), b"compiled")?;
+        assert_eq!(build_fingerprint(&config, None).await?, fingerprint);
+        assert_eq!(source_digest(&config.common)?, digest);
+
+        // Signing differently or changing a source file does
+        assert_ne!(
+            build_fingerprint(&config, Some(&SignConfig::default())).await?,
+            fingerprint
+        );
+        std::fs::write(dir.join("src/lib.rs"), "fn main() { todo!() }")?;
+        let changed = build_fingerprint(&config, None).await?;
+        assert_ne!(changed, fingerprint);
+        assert_ne!(source_digest(&config.common)?, digest);
+        assert!(cached_artifact(&config, &changed).await.is_none());
+
+        // Generated files ignored by git go into the fingerprint but not into the source digest
+        let digest = source_digest(&config.common)?;
+        std::fs::write(dir.join(".gitignore"), "generated.rs\n")?;
+        std::fs::write(dir.join("generated.rs"), "// generated")?;
+        let generated = build_fingerprint(&config, None).await?;
+        std::fs::write(dir.join("generated.rs"), "// generated again")?;
+        assert_ne!(build_fingerprint(&config, None).await?, generated);
+        let digest_with_gitignore = source_digest(&config.common)?;
+        assert_ne!(digest_with_gitignore, digest);
+        std::fs::write(dir.join("generated.rs"), "// generated once more")?;
+        assert_eq!(source_digest(&config.common)?, digest_with_gitignore);
+
+        // So do the signing keys
+        let keys = tempfile::tempdir()?;
+        let signing = SignConfig {
+            keys_directory: Some(keys.path().to_path_buf()),
+            ..Default::default()
+        };
+        let module_key = keys.path().join("cached_module.nk");
+        std::fs::write(&module_key, KeyPair::new_module().seed()?)?;
+        let signed = build_fingerprint(&config, Some(&signing)).await?;
+        assert_eq!(build_fingerprint(&config, Some(&signing)).await?, signed);
+        std::fs::write(&module_key, KeyPair::new_module().seed()?)?;
+        assert_ne!(build_fingerprint(&config, Some(&signing)).await?, signed);
+
+        // An artifact changed since it was built isn't reused
+        std::fs::write(&artifact, b"something else")?;
+        assert!(cached_artifact(&config, &fingerprint).await.is_none());
+
+        clear_build_cache(&config).await?;
+        assert!(!build_cache_path(&config).exists());
+        clear_build_cache(&config).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rotating_an_env_seed_changes_the_fingerprint() -> Result<()> {
+        const SEED_VAR: &str = "WASH_BUILD_CACHE_TEST_ISSUER_SEED";
+        let project_dir = tempfile::tempdir()?;
+        let dir = project_dir.path();
+        std::fs::write(dir.join("wasmcloud.toml"), WASMCLOUD_TOML)?;
+        std::fs::write(dir.join("Cargo.toml"), CARGO_TOML)?;
+        std::fs::create_dir_all(dir.join("src"))?;
+        std::fs::write(dir.join("src/lib.rs"), "fn main() {}")?;
+        let config = load_config(Some(dir.to_path_buf()), Some(true)).await?;
+        let keys = tempfile::tempdir()?;
+        let signing = SignConfig {
+            keys_directory: Some(keys.path().to_path_buf()),
+            sign_with: SigningBackend::Env(SEED_VAR.to_string()),
+            ..Default::default()
+        };
+
+        let first = KeyPair::new_account();
+        std::env::set_var(SEED_VAR, first.seed()?);
+        let fingerprint = build_fingerprint(&config, Some(&signing)).await?;
+        assert_eq!(
+            build_fingerprint(&config, Some(&signing)).await?,
+            fingerprint
+        );
+
+        // The configuration is the same, only the seed in the variable changed
+        std::env::set_var(SEED_VAR, KeyPair::new_account().seed()?);
+        let rotated = build_fingerprint(&config, Some(&signing)).await?;
+        assert_ne!(rotated, fingerprint);
+
+        // Only the public key of the issuer goes into the fingerprint, not the seed as written
+        std::env::set_var(SEED_VAR, format!("  {}\n", first.seed()?));
+        assert_eq!(
+            build_fingerprint(&config, Some(&signing)).await?,
+            fingerprint
+        );
+
+        std::env::remove_var(SEED_VAR);
+        Ok(())
+    }
+}
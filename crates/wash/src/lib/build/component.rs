@@ -8,6 +8,7 @@ use std::{
 use anyhow::{anyhow, bail, Context, Result};
 use normpath::PathExt;
 use tracing::{debug, info, warn};
+use wascap::jwt::BuildMetadata;
 use wasi_preview1_component_adapter_provider::{
     WASI_SNAPSHOT_PREVIEW1_ADAPTER_NAME, WASI_SNAPSHOT_PREVIEW1_REACTOR_ADAPTER,
 };
@@ -15,7 +16,10 @@ use wasm_encoder::{Encode, Section};
 use wit_component::{ComponentEncoder, StringEncoding};
 
 use crate::lib::{
-    build::{convert_wit_dir_to_world, SignConfig, WASMCLOUD_WASM_TAG_EXPERIMENTAL},
+    build::{
+        collect_build_metadata, convert_wit_dir_to_world, SignConfig,
+        WASMCLOUD_WASM_TAG_EXPERIMENTAL,
+    },
     cli::{
         claims::{sign_file, ComponentMetadata, GenerateCommon, SignCommand},
         OutputKind,
@@ -44,7 +48,9 @@ pub async fn build_component(
 ) -> Result<PathBuf> {
     // Build component
     let component_wasm_path = if let Some(raw_command) = component_config.build_command.as_ref() {
-        build_custom_component(common_config, component_config, raw_command).await?
+        let custom_wasm_path =
+            build_custom_component(common_config, component_config, raw_command).await?;
+        componentize_custom_artifact(custom_wasm_path, common_config, component_config)?
     } else {
         // Build component based on language toolchain
         match language_config {
@@ -113,7 +119,17 @@ pub async fn build_component(
 
     // Sign the wasm file (if configured)
     if let Some(cfg) = signing_config {
-        sign_component_wasm(common_config, component_config, cfg, component_wasm_path)
+        let build_metadata = cfg
+            .embed_build_metadata
+            .then(|| collect_build_metadata(common_config, Some(language_config)))
+            .transpose()?;
+        sign_component_wasm(
+            common_config,
+            component_config,
+            cfg,
+            component_wasm_path,
+            build_metadata,
+        )
     } else {
         Ok(component_wasm_path)
     }
@@ -141,12 +157,14 @@ pub(crate) fn adapt_component_to_wasip2(
     Ok(adapted_wasm_path.to_path_buf())
 }
 
-/// Sign the component at `component_wasm_path` using the provided configuration
+/// Sign the component at `component_wasm_path` using the provided configuration, embedding
+/// `build_metadata` into its claims if given
 pub fn sign_component_wasm(
     common_config: &CommonConfig,
     component_config: &ComponentConfig,
     signing_config: &SignConfig,
     component_wasm_path: impl AsRef<Path>,
+    build_metadata: Option<BuildMetadata>,
 ) -> Result<PathBuf> {
     // If we're building for WASIP1 or WASIP2, we're targeting components-first
     // functionality, and the signed module should be marked as experimental
@@ -189,6 +207,8 @@ pub fn sign_component_wasm(
             },
             tags: tags.into_iter().collect(),
         },
+        sign_with: signing_config.sign_with.clone(),
+        build_metadata,
     };
     sign_file(sign_options, OutputKind::Json)?;
 
@@ -391,6 +411,7 @@ async fn build_custom_component(
     command
         .current_dir(&common_config.project_dir)
         .args(args)
+        .envs(&component_config.build_env)
         .stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped());
 
@@ -440,6 +461,47 @@ async fn build_custom_component(
     Ok(component_path)
 }
 
+/// Turn the artifact of a custom build command into a component, if it isn't one already, according
+/// to the `wasm_target` of the component. A core module has the WIT world of the component embedded
+/// if it has one, and both core and WASI preview 1 modules are adapted to WASI preview 2. The
+/// component is written next to the other build output, leaving the artifact as it was built
+fn componentize_custom_artifact(
+    artifact: PathBuf,
+    common_config: &CommonConfig,
+    component_config: &ComponentConfig,
+) -> Result<PathBuf> {
+    // A missing artifact was already warned about, signing it reports the error
+    let Ok(wasm) = fs::read(&artifact) else {
+        return Ok(artifact);
+    };
+    if wasmparser::Parser::is_component(&wasm) {
+        return Ok(artifact);
+    }
+    let component_wasm_path = common_config
+        .build_dir
+        .join(format!("{}_component.wasm", common_config.wasm_bin_name()));
+    fs::create_dir_all(&common_config.build_dir).context("failed to create build directory")?;
+    match (&component_config.wasm_target, &component_config.wit_world) {
+        (WasmTarget::CoreModule, Some(wit_world)) => embed_wasm_component_metadata(
+            &common_config.wit_dir,
+            wit_world,
+            &artifact,
+            &component_wasm_path,
+        )?,
+        // Without a world to embed, a core module is signed as it was built
+        (WasmTarget::CoreModule, None) => return Ok(artifact),
+        (WasmTarget::WasiP1 | WasmTarget::WasiP2, _) => {
+            fs::write(&component_wasm_path, wasm).with_context(|| {
+                format!(
+                    "failed to write component to [{}]",
+                    component_wasm_path.display()
+                )
+            })?;
+        }
+    }
+    adapt_component_to_wasip2(&component_wasm_path, component_config)
+}
+
 /// Generate the bindgen code that `TinyGo` components need
 async fn generate_tinygo_bindgen(project_dir: impl AsRef<Path>) -> Result<()> {
     let project_dir = project_dir.as_ref();
@@ -613,14 +675,15 @@ mod tests {
     use std::path::PathBuf;
 
     use crate::lib::build::{CommonConfig, WASMCLOUD_WASM_TAG_EXPERIMENTAL};
-    use crate::lib::parser::{ComponentConfig, RegistryConfig, WasmTarget};
+    use crate::lib::parser::{ComponentConfig, LanguageConfig, RegistryConfig, WasmTarget};
     use anyhow::{Context, Result};
     use semver::Version;
     use wascap::{jwt::Token, wasm::extract_claims};
     use wasmparser::{Parser, Payload};
 
     use super::{
-        embed_wasm_component_metadata, generate_tinygo_bindgen, sign_component_wasm, SignConfig,
+        build_component, embed_wasm_component_metadata, generate_tinygo_bindgen,
+        sign_component_wasm, SignConfig,
     };
 
     const MODULE_WAT: &str = "(module)";
@@ -824,6 +887,7 @@ func main() {}
                 },
                 &SignConfig::default(),
                 &wasm_path,
+                None,
             )?;
 
             // Check that the experimental tag is present
@@ -858,6 +922,47 @@ func main() {}
         Ok(())
     }
 
+    /// Ensure that a core module built by a custom build command is turned into a component
+    #[tokio::test]
+    async fn build_command_core_module_is_made_a_component() -> Result<()> {
+        let project_dir = tempfile::tempdir()?;
+        setup_build_component(&project_dir)?;
+        let common_config = CommonConfig {
+            name: "test".into(),
+            version: Version::parse("0.1.0")?,
+            revision: 0,
+            wit_dir: project_dir.path().join("wit"),
+            build_dir: project_dir.path().join("build"),
+            project_dir: project_dir.path().into(),
+            wasm_bin_name: None,
+            registry: RegistryConfig::default(),
+        };
+        let mut component_config = ComponentConfig {
+            wit_world: Some("test-world".into()),
+            build_command: Some("cp test.wasm built.wasm".into()),
+            build_artifact: Some(PathBuf::from("built.wasm")),
+            ..ComponentConfig::default()
+        };
+        let language = LanguageConfig::Other("c".into());
+
+        let built = build_component(&component_config, &language, &common_config, None).await?;
+        assert_eq!(built, project_dir.path().join("build/test_component.wasm"));
+        assert!(wasmparser::Parser::is_component(&fs::read(&built)?));
+        // The artifact of the command is left as it is
+        assert!(!wasmparser::Parser::is_component(&fs::read(
+            project_dir.path().join("built.wasm")
+        )?));
+
+        // A failing command fails the build
+        component_config.build_command = Some("false".into());
+        assert!(
+            build_component(&component_config, &language, &common_config, None)
+                .await
+                .is_err()
+        );
+        Ok(())
+    }
+
     /// Ensure that golang component generation works with a bindgen'd component
     #[tokio::test]
     async fn golang_generate_bindgen_component_basic() -> Result<()> {
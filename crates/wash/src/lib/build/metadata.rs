@@ -0,0 +1,176 @@
+//! Metadata about how a component was built, embedded into its claims when it is signed so a
+//! deployed component can be traced back to the sources and toolchains it was built from.
+//!
+//! The time of the build honors [`SOURCE_DATE_EPOCH`](https://reproducible-builds.org/specs/source-date-epoch/),
+//! so reproducible builds of the same sources embed the same metadata.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use tracing::debug;
+use wascap::jwt::BuildMetadata;
+
+use crate::lib::parser::{CommonConfig, LanguageConfig};
+
+use super::source_digest;
+
+/// Collect the [`BuildMetadata`] of a project being built with the toolchain of `language_config`,
+/// if it's built with one rather than with a `build_command`. Details that can't be found, like
+/// the revision of a project outside of git or the version of a missing toolchain, are left out
+pub fn collect_build_metadata(
+    common_config: &CommonConfig,
+    language_config: Option<&LanguageConfig>,
+) -> Result<BuildMetadata> {
+    Ok(BuildMetadata {
+        source_revision: source_revision(&common_config.project_dir),
+        source_digest: Some(source_digest(common_config)?),
+        built_at: Some(build_time()?),
+        toolchains: toolchain_versions(language_config, &common_config.project_dir),
+    })
+}
+
+/// Versions of wash and of the toolchain of `language_config`, keyed by the name of each tool.
+/// Tools that can't be run are left out
+pub(crate) fn toolchain_versions(
+    language_config: Option<&LanguageConfig>,
+    dir: &Path,
+) -> BTreeMap<String, String> {
+    let mut toolchains =
+        BTreeMap::from([("wash".to_string(), env!("CARGO_PKG_VERSION").to_string())]);
+    let versions: Vec<(&str, PathBuf, &[&str])> = match language_config {
+        Some(LanguageConfig::Rust(rust_config)) => vec![
+            (
+                "cargo",
+                rust_config
+                    .cargo_path
+                    .clone()
+                    .unwrap_or_else(|| "cargo".into()),
+                &["--version"],
+            ),
+            ("rustc", "rustc".into(), &["--version"]),
+        ],
+        Some(LanguageConfig::TinyGo(tinygo_config)) => vec![(
+            "tinygo",
+            tinygo_config
+                .tinygo_path
+                .clone()
+                .unwrap_or_else(|| "tinygo".into()),
+            &["version"],
+        )],
+        Some(LanguageConfig::Go(go_config)) => vec![(
+            "go",
+            go_config.go_path.clone().unwrap_or_else(|| "go".into()),
+            &["version"],
+        )],
+        Some(LanguageConfig::Other(_)) | None => Vec::new(),
+    };
+    for (name, bin, args) in versions {
+        if let Some(version) = command_output(&bin, args, dir) {
+            toolchains.insert(name.to_string(), version);
+        }
+    }
+    toolchains
+}
+
+/// The git commit checked out in `dir`, with a `-dirty` suffix if there are uncommitted changes
+/// in `dir`. Changes elsewhere in the repository don't go into the project, so they are ignored
+fn source_revision(dir: &Path) -> Option<String> {
+    let revision = command_output(Path::new("git"), &["rev-parse", "HEAD"], dir)?;
+    let dirty = Command::new("git")
+        .args(["status", "--porcelain", "--", "."])
+        .current_dir(dir)
+        .output()
+        .is_ok_and(|output| !output.stdout.is_empty());
+    Some(if dirty {
+        format!("{revision}-dirty")
+    } else {
+        revision
+    })
+}
+
+/// Seconds since the epoch from `SOURCE_DATE_EPOCH` if it is set, or now
+fn build_time() -> Result<u64> {
+    match std::env::var("SOURCE_DATE_EPOCH") {
+        Ok(epoch) => epoch
+            .trim()
+            .parse()
+            .with_context(|| format!("invalid SOURCE_DATE_EPOCH [{epoch}]")),
+        Err(_) => Ok(SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .context("system time is before the epoch")?
+            .as_secs()),
+    }
+}
+
+/// The trimmed stdout of a command that succeeded
+fn command_output(bin: &Path, args: &[&str], dir: &Path) -> Option<String> {
+    let output = match Command::new(bin).args(args).current_dir(dir).output() {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            debug!(bin = %bin.display(), status = %output.status, "command failed");
+            return None;
+        }
+        Err(e) => {
+            debug!(bin = %bin.display(), ?e, "failed to run command");
+            return None;
+        }
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!stdout.is_empty()).then_some(stdout)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::lib::parser::load_config;
+
+    #[tokio::test]
+    async fn metadata_describes_the_sources_of_a_project() -> Result<()> {
+        let project_dir = tempfile::tempdir()?;
+        let dir = project_dir.path();
+        std::fs::write(
+            dir.join("wasmcloud.toml"),
+            "name = \"meta\"\nlanguage = \"rust\"\ntype = \"component\"\nversion = \"0.1.0\"\n",
+        )?;
+        let config = load_config(Some(dir.to_path_buf()), Some(true)).await?;
+
+        let metadata = collect_build_metadata(&config.common, None)?;
+        assert_eq!(
+            metadata.toolchains.get("wash").map(String::as_str),
+            Some(env!("CARGO_PKG_VERSION"))
+        );
+        assert_eq!(metadata.toolchains.len(), 1);
+        assert_eq!(metadata.source_digest, Some(source_digest(&config.common)?));
+        assert!(metadata.built_at.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn only_changes_to_the_project_mark_it_dirty() -> Result<()> {
+        let repo = tempfile::tempdir()?;
+        let project = repo.path().join("project");
+        std::fs::create_dir_all(&project)?;
+        std::fs::write(project.join("lib.rs"), "fn main() {}")?;
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .args(["-c", "user.name=wash", "-c", "user.email=wash@example.com"])
+                .args(args)
+                .current_dir(repo.path())
+                .output()
+        };
+        git(&["init"])?;
+        git(&["add", "."])?;
+        git(&["commit", "-m", "init"])?;
+        let revision = source_revision(&project).context("missing revision")?;
+        assert!(!revision.ends_with("-dirty"));
+
+        std::fs::write(repo.path().join("unrelated.txt"), "changed")?;
+        assert_eq!(source_revision(&project), Some(revision.clone()));
+        std::fs::write(project.join("lib.rs"), "fn main() { todo!() }")?;
+        assert_eq!(source_revision(&project), Some(format!("{revision}-dirty")));
+        Ok(())
+    }
+}
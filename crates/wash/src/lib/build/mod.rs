@@ -3,20 +3,26 @@
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
-use tracing::info;
+use tracing::{info, warn};
 use wasm_pkg_core::lock::LockFile;
 use wit_parser::{Resolve, WorldId};
 
 use crate::lib::{
-    cli::CommonPackageArgs,
+    cli::{claims::SigningBackend, CommonPackageArgs},
     deps::WkgFetcher,
     parser::{CommonConfig, ProjectConfig, RegistryConfig, TypeConfig},
 };
 
+mod cache;
+pub use cache::*;
 mod component;
 pub use component::*;
+mod metadata;
+pub use metadata::*;
 mod provider;
 use provider::build_provider;
+mod workspace;
+pub use workspace::*;
 
 /// This tag indicates that a Wasm module uses experimental features of wasmCloud
 /// and/or the surrounding ecosystem.
@@ -74,6 +80,13 @@ pub struct SignConfig {
 
     /// Disables autogeneration of keys if seed(s) are not provided
     pub disable_keygen: bool,
+
+    /// Where the issuer key is held, which may be outside of wash
+    pub sign_with: SigningBackend,
+
+    /// Embed [`BuildMetadata`](wascap::jwt::BuildMetadata) about the sources and toolchains of
+    /// the build into the claims of components
+    pub embed_build_metadata: bool,
 }
 
 /// Using a [`ProjectConfig`], usually parsed from a `wasmcloud.toml` file, build the project
@@ -81,6 +94,9 @@ pub struct SignConfig {
 /// or [`build_provider`] when the project is a provider.
 ///
 /// This function returns the path to the compiled artifact, a signed Wasm component or signed provider archive.
+/// When nothing that goes into the build changed since the last one, the artifact of the last
+/// build is returned without building again (see [`build_fingerprint`]). Builds that embed
+/// [`BuildMetadata`](wascap::jwt::BuildMetadata) always build again.
 ///
 /// # Usage
 /// ```
@@ -156,14 +172,47 @@ pub async fn build_project(
             .context("Unable to write lock file for dependencies")?;
     }
 
-    match &config.project_type {
+    // The metadata embedded into a build describes when and from which revision it was built, so
+    // reusing an earlier build would embed stale metadata
+    let fingerprint = if signing.is_some_and(|signing| signing.embed_build_metadata) {
+        None
+    } else {
+        match build_fingerprint(config, signing).await {
+            Ok(fingerprint) => Some(fingerprint),
+            Err(e) => {
+                warn!(
+                    ?e,
+                    "failed to fingerprint build, building without the build cache"
+                );
+                None
+            }
+        }
+    };
+    if let Some(fingerprint) = &fingerprint {
+        if let Some(artifact) = cached_artifact(config, fingerprint).await {
+            info!(
+                "Skipping build, nothing changed since [{}] was built",
+                artifact.display()
+            );
+            return Ok(artifact);
+        }
+    }
+
+    let artifact = match &config.project_type {
         TypeConfig::Component(component_config) => {
             build_component(component_config, &config.language, &config.common, signing).await
         }
         TypeConfig::Provider(provider_config) => {
             build_provider(provider_config, &config.language, &config.common, signing).await
         }
+    }?;
+    // The cache only saves time, a build that succeeded shouldn't fail because it can't be cached
+    if let Some(fingerprint) = fingerprint {
+        if let Err(e) = record_build(config, fingerprint, &artifact).await {
+            warn!(?e, "failed to record build in the build cache");
+        }
     }
+    Ok(artifact)
 }
 
 /// Build a [`wit_parser::Resolve`] from a provided directory
@@ -8,6 +8,7 @@ use nkeys::KeyPairType;
 use tracing::{trace, warn};
 
 use crate::lib::build::SignConfig;
+use crate::lib::cli::claims::{EnvSigner, SigningBackend};
 use crate::lib::cli::par::{create_provider_archive, detect_arch, ParCreateArgs};
 use crate::lib::cli::{extract_keypair, OutputKind};
 use crate::lib::parser::{CommonConfig, GoConfig, LanguageConfig, ProviderConfig, RustConfig};
@@ -91,14 +92,20 @@ pub(crate) async fn build_provider(
             .await
             .with_context(|| format!("failed to create directory [{}]", parent.display()))?;
     }
-    let issuer = extract_keypair(
-        sign_config.issuer.as_deref(),
-        Some(&provider_path_buf.to_string_lossy()),
-        sign_config.keys_directory.clone(),
-        KeyPairType::Account,
-        sign_config.disable_keygen,
-        OutputKind::Json,
-    )?;
+    let issuer = match &sign_config.sign_with {
+        SigningBackend::File => extract_keypair(
+            sign_config.issuer.as_deref(),
+            Some(&provider_path_buf.to_string_lossy()),
+            sign_config.keys_directory.clone(),
+            KeyPairType::Account,
+            sign_config.disable_keygen,
+            OutputKind::Json,
+        )?,
+        SigningBackend::Env(var) => EnvSigner::new(var).keypair()?,
+        SigningBackend::Exec(_) => {
+            bail!("provider archives can't be signed by an external signer yet, use a file or environment variable key")
+        }
+    };
     let subject = extract_keypair(
         sign_config.subject.as_deref(),
         Some(&provider_path_buf.to_string_lossy()),
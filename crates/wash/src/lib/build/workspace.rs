@@ -0,0 +1,171 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use tracing::info;
+use wadm_types::{
+    CapabilityProperties, Component, ComponentProperties, Manifest, Metadata, Properties,
+    Specification, SpreadScalerProperty, Trait, TraitProperty,
+};
+
+use crate::lib::{
+    cli::CommonPackageArgs,
+    parser::{
+        link_member_wit, member_wit, workspace_build_order, ProjectConfig, TypeConfig,
+        WorkspaceConfig,
+    },
+};
+
+use super::{build_project, SignConfig};
+
+/// A member of a workspace and the artifact it was built into
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuiltMember {
+    pub name: String,
+    pub provider: bool,
+    pub artifact: PathBuf,
+}
+
+/// Build every member of a workspace with [`build_project`], each after the members whose WIT
+/// packages it imports, which are resolved from the WIT directory of those members. `signing`
+/// gives the [`SignConfig`] for each member, if it should be signed.
+///
+/// Returns the built members in the order they were built
+pub async fn build_workspace(
+    workspace: &WorkspaceConfig,
+    signing: impl Fn(&ProjectConfig) -> Option<SignConfig>,
+    package_args: &CommonPackageArgs,
+    skip_fetch: bool,
+) -> Result<Vec<BuiltMember>> {
+    let mut members = workspace.members.clone();
+    let wits = members
+        .iter()
+        .map(|m| member_wit(&m.common.wit_dir))
+        .collect::<Result<Vec<_>>>()?;
+    let names = members
+        .iter()
+        .map(|m| m.common.name.as_str())
+        .collect::<Vec<_>>();
+    let order = workspace_build_order(&names, &wits)?;
+    link_member_wit(&mut members, &wits);
+
+    let mut built = Vec::with_capacity(members.len());
+    for member in order.into_iter().map(|i| &members[i]) {
+        info!("Building workspace member [{}]", member.common.name);
+        let artifact = build_project(member, signing(member).as_ref(), package_args, skip_fetch)
+            .await
+            .with_context(|| {
+                format!("failed to build workspace member [{}]", member.common.name)
+            })?;
+        built.push(BuiltMember {
+            name: member.common.name.clone(),
+            provider: matches!(member.project_type, TypeConfig::Provider(_)),
+            artifact,
+        });
+    }
+    Ok(built)
+}
+
+/// A wadm manifest deploying every built member of a workspace from its local artifact. Links
+/// between members depend on how they are used, so they are left to be added to the manifest
+#[must_use]
+pub fn workspace_manifest(workspace: &WorkspaceConfig, built: &[BuiltMember]) -> Manifest {
+    let components = built
+        .iter()
+        .map(|member| {
+            let id = member.name.to_lowercase().replace(' ', "-");
+            let image = Some(format!("file://{}", member.artifact.display()));
+            let (properties, instances) = if member.provider {
+                let properties = CapabilityProperties {
+                    image,
+                    application: None,
+                    id: Some(id.clone()),
+                    config: Vec::new(),
+                    secrets: Vec::new(),
+                };
+                (Properties::Capability { properties }, 1)
+            } else {
+                let properties = ComponentProperties {
+                    image,
+                    application: None,
+                    id: Some(id.clone()),
+                    config: Vec::new(),
+                    secrets: Vec::new(),
+                };
+                (Properties::Component { properties }, 100)
+            };
+            Component {
+                name: id,
+                properties,
+                traits: Some(vec![Trait {
+                    trait_type: "spreadscaler".into(),
+                    properties: TraitProperty::SpreadScaler(SpreadScalerProperty {
+                        instances,
+                        spread: Vec::new(),
+                    }),
+                }]),
+            }
+        })
+        .collect();
+
+    Manifest {
+        api_version: "core.oam.dev/v1beta1".into(),
+        kind: "Application".into(),
+        metadata: Metadata {
+            name: workspace.name.clone(),
+            annotations: BTreeMap::from([(
+                "description".into(),
+                format!("Members of the {} workspace", workspace.name),
+            )]),
+            labels: BTreeMap::from([(
+                "wasmcloud.dev/generated-by".into(),
+                format!("wash-build-{}", env!("CARGO_PKG_VERSION")),
+            )]),
+        },
+        spec: Specification {
+            components,
+            policies: Vec::new(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn manifest_deploys_every_member() {
+        let workspace = WorkspaceConfig {
+            name: "petclinic".into(),
+            dir: PathBuf::from("/petclinic"),
+            members: Vec::new(),
+        };
+        let built = [
+            BuiltMember {
+                name: "Vets API".into(),
+                provider: false,
+                artifact: PathBuf::from("/petclinic/api/build/api_s.wasm"),
+            },
+            BuiltMember {
+                name: "db".into(),
+                provider: true,
+                artifact: PathBuf::from("/petclinic/db/build/db.par.gz"),
+            },
+        ];
+
+        let manifest = workspace_manifest(&workspace, &built);
+        assert_eq!(manifest.metadata.name, "petclinic");
+        let components = &manifest.spec.components;
+        assert_eq!(components.len(), 2);
+        assert_eq!(components[0].name, "vets-api");
+        assert!(matches!(
+            &components[0].properties,
+            Properties::Component { properties }
+                if properties.image.as_deref() == Some("file:///petclinic/api/build/api_s.wasm")
+        ));
+        assert!(matches!(
+            &components[1].properties,
+            Properties::Capability { properties } if properties.id.as_deref() == Some("db")
+        ));
+    }
+}
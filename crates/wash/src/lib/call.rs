@@ -0,0 +1,844 @@
+//! Typed invocation of component functions over wRPC, as done by `wash call`. The parameter and
+//! result types of a function are taken from its WIT, and values are given and returned as JSON:
+//!
+//! | WIT | JSON |
+//! | --- | --- |
+//! | `bool`, integers, floats, `string` | boolean, number, string |
+//! | `char` | string of one character |
+//! | `list<T>`, `tuple<..>` | array |
+//! | `record` | object keyed by field name |
+//! | `option<T>` | `null` for `none`, otherwise the value |
+//! | `result<T, E>` | `{"ok": value}` or `{"err": value}` |
+//! | `enum` | case name |
+//! | `variant` | case name, or `{"case": value}` for cases with a payload |
+//! | `flags` | array of the names of the set flags |
+//!
+//! Resources, streams and futures can't be passed from the CLI. When the WIT isn't at hand, it is
+//! read from the component itself, pulled from the image reference it is running from.
+//!
+//! Arguments may also be given as CBOR, which is converted to JSON first: byte strings become
+//! arrays of numbers, as taken by `list<u8>`, and tagged values become the value they tag
+
+use std::path::Path;
+
+use anyhow::{bail, ensure, Context, Result};
+use bytes::{BufMut, Bytes, BytesMut};
+use serde_json::{Map, Value};
+use wasmcloud_control_interface::HostInventory;
+use wit_parser::{Resolve, Type, TypeDefKind};
+
+use crate::lib::common::get_all_inventories;
+use crate::lib::registry::{get_oci_artifact, OciPullOptions};
+
+/// The parameter and result types of a function, along with the WIT they are defined in
+pub struct FunctionSignature {
+    resolve: Resolve,
+    pub params: Vec<(String, Type)>,
+    pub results: Vec<Type>,
+}
+
+impl FunctionSignature {
+    /// Look up function `name` of interface `instance`, e.g. `wasi:cli/run`, in a WIT file or
+    /// directory, or in the WIT of a built component
+    pub fn load(path: &Path, instance: &str, name: &str) -> Result<Self> {
+        let resolve = load_resolve(path)?;
+        Self::find(resolve, instance, name).with_context(|| {
+            format!(
+                "failed to find function [{instance}.{name}] in WIT at [{}]",
+                path.display()
+            )
+        })
+    }
+
+    /// Look up function `name` of interface `instance` in the WIT of a component running in the
+    /// lattice, pulling the component from the image it was started from with `options`
+    pub async fn fetch(
+        client: &wasmcloud_control_interface::Client,
+        component_id: &str,
+        instance: &str,
+        name: &str,
+        options: OciPullOptions,
+    ) -> Result<Self> {
+        let inventories = get_all_inventories(client).await?;
+        let image_ref = component_image_ref(component_id, &inventories).with_context(|| {
+            format!("component [{component_id}] is not running in the lattice, its WIT is needed")
+        })?;
+        let wasm = get_oci_artifact(
+            image_ref
+                .strip_prefix("file://")
+                .unwrap_or(image_ref)
+                .to_string(),
+            None,
+            options,
+        )
+        .await
+        .with_context(|| format!("failed to pull component [{image_ref}] to read its WIT"))?;
+        let resolve = decode_resolve(&wasm)
+            .with_context(|| format!("failed to decode WIT from component [{image_ref}]"))?;
+        Self::find(resolve, instance, name).with_context(|| {
+            format!("failed to find function [{instance}.{name}] in component [{image_ref}]")
+        })
+    }
+
+    /// Look up function `name` of interface `instance`. The version of the interface may be left
+    /// out of `instance`
+    pub fn find(resolve: Resolve, instance: &str, name: &str) -> Result<Self> {
+        let (_, iface) = resolve
+            .interfaces
+            .iter()
+            .find(|(id, _)| {
+                resolve.id_of(*id).is_some_and(|id| {
+                    id == instance || id.split_once('@').is_some_and(|(id, _)| id == instance)
+                })
+            })
+            .with_context(|| format!("interface [{instance}] not found"))?;
+        let func = iface
+            .functions
+            .get(name)
+            .with_context(|| format!("function [{name}] not found in interface [{instance}]"))?;
+        let params = func.params.clone();
+        let results = func.results.iter_types().copied().collect();
+        Ok(Self {
+            resolve,
+            params,
+            results,
+        })
+    }
+
+    /// Encode the arguments of a call, one value per parameter
+    pub fn encode_params(&self, args: &[Value]) -> Result<Bytes> {
+        ensure!(
+            args.len() == self.params.len(),
+            "function takes {} argument(s) ({}) but {} were given",
+            self.params.len(),
+            self.params
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            args.len()
+        );
+        let mut buf = BytesMut::new();
+        for ((name, ty), arg) in self.params.iter().zip(args) {
+            encode_value(&self.resolve, ty, arg, &mut buf)
+                .with_context(|| format!("invalid value for parameter [{name}]"))?;
+        }
+        Ok(buf.freeze())
+    }
+
+    /// A JSON array with a placeholder value for each parameter, to fill in and pass as the
+    /// arguments of a call
+    #[must_use]
+    pub fn payload_template(&self) -> Value {
+        Value::Array(
+            self.params
+                .iter()
+                .map(|(_, ty)| template_value(&self.resolve, ty))
+                .collect(),
+        )
+    }
+
+    /// Decode the results of a call, one value per result
+    pub fn decode_results(&self, mut bytes: &[u8]) -> Result<Vec<Value>> {
+        let results = self
+            .results
+            .iter()
+            .map(|ty| decode_value(&self.resolve, ty, &mut bytes))
+            .collect::<Result<Vec<_>>>()
+            .context("failed to decode results")?;
+        ensure!(
+            bytes.is_empty(),
+            "{} unexpected bytes after the results",
+            bytes.len()
+        );
+        Ok(results)
+    }
+}
+
+/// The image reference of a running component, as reported by the hosts running it
+#[must_use]
+pub fn component_image_ref<'a>(
+    component_id: &str,
+    inventories: &'a [HostInventory],
+) -> Option<&'a str> {
+    inventories
+        .iter()
+        .flat_map(HostInventory::components)
+        .find(|component| component.id() == component_id)
+        .map(|component| component.image_ref())
+}
+
+/// Read the arguments of a function from a CBOR array with one value per parameter, converted to
+/// the JSON they are encoded from
+pub fn read_cbor_args(reader: impl std::io::Read) -> Result<Vec<Value>> {
+    let value: ciborium::Value = ciborium::from_reader(reader).context("failed to parse CBOR")?;
+    match cbor_to_json(value)? {
+        Value::Array(args) => Ok(args),
+        value => bail!("expected an array with one value per parameter, found {value}"),
+    }
+}
+
+fn cbor_to_json(value: ciborium::Value) -> Result<Value> {
+    use ciborium::Value as Cbor;
+
+    Ok(match value {
+        Cbor::Null => Value::Null,
+        Cbor::Bool(b) => Value::Bool(b),
+        Cbor::Integer(n) => {
+            let n = i128::from(n);
+            match (u64::try_from(n), i64::try_from(n)) {
+                (Ok(n), _) => Value::from(n),
+                (_, Ok(n)) => Value::from(n),
+                _ => bail!("integer {n} is out of range"),
+            }
+        }
+        Cbor::Float(f) => serde_json::Number::from_f64(f)
+            .map(Value::Number)
+            .with_context(|| format!("float {f} has no JSON representation"))?,
+        Cbor::Text(s) => Value::String(s),
+        Cbor::Bytes(bytes) => Value::Array(bytes.into_iter().map(Value::from).collect()),
+        Cbor::Array(values) => Value::Array(
+            values
+                .into_iter()
+                .map(cbor_to_json)
+                .collect::<Result<_>>()?,
+        ),
+        Cbor::Map(entries) => Value::Object(
+            entries
+                .into_iter()
+                .map(|(key, value)| {
+                    let Cbor::Text(key) = key else {
+                        bail!("map keys must be strings, found {key:?}");
+                    };
+                    Ok((key, cbor_to_json(value)?))
+                })
+                .collect::<Result<_>>()?,
+        ),
+        Cbor::Tag(_, value) => cbor_to_json(*value)?,
+        value => bail!("unsupported CBOR value {value:?}"),
+    })
+}
+
+fn load_resolve(path: &Path) -> Result<Resolve> {
+    if path.extension().is_some_and(|ext| ext == "wasm") {
+        let wasm = std::fs::read(path)
+            .with_context(|| format!("failed to read component [{}]", path.display()))?;
+        return decode_resolve(&wasm)
+            .with_context(|| format!("failed to decode WIT from [{}]", path.display()));
+    }
+    let mut resolve = Resolve::default();
+    resolve
+        .push_path(path)
+        .with_context(|| format!("failed to parse WIT at [{}]", path.display()))?;
+    Ok(resolve)
+}
+
+fn decode_resolve(wasm: &[u8]) -> Result<Resolve> {
+    match wit_parser::decoding::decode(wasm)? {
+        wit_parser::decoding::DecodedWasm::Component(resolve, _)
+        | wit_parser::decoding::DecodedWasm::WitPackage(resolve, _) => Ok(resolve),
+    }
+}
+
+/// A placeholder value of a type, e.g. `0` for numbers or the first case of an enum
+fn template_value(resolve: &Resolve, ty: &Type) -> Value {
+    match ty {
+        Type::Bool => Value::from(false),
+        Type::U8
+        | Type::U16
+        | Type::U32
+        | Type::U64
+        | Type::S8
+        | Type::S16
+        | Type::S32
+        | Type::S64 => Value::from(0),
+        Type::F32 | Type::F64 => Value::from(0.0),
+        Type::Char => Value::from("a"),
+        Type::String => Value::from(""),
+        Type::Id(id) => match &resolve.types[*id].kind {
+            TypeDefKind::Type(ty) => template_value(resolve, ty),
+            TypeDefKind::Record(record) => Value::Object(
+                record
+                    .fields
+                    .iter()
+                    .map(|field| (field.name.clone(), template_value(resolve, &field.ty)))
+                    .collect(),
+            ),
+            TypeDefKind::Tuple(tuple) => Value::Array(
+                tuple
+                    .types
+                    .iter()
+                    .map(|ty| template_value(resolve, ty))
+                    .collect(),
+            ),
+            TypeDefKind::Result(result) => Value::Object(Map::from_iter([(
+                "ok".to_string(),
+                result
+                    .ok
+                    .map_or(Value::Null, |ty| template_value(resolve, &ty)),
+            )])),
+            TypeDefKind::Enum(enum_) => enum_
+                .cases
+                .first()
+                .map_or(Value::Null, |case| Value::from(case.name.clone())),
+            TypeDefKind::Variant(variant) => {
+                variant
+                    .cases
+                    .first()
+                    .map_or(Value::Null, |case| match case.ty {
+                        Some(ty) => Value::Object(Map::from_iter([(
+                            case.name.clone(),
+                            template_value(resolve, &ty),
+                        )])),
+                        None => Value::from(case.name.clone()),
+                    })
+            }
+            TypeDefKind::List(_) | TypeDefKind::Flags(_) => Value::Array(Vec::new()),
+            _ => Value::Null,
+        },
+    }
+}
+
+fn encode_value(resolve: &Resolve, ty: &Type, value: &Value, buf: &mut BytesMut) -> Result<()> {
+    match ty {
+        Type::Bool => buf.put_u8(value.as_bool().context("expected a boolean")?.into()),
+        Type::U8 => buf.put_u8(int(value)?),
+        Type::S8 => buf.put_i8(int(value)?),
+        Type::U16 => write_unsigned(buf, int::<u16>(value)?.into()),
+        Type::U32 => write_unsigned(buf, int::<u32>(value)?.into()),
+        Type::U64 => write_unsigned(buf, int(value)?),
+        Type::S16 => write_signed(buf, int::<i16>(value)?.into()),
+        Type::S32 => write_signed(buf, int::<i32>(value)?.into()),
+        Type::S64 => write_signed(buf, int(value)?),
+        Type::F32 => buf.put_f32_le(value.as_f64().context("expected a number")? as f32),
+        Type::F64 => buf.put_f64_le(value.as_f64().context("expected a number")?),
+        Type::Char => {
+            let s = value.as_str().context("expected a string")?;
+            let mut chars = s.chars();
+            let (Some(c), None) = (chars.next(), chars.next()) else {
+                bail!("expected a single character, got [{s}]");
+            };
+            buf.put_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+        }
+        Type::String => {
+            let s = value.as_str().context("expected a string")?;
+            write_len(buf, s.len())?;
+            buf.put_slice(s.as_bytes());
+        }
+        Type::Id(id) => match &resolve.types[*id].kind {
+            TypeDefKind::Type(ty) => encode_value(resolve, ty, value, buf)?,
+            TypeDefKind::Record(record) => {
+                let fields = value.as_object().context("expected an object")?;
+                for field in &record.fields {
+                    let value = fields
+                        .get(&field.name)
+                        .with_context(|| format!("missing field [{}]", field.name))?;
+                    encode_value(resolve, &field.ty, value, buf)
+                        .with_context(|| format!("invalid value for field [{}]", field.name))?;
+                }
+            }
+            TypeDefKind::Tuple(tuple) => {
+                let items = value.as_array().context("expected an array")?;
+                ensure!(
+                    items.len() == tuple.types.len(),
+                    "expected {} items, got {}",
+                    tuple.types.len(),
+                    items.len()
+                );
+                for (ty, item) in tuple.types.iter().zip(items) {
+                    encode_value(resolve, ty, item, buf)?;
+                }
+            }
+            TypeDefKind::List(ty) => {
+                let items = value.as_array().context("expected an array")?;
+                write_len(buf, items.len())?;
+                for item in items {
+                    encode_value(resolve, ty, item, buf)?;
+                }
+            }
+            TypeDefKind::Option(ty) => {
+                if value.is_null() {
+                    buf.put_u8(0);
+                } else {
+                    buf.put_u8(1);
+                    encode_value(resolve, ty, value, buf)?;
+                }
+            }
+            TypeDefKind::Result(result) => {
+                let (case, payload) = single_entry(value)
+                    .context("expected an object with either an `ok` or an `err` field")?;
+                let ty = match case {
+                    "ok" => {
+                        buf.put_u8(0);
+                        result.ok
+                    }
+                    "err" => {
+                        buf.put_u8(1);
+                        result.err
+                    }
+                    _ => bail!("expected an `ok` or an `err` field, got [{case}]"),
+                };
+                if let Some(ty) = ty {
+                    encode_value(resolve, &ty, payload, buf)?;
+                }
+            }
+            TypeDefKind::Enum(enum_) => {
+                let name = value.as_str().context("expected a case name")?;
+                let i = enum_
+                    .cases
+                    .iter()
+                    .position(|case| case.name == name)
+                    .with_context(|| format!("unknown case [{name}]"))?;
+                write_len(buf, i)?;
+            }
+            TypeDefKind::Variant(variant) => {
+                let (name, payload) = match value.as_str() {
+                    Some(name) => (name, None),
+                    None => single_entry(value)
+                        .map(|(name, payload)| (name, Some(payload)))
+                        .context("expected a case name or an object with a single case")?,
+                };
+                let (i, case) = variant
+                    .cases
+                    .iter()
+                    .enumerate()
+                    .find(|(_, case)| case.name == name)
+                    .with_context(|| format!("unknown case [{name}]"))?;
+                write_len(buf, i)?;
+                match (case.ty, payload) {
+                    (Some(ty), Some(payload)) => encode_value(resolve, &ty, payload, buf)
+                        .with_context(|| format!("invalid value for case [{name}]"))?,
+                    (Some(_), None) => bail!("case [{name}] takes a value"),
+                    (None, payload) => ensure!(
+                        payload.is_none_or(Value::is_null),
+                        "case [{name}] doesn't take a value"
+                    ),
+                }
+            }
+            TypeDefKind::Flags(flags) => {
+                let mut bits = vec![0u8; flags.flags.len().div_ceil(8)];
+                for name in value
+                    .as_array()
+                    .context("expected an array of flag names")?
+                {
+                    let name = name.as_str().context("expected a flag name")?;
+                    let i = flags
+                        .flags
+                        .iter()
+                        .position(|flag| flag.name == name)
+                        .with_context(|| format!("unknown flag [{name}]"))?;
+                    bits[i / 8] |= 1 << (i % 8);
+                }
+                buf.put_slice(&bits);
+            }
+            kind => bail!("values of type `{}` can't be passed", kind.as_str()),
+        },
+    }
+    Ok(())
+}
+
+fn decode_value(resolve: &Resolve, ty: &Type, bytes: &mut &[u8]) -> Result<Value> {
+    Ok(match ty {
+        Type::Bool => Value::from(take(bytes, 1)?[0] != 0),
+        Type::U8 => Value::from(take(bytes, 1)?[0]),
+        Type::S8 => Value::from(i8::from_le_bytes([take(bytes, 1)?[0]])),
+        Type::U16 | Type::U32 | Type::U64 => Value::from(read_unsigned(bytes)?),
+        Type::S16 | Type::S32 | Type::S64 => Value::from(read_signed(bytes)?),
+        Type::F32 => Value::from(f32::from_le_bytes(take(bytes, 4)?.try_into()?)),
+        Type::F64 => Value::from(f64::from_le_bytes(take(bytes, 8)?.try_into()?)),
+        Type::Char => {
+            let len = match bytes.first().context("unexpected end of results")? {
+                0x00..=0x7f => 1,
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                _ => 4,
+            };
+            Value::from(std::str::from_utf8(take(bytes, len)?).context("invalid char")?)
+        }
+        Type::String => {
+            let len = read_len(bytes)?;
+            Value::from(std::str::from_utf8(take(bytes, len)?).context("invalid string")?)
+        }
+        Type::Id(id) => match &resolve.types[*id].kind {
+            TypeDefKind::Type(ty) => decode_value(resolve, ty, bytes)?,
+            TypeDefKind::Record(record) => Value::Object(
+                record
+                    .fields
+                    .iter()
+                    .map(|field| Ok((field.name.clone(), decode_value(resolve, &field.ty, bytes)?)))
+                    .collect::<Result<Map<_, _>>>()?,
+            ),
+            TypeDefKind::Tuple(tuple) => Value::Array(
+                tuple
+                    .types
+                    .iter()
+                    .map(|ty| decode_value(resolve, ty, bytes))
+                    .collect::<Result<_>>()?,
+            ),
+            TypeDefKind::List(ty) => {
+                let len = read_len(bytes)?;
+                Value::Array(
+                    (0..len)
+                        .map(|_| decode_value(resolve, ty, bytes))
+                        .collect::<Result<_>>()?,
+                )
+            }
+            TypeDefKind::Option(ty) => match take(bytes, 1)?[0] {
+                0 => Value::Null,
+                1 => decode_value(resolve, ty, bytes)?,
+                tag => bail!("invalid option tag {tag}"),
+            },
+            TypeDefKind::Result(result) => {
+                let (case, ty) = match take(bytes, 1)?[0] {
+                    0 => ("ok", result.ok),
+                    1 => ("err", result.err),
+                    tag => bail!("invalid result tag {tag}"),
+                };
+                let payload = match ty {
+                    Some(ty) => decode_value(resolve, &ty, bytes)?,
+                    None => Value::Null,
+                };
+                Value::Object(Map::from_iter([(case.to_string(), payload)]))
+            }
+            TypeDefKind::Enum(enum_) => {
+                let i = read_len(bytes)?;
+                let case = enum_.cases.get(i).context("invalid enum case")?;
+                Value::from(case.name.clone())
+            }
+            TypeDefKind::Variant(variant) => {
+                let i = read_len(bytes)?;
+                let case = variant.cases.get(i).context("invalid variant case")?;
+                match case.ty {
+                    Some(ty) => Value::Object(Map::from_iter([(
+                        case.name.clone(),
+                        decode_value(resolve, &ty, bytes)?,
+                    )])),
+                    None => Value::from(case.name.clone()),
+                }
+            }
+            TypeDefKind::Flags(flags) => {
+                let bits = take(bytes, flags.flags.len().div_ceil(8))?;
+                Value::Array(
+                    flags
+                        .flags
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, _)| bits[i / 8] & (1 << (i % 8)) != 0)
+                        .map(|(_, flag)| Value::from(flag.name.clone()))
+                        .collect(),
+                )
+            }
+            kind => bail!("values of type `{}` can't be returned", kind.as_str()),
+        },
+    })
+}
+
+/// The single field of an object, e.g. the case of a `result` or `variant`
+fn single_entry(value: &Value) -> Option<(&str, &Value)> {
+    let object = value.as_object()?;
+    let mut entries = object.iter();
+    match (entries.next(), entries.next()) {
+        (Some((key, value)), None) => Some((key.as_str(), value)),
+        _ => None,
+    }
+}
+
+fn int<T: TryFrom<u64> + TryFrom<i64>>(value: &Value) -> Result<T> {
+    value
+        .as_u64()
+        .map(|n| T::try_from(n).ok())
+        .or_else(|| value.as_i64().map(|n| T::try_from(n).ok()))
+        .context("expected an integer")?
+        .context("integer out of range")
+}
+
+fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+    ensure!(bytes.len() >= len, "unexpected end of results");
+    let (taken, rest) = bytes.split_at(len);
+    *bytes = rest;
+    Ok(taken)
+}
+
+/// Lengths and discriminants are encoded as unsigned LEB128 `u32`s
+fn write_len(buf: &mut BytesMut, len: usize) -> Result<()> {
+    let len = u32::try_from(len).context("length does not fit in u32")?;
+    write_unsigned(buf, len.into());
+    Ok(())
+}
+
+fn read_len(bytes: &mut &[u8]) -> Result<usize> {
+    let len = u32::try_from(read_unsigned(bytes)?).context("length does not fit in u32")?;
+    Ok(usize::try_from(len)?)
+}
+
+fn write_unsigned(buf: &mut BytesMut, mut n: u64) {
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            buf.put_u8(byte);
+            return;
+        }
+        buf.put_u8(byte | 0x80);
+    }
+}
+
+fn write_signed(buf: &mut BytesMut, mut n: i64) {
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+        if (n == 0 && byte & 0x40 == 0) || (n == -1 && byte & 0x40 != 0) {
+            buf.put_u8(byte);
+            return;
+        }
+        buf.put_u8(byte | 0x80);
+    }
+}
+
+fn read_unsigned(bytes: &mut &[u8]) -> Result<u64> {
+    let mut n = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = take(bytes, 1)?[0];
+        n |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(n);
+        }
+    }
+    bail!("integer is too long")
+}
+
+fn read_signed(bytes: &mut &[u8]) -> Result<i64> {
+    let mut n = 0;
+    let mut shift = 0;
+    loop {
+        let byte = take(bytes, 1)?[0];
+        n |= i64::from(byte & 0x7f) << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            if shift < 64 && byte & 0x40 != 0 {
+                n |= -1 << shift;
+            }
+            return Ok(n);
+        }
+        ensure!(shift < 64, "integer is too long");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    const WIT: &str = r#"
+        package wasmcloud:test@0.1.0;
+
+        interface shapes {
+            enum color { red, green, blue }
+            flags features { round, shiny }
+            record shape {
+                name: string,
+                sides: u32,
+                color: color,
+                offset: s64,
+                features: features,
+            }
+            variant size { small, custom(tuple<f64, f64>) }
+
+            describe: func(shape: shape, size: size, tag: option<char>) -> result<list<string>, string>;
+        }
+    "#;
+
+    fn signature() -> FunctionSignature {
+        let mut resolve = Resolve::default();
+        resolve
+            .push_str("shapes.wit", WIT)
+            .expect("failed to parse WIT");
+        FunctionSignature::find(resolve, "wasmcloud:test/shapes", "describe")
+            .expect("failed to find function")
+    }
+
+    #[test]
+    fn values_round_trip_through_their_wit_types() {
+        let signature = signature();
+        assert_eq!(signature.params.len(), 3);
+
+        let args = [
+            json!({
+                "name": "triangle",
+                "sides": 3,
+                "color": "green",
+                "offset": -200,
+                "features": ["shiny"],
+            }),
+            json!({"custom": [1.5, 2.0]}),
+            json!("▲"),
+        ];
+        let encoded = signature.encode_params(&args).unwrap();
+
+        // Decode the parameters by treating them as the results of a function returning them
+        let decoded = FunctionSignature {
+            results: signature.params.iter().map(|(_, ty)| *ty).collect(),
+            params: Vec::new(),
+            resolve: signature.resolve,
+        }
+        .decode_results(&encoded)
+        .unwrap();
+        assert_eq!(decoded, args);
+    }
+
+    #[test]
+    fn cbor_args_are_encoded_like_json_args() {
+        let args = ciborium::Value::Array(vec![
+            ciborium::Value::Map(vec![
+                ("name".into(), "triangle".into()),
+                ("sides".into(), 3.into()),
+                ("color".into(), "green".into()),
+                ("offset".into(), (-200).into()),
+                (
+                    "features".into(),
+                    ciborium::Value::Array(vec!["shiny".into()]),
+                ),
+            ]),
+            ciborium::Value::Map(vec![(
+                "custom".into(),
+                ciborium::Value::Array(vec![1.5.into(), 2.0.into()]),
+            )]),
+            ciborium::Value::Tag(0, Box::new("▲".into())),
+        ]);
+        let mut cbor = Vec::new();
+        ciborium::into_writer(&args, &mut cbor).unwrap();
+
+        let args = read_cbor_args(cbor.as_slice()).unwrap();
+        assert_eq!(
+            args,
+            [
+                json!({
+                    "name": "triangle",
+                    "sides": 3,
+                    "color": "green",
+                    "offset": -200,
+                    "features": ["shiny"],
+                }),
+                json!({"custom": [1.5, 2.0]}),
+                json!("▲"),
+            ]
+        );
+        signature().encode_params(&args).unwrap();
+
+        let mut cbor = Vec::new();
+        ciborium::into_writer(
+            &ciborium::Value::Array(vec![ciborium::Value::Bytes(vec![1, 2])]),
+            &mut cbor,
+        )
+        .unwrap();
+        assert_eq!(read_cbor_args(cbor.as_slice()).unwrap(), [json!([1, 2])]);
+
+        let mut cbor = Vec::new();
+        ciborium::into_writer(&ciborium::Value::from("not an array"), &mut cbor).unwrap();
+        assert!(read_cbor_args(cbor.as_slice()).is_err());
+    }
+
+    #[test]
+    fn payload_template_can_be_sent() {
+        let signature = signature();
+        let template = signature.payload_template();
+        assert_eq!(
+            template,
+            json!([
+                {
+                    "name": "",
+                    "sides": 0,
+                    "color": "red",
+                    "offset": 0,
+                    "features": [],
+                },
+                "small",
+                null,
+            ])
+        );
+        signature
+            .encode_params(template.as_array().unwrap())
+            .expect("template should be a valid payload");
+    }
+
+    #[test]
+    fn component_image_ref_is_read_from_inventories() {
+        let inventory = HostInventory::builder()
+            .host_id("host1".into())
+            .friendly_name("quiet-dawn".into())
+            .version("1.0.0".into())
+            .uptime_human("t".into())
+            .uptime_seconds(100)
+            .components(vec![
+                wasmcloud_control_interface::ComponentDescription::builder()
+                    .id("shapes".into())
+                    .image_ref("ghcr.io/wasmcloud/shapes:0.1.0".into())
+                    .max_instances(1)
+                    .build()
+                    .expect("should build component"),
+            ])
+            .build()
+            .expect("should build inventory");
+        let inventories = [inventory];
+        assert_eq!(
+            component_image_ref("shapes", &inventories),
+            Some("ghcr.io/wasmcloud/shapes:0.1.0")
+        );
+        assert_eq!(component_image_ref("circles", &inventories), None);
+    }
+
+    #[test]
+    fn results_are_decoded() {
+        let signature = signature();
+        let mut buf = BytesMut::new();
+        buf.put_u8(0);
+        write_len(&mut buf, 2).unwrap();
+        for s in ["a", "bc"] {
+            write_len(&mut buf, s.len()).unwrap();
+            buf.put_slice(s.as_bytes());
+        }
+        assert_eq!(
+            signature.decode_results(&buf).unwrap(),
+            [json!({"ok": ["a", "bc"]})]
+        );
+        assert!(signature.decode_results(&buf[..3]).is_err());
+    }
+
+    #[test]
+    fn invalid_arguments_are_rejected() {
+        let signature = signature();
+        let shape = json!({
+            "name": "square",
+            "sides": 4,
+            "color": "blue",
+            "offset": 0,
+            "features": [],
+        });
+
+        let err = signature
+            .encode_params(std::slice::from_ref(&shape))
+            .unwrap_err();
+        assert!(err.to_string().contains("(shape, size, tag)"), "{err}");
+
+        let mut negative_sides = shape.clone();
+        negative_sides["sides"] = json!(-4);
+        assert!(signature
+            .encode_params(&[negative_sides, json!("small"), json!(null)])
+            .is_err());
+
+        assert!(signature
+            .encode_params(&[shape.clone(), json!("medium"), json!(null)])
+            .is_err());
+        assert!(signature
+            .encode_params(&[shape.clone(), json!("custom"), json!(null)])
+            .is_err());
+        assert!(signature
+            .encode_params(&[shape, json!("small"), json!("ab")])
+            .is_err());
+
+        let mut buf = BytesMut::new();
+        write_signed(&mut buf, -200);
+        assert_eq!(read_signed(&mut &buf[..]).unwrap(), -200);
+    }
+}
@@ -116,6 +116,7 @@ pub async fn handle_replay_command(cmd: CaptureReplayCommand) -> Result<CommandO
                 to,
                 operation,
                 message: ObservedMessage::parse(msg.payload.to_vec()),
+                reply: None,
             },
             msg.published,
         ))
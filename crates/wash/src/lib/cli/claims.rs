@@ -1,18 +1,22 @@
-use anyhow::{bail, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use clap::{Args, Parser, Subcommand};
 use nkeys::{KeyPair, KeyPairType};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::{
     collections::{BTreeSet, HashMap},
+    fmt,
     fs::{self, File},
-    io::Read,
+    io::{Read, Write},
     path::{Path, PathBuf},
+    process::{Command, Stdio},
+    str::FromStr,
+    sync::OnceLock,
 };
 use tracing::warn;
 use wascap::{
-    jwt::{Account, CapabilityProvider, Claims, Component, Operator},
-    wasm::{days_from_now_to_jwt_time, sign_buffer_with_claims},
+    jwt::{Account, BuildMetadata, CapabilityProvider, Claims, Component, Operator},
+    wasm::{days_from_now_to_jwt_time, embed_claims_with},
 };
 
 use super::{extract_keypair, get::GetClaimsCommand, CommandOutput, OutputKind};
@@ -100,6 +104,225 @@ pub struct SignCommand {
 
     #[clap(flatten)]
     pub metadata: ComponentMetadata,
+
+    /// Where the issuer key signing the component is held: `file` for a seed given with --issuer
+    /// or found in the keys directory, `env:VAR` for a seed in the environment variable VAR, or
+    /// `exec:COMMAND` for an external signer. The signer is run as `COMMAND public-key` to print
+    /// the public key of the issuer and as `COMMAND sign` to write the raw Ed25519 signature of
+    /// its stdin to stdout
+    #[clap(
+        long = "sign-with",
+        env = "WASH_SIGN_WITH",
+        default_value_t = SigningBackend::File
+    )]
+    pub sign_with: SigningBackend,
+
+    /// Metadata about how the component was built, embedded into its claims
+    #[clap(skip)]
+    pub build_metadata: Option<BuildMetadata>,
+}
+
+/// Signs claims as their issuer. The key of the issuer doesn't have to be held by wash, so claims
+/// can be signed by keys kept in external systems
+pub trait ClaimsSigner {
+    /// The public key of the issuer
+    fn public_key(&self) -> Result<String>;
+
+    /// Sign `data` with the key of the issuer, returning the Ed25519 signature
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// Seeds given on the command line or stored in key files
+impl ClaimsSigner for KeyPair {
+    fn public_key(&self) -> Result<String> {
+        Ok(KeyPair::public_key(self))
+    }
+
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>> {
+        KeyPair::sign(self, data).context("failed to sign with key")
+    }
+}
+
+/// Signs with a seed injected into an environment variable, e.g. a CI secret, so the seed never
+/// has to be written to disk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvSigner {
+    var: String,
+}
+
+impl EnvSigner {
+    #[must_use]
+    pub fn new(var: impl Into<String>) -> Self {
+        Self { var: var.into() }
+    }
+
+    /// The key pair of the seed in the environment variable
+    pub fn keypair(&self) -> Result<KeyPair> {
+        self.keypair_from(std::env::var(&self.var).ok())
+    }
+
+    /// The key pair of `seed`, the value of the environment variable if it is set
+    fn keypair_from(&self, seed: Option<String>) -> Result<KeyPair> {
+        let seed =
+            seed.with_context(|| format!("signing seed variable [{}] is not set", self.var))?;
+        KeyPair::from_seed(seed.trim())
+            .with_context(|| format!("signing seed variable [{}] is not a valid seed", self.var))
+    }
+}
+
+impl ClaimsSigner for EnvSigner {
+    fn public_key(&self) -> Result<String> {
+        Ok(self.keypair()?.public_key())
+    }
+
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>> {
+        ClaimsSigner::sign(&self.keypair()?, data)
+    }
+}
+
+/// Signs by running an external signer, e.g. a wrapper around a key management service or a
+/// hardware key. The command is run with the argument `public-key` to print the public key of the
+/// issuer, and with the argument `sign` to sign the data it is given on stdin, writing the raw
+/// 64 byte Ed25519 signature to stdout. Signatures are verified before they are used, and the
+/// public key is only asked for once
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecSigner {
+    command: String,
+    public_key: OnceLock<String>,
+}
+
+impl ExecSigner {
+    #[must_use]
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+            public_key: OnceLock::new(),
+        }
+    }
+
+    fn run(&self, operation: &str, input: &[u8]) -> Result<Vec<u8>> {
+        let mut parts = self.command.split_ascii_whitespace();
+        let program = parts.next().context("signer command is empty")?;
+        let mut child = Command::new(program)
+            .args(parts)
+            .arg(operation)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("failed to run signer [{}]", self.command))?;
+        // Write from another thread while reading the output, so a signer that writes before it
+        // has read everything can't fill its pipes and block both
+        let mut stdin = child
+            .stdin
+            .take()
+            .context("failed to open stdin of signer")?;
+        let input = input.to_vec();
+        let writer = std::thread::spawn(move || stdin.write_all(&input));
+        let output = child
+            .wait_with_output()
+            .with_context(|| format!("failed to run signer [{}]", self.command))?;
+        writer
+            .join()
+            .map_err(|_| anyhow!("failed to write to signer"))?
+            .context("failed to write to signer")?;
+        if !output.status.success() {
+            bail!(
+                "signer [{}] failed to {operation}: {}",
+                self.command,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(output.stdout)
+    }
+}
+
+impl ClaimsSigner for ExecSigner {
+    fn public_key(&self) -> Result<String> {
+        if let Some(public_key) = self.public_key.get() {
+            return Ok(public_key.clone());
+        }
+        let output = self.run("public-key", &[])?;
+        let public_key = String::from_utf8(output)
+            .context("signer printed an invalid public key")?
+            .trim()
+            .to_string();
+        KeyPair::from_public_key(&public_key)
+            .with_context(|| format!("signer printed an invalid public key [{public_key}]"))?;
+        Ok(self.public_key.get_or_init(|| public_key).clone())
+    }
+
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let signature = self.run("sign", data)?;
+        KeyPair::from_public_key(&self.public_key()?)?
+            .verify(data, &signature)
+            .context("signer returned a signature that doesn't match its public key")?;
+        Ok(signature)
+    }
+}
+
+/// The signer of the issuer held by `sign_with`. For [`SigningBackend::File`], the issuer is the
+/// seed or seed file given as `issuer`, or the account key found in (or generated into) the keys
+/// `directory`, see [`extract_keypair`]
+pub fn issuer_signer(
+    sign_with: &SigningBackend,
+    issuer: Option<&str>,
+    module_path: Option<&str>,
+    directory: Option<PathBuf>,
+    disable_keygen: bool,
+    output_kind: OutputKind,
+) -> Result<Box<dyn ClaimsSigner>> {
+    Ok(match sign_with {
+        SigningBackend::File => Box::new(extract_keypair(
+            issuer,
+            module_path,
+            directory,
+            KeyPairType::Account,
+            disable_keygen,
+            output_kind,
+        )?),
+        SigningBackend::Env(var) => Box::new(EnvSigner::new(var)),
+        SigningBackend::Exec(command) => Box::new(ExecSigner::new(command)),
+    })
+}
+
+/// Where the key of the issuer signing an artifact is held
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum SigningBackend {
+    /// A seed given with `--issuer` or found in the keys directory
+    #[default]
+    File,
+    /// A seed in the given environment variable
+    Env(String),
+    /// An external signer run as the given command, see [`ExecSigner`]
+    Exec(String),
+}
+
+impl FromStr for SigningBackend {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.split_once(':') {
+            None if s == "file" => Ok(Self::File),
+            Some(("env", var)) if !var.is_empty() => Ok(Self::Env(var.to_string())),
+            Some(("exec", command)) if !command.trim().is_empty() => {
+                Ok(Self::Exec(command.to_string()))
+            }
+            _ => {
+                bail!("invalid signing backend [{s}], expected `file`, `env:VAR` or `exec:COMMAND`")
+            }
+        }
+    }
+}
+
+impl fmt::Display for SigningBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File => write!(f, "file"),
+            Self::Env(var) => write!(f, "env:{var}"),
+            Self::Exec(command) => write!(f, "exec:{command}"),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Subcommand)]
@@ -341,6 +564,9 @@ impl From<InspectCommand> for inspect::InspectCliCommand {
             insecure: cmd.insecure,
             insecure_skip_tls_verify: cmd.insecure_skip_tls_verify,
             no_cache: cmd.no_cache,
+            interfaces: false,
+            verify_build: None,
+            check_compat: None,
         }
     }
 }
@@ -600,31 +826,35 @@ pub fn sign_file(cmd: SignCommand, output_kind: OutputKind) -> Result<CommandOut
     let mut buf = Vec::new();
     sfile.read_to_end(&mut buf).unwrap();
 
-    let issuer = extract_keypair(
+    let issuer = issuer_signer(
+        &cmd.sign_with,
         cmd.metadata.issuer.as_deref(),
         Some(&cmd.source),
         cmd.metadata.common.directory.clone(),
-        KeyPairType::Account,
-        cmd.metadata.common.disable_keygen,
-        output_kind,
-    )?;
-    let subject = extract_keypair(
-        cmd.metadata.subject.as_deref(),
-        Some(&cmd.source),
-        cmd.metadata.common.directory.clone(),
-        KeyPairType::Module,
         cmd.metadata.common.disable_keygen,
         output_kind,
     )?;
+    // Only the public key of the subject goes into the claims, so it can be given as one
+    let subject = match cmd.metadata.subject.as_deref() {
+        Some(key) if KeyPair::from_public_key(key).is_ok() => key.to_string(),
+        subject => extract_keypair(
+            subject,
+            Some(&cmd.source),
+            cmd.metadata.common.directory.clone(),
+            KeyPairType::Module,
+            cmd.metadata.common.disable_keygen,
+            output_kind,
+        )?
+        .public_key(),
+    };
 
-    let signed = sign_buffer_with_claims(
+    let mut claims = Claims::<Component>::with_dates(
         cmd.metadata.name.context("component name is required")?,
-        &buf,
-        &subject,
-        &issuer,
-        cmd.metadata.common.expires_in_days,
-        cmd.metadata.common.not_before_days,
-        cmd.metadata.tags.clone(),
+        issuer.public_key()?,
+        subject,
+        Some(cmd.metadata.tags.clone()),
+        days_from_now_to_jwt_time(cmd.metadata.common.not_before_days),
+        days_from_now_to_jwt_time(cmd.metadata.common.expires_in_days),
         false,
         Some(
             cmd.metadata
@@ -633,7 +863,13 @@ pub fn sign_file(cmd: SignCommand, output_kind: OutputKind) -> Result<CommandOut
         ),
         Some(cmd.metadata.ver.context("component version is required")?),
         sanitize_alias(cmd.metadata.call_alias)?,
-    )?;
+    );
+    if let Some(component) = claims.metadata.as_mut() {
+        component.build = cmd.build_metadata;
+    }
+    let signed = embed_claims_with(&buf, &claims, |data| {
+        issuer.sign(data).map_err(|e| format!("{e:#}"))
+    })?;
 
     let destination = cmd.destination.unwrap_or_else(|| {
         let source = Path::new(&cmd.source);
@@ -688,7 +924,7 @@ fn sanitize_alias(call_alias: Option<String>) -> Result<Option<String>> {
 
 /// Retrieve claims from a given wasmCloud instance
 pub async fn get_claims(
-    GetClaimsCommand { opts }: GetClaimsCommand,
+    GetClaimsCommand { opts, .. }: GetClaimsCommand,
 ) -> Result<Vec<HashMap<String, String>>> {
     let wco: WashConnectionOptions = opts.try_into()?;
     let client = wco.into_ctl_client(None).await?;
@@ -724,6 +960,121 @@ mod test {
     const HELLO_WORLD_SHA: &str =
         "sha256:079275a324c0fcd0c201878f0c158120c4984472215ec3f64eb91ba9ee139f72";
 
+    #[test]
+    fn signing_backends_are_parsed() {
+        assert_eq!(
+            "file".parse::<SigningBackend>().unwrap(),
+            SigningBackend::File
+        );
+        assert_eq!(
+            "env:ISSUER_SEED".parse::<SigningBackend>().unwrap(),
+            SigningBackend::Env("ISSUER_SEED".into())
+        );
+        assert_eq!(
+            "exec:kms-signer --key wasmcloud"
+                .parse::<SigningBackend>()
+                .unwrap(),
+            SigningBackend::Exec("kms-signer --key wasmcloud".into())
+        );
+        for invalid in ["", "env:", "exec: ", "vault:key"] {
+            assert!(invalid.parse::<SigningBackend>().is_err());
+        }
+        let backend = SigningBackend::Exec("kms-signer sign".into());
+        assert_eq!(
+            backend.to_string().parse::<SigningBackend>().unwrap(),
+            backend
+        );
+    }
+
+    #[test]
+    fn components_are_signed_with_build_metadata() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let source = dir.path().join("component.wasm");
+        fs::write(&source, wat::parse_str("(module)")?)?;
+        let issuer = KeyPair::new_account();
+        let subject = KeyPair::new_module();
+
+        sign_file(
+            SignCommand {
+                source: source.to_string_lossy().to_string(),
+                destination: None,
+                metadata: ComponentMetadata {
+                    name: Some("signed".into()),
+                    rev: Some(1),
+                    ver: Some("0.1.0".into()),
+                    issuer: Some(issuer.seed()?),
+                    subject: Some(subject.public_key()),
+                    ..Default::default()
+                },
+                sign_with: SigningBackend::File,
+                build_metadata: Some(BuildMetadata {
+                    source_revision: Some("85704ed".into()),
+                    ..Default::default()
+                }),
+            },
+            OutputKind::Json,
+        )?;
+
+        let signed = fs::read(dir.path().join("component_s.wasm"))?;
+        let token = wascap::wasm::extract_claims(signed)?.context("missing claims")?;
+        assert_eq!(token.claims.issuer, issuer.public_key());
+        assert_eq!(token.claims.subject, subject.public_key());
+        assert_eq!(
+            token.claims.metadata.and_then(|m| m.build?.source_revision),
+            Some("85704ed".to_string())
+        );
+        assert!(wascap::jwt::validate_token::<Component>(&token.jwt)?.signature_valid);
+        Ok(())
+    }
+
+    #[test]
+    fn seeds_from_the_environment_are_validated() -> Result<()> {
+        let signer = EnvSigner::new("ISSUER_SEED");
+        let issuer = KeyPair::new_account();
+        assert_eq!(
+            signer
+                .keypair_from(Some(format!("{}\n", issuer.seed()?)))?
+                .public_key(),
+            issuer.public_key()
+        );
+        let err = signer
+            .keypair_from(None)
+            .expect_err("a missing seed should fail");
+        assert!(format!("{err:#}").contains("is not set"));
+        let err = signer
+            .keypair_from(Some("SAbogus".into()))
+            .expect_err("an invalid seed should fail");
+        assert!(format!("{err:#}").contains("not a valid seed"));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn signatures_from_external_signers_are_verified() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let issuer = KeyPair::new_account();
+        let script = dir.path().join("signer.sh");
+        let calls = dir.path().join("calls");
+        // The signer writes more than a pipe holds before it reads what it signs
+        fs::write(
+            &script,
+            format!(
+                "echo $1 >> {}; case $1 in public-key) echo {} ;; sign) head -c 100000 /dev/zero; cat > /dev/null ;; *) exit 1 ;; esac",
+                calls.display(),
+                issuer.public_key()
+            ),
+        )?;
+        let signer = ExecSigner::new(format!("sh {}", script.display()));
+
+        assert_eq!(signer.public_key()?, issuer.public_key());
+        let err = signer
+            .sign(&[0; 1 << 20])
+            .expect_err("bogus signature should be rejected");
+        assert!(format!("{err:#}").contains("doesn't match"));
+        assert_eq!(fs::read_to_string(&calls)?, "public-key\nsign\n");
+        Ok(())
+    }
+
     #[test]
     fn test_claims_sanitize_alias() {
         const VALID_ALPHANUMERIC: &str = "abc123";
@@ -909,8 +1260,12 @@ mod test {
                 source,
                 destination,
                 metadata,
+                sign_with,
+                build_metadata,
             }) => {
                 assert_eq!(source, LOCAL_WASM);
+                assert_eq!(sign_with, SigningBackend::File);
+                assert!(build_metadata.is_none());
                 assert_eq!(destination.unwrap(), "./mycomponent_s.wasm");
                 assert_eq!(metadata.common.directory.unwrap(), PathBuf::from("./dir"));
                 assert_eq!(metadata.common.expires_in_days.unwrap(), 3);
@@ -957,8 +1312,12 @@ mod test {
                 source,
                 destination,
                 metadata,
+                sign_with,
+                build_metadata,
             }) => {
                 assert_eq!(source, LOCAL_WASM);
+                assert_eq!(sign_with, SigningBackend::File);
+                assert!(build_metadata.is_none());
                 assert_eq!(destination.unwrap(), "./mycomponent_s.wasm");
                 assert_eq!(metadata.common.directory.unwrap(), PathBuf::from("./dir"));
                 assert_eq!(metadata.common.expires_in_days.unwrap(), 3);
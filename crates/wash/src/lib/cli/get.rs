@@ -4,10 +4,14 @@ use crate::lib::{
     common::{boxed_err_to_anyhow, get_all_inventories},
     config::WashConnectionOptions,
     id::ServerId,
+    provider::{find_providers_serving, InterfaceQuery, ServingProvider},
+    wait::{record_events, EventFilter, START_AND_SCALE_EVENTS},
 };
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use clap::Parser;
-use wasmcloud_control_interface::{Host, HostInventory};
+use cloudevents::Event;
+use tokio::sync::mpsc::Receiver;
+use wasmcloud_control_interface::{ComponentDescription, Host, HostInventory, ProviderDescription};
 
 use super::CliConnectionOpts;
 
@@ -15,6 +19,12 @@ use super::CliConnectionOpts;
 pub struct GetClaimsCommand {
     #[clap(flatten)]
     pub opts: CliConnectionOpts,
+
+    /// Keep the output up to date, re-rendering it when the lattice reports a change to running components or providers and
+    /// at least every interval, given in ms or in humantime (eg: 2s, 5m, 54ms). Defaults to 5000
+    /// milliseconds.
+    #[clap(long, short, num_args = 0..=1, default_missing_value = "5000", value_parser = parse_watch_interval)]
+    pub watch: Option<std::time::Duration>,
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -26,7 +36,19 @@ pub struct GetHostInventoriesCommand {
     #[clap(name = "host-id", value_parser)]
     pub host_id: Option<ServerId>,
 
-    /// Enables Real-time updates, duration can be specified in ms or in humantime (eg: 2s, 5m, 54ms). Defaults to 5000 milliseconds.
+    /// Query the inventories of all running hosts concurrently, the default when no host ID is
+    /// given
+    #[clap(long = "all", conflicts_with = "host-id")]
+    pub all: bool,
+
+    /// Only show the components or providers matching a reference or ID, given as
+    /// `component=<ref>` (or `actor=<ref>`) or `provider=<ref>`. Hosts running none of them are
+    /// left out. May be given more than once
+    #[clap(long = "filter")]
+    pub filters: Vec<InventoryFilter>,
+
+    /// Enables Real-time updates, re-rendering when the lattice reports a change to a host's
+    /// components, providers or labels and at least every interval. Duration can be specified in ms or in humantime (eg: 2s, 5m, 54ms). Defaults to 5000 milliseconds.
     #[clap(long, short, num_args = 0..=1, default_missing_value = "5000", value_parser = parse_watch_interval)]
     pub watch: Option<std::time::Duration>,
 }
@@ -35,12 +57,89 @@ pub struct GetHostInventoriesCommand {
 pub struct GetLinksCommand {
     #[clap(flatten)]
     pub opts: CliConnectionOpts,
+
+    /// Keep the output up to date, re-rendering it when the lattice reports a change to links and
+    /// at least every interval, given in ms or in humantime (eg: 2s, 5m, 54ms). Defaults to 5000
+    /// milliseconds.
+    #[clap(long, short, num_args = 0..=1, default_missing_value = "5000", value_parser = parse_watch_interval)]
+    pub watch: Option<std::time::Duration>,
 }
 
 #[derive(Debug, Clone, Parser)]
 pub struct GetHostsCommand {
     #[clap(flatten)]
     pub opts: CliConnectionOpts,
+
+    /// Keep the output up to date, re-rendering it when the lattice reports a change to hosts and
+    /// at least every interval, given in ms or in humantime (eg: 2s, 5m, 54ms). Defaults to 5000
+    /// milliseconds.
+    #[clap(long, short, num_args = 0..=1, default_missing_value = "5000", value_parser = parse_watch_interval)]
+    pub watch: Option<std::time::Duration>,
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct GetEventsCommand {
+    #[clap(flatten)]
+    pub opts: CliConnectionOpts,
+
+    /// Only record events about this component or provider reference
+    #[clap(long = "for-ref")]
+    pub for_ref: Option<String>,
+
+    /// Only record events from this host
+    #[clap(long = "for-host")]
+    pub for_host: Option<String>,
+
+    /// Only record events about this component or provider ID
+    #[clap(long = "for-component")]
+    pub for_component: Option<String>,
+
+    /// Only record events of this type, e.g. `component_scaled` or `host_heartbeat`. May be given
+    /// more than once. Defaults to the start and scale events, or to every event with `--tail`
+    #[clap(long = "type", name = "event_types", value_parser = parse_event_type)]
+    pub event_types: Vec<String>,
+
+    /// How long to record events for, in ms or in humantime (eg: 2s, 5m, 54ms). Defaults to 10 seconds.
+    #[clap(long = "duration", default_value = "10s", value_parser = parse_watch_interval)]
+    pub duration: std::time::Duration,
+
+    /// Print the matching events as they arrive until interrupted, instead of recording them for a
+    /// while. With structured output (json, ndjson or yaml), each event is printed as a JSON line
+    #[clap(long = "tail", conflicts_with = "duration")]
+    pub tail: bool,
+}
+
+impl GetEventsCommand {
+    /// The types of events to subscribe to
+    fn subscribed_event_types(&self) -> Vec<String> {
+        if !self.event_types.is_empty() {
+            self.event_types.clone()
+        } else if self.tail {
+            // Every event published on the lattice
+            vec![">".to_string()]
+        } else {
+            START_AND_SCALE_EVENTS.map(ToString::to_string).to_vec()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct GetProvidersCommand {
+    #[clap(flatten)]
+    pub opts: CliConnectionOpts,
+
+    /// Only list providers serving this WIT interface, either fully qualified (e.g.
+    /// `wasi:keyvalue/store`) or just the interface name (e.g. `store`)
+    #[clap(long = "interface")]
+    pub interface: Option<String>,
+
+    /// Only list providers serving interfaces in this WIT namespace, e.g. `wasi`
+    #[clap(long = "namespace")]
+    pub namespace: Option<String>,
+
+    /// Only list providers serving interfaces in this WIT package, e.g. `keyvalue`
+    #[clap(long = "package")]
+    pub package: Option<String>,
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -60,10 +159,133 @@ pub enum GetCommand {
     /// Retrieve inventory a given host on in the lattice
     #[clap(name = "inventory", alias = "inventories")]
     HostInventories(GetHostInventoriesCommand),
+
+    /// Record the start and scale events emitted by the lattice for a while, to troubleshoot
+    /// failed starts and scales
+    #[clap(name = "events")]
+    Events(GetEventsCommand),
+
+    /// Retrieve the running providers that serve a WIT interface, based on the links that target
+    /// them
+    #[clap(name = "providers")]
+    Providers(GetProvidersCommand),
+}
+
+/// Lattice events after which `wash get hosts --watch` re-renders. Heartbeats are included so
+/// uptimes stay current
+pub const HOST_WATCH_EVENTS: [&str; 3] = ["host_started", "host_stopped", "host_heartbeat"];
+
+/// Lattice events after which `wash get inventory --watch` re-renders
+pub const INVENTORY_WATCH_EVENTS: [&str; 8] = [
+    "host_started",
+    "host_stopped",
+    "labels_changed",
+    "component_scaled",
+    "component_scale_failed",
+    "provider_started",
+    "provider_start_failed",
+    "provider_stopped",
+];
+
+/// Lattice events after which `wash get links --watch` re-renders
+pub const LINK_WATCH_EVENTS: [&str; 2] = ["linkdef_set", "linkdef_deleted"];
+
+/// Lattice events after which `wash get claims --watch` re-renders, as claims are those of the
+/// running components and providers
+pub const CLAIMS_WATCH_EVENTS: [&str; 3] =
+    ["component_scaled", "provider_started", "provider_stopped"];
+
+/// The prefix of the CloudEvent type of every lattice event
+pub const EVENT_TYPE_PREFIX: &str = "com.wasmcloud.lattice.";
+
+/// A filter on the components or providers shown by `wash get inventory`, matching an entry by its
+/// image reference or ID
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InventoryFilter {
+    Component(String),
+    Provider(String),
+}
+
+impl FromStr for InventoryFilter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let Some((kind, reference)) = s.split_once('=') else {
+            bail!("invalid filter `{s}`, expected `component=<ref>` or `provider=<ref>`");
+        };
+        if reference.is_empty() {
+            bail!("invalid filter `{s}`, the reference cannot be empty");
+        }
+        match kind {
+            "component" | "actor" => Ok(Self::Component(reference.to_string())),
+            "provider" => Ok(Self::Provider(reference.to_string())),
+            _ => bail!("unknown filter `{kind}`, expected `component` or `provider`"),
+        }
+    }
+}
+
+impl InventoryFilter {
+    fn matches_component(&self, component: &ComponentDescription) -> bool {
+        matches!(self, Self::Component(r) if component.id() == r || component.image_ref() == r)
+    }
+
+    fn matches_provider(&self, provider: &ProviderDescription) -> bool {
+        matches!(self, Self::Provider(r) if provider.id() == r || provider.image_ref() == Some(r))
+    }
+}
+
+/// Narrow an inventory down to the components and providers matching any of the filters,
+/// returning `None` if the host runs none of them. The inventory is returned as is without filters
+pub fn filter_inventory(
+    inventory: HostInventory,
+    filters: &[InventoryFilter],
+) -> Result<Option<HostInventory>> {
+    if filters.is_empty() {
+        return Ok(Some(inventory));
+    }
+    let components = inventory
+        .components()
+        .iter()
+        .filter(|c| filters.iter().any(|f| f.matches_component(c)))
+        .cloned()
+        .collect::<Vec<_>>();
+    let providers = inventory
+        .providers()
+        .iter()
+        .filter(|p| filters.iter().any(|f| f.matches_provider(p)))
+        .cloned()
+        .collect::<Vec<_>>();
+    if components.is_empty() && providers.is_empty() {
+        return Ok(None);
+    }
+    HostInventory::builder()
+        .host_id(inventory.host_id().to_string())
+        .friendly_name(inventory.friendly_name().to_string())
+        .version(inventory.version().to_string())
+        .uptime_human(inventory.uptime_human().to_string())
+        .uptime_seconds(inventory.uptime_seconds())
+        .labels(inventory.labels().clone())
+        .components(components)
+        .providers(providers)
+        .build()
+        .map(Some)
+        .map_err(boxed_err_to_anyhow)
 }
 
 /// Retrieve host inventory
 pub async fn get_host_inventories(cmd: GetHostInventoriesCommand) -> Result<Vec<HostInventory>> {
+    let filters = cmd.filters.clone();
+    let inventories = query_host_inventories(cmd).await?;
+    let mut filtered = Vec::with_capacity(inventories.len());
+    for inventory in inventories {
+        if let Some(inventory) = filter_inventory(inventory, &filters)? {
+            filtered.push(inventory);
+        }
+    }
+    Ok(filtered)
+}
+
+async fn query_host_inventories(cmd: GetHostInventoriesCommand) -> Result<Vec<HostInventory>> {
     let wco: WashConnectionOptions = cmd.opts.try_into()?;
     let client = wco.into_ctl_client(None).await?;
 
@@ -91,6 +313,40 @@ pub async fn get_host_inventories(cmd: GetHostInventoriesCommand) -> Result<Vec<
     }
 }
 
+/// Record the lattice events matching the command's filters for the command's duration
+pub async fn get_events(cmd: GetEventsCommand) -> Result<Vec<Event>> {
+    let duration = cmd.duration;
+    let (mut receiver, filter) = subscribe_events(cmd).await?;
+    Ok(record_events(&mut receiver, duration, &filter).await)
+}
+
+/// Subscribe to the lattice events selected by the command. Events received on the channel still
+/// need to be checked against the returned filter
+pub async fn subscribe_events(cmd: GetEventsCommand) -> Result<(Receiver<Event>, EventFilter)> {
+    let event_types = cmd.subscribed_event_types();
+    let wco: WashConnectionOptions = cmd.opts.try_into()?;
+    let client = wco.into_ctl_client(None).await?;
+    let receiver = client
+        .events_receiver(event_types)
+        .await
+        .map_err(boxed_err_to_anyhow)
+        .context("Failed to get lattice event channel")?;
+    let filter = EventFilter {
+        image_ref: cmd.for_ref,
+        host_id: cmd.for_host,
+        component_id: cmd.for_component,
+    };
+    Ok((receiver, filter))
+}
+
+/// Retrieve the running providers serving the requested interfaces
+pub async fn get_providers(cmd: GetProvidersCommand) -> Result<Vec<ServingProvider>> {
+    let query = InterfaceQuery::new(cmd.interface.as_deref(), cmd.namespace, cmd.package)?;
+    let wco: WashConnectionOptions = cmd.opts.try_into()?;
+    let client = wco.into_ctl_client(None).await?;
+    find_providers_serving(&client, &query).await
+}
+
 /// Retrieve hosts
 pub async fn get_hosts(cmd: GetHostsCommand) -> Result<Vec<Host>> {
     let wco: WashConnectionOptions = cmd.opts.try_into()?;
@@ -108,6 +364,21 @@ pub async fn get_hosts(cmd: GetHostsCommand) -> Result<Vec<Host>> {
         .context("Was able to connect to NATS, but failed to get hosts.")
 }
 
+/// Parse a lattice event type, given with or without its `com.wasmcloud.lattice.` prefix
+pub fn parse_event_type(arg: &str) -> Result<String, String> {
+    let event_type = arg.strip_prefix(EVENT_TYPE_PREFIX).unwrap_or(arg);
+    if event_type.is_empty()
+        || event_type
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '.' | '*' | '>'))
+    {
+        return Err(format!(
+            "Invalid event type: '{arg}'. Expected an event type like 'component_scaled'."
+        ));
+    }
+    Ok(event_type.to_string())
+}
+
 pub fn parse_watch_interval(arg: &str) -> Result<std::time::Duration, String> {
     if let Ok(duration) = humantime::Duration::from_str(arg) {
         return Ok(duration.into());
@@ -119,3 +390,105 @@ pub fn parse_watch_interval(arg: &str) -> Result<std::time::Duration, String> {
 
     Err(format!("Invalid duration: '{arg}'. Expected a duration like '5s', '1m', '100ms', or milliseconds as an integer."))
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn inventory() -> HostInventory {
+        HostInventory::builder()
+            .host_id("NHOST".to_string())
+            .friendly_name("quiet-pond-1234".to_string())
+            .version("1.0.0".to_string())
+            .uptime_human("1m".to_string())
+            .uptime_seconds(60)
+            .components(vec![
+                ComponentDescription::builder()
+                    .id("echo".to_string())
+                    .image_ref("ghcr.io/wasmcloud/echo:0.1.0".to_string())
+                    .build()
+                    .unwrap(),
+                ComponentDescription::builder()
+                    .id("kvcounter".to_string())
+                    .image_ref("ghcr.io/wasmcloud/kvcounter:0.1.0".to_string())
+                    .build()
+                    .unwrap(),
+            ])
+            .providers(vec![ProviderDescription::builder()
+                .id("http-server")
+                .image_ref("ghcr.io/wasmcloud/http-server:0.23.2")
+                .build()
+                .unwrap()])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn event_types_are_parsed() {
+        assert_eq!(
+            parse_event_type("component_scaled"),
+            Ok("component_scaled".to_string())
+        );
+        assert_eq!(
+            parse_event_type("com.wasmcloud.lattice.host_heartbeat"),
+            Ok("host_heartbeat".to_string())
+        );
+        assert!(parse_event_type("").is_err());
+        assert!(parse_event_type("component.*").is_err());
+        assert!(parse_event_type(">").is_err());
+
+        let cmd = GetEventsCommand::try_parse_from(["events", "--tail"]).unwrap();
+        assert_eq!(cmd.subscribed_event_types(), vec![">"]);
+        let cmd = GetEventsCommand::try_parse_from(["events"]).unwrap();
+        assert_eq!(cmd.subscribed_event_types(), START_AND_SCALE_EVENTS);
+        let cmd = GetEventsCommand::try_parse_from(["events", "--tail", "--type", "host_started"])
+            .unwrap();
+        assert_eq!(cmd.subscribed_event_types(), vec!["host_started"]);
+        assert!(
+            GetEventsCommand::try_parse_from(["events", "--tail", "--duration", "5s"]).is_err()
+        );
+    }
+
+    #[test]
+    fn inventories_are_filtered_by_reference() {
+        let filter = |s: &str| s.parse::<InventoryFilter>().unwrap();
+        assert_eq!(
+            filter("actor=echo"),
+            InventoryFilter::Component("echo".into())
+        );
+        assert!("echo".parse::<InventoryFilter>().is_err());
+        assert!("host=echo".parse::<InventoryFilter>().is_err());
+        assert!("provider=".parse::<InventoryFilter>().is_err());
+
+        let filtered = filter_inventory(
+            inventory(),
+            &[filter("component=ghcr.io/wasmcloud/echo:0.1.0")],
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(filtered.components().len(), 1);
+        assert_eq!(filtered.components()[0].id(), "echo");
+        assert!(filtered.providers().is_empty());
+        assert_eq!(filtered.friendly_name(), "quiet-pond-1234");
+
+        let filtered = filter_inventory(
+            inventory(),
+            &[
+                filter("provider=http-server"),
+                filter("component=kvcounter"),
+            ],
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(filtered.components().len(), 1);
+        assert_eq!(filtered.providers().len(), 1);
+
+        assert!(filter_inventory(inventory(), &[filter("provider=echo")])
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            filter_inventory(inventory(), &[]).unwrap(),
+            Some(inventory())
+        );
+    }
+}
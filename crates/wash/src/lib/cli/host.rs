@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use wasmcloud_control_interface::HostInventory;
+
+use crate::lib::cli::stop::stop_provider;
+use crate::lib::cli::{CliConnectionOpts, CommandOutput, NdjsonStream, OutputKind};
+use crate::lib::common::{boxed_err_to_anyhow, find_host_id_with, HostMatchOpts};
+use crate::lib::component::{scale_component, ScaleComponentArgs};
+use crate::lib::config::{close_ctl_client, WashConnectionOptions};
+use crate::lib::context::default_component_operation_timeout_ms;
+use crate::lib::failure::{Failure, FailureKind};
+
+/// Host label marking a host that wash auctions should not place new workloads on. Remove it with
+/// `wash label --delete <host-id> unschedulable` once maintenance is done
+pub const UNSCHEDULABLE_LABEL: &str = "unschedulable";
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum HostCommand {
+    /// Scale down every component and stop every provider on a host, e.g. before maintenance
+    #[clap(name = "drain")]
+    Drain(DrainHostCommand),
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct DrainHostCommand {
+    #[clap(flatten)]
+    pub opts: CliConnectionOpts,
+
+    /// ID of the host to drain. If a non-ID is provided, the host will be selected based on matching
+    /// the friendly name and will return an error if more than one host matches.
+    #[clap(name = "host-id")]
+    pub host_id: String,
+
+    #[clap(flatten)]
+    pub host_match: HostMatchOpts,
+
+    /// Label the host as unschedulable before draining it, so wash auctions skip it
+    #[clap(long = "cordon")]
+    pub cordon: bool,
+
+    /// How long to wait for each component to scale down or provider to stop, in milliseconds
+    #[clap(long = "wait-timeout-ms", default_value_t = default_component_operation_timeout_ms())]
+    pub wait_timeout_ms: u64,
+}
+
+/// What a drain step acts on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DrainKind {
+    Component,
+    Provider,
+}
+
+/// A single component or provider to remove from a host while draining it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DrainStep {
+    pub kind: DrainKind,
+    pub id: String,
+    pub image_ref: Option<String>,
+}
+
+/// The outcome of a drain step
+#[derive(Debug, Clone, Serialize)]
+pub struct DrainResult {
+    #[serde(flatten)]
+    pub step: DrainStep,
+    /// Set if the component or provider could not be removed
+    pub error: Option<String>,
+}
+
+/// The steps to drain a host: components are scaled down first, so they stop using the host's
+/// providers before those are stopped
+#[must_use]
+pub fn plan_host_drain(inventory: &HostInventory) -> Vec<DrainStep> {
+    let components = inventory.components().iter().map(|c| DrainStep {
+        kind: DrainKind::Component,
+        id: c.id().to_string(),
+        image_ref: Some(c.image_ref().to_string()),
+    });
+    let providers = inventory.providers().iter().map(|p| DrainStep {
+        kind: DrainKind::Provider,
+        id: p.id().to_string(),
+        image_ref: p.image_ref().map(ToString::to_string),
+    });
+    components.chain(providers).collect()
+}
+
+pub async fn handle_command(cmd: HostCommand, output_kind: OutputKind) -> Result<CommandOutput> {
+    match cmd {
+        HostCommand::Drain(cmd) => handle_drain_host(cmd, output_kind).await,
+    }
+}
+
+async fn handle_drain_host(
+    cmd: DrainHostCommand,
+    output_kind: OutputKind,
+) -> Result<CommandOutput> {
+    let wco: WashConnectionOptions = cmd.opts.try_into()?;
+    let client = wco.into_ctl_client(None).await?;
+
+    let mut stream = NdjsonStream::for_output(output_kind);
+    let result = async {
+        let host_id = find_host_id_with(&cmd.host_id, &client, &cmd.host_match)
+            .await?
+            .0
+            .to_string();
+        let results = drain_host_with_client(
+            &client,
+            &host_id,
+            cmd.cordon,
+            cmd.wait_timeout_ms,
+            |result| match stream.as_mut() {
+                Some(stream) => stream.emit(result),
+                None => Ok(()),
+            },
+        )
+        .await?;
+        drain_output(host_id, cmd.cordon, results)
+    }
+    .await;
+    close_ctl_client(&client).await;
+    result
+}
+
+/// Drain the host with the given ID, calling `on_result` as each component or provider is
+/// removed, e.g. to report progress. If `cordon` is set, the host is first labeled unschedulable
+/// so wash auctions stop placing new work on it
+pub async fn drain_host_with_client(
+    client: &wasmcloud_control_interface::Client,
+    host_id: &str,
+    cordon: bool,
+    wait_timeout_ms: u64,
+    mut on_result: impl FnMut(&DrainResult) -> Result<()>,
+) -> Result<Vec<DrainResult>> {
+    if cordon {
+        let ack = client
+            .put_label(host_id, UNSCHEDULABLE_LABEL, "true")
+            .await
+            .map_err(boxed_err_to_anyhow)?;
+        if !ack.succeeded() {
+            return Err(Failure::new(
+                FailureKind::AckRejected,
+                format!(
+                    "Failed to mark host [{host_id}] as unschedulable: {}",
+                    ack.message()
+                ),
+            )
+            .into());
+        }
+    }
+
+    let inventory = client
+        .get_host_inventory(host_id)
+        .await
+        .map_err(boxed_err_to_anyhow)?
+        .into_data()
+        .with_context(|| format!("No inventory returned for host [{host_id}]"))?;
+
+    let mut results = Vec::new();
+    for step in plan_host_drain(&inventory) {
+        let outcome = match step.kind {
+            DrainKind::Component => scale_component(ScaleComponentArgs {
+                client,
+                host_id,
+                component_id: &step.id,
+                component_ref: step.image_ref.as_deref().unwrap_or_default(),
+                max_instances: 0,
+                annotations: None,
+                config: Vec::new(),
+                skip_wait: false,
+                timeout_ms: Some(wait_timeout_ms),
+            })
+            .await
+            .map(|_| ()),
+            DrainKind::Provider => {
+                stop_provider(client, Some(host_id), &step.id, false, wait_timeout_ms).await
+            }
+        };
+        let result = DrainResult {
+            step,
+            error: outcome.err().map(|e| format!("{e:#}")),
+        };
+        on_result(&result)?;
+        results.push(result);
+    }
+    Ok(results)
+}
+
+/// Describe what happened to each component and provider of a drained host, one per line
+#[must_use]
+pub fn describe_drain_results(results: &[DrainResult]) -> String {
+    let mut text = String::new();
+    for result in results {
+        let kind = match result.step.kind {
+            DrainKind::Component => "component",
+            DrainKind::Provider => "provider",
+        };
+        match &result.error {
+            None => text.push_str(&format!("\n  removed {kind} [{}]", result.step.id)),
+            Some(e) => text.push_str(&format!(
+                "\n  failed to remove {kind} [{}]: {e}",
+                result.step.id
+            )),
+        }
+    }
+    text
+}
+
+/// Render the outcome of a drain. If any component or provider could not be removed, the output is
+/// returned as a [`FailureKind::TargetsFailed`] error so the command exits with its code and
+/// scripts don't go on to take the host down
+fn drain_output(host_id: String, cordon: bool, results: Vec<DrainResult>) -> Result<CommandOutput> {
+    let failed = results.iter().filter(|r| r.error.is_some()).count();
+    let mut text = if failed == 0 {
+        format!(
+            "Host [{host_id}] drained, removed {} component(s) and provider(s)",
+            results.len()
+        )
+    } else {
+        format!(
+            "Host [{host_id}] partially drained, failed to remove {failed} of {} component(s) and provider(s)",
+            results.len()
+        )
+    };
+    text.push_str(&describe_drain_results(&results));
+    if cordon {
+        text.push_str(&format!(
+            "\nHost is labeled `{UNSCHEDULABLE_LABEL}=true`, remove the label to schedule on it again"
+        ));
+    }
+
+    let output = CommandOutput::new(
+        text,
+        HashMap::from([
+            ("host_id".into(), host_id.into()),
+            ("cordoned".into(), cordon.into()),
+            ("drained".into(), serde_json::to_value(&results)?),
+            ("partial".into(), (failed > 0).into()),
+        ]),
+    );
+    if failed > 0 {
+        return Err(
+            Failure::with_details(FailureKind::TargetsFailed, output.text, output.map).into(),
+        );
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use wasmcloud_control_interface::{ComponentDescription, ProviderDescription};
+
+    use super::*;
+
+    #[test]
+    fn drain_scales_components_before_stopping_providers() {
+        let inventory = HostInventory::builder()
+            .host_id("host1".into())
+            .friendly_name("quiet-dawn".into())
+            .version("1.0.0".into())
+            .uptime_human("t".into())
+            .uptime_seconds(100)
+            .providers(vec![ProviderDescription::builder()
+                .id("http-server")
+                .image_ref("ghcr.io/wasmcloud/http-server:0.23.2")
+                .build()
+                .expect("should build provider")])
+            .components(vec![ComponentDescription::builder()
+                .id("hello".into())
+                .image_ref("ghcr.io/wasmcloud/hello:0.1.0".into())
+                .max_instances(5)
+                .annotations(BTreeMap::new())
+                .build()
+                .expect("should build component")])
+            .build()
+            .expect("should build inventory");
+
+        assert_eq!(
+            plan_host_drain(&inventory),
+            vec![
+                DrainStep {
+                    kind: DrainKind::Component,
+                    id: "hello".to_string(),
+                    image_ref: Some("ghcr.io/wasmcloud/hello:0.1.0".to_string()),
+                },
+                DrainStep {
+                    kind: DrainKind::Provider,
+                    id: "http-server".to_string(),
+                    image_ref: Some("ghcr.io/wasmcloud/http-server:0.23.2".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn partial_drain_fails_with_the_results() {
+        let results = vec![
+            DrainResult {
+                step: DrainStep {
+                    kind: DrainKind::Component,
+                    id: "hello".to_string(),
+                    image_ref: Some("ghcr.io/wasmcloud/hello:0.1.0".to_string()),
+                },
+                error: None,
+            },
+            DrainResult {
+                step: DrainStep {
+                    kind: DrainKind::Provider,
+                    id: "http-server".to_string(),
+                    image_ref: None,
+                },
+                error: Some("timed out waiting for provider to stop".to_string()),
+            },
+        ];
+
+        let Err(err) = drain_output("host1".to_string(), true, results.clone()) else {
+            panic!("a partial drain should fail");
+        };
+        assert_eq!(FailureKind::of(&err), Some(FailureKind::TargetsFailed));
+        let failure = err
+            .downcast_ref::<Failure>()
+            .expect("error should be a failure");
+        assert!(failure
+            .message
+            .starts_with("Host [host1] partially drained, failed to remove 1 of 2"));
+        assert!(failure.message.contains(UNSCHEDULABLE_LABEL));
+        assert_eq!(failure.details["partial"], true);
+        assert_eq!(failure.details["drained"][1]["id"], "http-server");
+
+        let output = drain_output("host1".to_string(), false, results[..1].to_vec())
+            .expect("a complete drain should succeed");
+        assert_eq!(output.map["partial"], false);
+    }
+}
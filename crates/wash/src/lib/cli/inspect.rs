@@ -1,17 +1,30 @@
 use super::{cached_oci_file, CommandOutput, OutputKind};
-use crate::lib::registry::{get_oci_artifact, OciPullOptions};
+use crate::lib::{
+    build::source_digest,
+    parser::load_config,
+    registry::{get_oci_artifact, OciPullOptions},
+};
 use anyhow::{anyhow, bail, Context, Result};
 use clap::Parser;
 use provider_archive::ProviderArchive;
-use serde::de::DeserializeOwned;
+use semver::Version;
+use serde::{de::DeserializeOwned, Serialize};
 use serde_json::json;
-use std::{collections::HashMap, fs::File, io::Read, path::PathBuf};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+};
 use term_table::{
     row::Row,
     table_cell::{Alignment, TableCell},
     Table,
 };
-use wascap::jwt::{Claims, Component, Token, TokenValidation, WascapEntity};
+use wascap::jwt::{BuildMetadata, Claims, Component, Token, TokenValidation, WascapEntity};
+use wit_component::DecodedWasm;
+use wit_parser::{Resolve, WorldItem, WorldKey};
 
 #[derive(Debug, Parser, Clone)]
 pub struct InspectCliCommand {
@@ -32,6 +45,11 @@ pub struct InspectCliCommand {
     )]
     pub wit: bool,
 
+    /// Print the interfaces a component or provider archive imports and exports, with their
+    /// versions, instead of the claims. Use --wit for the raw WIT instead
+    #[clap(long = "interfaces", conflicts_with_all = ["jwt_only", "wit"])]
+    pub interfaces: bool,
+
     /// Digest to verify artifact against (if OCI URL is provided for `<target>`)
     #[clap(short = 'd', long = "digest")]
     pub digest: Option<String>,
@@ -69,6 +87,25 @@ pub struct InspectCliCommand {
     /// skip the local OCI cache and pull the artifact from the registry to inspect
     #[clap(long = "no-cache")]
     pub no_cache: bool,
+
+    /// Verify that a component was built from the sources of the project in this directory,
+    /// by comparing them to the source digest in its build metadata
+    #[clap(
+        long = "verify-build",
+        value_name = "PROJECT_DIR",
+        conflicts_with_all = ["jwt_only", "wit", "interfaces"]
+    )]
+    pub verify_build: Option<PathBuf>,
+
+    /// Check which imports of the component would be satisfied by linking it to this provider
+    /// archive or component (a path or OCI URL), instead of printing the claims. Registry options
+    /// apply to pulling both artifacts
+    #[clap(
+        long = "check-compat",
+        value_name = "TARGET",
+        conflicts_with_all = ["jwt_only", "wit", "interfaces", "verify_build"]
+    )]
+    pub check_compat: Option<String>,
 }
 
 /// Attempts to inspect a provider archive or component
@@ -103,30 +140,52 @@ pub async fn handle_command(
         .await?;
     }
 
+    if let Some(target) = &command.check_compat {
+        let target_buf = get_oci_artifact(
+            target.clone(),
+            (!command.no_cache).then(|| cached_oci_file(target)),
+            OciPullOptions {
+                digest: None,
+                allow_latest: command.allow_latest,
+                user: command.user.clone(),
+                password: command.password.clone(),
+                insecure: command.insecure,
+                insecure_skip_tls_verify: command.insecure_skip_tls_verify,
+            },
+        )
+        .await?;
+        let component = match decode_artifact_wit(&buf).await? {
+            decoded @ DecodedWasm::Component(..) => decoded,
+            DecodedWasm::WitPackage(..) => bail!(
+                "[{}] is not a component, only the imports of components can be checked",
+                command.target
+            ),
+        };
+        let target_wit = decode_artifact_wit(&target_buf)
+            .await
+            .with_context(|| format!("failed to get the WIT of [{target}]"))?;
+        let compat = check_compat(&component, &target_wit);
+        return Ok(render_compat(&command.target, target, &compat));
+    }
+
+    if command.wit || command.interfaces {
+        let decoded = decode_artifact_wit(&buf).await?;
+        if command.interfaces {
+            return Ok(render_wit_interfaces(&decoded));
+        }
+        let mut printer = wit_component::WitPrinter::default();
+        printer
+            .print(decoded.resolve(), decoded.package(), &[])
+            .context("should be able to print WIT world")?;
+        return Ok(CommandOutput::from_key_and_text(
+            "wit",
+            printer.output.to_string(),
+        ));
+    }
+
     let wit_parsed = wasmparser::Parser::new(0).parse_all(&buf).next();
 
     let output = match wit_parsed {
-        // Inspect the WIT of a Wasm component
-        Some(Ok(wasmparser::Payload::Version {
-            encoding: wasmparser::Encoding::Component,
-            ..
-        })) if command.wit => {
-            let witty = wit_component::decode(&buf).expect("Failed to decode WIT");
-            let resolve = witty.resolve();
-            let main = witty.package();
-            let mut printer = wit_component::WitPrinter::default();
-            printer
-                .print(resolve, main, &[])
-                .context("should be able to print WIT world from a component")?;
-            CommandOutput::from_key_and_text("wit", printer.output.to_string())
-        }
-        // Catch trying to inspect a WIT from a WASI Preview 1 module
-        Some(Ok(wasmparser::Payload::Version {
-            encoding: wasmparser::Encoding::Module,
-            ..
-        })) if command.wit => {
-            bail!("No WIT present in Wasm, this looks like a WASI Preview 1 module")
-        }
         // Inspect claims inside of Wasm
         Some(Ok(_)) => {
             let module_name = command.target.clone();
@@ -138,6 +197,9 @@ pub async fn handle_command(
             if jwt_only {
                 CommandOutput::from_key_and_text("token", token.jwt)
             } else {
+                if let Some(project_dir) = &command.verify_build {
+                    verify_build_metadata(&token.claims, project_dir).await?;
+                }
                 let validation = wascap::jwt::validate_token::<Component>(&token.jwt)?;
                 let is_component = matches!(
                     wit_parsed,
@@ -154,26 +216,371 @@ pub async fn handle_command(
             let artifact = ProviderArchive::try_load(&buf)
                 .await
                 .map_err(|e| anyhow!("{}", e))?;
-            if command.wit {
-                let wit_bytes = artifact
-                    .wit_world()
-                    .ok_or_else(|| anyhow!("No wit encoded in PAR"))?;
-                let witty = wit_component::decode(wit_bytes).context("Failed to decode WIT")?;
-                let resolve = witty.resolve();
-                let main = witty.package();
-                let mut printer = wit_component::WitPrinter::default();
-                printer
-                    .print(resolve, main, &[])
-                    .context("should be able to print WIT world from provider archive")?;
-                CommandOutput::from_key_and_text("wit", printer.output.to_string())
-            } else {
-                render_provider_claims(command.clone(), &artifact).await?
-            }
+            render_provider_claims(command.clone(), &artifact).await?
         }
     };
     Ok(output)
 }
 
+/// Something a WIT world imports or exports
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WitWorldItem {
+    /// Name of the item, `namespace:package/interface` for interfaces from a package
+    pub name: String,
+    pub kind: WitItemKind,
+    /// Version of the package of an interface, if it's versioned
+    pub version: Option<String>,
+}
+
+/// What kind of item a WIT world imports or exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WitItemKind {
+    Interface,
+    Function,
+    Type,
+}
+
+impl fmt::Display for WitItemKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Interface => write!(f, "interface"),
+            Self::Function => write!(f, "function"),
+            Self::Type => write!(f, "type"),
+        }
+    }
+}
+
+/// The imports and exports of a WIT world
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WitWorldSummary {
+    /// Name of the world, `namespace:package/world`
+    pub name: String,
+    pub imports: Vec<WitWorldItem>,
+    pub exports: Vec<WitWorldItem>,
+}
+
+/// Summarize the worlds of decoded WIT: the world of a component, or every world of a WIT
+/// package
+#[must_use]
+pub fn wit_world_summaries(decoded: &DecodedWasm) -> Vec<WitWorldSummary> {
+    let resolve = decoded.resolve();
+    let worlds = match decoded {
+        DecodedWasm::Component(_, world) => vec![*world],
+        DecodedWasm::WitPackage(_, package) => resolve.packages[*package]
+            .worlds
+            .values()
+            .copied()
+            .collect(),
+    };
+    worlds
+        .into_iter()
+        .map(|id| {
+            let world = &resolve.worlds[id];
+            let name = match world.package {
+                Some(package) => {
+                    let package = &resolve.packages[package].name;
+                    format!("{}:{}/{}", package.namespace, package.name, world.name)
+                }
+                None => world.name.clone(),
+            };
+            WitWorldSummary {
+                name,
+                imports: world
+                    .imports
+                    .iter()
+                    .map(|(key, item)| wit_world_item(resolve, key, item))
+                    .collect(),
+                exports: world
+                    .exports
+                    .iter()
+                    .map(|(key, item)| wit_world_item(resolve, key, item))
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+fn wit_world_item(resolve: &Resolve, key: &WorldKey, item: &WorldItem) -> WitWorldItem {
+    match item {
+        WorldItem::Interface { id, .. } => {
+            let interface = &resolve.interfaces[*id];
+            let package = interface.package.map(|p| &resolve.packages[p].name);
+            let name = match (key, package, &interface.name) {
+                (WorldKey::Interface(_), Some(package), Some(name)) => {
+                    format!("{}:{}/{name}", package.namespace, package.name)
+                }
+                _ => resolve.name_world_key(key),
+            };
+            WitWorldItem {
+                name,
+                kind: WitItemKind::Interface,
+                version: package
+                    .and_then(|p| p.version.as_ref())
+                    .map(ToString::to_string),
+            }
+        }
+        WorldItem::Function(function) => WitWorldItem {
+            name: function.name.clone(),
+            kind: WitItemKind::Function,
+            version: None,
+        },
+        WorldItem::Type(_) => WitWorldItem {
+            name: resolve.name_world_key(key),
+            kind: WitItemKind::Type,
+            version: None,
+        },
+    }
+}
+
+/// Renders the imports and exports of decoded WIT
+fn render_wit_interfaces(decoded: &DecodedWasm) -> CommandOutput {
+    let worlds = wit_world_summaries(decoded);
+    let mut table = Table::new();
+    super::configure_table_style(&mut table);
+    for world in &worlds {
+        table.add_row(Row::new(vec![TableCell::new_with_alignment(
+            format!("World {}", world.name),
+            2,
+            Alignment::Center,
+        )]));
+        for (label, items) in [("Imports", &world.imports), ("Exports", &world.exports)] {
+            table.add_row(Row::new(vec![TableCell::new_with_alignment(
+                label,
+                2,
+                Alignment::Left,
+            )]));
+            if items.is_empty() {
+                table.add_row(Row::new(vec![TableCell::new_with_alignment(
+                    "None",
+                    2,
+                    Alignment::Left,
+                )]));
+            }
+            for item in items {
+                let name = if item.kind == WitItemKind::Interface {
+                    item.name.clone()
+                } else {
+                    format!("{} ({})", item.name, item.kind)
+                };
+                table.add_row(Row::new(vec![
+                    TableCell::new(name),
+                    TableCell::new_with_alignment(
+                        item.version.as_deref().unwrap_or("-"),
+                        1,
+                        Alignment::Right,
+                    ),
+                ]));
+            }
+        }
+    }
+    CommandOutput::new(
+        table.render(),
+        HashMap::from([("worlds".to_string(), json!(worlds))]),
+    )
+}
+
+/// Whether an import of a component is satisfied by what another artifact exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompatStatus {
+    /// The interface is exported at a semver compatible version
+    Satisfied,
+    /// The interface is exported, but at a version that isn't compatible
+    VersionMismatch,
+    /// The interface isn't exported, so it has to be satisfied by the host or another link
+    NotExported,
+}
+
+/// An import of a component checked against the exports of another artifact
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CompatEntry {
+    /// The imported interface, as `namespace:package/interface`
+    pub interface: String,
+    pub import_version: Option<String>,
+    /// Version of the interface the other artifact exports, if it does
+    pub export_version: Option<String>,
+    pub status: CompatStatus,
+}
+
+/// Check the interfaces a component imports against the interfaces `target` exports, from any of
+/// its worlds. Functions imported directly into the world can't be satisfied by links, so they
+/// are left out
+#[must_use]
+pub fn check_compat(component: &DecodedWasm, target: &DecodedWasm) -> Vec<CompatEntry> {
+    let exports = wit_world_summaries(target)
+        .into_iter()
+        .flat_map(|world| world.exports)
+        .filter(|item| item.kind == WitItemKind::Interface)
+        .collect::<Vec<_>>();
+    wit_world_summaries(component)
+        .into_iter()
+        .flat_map(|world| world.imports)
+        .filter(|item| item.kind == WitItemKind::Interface)
+        .map(|import| {
+            let exported = exports
+                .iter()
+                .filter(|export| export.name == import.name)
+                .collect::<Vec<_>>();
+            let compatible = exported.iter().find(|export| {
+                versions_compatible(import.version.as_deref(), export.version.as_deref())
+            });
+            let (export, status) = match (compatible, exported.first()) {
+                (Some(export), _) => (Some(*export), CompatStatus::Satisfied),
+                (None, Some(export)) => (Some(*export), CompatStatus::VersionMismatch),
+                (None, None) => (None, CompatStatus::NotExported),
+            };
+            CompatEntry {
+                interface: import.name,
+                import_version: import.version,
+                export_version: export.and_then(|e| e.version.clone()),
+                status,
+            }
+        })
+        .collect()
+}
+
+/// Whether an interface exported at version `export` satisfies an import of version `import`,
+/// following semver: the export must be at least as new and have the same major version, or the
+/// same minor version before 1.0
+fn versions_compatible(import: Option<&str>, export: Option<&str>) -> bool {
+    let (import, export) = match (import, export) {
+        (None, None) => return true,
+        (Some(import), Some(export)) => (import, export),
+        _ => return false,
+    };
+    let (Ok(import), Ok(export)) = (Version::parse(import), Version::parse(export)) else {
+        return import == export;
+    };
+    if !import.pre.is_empty() || !export.pre.is_empty() {
+        return import == export;
+    }
+    let same_series = if import.major == 0 {
+        export.major == 0 && import.minor == export.minor
+    } else {
+        import.major == export.major
+    };
+    same_series && export >= import
+}
+
+/// The WIT of a component, or the WIT package embedded in a provider archive
+async fn decode_artifact_wit(buf: &[u8]) -> Result<DecodedWasm> {
+    match wasmparser::Parser::new(0).parse_all(buf).next() {
+        Some(Ok(wasmparser::Payload::Version {
+            encoding: wasmparser::Encoding::Component,
+            ..
+        })) => wit_component::decode(buf).context("Failed to decode WIT"),
+        Some(Ok(wasmparser::Payload::Version {
+            encoding: wasmparser::Encoding::Module,
+            ..
+        })) => bail!("No WIT present in Wasm, this looks like a WASI Preview 1 module"),
+        _ => {
+            let artifact = ProviderArchive::try_load(buf)
+                .await
+                .map_err(|e| anyhow!("{}", e))?;
+            let wit_bytes = artifact
+                .wit_world()
+                .ok_or_else(|| anyhow!("No wit encoded in PAR"))?;
+            wit_component::decode(wit_bytes).context("Failed to decode WIT")
+        }
+    }
+}
+
+/// Renders the result of [`check_compat`], with the link that would satisfy the imports
+fn render_compat(component: &str, target: &str, compat: &[CompatEntry]) -> CommandOutput {
+    let mut table = Table::new();
+    super::configure_table_style(&mut table);
+    table.add_row(Row::new(vec![TableCell::new_with_alignment(
+        format!("Imports of {component} exported by {target}"),
+        4,
+        Alignment::Center,
+    )]));
+    table.add_row(Row::new(vec![
+        TableCell::new("Interface"),
+        TableCell::new("Imported"),
+        TableCell::new("Exported"),
+        TableCell::new("Status"),
+    ]));
+    for entry in compat {
+        let status = match entry.status {
+            CompatStatus::Satisfied => "satisfied",
+            CompatStatus::VersionMismatch => "version mismatch",
+            CompatStatus::NotExported => "not exported",
+        };
+        table.add_row(Row::new(vec![
+            TableCell::new(&entry.interface),
+            TableCell::new(entry.import_version.as_deref().unwrap_or("-")),
+            TableCell::new(entry.export_version.as_deref().unwrap_or("-")),
+            TableCell::new(status),
+        ]));
+    }
+
+    // Links are made per package, listing the interfaces of the package to link
+    let mut links = BTreeMap::<(&str, &str), Vec<&str>>::new();
+    for entry in compat
+        .iter()
+        .filter(|e| e.status == CompatStatus::Satisfied)
+    {
+        let Some((package, interface)) = entry.interface.split_once('/') else {
+            continue;
+        };
+        let Some((namespace, package)) = package.split_once(':') else {
+            continue;
+        };
+        links
+            .entry((namespace, package))
+            .or_default()
+            .push(interface);
+    }
+    let mut text = table.render();
+    if links.is_empty() {
+        text.push_str(&format!(
+            "\nNo imports of {component} would be satisfied, a link to {target} would do nothing"
+        ));
+    }
+    for ((namespace, package), interfaces) in &links {
+        text.push_str(&format!(
+            "\nLink with namespace [{namespace}], package [{package}] and interfaces [{}]",
+            interfaces.join(", ")
+        ));
+    }
+    let links = links
+        .into_iter()
+        .map(|((namespace, package), interfaces)| {
+            json!({ "namespace": namespace, "package": package, "interfaces": interfaces })
+        })
+        .collect::<Vec<_>>();
+    CommandOutput::new(
+        text,
+        HashMap::from([
+            ("imports".to_string(), json!(compat)),
+            ("links".to_string(), json!(links)),
+        ]),
+    )
+}
+
+/// Check that the component was built from the sources of the project in `project_dir`, failing
+/// if its claims have no build metadata or the digest of the sources doesn't match
+pub async fn verify_build_metadata(claims: &Claims<Component>, project_dir: &Path) -> Result<()> {
+    let expected = claims
+        .metadata
+        .as_ref()
+        .and_then(|md| md.build.as_ref())
+        .and_then(|build| build.source_digest.as_deref())
+        .context("component has no source digest in its build metadata, build it with `wash build --embed-build-metadata`")?;
+    let config = load_config(Some(project_dir.to_path_buf()), Some(true))
+        .await
+        .with_context(|| format!("failed to load project in [{}]", project_dir.display()))?;
+    let actual = source_digest(&config.common)?;
+    if actual != expected {
+        bail!(
+            "component was not built from the sources in [{}]: expected source digest [{expected}], found [{actual}]",
+            project_dir.display()
+        );
+    }
+    Ok(())
+}
+
 /// Extracts claims for a given OCI artifact
 async fn get_caps(
     cmd: InspectCliCommand,
@@ -222,6 +629,9 @@ pub fn render_component_claims(
     map.insert("revision".to_string(), json!(friendly_rev));
     map.insert("tags".to_string(), json!(tags));
     map.insert("name".to_string(), json!(name));
+    if let Some(build) = &md.build {
+        map.insert("build".to_string(), json!(build));
+    }
 
     let mut table = render_core(&claims, validation);
 
@@ -247,9 +657,46 @@ pub fn render_component_claims(
         Alignment::Left,
     )]));
 
+    if let Some(build) = &md.build {
+        render_build_metadata(&mut table, build);
+    }
+
     CommandOutput::new(table.render(), map)
 }
 
+fn render_build_metadata(table: &mut Table, build: &BuildMetadata) {
+    table.add_row(Row::new(vec![TableCell::new_with_alignment(
+        "Build",
+        2,
+        Alignment::Center,
+    )]));
+
+    let built_at = build.built_at.map(|secs| {
+        i64::try_from(secs)
+            .ok()
+            .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+            .map_or_else(|| secs.to_string(), |time| time.to_rfc3339())
+    });
+    for (label, value) in [
+        ("Source Revision", build.source_revision.clone()),
+        ("Source Digest", build.source_digest.clone()),
+        ("Built At", built_at),
+    ] {
+        if let Some(value) = value {
+            table.add_row(Row::new(vec![
+                TableCell::new(label),
+                TableCell::new_with_alignment(value, 1, Alignment::Right),
+            ]));
+        }
+    }
+    for (toolchain, version) in &build.toolchains {
+        table.add_row(Row::new(vec![
+            TableCell::new(toolchain),
+            TableCell::new_with_alignment(version, 1, Alignment::Right),
+        ]));
+    }
+}
+
 // * - we don't need render impls for Operator or Account because those tokens are never embedded into a module,
 // only components.
 fn token_label(pk: &str) -> String {
@@ -439,6 +886,9 @@ mod test {
             insecure_skip_tls_verify,
             no_cache,
             wit,
+            interfaces,
+            verify_build,
+            check_compat,
         } = inspect_long.command;
         assert_eq!(target, LOCAL);
         assert_eq!(digest.unwrap(), "sha256:blah");
@@ -450,6 +900,9 @@ mod test {
         assert!(jwt_only);
         assert!(no_cache);
         assert!(!wit);
+        assert!(!interfaces);
+        assert!(verify_build.is_none());
+        assert!(check_compat.is_none());
 
         let inspect_short: Cmd = Parser::try_parse_from([
             "inspect",
@@ -477,6 +930,9 @@ mod test {
             insecure_skip_tls_verify,
             no_cache,
             wit,
+            interfaces,
+            verify_build,
+            check_compat,
         } = inspect_short.command;
         assert_eq!(target, REMOTE);
         assert_eq!(digest.unwrap(), "sha256:blah");
@@ -488,6 +944,9 @@ mod test {
         assert!(jwt_only);
         assert!(no_cache);
         assert!(!wit);
+        assert!(!interfaces);
+        assert!(verify_build.is_none());
+        assert!(check_compat.is_none());
 
         let cmd: Cmd = Parser::try_parse_from([
             "inspect",
@@ -516,6 +975,9 @@ mod test {
             insecure_skip_tls_verify,
             no_cache,
             wit,
+            interfaces,
+            verify_build,
+            check_compat,
         } = cmd.command;
         assert_eq!(target, HELLO_WORLD_OCI);
         assert_eq!(digest.unwrap(), HELLO_WORLD_SHA);
@@ -527,6 +989,9 @@ mod test {
         assert!(jwt_only);
         assert!(no_cache);
         assert!(!wit);
+        assert!(!interfaces);
+        assert!(verify_build.is_none());
+        assert!(check_compat.is_none());
 
         let short_cmd: Cmd = Parser::try_parse_from([
             "inspect",
@@ -556,6 +1021,9 @@ mod test {
             insecure_skip_tls_verify,
             no_cache,
             wit,
+            interfaces,
+            verify_build,
+            check_compat,
         } = short_cmd.command;
         assert_eq!(target, HELLO_WORLD_OCI);
         assert_eq!(digest.unwrap(), HELLO_WORLD_SHA);
@@ -567,5 +1035,213 @@ mod test {
         assert!(!jwt_only);
         assert!(no_cache);
         assert!(wit);
+        assert!(!interfaces);
+        assert!(verify_build.is_none());
+        assert!(check_compat.is_none());
+    }
+
+    #[tokio::test]
+    async fn build_metadata_is_verified_against_project_sources() -> Result<()> {
+        let project_dir = tempfile::tempdir()?;
+        let dir = project_dir.path();
+        std::fs::write(
+            dir.join("wasmcloud.toml"),
+            "name = \"verified\"\nlanguage = \"rust\"\ntype = \"component\"\nversion = \"0.1.0\"\n",
+        )?;
+        std::fs::write(dir.join("lib.rs"), "fn main() {}")?;
+        let config = load_config(Some(dir.to_path_buf()), Some(true)).await?;
+
+        let issuer = nkeys::KeyPair::new_account();
+        let mut claims = Claims::<Component>::with_dates(
+            "verified".into(),
+            issuer.public_key(),
+            nkeys::KeyPair::new_module().public_key(),
+            None,
+            None,
+            None,
+            false,
+            Some(0),
+            Some("0.1.0".into()),
+            None,
+        );
+        // Claims without build metadata can't be verified
+        assert!(verify_build_metadata(&claims, dir).await.is_err());
+
+        if let Some(component) = claims.metadata.as_mut() {
+            component.build = Some(BuildMetadata {
+                source_digest: Some(source_digest(&config.common)?),
+                ..Default::default()
+            });
+        }
+        verify_build_metadata(&claims, dir).await?;
+        let validation = wascap::jwt::validate_token::<Component>(&claims.encode(&issuer)?)?;
+        let output = render_component_claims(claims.clone(), validation, true);
+        assert!(output.text.contains("Source Digest"));
+        assert!(output.map.contains_key("build"));
+
+        std::fs::write(dir.join("lib.rs"), "fn main() { todo!() }")?;
+        assert!(verify_build_metadata(&claims, dir).await.is_err());
+        Ok(())
+    }
+
+    const PETS_WIT: &str = r#"
+package petclinic:pets@0.1.0;
+
+interface store {
+    count: func() -> u32;
+}
+
+world pets {
+    import wasi:logging/logging@0.1.0-draft;
+    import store;
+    import now: func() -> u64;
+    export wasi:http/incoming-handler@0.2.0;
+}
+
+package wasi:logging@0.1.0-draft {
+    interface logging {
+        log: func(message: string);
+    }
+}
+
+package wasi:http@0.2.0 {
+    interface incoming-handler {
+        handle: func();
+    }
+}
+"#;
+
+    #[test]
+    fn wit_worlds_are_summarized_with_versions() -> Result<()> {
+        let mut resolve = Resolve::default();
+        let package = resolve.push_str("pets.wit", PETS_WIT)?;
+        let world = resolve.select_world(package, Some("pets"))?;
+
+        let summaries = wit_world_summaries(&DecodedWasm::Component(resolve.clone(), world));
+        assert_eq!(summaries.len(), 1);
+        let pets = &summaries[0];
+        assert_eq!(pets.name, "petclinic:pets/pets");
+        let item = |name: &str, kind, version: Option<&str>| WitWorldItem {
+            name: name.to_string(),
+            kind,
+            version: version.map(ToString::to_string),
+        };
+        assert_eq!(
+            pets.imports,
+            [
+                item(
+                    "wasi:logging/logging",
+                    WitItemKind::Interface,
+                    Some("0.1.0-draft")
+                ),
+                item(
+                    "petclinic:pets/store",
+                    WitItemKind::Interface,
+                    Some("0.1.0")
+                ),
+                item("now", WitItemKind::Function, None),
+            ]
+        );
+        assert_eq!(
+            pets.exports,
+            [item(
+                "wasi:http/incoming-handler",
+                WitItemKind::Interface,
+                Some("0.2.0")
+            )]
+        );
+
+        // WIT packages, like the ones in provider archives, have every world summarized
+        let encoded = wit_component::encode(&resolve, package)?;
+        let decoded = wit_component::decode(&encoded)?;
+        assert_eq!(wit_world_summaries(&decoded), summaries);
+
+        let output = render_wit_interfaces(&decoded);
+        assert!(output.text.contains("wasi:http/incoming-handler"));
+        assert!(output.text.contains("0.1.0-draft"));
+        assert_eq!(output.map["worlds"][0]["exports"][0]["version"], "0.2.0");
+        Ok(())
+    }
+
+    const DB_WIT: &str = r#"
+package petclinic:db@0.1.0;
+
+world db {
+    export petclinic:pets/store@0.2.0;
+    export wasi:http/incoming-handler@0.2.1;
+}
+
+package petclinic:pets@0.2.0 {
+    interface store {
+        count: func() -> u32;
+    }
+}
+
+package wasi:http@0.2.1 {
+    interface incoming-handler {
+        handle: func();
+    }
+}
+"#;
+
+    #[test]
+    fn imports_are_checked_against_exports() -> Result<()> {
+        let mut resolve = Resolve::default();
+        let package = resolve.push_str("pets.wit", PETS_WIT)?;
+        let world = resolve.select_world(package, Some("pets"))?;
+        let component = DecodedWasm::Component(resolve, world);
+        let mut resolve = Resolve::default();
+        let package = resolve.push_str("db.wit", DB_WIT)?;
+        let provider = DecodedWasm::WitPackage(resolve, package);
+
+        // The provider exports an incompatible version of the store, and no logging
+        let compat = check_compat(&component, &provider);
+        let status = |interface: &str| {
+            compat
+                .iter()
+                .find(|e| e.interface == interface)
+                .map(|e| e.status)
+        };
+        assert_eq!(compat.len(), 2);
+        assert_eq!(
+            status("petclinic:pets/store"),
+            Some(CompatStatus::VersionMismatch)
+        );
+        assert_eq!(
+            status("wasi:logging/logging"),
+            Some(CompatStatus::NotExported)
+        );
+        let output = render_compat("pets", "db", &compat);
+        assert!(output.text.contains("would do nothing"));
+
+        // Providers don't import anything through links
+        assert!(check_compat(&provider, &component).is_empty());
+
+        // A component importing what the provider exports can be linked to it
+        let mut resolve = Resolve::default();
+        let package = resolve.push_str(
+            "client.wit",
+            "package petclinic:client;\nworld client { import wasi:http/incoming-handler@0.2.0; }\npackage wasi:http@0.2.0 { interface incoming-handler { handle: func(); } }\n",
+        )?;
+        let world = resolve.select_world(package, Some("client"))?;
+        let compat = check_compat(&DecodedWasm::Component(resolve, world), &provider);
+        assert_eq!(compat[0].status, CompatStatus::Satisfied);
+        assert_eq!(compat[0].export_version.as_deref(), Some("0.2.1"));
+        let output = render_compat("client", "db", &compat);
+        assert_eq!(output.map["links"][0]["namespace"], "wasi");
+        assert_eq!(output.map["links"][0]["interfaces"][0], "incoming-handler");
+        Ok(())
+    }
+
+    #[test]
+    fn interface_versions_are_compatible_by_semver() {
+        assert!(versions_compatible(None, None));
+        assert!(versions_compatible(Some("0.2.0"), Some("0.2.3")));
+        assert!(versions_compatible(Some("1.1.0"), Some("1.4.0")));
+        assert!(!versions_compatible(Some("0.2.3"), Some("0.2.0")));
+        assert!(!versions_compatible(Some("0.2.0"), Some("0.3.0")));
+        assert!(!versions_compatible(Some("1.0.0"), Some("2.0.0")));
+        assert!(!versions_compatible(Some("0.1.0-draft"), Some("0.1.0")));
+        assert!(!versions_compatible(Some("0.2.0"), None));
     }
 }
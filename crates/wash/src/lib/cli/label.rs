@@ -1,13 +1,16 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
 use serde_json::json;
+use tokio::time::Duration;
 use tracing::{error, warn};
 
 use crate::lib::{
-    common::{boxed_err_to_anyhow, find_host_id},
+    common::{boxed_err_to_anyhow, find_host_id_with, HostMatchOpts},
     config::WashConnectionOptions,
+    context::default_component_operation_timeout_ms,
+    wait::wait_for_host_labels,
 };
 
 use super::{CliConnectionOpts, CommandOutput};
@@ -23,6 +26,9 @@ pub struct LabelHostCommand {
     #[clap(name = "host-id")]
     pub host_id: String,
 
+    #[clap(flatten)]
+    pub host_match: HostMatchOpts,
+
     /// Delete the label, instead of adding it
     #[clap(long = "delete", default_value = "false")]
     pub delete: bool,
@@ -30,13 +36,45 @@ pub struct LabelHostCommand {
     /// Host label in the form of a `[key]=[value]` pair, e.g. "cloud=aws". When `--delete` is set, only the key is provided
     #[clap(name = "label", alias = "label", value_delimiter = ',')]
     pub labels: Vec<String>,
+
+    /// By default, the command waits until the host inventory reflects the label changes. If this
+    /// flag is passed, the command returns as soon as the host acknowledges them
+    #[clap(long = "skip-wait")]
+    pub skip_wait: bool,
+
+    /// How long to wait for the host inventory to reflect the label changes, in milliseconds
+    #[clap(long = "wait-timeout-ms", default_value_t = default_component_operation_timeout_ms())]
+    pub wait_timeout_ms: u64,
+}
+
+/// How often the host inventory is polled while waiting for label changes
+const LABEL_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The label changes in `processed` that `current`, the labels a host reports, doesn't reflect
+/// yet. Labels are given as `key=value`, or as just the key when deleting
+#[must_use]
+pub fn pending_labels(
+    current: &BTreeMap<String, String>,
+    processed: &[(&str, &str)],
+    delete: bool,
+) -> Vec<String> {
+    processed
+        .iter()
+        .filter_map(|(key, value)| match (delete, current.get(*key)) {
+            (true, Some(_)) => Some((*key).to_string()),
+            (false, Some(v)) if v == value => None,
+            (false, _) => Some(format!("{key}={value}")),
+            (true, None) => None,
+        })
+        .collect()
 }
 
 pub async fn handle_label_host(cmd: LabelHostCommand) -> Result<CommandOutput> {
     let wco: WashConnectionOptions = cmd.opts.try_into()?;
     let client = wco.into_ctl_client(None).await?;
 
-    let (host_id, friendly_name) = find_host_id(&cmd.host_id, &client).await?;
+    let (host_id, friendly_name) =
+        find_host_id_with(&cmd.host_id, &client, &cmd.host_match).await?;
 
     let friendly_name = if friendly_name.is_empty() {
         host_id.to_string()
@@ -92,6 +130,27 @@ pub async fn handle_label_host(cmd: LabelHostCommand) -> Result<CommandOutput> {
         }
     }
 
+    let wait = succeeded && !cmd.skip_wait && !processed.is_empty();
+    if wait {
+        wait_for_host_labels(
+            Duration::from_millis(cmd.wait_timeout_ms),
+            LABEL_POLL_INTERVAL,
+            || async {
+                let inventory = client
+                    .get_host_inventory(&host_id)
+                    .await
+                    .map_err(boxed_err_to_anyhow)?
+                    .into_data()
+                    .context("no inventory returned for host")?;
+                Ok(pending_labels(inventory.labels(), &processed, cmd.delete))
+            },
+        )
+        .await
+        .with_context(|| {
+            format!("Host `{friendly_name}` acknowledged the label changes but never reported them")
+        })?;
+    }
+
     let output = format!(
         "Host `{friendly_name}` {} with `{}`",
         if cmd.delete { "unlabeled" } else { "labeled" },
@@ -108,6 +167,7 @@ pub async fn handle_label_host(cmd: LabelHostCommand) -> Result<CommandOutput> {
             ("success".into(), json!(succeeded)),
             ("deleted".into(), json!(cmd.delete)),
             ("processed".into(), json!(processed)),
+            ("waited".into(), json!(wait)),
         ]),
     ))
 }
@@ -116,7 +176,9 @@ pub async fn handle_label_host(cmd: LabelHostCommand) -> Result<CommandOutput> {
 mod tests {
     use clap::Parser;
 
-    use super::LabelHostCommand;
+    use std::collections::BTreeMap;
+
+    use super::{pending_labels, LabelHostCommand};
 
     const HOST_ID: &str = "host-id";
 
@@ -163,4 +225,23 @@ mod tests {
         assert!(!delete);
         assert_eq!(labels, vec!["key1=value1"]);
     }
+
+    /// Ensure label changes are only settled once the host reports them
+    #[test]
+    fn test_pending_labels() {
+        let current = BTreeMap::from([
+            ("cloud".to_string(), "aws".to_string()),
+            ("zone".to_string(), "us-east-1".to_string()),
+        ]);
+
+        let put = [("cloud", "aws"), ("zone", "us-west-2"), ("gpu", "true")];
+        assert_eq!(
+            pending_labels(&current, &put, false),
+            vec!["zone=us-west-2", "gpu=true"]
+        );
+        assert!(pending_labels(&current, &[("cloud", "aws")], false).is_empty());
+
+        let delete = [("cloud", ""), ("gpu", "")];
+        assert_eq!(pending_labels(&current, &delete, true), vec!["cloud"]);
+    }
 }
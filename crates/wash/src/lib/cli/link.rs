@@ -1,9 +1,12 @@
-use anyhow::{Context, Result};
-use clap::Parser;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use clap::{Args, Parser};
 use wasmcloud_control_interface::{CtlResponse, Link};
 
 use crate::lib::{
     cli::CliConnectionOpts, common::boxed_err_to_anyhow, config::WashConnectionOptions,
+    links::LinksDiff,
 };
 
 use super::validate_component_id;
@@ -14,7 +17,7 @@ pub struct LinkDelCommand {
     pub opts: CliConnectionOpts,
 
     /// Component ID or name of the source of the link.
-    #[clap(name = "source-id", value_parser = validate_component_id, required_unless_present("all"))]
+    #[clap(name = "source-id", value_parser = validate_component_id, required_unless_present_any(["all", "all_for_source"]))]
     pub source_id: Option<String>,
 
     /// Link name, defaults to "default"
@@ -22,17 +25,34 @@ pub struct LinkDelCommand {
     pub link_name: Option<String>,
 
     /// WIT namespace of the link
-    #[clap(name = "wit-namespace", required_unless_present("all"))]
+    #[clap(name = "wit-namespace", required_unless_present_any(["all", "all_for_source"]))]
     pub wit_namespace: Option<String>,
 
     /// WIT package of the link
-    #[clap(name = "wit-package", required_unless_present("all"))]
+    #[clap(name = "wit-package", required_unless_present_any(["all", "all_for_source"]))]
     pub wit_package: Option<String>,
 
+    /// Only delete these interfaces from the link, keeping the rest of its interfaces linked
+    #[clap(long = "interface", conflicts_with_all = ["all", "all_for_source"])]
+    pub interfaces: Vec<String>,
+
     /// Delete all links present in the cluster (with prompt)
     #[clap(long = "all", default_value = "false")]
     pub all: bool,
 
+    /// Delete every link where the given component is the source, e.g. when decommissioning it
+    #[clap(
+        long = "all-for-source",
+        name = "all_for_source",
+        value_parser = validate_component_id,
+        conflicts_with_all = ["all", "source-id"]
+    )]
+    pub all_for_source: Option<String>,
+
+    /// Only report the links that would be deleted by `--all-for-source`, without deleting them
+    #[clap(long = "dry-run", requires = "all_for_source")]
+    pub dry_run: bool,
+
     /// Force an operation that is otherwise seen as risky
     #[clap(long = "force", default_value = "false")]
     pub force: bool,
@@ -82,6 +102,106 @@ pub struct LinkPutCommand {
 pub struct LinkQueryCommand {
     #[clap(flatten)]
     pub opts: CliConnectionOpts,
+
+    #[clap(flatten)]
+    pub filter: LinkQueryFilter,
+
+    /// Show the links as a tree grouped by source component instead of a table
+    #[clap(long = "tree")]
+    pub tree: bool,
+}
+
+/// Narrow the links returned by `wash link query` down to those matching every given field
+#[derive(Args, Debug, Clone, Default)]
+pub struct LinkQueryFilter {
+    /// Only show links from this source component
+    #[clap(long = "source-id")]
+    pub source_id: Option<String>,
+
+    /// Only show links to this target component
+    #[clap(long = "target")]
+    pub target: Option<String>,
+
+    /// Only show links with this link name
+    #[clap(short = 'l', long = "link-name", alias = "name")]
+    pub link_name: Option<String>,
+
+    /// Only show links on this WIT namespace
+    #[clap(long = "wit-namespace")]
+    pub wit_namespace: Option<String>,
+
+    /// Only show links on this WIT package
+    #[clap(long = "wit-package")]
+    pub wit_package: Option<String>,
+
+    /// Only show links covering any of these interfaces
+    #[clap(long = "interface")]
+    pub interfaces: Vec<String>,
+}
+
+impl LinkQueryFilter {
+    /// Whether the link matches every field set on the filter
+    #[must_use]
+    pub fn matches(&self, link: &Link) -> bool {
+        self.source_id
+            .as_deref()
+            .is_none_or(|s| link.source_id() == s)
+            && self.target.as_deref().is_none_or(|t| link.target() == t)
+            && self.link_name.as_deref().is_none_or(|n| link.name() == n)
+            && self
+                .wit_namespace
+                .as_deref()
+                .is_none_or(|ns| link.wit_namespace() == ns)
+            && self
+                .wit_package
+                .as_deref()
+                .is_none_or(|p| link.wit_package() == p)
+            && (self.interfaces.is_empty()
+                || link
+                    .interfaces()
+                    .iter()
+                    .any(|i| self.interfaces.contains(i)))
+    }
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct LinkVerifyCommand {
+    #[clap(flatten)]
+    pub opts: CliConnectionOpts,
+
+    /// Path to a snapshot of links to compare against, either a JSON array of links or the output
+    /// of `wash get links -o json`
+    #[clap(long = "baseline")]
+    pub baseline: PathBuf,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct LinkApplyCommand {
+    #[clap(flatten)]
+    pub opts: CliConnectionOpts,
+
+    /// Path to the file declaring the links the lattice should have, in the format written by
+    /// `wash link export` (YAML or JSON)
+    #[clap(name = "file")]
+    pub file: PathBuf,
+
+    /// Delete links in the lattice that aren't declared in the file
+    #[clap(long = "prune")]
+    pub prune: bool,
+
+    /// Only print the changes that would be made, without applying them
+    #[clap(long = "dry-run")]
+    pub dry_run: bool,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct LinkExportCommand {
+    #[clap(flatten)]
+    pub opts: CliConnectionOpts,
+
+    /// Write the links to this file instead of printing them
+    #[clap(long = "file", short = 'f')]
+    pub file: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -97,6 +217,64 @@ pub enum LinkCommand {
     /// Delete a link
     #[clap(name = "del", alias = "delete")]
     Del(LinkDelCommand),
+
+    /// Report any drift between the links in the lattice and a baseline snapshot
+    #[clap(name = "verify")]
+    Verify(LinkVerifyCommand),
+
+    /// Reconcile the links in the lattice with the links declared in a file
+    #[clap(name = "apply")]
+    Apply(LinkApplyCommand),
+
+    /// Export the links in the lattice as a file that `wash link apply` accepts
+    #[clap(name = "export")]
+    Export(LinkExportCommand),
+}
+
+/// The document written by `wash link export` and read by `wash link apply`
+#[derive(Debug, Default, serde::Serialize)]
+pub struct LinksFile {
+    pub links: Vec<Link>,
+}
+
+/// Error returned by `wash link verify` when the links in the lattice differ from the baseline
+#[derive(Debug, thiserror::Error)]
+#[error("links have drifted from baseline `{}`:\n{diff}", .baseline.display())]
+pub struct LinksDrifted {
+    pub baseline: PathBuf,
+    pub diff: LinksDiff,
+}
+
+/// Check the interfaces given for a link before sending it to the lattice: every interface needs a
+/// name and may only be given once
+pub fn validate_interfaces(interfaces: &[String]) -> Result<()> {
+    for (idx, interface) in interfaces.iter().enumerate() {
+        if interface.trim().is_empty() {
+            bail!("interface names cannot be empty");
+        }
+        if interfaces[..idx].contains(interface) {
+            bail!("interface `{interface}` was given more than once");
+        }
+    }
+    Ok(())
+}
+
+/// Load a snapshot of links from a file, accepting either a plain array of links or a document
+/// with a `links` field such as the JSON output of `wash get links` or a file written by
+/// `wash link export`. YAML is accepted as well as JSON
+pub fn load_links_snapshot(path: &Path) -> Result<Vec<Link>> {
+    let contents = std::fs::read(path)
+        .with_context(|| format!("failed to read links snapshot `{}`", path.display()))?;
+    let value: serde_json::Value = serde_yaml::from_slice(&contents)
+        .with_context(|| format!("failed to parse links snapshot `{}`", path.display()))?;
+    let links = match value {
+        serde_json::Value::Object(mut map) => map
+            .remove("links")
+            .with_context(|| format!("links snapshot `{}` has no `links` field", path.display()))?,
+        value => value,
+    };
+    serde_json::from_value(links)
+        .with_context(|| format!("invalid links in snapshot `{}`", path.display()))
 }
 
 /// Query links for a given Wash instance
@@ -220,3 +398,48 @@ pub async fn put_link(wco: WashConnectionOptions, link: Link) -> Result<CtlRespo
             )
         })
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn interfaces_are_validated_and_filtered() {
+        let interfaces = |names: &[&str]| names.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(validate_interfaces(&interfaces(&["store", "atomics"])).is_ok());
+        assert!(validate_interfaces(&interfaces(&["store", " "])).is_err());
+        assert!(validate_interfaces(&interfaces(&["store", "atomics", "store"])).is_err());
+
+        let link = Link::builder()
+            .source_id("echo")
+            .target("kv-redis")
+            .name("default")
+            .wit_namespace("wasi")
+            .wit_package("keyvalue")
+            .interfaces(interfaces(&["store", "atomics"]))
+            .build()
+            .unwrap();
+        assert!(LinkQueryFilter::default().matches(&link));
+        let filter = LinkQueryFilter {
+            source_id: Some("echo".into()),
+            interfaces: interfaces(&["atomics", "batch"]),
+            ..Default::default()
+        };
+        assert!(filter.matches(&link));
+        let filter = LinkQueryFilter {
+            interfaces: interfaces(&["batch"]),
+            ..Default::default()
+        };
+        assert!(!filter.matches(&link));
+        let filter = LinkQueryFilter {
+            target: Some("kv-nats".into()),
+            ..Default::default()
+        };
+        assert!(!filter.matches(&link));
+        let filter = LinkQueryFilter {
+            wit_package: Some("http".into()),
+            ..Default::default()
+        };
+        assert!(!filter.matches(&link));
+    }
+}
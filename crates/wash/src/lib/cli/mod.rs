@@ -10,6 +10,7 @@
 
 use std::collections::HashMap;
 use std::error::Error;
+use std::io::Write;
 use std::path::PathBuf;
 use std::str::FromStr;
 
@@ -18,6 +19,7 @@ use clap::Args;
 use nkeys::{KeyPair, KeyPairType};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use termcolor::{Color, ColorSpec, WriteColor};
 use tracing::info;
 use wasm_pkg_client::{
     caching::{CachingClient, FileCache},
@@ -40,12 +42,18 @@ pub mod capture;
 pub mod claims;
 pub mod dev;
 pub mod get;
+pub mod host;
 pub mod inspect;
 pub mod label;
 pub mod link;
 pub mod output;
 pub mod par;
+pub mod progress;
+pub mod provider;
+pub mod receipt;
 pub mod registry;
+pub mod render;
+pub mod rollout;
 pub mod scale;
 pub mod spy;
 pub mod start;
@@ -55,8 +63,25 @@ pub mod update;
 /// Used for displaying human-readable output vs JSON format
 #[derive(Debug, Copy, Clone, Eq, Serialize, Deserialize, PartialEq)]
 pub enum OutputKind {
+    /// Human readable output, which is a table for commands that list things. Also accepted as
+    /// `table`
     Text,
     Json,
+    /// Newline-delimited JSON. Batch operations stream one JSON object per completed target as it
+    /// finishes, and the final output is printed as a single JSON line
+    Ndjson,
+    /// The same fields as `json`, as a YAML document
+    Yaml,
+    /// Human readable output followed by a table of every field of the JSON output
+    Wide,
+}
+
+impl OutputKind {
+    /// Whether the output is machine readable, i.e. only the fields of the output map are printed
+    #[must_use]
+    pub const fn is_structured(&self) -> bool {
+        matches!(self, Self::Json | Self::Ndjson | Self::Yaml)
+    }
 }
 
 impl FromStr for OutputKind {
@@ -65,7 +90,10 @@ impl FromStr for OutputKind {
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         match s {
             "json" => Ok(Self::Json),
-            "text" => Ok(Self::Text),
+            "ndjson" => Ok(Self::Ndjson),
+            "text" | "table" => Ok(Self::Text),
+            "yaml" => Ok(Self::Yaml),
+            "wide" => Ok(Self::Wide),
             _ => Err(OutputParseErr),
         }
     }
@@ -85,6 +113,43 @@ impl std::fmt::Display for OutputParseErr {
     }
 }
 
+/// Streams the results of a batch operation as newline-delimited JSON, one complete object per
+/// line, flushing after every line so consumers see each result as soon as it completes
+pub struct NdjsonStream<W: Write = std::io::Stdout> {
+    writer: W,
+}
+
+impl NdjsonStream {
+    /// Stream to stdout
+    #[must_use]
+    pub fn stdout() -> Self {
+        Self::new(std::io::stdout())
+    }
+
+    /// Stream to stdout if the output kind is `ndjson`
+    #[must_use]
+    pub fn for_output(output_kind: OutputKind) -> Option<Self> {
+        (output_kind == OutputKind::Ndjson).then(Self::stdout)
+    }
+}
+
+impl<W: Write> NdjsonStream<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Write a record as a single JSON line
+    pub fn emit(&mut self, record: &impl Serialize) -> Result<()> {
+        serde_json::to_writer(&mut self.writer, record).context("failed to serialize result")?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush().context("failed to write result")
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
 #[derive(Default)]
 pub struct CommandOutput {
     pub map: std::collections::HashMap<String, serde_json::Value>,
@@ -112,6 +177,144 @@ impl CommandOutput {
             text: text_string,
         }
     }
+
+    /// Output of a command run with `--dry-run`: the control command that would have been sent
+    /// (e.g. `start component [echo]`) and the host it would have been sent to. `map` holds the
+    /// resolved arguments of the control command
+    pub fn dry_run(
+        host_id: impl Into<String>,
+        action: impl Into<String>,
+        mut map: std::collections::HashMap<String, serde_json::Value>,
+    ) -> Self {
+        let host_id = host_id.into();
+        let action = action.into();
+        let text = format!("Dry run, nothing was sent. Host [{host_id}] would receive: {action}");
+        map.insert("dry_run".into(), true.into());
+        map.insert("host_id".into(), host_id.into());
+        map.insert("action".into(), action.into());
+        map.insert("result".into(), text.clone().into());
+        Self { map, text }
+    }
+}
+
+/// The overall outcome of a command, used to pick a color when rendering human readable output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputStatus {
+    Success,
+    Partial,
+    Failure,
+}
+
+impl CommandOutput {
+    /// The status of a successfully returned output. Commands that only partially succeeded signal
+    /// it by setting `"partial": true` in their JSON map, and ones that report their own failure set
+    /// `"success": false`.
+    #[must_use]
+    pub fn status(&self) -> OutputStatus {
+        if self.map.get("success") == Some(&json!(false)) {
+            OutputStatus::Failure
+        } else if self.map.get("partial") == Some(&json!(true)) {
+            OutputStatus::Partial
+        } else {
+            OutputStatus::Success
+        }
+    }
+}
+
+/// A destination for rendered command output, e.g. stdout, a file, or an in-memory buffer in
+/// tests. Anything implementing [`std::io::Write`] is a sink; embedders can implement this for
+/// their own types to route output elsewhere
+pub trait OutputSink {
+    /// Write a fully rendered command output
+    fn write_output(&mut self, rendered: &[u8]) -> std::io::Result<()>;
+}
+
+impl<W: Write> OutputSink for W {
+    fn write_output(&mut self, rendered: &[u8]) -> std::io::Result<()> {
+        self.write_all(rendered)?;
+        self.flush()
+    }
+}
+
+impl CommandOutput {
+    /// Render the output the way wash prints it: the JSON map (pretty printed, on a single line for
+    /// `ndjson`, or as YAML), or the human readable text, colored by status if `color` is set and
+    /// followed by a table of the map for `wide`
+    #[must_use]
+    pub fn render(&self, output_kind: OutputKind, color: bool) -> Vec<u8> {
+        match output_kind {
+            OutputKind::Yaml => format!("\n{}", render::yaml(&self.map)).into_bytes(),
+            OutputKind::Wide => {
+                let mut rendered = self.render(OutputKind::Text, color);
+                let fields = render::fields_table(&self.map, &self.text);
+                if !fields.is_empty() {
+                    rendered.push(b'\n');
+                    rendered.extend_from_slice(fields.as_bytes());
+                }
+                rendered
+            }
+            OutputKind::Json => format!(
+                "\n{}\n",
+                serde_json::to_string_pretty(&self.map).unwrap_or_default()
+            )
+            .into_bytes(),
+            OutputKind::Ndjson => {
+                format!("{}\n", serde_json::to_string(&self.map).unwrap_or_default()).into_bytes()
+            }
+            OutputKind::Text => {
+                let mut rendered = b"\n".to_vec();
+                // Writing to a Vec can't fail
+                let _ = if color {
+                    write_text_output(
+                        &mut termcolor::Ansi::new(&mut rendered),
+                        &self.text,
+                        self.status(),
+                    )
+                } else {
+                    write_text_output(
+                        &mut termcolor::NoColor::new(&mut rendered),
+                        &self.text,
+                        self.status(),
+                    )
+                };
+                rendered
+            }
+        }
+    }
+
+    /// Render the output and write it to `sink`
+    pub fn write_to(
+        &self,
+        sink: &mut impl OutputSink,
+        output_kind: OutputKind,
+        color: bool,
+    ) -> std::io::Result<()> {
+        sink.write_output(&self.render(output_kind, color))
+    }
+}
+
+/// Whether human readable output should be colored. Color is disabled by `--no-color` or a
+/// non-empty `NO_COLOR` environment variable, and is otherwise only used when writing to a terminal.
+#[must_use]
+pub fn use_color(no_color: bool, is_terminal: bool) -> bool {
+    !no_color && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty()) && is_terminal
+}
+
+/// Write human readable output followed by a newline, colored by status if the writer supports it
+pub fn write_text_output(
+    w: &mut impl WriteColor,
+    text: &str,
+    status: OutputStatus,
+) -> std::io::Result<()> {
+    let color = match status {
+        OutputStatus::Success => Color::Green,
+        OutputStatus::Partial => Color::Yellow,
+        OutputStatus::Failure => Color::Red,
+    };
+    w.set_color(ColorSpec::new().set_fg(Some(color)))?;
+    write!(w, "{text}")?;
+    w.reset()?;
+    writeln!(w)
 }
 
 impl From<String> for CommandOutput {
@@ -194,6 +397,11 @@ pub struct CliConnectionOpts {
     )]
     pub timeout_ms: u64,
 
+    /// Close the CTL connection after it has been idle for this many milliseconds. Mostly useful
+    /// for long running commands and embedders, defaults to keeping the connection open
+    #[clap(long = "idle-timeout-ms", env = "WASH_CTL_IDLE_TIMEOUT_MS")]
+    pub idle_timeout_ms: Option<u64>,
+
     /// Name of a context to use for CTL connection and authentication
     #[clap(long = "context")]
     pub context: Option<String>,
@@ -212,6 +420,7 @@ impl Default for CliConnectionOpts {
             js_domain: None,
             lattice: Some(DEFAULT_LATTICE.to_string()),
             timeout_ms: DEFAULT_NATS_TIMEOUT_MS,
+            idle_timeout_ms: None,
             context: None,
         }
     }
@@ -232,6 +441,7 @@ impl TryFrom<CliConnectionOpts> for WashConnectionOptions {
             js_domain,
             lattice,
             timeout_ms,
+            idle_timeout_ms,
             context,
         }: CliConnectionOpts,
     ) -> Result<Self> {
@@ -258,6 +468,7 @@ impl TryFrom<CliConnectionOpts> for WashConnectionOptions {
             js_domain,
             lattice,
             timeout_ms,
+            idle_timeout_ms,
             ctx,
         })
     }
@@ -429,13 +640,13 @@ pub fn extract_keypair(
             // No default key, generating for user
             None if !disable_keygen => {
                 match output_kind {
-                    OutputKind::Text => info!(
+                    OutputKind::Text | OutputKind::Wide => info!(
                         "No keypair found in \"{}\".
                     We will generate one for you and place it there.
                     If you'd like to use an existing key, you can supply it on the CLI as a flag.\n",
                         path.display()
                     ),
-                    OutputKind::Json => {
+                    OutputKind::Json | OutputKind::Ndjson | OutputKind::Yaml => {
                         info!(
                             "{}",
                             json!({"status": "No existing keypair found, automatically generated and stored a new one", "path": path, "keygen": "true"})
@@ -498,7 +709,7 @@ fn valid_component_char(c: char) -> bool {
     c.is_alphanumeric() || c == '_' || c == '-'
 }
 
-fn determine_directory(directory: Option<PathBuf>) -> Result<PathBuf> {
+pub(crate) fn determine_directory(directory: Option<PathBuf>) -> Result<PathBuf> {
     if let Some(d) = directory {
         Ok(d)
     } else {
@@ -578,7 +789,10 @@ mod test {
         context::{fs::ContextDir, ContextManager, WashContext},
     };
 
-    use super::{CliConnectionOpts, CommonPackageArgs};
+    use super::{
+        use_color, write_text_output, CliConnectionOpts, CommandOutput, CommonPackageArgs,
+        OutputKind, OutputStatus,
+    };
 
     struct CurDir {
         cwd: PathBuf,
@@ -820,4 +1034,124 @@ mod test {
             .namespace_registry(&"wrpc".parse().unwrap())
             .expect("Should have a namespace set for wrpc");
     }
+
+    #[test]
+    fn text_output_is_only_colored_when_enabled() {
+        let output = CommandOutput::from("Provider started");
+        assert_eq!(output.status(), OutputStatus::Success);
+
+        let mut plain = termcolor::NoColor::new(Vec::new());
+        write_text_output(&mut plain, &output.text, output.status()).unwrap();
+        let plain = String::from_utf8(plain.into_inner()).unwrap();
+        assert_eq!(plain, "Provider started\n");
+        assert!(!plain.contains('\x1b'));
+
+        // A writer standing in for a TTY
+        let mut tty = termcolor::Ansi::new(Vec::new());
+        write_text_output(&mut tty, &output.text, OutputStatus::Partial).unwrap();
+        let colored = String::from_utf8(tty.into_inner()).unwrap();
+        assert!(colored.starts_with("\x1b[0m\x1b[33m"));
+        assert!(colored.contains("Provider started"));
+
+        assert!(!use_color(true, true), "--no-color should disable color");
+        assert!(
+            !use_color(false, false),
+            "non-terminals should not be colored"
+        );
+    }
+
+    #[test]
+    fn output_is_rendered_into_sink() {
+        let output = CommandOutput::new(
+            "Scaled component [hello] to 3 max instances",
+            std::collections::HashMap::from([("partial".to_string(), serde_json::json!(true))]),
+        );
+
+        let mut sink = Vec::new();
+        output
+            .write_to(&mut sink, OutputKind::Text, false)
+            .expect("should write to memory");
+        assert_eq!(sink, b"\nScaled component [hello] to 3 max instances\n");
+
+        let mut sink = Vec::new();
+        output
+            .write_to(&mut sink, OutputKind::Text, true)
+            .expect("should write to memory");
+        assert_eq!(
+            sink,
+            b"\n\x1b[0m\x1b[33mScaled component [hello] to 3 max instances\x1b[0m\n"
+        );
+
+        let mut sink = Vec::new();
+        output
+            .write_to(&mut sink, OutputKind::Json, true)
+            .expect("should write to memory");
+        assert_eq!(sink, b"\n{\n  \"partial\": true\n}\n");
+
+        let mut sink = Vec::new();
+        output
+            .write_to(&mut sink, OutputKind::Ndjson, false)
+            .expect("should write to memory");
+        assert_eq!(sink, b"{\"partial\":true}\n");
+
+        let mut sink = Vec::new();
+        output
+            .write_to(&mut sink, OutputKind::Yaml, false)
+            .expect("should write to memory");
+        assert_eq!(sink, b"\npartial: true\n");
+    }
+
+    #[test]
+    fn wide_output_adds_every_field_to_the_text() {
+        let text = "Component [hello] scaled on host [NHOST] to 3 max concurrent instances";
+        let output = CommandOutput::new(
+            text,
+            std::collections::HashMap::from([
+                ("host_id".to_string(), serde_json::json!("NHOST")),
+                ("max_instances".to_string(), serde_json::json!(3)),
+                ("annotations".to_string(), serde_json::json!({"team": "a"})),
+                ("result".to_string(), serde_json::json!(text)),
+            ]),
+        );
+        let rendered = String::from_utf8(output.render(OutputKind::Wide, false))
+            .expect("output should be UTF-8");
+        assert!(rendered.starts_with(&format!("\n{text}\n\n")));
+        let rows = rendered
+            .lines()
+            .skip(3)
+            .map(|line| line.split_whitespace().collect::<Vec<_>>())
+            .filter(|cells| !cells.is_empty())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            vec![
+                vec!["Field", "Value"],
+                vec!["annotations", r#"{"team":"a"}"#],
+                vec!["host_id", "NHOST"],
+                vec!["max_instances", "3"],
+            ]
+        );
+
+        assert_eq!("table".parse::<OutputKind>(), Ok(OutputKind::Text));
+        assert_eq!("yaml".parse::<OutputKind>(), Ok(OutputKind::Yaml));
+        assert!(OutputKind::Yaml.is_structured());
+        assert!(!OutputKind::Wide.is_structured());
+    }
+
+    #[test]
+    fn dry_run_output_names_the_host_and_command() {
+        let output = CommandOutput::dry_run(
+            "NHOST",
+            "stop provider [http-server]",
+            std::collections::HashMap::from([("provider_id".into(), "http-server".into())]),
+        );
+        assert_eq!(
+            output.text,
+            "Dry run, nothing was sent. Host [NHOST] would receive: stop provider [http-server]"
+        );
+        assert_eq!(output.map["dry_run"], true);
+        assert_eq!(output.map["host_id"], "NHOST");
+        assert_eq!(output.map["action"], "stop provider [http-server]");
+        assert_eq!(output.map["provider_id"], "http-server");
+    }
 }
@@ -96,6 +96,7 @@ pub struct LabelHostCommandOutput {
     pub success: bool,
     pub deleted: bool,
     pub processed: Vec<(String, String)>,
+    pub waited: bool,
 }
 
 /// JSON output representation of the `wash up` command
@@ -1,4 +1,4 @@
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use provider_archive::ProviderArchive;
 use std::path::PathBuf;
 
@@ -8,6 +8,101 @@ pub fn detect_arch() -> String {
     format!("{}-{}", std::env::consts::ARCH, std::env::consts::OS)
 }
 
+/// Architectures targets can be for, as Rust names them
+const TARGET_ARCHES: &[&str] = &[
+    "x86",
+    "x86_64",
+    "arm",
+    "aarch64",
+    "loongarch64",
+    "mips",
+    "mips64",
+    "powerpc",
+    "powerpc64",
+    "riscv32",
+    "riscv64",
+    "s390x",
+    "sparc64",
+];
+
+/// Operating systems targets can be for, as Rust names them
+const TARGET_OSES: &[&str] = &[
+    "linux",
+    "macos",
+    "windows",
+    "freebsd",
+    "netbsd",
+    "openbsd",
+    "dragonfly",
+    "illumos",
+    "solaris",
+    "android",
+];
+
+/// Parse a provider binary given as `PATH` for `default_target`, or as `TARGET=PATH`. Targets are
+/// given as `ARCH-OS` like `x86_64-linux`, or as `OS/ARCH` like `linux/amd64`, which is converted
+/// to `ARCH-OS` using Rust's names for architectures and operating systems. Anything before a `=`
+/// that looks like a target has to be a known one, so a path containing `=` has to start with
+/// `./`
+pub fn parse_provider_binary(value: &str, default_target: &str) -> Result<(String, PathBuf)> {
+    match value.split_once('=') {
+        Some((target, path)) if is_target(target) => {
+            let target = parse_target(target).with_context(|| {
+                format!("invalid target in [{value}], start a path containing `=` with `./`")
+            })?;
+            Ok((target, path.into()))
+        }
+        _ => Ok((default_target.to_string(), value.into())),
+    }
+}
+
+/// Whether `value` looks like a target rather than part of a path
+fn is_target(value: &str) -> bool {
+    !value.is_empty()
+        && value.matches('/').count() <= 1
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '/'))
+}
+
+/// Parse an `ARCH-OS` or `OS/ARCH` target into `ARCH-OS`, failing if the architecture or the
+/// operating system isn't known
+fn parse_target(target: &str) -> Result<String> {
+    let (arch, os) = match target.split_once('/') {
+        Some((os, arch)) => {
+            let os = match os {
+                "darwin" => "macos",
+                other => other,
+            };
+            let arch = match arch {
+                "amd64" => "x86_64",
+                "arm64" => "aarch64",
+                "386" => "x86",
+                "ppc64" | "ppc64le" => "powerpc64",
+                "loong64" => "loongarch64",
+                other => other,
+            };
+            (arch, os)
+        }
+        None => target
+            .split_once('-')
+            .with_context(|| format!("target [{target}] is not in format ARCH-OS or OS/ARCH"))?,
+    };
+    if !TARGET_ARCHES.contains(&arch) {
+        bail!(
+            "unknown architecture [{arch}] in target [{target}], expected one of {}",
+            TARGET_ARCHES.join(", ")
+        );
+    }
+    if !TARGET_OSES.contains(&os) {
+        bail!(
+            "unknown operating system [{os}] in target [{target}], expected one of {}",
+            TARGET_OSES.join(", ")
+        );
+    }
+    Ok(format!("{arch}-{os}"))
+}
+
 pub struct ParCreateArgs {
     pub vendor: String,
     pub revision: Option<i32>,
@@ -61,8 +156,80 @@ pub async fn insert_provider_binary(
     Ok(par)
 }
 
+/// Add a binary for each target to a provider archive, failing if a target is given twice
+pub fn add_provider_binaries(
+    par: &mut ProviderArchive,
+    binaries: &[(String, Vec<u8>)],
+) -> Result<()> {
+    for (target, bytes) in binaries {
+        if par.targets().contains(target) {
+            bail!("more than one binary given for target [{target}]");
+        }
+        par.add_library(target, bytes).map_err(convert_error)?;
+    }
+    Ok(())
+}
+
 /// Converts error from Send + Sync error to standard anyhow error
 #[must_use]
 pub fn convert_error(e: Box<dyn ::std::error::Error + Send + Sync>) -> anyhow::Error {
     anyhow!(e.to_string())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn provider_binaries_are_parsed_with_targets() -> Result<()> {
+        let default = "x86_64-linux";
+        assert_eq!(
+            parse_provider_binary("./provider", default)?,
+            (default.to_string(), PathBuf::from("./provider"))
+        );
+        assert_eq!(
+            parse_provider_binary("aarch64-macos=build/provider", default)?,
+            ("aarch64-macos".to_string(), PathBuf::from("build/provider"))
+        );
+        assert_eq!(
+            parse_provider_binary("darwin/arm64=provider", default)?,
+            ("aarch64-macos".to_string(), PathBuf::from("provider"))
+        );
+        assert_eq!(
+            parse_provider_binary("windows/amd64=provider.exe", default)?,
+            ("x86_64-windows".to_string(), PathBuf::from("provider.exe"))
+        );
+        // Paths that happen to contain `=` aren't mistaken for targets
+        assert_eq!(
+            parse_provider_binary("./out/a=b", default)?,
+            (default.to_string(), PathBuf::from("./out/a=b"))
+        );
+        // but anything that looks like a target has to be one
+        for invalid in ["out/a=b", "x86-64-linux=provider", "x86_64=provider"] {
+            assert!(
+                parse_provider_binary(invalid, default).is_err(),
+                "{invalid} should be rejected"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn every_target_gets_one_binary() -> Result<()> {
+        let mut par = ProviderArchive::new("multi", "wasmcloud", None, None);
+        add_provider_binaries(
+            &mut par,
+            &[
+                ("x86_64-linux".to_string(), b"linux".to_vec()),
+                ("aarch64-macos".to_string(), b"macos".to_vec()),
+            ],
+        )?;
+        let mut targets = par.targets();
+        targets.sort();
+        assert_eq!(targets, ["aarch64-macos", "x86_64-linux"]);
+        assert!(
+            add_provider_binaries(&mut par, &[("x86_64-linux".to_string(), Vec::new())]).is_err()
+        );
+        Ok(())
+    }
+}
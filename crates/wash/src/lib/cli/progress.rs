@@ -0,0 +1,157 @@
+//! Progress of long-running commands, such as starting a provider, reported as a sequence of
+//! steps. Commands report each step as they reach it to the [`Progress`] they are given, which
+//! passes them on to its [`ProgressSink`]: the spinner for text output, or [`NdjsonProgress`]
+//! records when `--progress` is set with structured output. The default [`Progress`] reports
+//! nothing
+
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use serde::Serialize;
+
+use super::NdjsonStream;
+
+/// A step of a long-running command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProgressStep {
+    /// Auctioning a component or provider to find a host for it
+    Auctioning,
+    /// Downloading an image before it is started, e.g. to validate or verify it
+    DownloadingImage,
+    /// Waiting for a host to acknowledge a request
+    WaitingForAck,
+    /// Waiting for the event confirming that a component or provider started
+    WaitingForStartEvent,
+    /// Waiting for a started provider to report that it is healthy
+    WaitingForHealth,
+    /// Waiting for the links of a started provider to become live
+    WaitingForLinks,
+}
+
+/// A step that was reached, with a human readable description of it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProgressEvent {
+    pub step: ProgressStep,
+    pub message: String,
+}
+
+/// Somewhere to show progress
+pub trait ProgressSink: Send + Sync {
+    fn report(&self, event: &ProgressEvent);
+}
+
+/// Where a command reports the steps it reaches. The default reports nowhere
+#[derive(Clone, Default)]
+pub struct Progress(Option<Arc<dyn ProgressSink>>);
+
+impl Progress {
+    /// Report steps to `sink`
+    #[must_use]
+    pub fn new(sink: Arc<dyn ProgressSink>) -> Self {
+        Self(Some(sink))
+    }
+
+    /// Report that a command reached a step
+    pub fn report(&self, step: ProgressStep, message: impl Into<String>) {
+        if let Some(sink) = &self.0 {
+            sink.report(&ProgressEvent {
+                step,
+                message: message.into(),
+            });
+        }
+    }
+}
+
+impl std::fmt::Debug for Progress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Progress")
+            .field("reporting", &self.0.is_some())
+            .finish()
+    }
+}
+
+/// Writes each step as a JSON line, with the milliseconds since the sink was created. Used on
+/// stderr for `--progress`, so the command output on stdout stays a single JSON document
+pub struct NdjsonProgress<W: Write = std::io::Stderr> {
+    stream: Mutex<NdjsonStream<W>>,
+    started: Instant,
+}
+
+#[derive(Serialize)]
+struct ProgressRecord<'a> {
+    progress: ProgressStep,
+    message: &'a str,
+    elapsed_ms: u128,
+}
+
+impl NdjsonProgress {
+    /// Write progress records to stderr
+    #[must_use]
+    pub fn stderr() -> Self {
+        Self::new(std::io::stderr())
+    }
+}
+
+impl<W: Write> NdjsonProgress<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            stream: Mutex::new(NdjsonStream::new(writer)),
+            started: Instant::now(),
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.stream
+            .into_inner()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .into_inner()
+    }
+}
+
+impl<W: Write + Send> ProgressSink for NdjsonProgress<W> {
+    fn report(&self, event: &ProgressEvent) {
+        let record = ProgressRecord {
+            progress: event.step,
+            message: &event.message,
+            elapsed_ms: self.started.elapsed().as_millis(),
+        };
+        if let Ok(mut stream) = self.stream.lock() {
+            // Progress is best effort, a closed stderr shouldn't fail the command
+            let _ = stream.emit(&record);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn progress_is_written_as_json_lines() {
+        let sink = NdjsonProgress::new(Vec::new());
+        sink.report(&ProgressEvent {
+            step: ProgressStep::Auctioning,
+            message: "Auctioning provider ghcr.io/http:0.1.0".to_string(),
+        });
+        sink.report(&ProgressEvent {
+            step: ProgressStep::WaitingForStartEvent,
+            message: "Waiting for provider http to start".to_string(),
+        });
+
+        let written = String::from_utf8(sink.into_inner()).unwrap();
+        let records = written
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["progress"], "auctioning");
+        assert_eq!(
+            records[0]["message"],
+            "Auctioning provider ghcr.io/http:0.1.0"
+        );
+        assert_eq!(records[1]["progress"], "waiting-for-start-event");
+        assert!(records[1]["elapsed_ms"].is_u64());
+    }
+}
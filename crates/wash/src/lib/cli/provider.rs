@@ -0,0 +1,175 @@
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use tokio::sync::mpsc::Receiver;
+use wasmcloud_control_interface::{Host, ProviderLogLine};
+
+use crate::lib::cli::get::parse_watch_interval;
+use crate::lib::cli::CliConnectionOpts;
+use crate::lib::common::boxed_err_to_anyhow;
+use crate::lib::config::WashConnectionOptions;
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum ProviderCommand {
+    /// Show the output of a provider on the hosts running it, optionally following new lines
+    #[clap(name = "logs")]
+    Logs(ProviderLogsCommand),
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct ProviderLogsCommand {
+    #[clap(flatten)]
+    pub opts: CliConnectionOpts,
+
+    /// ID of the provider to show logs for
+    #[clap(name = "provider-id")]
+    pub provider_id: String,
+
+    /// Only show the output of the provider on this host. Defaults to every host running it
+    #[clap(long = "host-id")]
+    pub host_id: Option<String>,
+
+    /// Keep printing lines as the provider writes them until interrupted
+    #[clap(short = 'f', long = "follow")]
+    pub follow: bool,
+
+    /// Only show lines written at most this long ago, in ms or in humantime (eg: 30s, 5m, 1h).
+    /// Defaults to every line the hosts still hold
+    #[clap(long = "since", value_parser = parse_watch_interval)]
+    pub since: Option<Duration>,
+}
+
+/// The output of a provider, as held by the hosts running it and, when following, as they
+/// publish new lines
+pub struct ProviderLogs {
+    /// Lines the hosts still held, oldest first per host
+    pub history: Vec<ProviderLogLine>,
+    /// Lines published after `history` was retrieved, when following
+    pub follow: Option<Receiver<ProviderLogLine>>,
+}
+
+/// Retrieve the output of a provider from the hosts that forward provider logs, subscribing to
+/// new lines first when following so none are missed between the two. Fails with a "log
+/// forwarding not enabled" error when none of the targeted hosts forward provider logs
+pub async fn get_provider_logs(cmd: &ProviderLogsCommand) -> Result<ProviderLogs> {
+    let wco: WashConnectionOptions = cmd.opts.clone().try_into()?;
+    let client = wco.into_ctl_client(None).await?;
+
+    let hosts = client
+        .get_hosts()
+        .await
+        .map_err(boxed_err_to_anyhow)
+        .context("Failed to get hosts")?
+        .into_iter()
+        .filter_map(wasmcloud_control_interface::CtlResponse::into_data)
+        .collect::<Vec<_>>();
+    let host_ids = forwarding_hosts(&hosts, cmd.host_id.as_deref())?;
+
+    let follow = if cmd.follow {
+        Some(
+            client
+                .provider_logs_receiver(&cmd.provider_id, cmd.host_id.as_deref())
+                .await
+                .map_err(boxed_err_to_anyhow)
+                .context("Failed to subscribe to provider logs")?,
+        )
+    } else {
+        None
+    };
+
+    let mut history = Vec::new();
+    for host_id in host_ids {
+        // Hosts that don't run the provider don't answer, which isn't an error when looking at
+        // every host
+        match client
+            .get_provider_log_history(&cmd.provider_id, &host_id, cmd.since)
+            .await
+        {
+            Ok(lines) => history.extend(lines),
+            Err(e) if cmd.host_id.is_some() => {
+                return Err(boxed_err_to_anyhow(e)).with_context(|| {
+                    format!(
+                        "Failed to get the logs of provider [{}] on host [{host_id}], is it running there?",
+                        cmd.provider_id
+                    )
+                });
+            }
+            Err(_) => {}
+        }
+    }
+    Ok(ProviderLogs { history, follow })
+}
+
+/// The IDs of the hosts to retrieve provider output from, either the given host or every host.
+/// Fails when none of them forward provider logs
+fn forwarding_hosts(hosts: &[Host], host_id: Option<&str>) -> Result<Vec<String>> {
+    let targeted = hosts
+        .iter()
+        .filter(|host| host_id.is_none_or(|id| host.id() == id))
+        .collect::<Vec<_>>();
+    if targeted.is_empty() {
+        match host_id {
+            Some(id) => bail!("Host [{id}] was not found in the lattice"),
+            None => bail!("No hosts were found in the lattice"),
+        }
+    }
+    let forwarding = targeted
+        .iter()
+        .filter(|host| host.provider_log_forwarding())
+        .map(|host| host.id().to_string())
+        .collect::<Vec<_>>();
+    if forwarding.is_empty() {
+        bail!(
+            "Log forwarding not enabled on {}. Start the host with --forward-provider-logs (or WASMCLOUD_FORWARD_PROVIDER_LOGS=true) to read provider logs",
+            match host_id {
+                Some(id) => format!("host [{id}]"),
+                None => "any host in the lattice".to_string(),
+            }
+        );
+    }
+    Ok(forwarding)
+}
+
+#[cfg(test)]
+mod tests {
+    use wasmcloud_control_interface::Host;
+
+    use super::forwarding_hosts;
+
+    fn host(id: &str, provider_log_forwarding: bool) -> Host {
+        Host::builder()
+            .id(id.into())
+            .friendly_name(id.into())
+            .lattice("default".into())
+            .uptime_seconds(1)
+            .provider_log_forwarding(provider_log_forwarding)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn forwarding_hosts_skips_hosts_without_forwarding() {
+        let hosts = [host("NA", true), host("NB", false)];
+        assert_eq!(forwarding_hosts(&hosts, None).unwrap(), vec!["NA"]);
+        assert_eq!(forwarding_hosts(&hosts, Some("NA")).unwrap(), vec!["NA"]);
+    }
+
+    #[test]
+    fn forwarding_hosts_reports_forwarding_not_enabled() {
+        let hosts = [host("NA", false), host("NB", false)];
+        let err = forwarding_hosts(&hosts, None).unwrap_err().to_string();
+        assert!(err.starts_with("Log forwarding not enabled on any host"));
+
+        let hosts = [host("NA", true), host("NB", false)];
+        let err = forwarding_hosts(&hosts, Some("NB"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with("Log forwarding not enabled on host [NB]"));
+
+        let err = forwarding_hosts(&hosts, Some("NC"))
+            .unwrap_err()
+            .to_string();
+        assert_eq!(err, "Host [NC] was not found in the lattice");
+    }
+}
@@ -0,0 +1,272 @@
+//! Operation receipts: a portable record of a single start or scale operation, for audits
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use clap::Args;
+use nkeys::KeyPair;
+use serde::{Deserialize, Serialize};
+
+use crate::lib::cli::CommandOutput;
+
+/// Keys of a command's output that identify what the operation acted on, after host and ref
+/// resolution
+const TARGET_KEYS: [&str; 6] = [
+    "host_id",
+    "provider_id",
+    "provider_ref",
+    "component_id",
+    "component_ref",
+    "link_name",
+];
+
+#[derive(Args, Debug, Clone, Default)]
+pub struct ReceiptOpts {
+    /// Add an operation receipt to the output: a JSON record of what was done, to whom, when and
+    /// with which result, that can be kept for audits
+    #[clap(long = "receipt")]
+    pub receipt: bool,
+
+    /// Correlation ID to put in the receipt, e.g. the ID of a change request. Defaults to a
+    /// random ID
+    #[clap(long = "correlation-id", requires = "receipt")]
+    pub correlation_id: Option<String>,
+
+    /// Seed of the key to sign the receipt with, so it can be verified later
+    #[clap(
+        long = "receipt-seed",
+        env = "WASH_RECEIPT_SEED",
+        hide_env_values = true,
+        requires = "receipt"
+    )]
+    pub receipt_seed: Option<String>,
+}
+
+/// How an operation ended
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReceiptOutcome {
+    /// Whether the operation fully succeeded. Operations that only partially succeeded are not
+    /// successful
+    pub success: bool,
+    pub message: String,
+}
+
+/// Signature over a receipt, made with an nkey
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReceiptSignature {
+    /// Public key of the signer
+    pub issuer: String,
+    /// Hex encoded signature of the receipt serialized without its signature
+    pub signature: String,
+}
+
+/// A record of a single operation, meant to be stored and checked later, e.g. by compliance
+/// tooling. Only operations that completed get a receipt; a command that fails outright returns
+/// its error instead
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationReceipt {
+    pub correlation_id: String,
+    pub timestamp: DateTime<Utc>,
+    /// Who ran the operation, from the local user name, if known
+    pub operator: Option<String>,
+    /// The operation and the arguments it was given, e.g. `start provider <ref> <id>`
+    pub command: String,
+    /// What the operation acted on once resolved, e.g. the ID of the host chosen by an auction
+    pub targets: BTreeMap<String, String>,
+    pub outcome: ReceiptOutcome,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<ReceiptSignature>,
+}
+
+impl OperationReceipt {
+    /// Create an unsigned receipt for an operation that just completed
+    #[must_use]
+    pub fn new(
+        correlation_id: Option<String>,
+        command: impl Into<String>,
+        targets: BTreeMap<String, String>,
+        outcome: ReceiptOutcome,
+    ) -> Self {
+        Self {
+            correlation_id: correlation_id
+                .unwrap_or_else(|| format!("{:032x}", rand::random::<u128>())),
+            timestamp: Utc::now(),
+            operator: std::env::var("USER")
+                .or_else(|_| std::env::var("USERNAME"))
+                .ok(),
+            command: command.into(),
+            targets,
+            outcome,
+            signature: None,
+        }
+    }
+
+    /// Create a receipt from the output of a completed command, taking the targets from the
+    /// output and treating a `partial` output as unsuccessful
+    #[must_use]
+    pub fn for_output(
+        correlation_id: Option<String>,
+        command: impl Into<String>,
+        output: &CommandOutput,
+    ) -> Self {
+        let targets = TARGET_KEYS
+            .iter()
+            .filter_map(|key| {
+                let value = output.map.get(*key)?.as_str()?;
+                Some((key.to_string(), value.to_string()))
+            })
+            .collect();
+        let partial = output
+            .map
+            .get("partial")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false);
+        Self::new(
+            correlation_id,
+            command,
+            targets,
+            ReceiptOutcome {
+                success: !partial,
+                message: output.text.clone(),
+            },
+        )
+    }
+
+    /// The bytes covered by the signature: the receipt as JSON, without its signature
+    fn signed_bytes(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(&Self {
+            signature: None,
+            ..self.clone()
+        })
+        .context("failed to serialize receipt")
+    }
+
+    /// Sign the receipt, replacing any previous signature
+    pub fn sign(&mut self, key: &KeyPair) -> Result<()> {
+        let signature = key
+            .sign(&self.signed_bytes()?)
+            .context("failed to sign receipt")?;
+        self.signature = Some(ReceiptSignature {
+            issuer: key.public_key(),
+            signature: signature.iter().map(|b| format!("{b:02x}")).collect(),
+        });
+        Ok(())
+    }
+
+    /// Check that the receipt is signed and hasn't been changed since
+    pub fn verify(&self) -> Result<()> {
+        let Some(signature) = &self.signature else {
+            bail!("receipt is not signed");
+        };
+        let bytes = (0..signature.signature.len())
+            .step_by(2)
+            .map(|i| {
+                signature
+                    .signature
+                    .get(i..i + 2)
+                    .and_then(|b| u8::from_str_radix(b, 16).ok())
+                    .context("receipt signature is not valid hex")
+            })
+            .collect::<Result<Vec<_>>>()?;
+        KeyPair::from_public_key(&signature.issuer)
+            .context("receipt issuer is not a valid public key")?
+            .verify(&self.signed_bytes()?, &bytes)
+            .context("receipt signature does not match its contents")
+    }
+}
+
+/// Add a receipt for the completed command to its output under the `receipt` key, signing it if
+/// a seed was given. Outputs are returned unchanged unless `--receipt` was passed
+pub fn attach_receipt(
+    mut output: CommandOutput,
+    opts: &ReceiptOpts,
+    command: impl Into<String>,
+) -> Result<CommandOutput> {
+    if !opts.receipt {
+        return Ok(output);
+    }
+    let mut receipt = OperationReceipt::for_output(opts.correlation_id.clone(), command, &output);
+    if let Some(seed) = &opts.receipt_seed {
+        let key = KeyPair::from_seed(seed).context("invalid receipt seed")?;
+        receipt.sign(&key)?;
+    }
+    output
+        .map
+        .insert("receipt".into(), serde_json::to_value(&receipt)?);
+    Ok(output)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn receipt_records_the_operation_and_round_trips() {
+        let output = CommandOutput::new(
+            "Provider [http-server] started",
+            HashMap::from([
+                ("host_id".into(), "NHOST".into()),
+                ("provider_id".into(), "http-server".into()),
+                (
+                    "provider_ref".into(),
+                    "ghcr.io/wasmcloud/http-server:0.23.2".into(),
+                ),
+                ("result".into(), "started".into()),
+            ]),
+        );
+        let key = KeyPair::new_account();
+        let opts = ReceiptOpts {
+            receipt: true,
+            correlation_id: Some("CHG-1234".to_string()),
+            receipt_seed: Some(key.seed().expect("account keys have a seed")),
+        };
+
+        let output = attach_receipt(output, &opts, "start provider http-server")
+            .expect("should attach receipt");
+        let json = &output.map["receipt"];
+        for field in [
+            "correlation_id",
+            "timestamp",
+            "operator",
+            "command",
+            "targets",
+            "outcome",
+            "signature",
+        ] {
+            assert!(json.get(field).is_some(), "receipt should have {field}");
+        }
+
+        let receipt: OperationReceipt =
+            serde_json::from_value(json.clone()).expect("receipt should deserialize");
+        assert_eq!(receipt.correlation_id, "CHG-1234");
+        assert_eq!(receipt.command, "start provider http-server");
+        assert_eq!(
+            receipt.targets,
+            BTreeMap::from([
+                ("host_id".to_string(), "NHOST".to_string()),
+                ("provider_id".to_string(), "http-server".to_string()),
+                (
+                    "provider_ref".to_string(),
+                    "ghcr.io/wasmcloud/http-server:0.23.2".to_string()
+                ),
+            ])
+        );
+        assert!(receipt.outcome.success);
+        assert_eq!(
+            receipt.signature.as_ref().map(|s| s.issuer.as_str()),
+            Some(key.public_key().as_str())
+        );
+        assert_eq!(
+            serde_json::to_value(&receipt).expect("receipt should serialize"),
+            *json
+        );
+        receipt.verify().expect("signature should verify");
+
+        let mut tampered = receipt;
+        tampered.outcome.success = false;
+        assert!(tampered.verify().is_err());
+    }
+}
@@ -0,0 +1,49 @@
+//! Renderers for the structured part of a [`CommandOutput`](super::CommandOutput) in the formats
+//! that aren't JSON
+
+use std::collections::{BTreeMap, HashMap};
+
+use term_table::row::Row;
+use term_table::table_cell::{Alignment, TableCell};
+use term_table::Table;
+
+use super::configure_table_style;
+
+/// Render the output map as a YAML document, with keys in a stable order
+#[must_use]
+pub fn yaml(map: &HashMap<String, serde_json::Value>) -> String {
+    let sorted: BTreeMap<_, _> = map.iter().collect();
+    serde_yaml::to_string(&sorted).unwrap_or_default()
+}
+
+/// Render every field of the output map as a two column table, for `--output wide`. Nested
+/// values are shown as single line JSON. A field only repeating the text output (e.g. `result`) is
+/// left out, as the text is printed above the table
+#[must_use]
+pub fn fields_table(map: &HashMap<String, serde_json::Value>, text: &str) -> String {
+    let fields: BTreeMap<_, _> = map
+        .iter()
+        .filter(|(_, value)| value.as_str() != Some(text))
+        .collect();
+    if fields.is_empty() {
+        return String::new();
+    }
+
+    let mut table = Table::new();
+    configure_table_style(&mut table);
+    table.add_row(Row::new(vec![
+        TableCell::new_with_alignment("Field", 1, Alignment::Left),
+        TableCell::new_with_alignment("Value", 1, Alignment::Left),
+    ]));
+    for (key, value) in fields {
+        let value = match value {
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        table.add_row(Row::new(vec![
+            TableCell::new_with_alignment(key, 1, Alignment::Left),
+            TableCell::new_with_alignment(value, 1, Alignment::Left),
+        ]));
+    }
+    table.render()
+}
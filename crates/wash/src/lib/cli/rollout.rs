@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use tracing::warn;
+
+use crate::lib::cli::start::{handle_start_provider, StartProviderCommand};
+use crate::lib::cli::{CliConnectionOpts, CommandOutput};
+use crate::lib::rollout::Rollout;
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum RolloutCommand {
+    /// Start the provider of a canary rollout on the rest of its hosts
+    #[clap(name = "promote")]
+    Promote(PromoteRolloutCommand),
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct PromoteRolloutCommand {
+    #[clap(flatten)]
+    pub opts: CliConnectionOpts,
+
+    /// Handle of the rollout, as returned by `wash start provider --canary`
+    #[clap(name = "handle")]
+    pub handle: String,
+}
+
+pub async fn handle_command(cmd: RolloutCommand) -> Result<CommandOutput> {
+    match cmd {
+        RolloutCommand::Promote(cmd) => handle_promote(cmd).await,
+    }
+}
+
+/// Build the start command for one of the pending hosts of a rollout
+fn start_command_for(
+    rollout: &Rollout,
+    host_id: &str,
+    opts: &CliConnectionOpts,
+) -> Result<StartProviderCommand> {
+    let args = [
+        "provider".to_string(),
+        rollout.provider_ref.clone(),
+        rollout.provider_id.clone(),
+        "--host-id".to_string(),
+        host_id.to_string(),
+    ]
+    .into_iter()
+    .chain(rollout.start_args.iter().cloned());
+    let mut cmd = StartProviderCommand::try_parse_from(args)
+        .context("failed to build start command for rollout")?;
+    cmd.opts = opts.clone();
+    Ok(cmd)
+}
+
+async fn handle_promote(cmd: PromoteRolloutCommand) -> Result<CommandOutput> {
+    let mut rollout = Rollout::load(&cmd.handle)?;
+    if rollout.is_complete() {
+        bail!(
+            "Rollout [{}] has already been promoted to all of its hosts",
+            rollout.handle
+        );
+    }
+
+    for host_id in rollout.pending.clone() {
+        let start = start_command_for(&rollout, &host_id, &cmd.opts)?;
+        if let Err(e) = handle_start_provider(start).await {
+            // Keep the progress so far, promoting again picks up from the failed host
+            if let Err(save_err) = rollout.save() {
+                warn!(?save_err, "failed to save rollout progress");
+            }
+            return Err(e).with_context(|| {
+                format!(
+                    "Failed to start provider [{}] on host [{host_id}] while promoting rollout [{}]",
+                    rollout.provider_id, rollout.handle
+                )
+            });
+        }
+        rollout.mark_started(&host_id);
+    }
+    rollout.save()?;
+
+    let text = format!(
+        "Rollout [{}] promoted, provider [{}] is running on {} hosts",
+        rollout.handle,
+        rollout.provider_id,
+        rollout.started.len()
+    );
+    Ok(CommandOutput::new(
+        text.clone(),
+        HashMap::from([
+            ("result".into(), text.into()),
+            ("rollout".into(), serde_json::to_value(&rollout)?),
+        ]),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::lib::cli::start::rollout_start_args;
+
+    #[test]
+    fn promote_starts_pending_hosts_with_rollout_settings() {
+        let canary = StartProviderCommand::try_parse_from([
+            "provider",
+            "ghcr.io/wasmcloud/http-server:0.1.0",
+            "http-server",
+            "--canary",
+            "50%",
+            "--link-name",
+            "edge",
+            "--config",
+            "server-config",
+            "--link",
+            "echo=wasi:http/incoming-handler",
+            "--wait-healthy",
+            "--warmup-ms",
+            "250",
+            "--image-annotation",
+            "cache=pin",
+            "--retries",
+            "2",
+        ])
+        .expect("should parse canary start");
+        let rollout = Rollout::new(
+            &canary.provider_ref,
+            &canary.provider_id,
+            rollout_start_args(&canary).expect("should collect start args"),
+            vec!["host-a".to_string()],
+            vec!["host-b".to_string()],
+        );
+        let opts = CliConnectionOpts {
+            lattice: Some("staging".to_string()),
+            ..Default::default()
+        };
+        let cmd = start_command_for(&rollout, "host-b", &opts).expect("should build command");
+        assert_eq!(cmd.host_id.as_deref(), Some("host-b"));
+        assert_eq!(cmd.provider_ref, rollout.provider_ref);
+        assert_eq!(cmd.provider_id, "http-server");
+        assert_eq!(cmd.link_names, vec!["edge"]);
+        assert_eq!(cmd.config, vec!["server-config"]);
+        assert_eq!(cmd.links, canary.links);
+        assert!(cmd.wait_healthy);
+        assert_eq!(cmd.warmup_ms, 250);
+        assert_eq!(cmd.image_annotations, canary.image_annotations);
+        assert_eq!(cmd.retry.retries, 2);
+        assert_eq!(cmd.canary, None);
+        assert_eq!(cmd.opts.lattice.as_deref(), Some("staging"));
+    }
+}
@@ -1,22 +1,70 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use clap::Parser;
+use serde::Serialize;
+use term_table::row::Row;
+use term_table::table_cell::{Alignment, TableCell};
+use term_table::Table;
+use tracing::warn;
+use wasmcloud_control_interface::HostInventory;
 
-use crate::lib::cli::{input_vec_to_hashmap, CliConnectionOpts, CommandOutput};
-use crate::lib::common::find_host_id;
-use crate::lib::component::{scale_component, ScaleComponentArgs};
-use crate::lib::config::WashConnectionOptions;
+use crate::lib::backoff::RetryOpts;
+use crate::lib::cli::receipt::{attach_receipt, ReceiptOpts};
+use crate::lib::cli::{
+    configure_table_style, input_vec_to_hashmap, CliConnectionOpts, CommandOutput, NdjsonStream,
+    OutputKind,
+};
+use crate::lib::common::{
+    boxed_err_to_anyhow, find_host_id_with, get_all_inventories, pick_host, HostMatchOpts,
+};
+use crate::lib::component::{
+    cap_at_host_capacity, check_component_annotations, check_component_owner,
+    component_scale_status, is_scaled_to, plan_component_scale, preview_component_scale,
+    resolve_scale_manifest, scale_component, ComponentScaleAction, ComponentScalePreview,
+    ComponentScaledInfo, DesiredComponentCounts, ScaleComponentArgs, ScaleManifestEntry,
+    ScaleManifestRow, OWNER_ANNOTATION,
+};
+use crate::lib::config::{close_ctl_client, WashConnectionOptions};
 use crate::lib::context::default_component_operation_timeout_ms;
+use crate::lib::failure::{Failure, FailureKind};
+use crate::lib::id::ServerId;
+use crate::lib::wait::{record_events, wait_for_component_scale, EventFilter};
 
-use super::start::resolve_ref;
+use super::get::parse_watch_interval;
+use super::progress::Progress;
+use super::start::{
+    auction_component, choose_auction_host, filter_auction_candidates, resolve_ref, Placement,
+};
 use super::validate_component_id;
 
+/// How often to check the host inventory with `--wait-for-ready`
+const SCALE_READY_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
 #[derive(Debug, Clone, Parser)]
 pub enum ScaleCommand {
-    /// Scale a component running in a host to a certain level of concurrency
-    #[clap(name = "component")]
-    Component(ScaleComponentCommand),
+    /// Scale a component running in a host to a certain level of concurrency. Components were
+    /// previously called actors, so this is also available as `wash scale actor`
+    #[clap(name = "component", alias = "actor")]
+    Component(Box<ScaleComponentCommand>),
+
+    /// Scale components across hosts to the instance counts declared in a file
+    #[clap(name = "apply")]
+    Apply(ScaleApplyCommand),
+
+    /// Send every scale command listed in a manifest file concurrently and report the result of
+    /// each
+    #[clap(name = "manifest")]
+    Manifest(ScaleManifestCommand),
+
+    /// Report the current instance count of a component across hosts and whether its last scale
+    /// reached the target
+    #[clap(name = "status")]
+    Status(ScaleStatusCommand),
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -25,19 +73,33 @@ pub struct ScaleComponentCommand {
     pub opts: CliConnectionOpts,
 
     /// ID of host to scale component on. If a non-ID is provided, the host will be selected based on
-    /// matching the friendly name and will return an error if more than one host matches.
-    #[clap(name = "host-id")]
-    pub host_id: String,
+    /// matching the friendly name and will return an error if more than one host matches. If
+    /// omitted, a host matching `--constraint` is picked: the one already running the component, or
+    /// else one chosen by a component auction
+    #[clap(long = "host-id")]
+    pub host_id: Option<String>,
+
+    #[clap(flatten)]
+    pub host_match: HostMatchOpts,
 
     /// Component reference, e.g. the absolute file path or OCI URL.
     #[clap(name = "component-ref")]
     pub component_ref: String,
 
     /// Unique ID to use for the component
-    #[clap(name = "component-id", value_parser = validate_component_id)]
+    #[clap(name = "component-id")]
     pub component_id: String,
 
-    /// Maximum number of component instances allowed to run concurrently. Setting this value to `0` will stop the component.
+    /// Component ID when the host ID is given as the first positional argument, i.e. the
+    /// deprecated `wash scale component <host-id> <component-ref> <component-id>` form
+    #[clap(
+        name = "legacy-component-id",
+        hide = true,
+        conflicts_with_all = ["host_id", "constraints"]
+    )]
+    pub legacy_component_id: Option<String>,
+
+    /// Maximum number of component instances allowed to run concurrently. Setting this value to `0` will stop the component, keeping the annotations it runs with.
     #[clap(short = 'c', long = "max-instances", alias = "max-concurrent", alias = "max", alias = "count", default_value_t = u32::MAX)]
     pub max_instances: u32,
 
@@ -46,10 +108,31 @@ pub struct ScaleComponentCommand {
     #[clap(short = 'a', long = "annotations")]
     pub annotations: Vec<String>,
 
+    /// Path to a YAML or JSON file with a flat map of annotations to add to the scale command.
+    /// Annotations passed with `-a` are merged in and take precedence over the file
+    #[clap(long = "annotations-file")]
+    pub annotations_file: Option<PathBuf>,
+
     /// List of named configuration to apply to the component, may be empty
     #[clap(long = "config")]
     pub config: Vec<String>,
 
+    /// Constraints for the component auction used to pick a host when `--host-id` is omitted, in
+    /// the form of `label=value`. May be given more than once
+    #[clap(long = "constraint", name = "constraints", conflicts_with = "host_id")]
+    pub constraints: Option<Vec<String>>,
+
+    /// How to choose between the hosts that respond to the auction. Ignored if `--host-id` is
+    /// supplied
+    #[clap(
+        long = "placement",
+        alias = "selection-strategy",
+        value_enum,
+        default_value_t = Placement::First,
+        conflicts_with = "host_id"
+    )]
+    pub placement: Placement,
+
     /// By default, the command will wait until the component has been scaled.
     /// If this flag is passed, the command will return immediately after acknowledgement from the host, without waiting for the component to be scaled.
     /// If this flag is omitted, the command will wait until the scaled event has been acknowledged.
@@ -59,47 +142,1019 @@ pub struct ScaleComponentCommand {
     /// Timeout for waiting for scale to occur (normally on an auction response), defaults to 2000 milliseconds
     #[clap(long = "wait-timeout-ms", default_value_t = default_component_operation_timeout_ms())]
     pub wait_timeout_ms: u64,
+
+    /// After the host acknowledges the scale, poll its inventory until it reports the component at
+    /// `--max-instances` (or gone, when scaling to 0), failing if that takes longer than
+    /// `--wait-timeout-ms`
+    #[clap(long = "wait-for-ready")]
+    pub wait_for_ready: bool,
+
+    /// Run the usual checks and report the current instance count on each targeted host and the
+    /// count it would be scaled to, without sending the scale command. Without a host ID, every
+    /// host the scale could land on is reported
+    #[clap(long = "dry-run")]
+    pub dry_run: bool,
+
+    /// Clamp `--max-instances` to the capacity left on the host, i.e. its `capacity` label minus the
+    /// instances of its other components. Fails if the host doesn't advertise a capacity
+    #[clap(long = "cap-at-host-capacity")]
+    pub cap_at_host_capacity: bool,
+
+    /// Only scale the component if it is owned by this controller, i.e. it carries a matching
+    /// `owned-by` annotation or isn't running on the host yet. The scale marks the component as
+    /// owned by this controller
+    #[clap(long = "owner")]
+    pub owner: Option<String>,
+
+    /// Only scale the component if it is running on the host with this annotation, in the form
+    /// `key=value`, e.g. to only touch the instances of a given wadm deployment. May be given more
+    /// than once, in which case every annotation must match
+    #[clap(long = "match-annotation", name = "match_annotations")]
+    pub match_annotations: Vec<String>,
+
+    #[clap(flatten)]
+    pub retry: RetryOpts,
+
+    #[clap(flatten)]
+    pub receipt: ReceiptOpts,
+
+    /// Where to report the steps of the scale as they are reached
+    #[clap(skip)]
+    pub progress: Progress,
 }
 
-pub async fn handle_scale_component(cmd: ScaleComponentCommand) -> Result<CommandOutput> {
-    let wco: WashConnectionOptions = cmd.opts.try_into()?;
+#[derive(Debug, Clone, Parser)]
+pub struct ScaleApplyCommand {
+    #[clap(flatten)]
+    pub opts: CliConnectionOpts,
+
+    /// Path to a YAML file mapping component references to the desired max instances per host,
+    /// where hosts are identified by ID or friendly name
+    #[clap(short = 'f', long = "file")]
+    pub file: PathBuf,
+
+    /// Print the scale operations that would be performed without sending them
+    #[clap(long = "dry-run")]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct ScaleManifestCommand {
+    #[clap(flatten)]
+    pub opts: CliConnectionOpts,
+
+    /// Path to a YAML or JSON list of scale commands. Each entry names a `component_ref`, a
+    /// `max_instances` and the hosts to scale on, either a single `host` (ID or friendly name) or
+    /// every host with the given `host_labels`. An optional `component_id` and `annotations` may
+    /// also be given, e.g. `[{component_ref: ghcr.io/wasmcloud/hello:0.1.0, host_labels: {zone:
+    /// east}, max_instances: 5}]`
+    #[clap(short = 'f', long = "file", alias = "manifest")]
+    pub file: PathBuf,
+
+    /// Return as soon as each host acknowledges its scale command, without waiting for the
+    /// components to be scaled
+    #[clap(long = "skip-wait")]
+    pub skip_wait: bool,
+
+    /// How long to wait for each component to be scaled, in milliseconds
+    #[clap(long = "wait-timeout-ms", default_value_t = default_component_operation_timeout_ms())]
+    pub wait_timeout_ms: u64,
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct ScaleStatusCommand {
+    #[clap(flatten)]
+    pub opts: CliConnectionOpts,
+
+    /// Component reference, e.g. the absolute file path or OCI URL.
+    #[clap(name = "component-ref")]
+    pub component_ref: String,
+
+    /// Only report on this host. If a non-ID is provided, the host will be selected based on
+    /// matching the friendly name and will return an error if more than one host matches.
+    #[clap(long = "host-id")]
+    pub host_id: Option<String>,
+
+    #[clap(flatten)]
+    pub host_match: HostMatchOpts,
+
+    /// How long to listen for scale events before reporting, in ms or in humantime (eg: 2s, 5m, 54ms).
+    /// The current instance counts are read from the host inventories before listening, and read
+    /// again if a scale happens while listening. Defaults to 2 seconds.
+    #[clap(long = "window", default_value = "2s", value_parser = parse_watch_interval)]
+    pub window: std::time::Duration,
+}
+
+impl ScaleComponentCommand {
+    /// Shift the positional arguments of the deprecated `<host-id> <component-ref>
+    /// <component-id>` form into place, warning that `--host-id` should be used instead, and
+    /// validate the component ID
+    pub(crate) fn take_deprecated_host_id(&mut self) -> Result<()> {
+        if let Some(component_id) = self.legacy_component_id.take() {
+            warn!(
+                "passing the host ID as the first argument of `wash scale component` is deprecated, use `--host-id {}` instead",
+                self.component_ref
+            );
+            let component_ref = std::mem::replace(&mut self.component_id, component_id);
+            self.host_id = Some(std::mem::replace(&mut self.component_ref, component_ref));
+        }
+        validate_component_id(&self.component_id)?;
+        Ok(())
+    }
+}
+
+pub async fn handle_scale_component(mut cmd: ScaleComponentCommand) -> Result<CommandOutput> {
+    cmd.take_deprecated_host_id()?;
+    let wco: WashConnectionOptions = cmd.opts.clone().try_into()?;
     let client = wco.into_ctl_client(None).await?;
 
-    let annotations = input_vec_to_hashmap(cmd.annotations)?;
+    let receipt = cmd.receipt.clone();
+    let host = match &cmd.host_id {
+        Some(host_id) => format!("--host-id {host_id}"),
+        None => cmd
+            .constraints
+            .iter()
+            .flatten()
+            .map(|c| format!("--constraint {c}"))
+            .collect::<Vec<_>>()
+            .join(" "),
+    };
+    let command = format!(
+        "scale component {} {} {host} --max {}",
+        cmd.component_ref, cmd.component_id, cmd.max_instances
+    );
+    let result = scale_component_with_client(client.clone(), cmd).await;
+    close_ctl_client(&client).await;
+    attach_receipt(result?, &receipt, command)
+}
+
+async fn scale_component_with_client(
+    client: wasmcloud_control_interface::Client,
+    cmd: ScaleComponentCommand,
+) -> Result<CommandOutput> {
     let component_ref = resolve_ref(&cmd.component_ref).await?;
+    let Some(host) = cmd.host_id.as_deref() else {
+        let constraints = BTreeMap::from_iter(input_vec_to_hashmap(
+            cmd.constraints.clone().unwrap_or_default(),
+        )?);
+        if cmd.dry_run {
+            return preview_auction_scale(&client, &cmd, &component_ref, &constraints).await;
+        }
+        let host_id = auction_scale_host(&client, &cmd, &component_ref, &constraints).await?;
+        return scale_component_on_host(client, cmd, host_id, component_ref).await;
+    };
+    let host_id = find_host_id_with(host, &client, &cmd.host_match).await?.0;
+    scale_component_on_host(client, cmd, host_id, component_ref).await
+}
 
-    let info = scale_component(ScaleComponentArgs {
-        client: &client,
-        // NOTE(thomastaylor312): In the future, we could check if this is interactive and then
-        // prompt the user to choose if more than one thing matches
-        host_id: &find_host_id(&cmd.host_id, &client).await?.0,
-        component_id: &cmd.component_id,
-        component_ref: &component_ref,
-        max_instances: cmd.max_instances,
-        annotations: Some(annotations),
-        config: cmd.config,
-        skip_wait: cmd.skip_wait,
-        timeout_ms: None,
-    })
-    .await?;
+/// Pick the host to scale on when no host ID was given. Hosts already running the component don't
+/// take part in component auctions, so a host matching the constraints that runs it is used if
+/// there is one. Otherwise the component is auctioned to the hosts matching the constraints
+async fn auction_scale_host(
+    client: &wasmcloud_control_interface::Client,
+    cmd: &ScaleComponentCommand,
+    component_ref: &str,
+    constraints: &BTreeMap<String, String>,
+) -> Result<ServerId> {
+    let inventories = get_all_inventories(client).await?;
+    let running = hosts_running_component(&inventories, &cmd.component_id, constraints);
+    let host_id = match running.as_slice() {
+        [] => {
+            let responders = auction_component(
+                client,
+                component_ref,
+                &cmd.component_id,
+                constraints,
+                &cmd.progress,
+            )
+            .await?;
+            return Ok(choose_auction_host(
+                client,
+                &responders,
+                cmd.placement,
+                &cmd.component_id,
+                cmd.host_match.interactive,
+            )
+            .await?
+            .0);
+        }
+        [host_id] => host_id.clone(),
+        _ if cmd.host_match.interactive => {
+            let hosts = client
+                .get_hosts()
+                .await
+                .map_err(boxed_err_to_anyhow)
+                .context("unable to fetch hosts")?
+                .into_iter()
+                .filter_map(wasmcloud_control_interface::CtlResponse::into_data)
+                .collect::<Vec<_>>();
+            pick_host(&running, &hosts)?
+        }
+        _ => bail!(
+            "Component [{}] is running on {} hosts matching the constraints ({}), pass a host \
+             ID to choose one",
+            cmd.component_id,
+            running.len(),
+            running.join(", ")
+        ),
+    };
+    host_id
+        .parse()
+        .with_context(|| format!("Failed to parse host id: {host_id}"))
+}
+
+/// Preview a scale without a host ID on every host it could land on: the hosts matching the
+/// constraints that already run the component or, if there are none, the eligible hosts that
+/// respond to an auction. The same checks as a real scale are applied to each of them
+async fn preview_auction_scale(
+    client: &wasmcloud_control_interface::Client,
+    cmd: &ScaleComponentCommand,
+    component_ref: &str,
+    constraints: &BTreeMap<String, String>,
+) -> Result<CommandOutput> {
+    let inventories = get_all_inventories(client).await?;
+    let mut targets = hosts_running_component(&inventories, &cmd.component_id, constraints);
+    if targets.is_empty() {
+        let responders = auction_component(
+            client,
+            component_ref,
+            &cmd.component_id,
+            constraints,
+            &cmd.progress,
+        )
+        .await?;
+        targets = filter_auction_candidates(&responders.host_ids, &responders.labels).0;
+    }
+    let targeted = inventories
+        .into_iter()
+        .filter(|inv| targets.iter().any(|id| id == inv.host_id()))
+        .collect::<Vec<_>>();
+
+    let match_annotations = input_vec_to_hashmap(cmd.match_annotations.clone())?;
+    let mut preview = preview_component_scale(&targeted, &cmd.component_id, cmd.max_instances);
+    for (inventory, host) in targeted.iter().zip(preview.hosts.iter_mut()) {
+        if let Some(owner) = &cmd.owner {
+            check_component_owner(inventory, &cmd.component_id, owner)?;
+        }
+        check_component_annotations(inventory, &cmd.component_id, &match_annotations)?;
+        if cmd.cap_at_host_capacity {
+            host.target = cap_at_host_capacity(inventory, &cmd.component_id, host.target)?;
+        }
+    }
+    preview.target_total = preview.hosts.iter().map(|h| u64::from(h.target)).sum();
+    Ok(scale_preview_output(preview))
+}
+
+/// The IDs of the hosts whose labels match every constraint and that run the given component
+#[must_use]
+pub fn hosts_running_component(
+    inventories: &[HostInventory],
+    component_id: &str,
+    constraints: &BTreeMap<String, String>,
+) -> Vec<String> {
+    inventories
+        .iter()
+        .filter(|inv| {
+            constraints
+                .iter()
+                .all(|(k, v)| inv.labels().get(k) == Some(v))
+        })
+        .filter(|inv| inv.components().iter().any(|c| c.id() == component_id))
+        .map(|inv| inv.host_id().to_string())
+        .collect()
+}
+
+async fn scale_component_on_host(
+    client: wasmcloud_control_interface::Client,
+    cmd: ScaleComponentCommand,
+    host_id: ServerId,
+    component_ref: String,
+) -> Result<CommandOutput> {
+    let mut max_instances = cmd.max_instances;
+    let mut preview = None;
+    let match_annotations = input_vec_to_hashmap(cmd.match_annotations.clone())?;
+    if cmd.dry_run
+        || cmd.owner.is_some()
+        || cmd.cap_at_host_capacity
+        || !match_annotations.is_empty()
+    {
+        let inventory = client
+            .get_host_inventory(&host_id)
+            .await
+            .map_err(boxed_err_to_anyhow)?
+            .into_data()
+            .with_context(|| format!("No inventory returned for host [{host_id}]"))?;
+        if let Some(owner) = &cmd.owner {
+            check_component_owner(&inventory, &cmd.component_id, owner)?;
+        }
+        check_component_annotations(&inventory, &cmd.component_id, &match_annotations)?;
+        if cmd.cap_at_host_capacity {
+            max_instances = cap_at_host_capacity(&inventory, &cmd.component_id, max_instances)?;
+        }
+        if cmd.dry_run {
+            preview = Some(preview_component_scale(
+                &[inventory],
+                &cmd.component_id,
+                max_instances,
+            ));
+        }
+    }
+
+    let mut annotations = scale_annotations(cmd.annotations_file.as_deref(), cmd.annotations)?;
+    if let Some(owner) = cmd.owner {
+        annotations.insert(OWNER_ANNOTATION.to_string(), owner);
+    }
+
+    if let Some(preview) = preview {
+        return Ok(scale_preview_output(preview));
+    }
+
+    let info = cmd
+        .retry
+        .retry(|attempt| {
+            let (client, host_id, component_ref) = (&client, &host_id, &component_ref);
+            let (component_id, config) = (&cmd.component_id, cmd.config.clone());
+            let annotations = annotations.clone();
+            async move {
+                // A scale request that timed out may have reached the host after all, so only
+                // send it again if the host isn't at the requested scale yet
+                if attempt > 0
+                    && scaled_on_host(client, host_id, component_id, component_ref, max_instances)
+                        .await
+                {
+                    return Ok(ComponentScaledInfo {
+                        host_id: host_id.to_string(),
+                        component_ref: component_ref.to_string(),
+                        component_id: component_id.to_string(),
+                    });
+                }
+                scale_component(ScaleComponentArgs {
+                    client,
+                    host_id,
+                    component_id,
+                    component_ref,
+                    max_instances,
+                    annotations: Some(annotations),
+                    config,
+                    skip_wait: cmd.skip_wait,
+                    timeout_ms: None,
+                })
+                .await
+            }
+        })
+        .await?;
 
-    let scale_msg = if cmd.max_instances == u32::MAX {
+    if cmd.wait_for_ready {
+        wait_for_component_scale(
+            Duration::from_millis(cmd.wait_timeout_ms),
+            SCALE_READY_POLL_INTERVAL,
+            || async {
+                Ok(scaled_on_host(
+                    &client,
+                    &host_id,
+                    &info.component_id,
+                    &info.component_ref,
+                    max_instances,
+                )
+                .await)
+            },
+        )
+        .await
+        .with_context(|| {
+            format!(
+                "Component [{}] was scaled on host [{}] but never reached {max_instances} max instances",
+                info.component_id, info.host_id
+            )
+        })?;
+    }
+
+    let mut scale_msg = if max_instances == 0 {
+        "zero, stopping it".to_string()
+    } else if max_instances == u32::MAX {
         "unbounded concurrency".to_string()
     } else {
-        format!("{} max concurrent instances", cmd.max_instances)
+        format!("{max_instances} max concurrent instances")
     };
+    if max_instances < cmd.max_instances {
+        scale_msg.push_str(" (capped to the host's capacity)");
+    }
+
+    if cmd.wait_for_ready {
+        scale_msg.push_str(", as reported by the host");
+    }
 
     let text = format!(
         "Component [{}] (ref: [{}]) scaled on host [{}] to {scale_msg}",
         info.component_id, info.component_ref, info.host_id,
     );
-    Ok(CommandOutput::new(
+    let mut output = CommandOutput::new(
         text.clone(),
         HashMap::from([
             ("host_id".into(), info.host_id.into()),
             ("component_id".into(), info.component_id.into()),
             ("component_ref".into(), info.component_ref.into()),
+            ("max_instances".into(), max_instances.into()),
+            ("scaled_to_zero".into(), (max_instances == 0).into()),
             ("result".into(), text.into()),
         ]),
+    );
+    if cmd.wait_for_ready {
+        output.map.insert("ready".into(), true.into());
+    }
+    Ok(output)
+}
+
+/// Whether the host already runs the component at the given scale
+async fn scaled_on_host(
+    client: &wasmcloud_control_interface::Client,
+    host_id: &str,
+    component_id: &str,
+    component_ref: &str,
+    max_instances: u32,
+) -> bool {
+    client
+        .get_host_inventory(host_id)
+        .await
+        .ok()
+        .and_then(wasmcloud_control_interface::CtlResponse::into_data)
+        .is_some_and(|inventory| {
+            is_scaled_to(&inventory, component_id, component_ref, max_instances)
+        })
+}
+
+/// Build the annotations of a scale command from an optional annotations file and the `key=value`
+/// annotations given as flags. Like repeated flags, later values win, so a flag overrides the same
+/// key from the file
+pub fn scale_annotations(
+    file: Option<&Path>,
+    flags: Vec<String>,
+) -> Result<HashMap<String, String>> {
+    let mut annotations = HashMap::new();
+    if let Some(path) = file {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read annotations file [{}]", path.display()))?;
+        let from_file: BTreeMap<String, String> = serde_yaml::from_str(&contents)
+            .with_context(|| format!("failed to parse annotations file [{}]", path.display()))?;
+        annotations.extend(from_file);
+    }
+    annotations.extend(input_vec_to_hashmap(flags)?);
+    Ok(annotations)
+}
+
+fn scale_preview_output(preview: ComponentScalePreview) -> CommandOutput {
+    let mut text = format!(
+        "Dry run, no scale commands were sent for [{}]",
+        preview.component_id
+    );
+    for host in &preview.hosts {
+        text.push_str(&format!(
+            "\n  host [{}]: {} -> {} max instances",
+            host.host_id, host.current, host.target
+        ));
+    }
+    text.push_str(&format!(
+        "\n  total: {} -> {} max instances",
+        preview.current_total, preview.target_total
+    ));
+    CommandOutput::new(
+        text,
+        HashMap::from([
+            ("dry_run".into(), true.into()),
+            ("component_id".into(), preview.component_id.clone().into()),
+            ("current_total".into(), preview.current_total.into()),
+            ("target_total".into(), preview.target_total.into()),
+            (
+                "hosts".into(),
+                serde_json::to_value(&preview.hosts).unwrap_or_default(),
+            ),
+        ]),
+    )
+}
+
+/// A completed scale action of `wash scale apply`, as streamed with `--output ndjson`
+#[derive(Serialize)]
+struct ScaleApplyRecord<'a> {
+    #[serde(flatten)]
+    action: &'a ComponentScaleAction,
+    dry_run: bool,
+}
+
+/// Perform the planned scale actions in order, stopping at the first failure. Each completed action
+/// is written to `stream` as soon as it finishes
+async fn apply_scale_actions<W, F, Fut>(
+    actions: &[ComponentScaleAction],
+    dry_run: bool,
+    mut stream: Option<&mut NdjsonStream<W>>,
+    mut scale: F,
+) -> Result<()>
+where
+    W: Write,
+    F: FnMut(&ComponentScaleAction) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    for action in actions {
+        if !dry_run {
+            scale(action).await?;
+        }
+        if let Some(stream) = stream.as_deref_mut() {
+            stream.emit(&ScaleApplyRecord { action, dry_run })?;
+        }
+    }
+    Ok(())
+}
+
+pub async fn handle_scale_apply(
+    cmd: ScaleApplyCommand,
+    output_kind: OutputKind,
+) -> Result<CommandOutput> {
+    let contents = tokio::fs::read_to_string(&cmd.file)
+        .await
+        .with_context(|| format!("failed to read [{}]", cmd.file.display()))?;
+    let desired: DesiredComponentCounts = serde_yaml::from_str(&contents)
+        .with_context(|| format!("failed to parse [{}]", cmd.file.display()))?;
+
+    let wco: WashConnectionOptions = cmd.opts.try_into()?;
+    let client = wco.into_ctl_client(None).await?;
+
+    let inventories = get_all_inventories(&client).await?;
+    let actions = plan_component_scale(&desired, &inventories)?;
+
+    let mut stream = NdjsonStream::for_output(output_kind);
+    apply_scale_actions(&actions, cmd.dry_run, stream.as_mut(), |action| {
+        let client = client.clone();
+        let action = action.clone();
+        async move {
+            scale_component(ScaleComponentArgs {
+                client: &client,
+                host_id: &action.host_id,
+                component_id: &action.component_id,
+                component_ref: &action.component_ref,
+                max_instances: action.desired,
+                annotations: None,
+                config: vec![],
+                skip_wait: false,
+                timeout_ms: None,
+            })
+            .await
+            .map(|_| ())
+        }
+    })
+    .await?;
+
+    let verb = if cmd.dry_run { "Would scale" } else { "Scaled" };
+    let mut text = String::new();
+    for action in &actions {
+        text.push_str(&format!(
+            "{verb} component [{}] on host [{}] from {} to {} max instances\n",
+            action.component_id, action.host_id, action.current, action.desired
+        ));
+    }
+    if actions.is_empty() {
+        text.push_str("All components are already at their desired counts");
+    }
+
+    Ok(CommandOutput::new(
+        text.trim_end().to_string(),
+        HashMap::from([
+            ("dry_run".into(), cmd.dry_run.into()),
+            ("actions".into(), serde_json::to_value(&actions)?),
+        ]),
+    ))
+}
+
+/// The outcome of one scale command of a manifest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ScaleManifestResult {
+    #[serde(flatten)]
+    pub row: ScaleManifestRow,
+    /// Set if the component could not be scaled
+    pub error: Option<String>,
+}
+
+pub async fn handle_scale_manifest(cmd: ScaleManifestCommand) -> Result<CommandOutput> {
+    let contents = tokio::fs::read_to_string(&cmd.file)
+        .await
+        .with_context(|| format!("failed to read [{}]", cmd.file.display()))?;
+    let entries: Vec<ScaleManifestEntry> = serde_yaml::from_str(&contents)
+        .with_context(|| format!("failed to parse [{}]", cmd.file.display()))?;
+
+    let wco: WashConnectionOptions = cmd.opts.try_into()?;
+    let client = wco.into_ctl_client(None).await?;
+
+    let result = async {
+        let inventories = get_all_inventories(&client).await?;
+        let rows = resolve_scale_manifest(&entries, &inventories)?;
+        let scales = rows.iter().map(|row| {
+            scale_component(ScaleComponentArgs {
+                client: &client,
+                host_id: &row.host_id,
+                component_id: &row.component_id,
+                component_ref: &row.component_ref,
+                max_instances: row.max_instances,
+                annotations: (!row.annotations.is_empty())
+                    .then(|| row.annotations.clone().into_iter().collect()),
+                config: Vec::new(),
+                skip_wait: cmd.skip_wait,
+                timeout_ms: Some(cmd.wait_timeout_ms),
+            })
+        });
+        let outcomes = futures::future::join_all(scales).await;
+        let results = rows
+            .into_iter()
+            .zip(outcomes)
+            .map(|(row, outcome)| ScaleManifestResult {
+                row,
+                error: outcome.err().map(|e| format!("{e:#}")),
+            })
+            .collect::<Vec<_>>();
+        scale_manifest_output(&results)
+    }
+    .await;
+    close_ctl_client(&client).await;
+    result
+}
+
+/// Render the outcomes of a scale manifest as a table. If any scale failed, the table is returned
+/// as a [`FailureKind::TargetsFailed`] error so the command exits with its code
+pub fn scale_manifest_output(results: &[ScaleManifestResult]) -> Result<CommandOutput> {
+    let failed = results.iter().filter(|r| r.error.is_some()).count();
+    let mut table = Table::new();
+    configure_table_style(&mut table);
+    table.add_row(Row::new(vec![
+        TableCell::new_with_alignment("Component ID", 1, Alignment::Left),
+        TableCell::new_with_alignment("Host ID", 1, Alignment::Left),
+        TableCell::new_with_alignment("Max Instances", 1, Alignment::Left),
+        TableCell::new_with_alignment("Result", 1, Alignment::Left),
+    ]));
+    for result in results {
+        table.add_row(Row::new(vec![
+            TableCell::new_with_alignment(&result.row.component_id, 1, Alignment::Left),
+            TableCell::new_with_alignment(&result.row.host_id, 1, Alignment::Left),
+            TableCell::new_with_alignment(result.row.max_instances, 1, Alignment::Left),
+            TableCell::new_with_alignment(
+                result.error.as_deref().unwrap_or("scaled"),
+                1,
+                Alignment::Left,
+            ),
+        ]));
+    }
+    let summary = if failed == 0 {
+        format!("Sent {} scale command(s)", results.len())
+    } else {
+        format!("Failed {failed} of {} scale command(s)", results.len())
+    };
+    let output = CommandOutput::new(
+        format!("{summary}\n{}", table.render()),
+        HashMap::from([
+            ("results".into(), serde_json::to_value(results)?),
+            ("partial".into(), (failed > 0).into()),
+        ]),
+    );
+    if failed > 0 {
+        return Err(
+            Failure::with_details(FailureKind::TargetsFailed, output.text, output.map).into(),
+        );
+    }
+    Ok(output)
+}
+
+/// The inventory of the given host, or of every host in the lattice
+async fn scale_status_inventories(
+    client: &wasmcloud_control_interface::Client,
+    host_id: Option<&str>,
+) -> Result<Vec<HostInventory>> {
+    match host_id {
+        Some(host_id) => Ok(client
+            .get_host_inventory(host_id)
+            .await
+            .map_err(boxed_err_to_anyhow)?
+            .into_data()
+            .into_iter()
+            .collect()),
+        None => get_all_inventories(client).await,
+    }
+}
+
+pub async fn handle_scale_status(cmd: ScaleStatusCommand) -> Result<CommandOutput> {
+    let wco: WashConnectionOptions = cmd.opts.try_into()?;
+    let client = wco.into_ctl_client(None).await?;
+    let component_ref = resolve_ref(&cmd.component_ref).await?;
+
+    let host_id = match &cmd.host_id {
+        Some(host) => Some(
+            find_host_id_with(host, &client, &cmd.host_match)
+                .await?
+                .0
+                .to_string(),
+        ),
+        None => None,
+    };
+    let mut receiver = client
+        .events_receiver(vec![
+            "component_scaled".to_string(),
+            "component_scale_failed".to_string(),
+        ])
+        .await
+        .map_err(boxed_err_to_anyhow)
+        .context("Failed to get lattice event channel")?;
+    let mut inventories = scale_status_inventories(&client, host_id.as_deref()).await?;
+    let filter = EventFilter {
+        image_ref: Some(component_ref.clone()),
+        host_id: host_id.clone(),
+        ..Default::default()
+    };
+    let events = record_events(&mut receiver, cmd.window, &filter).await;
+    if !events.is_empty() {
+        inventories = scale_status_inventories(&client, host_id.as_deref()).await?;
+    }
+    let status = component_scale_status(&component_ref, &inventories, &events);
+
+    let mut text = format!(
+        "Component [{}] is running {} max instances across {} host(s)",
+        status.component_ref,
+        status.total_instances,
+        status.hosts.len()
+    );
+    for host in &status.hosts {
+        text.push_str(&format!(
+            "\n  host [{}]: component [{}] at {} max instances",
+            host.host_id, host.component_id, host.max_instances
+        ));
+    }
+    match (&status.last_scale, status.target_reached) {
+        (Some(scale), Some(reached)) => {
+            let outcome = match (&scale.error, reached) {
+                (Some(error), _) => format!("failed: {error}"),
+                (None, true) => "target reached".to_string(),
+                (None, false) => "target not reached yet".to_string(),
+            };
+            text.push_str(&format!(
+                "\nLast scale on host [{}] to {} max instances: {outcome}",
+                scale.host_id, scale.max_instances
+            ));
+        }
+        _ => text.push_str("\nNo scale events were observed"),
+    }
+
+    Ok(CommandOutput::new(
+        text,
+        HashMap::from([
+            ("component_ref".into(), status.component_ref.clone().into()),
+            ("total_instances".into(), status.total_instances.into()),
+            ("hosts".into(), serde_json::to_value(&status.hosts)?),
+            (
+                "last_scale".into(),
+                serde_json::to_value(&status.last_scale)?,
+            ),
+            ("target_reached".into(), status.target_reached.into()),
+        ]),
     ))
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn each_completed_scale_is_streamed_as_one_line() {
+        let actions = ["host-a", "host-b", "host-c"]
+            .into_iter()
+            .map(|host| ComponentScaleAction {
+                host_id: host.to_string(),
+                component_id: "hello".to_string(),
+                component_ref: "ghcr.io/wasmcloud/hello:0.1.0".to_string(),
+                current: 1,
+                desired: 3,
+            })
+            .collect::<Vec<_>>();
+
+        let mut stream = NdjsonStream::new(Vec::new());
+        apply_scale_actions(&actions, false, Some(&mut stream), |_| async { Ok(()) })
+            .await
+            .expect("should apply actions");
+        let output = String::from_utf8(stream.into_inner()).expect("should be utf8");
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), actions.len());
+        for (line, action) in lines.iter().zip(&actions) {
+            let record: serde_json::Value =
+                serde_json::from_str(line).expect("each line should be a complete JSON object");
+            assert_eq!(record["host_id"], action.host_id.as_str());
+            assert_eq!(record["component_id"], "hello");
+            assert_eq!(record["desired"], 3);
+            assert_eq!(record["dry_run"], false);
+        }
+
+        // Only targets that completed are streamed
+        let mut stream = NdjsonStream::new(Vec::new());
+        let err = apply_scale_actions(&actions, false, Some(&mut stream), |action| {
+            let failed = action.host_id == "host-b";
+            async move {
+                if failed {
+                    anyhow::bail!("host unreachable");
+                }
+                Ok(())
+            }
+        })
+        .await
+        .expect_err("failed scale should be returned");
+        assert_eq!(err.to_string(), "host unreachable");
+        let output = String::from_utf8(stream.into_inner()).expect("should be utf8");
+        assert_eq!(output.lines().count(), 1);
+        assert!(output.contains("\"host_id\":\"host-a\""));
+    }
+
+    #[test]
+    fn annotations_are_merged_from_file_and_flags() {
+        let dir = tempfile::tempdir().expect("should create temp dir");
+        let path = dir.path().join("annotations.yaml");
+        std::fs::write(&path, "deployment: checkout\nteam: payments\n")
+            .expect("should write annotations file");
+
+        let file_only = scale_annotations(Some(&path), Vec::new()).expect("should load file");
+        assert_eq!(
+            file_only,
+            HashMap::from([
+                ("deployment".to_string(), "checkout".to_string()),
+                ("team".to_string(), "payments".to_string()),
+            ])
+        );
+
+        let flag_only =
+            scale_annotations(None, vec!["team=search".to_string()]).expect("should parse flags");
+        assert_eq!(
+            flag_only,
+            HashMap::from([("team".to_string(), "search".to_string())])
+        );
+
+        let merged = scale_annotations(
+            Some(&path),
+            vec!["team=search".to_string(), "revision=3".to_string()],
+        )
+        .expect("should merge annotations");
+        assert_eq!(
+            merged,
+            HashMap::from([
+                ("deployment".to_string(), "checkout".to_string()),
+                ("team".to_string(), "search".to_string()),
+                ("revision".to_string(), "3".to_string()),
+            ])
+        );
+
+        let json = dir.path().join("annotations.json");
+        std::fs::write(&json, r#"{"deployment": "checkout"}"#).expect("should write json file");
+        assert_eq!(
+            scale_annotations(Some(&json), Vec::new()).expect("should load json file")
+                ["deployment"],
+            "checkout"
+        );
+
+        assert!(scale_annotations(Some(&dir.path().join("missing.yaml")), Vec::new()).is_err());
+        assert!(scale_annotations(None, vec!["not-a-pair".to_string()]).is_err());
+    }
+
+    #[test]
+    fn manifest_results_are_reported_per_row() {
+        let row = |host_id: &str| ScaleManifestRow {
+            host_id: host_id.to_string(),
+            component_id: "hello".to_string(),
+            component_ref: "ghcr.io/wasmcloud/hello:0.1.0".to_string(),
+            max_instances: 5,
+            annotations: BTreeMap::new(),
+        };
+        let results = vec![
+            ScaleManifestResult {
+                row: row("host-a"),
+                error: None,
+            },
+            ScaleManifestResult {
+                row: row("host-b"),
+                error: Some("Operation failed: host is shutting down".to_string()),
+            },
+        ];
+
+        let Err(err) = scale_manifest_output(&results) else {
+            panic!("a failed scale should fail");
+        };
+        assert_eq!(FailureKind::of(&err), Some(FailureKind::TargetsFailed));
+        let failure = err
+            .downcast_ref::<Failure>()
+            .expect("error should be a failure");
+        assert!(failure
+            .message
+            .starts_with("Failed 1 of 2 scale command(s)"));
+        assert!(failure.message.contains("host is shutting down"));
+        assert_eq!(failure.details["partial"], true);
+        assert_eq!(failure.details["results"][0]["host_id"], "host-a");
+        assert!(failure.details["results"][0]["error"].is_null());
+
+        let output = scale_manifest_output(&results[..1]).expect("should render output");
+        assert!(output.text.starts_with("Sent 1 scale command(s)"));
+        assert_eq!(output.map["partial"], false);
+    }
+
+    #[test]
+    fn scale_host_is_found_among_hosts_running_the_component() {
+        let inventory = |host_id: &str, zone: &str, components: &[&str]| {
+            HostInventory::builder()
+                .host_id(host_id.into())
+                .friendly_name(host_id.into())
+                .version("1.0.0".into())
+                .uptime_human("t".into())
+                .uptime_seconds(100)
+                .labels(BTreeMap::from([("zone".to_string(), zone.to_string())]))
+                .components(
+                    components
+                        .iter()
+                        .map(|id| {
+                            wasmcloud_control_interface::ComponentDescription::builder()
+                                .id(id.to_string())
+                                .image_ref(format!("ghcr.io/wasmcloud/{id}:0.1.0"))
+                                .build()
+                                .expect("should build component description")
+                        })
+                        .collect(),
+                )
+                .build()
+                .expect("should build host inventory")
+        };
+        let inventories = [
+            inventory("host-a", "east", &["hello"]),
+            inventory("host-b", "west", &["hello", "echo"]),
+            inventory("host-c", "east", &["echo"]),
+        ];
+        let east = BTreeMap::from([("zone".to_string(), "east".to_string())]);
+
+        assert_eq!(
+            hosts_running_component(&inventories, "hello", &BTreeMap::new()),
+            vec!["host-a", "host-b"]
+        );
+        assert_eq!(
+            hosts_running_component(&inventories, "hello", &east),
+            vec!["host-a"]
+        );
+        assert!(hosts_running_component(&inventories, "kvcounter", &east).is_empty());
+    }
+
+    #[test]
+    fn host_id_can_be_omitted_for_an_auction() {
+        #[derive(Parser, Debug)]
+        struct Cmd {
+            #[clap(flatten)]
+            command: ScaleComponentCommand,
+        }
+        let parse = |args: &[&str]| -> Result<ScaleComponentCommand> {
+            let mut cmd = Cmd::try_parse_from([&["scale"], args].concat())?.command;
+            cmd.take_deprecated_host_id()?;
+            Ok(cmd)
+        };
+
+        let cmd = parse(&["ghcr.io/hello:0.1.0", "hello", "--host-id", "host"]).unwrap();
+        assert_eq!(cmd.host_id.as_deref(), Some("host"));
+        assert_eq!(cmd.component_ref, "ghcr.io/hello:0.1.0");
+        assert_eq!(cmd.component_id, "hello");
+
+        let cmd = parse(&[
+            "ghcr.io/hello:0.1.0",
+            "hello",
+            "--constraint",
+            "zone=east",
+            "--placement",
+            "spread",
+        ])
+        .unwrap();
+        assert_eq!(cmd.host_id, None);
+        assert_eq!(cmd.component_ref, "ghcr.io/hello:0.1.0");
+        assert_eq!(cmd.component_id, "hello");
+        assert_eq!(cmd.placement, Placement::Spread);
+
+        assert!(parse(&["ghcr.io/hello:0.1.0", "hello/world"]).is_err());
+        assert!(parse(&["ghcr.io/hello:0.1.0"]).is_err());
+        assert!(parse(&[
+            "ghcr.io/hello:0.1.0",
+            "hello",
+            "--host-id",
+            "host",
+            "--constraint",
+            "a=b"
+        ])
+        .is_err());
+
+        // The deprecated form with the host ID as the first positional argument still works
+        let cmd = parse(&["host", "ghcr.io/hello:0.1.0", "hello"]).unwrap();
+        assert_eq!(cmd.host_id.as_deref(), Some("host"));
+        assert_eq!(cmd.component_ref, "ghcr.io/hello:0.1.0");
+        assert_eq!(cmd.component_id, "hello");
+        assert_eq!(cmd.legacy_component_id, None);
+
+        assert!(parse(&["host", "ghcr.io/hello:0.1.0", "hello/world"]).is_err());
+        assert!(parse(&["host", "ghcr.io/hello:0.1.0", "hello", "--host-id", "host"]).is_err());
+        assert!(parse(&[
+            "host",
+            "ghcr.io/hello:0.1.0",
+            "hello",
+            "--constraint",
+            "a=b"
+        ])
+        .is_err());
+    }
+}
@@ -1,9 +1,20 @@
-use anyhow::Result;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
 use clap::Parser;
+use futures::stream::FuturesUnordered;
 use futures::StreamExt;
+use serde::Serialize;
+
+use super::{validate_component_id, CliConnectionOpts, CommandOutput, NdjsonStream, OutputKind};
+use crate::lib::{
+    config::WashConnectionOptions,
+    spier::{trace_invocation, InvocationTrace, ObservedInvocation, Spier},
+};
 
-use super::{validate_component_id, CliConnectionOpts, CommandOutput};
-use crate::lib::{config::WashConnectionOptions, spier::Spier};
+/// How long to wait for the results of an invocation before reporting it as failed, by default
+const DEFAULT_RESPONSE_TIMEOUT_MS: u64 = 30_000;
 
 #[derive(Debug, Parser, Clone)]
 pub struct SpyCommand {
@@ -12,33 +23,220 @@ pub struct SpyCommand {
     #[clap(name = "component_id", value_parser = validate_component_id)]
     pub component_id: String,
 
+    /// Only show invocations matching a filter, given as `operation=<text>`, `source=<id>` or
+    /// `target=<id>`. An operation matches if it contains the text. May be passed multiple times,
+    /// in which case all filters must match
+    #[clap(long = "filter", name = "filters")]
+    pub filters: Vec<SpyFilter>,
+
+    /// How long to wait for the results of an invocation before reporting it as failed, in
+    /// milliseconds
+    #[clap(long = "response-timeout-ms", default_value_t = DEFAULT_RESPONSE_TIMEOUT_MS)]
+    pub response_timeout_ms: u64,
+
     #[clap(flatten)]
     pub opts: CliConnectionOpts,
 }
 
-/// Handles the spy command, printing all output to stdout until the command is interrupted
-pub async fn handle_command(cmd: SpyCommand) -> Result<CommandOutput> {
-    let wco: WashConnectionOptions = cmd.opts.try_into()?;
-    let ctl_client = wco.clone().into_ctl_client(None).await?;
-    let nats_client = wco.into_nats_client().await?;
+/// A filter on the invocations shown by `wash spy`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpyFilter {
+    /// The invoked operation contains the text
+    Operation(String),
+    /// The invocation was sent by the entity
+    Source(String),
+    /// The invocation was sent to the entity
+    Target(String),
+}
 
-    let mut spier = Spier::new(&cmd.component_id, &ctl_client, &nats_client).await?;
+impl SpyFilter {
+    #[must_use]
+    pub fn matches(&self, invocation: &ObservedInvocation) -> bool {
+        match self {
+            Self::Operation(text) => invocation.operation.contains(text.as_str()),
+            Self::Source(id) => invocation.from == *id,
+            Self::Target(id) => invocation.to == *id,
+        }
+    }
+}
 
-    println!("Spying on component {}\n", spier.component_id());
+impl FromStr for SpyFilter {
+    type Err = anyhow::Error;
 
-    while let Some(msg) = spier.next().await {
-        println!(
-            r"
+    fn from_str(s: &str) -> Result<Self> {
+        let (field, value) = s
+            .split_once('=')
+            .context("filters must be given as <field>=<value>")?;
+        if value.is_empty() {
+            bail!("filter [{field}] has no value");
+        }
+        match field {
+            "operation" | "op" => Ok(Self::Operation(value.to_string())),
+            "source" | "from" => Ok(Self::Source(value.to_string())),
+            "target" | "to" => Ok(Self::Target(value.to_string())),
+            _ => bail!("unknown filter [{field}], expected operation, source or target"),
+        }
+    }
+}
+
+/// An invocation as printed for structured output, one line per invocation
+#[derive(Debug, Serialize)]
+struct SpyRecord<'a> {
+    timestamp: String,
+    from: &'a str,
+    to: &'a str,
+    operation: &'a str,
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latency_ms: Option<u128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'a str>,
+    message: String,
+}
+
+impl<'a> From<&'a InvocationTrace> for SpyRecord<'a> {
+    fn from(trace: &'a InvocationTrace) -> Self {
+        let invocation = &trace.invocation;
+        Self {
+            timestamp: invocation.timestamp.to_rfc3339(),
+            from: &invocation.from,
+            to: &invocation.to,
+            operation: &invocation.operation,
+            success: trace.succeeded(),
+            latency_ms: trace.latency.map(|latency| latency.as_millis()),
+            error: trace.error.as_deref(),
+            message: invocation.message.to_string(),
+        }
+    }
+}
+
+fn print_trace(trace: &InvocationTrace) {
+    let invocation = &trace.invocation;
+    let result = match (&trace.latency, &trace.error) {
+        (_, Some(err)) => format!("failed ({err})"),
+        (Some(latency), None) => format!("success in {}ms", latency.as_millis()),
+        (None, None) => "success".to_string(),
+    };
+    println!(
+        r"
 [{}]
 From: {:<25} To: {:<25}
 
 Operation: {}
+Result: {}
 Message: {}",
-            msg.timestamp, msg.from, msg.to, msg.operation, msg.message
-        );
+        invocation.timestamp,
+        invocation.from,
+        invocation.to,
+        invocation.operation,
+        result,
+        invocation.message
+    );
+}
+
+/// Handles the spy command, printing each invocation to stdout once it is answered (or times out)
+/// until the command is interrupted. With structured output, each invocation is printed as a JSON
+/// line
+pub async fn handle_command(cmd: SpyCommand, output_kind: OutputKind) -> Result<CommandOutput> {
+    let wco: WashConnectionOptions = cmd.opts.try_into()?;
+    let ctl_client = wco.clone().into_ctl_client(None).await?;
+    let nats_client = wco.into_nats_client().await?;
+
+    let mut spier = Spier::new(&cmd.component_id, &ctl_client, &nats_client).await?;
+    let mut stream = output_kind.is_structured().then(NdjsonStream::stdout);
+    let response_timeout = Duration::from_millis(cmd.response_timeout_ms);
+
+    if stream.is_none() {
+        println!("Spying on component {}\n", spier.component_id());
+    }
+
+    let mut report = |trace: InvocationTrace| match stream.as_mut() {
+        Some(stream) => stream.emit(&SpyRecord::from(&trace)),
+        None => {
+            print_trace(&trace);
+            Ok(())
+        }
+    };
+    let mut pending = FuturesUnordered::new();
+    loop {
+        tokio::select! {
+            invocation = spier.next() => match invocation {
+                Some(invocation) if cmd.filters.iter().all(|filter| filter.matches(&invocation)) => {
+                    // Subscribe to the results before anything else is polled, they may be sent
+                    // right away
+                    let trace = trace_invocation(&nats_client, invocation, response_timeout).await;
+                    pending.push(trace.finish());
+                }
+                Some(_) => {}
+                None => break,
+            },
+            Some(trace) = pending.next(), if !pending.is_empty() => report(trace)?,
+        }
+    }
+    while let Some(trace) = pending.next().await {
+        report(trace)?;
     }
 
-    println!("Message subscribers closed");
+    if stream.is_none() {
+        println!("Message subscribers closed");
+    }
 
     Ok(CommandOutput::default())
 }
+
+#[cfg(test)]
+mod test {
+    use chrono::Local;
+
+    use super::*;
+    use crate::lib::spier::ObservedMessage;
+
+    #[test]
+    fn invocations_are_filtered() {
+        let invocation = ObservedInvocation {
+            timestamp: Local::now(),
+            from: "wash".to_string(),
+            to: "http-hello".to_string(),
+            operation: "wasi:http/incoming-handler@0.2.0.handle".to_string(),
+            message: ObservedMessage::parse(Vec::new()),
+            reply: None,
+        };
+
+        let filter: SpyFilter = "operation=incoming-handler".parse().unwrap();
+        assert!(filter.matches(&invocation));
+        assert!(!"op=wasi:cli"
+            .parse::<SpyFilter>()
+            .unwrap()
+            .matches(&invocation));
+        assert!("source=wash"
+            .parse::<SpyFilter>()
+            .unwrap()
+            .matches(&invocation));
+        assert!("to=http-hello"
+            .parse::<SpyFilter>()
+            .unwrap()
+            .matches(&invocation));
+        assert!(!"target=wash"
+            .parse::<SpyFilter>()
+            .unwrap()
+            .matches(&invocation));
+
+        assert!("latency=10".parse::<SpyFilter>().is_err());
+        assert!("operation".parse::<SpyFilter>().is_err());
+        assert!("operation=".parse::<SpyFilter>().is_err());
+
+        let trace = InvocationTrace {
+            invocation,
+            latency: Some(Duration::from_millis(12)),
+            error: None,
+        };
+        let record = serde_json::to_value(SpyRecord::from(&trace)).unwrap();
+        assert_eq!(record["success"], true);
+        assert_eq!(record["latency_ms"], 12);
+        assert_eq!(
+            record["operation"],
+            "wasi:http/incoming-handler@0.2.0.handle"
+        );
+        assert!(record.get("error").is_none());
+    }
+}
@@ -1,30 +1,69 @@
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use anyhow::{bail, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use clap::Parser;
+use cloudevents::Event;
+use rand::distr::weighted::WeightedIndex;
+use rand::distr::Distribution;
+use rand::seq::IndexedRandom;
+use rand::Rng;
+use term_table::row::Row;
+use term_table::table_cell::{Alignment, TableCell};
+use term_table::Table;
 use tokio::time::Duration;
+use tracing::{info, warn};
+use wasmcloud_control_interface::{HostInventory, Link, ProviderAuctionAck};
 
-use crate::lib::cli::{input_vec_to_hashmap, CliConnectionOpts, CommandOutput};
-use crate::lib::common::{boxed_err_to_anyhow, find_host_id};
+use crate::lib::backoff::{Backoff, BackoffStrategy, RetryOpts};
+use crate::lib::cli::host::UNSCHEDULABLE_LABEL;
+use crate::lib::cli::link::{delete_link, get_links, put_link};
+use crate::lib::cli::progress::{Progress, ProgressStep};
+use crate::lib::cli::receipt::{attach_receipt, ReceiptOpts};
+use crate::lib::cli::{
+    configure_table_style, input_vec_to_hashmap, CliConnectionOpts, CommandOutput,
+};
+use crate::lib::common::{
+    boxed_err_to_anyhow, find_host_id_with, get_all_inventories, list_hosts, pick_host,
+    HostMatchOpts, HostQuery, ResolvedHost,
+};
+use crate::lib::compat::{ensure_host_features, HostFeature};
 use crate::lib::component::{scale_component, ComponentScaledInfo, ScaleComponentArgs};
 use crate::lib::config::{
-    WashConnectionOptions, DEFAULT_NATS_TIMEOUT_MS, DEFAULT_START_COMPONENT_TIMEOUT_MS,
-    DEFAULT_START_PROVIDER_TIMEOUT_MS,
+    close_ctl_client, WashConnectionOptions, DEFAULT_NATS_TIMEOUT_MS,
+    DEFAULT_START_COMPONENT_TIMEOUT_MS, DEFAULT_START_PROVIDER_TIMEOUT_MS,
 };
 use crate::lib::context::default_timeout_ms;
-use crate::lib::wait::{wait_for_provider_start_event, FindEventOutcome, ProviderStartedInfo};
+use crate::lib::failure::{Failure, FailureKind};
+use crate::lib::id::ServerId;
+use crate::lib::links::{LinkKey, LinkPutOutcome, Links};
+use crate::lib::provider::{
+    check_local_provider_ref, check_world_compatible, estimate_start_timeout,
+    fetch_provider_archive, load_host_world, load_provider_config_file, provider_config_schema,
+    provider_worlds, put_provider_config, start_with_fallback_refs, validate_provider_config,
+    verify_provider_signature, ConfigUpload, ProviderStartError, SignatureVerification,
+    SizedStartTimeout,
+};
+use crate::lib::rollout::{parse_canary_percent, split_canary, Rollout};
+use crate::lib::wait::{
+    wait_for_provider_health, wait_for_provider_links, wait_for_provider_start_or_inventory,
+    watch_events, watch_for_provider_start_event, EventFilter, FindEventOutcome, ProviderRefMatch,
+    ProviderStartTarget, ProviderStartedInfo,
+};
 
 use super::validate_component_id;
 
 #[derive(Debug, Clone, Parser)]
 pub enum StartCommand {
-    /// Launch a component in a host
-    #[clap(name = "component")]
-    Component(StartComponentCommand),
+    /// Launch a component in a host. Components were previously called actors, so this is also
+    /// available as `wash start actor`
+    #[clap(name = "component", alias = "actor")]
+    Component(Box<StartComponentCommand>),
 
     /// Launch a provider in a host
     #[clap(name = "provider")]
-    Provider(StartProviderCommand),
+    Provider(Box<StartProviderCommand>),
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -39,6 +78,9 @@ pub struct StartComponentCommand {
     #[clap(long = "host-id")]
     pub host_id: Option<String>,
 
+    #[clap(flatten)]
+    pub host_match: HostMatchOpts,
+
     /// Component reference, e.g. the absolute file path or OCI URL.
     #[clap(name = "component-ref")]
     pub component_ref: String,
@@ -74,6 +116,422 @@ pub struct StartComponentCommand {
     /// List of named configuration to apply to the component, may be empty
     #[clap(long = "config")]
     pub config: Vec<String>,
+
+    /// How to choose between the hosts that respond to the auction. Ignored if host-id is supplied
+    #[clap(
+        long = "placement",
+        alias = "selection-strategy",
+        value_enum,
+        default_value_t = Placement::First
+    )]
+    pub placement: Placement,
+
+    /// Check that the selected host is still in the lattice right before sending the start request,
+    /// and fail (so the start can be retried on another host) if it has left since the auction
+    #[clap(long = "strict-host")]
+    pub strict_host: bool,
+
+    /// Number of times to retry the auction if no suitable hosts respond. Ignored if host-id is
+    /// supplied
+    #[clap(long = "auction-retries", default_value_t = 0)]
+    pub auction_retries: u32,
+
+    /// How long to wait between auction retries
+    #[clap(long = "backoff", value_enum, default_value_t = BackoffStrategy::Exponential)]
+    pub backoff: BackoffStrategy,
+
+    /// Annotations for the host's handling of the component image, in the form `key=value`, e.g. to
+    /// pin the image in a host's image cache. Can be passed multiple times
+    #[clap(long = "image-annotation", value_parser = parse_image_annotation)]
+    pub image_annotations: Vec<(String, String)>,
+
+    /// Hold the auction and run the usual checks, then report which host would receive the start
+    /// request without sending it
+    #[clap(long = "dry-run")]
+    pub dry_run: bool,
+
+    /// Where to report the steps of the start as they are reached
+    #[clap(skip)]
+    pub progress: Progress,
+}
+
+/// Parse an `--image-annotation` of the form `key=value`. Keys follow the OCI annotation
+/// convention of alphanumeric segments separated by `.`, `-`, `_` or `/`
+pub fn parse_image_annotation(arg: &str) -> Result<(String, String)> {
+    let (key, value) = arg
+        .split_once('=')
+        .with_context(|| format!("image annotation [{arg}] must be in the form key=value"))?;
+    let valid_key = key.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && key.ends_with(|c: char| c.is_ascii_alphanumeric())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '/'));
+    if !valid_key {
+        bail!(
+            "image annotation key [{key}] must start and end with an alphanumeric character and only contain alphanumerics, '.', '-', '_' or '/'"
+        );
+    }
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// How to choose between the hosts that respond to an auction
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Placement {
+    /// Use the first host to respond
+    #[default]
+    First,
+    /// Pick one of the responding hosts uniformly at random
+    Random,
+    /// Pick one of the responding hosts at random, biased by its `weight` label. Hosts without a
+    /// (valid) weight label have a weight of 1
+    Weighted,
+    /// Pick the responding host running the fewest components and providers
+    LeastLoaded,
+    /// Pick the responding host running the fewest copies of the component or provider being
+    /// started, so copies spread across hosts. Ties go to the least loaded host
+    Spread,
+}
+
+impl Placement {
+    /// Whether the strategy needs the inventories of the candidate hosts, see [`select_by_load`]
+    #[must_use]
+    pub const fn needs_inventory(&self) -> bool {
+        matches!(self, Self::LeastLoaded | Self::Spread)
+    }
+}
+
+/// Host label read by `--placement weighted`
+pub const PLACEMENT_WEIGHT_LABEL: &str = "weight";
+
+/// Select a host from the auction responders according to the placement strategy. `labels` only
+/// needs to contain the labels of the candidate hosts for weighted placement. Strategies that need
+/// host inventories are handled by [`select_by_load`]; here they fall back to the first host
+pub fn select_auction_host(
+    candidates: &[String],
+    labels: &HashMap<String, BTreeMap<String, String>>,
+    placement: Placement,
+    rng: &mut impl Rng,
+) -> Option<String> {
+    match placement {
+        Placement::First | Placement::LeastLoaded | Placement::Spread => {
+            candidates.first().cloned()
+        }
+        Placement::Random => candidates.choose(rng).cloned(),
+        Placement::Weighted => {
+            let weights = candidates.iter().map(|host_id| {
+                labels
+                    .get(host_id)
+                    .and_then(|l| l.get(PLACEMENT_WEIGHT_LABEL))
+                    .and_then(|w| w.parse::<u32>().ok())
+                    .unwrap_or(1)
+            });
+            // Only fails if every host has a weight of 0 (or there are no hosts)
+            match WeightedIndex::new(weights) {
+                Ok(dist) => candidates.get(dist.sample(rng)).cloned(),
+                Err(_) => candidates.first().cloned(),
+            }
+        }
+    }
+}
+
+/// Select a host from the auction responders by what they already run, for least-loaded and
+/// spread placement. `workload_id` is the ID of the component or provider being started. Ties go
+/// to the earliest responder, and responders without an inventory are only picked last
+#[must_use]
+pub fn select_by_load(
+    candidates: &[String],
+    inventories: &[HostInventory],
+    placement: Placement,
+    workload_id: &str,
+) -> Option<String> {
+    candidates
+        .iter()
+        .min_by_key(|host_id| {
+            let Some(inventory) = inventories.iter().find(|inv| inv.host_id() == *host_id) else {
+                return (usize::MAX, usize::MAX);
+            };
+            let components = inventory.components().iter().map(|c| c.id());
+            let providers = inventory.providers().iter().map(|p| p.id());
+            let load = inventory.components().len() + inventory.providers().len();
+            let copies = match placement {
+                Placement::Spread => components
+                    .chain(providers)
+                    .filter(|id| *id == workload_id)
+                    .count(),
+                _ => 0,
+            };
+            (copies, load)
+        })
+        .cloned()
+}
+
+/// Drop the hosts labeled as unschedulable, e.g. by `wash host drain --cordon`, from the hosts
+/// that responded to an auction
+#[must_use]
+pub fn schedulable_hosts(
+    candidates: &[String],
+    labels: &HashMap<String, BTreeMap<String, String>>,
+) -> Vec<String> {
+    candidates
+        .iter()
+        .filter(|host_id| {
+            labels
+                .get(*host_id)
+                .and_then(|l| l.get(UNSCHEDULABLE_LABEL))
+                .is_none_or(|v| v != "true")
+        })
+        .cloned()
+        .collect()
+}
+
+/// The hosts running a component, matched by component ID or image reference, sorted by host ID.
+/// Fails if the component isn't running on any host
+pub fn colocation_hosts(component: &str, inventories: &[HostInventory]) -> Result<Vec<String>> {
+    let mut hosts = inventories
+        .iter()
+        .filter(|inv| {
+            inv.components()
+                .iter()
+                .any(|c| c.id() == component || c.image_ref() == component)
+        })
+        .map(|inv| inv.host_id().to_string())
+        .collect::<Vec<_>>();
+    if hosts.is_empty() {
+        bail!(
+            "Component [{component}] is not running on any host, there is nothing to colocate with"
+        );
+    }
+    hosts.sort();
+    hosts.dedup();
+    Ok(hosts)
+}
+
+/// How often to check whether a provider's links are live with `--await-provider-links`
+const PROVIDER_LINKS_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Describe the links sourced from a provider whose targets aren't among the running components
+/// and providers in `inventories`
+#[must_use]
+pub fn pending_provider_links(
+    provider_id: &str,
+    links: &[Link],
+    inventories: &[HostInventory],
+) -> Vec<String> {
+    let live = inventories
+        .iter()
+        .flat_map(|inv| {
+            let components = inv.components().iter().map(|c| c.id());
+            let providers = inv.providers().iter().map(|p| p.id());
+            components.chain(providers)
+        })
+        .collect::<std::collections::HashSet<_>>();
+    links
+        .iter()
+        .filter(|link| link.source_id() == provider_id && !live.contains(link.target()))
+        .map(|link| {
+            format!(
+                "{} link to [{}] on {}:{}",
+                link.name(),
+                link.target(),
+                link.wit_namespace(),
+                link.wit_package()
+            )
+        })
+        .collect()
+}
+
+/// Delay before the first auction retry
+const AUCTION_RETRY_BASE: Duration = Duration::from_millis(500);
+/// Longest delay between auction retries
+const AUCTION_RETRY_MAX: Duration = Duration::from_secs(10);
+
+fn auction_backoff(strategy: BackoffStrategy) -> Backoff {
+    Backoff::new(strategy, AUCTION_RETRY_BASE, AUCTION_RETRY_MAX)
+}
+
+/// The hosts that responded to an auction, in the order they responded, along with the labels
+/// each of them reported. Labels come with the auction responses, so placing on one of the hosts
+/// doesn't need another request to the lattice
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuctionResponders {
+    pub host_ids: Vec<String>,
+    pub labels: HashMap<String, BTreeMap<String, String>>,
+}
+
+impl<'a> FromIterator<(&'a str, &'a BTreeMap<String, String>)> for AuctionResponders {
+    fn from_iter<T: IntoIterator<Item = (&'a str, &'a BTreeMap<String, String>)>>(iter: T) -> Self {
+        let mut responders = Self::default();
+        for (host_id, labels) in iter {
+            responders.host_ids.push(host_id.to_string());
+            responders
+                .labels
+                .insert(host_id.to_string(), labels.clone());
+        }
+        responders
+    }
+}
+
+/// Auction a component, returning the hosts that responded. Fails if no hosts did
+pub(crate) async fn auction_component(
+    client: &wasmcloud_control_interface::Client,
+    component_ref: &str,
+    component_id: &str,
+    constraints: &BTreeMap<String, String>,
+    progress: &Progress,
+) -> Result<AuctionResponders> {
+    progress.report(
+        ProgressStep::Auctioning,
+        format!("Auctioning component {component_ref}"),
+    );
+    let suitable_hosts = client
+        .perform_component_auction(component_ref, component_id, constraints.clone())
+        .await
+        .map_err(boxed_err_to_anyhow)
+        .with_context(|| {
+            format!("Failed to auction component {component_ref} to hosts in lattice")
+        })?;
+    if suitable_hosts.is_empty() {
+        return Err(Failure::new(
+            FailureKind::AuctionEmpty,
+            format!("No suitable hosts found for component {component_ref}"),
+        )
+        .into());
+    }
+    let acks = suitable_hosts
+        .into_iter()
+        .filter_map(wasmcloud_control_interface::CtlResponse::into_data)
+        .collect::<Vec<_>>();
+    Ok(acks
+        .iter()
+        .map(|ack| (ack.host_id(), ack.labels()))
+        .collect())
+}
+
+/// Auction a provider, returning the responses of the hosts that responded. Fails if no hosts did
+async fn auction_provider(
+    client: &wasmcloud_control_interface::Client,
+    provider_ref: &str,
+    link_name: &str,
+    constraints: &BTreeMap<String, String>,
+    progress: &Progress,
+) -> Result<Vec<ProviderAuctionAck>> {
+    progress.report(
+        ProgressStep::Auctioning,
+        format!("Auctioning provider {provider_ref}"),
+    );
+    let suitable_hosts = client
+        .perform_provider_auction(provider_ref, link_name, constraints.clone())
+        .await
+        .map_err(boxed_err_to_anyhow)
+        .with_context(|| {
+            format!(
+                "Failed to auction provider {provider_ref} with link name {link_name} to hosts in lattice"
+            )
+        })?;
+    if suitable_hosts.is_empty() {
+        return Err(Failure::new(
+            FailureKind::AuctionEmpty,
+            format!("No suitable hosts found for provider {provider_ref}"),
+        )
+        .into());
+    }
+    Ok(suitable_hosts
+        .into_iter()
+        .filter_map(wasmcloud_control_interface::CtlResponse::into_data)
+        .collect())
+}
+
+/// How many hosts took part in an auction. A sudden drop in responders points at a connectivity or
+/// constraint problem, and few eligible responders at hosts being filtered out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuctionFanout {
+    /// Hosts that responded to the auction, i.e. matched its constraints
+    pub responders: usize,
+    /// Responders left after filtering out unschedulable hosts
+    pub eligible: usize,
+}
+
+/// Filter the hosts that responded to an auction down to the ones that can be placed on, along
+/// with how many hosts were left at each step
+#[must_use]
+pub fn filter_auction_candidates(
+    candidates: &[String],
+    labels: &HashMap<String, BTreeMap<String, String>>,
+) -> (Vec<String>, AuctionFanout) {
+    let eligible = schedulable_hosts(candidates, labels);
+    let fanout = AuctionFanout {
+        responders: candidates.len(),
+        eligible: eligible.len(),
+    };
+    (eligible, fanout)
+}
+
+/// Choose which of the hosts that responded to an auction to use for the component or provider
+/// with the given ID. With `interactive`, the user picks between several eligible hosts
+pub(crate) async fn choose_auction_host(
+    client: &wasmcloud_control_interface::Client,
+    responders: &AuctionResponders,
+    placement: Placement,
+    workload_id: &str,
+    interactive: bool,
+) -> Result<(ServerId, AuctionFanout)> {
+    let labels = &responders.labels;
+    let (candidates, fanout) = filter_auction_candidates(&responders.host_ids, labels);
+    info!(
+        responders = fanout.responders,
+        eligible = fanout.eligible,
+        "auction fan-out"
+    );
+    let host_id = if interactive && candidates.len() > 1 {
+        let hosts = client
+            .get_hosts()
+            .await
+            .map_err(boxed_err_to_anyhow)
+            .context("unable to fetch hosts")?
+            .into_iter()
+            .filter_map(wasmcloud_control_interface::CtlResponse::into_data)
+            .collect::<Vec<_>>();
+        Some(pick_host(&candidates, &hosts)?)
+    } else if placement.needs_inventory() {
+        let inventories = get_all_inventories(client).await?;
+        select_by_load(&candidates, &inventories, placement, workload_id)
+    } else {
+        select_auction_host(&candidates, labels, placement, &mut rand::rng())
+    }
+    .context("No suitable hosts found, or all of them are marked unschedulable")?;
+    let host_id = host_id
+        .parse()
+        .with_context(|| format!("Failed to parse host id: {host_id}"))?;
+    Ok((host_id, fanout))
+}
+
+/// Download a provider archive before it is started, e.g. to validate or verify it
+async fn download_provider_archive(provider_ref: &str, progress: &Progress) -> Result<Vec<u8>> {
+    progress.report(
+        ProgressStep::DownloadingImage,
+        format!("Downloading provider {provider_ref}"),
+    );
+    fetch_provider_archive(provider_ref).await
+}
+
+/// Check that the host picked for a start is among the hosts currently in the lattice
+pub fn check_host_present(host_id: &str, hosts: &[ResolvedHost]) -> Result<()> {
+    if hosts.iter().any(|h| h.id == host_id) {
+        Ok(())
+    } else {
+        bail!(
+            "Host [{host_id}] left the lattice after it was selected, no start request was sent. Retry the start to select another host"
+        )
+    }
+}
+
+/// Fail if the selected host has left the lattice, e.g. between an auction and the start request
+async fn ensure_host_still_present(
+    client: &wasmcloud_control_interface::Client,
+    host_id: &str,
+) -> Result<()> {
+    let hosts = list_hosts(client, &HostQuery::default()).await?;
+    check_host_present(host_id, &hosts)
 }
 
 /// Utility function for resolving component and provider references
@@ -125,37 +583,61 @@ pub async fn handle_start_component(cmd: StartComponentCommand) -> Result<Comman
     let component_ref = resolve_ref(&cmd.component_ref).await?;
 
     let host = if let Some(host) = cmd.host_id {
-        find_host_id(&host, &client).await?.0
+        find_host_id_with(&host, &client, &cmd.host_match).await?.0
     } else {
-        let suitable_hosts = client
-            .perform_component_auction(
-                &component_ref,
-                &cmd.component_id,
-                BTreeMap::from_iter(input_vec_to_hashmap(cmd.constraints.unwrap_or_default())?),
-            )
-            .await
-            .map_err(boxed_err_to_anyhow)
-            .with_context(|| {
-                format!(
-                    "Failed to auction component {} to hosts in lattice",
-                    &component_ref
+        let constraints =
+            BTreeMap::from_iter(input_vec_to_hashmap(cmd.constraints.unwrap_or_default())?);
+        let responders = auction_backoff(cmd.backoff)
+            .retry(cmd.auction_retries, || {
+                auction_component(
+                    &client,
+                    &component_ref,
+                    &cmd.component_id,
+                    &constraints,
+                    &cmd.progress,
                 )
-            })?;
-        if suitable_hosts.is_empty() {
-            bail!("No suitable hosts found for component {}", component_ref);
-        } else {
-            let acks = suitable_hosts
-                .into_iter()
-                .filter_map(wasmcloud_control_interface::CtlResponse::into_data)
-                .collect::<Vec<_>>();
-            let ack = acks.first().context("No suitable hosts found")?;
-            ack.host_id()
-                .parse()
-                .with_context(|| format!("Failed to parse host id: {}", ack.host_id()))?
-        }
+            })
+            .await?;
+        choose_auction_host(
+            &client,
+            &responders,
+            cmd.placement,
+            &cmd.component_id,
+            cmd.host_match.interactive,
+        )
+        .await?
+        .0
     };
 
+    if cmd.strict_host {
+        ensure_host_still_present(&client, &host).await?;
+    }
+
+    if cmd.dry_run {
+        return Ok(CommandOutput::dry_run(
+            host.to_string(),
+            format!(
+                "start component [{}] (ref: [{component_ref}]) with {} max instances",
+                cmd.component_id, cmd.max_instances
+            ),
+            HashMap::from([
+                ("component_ref".into(), component_ref.into()),
+                ("component_id".into(), cmd.component_id.into()),
+                ("max_instances".into(), cmd.max_instances.into()),
+                ("config".into(), cmd.config.into()),
+            ]),
+        ));
+    }
+
     // Start the component
+    cmd.progress.report(
+        if cmd.skip_wait {
+            ProgressStep::WaitingForAck
+        } else {
+            ProgressStep::WaitingForStartEvent
+        },
+        format!("Starting component {} on host {host}", cmd.component_id),
+    );
     let ComponentScaledInfo {
         host_id,
         component_ref,
@@ -168,7 +650,8 @@ pub async fn handle_start_component(cmd: StartComponentCommand) -> Result<Comman
         max_instances: cmd.max_instances,
         skip_wait: cmd.skip_wait,
         timeout_ms: Some(timeout_ms),
-        annotations: None,
+        annotations: (!cmd.image_annotations.is_empty())
+            .then(|| cmd.image_annotations.into_iter().collect()),
         config: cmd.config,
     })
     .await?;
@@ -202,158 +685,3016 @@ pub struct StartProviderCommand {
     #[clap(long = "host-id")]
     pub host_id: Option<String>,
 
+    #[clap(flatten)]
+    pub host_match: HostMatchOpts,
+
     /// Provider reference, e.g. the OCI URL for the provider
     #[clap(name = "provider-ref")]
     pub provider_ref: String,
 
+    /// Reference to try if the provider can't be pulled from `provider-ref` (or the previous
+    /// fallback), e.g. a mirror registry. Only pull failures fall back. May be passed multiple times
+    #[clap(long = "fallback-ref", name = "fallback_refs")]
+    pub fallback_refs: Vec<String>,
+
     /// Unique provider ID to use for the provider
     #[clap(name = "provider-id", value_parser = validate_component_id)]
     pub provider_id: String,
 
-    /// Link name of provider
-    #[clap(short = 'l', long = "link-name", default_value = "default")]
+    /// Start another provider in the same command, in the form `<provider-ref>=<provider-id>`.
+    /// All providers are started concurrently with the other options of this command, and their
+    /// results are reported together. May be passed multiple times
+    #[clap(long = "and-provider", name = "batch", conflicts_with = "canary")]
+    pub batch: Vec<BatchProvider>,
+
+    /// Path to a YAML or JSON list of further providers to start, as an alternative to
+    /// `--and-provider`, e.g. `[{provider_ref: ghcr.io/wasmcloud/keyvalue-redis:0.28.2,
+    /// provider_id: kv}]`
+    #[clap(long = "batch-file", conflicts_with = "canary")]
+    pub batch_file: Option<PathBuf>,
+
+    /// Link name of provider. May be passed multiple times for a provider that serves several
+    /// link names, in which case links given with `--link` are established under each of them.
+    /// The first link name is used for the auction
+    #[clap(
+        short = 'l',
+        long = "link-name",
+        name = "link_names",
+        default_value = "default"
+    )]
+    pub link_names: Vec<String>,
+
+    /// The single link name of the provider, from before `--link-name` could be repeated. If set,
+    /// it is used as the primary link name in place of the first of `link_names`
+    #[deprecated(note = "use `link_names`, or `link_name()` for the primary link name")]
+    #[clap(skip)]
     pub link_name: String,
 
+    /// Link a component to the provider as part of starting it, in the form
+    /// `<source-id>=<namespace>:<package>/<interface>[,<interface>...]` (e.g.
+    /// `echo=wasi:keyvalue/store,atomics`). A link is put under each `--link-name` and removed
+    /// again if the provider fails to start. May be passed multiple times
+    #[clap(long = "link", name = "links")]
+    pub links: Vec<InlineLink>,
+
+    /// Path to a YAML or JSON list of links to the provider, as an alternative to `--link` that
+    /// spells out each field, e.g. `[{source_id: echo, wit_namespace: wasi, wit_package: keyvalue,
+    /// interfaces: [store, atomics]}]`. The links are combined with any given with `--link`
+    #[clap(long = "link-file")]
+    pub link_file: Option<PathBuf>,
+
     /// Constraints for provider auction in the form of "label=value". If host-id is supplied, this list is ignored
     #[clap(short = 'c', long = "constraint", name = "constraints")]
     pub constraints: Option<Vec<String>>,
 
+    /// Start the provider on only this percentage (e.g. `10%`) of the hosts matching the
+    /// constraints and return a rollout handle. `wash rollout promote <handle>` starts it on the
+    /// rest of the hosts with the same flags, e.g. link names, config and wait options
+    #[clap(long = "canary", value_parser = parse_canary_percent, conflicts_with = "host_id")]
+    pub canary: Option<u8>,
+
+    /// Start the provider on a host already running this component, given by ID or reference, so
+    /// calls between them stay local. If the component runs on several hosts, one is chosen with
+    /// `--placement`, skipping unschedulable hosts. No auction is held
+    #[clap(
+        long = "colocate-with",
+        conflicts_with_all = ["host_id", "canary"]
+    )]
+    pub colocate_with: Option<String>,
+
+    /// Start the provider on every schedulable host matching the constraints, concurrently,
+    /// instead of on a single host. No auction is held. The outcome on each host is reported, and
+    /// a host failing to start the provider doesn't stop the others but fails the command
+    #[clap(
+        long = "every-host",
+        conflicts_with_all = ["host_id", "canary", "colocate_with", "batch", "batch_file", "interactive", "links", "link_file", "fallback_refs"]
+    )]
+    pub every_host: bool,
+
     /// Timeout to await an auction response, defaults to 2000 milliseconds
     #[clap(long = "auction-timeout-ms", default_value_t = default_timeout_ms())]
     pub auction_timeout_ms: u64,
 
+    /// How to choose between the hosts that respond to the auction. Ignored if host-id is supplied
+    #[clap(
+        long = "placement",
+        alias = "selection-strategy",
+        value_enum,
+        default_value_t = Placement::First
+    )]
+    pub placement: Placement,
+
+    /// Check that the selected host is still in the lattice right before sending the start request,
+    /// and fail (so the start can be retried on another host) if it has left since the auction
+    #[clap(long = "strict-host")]
+    pub strict_host: bool,
+
+    /// Number of times to retry the auction if no suitable hosts respond. Ignored if host-id is
+    /// supplied
+    #[clap(long = "auction-retries", default_value_t = 0)]
+    pub auction_retries: u32,
+
+    /// How long to wait between auction retries
+    #[clap(long = "backoff", value_enum, default_value_t = BackoffStrategy::Exponential)]
+    pub backoff: BackoffStrategy,
+
     /// List of named configuration to apply to the provider, may be empty
     #[clap(long = "config")]
     pub config: Vec<String>,
 
     /// By default, the command will wait until the provider has been started.
     /// If this flag is passed, the command will return immediately after acknowledgement from the host, without waiting for the provider to start.
-    /// If this flag is omitted, the timeout will be adjusted to 30 seconds to account for provider download times
+    /// If this flag is omitted and no `--timeout-ms` is given, the timeout will be sized to the provider image (from 15
+    /// seconds up to 5 minutes) to account for provider download times, or 30 seconds if its size can't be found
     #[clap(long = "skip-wait")]
     pub skip_wait: bool,
+
+    /// How long to wait for the host to acknowledge the start request, in milliseconds, separate
+    /// from the wait for the provider to start. A host that never acknowledges fails after this
+    /// timeout. Defaults to the connection timeout (`--timeout-ms`)
+    #[clap(long = "ack-timeout-ms")]
+    pub ack_timeout_ms: Option<u64>,
+
+    /// While waiting for the provider started event, also poll the host inventory every this many
+    /// milliseconds. If the provider shows up in the inventory it is treated as started, even if
+    /// the event itself was lost in transit
+    #[clap(long = "verify-poll-ms", conflicts_with = "skip_wait")]
+    pub verify_poll_ms: Option<u64>,
+
+    /// Only accept a provider started event carrying exactly the submitted provider reference. By
+    /// default an event for the provider ID, or for the digest the host resolved a tag to, matches
+    /// too
+    #[clap(long = "exact-ref-match", conflicts_with = "skip_wait")]
+    pub exact_ref_match: bool,
+
+    /// Verify the signature embedded in the provider archive before starting it. Unsigned
+    /// archives, or archives signed by an issuer not listed in `--trusted-issuer`, are refused
+    #[clap(long = "verify-signature", requires = "trusted_issuers")]
+    pub verify_signature: bool,
+
+    /// Public key of an account trusted to sign provider archives. May be passed multiple times
+    #[clap(long = "trusted-issuer", name = "trusted_issuers")]
+    pub trusted_issuers: Vec<String>,
+
+    /// Check that the WIT world embedded in the provider archive is compatible with the host's
+    /// world (`--host-wit`) before starting it. Everything the provider imports must be exported by
+    /// the host world, and everything it exports must be imported by the host world
+    #[clap(long = "validate-world", requires = "host_wit")]
+    pub validate_world: bool,
+
+    /// Path to a WIT file or directory declaring the single world supported by the host, used by
+    /// `--validate-world`
+    #[clap(long = "host-wit", name = "host_wit")]
+    pub host_wit: Option<PathBuf>,
+
+    /// Log the lattice events for the provider, as JSON at the `info` level, while waiting for it
+    /// to start. Set `RUST_LOG=info` to see them on stderr
+    #[clap(long = "watch", conflicts_with = "skip_wait")]
+    pub watch: bool,
+
+    /// Subscribe to lattice events as part of this NATS queue group while waiting for the provider
+    /// to start. Each event is then delivered to only one subscriber in the group, so concurrent
+    /// wash invocations sharing a group split the events between them instead of each seeing all
+    /// of them. Only use this when every member of the group can handle any provider's events
+    #[clap(long = "events-queue-group")]
+    pub events_queue_group: Option<String>,
+
+    /// How long to keep streaming events after the provider has started or failed, in
+    /// milliseconds. This is independent of the start timeout and only applies with `--watch`
+    #[clap(long = "watch-timeout-ms", default_value_t = 0, requires = "watch")]
+    pub watch_timeout_ms: u64,
+
+    /// After the provider has started, wait until it passes a health check before returning
+    #[clap(long = "wait-healthy", conflicts_with = "skip_wait")]
+    pub wait_healthy: bool,
+
+    /// Grace period after the provider starts during which health checks are not evaluated, in
+    /// milliseconds. Useful for providers that take a while to initialize. Only applies with
+    /// `--wait-healthy`
+    #[clap(long = "warmup-ms", default_value_t = 0, requires = "wait_healthy")]
+    pub warmup_ms: u64,
+
+    /// After the provider has started, wait until the links it is the source of are live, i.e.
+    /// their targets are running in the lattice, before returning. Useful for providers that only
+    /// finish initializing once their own links are established. A provider without any links is
+    /// reported as such rather than waited on
+    #[clap(long = "await-provider-links", conflicts_with = "skip_wait")]
+    pub await_provider_links: bool,
+
+    /// How long to wait for `--await-provider-links`, in milliseconds. Defaults to the start timeout
+    #[clap(long = "await-links-timeout-ms", requires = "await_provider_links")]
+    pub await_links_timeout_ms: Option<u64>,
+
+    /// Path to a JSON, YAML or TOML file of provider config values, by its extension (`.json`,
+    /// `.yaml`/`.yml` or `.toml`, anything else is read as YAML). The values are checked against the
+    /// config schema embedded in the provider archive, if it has one, then stored as named config
+    /// (named after a hash of their contents) and applied to the provider alongside `--config`
+    #[clap(long = "config-file")]
+    pub config_file: Option<PathBuf>,
+
+    /// Reuse config from `--config-file` that was already stored by a previous start instead of
+    /// sending it again. Falls back to sending the full config when it isn't stored yet
+    #[clap(long = "config-cache", requires = "config_file")]
+    pub config_cache: bool,
+
+    /// Hold the auction and run the usual checks, then report which host would receive the start
+    /// request without sending it. No config or links are stored. Values in `--config-file` are
+    /// checked against the config schema embedded in the provider archive, if it has one
+    #[clap(long = "dry-run", conflicts_with = "canary")]
+    pub dry_run: bool,
+
+    /// Have the host pull the provider image on its own before launching it, so that a failed or
+    /// slow download is reported as a failure of the pull phase rather than as a generic start
+    /// timeout. Hosts that don't support it pull without a limit and report failures as before,
+    /// which is warned about (see `--require-features`)
+    #[clap(long = "pre-pull")]
+    pub pre_pull: bool,
+
+    /// How long the host may spend pulling the provider image with `--pre-pull`, in milliseconds.
+    /// This is on top of the start timeout, which then only covers the launch
+    #[clap(
+        long = "pull-timeout-ms",
+        default_value_t = DEFAULT_START_PROVIDER_TIMEOUT_MS,
+        requires = "pre_pull"
+    )]
+    pub pull_timeout_ms: u64,
+
+    /// Fail instead of warning when the selected host is too old to act on fields sent with the
+    /// start request, such as the `--pull-timeout-ms` of `--pre-pull`
+    #[clap(long = "require-features")]
+    pub require_features: bool,
+
+    /// Maximum number of invocations the provider should handle concurrently. This is passed to
+    /// the host as a start annotation; hosts or providers that don't support it will ignore it
+    #[clap(long = "max-concurrent-invocations")]
+    pub max_concurrent_invocations: Option<u32>,
+
+    /// Annotations for the host's handling of the provider image, in the form `key=value`, e.g. to
+    /// pin the image in a host's image cache. Can be passed multiple times
+    #[clap(long = "image-annotation", value_parser = parse_image_annotation)]
+    pub image_annotations: Vec<(String, String)>,
+
+    #[clap(flatten)]
+    pub retry: RetryOpts,
+
+    #[clap(flatten)]
+    pub receipt: ReceiptOpts,
+
+    /// Picks the host from the auction responses instead of `placement`. Only settable by library
+    /// consumers, see [`handle_start_provider_with_selector`]
+    #[clap(skip)]
+    pub host_selector: Option<HostSelector>,
+
+    /// Where to report the steps of the start as they are reached
+    #[clap(skip)]
+    pub progress: Progress,
 }
 
-pub async fn handle_start_provider(cmd: StartProviderCommand) -> Result<CommandOutput> {
-    // If timeout isn't supplied, override with a longer timeout for starting provider
-    let timeout_ms = if cmd.opts.timeout_ms == DEFAULT_NATS_TIMEOUT_MS {
-        DEFAULT_START_PROVIDER_TIMEOUT_MS
-    } else {
-        cmd.opts.timeout_ms
-    };
-    let client = <CliConnectionOpts as TryInto<WashConnectionOptions>>::try_into(cmd.opts)?
-        .into_ctl_client(Some(cmd.auction_timeout_ms))
-        .await?;
+type SelectHost = dyn Fn(&[ProviderAuctionAck]) -> Option<String> + Send + Sync;
 
-    // Attempt to parse the provider_ref from strings that may look like paths or be OCI references
-    let provider_ref = resolve_ref(&cmd.provider_ref).await?;
+/// A callback that picks the host to start a provider on from the hosts that responded to its
+/// auction, returning `None` if none of them are acceptable
+#[derive(Clone)]
+pub struct HostSelector(Arc<SelectHost>);
 
-    let host = if let Some(host) = cmd.host_id {
-        find_host_id(&host, &client).await?.0
-    } else {
-        let suitable_hosts = client
-            .perform_provider_auction(
-                &provider_ref,
-                &cmd.link_name,
-                BTreeMap::from_iter(input_vec_to_hashmap(cmd.constraints.unwrap_or_default())?),
-            )
-            .await
-            .map_err(boxed_err_to_anyhow)
-            .with_context(|| {
-                format!(
-                    "Failed to auction provider {} with link name {} to hosts in lattice",
-                    &provider_ref, &cmd.link_name
-                )
-            })?;
-        if suitable_hosts.is_empty() {
-            bail!("No suitable hosts found for provider {}", provider_ref);
-        } else {
-            let acks = suitable_hosts
-                .into_iter()
-                .filter_map(wasmcloud_control_interface::CtlResponse::into_data)
-                .collect::<Vec<_>>();
-            let ack = acks.first().context("No suitable hosts found")?;
-            ack.host_id()
-                .parse()
-                .with_context(|| format!("Failed to parse host id: {}", ack.host_id()))?
+impl HostSelector {
+    pub fn new(
+        select: impl Fn(&[ProviderAuctionAck]) -> Option<String> + Send + Sync + 'static,
+    ) -> Self {
+        Self(Arc::new(select))
+    }
+
+    /// Pick a host from the auction responses. The picked host must be one of the responders
+    pub fn select(&self, responses: &[ProviderAuctionAck]) -> Result<String> {
+        let host_id = (self.0)(responses).context(
+            "The host selector did not pick any of the hosts that responded to the auction",
+        )?;
+        if !responses.iter().any(|ack| ack.host_id() == host_id) {
+            bail!(
+                "The host selector picked host [{host_id}], which did not respond to the auction"
+            );
         }
-    };
+        Ok(host_id)
+    }
+}
 
-    let mut receiver = client
-        .events_receiver(vec![
-            "provider_started".to_string(),
-            "provider_start_failed".to_string(),
-        ])
-        .await
-        .map_err(boxed_err_to_anyhow)
-        .context("Failed to get lattice event channel")?;
+impl std::fmt::Debug for HostSelector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("HostSelector").finish_non_exhaustive()
+    }
+}
 
-    let ack = client
-        .start_provider(&host, &provider_ref, &cmd.provider_id, None, cmd.config)
-        .await
-        .map_err(boxed_err_to_anyhow)
-        .with_context(|| {
-            format!(
-                "Failed to start provider {} on host {:?}",
-                &cmd.provider_id, &host
-            )
-        })?;
+impl StartProviderCommand {
+    /// Move the deprecated `link_name` field, which library consumers may still set, into
+    /// `link_names` as the primary link name
+    #[allow(deprecated)]
+    fn take_deprecated_link_name(&mut self) {
+        let link_name = std::mem::take(&mut self.link_name);
+        if link_name.is_empty() || self.link_names.contains(&link_name) {
+            return;
+        }
+        match self.link_names.first_mut() {
+            Some(first) => *first = link_name,
+            None => self.link_names.push(link_name),
+        }
+    }
 
-    if !ack.succeeded() {
-        bail!("Start provider ack not accepted: {}", ack.message());
+    /// The primary link name of the provider, i.e. the first `--link-name`
+    #[must_use]
+    pub fn link_name(&self) -> &str {
+        self.link_names.first().map_or("default", String::as_str)
     }
 
-    if cmd.skip_wait {
-        let text = format!("Start provider request received: {}", &provider_ref);
-        return Ok(CommandOutput::new(
-            text.clone(),
-            HashMap::from([
-                ("result".into(), text.into()),
-                ("provider_ref".into(), provider_ref.into()),
-                ("link_name".into(), cmd.link_name.into()),
-                ("host_id".into(), host.to_string().into()),
-            ]),
-        ));
+    /// The links given with `--link` and `--link-file`, under each of the provider's link names.
+    /// Fails if any of the links overlap with each other
+    pub fn inline_links(&self) -> Result<Vec<Link>> {
+        let from_file = match &self.link_file {
+            Some(path) => load_link_file(path)?,
+            None => Vec::new(),
+        };
+        let links = self
+            .links
+            .iter()
+            .chain(&from_file)
+            .flat_map(|link| {
+                self.link_names
+                    .iter()
+                    .map(|name| link.to_link(&self.provider_id, name))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Links::builder()
+            .links(links.clone())
+            .build()
+            .context("links to the provider overlap")?;
+        Ok(links)
     }
 
-    let event = wait_for_provider_start_event(
-        &mut receiver,
-        Duration::from_millis(timeout_ms),
-        host.to_string(),
-        provider_ref.clone(),
-    )
-    .await
-    .with_context(|| {
-        format!(
-            "Timed out waiting for start event for provider {} on host {}",
-            &provider_ref, &host
-        )
-    })?;
+    /// Whether any links should be put as part of the start
+    #[must_use]
+    pub fn has_inline_links(&self) -> bool {
+        !self.links.is_empty() || self.link_file.is_some()
+    }
 
-    match event {
-        FindEventOutcome::Success(ProviderStartedInfo {
-            provider_id,
-            provider_ref,
-            host_id,
-        }) => {
-            let text = format!(
-                "Provider [{}] (ref: [{}]) started on host [{}]",
-                &provider_id, &provider_ref, &host_id
+    /// Whether more than one provider should be started
+    #[must_use]
+    pub fn is_batch(&self) -> bool {
+        !self.batch.is_empty() || self.batch_file.is_some()
+    }
+
+    /// Every provider to start: the one given as `provider-ref` and `provider-id` first, then
+    /// those given with `--and-provider` and in `--batch-file`. Fails if a provider ID is used
+    /// more than once
+    pub fn batch_providers(&self) -> Result<Vec<BatchProvider>> {
+        let mut providers = vec![BatchProvider {
+            provider_ref: self.provider_ref.clone(),
+            provider_id: self.provider_id.clone(),
+        }];
+        providers.extend(self.batch.iter().cloned());
+        if let Some(path) = &self.batch_file {
+            let contents = std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read batch file [{}]", path.display()))?;
+            let from_file: Vec<BatchProvider> = serde_yaml::from_str(&contents)
+                .with_context(|| format!("failed to parse batch file [{}]", path.display()))?;
+            for provider in &from_file {
+                validate_component_id(&provider.provider_id).with_context(|| {
+                    format!("invalid provider ID in batch file [{}]", path.display())
+                })?;
+            }
+            providers.extend(from_file);
+        }
+        let mut seen = std::collections::HashSet::new();
+        if let Some(duplicate) = providers
+            .iter()
+            .find(|p| !seen.insert(p.provider_id.as_str()))
+        {
+            bail!(
+                "provider ID [{}] is used more than once in the batch",
+                duplicate.provider_id
             );
-            Ok(CommandOutput::new(
-                text.clone(),
-                HashMap::from([
-                    ("result".into(), text.into()),
-                    ("provider_ref".into(), provider_ref.into()),
-                    ("provider_id".into(), provider_id.into()),
-                    ("host_id".into(), host_id.into()),
-                ]),
-            ))
         }
-        FindEventOutcome::Failure(err) => Err(err).with_context(|| {
-            format!(
-                "Failed starting provider {} on host {}",
-                &provider_ref, &host
-            )
-        }),
+        Ok(providers)
+    }
+}
+
+/// A provider started as part of a batch, given with `--and-provider` in the form
+/// `<provider-ref>=<provider-id>` or as an entry of a `--batch-file`
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatchProvider {
+    pub provider_ref: String,
+    pub provider_id: String,
+}
+
+impl std::str::FromStr for BatchProvider {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (provider_ref, provider_id) = s
+            .rsplit_once('=')
+            .context("provider must be in the form <provider-ref>=<provider-id>")?;
+        if provider_ref.is_empty() {
+            bail!("provider [{s}] has an empty reference");
+        }
+        Ok(Self {
+            provider_ref: provider_ref.to_string(),
+            provider_id: validate_component_id(provider_id)?,
+        })
+    }
+}
+
+/// The outcome of starting one provider of a batch
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct BatchStartResult {
+    pub provider_ref: String,
+    pub provider_id: String,
+    /// The host the provider was started on, if it started
+    pub host_id: Option<String>,
+    /// Set if the provider could not be started
+    pub error: Option<String>,
+}
+
+/// Load the links in a `--link-file`. Parse errors point at the offending line of the file
+pub fn load_link_file(path: &std::path::Path) -> Result<Vec<InlineLink>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read link file [{}]", path.display()))?;
+    let links: Vec<InlineLink> = serde_yaml::from_str(&contents).map_err(|e| {
+        let line = e.location().and_then(|loc| {
+            Some((
+                loc.line(),
+                contents.lines().nth(loc.line().checked_sub(1)?)?,
+            ))
+        });
+        match line {
+            Some((number, text)) => anyhow!(
+                "failed to parse link file [{}]: {e}\n  {number} | {}",
+                path.display(),
+                text.trim_end()
+            ),
+            None => anyhow!("failed to parse link file [{}]: {e}", path.display()),
+        }
+    })?;
+    for (i, link) in links.iter().enumerate() {
+        link.validate().with_context(|| {
+            format!("invalid link #{} in link file [{}]", i + 1, path.display())
+        })?;
+    }
+    Ok(links)
+}
+
+/// Fail if the same link name was given more than once
+pub fn validate_link_names(link_names: &[String]) -> Result<()> {
+    let mut seen = std::collections::HashSet::new();
+    if let Some(duplicate) = link_names.iter().find(|name| !seen.insert(name.as_str())) {
+        bail!("link name [{duplicate}] was given more than once");
+    }
+    Ok(())
+}
+
+/// Annotation used to pass `--max-concurrent-invocations` through to the host
+pub const MAX_CONCURRENT_INVOCATIONS_ANNOTATION: &str = "wasmcloud.dev/max-concurrent-invocations";
+
+/// Build the annotations sent along with a provider start request
+pub fn provider_start_annotations(cmd: &StartProviderCommand) -> Option<BTreeMap<String, String>> {
+    let mut annotations = BTreeMap::from_iter(cmd.image_annotations.iter().cloned());
+    if let Some(max) = cmd.max_concurrent_invocations {
+        annotations.insert(
+            MAX_CONCURRENT_INVOCATIONS_ANNOTATION.to_string(),
+            max.to_string(),
+        );
+    }
+    (!annotations.is_empty()).then_some(annotations)
+}
+
+/// A link from a component to the provider being started, given inline with `--link` in the form
+/// `<source-id>=<namespace>:<package>/<interface>[,<interface>...]` or as an entry of a
+/// `--link-file`
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InlineLink {
+    pub source_id: String,
+    pub wit_namespace: String,
+    pub wit_package: String,
+    pub interfaces: Vec<String>,
+}
+
+impl std::str::FromStr for InlineLink {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (source_id, wit) = s
+            .split_once('=')
+            .context("link must be in the form <source-id>=<namespace>:<package>/<interface>")?;
+        let (namespace_package, interfaces) = wit
+            .split_once('/')
+            .context("link is missing the interface(s), e.g. wasi:keyvalue/store")?;
+        let (wit_namespace, wit_package) = namespace_package
+            .split_once(':')
+            .context("link is missing the WIT namespace or package, e.g. wasi:keyvalue/store")?;
+        let interfaces = interfaces
+            .split(',')
+            .map(str::trim)
+            .filter(|i| !i.is_empty())
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        let link = Self {
+            source_id: source_id.to_string(),
+            wit_namespace: wit_namespace.to_string(),
+            wit_package: wit_package.to_string(),
+            interfaces,
+        };
+        link.validate()
+            .with_context(|| format!("invalid link [{s}]"))?;
+        Ok(link)
+    }
+}
+
+impl std::fmt::Display for InlineLink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}={}:{}/{}",
+            self.source_id,
+            self.wit_namespace,
+            self.wit_package,
+            self.interfaces.join(",")
+        )
+    }
+}
+
+impl InlineLink {
+    /// Check that no field is empty and the source is a valid component ID
+    fn validate(&self) -> Result<()> {
+        if self.source_id.is_empty()
+            || self.wit_namespace.is_empty()
+            || self.wit_package.is_empty()
+            || self.interfaces.iter().all(|i| i.trim().is_empty())
+        {
+            bail!("link has an empty source, namespace, package or interface list");
+        }
+        validate_component_id(&self.source_id)?;
+        Ok(())
+    }
+
+    /// Build the link to the given provider under the given link name
+    pub fn to_link(&self, provider_id: &str, link_name: &str) -> Result<Link> {
+        Link::builder()
+            .source_id(&self.source_id)
+            .target(provider_id)
+            .name(link_name)
+            .wit_namespace(&self.wit_namespace)
+            .wit_package(&self.wit_package)
+            .interfaces(self.interfaces.clone())
+            .build()
+            .map_err(|e| anyhow::anyhow!(e))
+    }
+}
+
+/// Error context recording how the links put for a provider were rolled back because it failed
+/// to start. Links are described as `<source> -> <target> on <namespace>:<package>/<interfaces>
+/// (<name>)`
+#[derive(Debug, Default)]
+pub struct LinksRolledBack {
+    /// The links created for the provider that were deleted again
+    pub links: Vec<String>,
+    /// The links updated for the provider that were put back as they were before
+    pub restored: Vec<String>,
+    /// The deletes and puts of the rollback that failed or the host rejected
+    pub failed: Vec<String>,
+}
+
+impl LinksRolledBack {
+    /// Whether the rollback did nothing
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.links.is_empty() && self.restored.is_empty() && self.failed.is_empty()
+    }
+}
+
+impl std::fmt::Display for LinksRolledBack {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if !self.links.is_empty() {
+            parts.push(format!(
+                "rolled back {} link(s) created for the provider: {}",
+                self.links.len(),
+                self.links.join(", ")
+            ));
+        }
+        if !self.restored.is_empty() {
+            parts.push(format!(
+                "restored {} link(s) updated for the provider: {}",
+                self.restored.len(),
+                self.restored.join(", ")
+            ));
+        }
+        if !self.failed.is_empty() {
+            parts.push(format!(
+                "failed to roll back {} link change(s): {}",
+                self.failed.len(),
+                self.failed.join(", ")
+            ));
+        }
+        f.write_str(&parts.join("; "))
+    }
+}
+
+impl std::error::Error for LinksRolledBack {}
+
+fn describe_link(link: &Link) -> String {
+    format!(
+        "{} -> {} on {}:{}/{} ({})",
+        link.source_id(),
+        link.target(),
+        link.wit_namespace(),
+        link.wit_package(),
+        link.interfaces().join(","),
+        link.name(),
+    )
+}
+
+/// Pick out the links to the provider that the host reports for the links wired during a start,
+/// so the output reflects the confirmed state rather than what was requested
+#[must_use]
+pub fn established_links(
+    requested: &[Link],
+    lattice_links: Vec<Link>,
+    provider_id: &str,
+) -> Vec<Link> {
+    let keys = requested.iter().map(LinkKey::from).collect::<Vec<_>>();
+    Links::from_iter(lattice_links)
+        .iter_for_target(provider_id)
+        .filter(|link| keys.contains(&LinkKey::from(*link)))
+        .cloned()
+        .collect()
+}
+
+/// Start every provider of a batch concurrently and report their outcomes together. A provider
+/// failing to start doesn't stop the others
+async fn start_provider_batch(cmd: StartProviderCommand) -> Result<CommandOutput> {
+    let providers = cmd.batch_providers()?;
+    let starts = providers.iter().map(|provider| {
+        Box::pin(handle_start_provider(StartProviderCommand {
+            provider_ref: provider.provider_ref.clone(),
+            provider_id: provider.provider_id.clone(),
+            batch: Vec::new(),
+            batch_file: None,
+            ..cmd.clone()
+        }))
+    });
+    let outcomes = futures::future::join_all(starts).await;
+
+    let results = providers
+        .into_iter()
+        .zip(outcomes)
+        .map(|(provider, outcome)| {
+            let (host_id, error) = match outcome {
+                Ok(output) => (
+                    output
+                        .map
+                        .get("host_id")
+                        .and_then(serde_json::Value::as_str)
+                        .map(ToString::to_string),
+                    None,
+                ),
+                Err(e) => (None, Some(format!("{e:#}"))),
+            };
+            BatchStartResult {
+                provider_ref: provider.provider_ref,
+                provider_id: provider.provider_id,
+                host_id,
+                error,
+            }
+        })
+        .collect::<Vec<_>>();
+    batch_start_output(&results, cmd.dry_run)
+}
+
+/// Render the outcomes of a batch start as a table. If any provider failed, the table is returned
+/// as a [`FailureKind::TargetsFailed`] error so the command exits with its code. With `dry_run`,
+/// the hosts are the ones that would have received the starts
+pub fn batch_start_output(results: &[BatchStartResult], dry_run: bool) -> Result<CommandOutput> {
+    let failed = results.iter().filter(|r| r.error.is_some()).count();
+    let mut table = Table::new();
+    configure_table_style(&mut table);
+    table.add_row(Row::new(vec![
+        TableCell::new_with_alignment("Provider ID", 1, Alignment::Left),
+        TableCell::new_with_alignment("Reference", 1, Alignment::Left),
+        TableCell::new_with_alignment("Host ID", 1, Alignment::Left),
+        TableCell::new_with_alignment("Result", 1, Alignment::Left),
+    ]));
+    for result in results {
+        table.add_row(Row::new(vec![
+            TableCell::new_with_alignment(&result.provider_id, 1, Alignment::Left),
+            TableCell::new_with_alignment(&result.provider_ref, 1, Alignment::Left),
+            TableCell::new_with_alignment(
+                result.host_id.as_deref().unwrap_or("-"),
+                1,
+                Alignment::Left,
+            ),
+            TableCell::new_with_alignment(
+                result
+                    .error
+                    .as_deref()
+                    .unwrap_or(if dry_run { "would start" } else { "started" }),
+                1,
+                Alignment::Left,
+            ),
+        ]));
+    }
+    let summary = if dry_run {
+        format!(
+            "Dry run, nothing was sent. {} of {} provider(s) could be started",
+            results.len() - failed,
+            results.len()
+        )
+    } else if failed == 0 {
+        format!("Started {} provider(s)", results.len())
+    } else {
+        format!("Failed to start {failed} of {} provider(s)", results.len())
+    };
+    let output = CommandOutput::new(
+        format!("{summary}\n{}", table.render()),
+        HashMap::from([
+            ("providers".into(), serde_json::to_value(results)?),
+            ("partial".into(), (failed > 0).into()),
+            ("dry_run".into(), dry_run.into()),
+        ]),
+    );
+    if failed > 0 {
+        return Err(
+            Failure::with_details(FailureKind::TargetsFailed, output.text, output.map).into(),
+        );
+    }
+    Ok(output)
+}
+
+/// The outcome of starting the provider on one host with `--every-host`
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct HostStartResult {
+    pub host_id: String,
+    pub friendly_name: String,
+    /// Set if the provider could not be started on the host
+    pub error: Option<String>,
+}
+
+/// Start the provider on every schedulable host matching the constraints concurrently and report
+/// the outcome on each host together. The provider is checked once for every host, and a host
+/// failing to start the provider doesn't stop the others
+async fn start_provider_every_host(cmd: StartProviderCommand) -> Result<CommandOutput> {
+    let mut wco: WashConnectionOptions = cmd.opts.clone().try_into()?;
+    // Requests must not time out before the ack timeout does
+    wco.timeout_ms = wco.timeout_ms.max(cmd.ack_timeout_ms.unwrap_or_default());
+    let client = wco.into_ctl_client(None).await?;
+
+    let result = start_provider_on_hosts(&client, &cmd).await;
+    close_ctl_client(&client).await;
+    result
+}
+
+async fn start_provider_on_hosts(
+    client: &wasmcloud_control_interface::Client,
+    cmd: &StartProviderCommand,
+) -> Result<CommandOutput> {
+    let query = HostQuery {
+        label_filter: input_vec_to_hashmap(cmd.constraints.clone().unwrap_or_default())?,
+        label_projection: Some(vec![UNSCHEDULABLE_LABEL.to_string()]),
+    };
+    let mut hosts = list_hosts(client, &query).await?;
+    // Cordoned hosts are left out, as they are from auctions
+    let schedulable = schedulable_hosts(
+        &hosts.iter().map(|host| host.id.clone()).collect::<Vec<_>>(),
+        &hosts
+            .iter()
+            .map(|host| (host.id.clone(), host.labels.clone()))
+            .collect(),
+    );
+    hosts.retain(|host| schedulable.contains(&host.id));
+    if hosts.is_empty() {
+        bail!(
+            "No schedulable hosts match the constraints to start provider {} on",
+            cmd.provider_ref
+        );
+    }
+
+    let prepared = prepare_provider_start(cmd).await?;
+    let config_upload = if cmd.dry_run {
+        None
+    } else {
+        upload_provider_config(client, cmd, &prepared).await?
+    };
+    let starts = hosts.iter().map(|host| {
+        let (prepared, config_upload) = (&prepared, config_upload.as_ref());
+        async move {
+            let host_id: ServerId = host
+                .id
+                .parse()
+                .with_context(|| format!("Failed to parse host id: {}", host.id))?;
+            if !cmd.dry_run {
+                send_provider_start(client, cmd, prepared, config_upload, host_id, None).await?;
+            }
+            anyhow::Ok(())
+        }
+    });
+    let outcomes = futures::future::join_all(starts).await;
+
+    let results = hosts
+        .into_iter()
+        .zip(outcomes)
+        .map(|(host, outcome)| HostStartResult {
+            host_id: host.id,
+            friendly_name: host.friendly_name,
+            error: outcome.err().map(|e| format!("{e:#}")),
+        })
+        .collect::<Vec<_>>();
+    every_host_start_output(&cmd.provider_id, &results, cmd.dry_run)
+}
+
+/// Render the outcomes of an `--every-host` start as a table. If any host failed to start the
+/// provider, this is a [`FailureKind::TargetsFailed`] failure carrying the output
+pub fn every_host_start_output(
+    provider_id: &str,
+    results: &[HostStartResult],
+    dry_run: bool,
+) -> Result<CommandOutput> {
+    let failed = results.iter().filter(|r| r.error.is_some()).count();
+    let mut table = Table::new();
+    configure_table_style(&mut table);
+    table.add_row(Row::new(vec![
+        TableCell::new_with_alignment("Host ID", 1, Alignment::Left),
+        TableCell::new_with_alignment("Friendly name", 1, Alignment::Left),
+        TableCell::new_with_alignment("Result", 1, Alignment::Left),
+    ]));
+    for result in results {
+        table.add_row(Row::new(vec![
+            TableCell::new_with_alignment(&result.host_id, 1, Alignment::Left),
+            TableCell::new_with_alignment(&result.friendly_name, 1, Alignment::Left),
+            TableCell::new_with_alignment(
+                result
+                    .error
+                    .as_deref()
+                    .unwrap_or(if dry_run { "would start" } else { "started" }),
+                1,
+                Alignment::Left,
+            ),
+        ]));
+    }
+    let summary = if dry_run {
+        format!(
+            "Dry run, nothing was sent. Provider [{provider_id}] could be started on {} of {} host(s)",
+            results.len() - failed,
+            results.len()
+        )
+    } else if failed == 0 {
+        format!(
+            "Provider [{provider_id}] started on {} host(s)",
+            results.len()
+        )
+    } else {
+        format!(
+            "Failed to start provider [{provider_id}] on {failed} of {} host(s)",
+            results.len()
+        )
+    };
+    let output = CommandOutput::new(
+        format!("{summary}\n{}", table.render()),
+        HashMap::from([
+            ("provider_id".into(), provider_id.into()),
+            ("hosts".into(), serde_json::to_value(results)?),
+            ("partial".into(), (failed > 0).into()),
+            ("dry_run".into(), dry_run.into()),
+        ]),
+    );
+    if failed > 0 {
+        return Err(
+            Failure::with_details(FailureKind::TargetsFailed, output.text, output.map).into(),
+        );
+    }
+    Ok(output)
+}
+
+/// The flags of a canary start that apply to every host of the rollout, as command line arguments
+/// to pass again when the rollout is promoted. Host selection and connection options are left out,
+/// and relative paths are made absolute so the rollout can be promoted from another directory
+pub fn rollout_start_args(cmd: &StartProviderCommand) -> Result<Vec<String>> {
+    let mut args = Vec::new();
+    let mut flag = |name: &str, value: Option<String>| {
+        args.push(format!("--{name}"));
+        args.extend(value);
+    };
+    let path = |path: &Path| {
+        std::path::absolute(path)
+            .map(|p| p.display().to_string())
+            .with_context(|| format!("failed to resolve path `{}`", path.display()))
+    };
+    for fallback_ref in &cmd.fallback_refs {
+        flag("fallback-ref", Some(fallback_ref.clone()));
+    }
+    for link_name in &cmd.link_names {
+        flag("link-name", Some(link_name.clone()));
+    }
+    for link in &cmd.links {
+        flag("link", Some(link.to_string()));
+    }
+    if let Some(link_file) = &cmd.link_file {
+        flag("link-file", Some(path(link_file)?));
+    }
+    for config in &cmd.config {
+        flag("config", Some(config.clone()));
+    }
+    if let Some(config_file) = &cmd.config_file {
+        flag("config-file", Some(path(config_file)?));
+    }
+    if cmd.config_cache {
+        flag("config-cache", None);
+    }
+    if cmd.strict_host {
+        flag("strict-host", None);
+    }
+    if cmd.skip_wait {
+        flag("skip-wait", None);
+    }
+    if let Some(ack_timeout_ms) = cmd.ack_timeout_ms {
+        flag("ack-timeout-ms", Some(ack_timeout_ms.to_string()));
+    }
+    if let Some(verify_poll_ms) = cmd.verify_poll_ms {
+        flag("verify-poll-ms", Some(verify_poll_ms.to_string()));
+    }
+    if cmd.exact_ref_match {
+        flag("exact-ref-match", None);
+    }
+    if cmd.verify_signature {
+        flag("verify-signature", None);
+    }
+    for issuer in &cmd.trusted_issuers {
+        flag("trusted-issuer", Some(issuer.clone()));
+    }
+    if cmd.validate_world {
+        flag("validate-world", None);
+    }
+    if let Some(host_wit) = &cmd.host_wit {
+        flag("host-wit", Some(path(host_wit)?));
+    }
+    if cmd.watch {
+        flag("watch", None);
+        flag("watch-timeout-ms", Some(cmd.watch_timeout_ms.to_string()));
+    }
+    if let Some(group) = &cmd.events_queue_group {
+        flag("events-queue-group", Some(group.clone()));
+    }
+    if cmd.wait_healthy {
+        flag("wait-healthy", None);
+        flag("warmup-ms", Some(cmd.warmup_ms.to_string()));
+    }
+    if cmd.await_provider_links {
+        flag("await-provider-links", None);
+    }
+    if let Some(timeout_ms) = cmd.await_links_timeout_ms {
+        flag("await-links-timeout-ms", Some(timeout_ms.to_string()));
+    }
+    if cmd.pre_pull {
+        flag("pre-pull", None);
+        flag("pull-timeout-ms", Some(cmd.pull_timeout_ms.to_string()));
+    }
+    if cmd.require_features {
+        flag("require-features", None);
+    }
+    if let Some(max) = cmd.max_concurrent_invocations {
+        flag("max-concurrent-invocations", Some(max.to_string()));
+    }
+    for (key, value) in &cmd.image_annotations {
+        flag("image-annotation", Some(format!("{key}={value}")));
+    }
+    flag("retries", Some(cmd.retry.retries.to_string()));
+    flag(
+        "retry-backoff-ms",
+        Some(cmd.retry.retry_backoff_ms.to_string()),
+    );
+    Ok(args)
+}
+
+/// Start the provider on the canary share of the hosts matching the constraints and persist a
+/// rollout for the remaining hosts
+async fn start_provider_canary(cmd: StartProviderCommand, percent: u8) -> Result<CommandOutput> {
+    let client = <CliConnectionOpts as TryInto<WashConnectionOptions>>::try_into(cmd.opts.clone())?
+        .into_ctl_client(None)
+        .await?;
+    let query = HostQuery {
+        label_filter: input_vec_to_hashmap(cmd.constraints.clone().unwrap_or_default())?,
+        label_projection: Some(Vec::new()),
+    };
+    let group = list_hosts(&client, &query)
+        .await?
+        .into_iter()
+        .map(|host| host.id)
+        .collect();
+    let (canary, pending) = split_canary(group, percent);
+    if canary.is_empty() {
+        bail!(
+            "No hosts match the constraints for a canary rollout of provider {}",
+            cmd.provider_ref
+        );
+    }
+
+    // The rollout is saved before anything is started, so a canary that fails part way can still
+    // be promoted (or inspected) by its handle
+    let mut rollout = Rollout::new(
+        &cmd.provider_ref,
+        &cmd.provider_id,
+        rollout_start_args(&cmd)?,
+        Vec::new(),
+        canary.iter().chain(&pending).cloned().collect(),
+    );
+    rollout.save()?;
+
+    for host_id in &canary {
+        let start = StartProviderCommand {
+            host_id: Some(host_id.clone()),
+            canary: None,
+            ..cmd.clone()
+        };
+        if let Err(e) = Box::pin(handle_start_provider(start)).await {
+            if let Err(save_err) = rollout.save() {
+                warn!(?save_err, "failed to save rollout progress");
+            }
+            return Err(e).with_context(|| {
+                format!(
+                    "Failed to start canary of provider [{}] on host [{host_id}] for rollout [{}]",
+                    cmd.provider_id, rollout.handle
+                )
+            });
+        }
+        rollout.mark_started(host_id);
+    }
+    rollout.save()?;
+
+    let text = format!(
+        "Provider [{}] started on {} of {} hosts as a canary. Run `wash rollout promote {}` to start it on the rest",
+        rollout.provider_id,
+        rollout.started.len(),
+        rollout.started.len() + rollout.pending.len(),
+        rollout.handle
+    );
+    Ok(CommandOutput::new(
+        text.clone(),
+        HashMap::from([
+            ("result".into(), text.into()),
+            ("rollout_handle".into(), rollout.handle.clone().into()),
+            ("rollout".into(), serde_json::to_value(&rollout)?),
+        ]),
+    ))
+}
+
+/// Start a provider like [`handle_start_provider`], letting `selector` pick the host from the
+/// auction responses instead of the built-in placement strategies. The selector is not used if
+/// the command names a host
+pub async fn handle_start_provider_with_selector(
+    cmd: StartProviderCommand,
+    selector: HostSelector,
+) -> Result<CommandOutput> {
+    handle_start_provider(StartProviderCommand {
+        host_selector: Some(selector),
+        ..cmd
+    })
+    .await
+}
+
+pub async fn handle_start_provider(mut cmd: StartProviderCommand) -> Result<CommandOutput> {
+    cmd.take_deprecated_link_name();
+    if cmd.receipt.receipt {
+        let opts = cmd.receipt.clone();
+        let command = format!("start provider {} {}", cmd.provider_ref, cmd.provider_id);
+        let output = Box::pin(handle_start_provider(StartProviderCommand {
+            receipt: ReceiptOpts::default(),
+            ..cmd
+        }))
+        .await?;
+        return attach_receipt(output, &opts, command);
+    }
+    if cmd.is_batch() {
+        return start_provider_batch(cmd).await;
+    }
+    validate_link_names(&cmd.link_names)?;
+    if cmd.every_host {
+        return start_provider_every_host(cmd).await;
+    }
+    if cmd.dry_run {
+        return dry_run_start_provider(cmd).await;
+    }
+    if let Some(percent) = cmd.canary {
+        return start_provider_canary(cmd, percent).await;
+    }
+    if !cmd.has_inline_links() {
+        return start_provider_with_fallbacks(cmd).await;
+    }
+
+    let links = cmd.inline_links()?;
+    let provider_id = cmd.provider_id.clone();
+    let wco: WashConnectionOptions = cmd.opts.clone().try_into()?;
+
+    // Links already in the lattice must be left as they were if the provider fails to start, so
+    // each link is classified against them before anything is put
+    let before = Links::from_iter(
+        get_links(wco.clone())
+            .await
+            .context("failed to retrieve links")?,
+    );
+    let mut planned = before.clone();
+    let mut outcomes = Vec::with_capacity(links.len());
+    for link in &links {
+        let outcome = planned.put(link.clone()).map_err(|conflict| {
+            anyhow!("Failed to put link {}: {conflict}", describe_link(link))
+        })?;
+        outcomes.push(outcome);
+    }
+
+    // Links are put before the provider starts so it receives them as part of its startup
+    let mut after = before.clone();
+    let mut put = Vec::with_capacity(links.len());
+    let result = async {
+        for (link, outcome) in links.iter().zip(outcomes) {
+            if outcome == LinkPutOutcome::Unchanged {
+                continue;
+            }
+            let ack = put_link(wco.clone(), link.clone()).await?;
+            if !ack.succeeded() {
+                return Err(Failure::new(
+                    FailureKind::AckRejected,
+                    format!(
+                        "Failed to put link {}: {}",
+                        describe_link(link),
+                        ack.message()
+                    ),
+                )
+                .into());
+            }
+            // Conflicts were already ruled out when classifying the links
+            let _ = after.put(link.clone());
+            put.push((link.clone(), outcome));
+        }
+        start_provider_with_fallbacks(cmd).await
+    }
+    .await;
+
+    match result {
+        Ok(mut output) => {
+            output.map.insert(
+                "links".into(),
+                links.iter().map(describe_link).collect::<Vec<_>>().into(),
+            );
+            match get_links(wco).await {
+                Ok(lattice_links) => {
+                    let established = established_links(&links, lattice_links, &provider_id);
+                    output.text.push_str("\nEstablished links:");
+                    for link in &established {
+                        output
+                            .text
+                            .push_str(&format!("\n  {}", describe_link(link)));
+                    }
+                    output.map.insert(
+                        "established_links".into(),
+                        serde_json::to_value(&established)?,
+                    );
+                }
+                Err(e) => warn!(?e, "failed to list links after starting provider"),
+            }
+            Ok(output)
+        }
+        Err(err) => {
+            let rolled_back = roll_back_links(&wco, &before, &after, &put).await;
+            if rolled_back.is_empty() {
+                Err(err)
+            } else {
+                Err(err.context(rolled_back))
+            }
+        }
+    }
+}
+
+/// Undo the links put for a provider that failed to start, so the lattice holds the links in
+/// `before` again. Created links are deleted and updated links are put back as they were, while
+/// links that were already present unchanged are never touched. The host deletes a single link
+/// under a key at a time, so a key holding a created link is cleared and the links it held before
+/// are put back
+async fn roll_back_links(
+    wco: &WashConnectionOptions,
+    before: &Links,
+    after: &Links,
+    put: &[(Link, LinkPutOutcome)],
+) -> LinksRolledBack {
+    let mut rolled_back = LinksRolledBack::default();
+    let created_keys = put
+        .iter()
+        .filter(|(_, outcome)| *outcome == LinkPutOutcome::Created)
+        .map(|(link, _)| LinkKey::from(link))
+        .collect::<BTreeSet<_>>();
+    let previous = |link: &Link| {
+        before
+            .get(&LinkKey::from(link))
+            .iter()
+            .find(|previous| previous.target() == link.target())
+            .cloned()
+    };
+
+    for key in &created_keys {
+        let mut cleared = true;
+        for _ in after.get(key) {
+            let failure = match delete_link(
+                wco.clone(),
+                key.source_id(),
+                key.name(),
+                key.wit_namespace(),
+                key.wit_package(),
+            )
+            .await
+            {
+                Ok(ack) if ack.succeeded() => continue,
+                Ok(ack) => ack.message().to_string(),
+                Err(e) => format!("{e:#}"),
+            };
+            rolled_back
+                .failed
+                .push(format!("deleting link {key}: {failure}"));
+            cleared = false;
+            break;
+        }
+        if !cleared {
+            continue;
+        }
+        for (link, outcome) in put {
+            if LinkKey::from(link) != *key {
+                continue;
+            }
+            match outcome {
+                LinkPutOutcome::Created => rolled_back.links.push(describe_link(link)),
+                _ => {
+                    if let Some(previous) = previous(link) {
+                        rolled_back.restored.push(describe_link(&previous));
+                    }
+                }
+            }
+        }
+        for link in before.get(key) {
+            if let Err(failure) = put_back_link(wco, link).await {
+                rolled_back.failed.push(failure);
+            }
+        }
+    }
+
+    for (link, outcome) in put {
+        if *outcome != LinkPutOutcome::Updated || created_keys.contains(&LinkKey::from(link)) {
+            continue;
+        }
+        let Some(previous) = previous(link) else {
+            continue;
+        };
+        match put_back_link(wco, &previous).await {
+            Ok(()) => rolled_back.restored.push(describe_link(&previous)),
+            Err(failure) => rolled_back.failed.push(failure),
+        }
+    }
+    rolled_back
+}
+
+/// Put a link back as it was before a failed provider start, describing the failure if the host
+/// didn't accept it
+async fn put_back_link(wco: &WashConnectionOptions, link: &Link) -> Result<(), String> {
+    let failure = match put_link(wco.clone(), link.clone()).await {
+        Ok(ack) if ack.succeeded() => return Ok(()),
+        Ok(ack) => ack.message().to_string(),
+        Err(e) => format!("{e:#}"),
+    };
+    Err(format!("restoring link {}: {failure}", describe_link(link)))
+}
+
+/// Check every world declared by the provider archive against the host world
+async fn validate_provider_world(archive: &[u8], host_wit: &Path) -> Result<()> {
+    let host = load_host_world(host_wit)?;
+    let worlds = provider_worlds(archive).await?;
+    if worlds.is_empty() {
+        bail!("provider WIT package does not declare any worlds");
+    }
+    for world in &worlds {
+        check_world_compatible(world, &host)?;
+    }
+    Ok(())
+}
+
+/// Run every check of a provider start and select its host, without sending the start request or
+/// putting any links
+async fn dry_run_start_provider(cmd: StartProviderCommand) -> Result<CommandOutput> {
+    let links = cmd.inline_links()?;
+
+    let mut output = start_provider_with_fallbacks(cmd).await?;
+    if !links.is_empty() {
+        output.text.push_str("\nLinks that would be put:");
+        for link in &links {
+            output
+                .text
+                .push_str(&format!("\n  {}", describe_link(link)));
+        }
+        output.map.insert(
+            "links".into(),
+            links.iter().map(describe_link).collect::<Vec<_>>().into(),
+        );
+    }
+    Ok(output)
+}
+
+/// Start the provider from its reference, falling back to each `--fallback-ref` in turn if pulling
+/// the previous reference failed
+async fn start_provider_with_fallbacks(cmd: StartProviderCommand) -> Result<CommandOutput> {
+    if cmd.fallback_refs.is_empty() {
+        return start_provider(cmd).await;
+    }
+
+    let refs = std::iter::once(cmd.provider_ref.clone())
+        .chain(cmd.fallback_refs.clone())
+        .collect::<Vec<_>>();
+    let (used_ref, mut output) = start_with_fallback_refs(refs, |provider_ref| {
+        let cmd = StartProviderCommand {
+            provider_ref,
+            ..cmd.clone()
+        };
+        start_provider(cmd)
+    })
+    .await?;
+    let fallback_used = used_ref != cmd.provider_ref;
+    if fallback_used {
+        output.text = format!("{} (using fallback ref [{used_ref}])", output.text);
+    }
+    output
+        .map
+        .insert("fallback_used".into(), fallback_used.into());
+    Ok(output)
+}
+
+async fn start_provider(cmd: StartProviderCommand) -> Result<CommandOutput> {
+    let mut wco: WashConnectionOptions = cmd.opts.clone().try_into()?;
+    // Requests must not time out before the ack timeout does
+    wco.timeout_ms = wco.timeout_ms.max(cmd.ack_timeout_ms.unwrap_or_default());
+    let client = wco.into_ctl_client(Some(cmd.auction_timeout_ms)).await?;
+
+    let result = start_provider_with_client(client.clone(), cmd).await;
+    close_ctl_client(&client).await;
+    result
+}
+
+async fn start_provider_with_client(
+    client: wasmcloud_control_interface::Client,
+    cmd: StartProviderCommand,
+) -> Result<CommandOutput> {
+    let prepared = prepare_provider_start(&cmd).await?;
+    let (host, fanout) = select_provider_host(&client, &cmd, &prepared.provider_ref).await?;
+    if cmd.dry_run {
+        return Ok(dry_run_provider_output(&cmd, &prepared, &host, fanout));
+    }
+    let config_upload = upload_provider_config(&client, &cmd, &prepared).await?;
+    send_provider_start(
+        &client,
+        &cmd,
+        &prepared,
+        config_upload.as_ref(),
+        host,
+        fanout,
+    )
+    .await
+}
+
+/// What starting a provider needs that doesn't depend on the host it starts on: its resolved
+/// reference and start timeout, and the outcome of the checks run on its archive
+struct PreparedProviderStart {
+    provider_ref: String,
+    timeout_ms: u64,
+    sized_timeout: Option<SizedStartTimeout>,
+    config_values: Option<ProviderConfigValues>,
+    verification: Option<SignatureVerification>,
+}
+
+/// The values of `--config-file`, checked against the provider's config schema if it embeds one
+struct ProviderConfigValues {
+    values: BTreeMap<String, String>,
+    /// The keywords of the schema that were not checked, if there is a schema
+    unchecked: Option<BTreeSet<String>>,
+}
+
+/// Resolve the provider reference and run the checks of `cmd` on the provider archive, which is
+/// downloaded at most once
+async fn prepare_provider_start(cmd: &StartProviderCommand) -> Result<PreparedProviderStart> {
+    let user_timeout_ms =
+        (cmd.opts.timeout_ms != DEFAULT_NATS_TIMEOUT_MS).then_some(cmd.opts.timeout_ms);
+
+    // Attempt to parse the provider_ref from strings that may look like paths or be OCI references
+    let provider_ref = resolve_ref(&cmd.provider_ref).await?;
+    check_local_provider_ref(&provider_ref).await?;
+
+    // If timeout isn't supplied, override with a longer timeout for starting provider, sized to
+    // the provider image when its manifest can be fetched
+    let sized_timeout = match user_timeout_ms {
+        None if !cmd.skip_wait => estimate_start_timeout(&provider_ref).await,
+        _ => None,
+    };
+    let timeout_ms = user_timeout_ms
+        .or_else(|| sized_timeout.and_then(|sized| u64::try_from(sized.timeout.as_millis()).ok()))
+        .unwrap_or(DEFAULT_START_PROVIDER_TIMEOUT_MS);
+    if let Some(sized) = sized_timeout {
+        info!(
+            image_size = sized.image_size,
+            timeout_ms, "Sized the provider start timeout to the provider image"
+        );
+    }
+
+    // The provider archive, once downloaded by one of the checks below, is shared with the rest
+    let mut pulled = None;
+
+    if let (true, Some(host_wit)) = (cmd.validate_world, &cmd.host_wit) {
+        let archive = match pulled.take() {
+            Some(archive) => archive,
+            None => download_provider_archive(&provider_ref, &cmd.progress).await?,
+        };
+        validate_provider_world(&archive, host_wit)
+            .await
+            .with_context(|| {
+                format!("Refusing to start provider {provider_ref}: WIT world validation failed")
+            })?;
+        pulled = Some(archive);
+    }
+
+    // Config from a file is checked against the provider's config schema, if it embeds one,
+    // before anything is sent to a host
+    let config_values = if let Some(path) = &cmd.config_file {
+        let values = load_provider_config_file(path).await?;
+        let archive = match pulled.take() {
+            Some(archive) => archive,
+            None => download_provider_archive(&provider_ref, &cmd.progress).await?,
+        };
+        let unchecked = match provider_config_schema(&archive).await? {
+            Some(schema) => {
+                Some(validate_provider_config(&schema, &values).with_context(|| {
+                    format!("Refusing to start provider {provider_ref}: invalid config")
+                })?)
+            }
+            None => None,
+        };
+        pulled = Some(archive);
+        Some(ProviderConfigValues { values, unchecked })
+    } else {
+        None
+    };
+
+    let verification = if cmd.verify_signature {
+        let archive = match pulled {
+            Some(archive) => archive,
+            None => download_provider_archive(&provider_ref, &cmd.progress).await?,
+        };
+        Some(
+            verify_provider_signature(&archive, &cmd.trusted_issuers)
+                .await
+                .with_context(|| {
+                    format!(
+                        "Refusing to start provider {provider_ref}: signature verification failed"
+                    )
+                })?,
+        )
+    } else {
+        None
+    };
+
+    Ok(PreparedProviderStart {
+        provider_ref,
+        timeout_ms,
+        sized_timeout,
+        config_values,
+        verification,
+    })
+}
+
+/// Pick the host to start the provider on: the one named by `cmd`, one running the component it
+/// is colocated with, or one chosen by an auction
+async fn select_provider_host(
+    client: &wasmcloud_control_interface::Client,
+    cmd: &StartProviderCommand,
+    provider_ref: &str,
+) -> Result<(ServerId, Option<AuctionFanout>)> {
+    Ok(if let Some(host) = &cmd.host_id {
+        (
+            find_host_id_with(host, client, &cmd.host_match).await?.0,
+            None,
+        )
+    } else if let Some(component) = &cmd.colocate_with {
+        let inventories = get_all_inventories(client).await?;
+        let candidates = colocation_hosts(component, &inventories)?;
+        let responders = inventories
+            .iter()
+            .filter(|inv| candidates.iter().any(|id| id == inv.host_id()))
+            .map(|inv| (inv.host_id(), inv.labels()))
+            .collect();
+        let (host_id, _) = choose_auction_host(
+            client,
+            &responders,
+            cmd.placement,
+            &cmd.provider_id,
+            cmd.host_match.interactive,
+        )
+        .await
+        .with_context(|| format!("Failed to colocate provider with component [{component}]"))?;
+        (host_id, None)
+    } else {
+        let constraints = BTreeMap::from_iter(input_vec_to_hashmap(
+            cmd.constraints.clone().unwrap_or_default(),
+        )?);
+        let responses = auction_backoff(cmd.backoff)
+            .retry(cmd.auction_retries, || {
+                auction_provider(
+                    client,
+                    provider_ref,
+                    cmd.link_name(),
+                    &constraints,
+                    &cmd.progress,
+                )
+            })
+            .await?;
+        if let Some(selector) = &cmd.host_selector {
+            // The selector does its own filtering, so every responder counts as eligible
+            let host_id = selector.select(&responses)?;
+            let fanout = AuctionFanout {
+                responders: responses.len(),
+                eligible: responses.len(),
+            };
+            let host_id = host_id
+                .parse()
+                .with_context(|| format!("Failed to parse host id: {host_id}"))?;
+            (host_id, Some(fanout))
+        } else {
+            let responders = responses
+                .iter()
+                .map(|ack| (ack.host_id(), ack.labels()))
+                .collect();
+            let (host_id, fanout) = choose_auction_host(
+                client,
+                &responders,
+                cmd.placement,
+                &cmd.provider_id,
+                cmd.host_match.interactive,
+            )
+            .await?;
+            (host_id, Some(fanout))
+        }
+    })
+}
+
+/// The output of a dry run of a provider start, reporting the host it would be started on
+fn dry_run_provider_output(
+    cmd: &StartProviderCommand,
+    prepared: &PreparedProviderStart,
+    host: &ServerId,
+    fanout: Option<AuctionFanout>,
+) -> CommandOutput {
+    let PreparedProviderStart {
+        provider_ref,
+        config_values,
+        verification,
+        ..
+    } = prepared;
+    let mut output = CommandOutput::dry_run(
+        host.to_string(),
+        format!(
+            "start provider [{}] (ref: [{provider_ref}])",
+            cmd.provider_id
+        ),
+        HashMap::from([
+            ("provider_ref".into(), provider_ref.as_str().into()),
+            ("provider_id".into(), cmd.provider_id.clone().into()),
+            ("link_name".into(), cmd.link_name().into()),
+            ("link_names".into(), cmd.link_names.clone().into()),
+            ("config".into(), cmd.config.clone().into()),
+        ]),
+    );
+    if let Some(ProviderConfigValues { unchecked, .. }) = config_values {
+        match unchecked {
+            Some(unchecked) if unchecked.is_empty() => output
+                .text
+                .push_str("\nConfig matches the provider's config schema"),
+            Some(unchecked) => output.text.push_str(&format!(
+                "\nConfig matches the provider's config schema, except for keywords that are not checked: {}",
+                unchecked.iter().cloned().collect::<Vec<_>>().join(", ")
+            )),
+            None => output
+                .text
+                .push_str("\nConfig is valid, the provider does not embed a config schema"),
+        }
+        output
+            .map
+            .insert("schema_validated".into(), unchecked.is_some().into());
+        if let Some(unchecked) = unchecked {
+            output.map.insert(
+                "schema_unchecked_keywords".into(),
+                unchecked.iter().cloned().collect::<Vec<_>>().into(),
+            );
+        }
+    }
+    with_signature_verification(with_auction_fanout(output, fanout), verification.clone())
+}
+
+/// Put the config of `--config-file` in the lattice, returning its name and how it was stored
+async fn upload_provider_config(
+    client: &wasmcloud_control_interface::Client,
+    cmd: &StartProviderCommand,
+    prepared: &PreparedProviderStart,
+) -> Result<Option<(String, ConfigUpload)>> {
+    match &prepared.config_values {
+        Some(ProviderConfigValues { values, .. }) => Ok(Some(
+            put_provider_config(client, values.clone(), cmd.config_cache).await?,
+        )),
+        None => Ok(None),
+    }
+}
+
+/// Ask `host` to start the prepared provider and wait for it to start, as configured by `cmd`
+async fn send_provider_start(
+    client: &wasmcloud_control_interface::Client,
+    cmd: &StartProviderCommand,
+    prepared: &PreparedProviderStart,
+    config_upload: Option<&(String, ConfigUpload)>,
+    host: ServerId,
+    fanout: Option<AuctionFanout>,
+) -> Result<CommandOutput> {
+    let ack_timeout = Duration::from_millis(cmd.ack_timeout_ms.unwrap_or(cmd.opts.timeout_ms));
+    let annotations = provider_start_annotations(cmd);
+    let link_name = cmd.link_name().to_string();
+    let provider_ref = prepared.provider_ref.clone();
+    // With `--pre-pull` the host pulls before launching, within its own timeout
+    let pull_timeout = cmd
+        .pre_pull
+        .then(|| Duration::from_millis(cmd.pull_timeout_ms));
+    let timeout_ms = prepared.timeout_ms + pull_timeout.map_or(0, |_| cmd.pull_timeout_ms);
+
+    let mut config = cmd.config.clone();
+    if let Some((name, _)) = config_upload {
+        config.push(name.clone());
+    }
+
+    let mut event_types = vec![
+        "provider_started".to_string(),
+        "provider_start_failed".to_string(),
+    ];
+    if cmd.watch || cmd.wait_healthy {
+        event_types.extend(PROVIDER_HEALTH_EVENTS.iter().map(ToString::to_string));
+    }
+    let mut receiver = client
+        .events_receiver_with_queue_group(event_types, cmd.events_queue_group.clone())
+        .await
+        .map_err(boxed_err_to_anyhow)
+        .context("Failed to get lattice event channel")?;
+
+    if cmd.strict_host {
+        ensure_host_still_present(client, &host).await?;
+    }
+
+    if pull_timeout.is_some() {
+        ensure_host_features(
+            client,
+            &host,
+            &[HostFeature::ProviderPullTimeout],
+            cmd.require_features,
+        )
+        .await?;
+    }
+
+    cmd.progress.report(
+        ProgressStep::WaitingForAck,
+        format!("Asking host {host} to start provider {}", cmd.provider_id),
+    );
+    let ack = cmd
+        .retry
+        .retry(|attempt| {
+            let (client, host, provider_ref, provider_id) =
+                (&client, &host, &provider_ref, &cmd.provider_id);
+            let (annotations, config) = (annotations.clone(), config.clone());
+            async move {
+                // A start request that timed out may have reached the host after all, so only send
+                // it again if the provider didn't start
+                if attempt > 0 && provider_on_host(client, host, provider_id).await {
+                    return Ok(None);
+                }
+                wait_for_ack(
+                    async {
+                        match pull_timeout {
+                            Some(pull_timeout) => {
+                                client
+                                    .start_provider_with_pull_timeout(
+                                        host,
+                                        provider_ref,
+                                        provider_id,
+                                        annotations,
+                                        config,
+                                        pull_timeout,
+                                    )
+                                    .await
+                            }
+                            None => {
+                                client
+                                    .start_provider(
+                                        host,
+                                        provider_ref,
+                                        provider_id,
+                                        annotations,
+                                        config,
+                                    )
+                                    .await
+                            }
+                        }
+                        .map_err(boxed_err_to_anyhow)
+                    },
+                    ack_timeout,
+                    host,
+                )
+                .await
+                .map(Some)
+            }
+        })
+        .await
+        .with_context(|| {
+            format!(
+                "Failed to start provider {} on host {:?}",
+                &cmd.provider_id, &host
+            )
+        })?;
+
+    if let Some(ack) = ack.filter(|ack| !ack.succeeded()) {
+        return Err(Failure::new(
+            FailureKind::AckRejected,
+            format!("Start provider ack not accepted: {}", ack.message()),
+        )
+        .into());
+    }
+
+    if cmd.skip_wait {
+        let text = format!("Start provider request received: {}", &provider_ref);
+        let output = with_signature_verification(
+            CommandOutput::new(
+                text.clone(),
+                HashMap::from([
+                    ("result".into(), text.into()),
+                    ("provider_ref".into(), provider_ref.into()),
+                    ("link_name".into(), link_name.into()),
+                    ("link_names".into(), cmd.link_names.clone().into()),
+                    ("host_id".into(), host.to_string().into()),
+                ]),
+            ),
+            prepared.verification.clone(),
+        );
+        return Ok(with_auction_fanout(output, fanout));
+    }
+
+    let watched = EventFilter {
+        host_id: Some(host.to_string()),
+        component_id: Some(cmd.provider_id.clone()),
+        ..Default::default()
+    };
+    let on_event = |event: &Event| {
+        if cmd.watch {
+            log_watched_event(event, &watched);
+        }
+    };
+    let target = ProviderStartTarget {
+        provider_ref: provider_ref.clone(),
+        provider_id: cmd.provider_id.clone(),
+        ref_match: if cmd.exact_ref_match {
+            ProviderRefMatch::Exact
+        } else {
+            ProviderRefMatch::Lenient
+        },
+    };
+    cmd.progress.report(
+        ProgressStep::WaitingForStartEvent,
+        format!(
+            "Waiting up to {} for provider {} to start on host {host}",
+            humantime::format_duration(Duration::from_millis(timeout_ms)),
+            cmd.provider_id
+        ),
+    );
+    let event = match cmd.verify_poll_ms {
+        Some(poll_ms) => {
+            let poll_inventory = || async {
+                let inventory = client
+                    .get_host_inventory(host.as_ref())
+                    .await
+                    .map_err(boxed_err_to_anyhow)?
+                    .into_data()
+                    .context("host returned no inventory")?;
+                Ok(inventory
+                    .providers()
+                    .iter()
+                    .find(|p| {
+                        p.image_ref()
+                            .is_some_and(|image_ref| target.matches(image_ref, Some(p.id())))
+                    })
+                    .map(|p| p.id().to_string()))
+            };
+            wait_for_provider_start_or_inventory(
+                &mut receiver,
+                Duration::from_millis(timeout_ms),
+                host.to_string(),
+                target.clone(),
+                Duration::from_millis(poll_ms),
+                on_event,
+                poll_inventory,
+            )
+            .await
+        }
+        None => {
+            watch_for_provider_start_event(
+                &mut receiver,
+                Duration::from_millis(timeout_ms),
+                host.to_string(),
+                target,
+                on_event,
+            )
+            .await
+        }
+    }
+    .with_context(|| {
+        format!(
+            "Timed out waiting for start event for provider {} on host {}",
+            &provider_ref, &host
+        )
+    })
+    .map_err(|err| ProviderStartError::timeout(&err))?;
+
+    let health = match (&event, cmd.wait_healthy) {
+        (FindEventOutcome::Success(info), true) => {
+            cmd.progress.report(
+                ProgressStep::WaitingForHealth,
+                format!(
+                    "Waiting for provider {} to become healthy",
+                    info.provider_id
+                ),
+            );
+            Some(
+                wait_for_provider_health(
+                    &mut receiver,
+                    Duration::from_millis(cmd.warmup_ms),
+                    Duration::from_millis(timeout_ms),
+                    host.to_string(),
+                    info.provider_id.clone(),
+                )
+                .await
+                .with_context(|| {
+                    format!(
+                        "Timed out waiting for provider {} on host {} to become healthy",
+                        &provider_ref, &host
+                    )
+                })?,
+            )
+        }
+        _ => None,
+    };
+
+    if cmd.watch && cmd.watch_timeout_ms > 0 {
+        watch_events(
+            &mut receiver,
+            Duration::from_millis(cmd.watch_timeout_ms),
+            |event| log_watched_event(event, &watched),
+        )
+        .await;
+    }
+
+    match event {
+        FindEventOutcome::Success(ProviderStartedInfo {
+            provider_id,
+            provider_ref,
+            host_id,
+        }) => {
+            if let Some(FindEventOutcome::Failure(err)) = health {
+                return Err(err).with_context(|| {
+                    format!("Provider [{provider_id}] started on host [{host_id}] but is unhealthy")
+                });
+            }
+            // The links are fetched once, only the inventories are polled for their targets
+            let awaited_links = if cmd.await_provider_links {
+                let links = client
+                    .get_links()
+                    .await
+                    .map_err(boxed_err_to_anyhow)?
+                    .into_data()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|link| link.source_id() == provider_id)
+                    .collect::<Vec<_>>();
+                if !links.is_empty() {
+                    let pending = || async {
+                        let inventories = get_all_inventories(client).await?;
+                        Ok(pending_provider_links(&provider_id, &links, &inventories))
+                    };
+                    cmd.progress.report(
+                        ProgressStep::WaitingForLinks,
+                        format!("Waiting for the links of provider {provider_id} to become live"),
+                    );
+                    wait_for_provider_links(
+                        Duration::from_millis(cmd.await_links_timeout_ms.unwrap_or(timeout_ms)),
+                        PROVIDER_LINKS_POLL_INTERVAL,
+                        pending,
+                    )
+                    .await
+                    .with_context(|| {
+                        format!(
+                            "Provider [{provider_id}] started on host [{host_id}] but its links are not live"
+                        )
+                    })?;
+                }
+                Some(links.len())
+            } else {
+                None
+            };
+            if cmd.max_concurrent_invocations.is_some() {
+                warn_if_annotation_dropped(
+                    client,
+                    &host_id,
+                    &provider_id,
+                    MAX_CONCURRENT_INVOCATIONS_ANNOTATION,
+                )
+                .await;
+            }
+            let text = format!(
+                "Provider [{}] (ref: [{}]) started on host [{}]",
+                &provider_id, &provider_ref, &host_id
+            );
+            let mut output = with_signature_verification(
+                CommandOutput::new(
+                    text.clone(),
+                    HashMap::from([
+                        ("result".into(), text.into()),
+                        ("provider_ref".into(), provider_ref.into()),
+                        ("provider_id".into(), provider_id.into()),
+                        ("link_names".into(), cmd.link_names.clone().into()),
+                        ("host_id".into(), host_id.into()),
+                        ("start_timeout_ms".into(), timeout_ms.into()),
+                    ]),
+                ),
+                prepared.verification.clone(),
+            );
+            output = with_auction_fanout(output, fanout);
+            if let Some(sized) = prepared.sized_timeout {
+                output.text.push_str(&format!(
+                    "\nStart timeout of {} was sized to the {} byte provider image",
+                    humantime::format_duration(sized.timeout),
+                    sized.image_size
+                ));
+                output
+                    .map
+                    .insert("image_size".into(), sized.image_size.into());
+            }
+            if let Some(awaited) = awaited_links {
+                if awaited == 0 {
+                    output.text.push_str(
+                        "\nProvider is not the source of any links, there were none to await",
+                    );
+                }
+                output.map.insert("awaited_links".into(), awaited.into());
+            }
+            if let Some((name, upload)) = config_upload {
+                output.map.insert("config_name".into(), name.clone().into());
+                output.map.insert(
+                    "config_cached".into(),
+                    (*upload == ConfigUpload::Cached).into(),
+                );
+            }
+            Ok(output)
+        }
+        FindEventOutcome::Failure(err) => {
+            Err(ProviderStartError::from_failure(&err)).with_context(|| {
+                format!(
+                    "Failed starting provider {} on host {}",
+                    &provider_ref, &host
+                )
+            })
+        }
+    }
+}
+
+/// Wait for a host to acknowledge a request, failing as soon as `timeout` passes without an ack
+/// rather than waiting out the longer timeout for the operation itself. The failure is a timeout,
+/// see [`crate::lib::backoff::is_timeout`]
+pub async fn wait_for_ack<T>(
+    ack: impl std::future::Future<Output = Result<T>>,
+    timeout: Duration,
+    host_id: &str,
+) -> Result<T> {
+    tokio::time::timeout(timeout, ack).await.map_err(|_| {
+        anyhow::Error::new(std::io::Error::new(
+            std::io::ErrorKind::TimedOut,
+            format!(
+                "Host [{host_id}] did not acknowledge the request within {}ms, it may be unresponsive",
+                timeout.as_millis()
+            ),
+        ))
+    })?
+}
+
+/// Whether the host's inventory lists the provider
+async fn provider_on_host(
+    client: &wasmcloud_control_interface::Client,
+    host_id: &str,
+    provider_id: &str,
+) -> bool {
+    client
+        .get_host_inventory(host_id)
+        .await
+        .ok()
+        .and_then(wasmcloud_control_interface::CtlResponse::into_data)
+        .is_some_and(|inventory| inventory.providers().iter().any(|p| p.id() == provider_id))
+}
+
+/// Warn when the host's inventory shows a started provider without an annotation we sent, which
+/// means the host (or provider) does not support the setting it carries
+async fn warn_if_annotation_dropped(
+    client: &wasmcloud_control_interface::Client,
+    host_id: &str,
+    provider_id: &str,
+    annotation: &str,
+) {
+    let supported = client
+        .get_host_inventory(host_id)
+        .await
+        .ok()
+        .and_then(wasmcloud_control_interface::CtlResponse::into_data)
+        .and_then(|inv| {
+            inv.providers()
+                .iter()
+                .find(|p| p.id() == provider_id)
+                .and_then(|p| p.annotations())
+                .map(|a| a.contains_key(annotation))
+        })
+        .unwrap_or(false);
+    if !supported {
+        warn!(
+            host_id,
+            provider_id,
+            "host did not record the `{annotation}` annotation, the setting may be ignored"
+        );
+    }
+}
+
+/// Provider health events, streamed with `--watch` and used to gate `--wait-healthy`
+const PROVIDER_HEALTH_EVENTS: [&str; 3] = [
+    "health_check_passed",
+    "health_check_failed",
+    "health_check_status",
+];
+
+/// Log a watched lattice event as JSON, if it's about the provider being started
+fn log_watched_event(event: &Event, filter: &EventFilter) {
+    if !filter.matches(event) {
+        return;
+    }
+    match serde_json::to_string(event) {
+        Ok(json) => info!(event = %json, "watched lattice event"),
+        Err(e) => warn!(?e, "failed to serialize watched event"),
+    }
+}
+
+/// Record which issuer satisfied the trust policy, if the provider signature was verified
+fn with_signature_verification(
+    mut output: CommandOutput,
+    verification: Option<SignatureVerification>,
+) -> CommandOutput {
+    if let Some(SignatureVerification { issuer, .. }) = verification {
+        output.text = format!("{} (signature verified, issuer [{issuer}])", output.text);
+        output.map.insert("verified_issuer".into(), issuer.into());
+    }
+    output
+}
+
+fn with_auction_fanout(mut output: CommandOutput, fanout: Option<AuctionFanout>) -> CommandOutput {
+    if let Some(AuctionFanout {
+        responders,
+        eligible,
+    }) = fanout
+    {
+        output.text = format!(
+            "{}\nAuction: {eligible} of {responders} responding host(s) eligible",
+            output.text
+        );
+        output
+            .map
+            .insert("auction_responders".into(), responders.into());
+        output
+            .map
+            .insert("auction_eligible".into(), eligible.into());
+    }
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use clap::Parser;
+
+    use super::*;
+
+    #[derive(Parser)]
+    struct Cmd {
+        #[clap(subcommand)]
+        command: StartCommand,
+    }
+
+    fn parse_provider(args: &[&str]) -> StartProviderCommand {
+        let cmd = Cmd::try_parse_from(
+            ["start", "provider"]
+                .iter()
+                .chain(args)
+                .chain(&["ghcr.io/provider:v1", "provider"]),
+        )
+        .expect("failed to parse start provider command");
+        match cmd.command {
+            StartCommand::Provider(cmd) => *cmd,
+            cmd => panic!("expected a start provider command, got {cmd:?}"),
+        }
+    }
+
+    #[test]
+    fn weighted_placement_follows_host_weights() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let candidates = vec![
+            "light".to_string(),
+            "medium".to_string(),
+            "heavy".to_string(),
+        ];
+        let labels = HashMap::from([
+            // No weight label, so it defaults to 1
+            ("light".to_string(), BTreeMap::new()),
+            (
+                "medium".to_string(),
+                BTreeMap::from([(PLACEMENT_WEIGHT_LABEL.to_string(), "3".to_string())]),
+            ),
+            (
+                "heavy".to_string(),
+                BTreeMap::from([(PLACEMENT_WEIGHT_LABEL.to_string(), "6".to_string())]),
+            ),
+        ]);
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut counts = HashMap::<String, usize>::new();
+        for _ in 0..10_000 {
+            let host = select_auction_host(&candidates, &labels, Placement::Weighted, &mut rng)
+                .expect("should select a host");
+            *counts.entry(host).or_default() += 1;
+        }
+        // Expected shares are 10%, 30% and 60%
+        for (host, expected) in [("light", 1_000), ("medium", 3_000), ("heavy", 6_000)] {
+            let count = counts[host];
+            assert!(
+                count.abs_diff(expected) < 300,
+                "host {host} was selected {count} times, expected about {expected}"
+            );
+        }
+
+        assert_eq!(
+            select_auction_host(&candidates, &labels, Placement::First, &mut rng),
+            Some("light".to_string())
+        );
+        assert_eq!(
+            select_auction_host(&[], &labels, Placement::Weighted, &mut rng),
+            None
+        );
+    }
+
+    #[test]
+    fn load_aware_placement_uses_host_inventories() {
+        let inventory = |host_id: &str, components: &[&str], providers: &[&str]| {
+            HostInventory::builder()
+                .host_id(host_id.into())
+                .friendly_name(format!("{host_id}-name"))
+                .version("1.0.0".into())
+                .uptime_human("t".into())
+                .uptime_seconds(100)
+                .components(
+                    components
+                        .iter()
+                        .map(|id| {
+                            wasmcloud_control_interface::ComponentDescription::builder()
+                                .id((*id).into())
+                                .image_ref(format!("ghcr.io/{id}:0.1.0"))
+                                .max_instances(1)
+                                .build()
+                                .expect("should build component")
+                        })
+                        .collect(),
+                )
+                .providers(
+                    providers
+                        .iter()
+                        .map(|id| {
+                            wasmcloud_control_interface::ProviderDescription::builder()
+                                .id(id)
+                                .build()
+                                .expect("should build provider")
+                        })
+                        .collect(),
+                )
+                .build()
+                .expect("should build inventory")
+        };
+        let inventories = [
+            inventory("busy", &["a", "b", "c"], &["kv"]),
+            inventory("quiet", &["hello"], &[]),
+            inventory("idle", &[], &["kv"]),
+        ];
+        let candidates = ["busy", "quiet", "idle"].map(String::from).to_vec();
+
+        assert_eq!(
+            select_by_load(&candidates, &inventories, Placement::LeastLoaded, "kv"),
+            Some("quiet".to_string())
+        );
+        // "idle" is as loaded as "quiet" but already runs the provider being started
+        assert_eq!(
+            select_by_load(&candidates, &inventories, Placement::Spread, "kv"),
+            Some("quiet".to_string())
+        );
+        assert_eq!(
+            select_by_load(&candidates, &inventories, Placement::Spread, "hello"),
+            Some("idle".to_string())
+        );
+        // Hosts without an inventory are a last resort
+        assert_eq!(
+            select_by_load(
+                &["gone".to_string(), "busy".to_string()],
+                &inventories,
+                Placement::LeastLoaded,
+                "kv"
+            ),
+            Some("busy".to_string())
+        );
+
+        let cmd = parse_provider(&["--selection-strategy", "least-loaded"]);
+        assert_eq!(cmd.placement, Placement::LeastLoaded);
+        assert!(cmd.placement.needs_inventory());
+    }
+
+    #[test]
+    fn provider_links_are_pending_until_targets_run() {
+        let provider_link = |target: &str| {
+            Link::builder()
+                .source_id("messaging-nats")
+                .target(target)
+                .name("default")
+                .wit_namespace("wasmcloud")
+                .wit_package("messaging")
+                .interfaces(vec!["handler".to_string()])
+                .build()
+                .expect("should build link")
+        };
+        let links = vec![
+            provider_link("subscriber"),
+            provider_link("audit"),
+            Link::builder()
+                .source_id("subscriber")
+                .target("messaging-nats")
+                .name("default")
+                .wit_namespace("wasmcloud")
+                .wit_package("messaging")
+                .interfaces(vec!["consumer".to_string()])
+                .build()
+                .expect("should build link"),
+        ];
+        let inventory = HostInventory::builder()
+            .host_id("host1".into())
+            .friendly_name("quiet-dawn".into())
+            .version("1.0.0".into())
+            .uptime_human("t".into())
+            .uptime_seconds(100)
+            .components(vec![
+                wasmcloud_control_interface::ComponentDescription::builder()
+                    .id("subscriber".into())
+                    .image_ref("ghcr.io/wasmcloud/subscriber:0.1.0".into())
+                    .max_instances(1)
+                    .annotations(BTreeMap::new())
+                    .build()
+                    .expect("should build component"),
+            ])
+            .build()
+            .expect("should build inventory");
+
+        assert_eq!(
+            pending_provider_links("messaging-nats", &links, &[inventory]),
+            vec!["default link to [audit] on wasmcloud:messaging"]
+        );
+        assert_eq!(
+            pending_provider_links("messaging-nats", &links, &[]).len(),
+            2
+        );
+        assert!(pending_provider_links("other", &links, &[]).is_empty());
+    }
+
+    #[test]
+    fn provider_is_colocated_with_running_component() {
+        let inventory = |host_id: &str, components: Vec<(&str, &str)>| {
+            HostInventory::builder()
+                .host_id(host_id.into())
+                .friendly_name(format!("{host_id}-name"))
+                .version("1.0.0".into())
+                .uptime_human("t".into())
+                .uptime_seconds(100)
+                .components(
+                    components
+                        .into_iter()
+                        .map(|(id, image_ref)| {
+                            wasmcloud_control_interface::ComponentDescription::builder()
+                                .id(id.into())
+                                .image_ref(image_ref.into())
+                                .max_instances(1)
+                                .annotations(BTreeMap::new())
+                                .build()
+                                .expect("should build component")
+                        })
+                        .collect(),
+                )
+                .build()
+                .expect("should build inventory")
+        };
+        let inventories = vec![
+            inventory("host-a", vec![]),
+            inventory("host-c", vec![("echo", "ghcr.io/wasmcloud/echo:0.1.0")]),
+            inventory(
+                "host-b",
+                vec![
+                    ("hello", "ghcr.io/wasmcloud/hello:0.1.0"),
+                    ("echo", "ghcr.io/wasmcloud/echo:0.1.0"),
+                ],
+            ),
+        ];
+
+        assert_eq!(
+            colocation_hosts("hello", &inventories).expect("should find host"),
+            vec!["host-b"]
+        );
+        assert_eq!(
+            colocation_hosts("ghcr.io/wasmcloud/echo:0.1.0", &inventories)
+                .expect("should find hosts"),
+            vec!["host-b", "host-c"]
+        );
+        let err = colocation_hosts("missing", &inventories)
+            .expect_err("component that isn't running should be rejected");
+        assert!(err
+            .to_string()
+            .contains("Component [missing] is not running on any host"));
+
+        let cmd = StartProviderCommand::try_parse_from([
+            "provider",
+            "ghcr.io/wasmcloud/http-server:0.23.2",
+            "http-server",
+            "--colocate-with",
+            "hello",
+        ])
+        .expect("should parse command");
+        assert_eq!(cmd.colocate_with.as_deref(), Some("hello"));
+        assert!(StartProviderCommand::try_parse_from([
+            "provider",
+            "ghcr.io/wasmcloud/http-server:0.23.2",
+            "http-server",
+            "--colocate-with",
+            "hello",
+            "--host-id",
+            "host-a",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn cordoned_hosts_are_skipped_by_auctions() {
+        let candidates = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let labels = HashMap::from([
+            (
+                "a".to_string(),
+                BTreeMap::from([(UNSCHEDULABLE_LABEL.to_string(), "true".to_string())]),
+            ),
+            (
+                "c".to_string(),
+                BTreeMap::from([(UNSCHEDULABLE_LABEL.to_string(), "false".to_string())]),
+            ),
+        ]);
+        assert_eq!(schedulable_hosts(&candidates, &labels), vec!["b", "c"]);
+        assert_eq!(
+            select_auction_host(
+                &schedulable_hosts(&candidates, &labels),
+                &labels,
+                Placement::First,
+                &mut rand::rng()
+            ),
+            Some("b".to_string())
+        );
+    }
+
+    #[test]
+    fn auction_responders_carry_the_labels_of_each_host() {
+        let acks = ["b", "a"]
+            .map(|host_id| {
+                ProviderAuctionAck::builder()
+                    .provider_ref("ghcr.io/kv:0.1.0".to_string())
+                    .provider_id("kv".to_string())
+                    .host_id(host_id.to_string())
+                    .labels(BTreeMap::from([(
+                        UNSCHEDULABLE_LABEL.to_string(),
+                        (host_id == "b").to_string(),
+                    )]))
+                    .build()
+                    .expect("should build ack")
+            })
+            .to_vec();
+        let responders = acks
+            .iter()
+            .map(|ack| (ack.host_id(), ack.labels()))
+            .collect::<AuctionResponders>();
+        assert_eq!(responders.host_ids, vec!["b", "a"]);
+        assert_eq!(responders.labels["b"][UNSCHEDULABLE_LABEL], "true");
+        let (eligible, _) = filter_auction_candidates(&responders.host_ids, &responders.labels);
+        assert_eq!(eligible, vec!["a"]);
+    }
+
+    #[test]
+    fn auction_fanout_counts_responders_and_eligible_hosts() {
+        let candidates = ["a", "b", "c", "d"].map(String::from).to_vec();
+        let cordoned = BTreeMap::from([(UNSCHEDULABLE_LABEL.to_string(), "true".to_string())]);
+        let labels = HashMap::from([
+            ("b".to_string(), cordoned.clone()),
+            ("d".to_string(), cordoned),
+        ]);
+
+        let (eligible, fanout) = filter_auction_candidates(&candidates, &labels);
+        assert_eq!(eligible, vec!["a", "c"]);
+        assert_eq!(
+            fanout,
+            AuctionFanout {
+                responders: 4,
+                eligible: 2
+            }
+        );
+
+        let output =
+            with_auction_fanout(CommandOutput::new("started", HashMap::new()), Some(fanout));
+        assert_eq!(output.map["auction_responders"], 4);
+        assert_eq!(output.map["auction_eligible"], 2);
+        assert!(output
+            .text
+            .ends_with("Auction: 2 of 4 responding host(s) eligible"));
+        let direct = with_auction_fanout(CommandOutput::new("started", HashMap::new()), None);
+        assert!(!direct.map.contains_key("auction_responders"));
+
+        let (eligible, fanout) = filter_auction_candidates(&[], &labels);
+        assert!(eligible.is_empty());
+        assert_eq!(fanout.responders, 0);
+    }
+
+    #[test]
+    fn strict_host_detects_host_leaving_after_auction() {
+        let host = |id: &str| ResolvedHost {
+            id: id.to_string(),
+            friendly_name: format!("{id}-name"),
+            labels: BTreeMap::new(),
+        };
+        let candidates = vec!["NA".to_string(), "NB".to_string()];
+        let selected = select_auction_host(
+            &candidates,
+            &HashMap::new(),
+            Placement::First,
+            &mut rand::rng(),
+        )
+        .expect("should select a host");
+
+        // Both hosts were still around right before the start
+        check_host_present(&selected, &[host("NA"), host("NB")])
+            .expect("host should still be present");
+
+        // The selected host left between the auction and the start
+        let err = check_host_present(&selected, &[host("NB")])
+            .expect_err("missing host should be an error");
+        assert!(err.to_string().contains("left the lattice"));
+
+        let cmd = parse_provider(&["--strict-host"]);
+        assert!(cmd.strict_host);
+    }
+
+    #[test]
+    fn established_links_are_read_back_from_the_lattice() {
+        let cmd = parse_provider(&[
+            "--link",
+            "echo=wasi:keyvalue/store,atomics",
+            "--link",
+            "other=wasi:keyvalue/store",
+        ]);
+        let requested = cmd
+            .links
+            .iter()
+            .map(|link| link.to_link(&cmd.provider_id, cmd.link_name()))
+            .collect::<Result<Vec<_>>>()
+            .expect("should build links");
+        let unrelated = "third=wasi:http/outgoing-handler"
+            .parse::<InlineLink>()
+            .and_then(|link| link.to_link("http-client", "default"))
+            .expect("should build link");
+        // The host only confirmed one of the requested links
+        let lattice = vec![requested[1].clone(), unrelated];
+
+        let established = established_links(&requested, lattice, &cmd.provider_id);
+        assert_eq!(established, vec![requested[1].clone()]);
+        assert_eq!(
+            serde_json::to_value(&established).expect("links should serialize")[0]["source_id"],
+            "other"
+        );
+    }
+
+    #[test]
+    fn links_are_established_under_each_link_name() {
+        let cmd = parse_provider(&[
+            "--link-name",
+            "primary",
+            "--link-name",
+            "replica",
+            "--link",
+            "echo=wasi:keyvalue/store",
+        ]);
+        validate_link_names(&cmd.link_names).expect("link names should be unique");
+        assert_eq!(cmd.link_name(), "primary");
+
+        let requested = cmd.inline_links().expect("should build links");
+        assert_eq!(
+            requested.iter().map(Link::name).collect::<Vec<_>>(),
+            vec!["primary", "replica"]
+        );
+        let established = established_links(&requested, requested.clone(), &cmd.provider_id);
+        assert_eq!(established.len(), 2);
+        assert!(established
+            .iter()
+            .all(|link| link.source_id() == "echo" && link.target() == "provider"));
+
+        assert_eq!(parse_provider(&[]).link_names, vec!["default"]);
+        let duplicated = parse_provider(&["-l", "primary", "-l", "primary"]);
+        assert!(validate_link_names(&duplicated.link_names).is_err());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_link_name_is_the_primary_link_name() {
+        let mut cmd = StartProviderCommand {
+            link_name: "legacy".to_string(),
+            ..parse_provider(&["-l", "default", "-l", "replica"])
+        };
+        cmd.take_deprecated_link_name();
+        assert_eq!(cmd.link_names, vec!["legacy", "replica"]);
+        assert!(cmd.link_name.is_empty());
+
+        let mut cmd = parse_provider(&["-l", "primary"]);
+        cmd.take_deprecated_link_name();
+        assert_eq!(cmd.link_names, vec!["primary"]);
+    }
+
+    #[test]
+    fn inline_links_parse() {
+        let cmd = parse_provider(&[
+            "--link",
+            "echo=wasi:keyvalue/store,atomics",
+            "--link",
+            "other=wasi:keyvalue/store",
+            "--link-name",
+            "cache",
+        ]);
+        assert_eq!(cmd.links.len(), 2);
+        let link = cmd.links[0]
+            .to_link(&cmd.provider_id, cmd.link_name())
+            .expect("should build link");
+        assert_eq!(link.source_id(), "echo");
+        assert_eq!(link.target(), "provider");
+        assert_eq!(link.name(), "cache");
+        assert_eq!(link.wit_namespace(), "wasi");
+        assert_eq!(link.wit_package(), "keyvalue");
+        assert_eq!(link.interfaces(), &vec!["store", "atomics"]);
+
+        for bad in [
+            "echo",
+            "echo=wasi:keyvalue",
+            "echo=keyvalue/store",
+            "=wasi:keyvalue/store",
+            "echo=wasi:keyvalue/",
+        ] {
+            assert!(
+                bad.parse::<InlineLink>().is_err(),
+                "[{bad}] should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn link_file_links_are_established() {
+        let dir = tempfile::tempdir().expect("should create temp dir");
+        let path = dir.path().join("links.yaml");
+        std::fs::write(
+            &path,
+            "- source_id: echo\n  wit_namespace: wasi\n  wit_package: keyvalue\n  interfaces: [store, atomics]\n",
+        )
+        .expect("should write link file");
+        let path_arg = path.to_string_lossy().to_string();
+        let cmd = parse_provider(&[
+            "--link-file",
+            &path_arg,
+            "--link",
+            "other=wasi:keyvalue/store",
+        ]);
+        assert!(cmd.has_inline_links());
+
+        let requested = cmd.inline_links().expect("should build links");
+        assert_eq!(requested.len(), 2);
+        assert_eq!(requested[1].source_id(), "echo");
+        assert_eq!(requested[1].interfaces(), &vec!["store", "atomics"]);
+        let established = established_links(&requested, requested.clone(), &cmd.provider_id);
+        assert!(established.iter().any(|link| link.source_id() == "echo"
+            && link.target() == "provider"
+            && link.interfaces() == &vec!["store", "atomics"]));
+
+        // Links from the file are checked against the `--link` ones for overlaps
+        let overlapping = parse_provider(&[
+            "--link-file",
+            &path_arg,
+            "--link",
+            "echo=wasi:keyvalue/atomics",
+        ]);
+        let err = overlapping
+            .inline_links()
+            .expect_err("overlapping links should be rejected");
+        assert!(format!("{err:#}").contains("on interface(s) atomics"));
+
+        std::fs::write(
+            &path,
+            "- source_id: echo\n  wit_namespace: wasi\n  wit_pakage: keyvalue\n  interfaces: [store]\n",
+        )
+        .expect("should write link file");
+        let err = load_link_file(&path).expect_err("unknown fields should be rejected");
+        assert!(
+            err.to_string().contains("3 |   wit_pakage: keyvalue"),
+            "{err}"
+        );
+
+        std::fs::write(
+            &path,
+            "- source_id: echo\n  wit_namespace: wasi\n  wit_package: keyvalue\n  interfaces: []\n",
+        )
+        .expect("should write link file");
+        assert!(load_link_file(&path).is_err());
+    }
+
+    #[test]
+    fn custom_host_selector_picks_the_host() {
+        let responses = ["host-a", "host-b", "host-c"]
+            .into_iter()
+            .map(|host_id| {
+                ProviderAuctionAck::builder()
+                    .host_id(host_id.to_string())
+                    .provider_ref("ghcr.io/provider:v1".to_string())
+                    .provider_id("provider".to_string())
+                    .build()
+                    .expect("should build auction ack")
+            })
+            .collect::<Vec<_>>();
+
+        let selector = HostSelector::new(|responses| {
+            responses
+                .iter()
+                .find(|ack| ack.host_id() == "host-b")
+                .map(|ack| ack.host_id().to_string())
+        });
+        assert_eq!(
+            selector.select(&responses).expect("should pick a host"),
+            "host-b"
+        );
+
+        // The selector can be threaded through the command used for the start
+        let cmd = StartProviderCommand {
+            host_selector: Some(selector),
+            ..parse_provider(&[])
+        };
+        assert_eq!(
+            cmd.host_selector
+                .expect("selector should be set")
+                .select(&responses)
+                .expect("should pick a host"),
+            "host-b"
+        );
+
+        assert!(HostSelector::new(|_| None).select(&responses).is_err());
+        assert!(HostSelector::new(|_| Some("host-z".to_string()))
+            .select(&responses)
+            .is_err());
+    }
+
+    #[test]
+    fn start_actor_is_an_alias_for_start_component() {
+        let cmd = Cmd::try_parse_from([
+            "start",
+            "actor",
+            "ghcr.io/wasmcloud/components/http-hello-world-rust:0.1.0",
+            "hello",
+            "--constraint",
+            "region=us-east",
+            "--skip-wait",
+        ])
+        .expect("start actor should parse");
+        let StartCommand::Component(cmd) = cmd.command else {
+            panic!("start actor should start a component");
+        };
+        assert_eq!(cmd.component_id, "hello");
+        assert_eq!(cmd.constraints, Some(vec!["region=us-east".to_string()]));
+        assert!(cmd.skip_wait);
+        assert_eq!(cmd.host_id, None, "the host should be picked by auction");
+    }
+
+    #[test]
+    fn batch_providers_are_collected_from_flags_and_file() {
+        assert!(!parse_provider(&[]).is_batch());
+
+        let dir = tempfile::tempdir().expect("should create temp dir");
+        let path = dir.path().join("providers.yaml");
+        std::fs::write(
+            &path,
+            "- provider_ref: ghcr.io/wasmcloud/keyvalue-redis:0.28.2\n  provider_id: kv\n",
+        )
+        .expect("should write batch file");
+        let path_arg = path.to_string_lossy().to_string();
+        let cmd = parse_provider(&[
+            "--and-provider",
+            "ghcr.io/wasmcloud/http-server:0.23.2=http-server",
+            "--batch-file",
+            &path_arg,
+        ]);
+        assert!(cmd.is_batch());
+        assert_eq!(
+            cmd.batch_providers()
+                .expect("should collect providers")
+                .into_iter()
+                .map(|p| (p.provider_ref, p.provider_id))
+                .collect::<Vec<_>>(),
+            vec![
+                ("ghcr.io/provider:v1".to_string(), "provider".to_string()),
+                (
+                    "ghcr.io/wasmcloud/http-server:0.23.2".to_string(),
+                    "http-server".to_string()
+                ),
+                (
+                    "ghcr.io/wasmcloud/keyvalue-redis:0.28.2".to_string(),
+                    "kv".to_string()
+                ),
+            ]
+        );
+
+        let cmd = parse_provider(&["--and-provider", "ghcr.io/other:v1=provider"]);
+        assert!(
+            cmd.batch_providers().is_err(),
+            "provider IDs must be unique"
+        );
+        assert!("ghcr.io/provider:v1".parse::<BatchProvider>().is_err());
+        assert!("=provider".parse::<BatchProvider>().is_err());
+    }
+
+    #[test]
+    fn batch_results_are_reported_together() {
+        let results = vec![
+            BatchStartResult {
+                provider_ref: "ghcr.io/wasmcloud/http-server:0.23.2".to_string(),
+                provider_id: "http-server".to_string(),
+                host_id: Some("NHOST".to_string()),
+                error: None,
+            },
+            BatchStartResult {
+                provider_ref: "ghcr.io/wasmcloud/keyvalue-redis:0.28.2".to_string(),
+                provider_id: "kv".to_string(),
+                host_id: None,
+                error: Some("No suitable hosts found".to_string()),
+            },
+        ];
+        let Err(err) = batch_start_output(&results, false) else {
+            panic!("a failed start should fail");
+        };
+        assert_eq!(FailureKind::of(&err), Some(FailureKind::TargetsFailed));
+        let failure = err
+            .downcast_ref::<Failure>()
+            .expect("error should be a failure");
+        assert!(failure
+            .message
+            .starts_with("Failed to start 1 of 2 provider(s)"));
+        assert!(failure.message.contains("No suitable hosts found"));
+        assert_eq!(failure.details["partial"], true);
+        assert_eq!(failure.details["providers"][0]["host_id"], "NHOST");
+        assert!(failure.details["providers"][1]["host_id"].is_null());
+
+        let output = batch_start_output(&results[..1], false).expect("should render output");
+        assert_eq!(output.map["partial"], false);
+        assert!(output.text.starts_with("Started 1 provider(s)"));
+
+        let Err(err) = batch_start_output(&results, true) else {
+            panic!("a dry run should fail too");
+        };
+        let failure = err
+            .downcast_ref::<Failure>()
+            .expect("error should be a failure");
+        assert!(failure
+            .message
+            .starts_with("Dry run, nothing was sent. 1 of 2 provider(s) could be started"));
+        assert!(failure.message.contains("would start"));
+        assert_eq!(failure.details["dry_run"], true);
+    }
+
+    #[test]
+    fn every_host_results_are_reported_together() {
+        assert!(!parse_provider(&[]).every_host);
+        let cmd = parse_provider(&["--every-host", "--constraint", "zone=east"]);
+        assert!(cmd.every_host);
+        for conflicting in [
+            &["--host-id", "NHOST"][..],
+            &["--canary", "10%"],
+            &["--and-provider", "ghcr.io/kv:0.1.0=kv"],
+            &["--fallback-ref", "ghcr.io/provider:v0"],
+        ] {
+            assert!(Cmd::try_parse_from(
+                ["start", "provider", "--every-host"]
+                    .iter()
+                    .chain(conflicting)
+                    .chain(&["ghcr.io/provider:v1", "provider"]),
+            )
+            .is_err());
+        }
+
+        let results = vec![
+            HostStartResult {
+                host_id: "NHOST1".to_string(),
+                friendly_name: "quiet-pond-1234".to_string(),
+                error: None,
+            },
+            HostStartResult {
+                host_id: "NHOST2".to_string(),
+                friendly_name: "loud-lake-5678".to_string(),
+                error: Some("Provider failed to start".to_string()),
+            },
+        ];
+        let Err(err) = every_host_start_output("provider", &results, false) else {
+            panic!("a failed start on one host should fail");
+        };
+        assert_eq!(FailureKind::of(&err), Some(FailureKind::TargetsFailed));
+        let failure = err
+            .downcast_ref::<Failure>()
+            .expect("error should be a failure");
+        assert!(failure
+            .message
+            .starts_with("Failed to start provider [provider] on 1 of 2 host(s)"));
+        assert!(failure.message.contains("quiet-pond-1234"));
+        assert_eq!(failure.details["partial"], true);
+        assert_eq!(failure.details["hosts"][1]["host_id"], "NHOST2");
+        assert!(failure.details["hosts"][0]["error"].is_null());
+
+        let output = every_host_start_output("provider", &results[..1], false)
+            .expect("should render output");
+        assert!(output
+            .text
+            .starts_with("Provider [provider] started on 1 host(s)"));
+        assert_eq!(output.map["partial"], false);
+    }
+
+    #[test]
+    fn dry_run_does_not_need_a_config_file() {
+        assert!(!parse_provider(&[]).dry_run);
+        assert!(parse_provider(&["--dry-run"]).dry_run);
+        assert!(Cmd::try_parse_from([
+            "start",
+            "provider",
+            "--dry-run",
+            "--canary",
+            "10%",
+            "ghcr.io/provider:v1",
+            "provider",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn pull_timeout_requires_pre_pull() {
+        let cmd = parse_provider(&[]);
+        assert!(!cmd.pre_pull);
+        assert_eq!(cmd.pull_timeout_ms, DEFAULT_START_PROVIDER_TIMEOUT_MS);
+        let cmd = parse_provider(&["--pre-pull", "--pull-timeout-ms", "5000"]);
+        assert!(cmd.pre_pull);
+        assert_eq!(cmd.pull_timeout_ms, 5000);
+        assert!(Cmd::try_parse_from([
+            "start",
+            "provider",
+            "--pull-timeout-ms",
+            "5000",
+            "ghcr.io/provider:v1",
+            "provider",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn exact_ref_match_is_opt_in() {
+        assert!(!parse_provider(&[]).exact_ref_match);
+        assert!(parse_provider(&["--exact-ref-match"]).exact_ref_match);
+    }
+
+    #[test]
+    fn events_queue_group_is_optional() {
+        assert_eq!(parse_provider(&[]).events_queue_group, None);
+        assert_eq!(
+            parse_provider(&["--events-queue-group", "automation"])
+                .events_queue_group
+                .as_deref(),
+            Some("automation")
+        );
+    }
+
+    #[test]
+    fn max_concurrent_invocations_is_plumbed_into_annotations() {
+        assert_eq!(provider_start_annotations(&parse_provider(&[])), None);
+
+        let cmd = parse_provider(&["--max-concurrent-invocations", "25"]);
+        assert_eq!(
+            provider_start_annotations(&cmd),
+            Some(BTreeMap::from([(
+                MAX_CONCURRENT_INVOCATIONS_ANNOTATION.to_string(),
+                "25".to_string()
+            )]))
+        );
+    }
+
+    #[test]
+    fn image_annotations_are_plumbed_into_annotations() {
+        let cmd = parse_provider(&[
+            "--image-annotation",
+            "cache.wasmcloud.dev/pinned=true",
+            "--image-annotation",
+            "org.example.tier=gold=1",
+            "--max-concurrent-invocations",
+            "2",
+        ]);
+        assert_eq!(
+            provider_start_annotations(&cmd),
+            Some(BTreeMap::from([
+                ("cache.wasmcloud.dev/pinned".to_string(), "true".to_string()),
+                ("org.example.tier".to_string(), "gold=1".to_string()),
+                (
+                    MAX_CONCURRENT_INVOCATIONS_ANNOTATION.to_string(),
+                    "2".to_string()
+                ),
+            ]))
+        );
+
+        for bad in [
+            "pinned",
+            "=true",
+            "-pinned=true",
+            "pinned.=true",
+            "pin ned=true",
+        ] {
+            assert!(
+                parse_image_annotation(bad).is_err(),
+                "[{bad}] should not parse"
+            );
+            assert!(
+                Cmd::try_parse_from([
+                    "start",
+                    "provider",
+                    "--image-annotation",
+                    bad,
+                    "ghcr.io/provider:v1",
+                    "provider"
+                ])
+                .is_err(),
+                "[{bad}] should be rejected by the CLI"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn missing_ack_fails_on_ack_timeout() {
+        let start = std::time::Instant::now();
+        let err = wait_for_ack(
+            std::future::pending::<Result<()>>(),
+            Duration::from_millis(50),
+            "host",
+        )
+        .await
+        .expect_err("a host that never acks should time out");
+        assert!(err.to_string().contains("did not acknowledge"));
+        assert!(
+            crate::lib::backoff::is_timeout(&err),
+            "a missed ack can be retried"
+        );
+        // Well short of the provider start timeout
+        assert!(start.elapsed() < Duration::from_millis(DEFAULT_START_PROVIDER_TIMEOUT_MS / 10));
+
+        let acked = wait_for_ack(async { Ok(42) }, Duration::from_millis(50), "host")
+            .await
+            .expect("an ack within the timeout should be returned");
+        assert_eq!(acked, 42);
+
+        let cmd = parse_provider(&["--ack-timeout-ms", "500"]);
+        assert_eq!(cmd.ack_timeout_ms, Some(500));
     }
 }
@@ -1,16 +1,26 @@
 use anyhow::{anyhow, bail, Context, Result};
 use clap::Parser;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use tokio::time::Duration;
-use tracing::error;
+use tracing::{error, warn};
 use wasmcloud_control_interface::HostInventory;
 
 use crate::lib::{
-    cli::{CliConnectionOpts, CommandOutput},
-    common::{boxed_err_to_anyhow, find_host_id, get_all_inventories, FindIdError, Match},
-    component::{scale_component, ComponentScaledInfo, ScaleComponentArgs},
+    cli::{
+        host::{describe_drain_results, drain_host_with_client, DrainResult},
+        input_vec_to_hashmap, CliConnectionOpts, CommandOutput,
+    },
+    common::{
+        boxed_err_to_anyhow, find_host_id, find_host_id_with, get_all_inventories, FindIdError,
+        HostMatchOpts, Match,
+    },
+    component::{
+        annotations_match, describe_annotations, scale_component, ComponentScaledInfo,
+        ScaleComponentArgs,
+    },
     config::{host_pid_file, WashConnectionOptions},
     context::default_timeout_ms,
+    failure::{Failure, FailureKind},
     id::ServerId,
     wait::{wait_for_provider_stop_event, FindEventOutcome, ProviderStoppedInfo},
 };
@@ -20,7 +30,7 @@ use super::validate_component_id;
 #[derive(Debug, Clone, Parser)]
 pub enum StopCommand {
     /// Stop a component running in a host
-    #[clap(name = "component")]
+    #[clap(name = "component", alias = "actor")]
     Component(StopComponentCommand),
 
     /// Stop a provider running in a host
@@ -45,15 +55,39 @@ pub struct StopComponentCommand {
     #[clap(long = "host-id")]
     pub host_id: Option<String>,
 
+    #[clap(flatten)]
+    pub host_match: HostMatchOpts,
+
     /// Unique component Id or a string to match on the prefix of the ID. If multiple components are matched, then an error
-    /// will be returned with a list of all matching options
-    #[clap(name = "component-id", value_parser = validate_component_id)]
+    /// will be returned with a list of all matching options. A component reference (e.g.
+    /// `ghcr.io/wasmcloud/http-hello-world:0.1.0`) may be given instead, in which case the
+    /// component running from it is stopped
+    #[clap(name = "component-id", value_parser = parse_id_or_reference)]
     pub component_id: String,
 
     /// By default, the command will wait until the component has been stopped.
     /// If this flag is passed, the command will return immediately after acknowledgement from the host, without waiting for the component to stp[].
     #[clap(long = "skip-wait")]
     pub skip_wait: bool,
+
+    /// Stop the component on every host running it, instead of failing when it runs on more than
+    /// one host. With a reference, every component running from it is stopped
+    #[clap(long = "all-hosts", conflicts_with = "host_id")]
+    pub all_hosts: bool,
+
+    /// Only stop the component where it runs with this annotation, in the form `key=value`, e.g.
+    /// to leave the instances managed by a wadm deployment alone. May be given more than once, in
+    /// which case every annotation must match
+    #[clap(
+        long = "match-annotation",
+        alias = "annotation",
+        name = "match_annotations"
+    )]
+    pub match_annotations: Vec<String>,
+
+    /// Report which host would receive the stop request without sending it
+    #[clap(long = "dry-run")]
+    pub dry_run: bool,
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -69,10 +103,15 @@ pub struct StopProviderCommand {
     #[clap(long = "host-id")]
     pub host_id: Option<String>,
 
+    #[clap(flatten)]
+    pub host_match: HostMatchOpts,
+
     /// Provider Id (e.g. the public key for the provider) or a string to match on the prefix of the
     /// ID, or friendly name, or call alias of the provider. If multiple providers are matched, then
-    /// an error will be returned with a list of all matching options
-    #[clap(name = "provider-id", value_parser = validate_component_id)]
+    /// an error will be returned with a list of all matching options. A provider reference (e.g.
+    /// `ghcr.io/wasmcloud/http-server:0.23.2`) may be given instead, in which case the provider
+    /// running from it is stopped
+    #[clap(name = "provider-id", value_parser = parse_id_or_reference)]
     pub provider_id: String,
 
     /// By default, the command will wait until the provider has been stopped. If this flag is
@@ -80,6 +119,25 @@ pub struct StopProviderCommand {
     /// waiting for the provider to stop.
     #[clap(long = "skip-wait")]
     pub skip_wait: bool,
+
+    /// Stop the provider on every host running it, instead of failing when it runs on more than
+    /// one host. With a reference, every provider running from it is stopped
+    #[clap(long = "all-hosts", conflicts_with = "host_id")]
+    pub all_hosts: bool,
+
+    /// Only stop the provider where it runs with this annotation, in the form `key=value`, e.g. to
+    /// leave the instances managed by a wadm deployment alone. May be given more than once, in
+    /// which case every annotation must match
+    #[clap(
+        long = "match-annotation",
+        alias = "annotation",
+        name = "match_annotations"
+    )]
+    pub match_annotations: Vec<String>,
+
+    /// Report which host would receive the stop request without sending it
+    #[clap(long = "dry-run")]
+    pub dry_run: bool,
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -93,21 +151,94 @@ pub struct StopHostCommand {
     #[clap(name = "host-id")]
     pub host_id: String,
 
+    #[clap(flatten)]
+    pub host_match: HostMatchOpts,
+
     /// The timeout in ms for how much time to give the host for graceful shutdown
     #[clap(
         long = "host-timeout",
         default_value_t = default_timeout_ms()
     )]
     pub host_shutdown_timeout: u64,
+
+    /// Check that the host is running and report the stop request without sending it
+    #[clap(long = "dry-run")]
+    pub dry_run: bool,
+
+    /// Drain the host before stopping it: label it unschedulable so wash auctions stop placing
+    /// work on it, then scale down its components (letting in-flight invocations finish) and stop
+    /// its providers, as `wash host drain --cordon` does. Components are not started elsewhere.
+    /// If draining fails, the host is still stopped and the error is reported
+    #[clap(long = "drain", conflicts_with = "dry_run")]
+    pub drain: bool,
+
+    /// How long to spend draining the host, in milliseconds. When the deadline is reached the host
+    /// is stopped with whatever is still running on it
+    #[clap(
+        long = "drain-timeout-ms",
+        default_value_t = DEFAULT_DRAIN_TIMEOUT_MS,
+        requires = "drain"
+    )]
+    pub drain_timeout_ms: u64,
 }
 
+/// Default deadline for draining a host with `wash stop host --drain`
+pub const DEFAULT_DRAIN_TIMEOUT_MS: u64 = 30_000;
+
 pub async fn handle_stop_provider(cmd: StopProviderCommand) -> Result<CommandOutput> {
     let timeout_ms = cmd.opts.timeout_ms;
     let wco: WashConnectionOptions = cmd.opts.try_into()?;
     let ctl_client = wco.into_ctl_client(None).await?;
+    let host_id = match cmd.host_id.as_deref() {
+        Some(host) => Some(
+            find_host_id_with(host, &ctl_client, &cmd.host_match)
+                .await?
+                .0
+                .to_string(),
+        ),
+        None => None,
+    };
+    let match_annotations = input_vec_to_hashmap(cmd.match_annotations.clone())?;
+    if cmd.all_hosts || is_reference(&cmd.provider_id) || !match_annotations.is_empty() {
+        let inventories = query_inventories(&ctl_client, host_id.as_deref()).await?;
+        let found = scope_to_annotations(
+            find_running(&inventories, RunningKind::Provider, &cmd.provider_id),
+            &match_annotations,
+            RunningKind::Provider,
+            &cmd.provider_id,
+        )?;
+        let instances = select_instances(
+            found,
+            RunningKind::Provider,
+            &cmd.provider_id,
+            cmd.all_hosts,
+        )?;
+        if cmd.dry_run {
+            return Ok(dry_run_instances_output(RunningKind::Provider, &instances));
+        }
+        let stops = instances.iter().map(|instance| {
+            stop_provider(
+                &ctl_client,
+                Some(&instance.host_id),
+                &instance.id,
+                cmd.skip_wait,
+                timeout_ms,
+            )
+        });
+        let outcomes = futures::future::join_all(stops).await;
+        return stopped_instances_output(RunningKind::Provider, instances, outcomes, cmd.skip_wait);
+    }
+    if cmd.dry_run {
+        let host_id = find_provider_host(&ctl_client, host_id.as_deref(), &cmd.provider_id).await?;
+        return Ok(CommandOutput::dry_run(
+            host_id.to_string(),
+            format!("stop provider [{}]", cmd.provider_id),
+            HashMap::from([("provider_id".into(), cmd.provider_id.into())]),
+        ));
+    }
     stop_provider(
         &ctl_client,
-        cmd.host_id.as_deref(),
+        host_id.as_deref(),
         &cmd.provider_id,
         cmd.skip_wait,
         timeout_ms,
@@ -125,7 +256,7 @@ pub async fn handle_stop_provider(cmd: StopProviderCommand) -> Result<CommandOut
         HashMap::from([
             ("result".into(), text.into()),
             ("provider_id".into(), cmd.provider_id.into()),
-            ("host_id".into(), cmd.host_id.into()),
+            ("host_id".into(), host_id.into()),
         ]),
     ))
 }
@@ -145,11 +276,7 @@ pub async fn stop_provider(
         .await
         .map_err(boxed_err_to_anyhow)?;
 
-    let host_id = if let Some(host_id) = host_id {
-        find_host_id(host_id, client).await?.0
-    } else {
-        find_host_with_provider(provider_id, client).await?
-    };
+    let host_id = find_provider_host(client, host_id, provider_id).await?;
 
     let ack = client
         .stop_provider(&host_id, provider_id)
@@ -157,7 +284,11 @@ pub async fn stop_provider(
         .map_err(boxed_err_to_anyhow)?;
 
     if !ack.succeeded() {
-        bail!("Operation failed: {}", ack.message());
+        return Err(Failure::new(
+            FailureKind::AckRejected,
+            format!("Operation failed: {}", ack.message()),
+        )
+        .into());
     }
     if skip_wait {
         return Ok(());
@@ -177,14 +308,76 @@ pub async fn stop_provider(
     }
 }
 
+/// The host to stop a provider on: the given host, or else the one host running the provider
+async fn find_provider_host(
+    client: &wasmcloud_control_interface::Client,
+    host_id: Option<&str>,
+    provider_id: &str,
+) -> Result<ServerId> {
+    Ok(if let Some(host_id) = host_id {
+        find_host_id(host_id, client).await?.0
+    } else {
+        find_host_with_provider(provider_id, client).await?
+    })
+}
+
 pub async fn handle_stop_component(cmd: StopComponentCommand) -> Result<CommandOutput> {
     let timeout_ms = cmd.opts.timeout_ms;
     let wco: WashConnectionOptions = cmd.opts.try_into()?;
     let client = wco.into_ctl_client(None).await?;
+    let host_id = match cmd.host_id.as_deref() {
+        Some(host) => Some(
+            find_host_id_with(host, &client, &cmd.host_match)
+                .await?
+                .0
+                .to_string(),
+        ),
+        None => None,
+    };
+
+    let match_annotations = input_vec_to_hashmap(cmd.match_annotations.clone())?;
+    if cmd.all_hosts || is_reference(&cmd.component_id) || !match_annotations.is_empty() {
+        let inventories = query_inventories(&client, host_id.as_deref()).await?;
+        let found = scope_to_annotations(
+            find_running(&inventories, RunningKind::Component, &cmd.component_id),
+            &match_annotations,
+            RunningKind::Component,
+            &cmd.component_id,
+        )?;
+        let instances = select_instances(
+            found,
+            RunningKind::Component,
+            &cmd.component_id,
+            cmd.all_hosts,
+        )?;
+        if cmd.dry_run {
+            return Ok(dry_run_instances_output(RunningKind::Component, &instances));
+        }
+        let stops = instances.iter().map(|instance| {
+            scale_component(ScaleComponentArgs {
+                client: &client,
+                host_id: &instance.host_id,
+                component_id: &instance.id,
+                component_ref: &instance.image_ref,
+                max_instances: 0,
+                annotations: None,
+                config: vec![],
+                skip_wait: cmd.skip_wait,
+                timeout_ms: Some(timeout_ms),
+            })
+        });
+        let outcomes = futures::future::join_all(stops).await;
+        return stopped_instances_output(
+            RunningKind::Component,
+            instances,
+            outcomes,
+            cmd.skip_wait,
+        );
+    }
 
     let component_id = cmd.component_id;
 
-    let inventory = if let Some(host_id) = cmd.host_id {
+    let inventory = if let Some(host_id) = host_id {
         client
             .get_host_inventory(&host_id)
             .await
@@ -220,6 +413,17 @@ pub async fn handle_stop_component(cmd: StopComponentCommand) -> Result<CommandO
         );
     };
 
+    if cmd.dry_run {
+        return Ok(CommandOutput::dry_run(
+            host_id,
+            format!("stop component [{component_id}] (ref: [{component_ref}])"),
+            HashMap::from([
+                ("component_id".into(), component_id.into()),
+                ("component_ref".into(), component_ref.into()),
+            ]),
+        ));
+    }
+
     let ComponentScaledInfo {
         component_id,
         host_id,
@@ -254,19 +458,358 @@ pub async fn handle_stop_component(cmd: StopComponentCommand) -> Result<CommandO
 }
 
 pub async fn stop_host(cmd: StopHostCommand) -> Result<CommandOutput> {
+    stop_host_with_progress(cmd, |_| {}).await
+}
+
+/// Same as [`stop_host`], calling `on_progress` as each component or provider is removed from the
+/// host when draining it with `--drain`
+pub async fn stop_host_with_progress(
+    cmd: StopHostCommand,
+    mut on_progress: impl FnMut(&DrainResult),
+) -> Result<CommandOutput> {
     let wco: WashConnectionOptions = cmd.opts.try_into()?;
     let client = wco.into_ctl_client(None).await?;
+    let host_id = find_host_id_with(&cmd.host_id, &client, &cmd.host_match)
+        .await?
+        .0
+        .to_string();
+
+    if cmd.dry_run {
+        return Ok(CommandOutput::dry_run(
+            host_id,
+            "stop host",
+            HashMap::from([(
+                "host_shutdown_timeout".into(),
+                cmd.host_shutdown_timeout.into(),
+            )]),
+        ));
+    }
 
-    let (_, hosts_remain) = stop_hosts(client, Some(&cmd.host_id), false).await?;
+    let mut drained = Vec::new();
+    let mut drain_complete = true;
+    // A drain that fails part way doesn't keep the host from being stopped, the error is reported
+    // with the outcome instead
+    let mut drain_error = None;
+    if cmd.drain {
+        let drain = drain_host_with_client(&client, &host_id, true, cmd.drain_timeout_ms, |r| {
+            on_progress(r);
+            drained.push(r.clone());
+            Ok(())
+        });
+        match tokio::time::timeout(Duration::from_millis(cmd.drain_timeout_ms), drain).await {
+            Ok(Ok(results)) => drain_complete = results.iter().all(|r| r.error.is_none()),
+            Ok(Err(e)) => {
+                warn!(?e, %host_id, "failed to drain host, stopping it anyway");
+                drain_complete = false;
+                drain_error = Some(format!("{e:#}"));
+            }
+            Err(_) => drain_complete = false,
+        }
+    }
+
+    let (_, hosts_remain) = stop_hosts(client, Some(&host_id), false).await?;
     let pid_file_exists = tokio::fs::try_exists(host_pid_file()?).await?;
     if !hosts_remain && pid_file_exists {
         tokio::fs::remove_file(host_pid_file()?).await?;
     }
 
-    Ok(CommandOutput::from_key_and_text(
-        "result",
-        format!("Host {} acknowledged stop request", cmd.host_id),
-    ))
+    let mut text = format!("Host {host_id} acknowledged stop request");
+    let mut map = HashMap::from([("host_id".into(), host_id.into())]);
+    if cmd.drain {
+        if drain_complete {
+            text.push_str(&format!(
+                " after draining {} component(s) and provider(s)",
+                drained.len()
+            ));
+        } else {
+            text.push_str(
+                " before it was fully drained, what was still running was stopped with the host",
+            );
+        }
+        if let Some(e) = &drain_error {
+            text.push_str(&format!("\nFailed to drain the host: {e}"));
+        }
+        text.push_str(&describe_drain_results(&drained));
+        map.insert("drained".into(), serde_json::to_value(&drained)?);
+        map.insert("drain_error".into(), drain_error.into());
+        map.insert("drain_complete".into(), drain_complete.into());
+        map.insert("partial".into(), (!drain_complete).into());
+    }
+    map.insert("result".into(), text.clone().into());
+    Ok(CommandOutput::new(text, map))
+}
+
+/// Accept a component or provider ID, or a reference to the image it runs from
+fn parse_id_or_reference(arg: &str) -> Result<String> {
+    if is_reference(arg) {
+        Ok(arg.to_string())
+    } else {
+        validate_component_id(arg)
+    }
+}
+
+/// Whether a stop argument is an image reference (an OCI reference or file path) rather than an ID,
+/// as IDs can't contain any of `/`, `:` or `.`
+fn is_reference(arg: &str) -> bool {
+    arg.contains(['/', ':', '.'])
+}
+
+/// Whether a stop is looking for components or providers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunningKind {
+    Component,
+    Provider,
+}
+
+impl RunningKind {
+    fn capitalized(self) -> &'static str {
+        match self {
+            Self::Component => "Component",
+            Self::Provider => "Provider",
+        }
+    }
+}
+
+impl std::fmt::Display for RunningKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Component => write!(f, "component"),
+            Self::Provider => write!(f, "provider"),
+        }
+    }
+}
+
+/// A component or provider running on a host
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct RunningInstance {
+    pub host_id: String,
+    pub id: String,
+    pub image_ref: String,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, String>,
+}
+
+/// Find the components or providers on the inventories that have the given ID or, failing that,
+/// run from the given image reference
+#[must_use]
+pub fn find_running(
+    inventories: &[HostInventory],
+    kind: RunningKind,
+    id_or_ref: &str,
+) -> Vec<RunningInstance> {
+    let running = inventories
+        .iter()
+        .flat_map(|inv| {
+            let instance =
+                |id: &str, image_ref: &str, annotations: Option<&BTreeMap<_, _>>| RunningInstance {
+                    host_id: inv.host_id().to_string(),
+                    id: id.to_string(),
+                    image_ref: image_ref.to_string(),
+                    annotations: annotations.cloned().unwrap_or_default(),
+                };
+            match kind {
+                RunningKind::Component => inv
+                    .components()
+                    .iter()
+                    .map(|c| instance(c.id(), c.image_ref(), c.annotations()))
+                    .collect::<Vec<_>>(),
+                RunningKind::Provider => inv
+                    .providers()
+                    .iter()
+                    .map(|p| instance(p.id(), p.image_ref().unwrap_or_default(), p.annotations()))
+                    .collect(),
+            }
+        })
+        .collect::<Vec<_>>();
+    let by_id = running
+        .iter()
+        .filter(|instance| instance.id == id_or_ref)
+        .cloned()
+        .collect::<Vec<_>>();
+    if by_id.is_empty() {
+        running
+            .into_iter()
+            .filter(|instance| instance.image_ref == id_or_ref)
+            .collect()
+    } else {
+        by_id
+    }
+}
+
+/// Keep only the instances running with every one of the `required` annotations, failing if some
+/// were found but none of them match
+pub fn scope_to_annotations(
+    mut instances: Vec<RunningInstance>,
+    required: &HashMap<String, String>,
+    kind: RunningKind,
+    id_or_ref: &str,
+) -> Result<Vec<RunningInstance>> {
+    if instances.is_empty() {
+        return Ok(instances);
+    }
+    instances.retain(|instance| annotations_match(Some(&instance.annotations), required));
+    if instances.is_empty() {
+        bail!(
+            "{} [{id_or_ref}] is running, but not with annotations {}",
+            kind.capitalized(),
+            describe_annotations(required)
+        );
+    }
+    Ok(instances)
+}
+
+/// Pick what to stop out of the instances found for an ID or reference. Unless `all_hosts` is set,
+/// the instances must all be the same component or provider on a single host
+pub fn select_instances(
+    instances: Vec<RunningInstance>,
+    kind: RunningKind,
+    id_or_ref: &str,
+    all_hosts: bool,
+) -> Result<Vec<RunningInstance>> {
+    if instances.is_empty() {
+        bail!("No host found running {kind} [{id_or_ref}]");
+    }
+    if all_hosts {
+        return Ok(instances);
+    }
+    let mut ids = instances.iter().map(|i| i.id.as_str()).collect::<Vec<_>>();
+    ids.sort_unstable();
+    ids.dedup();
+    if ids.len() > 1 {
+        bail!(
+            "Several {kind}s are running from [{id_or_ref}]: {}. Stop one of them by ID, or pass --all-hosts to stop all of them",
+            ids.join(", ")
+        );
+    }
+    if instances.len() > 1 {
+        bail!(
+            "{} [{}] is running on several hosts: {}. Pass --host-id to pick one, or --all-hosts to stop it on all of them",
+            kind.capitalized(),
+            ids[0],
+            instances
+                .iter()
+                .map(|i| i.host_id.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    Ok(instances)
+}
+
+/// The inventory of the given host, or else of every host
+async fn query_inventories(
+    client: &wasmcloud_control_interface::Client,
+    host_id: Option<&str>,
+) -> Result<Vec<HostInventory>> {
+    let Some(host_id) = host_id else {
+        return get_all_inventories(client).await;
+    };
+    Ok(client
+        .get_host_inventory(host_id)
+        .await
+        .map(wasmcloud_control_interface::CtlResponse::into_data)
+        .map_err(boxed_err_to_anyhow)?
+        .into_iter()
+        .collect())
+}
+
+/// Output of a `--dry-run` stop of the instances resolved from a reference or with `--all-hosts`
+fn dry_run_instances_output(kind: RunningKind, instances: &[RunningInstance]) -> CommandOutput {
+    if let [instance] = instances {
+        return CommandOutput::dry_run(
+            instance.host_id.clone(),
+            format!(
+                "stop {kind} [{}] (ref: [{}])",
+                instance.id, instance.image_ref
+            ),
+            HashMap::from([
+                (format!("{kind}_id"), instance.id.clone().into()),
+                (format!("{kind}_ref"), instance.image_ref.clone().into()),
+            ]),
+        );
+    }
+    let mut text = format!(
+        "Dry run, nothing was sent. {} {kind}(s) would be stopped:",
+        instances.len()
+    );
+    for instance in instances {
+        text.push_str(&format!(
+            "\n  [{}] (ref: [{}]) on host [{}]",
+            instance.id, instance.image_ref, instance.host_id
+        ));
+    }
+    CommandOutput::new(
+        text.clone(),
+        HashMap::from([
+            ("result".into(), text.into()),
+            ("dry_run".into(), true.into()),
+            ("stopped".into(), serde_json::json!(instances)),
+        ]),
+    )
+}
+
+/// Report the outcome of stopping each instance. If only some of them could be stopped, the report
+/// is returned as a [`FailureKind::TargetsFailed`] error. A single instance is reported as a plain
+/// stop
+fn stopped_instances_output<T>(
+    kind: RunningKind,
+    instances: Vec<RunningInstance>,
+    outcomes: Vec<Result<T>>,
+    skip_wait: bool,
+) -> Result<CommandOutput> {
+    let total = instances.len();
+    let mut stopped = Vec::with_capacity(total);
+    let mut failures = Vec::new();
+    for (instance, outcome) in instances.into_iter().zip(outcomes) {
+        match outcome {
+            Ok(_) => stopped.push(instance),
+            Err(e) => failures.push(format!(
+                "[{}] on host [{}]: {e:#}",
+                instance.id, instance.host_id
+            )),
+        }
+    }
+    if stopped.is_empty() {
+        bail!("Failed to stop {kind} [{}]", failures.join("; "));
+    }
+    let verb = if skip_wait {
+        "stop request received"
+    } else {
+        "stopped"
+    };
+    let mut text = if let ([instance], true) = (stopped.as_slice(), failures.is_empty()) {
+        format!(
+            "{} [{}] on host [{}] {verb}",
+            kind.capitalized(),
+            instance.id,
+            instance.host_id
+        )
+    } else {
+        format!("{} of {total} {kind}(s) {verb}", stopped.len())
+    };
+    for failure in &failures {
+        text.push_str(&format!("\n  failed to stop {failure}"));
+    }
+    let mut map = HashMap::from([
+        ("stopped".into(), serde_json::to_value(&stopped)?),
+        ("partial".into(), (!failures.is_empty()).into()),
+    ]);
+    if let [instance] = stopped.as_slice() {
+        map.insert(format!("{kind}_id"), instance.id.clone().into());
+        map.insert("host_id".into(), instance.host_id.clone().into());
+    }
+    let partial = !failures.is_empty();
+    if partial {
+        map.insert("errors".into(), failures.into());
+    }
+    map.insert("result".into(), text.clone().into());
+    let output = CommandOutput::new(text, map);
+    if partial {
+        return Err(
+            Failure::with_details(FailureKind::TargetsFailed, output.text, output.map).into(),
+        );
+    }
+    Ok(output)
 }
 
 async fn find_host_with_provider(
@@ -382,3 +925,186 @@ pub async fn stop_hosts(
         )
     }
 }
+
+#[cfg(test)]
+mod test {
+    use wasmcloud_control_interface::{ComponentDescription, ProviderDescription};
+
+    use super::*;
+
+    fn inventory(host_id: &str, components: &[(&str, &str)]) -> HostInventory {
+        HostInventory::builder()
+            .host_id(host_id.to_string())
+            .friendly_name(format!("{host_id}-name"))
+            .version("1.0.0".to_string())
+            .uptime_human("1m".to_string())
+            .uptime_seconds(60)
+            .components(
+                components
+                    .iter()
+                    .map(|(id, image_ref)| {
+                        ComponentDescription::builder()
+                            .id(id.to_string())
+                            .image_ref(image_ref.to_string())
+                            .build()
+                            .unwrap()
+                    })
+                    .collect(),
+            )
+            .providers(vec![ProviderDescription::builder()
+                .id("http-server")
+                .image_ref("ghcr.io/wasmcloud/http-server:0.23.2")
+                .build()
+                .unwrap()])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn instances_are_found_by_id_or_reference() {
+        const HELLO: &str = "ghcr.io/wasmcloud/hello:0.1.0";
+        let inventories = vec![
+            inventory(
+                "NHOST1",
+                &[("hello", HELLO), ("echo", "ghcr.io/echo:0.1.0")],
+            ),
+            inventory("NHOST2", &[("hello", HELLO), ("hello2", HELLO)]),
+        ];
+
+        assert!(is_reference(HELLO));
+        assert!(is_reference("file:///tmp/hello.wasm"));
+        assert!(!is_reference("hello"));
+        assert_eq!(parse_id_or_reference(HELLO).unwrap(), HELLO);
+        assert!(parse_id_or_reference("not an id").is_err());
+
+        let found = find_running(&inventories, RunningKind::Component, "echo");
+        let selected = select_instances(found, RunningKind::Component, "echo", false).unwrap();
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].host_id, "NHOST1");
+        assert_eq!(selected[0].image_ref, "ghcr.io/echo:0.1.0");
+
+        // The same ID on several hosts needs a host or --all-hosts
+        let found = find_running(&inventories, RunningKind::Component, "hello");
+        let err = select_instances(found.clone(), RunningKind::Component, "hello", false)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("NHOST1, NHOST2"), "{err}");
+        assert_eq!(
+            select_instances(found, RunningKind::Component, "hello", true)
+                .unwrap()
+                .len(),
+            2
+        );
+
+        // A reference run by several components is ambiguous
+        let found = find_running(&inventories, RunningKind::Component, HELLO);
+        assert_eq!(found.len(), 3);
+        let err = select_instances(found.clone(), RunningKind::Component, HELLO, false)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("hello, hello2"), "{err}");
+        assert_eq!(
+            select_instances(found, RunningKind::Component, HELLO, true)
+                .unwrap()
+                .len(),
+            3
+        );
+
+        let found = find_running(
+            &inventories,
+            RunningKind::Provider,
+            "ghcr.io/wasmcloud/http-server:0.23.2",
+        );
+        assert_eq!(found.len(), 2);
+        assert!(found.iter().all(|i| i.id == "http-server"));
+        assert!(select_instances(
+            find_running(&inventories, RunningKind::Provider, "ghcr.io/nope:0.1.0"),
+            RunningKind::Provider,
+            "ghcr.io/nope:0.1.0",
+            true
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn instances_are_scoped_to_annotations() {
+        let instance = |host_id: &str, deployment: &str| RunningInstance {
+            host_id: host_id.to_string(),
+            id: "hello".to_string(),
+            image_ref: "ghcr.io/hello:0.1.0".to_string(),
+            annotations: BTreeMap::from([("deployment".to_string(), deployment.to_string())]),
+        };
+        let found = vec![instance("NHOST1", "blue"), instance("NHOST2", "green")];
+        let required = HashMap::from([("deployment".to_string(), "green".to_string())]);
+
+        let scoped =
+            scope_to_annotations(found.clone(), &required, RunningKind::Component, "hello")
+                .unwrap();
+        assert_eq!(scoped, vec![instance("NHOST2", "green")]);
+        assert_eq!(
+            scope_to_annotations(
+                found.clone(),
+                &HashMap::new(),
+                RunningKind::Component,
+                "hello"
+            )
+            .unwrap(),
+            found
+        );
+
+        let required = HashMap::from([("deployment".to_string(), "red".to_string())]);
+        let err = scope_to_annotations(found, &required, RunningKind::Component, "hello")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("[deployment=red]"), "{err}");
+        assert!(
+            scope_to_annotations(Vec::new(), &required, RunningKind::Component, "hello")
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn partial_stops_are_reported() {
+        let instance = |host_id: &str| RunningInstance {
+            host_id: host_id.to_string(),
+            id: "hello".to_string(),
+            image_ref: "ghcr.io/hello:0.1.0".to_string(),
+            annotations: BTreeMap::new(),
+        };
+        let Err(output) = stopped_instances_output(
+            RunningKind::Component,
+            vec![instance("NHOST1"), instance("NHOST2")],
+            vec![Ok(()), Err(anyhow!("timed out"))],
+            false,
+        ) else {
+            panic!("a partial stop should fail");
+        };
+        assert_eq!(FailureKind::of(&output), Some(FailureKind::TargetsFailed));
+        let failure = output
+            .downcast_ref::<Failure>()
+            .expect("error should be a failure");
+        assert!(failure.message.starts_with("1 of 2 component(s) stopped"));
+        assert!(failure.message.contains("timed out"));
+        assert_eq!(failure.details["partial"], true);
+        assert_eq!(failure.details["host_id"], "NHOST1");
+
+        let output = stopped_instances_output(
+            RunningKind::Component,
+            vec![instance("NHOST1"), instance("NHOST2")],
+            vec![Ok(()), Ok(())],
+            false,
+        )
+        .expect("stopping every instance should succeed");
+        assert!(output.text.starts_with("2 of 2 component(s) stopped"));
+        assert_eq!(output.map["partial"], false);
+
+        assert!(stopped_instances_output(
+            RunningKind::Provider,
+            vec![instance("NHOST1")],
+            vec![Err::<(), _>(anyhow!("timed out"))],
+            false,
+        )
+        .is_err());
+    }
+}
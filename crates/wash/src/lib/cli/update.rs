@@ -6,6 +6,7 @@ use crate::lib::{
     common::{boxed_err_to_anyhow, get_all_inventories},
     component::update_component,
     config::WashConnectionOptions,
+    failure::{Failure, FailureKind},
 };
 
 use super::{validate_component_id, CliConnectionOpts, CommandOutput};
@@ -116,7 +117,11 @@ pub async fn handle_update_component(cmd: UpdateComponentCommand) -> Result<Comm
     let ack =
         update_component(&client, &host_id, &cmd.component_id, &cmd.new_component_ref).await?;
     if !ack.succeeded() {
-        bail!("Operation failed on host [{host_id}]: {}", ack.message());
+        return Err(Failure::new(
+            FailureKind::AckRejected,
+            format!("Operation failed on host [{host_id}]: {}", ack.message()),
+        )
+        .into());
     }
 
     let message = match ack.message().to_string() {
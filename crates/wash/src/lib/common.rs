@@ -1,15 +1,18 @@
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::{Debug, Display};
 use std::path::Path;
 use std::process::Stdio;
 use std::str::FromStr;
 
 use anyhow::{bail, Result};
+use clap::Args;
 use semver::Version;
+use serde::Serialize;
 use tokio::process::Command;
 
 use anyhow::Context;
 use tracing::error;
-use wasmcloud_control_interface::HostInventory;
+use wasmcloud_control_interface::{Host, HostInventory};
 
 use crate::lib::id::{ModuleId, ServerId, ServiceId};
 
@@ -256,15 +259,248 @@ pub async fn find_host_id(
     }
 }
 
+/// How [`find_host_id_with`] settles on a single host when a `--host-id` value matches several
+/// hosts by ID prefix or friendly name
+#[derive(Args, Debug, Clone, Default, PartialEq, Eq)]
+pub struct HostMatchOpts {
+    /// Only match a host whose whole ID or friendly name is the given host ID (ignoring case),
+    /// instead of a prefix of its ID or part of its friendly name
+    #[clap(long = "exact-host", id = "exact_host")]
+    pub exact: bool,
+
+    /// When the given host ID matches several hosts, use the one at this position (starting at 1)
+    /// of the matching hosts sorted by friendly name, as they are listed in the error
+    #[clap(long = "host-index", id = "host_index")]
+    pub index: Option<usize>,
+
+    /// When the given host ID matches several hosts, or several hosts respond to the auction of a
+    /// command that holds one, list them with their labels and ask which one to use. Requires a
+    /// terminal
+    #[clap(long = "interactive", id = "interactive")]
+    pub interactive: bool,
+}
+
+/// Like [`find_host_id`], settling on one host as described by `how` when several match
+pub async fn find_host_id_with(
+    value: &str,
+    ctl_client: &wasmcloud_control_interface::Client,
+    how: &HostMatchOpts,
+) -> Result<(ServerId, String), FindIdError> {
+    if let Ok(id) = ServerId::from_str(value) {
+        return Ok((id, String::new()));
+    }
+
+    let hosts = ctl_client
+        .get_hosts()
+        .await
+        .map_err(boxed_err_to_anyhow)
+        .context("unable to fetch hosts for lookup")?
+        .into_iter()
+        .filter_map(wasmcloud_control_interface::CtlResponse::into_data)
+        .collect::<Vec<_>>();
+    let matches = matching_hosts(value, &hosts, how.exact);
+
+    match (matches.as_slice(), how.index) {
+        ([], _) => Err(FindIdError::NoMatches),
+        (_, Some(index)) => {
+            let host = index
+                .checked_sub(1)
+                .and_then(|i| matches.get(i))
+                .with_context(|| {
+                    format!(
+                        "--host-index {index} is out of range, {} host(s) match [{value}]",
+                        matches.len()
+                    )
+                })?;
+            Ok(host_match(host)?)
+        }
+        ([host], None) => Ok(host_match(host)?),
+        (_, None) if how.interactive => {
+            let candidates = matches
+                .iter()
+                .map(|h| h.id().to_string())
+                .collect::<Vec<_>>();
+            let picked = pick_host(&candidates, &hosts)?;
+            let host = matches
+                .iter()
+                .find(|h| h.id() == picked)
+                .context("No host was picked")?;
+            Ok(host_match(host)?)
+        }
+        (_, None) => Err(FindIdError::Error(anyhow::anyhow!(
+            "{}",
+            describe_host_matches(value, &matches)
+        ))),
+    }
+}
+
+/// Explain that several hosts match a host lookup value, listing them with their position, ID
+/// and labels and how to settle on one
+#[must_use]
+pub fn describe_host_matches(value: &str, matches: &[&Host]) -> String {
+    let listed = matches
+        .iter()
+        .enumerate()
+        .map(|(i, host)| format!("  {}. {}", i + 1, describe_host_choice(host)))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "{} hosts match [{value}]:\n{listed}\nPass --host-index <N> to use one of them, \
+         --exact-host to only match a whole ID or friendly name, or --interactive to choose one \
+         interactively",
+        matches.len()
+    )
+}
+
+/// The hosts matching a host lookup value, sorted by friendly name and then ID so that the order
+/// (and so `--host-index`) is stable
+#[must_use]
+pub fn matching_hosts<'a>(value: &str, hosts: &'a [Host], exact: bool) -> Vec<&'a Host> {
+    // Case insensitive searching here to make things nicer
+    let value = value.to_lowercase();
+    let mut matches = hosts
+        .iter()
+        .filter(|h| {
+            let (id, name) = (h.id().to_lowercase(), h.friendly_name().to_lowercase());
+            if exact {
+                id == value || name == value
+            } else {
+                id.starts_with(&value) || name.contains(&value)
+            }
+        })
+        .collect::<Vec<_>>();
+    matches.sort_by(|a, b| (a.friendly_name(), a.id()).cmp(&(b.friendly_name(), b.id())));
+    matches
+}
+
+fn host_match(host: &Host) -> anyhow::Result<(ServerId, String)> {
+    let id = ServerId::from_str(host.id())
+        .map_err(|e| anyhow::anyhow!("host [{}] has an invalid ID: {e}", host.id()))?;
+    Ok((id, host.friendly_name().to_string()))
+}
+
+/// Describe a host as an entry of a host picker: its ID, friendly name, uptime and labels
+#[must_use]
+pub fn describe_host_choice(host: &Host) -> String {
+    let labels = host
+        .labels()
+        .iter()
+        .map(|(k, v)| format!("{k}={v}"))
+        .collect::<Vec<_>>()
+        .join(",");
+    format!(
+        "{}  {}  up {}  [{labels}]",
+        host.id(),
+        host.friendly_name(),
+        host.uptime_human().map_or_else(
+            || format!("{}s", host.uptime_seconds()),
+            ToString::to_string
+        ),
+    )
+}
+
+/// Ask which of the candidate hosts to use, listing each with the details found in `hosts`.
+/// Candidates missing from `hosts` are listed by ID only. Fails without a terminal to ask on
+pub fn pick_host(candidates: &[String], hosts: &[Host]) -> Result<String> {
+    use std::io::IsTerminal;
+
+    if !std::io::stdin().is_terminal() {
+        bail!("Picking a host interactively requires a terminal");
+    }
+    let choices = candidates
+        .iter()
+        .map(|id| {
+            hosts
+                .iter()
+                .find(|h| h.id() == id)
+                .map_or_else(|| id.clone(), describe_host_choice)
+        })
+        .collect();
+    let chosen = crate::lib::generate::interactive::prompt_for_choice(
+        &crate::lib::generate::project_variables::StringEntry {
+            default: None,
+            choices: Some(choices),
+            regex: None,
+        },
+        "Several hosts are available, which one should be used?",
+    )?;
+    candidates
+        .get(chosen)
+        .cloned()
+        .context("No host was picked")
+}
+
+/// A host in the lattice along with the subset of its labels that were requested
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResolvedHost {
+    pub id: String,
+    pub friendly_name: String,
+    pub labels: BTreeMap<String, String>,
+}
+
+/// Filtering and projection applied when enumerating hosts with [`list_hosts`]
+#[derive(Debug, Clone, Default)]
+pub struct HostQuery {
+    /// Only hosts that carry every one of these labels (with the same value) are returned
+    pub label_filter: HashMap<String, String>,
+    /// The labels to include for each returned host. `None` includes all labels
+    pub label_projection: Option<Vec<String>>,
+}
+
+/// Enumerate all hosts in the lattice, keeping only those matching the query's label filter and
+/// projecting their labels down to the requested set
+pub async fn list_hosts(
+    client: &wasmcloud_control_interface::Client,
+    query: &HostQuery,
+) -> anyhow::Result<Vec<ResolvedHost>> {
+    let hosts = client
+        .get_hosts()
+        .await
+        .map_err(boxed_err_to_anyhow)
+        .context("unable to fetch hosts")?
+        .into_iter()
+        .filter_map(wasmcloud_control_interface::CtlResponse::into_data);
+    Ok(resolve_hosts(hosts, query))
+}
+
+/// Apply a [`HostQuery`] to an already fetched set of hosts
+pub fn resolve_hosts(
+    hosts: impl IntoIterator<Item = Host>,
+    query: &HostQuery,
+) -> Vec<ResolvedHost> {
+    hosts
+        .into_iter()
+        .filter(|host| {
+            query
+                .label_filter
+                .iter()
+                .all(|(k, v)| host.labels().get(k) == Some(v))
+        })
+        .map(|host| ResolvedHost {
+            id: host.id().to_string(),
+            friendly_name: host.friendly_name().to_string(),
+            labels: host
+                .labels()
+                .iter()
+                .filter(|(k, _)| {
+                    query
+                        .label_projection
+                        .as_ref()
+                        .is_none_or(|keep| keep.contains(k))
+                })
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+        })
+        .collect()
+}
+
 pub async fn get_all_inventories(
     client: &wasmcloud_control_interface::Client,
 ) -> anyhow::Result<Vec<HostInventory>> {
-    let hosts = client.get_hosts().await.map_err(boxed_err_to_anyhow)?;
+    let hosts = list_hosts(client, &HostQuery::default()).await?;
     let host_ids = match hosts.len() {
         0 => return Ok(Vec::with_capacity(0)),
-        _ => hosts
-            .into_iter()
-            .filter_map(|h| h.into_data().map(|h| h.id().to_string())),
+        _ => hosts.into_iter().map(|h| h.id),
     };
 
     let futs =
@@ -436,3 +672,118 @@ pub async fn clone_git_repo(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn host(id: &str, labels: &[(&str, &str)]) -> Host {
+        Host::builder()
+            .id(id.to_string())
+            .friendly_name(format!("{id}-friendly"))
+            .lattice("default".into())
+            .uptime_seconds(100)
+            .labels(
+                labels
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            )
+            .build()
+            .expect("failed to build host")
+    }
+
+    fn inventory() -> Vec<Host> {
+        vec![
+            host(
+                "NA",
+                &[("zone", "east"), ("arch", "x86_64"), ("gpu", "true")],
+            ),
+            host("NB", &[("zone", "west"), ("arch", "x86_64")]),
+            host("NC", &[("zone", "east"), ("arch", "aarch64")]),
+        ]
+    }
+
+    #[test]
+    fn hosts_are_matched_by_id_prefix_or_friendly_name() {
+        let hosts = vec![
+            host("NC", &[]),
+            host("NB", &[("zone", "west")]),
+            host("NBB", &[]),
+        ];
+        let ids = |matches: Vec<&Host>| {
+            matches
+                .into_iter()
+                .map(|h| h.id().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids(matching_hosts("nb", &hosts, false)), ["NB", "NBB"]);
+        assert_eq!(
+            ids(matching_hosts("friendly", &hosts, false)),
+            ["NB", "NBB", "NC"]
+        );
+        assert_eq!(ids(matching_hosts("nb", &hosts, true)), ["NB"]);
+        assert_eq!(ids(matching_hosts("NB-FRIENDLY", &hosts, true)), ["NB"]);
+        assert!(matching_hosts("friendly", &hosts, true).is_empty());
+
+        let described = describe_host_matches("nb", &matching_hosts("nb", &hosts, false));
+        assert!(described.starts_with("2 hosts match [nb]"));
+        assert!(described.contains("  1. NB  NB-friendly"));
+        assert!(described.contains("[zone=west]"));
+        assert!(described.contains("  2. NBB  NBB-friendly"));
+        assert!(described.contains("--host-index"));
+    }
+
+    #[test]
+    fn list_hosts_filters_and_projects_labels() {
+        let all = resolve_hosts(inventory(), &HostQuery::default());
+        assert_eq!(all.len(), 3);
+        assert_eq!(
+            all[0].labels.len(),
+            3,
+            "no projection should keep all labels"
+        );
+
+        let east = resolve_hosts(
+            inventory(),
+            &HostQuery {
+                label_filter: HashMap::from([("zone".to_string(), "east".to_string())]),
+                label_projection: Some(vec!["arch".to_string()]),
+            },
+        );
+        assert_eq!(
+            east,
+            vec![
+                ResolvedHost {
+                    id: "NA".to_string(),
+                    friendly_name: "NA-friendly".to_string(),
+                    labels: BTreeMap::from([("arch".to_string(), "x86_64".to_string())]),
+                },
+                ResolvedHost {
+                    id: "NC".to_string(),
+                    friendly_name: "NC-friendly".to_string(),
+                    labels: BTreeMap::from([("arch".to_string(), "aarch64".to_string())]),
+                },
+            ]
+        );
+
+        let none = resolve_hosts(
+            inventory(),
+            &HostQuery {
+                label_filter: HashMap::from([
+                    ("zone".to_string(), "west".to_string()),
+                    ("gpu".to_string(), "true".to_string()),
+                ]),
+                label_projection: None,
+            },
+        );
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn host_choices_show_name_uptime_and_labels() {
+        let choice = describe_host_choice(&host("NA", &[("zone", "east")]));
+        assert_eq!(choice, "NA  NA-friendly  up 100s  [zone=east]");
+    }
+}
@@ -0,0 +1,144 @@
+//! Detection of control interface fields that a host is too old to act on. Older hosts ignore
+//! fields they don't know about, so without this check a request can appear to succeed while
+//! silently dropping the behavior the field was meant to enable. Hosts advertise the fields they
+//! act on in the `features` of their inventory, which older hosts don't list at all.
+
+use std::fmt::Display;
+
+use anyhow::{bail, Context, Result};
+use tracing::warn;
+use wasmcloud_control_interface::{
+    Client as CtlClient, HostInventory, HOST_FEATURE_PROVIDER_PULL_TIMEOUT,
+};
+
+use crate::lib::common::boxed_err_to_anyhow;
+
+/// A field wash may send in a control interface request that only newer hosts act on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostFeature {
+    /// `pull_timeout_ms` on provider start requests, sent with `--pre-pull`
+    ProviderPullTimeout,
+}
+
+impl HostFeature {
+    /// The name hosts advertise the feature under
+    #[must_use]
+    pub const fn advertised_as(self) -> &'static str {
+        match self {
+            Self::ProviderPullTimeout => HOST_FEATURE_PROVIDER_PULL_TIMEOUT,
+        }
+    }
+
+    /// The request field the feature is about
+    #[must_use]
+    pub const fn field(self) -> &'static str {
+        match self {
+            Self::ProviderPullTimeout => "pull_timeout_ms",
+        }
+    }
+}
+
+impl Display for HostFeature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.field())
+    }
+}
+
+/// Return the features from `used` that the host `inventory` was taken from does not act on
+#[must_use]
+pub fn unsupported_features(inventory: &HostInventory, used: &[HostFeature]) -> Vec<HostFeature> {
+    used.iter()
+        .copied()
+        .filter(|feature| !inventory.supports(feature.advertised_as()))
+        .collect()
+}
+
+/// Check the features used by a request against the host it is sent to. Unsupported features are
+/// logged as warnings, or returned as an error if `require` is set
+pub fn check_host_features(
+    inventory: &HostInventory,
+    used: &[HostFeature],
+    require: bool,
+) -> Result<()> {
+    let missing = unsupported_features(inventory, used);
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let fields = missing
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    let (host_id, version) = (inventory.host_id(), inventory.version());
+    if require {
+        bail!("Host [{host_id}] (version {version}) does not support: {fields}");
+    }
+    warn!(
+        host_id,
+        version, "host will ignore fields it does not support: {fields}"
+    );
+    Ok(())
+}
+
+/// Fetch the inventory of a host and check the features used by a request against it. See
+/// [`check_host_features`]
+pub async fn ensure_host_features(
+    client: &CtlClient,
+    host_id: &str,
+    used: &[HostFeature],
+    require: bool,
+) -> Result<()> {
+    if used.is_empty() {
+        return Ok(());
+    }
+    let inventory = client
+        .get_host_inventory(host_id)
+        .await
+        .map_err(boxed_err_to_anyhow)?
+        .into_data()
+        .with_context(|| format!("No inventory returned for host [{host_id}]"))?;
+    check_host_features(&inventory, used, require)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn older_host_is_reported_as_missing_features() {
+        // An older host doesn't send `features` at all
+        let older: HostInventory = serde_json::from_value(serde_json::json!({
+            "components": [],
+            "providers": [],
+            "host_id": "NOLD",
+            "version": "1.8.0",
+        }))
+        .expect("failed to parse inventory");
+        let used = [HostFeature::ProviderPullTimeout];
+
+        assert_eq!(
+            unsupported_features(&older, &used),
+            vec![HostFeature::ProviderPullTimeout]
+        );
+        check_host_features(&older, &used, false).expect("missing features should only warn");
+        let err = check_host_features(&older, &used, true)
+            .expect_err("missing features should be an error when required");
+        assert_eq!(
+            err.to_string(),
+            "Host [NOLD] (version 1.8.0) does not support: pull_timeout_ms"
+        );
+        check_host_features(&older, &[], true).expect("nothing is required");
+
+        let newer = HostInventory::builder()
+            .host_id("NNEW".into())
+            .friendly_name("new".into())
+            .version("1.9.0".into())
+            .uptime_human("1s".into())
+            .uptime_seconds(1)
+            .features(vec![HOST_FEATURE_PROVIDER_PULL_TIMEOUT.into()])
+            .build()
+            .unwrap();
+        assert!(unsupported_features(&newer, &used).is_empty());
+        check_host_features(&newer, &used, true).expect("newer host supports the feature");
+    }
+}
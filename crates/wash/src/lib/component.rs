@@ -1,12 +1,20 @@
 use std::collections::{BTreeMap, HashMap};
 
 use anyhow::{bail, Context, Result};
+use cloudevents::Event;
+use serde::{Deserialize, Serialize};
 use tokio::time::Duration;
-use wasmcloud_control_interface::{Client as CtlClient, CtlResponse};
+use tracing::debug;
+use wasmcloud_control_interface::{Client as CtlClient, CtlResponse, HostInventory};
 
+use crate::lib::cli::sanitize_component_id;
 use crate::lib::common::boxed_err_to_anyhow;
 use crate::lib::config::DEFAULT_START_COMPONENT_TIMEOUT_MS;
-use crate::lib::wait::{wait_for_component_scaled_event, FindEventOutcome};
+use crate::lib::failure::{Failure, FailureKind};
+use crate::lib::wait::{
+    last_component_scale, wait_for_component_scaled_event, wait_for_component_stop_event,
+    FindEventOutcome, ObservedComponentScale,
+};
 
 /// Information related to a component scale
 pub struct ComponentScaledInfo {
@@ -45,7 +53,53 @@ pub struct ScaleComponentArgs<'a> {
     pub timeout_ms: Option<u64>,
 }
 
+/// How many times a scale is sent again while the host is still processing an earlier scale of
+/// the same component
+const ALREADY_SCALING_RETRIES: u32 = 5;
+/// Delay before sending a scale again while the host is still processing an earlier one
+const ALREADY_SCALING_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Whether a rejected scale ack only means the host is still processing an earlier scale of the
+/// component, which settles on its own
+fn is_already_scaling(message: &str) -> bool {
+    message.to_lowercase().contains("already scaling")
+}
+
+/// Send a scale request, sending it again (a bounded number of times) if the host rejects it
+/// because it is already scaling the component. Any other rejection fails immediately
+async fn send_scale_with_retry<F, Fut>(delay: Duration, mut send: F) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<CtlResponse<()>>>,
+{
+    let mut retries = 0;
+    loop {
+        let ack = send().await?;
+        if ack.succeeded() {
+            return Ok(());
+        }
+        if !is_already_scaling(ack.message()) || retries >= ALREADY_SCALING_RETRIES {
+            return Err(Failure::new(
+                FailureKind::AckRejected,
+                format!("Operation failed: {}", ack.message()),
+            )
+            .into());
+        }
+        debug!(
+            message = ack.message(),
+            "host is already scaling component, retrying"
+        );
+        tokio::time::sleep(delay).await;
+        retries += 1;
+    }
+}
+
 /// Scale a Wasmcloud component on a given host
+///
+/// Scaling to zero stops the component. The stop keeps the annotations the component runs with
+/// (see [`scale_to_zero_annotations`]) and waits for the host to report the component scaled to
+/// zero rather than for any scale of its reference, so scaling up, down and off can share this
+/// one call
 pub async fn scale_component(
     ScaleComponentArgs {
         client,
@@ -72,21 +126,39 @@ pub async fn scale_component(
         .map_err(boxed_err_to_anyhow)
         .context("Failed to get lattice event channel")?;
 
-    let ack = client
-        .scale_component(
-            host_id,
-            component_ref,
+    let annotations = if max_instances == 0 {
+        let inventory = match client.get_host_inventory(host_id).await {
+            Ok(inventory) => inventory.into_data(),
+            Err(e) => {
+                debug!(
+                    ?e,
+                    "failed to get host inventory, stopping without its annotations"
+                );
+                None
+            }
+        };
+        Some(scale_to_zero_annotations(
+            inventory.as_ref(),
             component_id,
-            max_instances,
-            annotations.map(BTreeMap::from_iter),
-            config,
-        )
-        .await
-        .map_err(boxed_err_to_anyhow)?;
-
-    if !ack.succeeded() {
-        bail!("Operation failed: {}", ack.message());
-    }
+            annotations,
+        ))
+    } else {
+        annotations.map(BTreeMap::from_iter)
+    };
+    send_scale_with_retry(ALREADY_SCALING_RETRY_DELAY, || async {
+        client
+            .scale_component(
+                host_id,
+                component_ref,
+                component_id,
+                max_instances,
+                annotations.clone(),
+                config.clone(),
+            )
+            .await
+            .map_err(boxed_err_to_anyhow)
+    })
+    .await?;
 
     // If skip_wait is specified, return incomplete information immediately
     if skip_wait {
@@ -97,6 +169,31 @@ pub async fn scale_component(
         });
     }
 
+    if max_instances == 0 {
+        let event = wait_for_component_stop_event(
+            &mut receiver,
+            Duration::from_millis(timeout_ms),
+            host_id.to_string(),
+            component_id.to_string(),
+        )
+        .await
+        .with_context(|| {
+            format!(
+                "Timed out waiting for component [{component_id}] to scale to zero on host [{host_id}]"
+            )
+        })?;
+        return match event {
+            FindEventOutcome::Success(info) => Ok(ComponentScaledInfo {
+                host_id: info.host_id,
+                component_ref: component_ref.into(),
+                component_id: info.component_id,
+            }),
+            FindEventOutcome::Failure(err) => Err(err).with_context(|| {
+                format!("Failed to scale component [{component_id}] to zero on host [{host_id}]")
+            }),
+        };
+    }
+
     // Wait for the component to start
     let event = wait_for_component_scaled_event(
         &mut receiver,
@@ -119,6 +216,426 @@ pub async fn scale_component(
     }
 }
 
+/// Annotations to send when scaling a component to zero, which stops it: the annotations it runs
+/// with on the host, overridden by the given ones. Keeping them on the stop lets whoever manages the
+/// component, e.g. an owner set with `--owner`, recognize its scaled to zero event
+#[must_use]
+pub fn scale_to_zero_annotations(
+    inventory: Option<&HostInventory>,
+    component_id: &str,
+    annotations: Option<HashMap<String, String>>,
+) -> BTreeMap<String, String> {
+    let mut merged = inventory
+        .and_then(|inventory| {
+            inventory
+                .components()
+                .iter()
+                .find(|c| c.id() == component_id)
+        })
+        .and_then(|component| component.annotations().cloned())
+        .unwrap_or_default();
+    merged.extend(annotations.unwrap_or_default());
+    merged
+}
+
+/// Desired component instance counts, keyed by component reference and then by host (either a host
+/// ID or a friendly name)
+pub type DesiredComponentCounts = BTreeMap<String, BTreeMap<String, u32>>;
+
+/// A single scale operation required to converge a host to its desired component count
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ComponentScaleAction {
+    pub host_id: String,
+    pub component_id: String,
+    pub component_ref: String,
+    /// The number of instances currently running, `0` if the component isn't running on the host
+    pub current: u32,
+    pub desired: u32,
+}
+
+/// Compare the desired component counts against the given host inventories and compute the scale
+/// operations needed to converge. Components that are already at their desired count produce no
+/// action, and components that aren't mentioned in `desired` are left untouched.
+///
+/// Components that aren't running yet are given an ID derived from their reference.
+pub fn plan_component_scale(
+    desired: &DesiredComponentCounts,
+    inventories: &[HostInventory],
+) -> Result<Vec<ComponentScaleAction>> {
+    let mut actions = Vec::new();
+    for (component_ref, hosts) in desired {
+        for (host, count) in hosts {
+            let inventory = inventories
+                .iter()
+                .find(|inv| inv.host_id() == host)
+                .or_else(|| inventories.iter().find(|inv| inv.friendly_name() == host))
+                .with_context(|| format!("No host found matching [{host}]"))?;
+            let running = inventory
+                .components()
+                .iter()
+                .find(|c| c.image_ref() == component_ref);
+            let current = running.map(|c| c.max_instances()).unwrap_or_default();
+            if current == *count {
+                continue;
+            }
+            actions.push(ComponentScaleAction {
+                host_id: inventory.host_id().to_string(),
+                component_id: running
+                    .map(|c| c.id().to_string())
+                    .unwrap_or_else(|| sanitize_component_id(component_ref)),
+                component_ref: component_ref.clone(),
+                current,
+                desired: *count,
+            });
+        }
+    }
+    Ok(actions)
+}
+
+/// An entry of a scale manifest, scaling a component on the hosts picked by its host selector
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScaleManifestEntry {
+    #[serde(alias = "actor_ref")]
+    pub component_ref: String,
+    /// Defaults to an ID derived from the reference, so the component has the same ID on every
+    /// host the entry selects
+    #[serde(default)]
+    pub component_id: Option<String>,
+    /// Host ID or friendly name of the single host to scale on
+    #[serde(default)]
+    pub host: Option<String>,
+    /// Scale on every host carrying all of these labels, instead of on a single `host`
+    #[serde(default)]
+    pub host_labels: BTreeMap<String, String>,
+    pub max_instances: u32,
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+}
+
+/// A single scale command of a scale manifest, once its host selector is resolved
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ScaleManifestRow {
+    pub host_id: String,
+    pub component_id: String,
+    pub component_ref: String,
+    pub max_instances: u32,
+    #[serde(skip)]
+    pub annotations: BTreeMap<String, String>,
+}
+
+/// Resolve the host selector of each manifest entry against the given host inventories, giving one
+/// row per host to scale on. Fails if an entry has no valid selector or selects no hosts
+pub fn resolve_scale_manifest(
+    entries: &[ScaleManifestEntry],
+    inventories: &[HostInventory],
+) -> Result<Vec<ScaleManifestRow>> {
+    let mut rows = Vec::new();
+    for entry in entries {
+        let component_ref = &entry.component_ref;
+        let hosts = match (&entry.host, entry.host_labels.is_empty()) {
+            (Some(host), true) => vec![inventories
+                .iter()
+                .find(|inv| inv.host_id() == host)
+                .or_else(|| inventories.iter().find(|inv| inv.friendly_name() == host))
+                .with_context(|| format!("No host found matching [{host}]"))?],
+            (None, false) => {
+                let hosts = inventories
+                    .iter()
+                    .filter(|inv| {
+                        entry
+                            .host_labels
+                            .iter()
+                            .all(|(k, v)| inv.labels().get(k) == Some(v))
+                    })
+                    .collect::<Vec<_>>();
+                if hosts.is_empty() {
+                    bail!("No host found with labels {:?} for [{component_ref}]", entry.host_labels);
+                }
+                hosts
+            }
+            _ => bail!(
+                "Entry for [{component_ref}] must select hosts with exactly one of `host` or `host_labels`"
+            ),
+        };
+        let component_id = entry
+            .component_id
+            .clone()
+            .unwrap_or_else(|| sanitize_component_id(component_ref));
+        for inventory in hosts {
+            rows.push(ScaleManifestRow {
+                host_id: inventory.host_id().to_string(),
+                component_id: component_id.clone(),
+                component_ref: component_ref.clone(),
+                max_instances: entry.max_instances,
+                annotations: entry.annotations.clone(),
+            });
+        }
+    }
+    Ok(rows)
+}
+
+/// The current and target instance count of a component on a single host
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ComponentScalePreviewHost {
+    pub host_id: String,
+    /// The max instances currently running, `0` if the component isn't running on the host
+    pub current: u32,
+    pub target: u32,
+}
+
+/// What scaling a component would change, without issuing any scale command
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ComponentScalePreview {
+    pub component_id: String,
+    pub hosts: Vec<ComponentScalePreviewHost>,
+    /// The sum of the current counts across all hosts
+    pub current_total: u64,
+    /// The sum of the target counts across all hosts
+    pub target_total: u64,
+}
+
+/// Annotation naming the controller that owns a component, checked by `wash scale component
+/// --owner`
+pub const OWNER_ANNOTATION: &str = "owned-by";
+
+/// Refuse to scale a component on the host of `inventory` unless it is owned by `owner`. A
+/// component that isn't running on the host yet has no owner and may be scaled (the scale then
+/// marks it as owned by `owner`), but a running component must carry a matching
+/// [`OWNER_ANNOTATION`]
+pub fn check_component_owner(
+    inventory: &HostInventory,
+    component_id: &str,
+    owner: &str,
+) -> Result<()> {
+    let Some(component) = inventory
+        .components()
+        .iter()
+        .find(|c| c.id() == component_id)
+    else {
+        return Ok(());
+    };
+    match component
+        .annotations()
+        .and_then(|annotations| annotations.get(OWNER_ANNOTATION))
+    {
+        Some(current) if current == owner => Ok(()),
+        Some(current) => bail!(
+            "Component [{component_id}] on host [{}] is owned by [{current}], refusing to scale it as [{owner}]",
+            inventory.host_id()
+        ),
+        None => bail!(
+            "Component [{component_id}] on host [{}] has no [{OWNER_ANNOTATION}] annotation, refusing to scale it as [{owner}]",
+            inventory.host_id()
+        ),
+    }
+}
+
+/// Whether a component or provider runs with every one of the `required` annotations, as given
+/// with `--match-annotation`. Nothing is required when `required` is empty
+#[must_use]
+pub fn annotations_match(
+    annotations: Option<&BTreeMap<String, String>>,
+    required: &HashMap<String, String>,
+) -> bool {
+    required
+        .iter()
+        .all(|(k, v)| annotations.and_then(|a| a.get(k)) == Some(v))
+}
+
+/// Describe required annotations for messages, e.g. `[app=web, team=search]`
+#[must_use]
+pub fn describe_annotations(annotations: &HashMap<String, String>) -> String {
+    let mut pairs = annotations
+        .iter()
+        .map(|(k, v)| format!("{k}={v}"))
+        .collect::<Vec<_>>();
+    pairs.sort_unstable();
+    format!("[{}]", pairs.join(", "))
+}
+
+/// Refuse to scale a component on the host of `inventory` unless it is running there with every
+/// one of the `required` annotations, so that e.g. a component managed by wadm is only touched
+/// when it belongs to the expected deployment
+pub fn check_component_annotations(
+    inventory: &HostInventory,
+    component_id: &str,
+    required: &HashMap<String, String>,
+) -> Result<()> {
+    if required.is_empty() {
+        return Ok(());
+    }
+    let Some(component) = inventory
+        .components()
+        .iter()
+        .find(|c| c.id() == component_id)
+    else {
+        bail!(
+            "Component [{component_id}] is not running on host [{}], so it can't match annotations {}",
+            inventory.host_id(),
+            describe_annotations(required)
+        );
+    };
+    if !annotations_match(component.annotations(), required) {
+        bail!(
+            "Component [{component_id}] on host [{}] doesn't have annotations {}, refusing to scale it",
+            inventory.host_id(),
+            describe_annotations(required)
+        );
+    }
+    Ok(())
+}
+
+/// Host label advertising how many component instances the host can run in total, used by `wash
+/// scale component --cap-at-host-capacity`
+pub const CAPACITY_LABEL: &str = "capacity";
+
+/// Clamp a requested max instances to the capacity left on the host of `inventory`: its
+/// [`CAPACITY_LABEL`] minus the max instances of the other components running there. Fails if the
+/// host doesn't advertise a valid capacity, or has none left for the component
+pub fn cap_at_host_capacity(
+    inventory: &HostInventory,
+    component_id: &str,
+    requested: u32,
+) -> Result<u32> {
+    let host_id = inventory.host_id();
+    let capacity = inventory
+        .labels()
+        .get(CAPACITY_LABEL)
+        .with_context(|| {
+            format!(
+                "Host [{host_id}] does not advertise its capacity with a [{CAPACITY_LABEL}] label"
+            )
+        })?
+        .parse::<u32>()
+        .with_context(|| format!("Host [{host_id}] has an invalid [{CAPACITY_LABEL}] label"))?;
+    let used = inventory
+        .components()
+        .iter()
+        .filter(|c| c.id() != component_id)
+        .map(|c| c.max_instances())
+        .fold(0u32, u32::saturating_add);
+    let available = capacity.saturating_sub(used);
+    if available == 0 && requested > 0 {
+        bail!(
+            "Host [{host_id}] has no capacity left, {used} of {capacity} instances are used by other components"
+        );
+    }
+    Ok(requested.min(available))
+}
+
+/// Whether the host already runs the component from `component_ref` at `max_instances`, or doesn't
+/// run it when scaling to 0. Used to tell whether a scale request that timed out reached the host
+#[must_use]
+pub fn is_scaled_to(
+    inventory: &HostInventory,
+    component_id: &str,
+    component_ref: &str,
+    max_instances: u32,
+) -> bool {
+    match inventory
+        .components()
+        .iter()
+        .find(|c| c.id() == component_id)
+    {
+        Some(component) => {
+            component.image_ref() == component_ref && component.max_instances() == max_instances
+        }
+        None => max_instances == 0,
+    }
+}
+
+/// Compute the current and target count of a component on each of the given hosts if it were
+/// scaled to `max_instances`
+#[must_use]
+pub fn preview_component_scale(
+    inventories: &[HostInventory],
+    component_id: &str,
+    max_instances: u32,
+) -> ComponentScalePreview {
+    let hosts = inventories
+        .iter()
+        .map(|inv| ComponentScalePreviewHost {
+            host_id: inv.host_id().to_string(),
+            current: inv
+                .components()
+                .iter()
+                .find(|c| c.id() == component_id)
+                .map(|c| c.max_instances())
+                .unwrap_or_default(),
+            target: max_instances,
+        })
+        .collect::<Vec<_>>();
+    ComponentScalePreview {
+        component_id: component_id.to_string(),
+        current_total: hosts.iter().map(|h| u64::from(h.current)).sum(),
+        target_total: hosts.iter().map(|h| u64::from(h.target)).sum(),
+        hosts,
+    }
+}
+
+/// The instance count of a component on a single host
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ComponentStatusHost {
+    pub host_id: String,
+    pub component_id: String,
+    pub max_instances: u32,
+}
+
+/// Where a component currently stands across the lattice, and whether the last scale observed for
+/// it has taken effect
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ComponentScaleStatus {
+    pub component_ref: String,
+    /// Every host running the component
+    pub hosts: Vec<ComponentStatusHost>,
+    /// The sum of the max instances across all hosts
+    pub total_instances: u64,
+    /// The most recent scale of the component seen in lattice events, if any
+    pub last_scale: Option<ObservedComponentScale>,
+    /// Whether the host of the last scale now runs the component at the scaled count. `None` if no
+    /// scale was observed
+    pub target_reached: Option<bool>,
+}
+
+/// Combine host inventories with recent lattice events into the scale status of a component
+#[must_use]
+pub fn component_scale_status(
+    component_ref: &str,
+    inventories: &[HostInventory],
+    events: &[Event],
+) -> ComponentScaleStatus {
+    let hosts = inventories
+        .iter()
+        .flat_map(|inv| {
+            inv.components()
+                .iter()
+                .filter(|c| c.image_ref() == component_ref)
+                .map(|c| ComponentStatusHost {
+                    host_id: inv.host_id().to_string(),
+                    component_id: c.id().to_string(),
+                    max_instances: c.max_instances(),
+                })
+        })
+        .collect::<Vec<_>>();
+    let last_scale = last_component_scale(events, component_ref);
+    let target_reached = last_scale.as_ref().map(|scale| {
+        let current = hosts
+            .iter()
+            .find(|h| h.host_id == scale.host_id && h.component_id == scale.component_id)
+            .map(|h| h.max_instances)
+            .unwrap_or_default();
+        scale.error.is_none() && current == scale.max_instances
+    });
+    ComponentScaleStatus {
+        component_ref: component_ref.to_string(),
+        total_instances: hosts.iter().map(|h| u64::from(h.max_instances)).sum(),
+        hosts,
+        last_scale,
+        target_reached,
+    }
+}
+
 pub async fn update_component(
     client: &CtlClient,
     host_id: &str,
@@ -130,3 +647,474 @@ pub async fn update_component(
         .await
         .map_err(boxed_err_to_anyhow)
 }
+
+#[cfg(test)]
+mod test {
+    use cloudevents::{EventBuilder, EventBuilderV10};
+    use serde_json::json;
+    use wasmcloud_control_interface::ComponentDescription;
+
+    use super::*;
+
+    fn inventory(host_id: &str, name: &str, components: &[(&str, &str, u32)]) -> HostInventory {
+        HostInventory::builder()
+            .host_id(host_id.into())
+            .friendly_name(name.into())
+            .version("1.0.0".into())
+            .uptime_human("t".into())
+            .uptime_seconds(100)
+            .components(
+                components
+                    .iter()
+                    .map(|(id, image_ref, max)| {
+                        ComponentDescription::builder()
+                            .id(id.to_string())
+                            .image_ref(image_ref.to_string())
+                            .max_instances(*max)
+                            .build()
+                            .expect("should build component description")
+                    })
+                    .collect(),
+            )
+            .build()
+            .expect("should build host inventory")
+    }
+
+    #[test]
+    fn scale_is_refused_for_components_owned_by_others() {
+        let owned = |owner: Option<&str>| {
+            let mut component = ComponentDescription::builder()
+                .id("echo".to_string())
+                .image_ref("ghcr.io/echo:0.1.0".to_string())
+                .max_instances(1);
+            if let Some(owner) = owner {
+                component = component.annotations(BTreeMap::from([(
+                    OWNER_ANNOTATION.to_string(),
+                    owner.to_string(),
+                )]));
+            }
+            HostInventory::builder()
+                .host_id("host-a".into())
+                .friendly_name("a".into())
+                .version("1.0.0".into())
+                .uptime_human("t".into())
+                .uptime_seconds(100)
+                .components(vec![component
+                    .build()
+                    .expect("should build component description")])
+                .build()
+                .expect("should build inventory")
+        };
+
+        check_component_owner(&owned(Some("team-a")), "echo", "team-a")
+            .expect("owner should be allowed to scale");
+        let err = check_component_owner(&owned(Some("team-b")), "echo", "team-a")
+            .expect_err("another controller's component should be rejected");
+        assert!(err.to_string().contains("owned by [team-b]"));
+        check_component_owner(&owned(None), "echo", "team-a")
+            .expect_err("unowned running component should be rejected");
+        // Nothing to take over if the component isn't running yet
+        check_component_owner(&owned(Some("team-b")), "other", "team-a")
+            .expect("new component should be allowed");
+    }
+
+    #[test]
+    fn scale_is_clamped_to_host_capacity() {
+        let with_capacity = |capacity: Option<&str>| {
+            let base = inventory(
+                "host-a",
+                "a",
+                &[
+                    ("echo", "ghcr.io/echo:0.1.0", 4),
+                    ("other", "ghcr.io/other:0.1.0", 6),
+                ],
+            );
+            HostInventory::builder()
+                .host_id(base.host_id().into())
+                .friendly_name(base.friendly_name().into())
+                .version(base.version().into())
+                .uptime_human(base.uptime_human().into())
+                .uptime_seconds(base.uptime_seconds())
+                .components(base.components().clone())
+                .labels(
+                    capacity
+                        .map(|c| BTreeMap::from([(CAPACITY_LABEL.to_string(), c.to_string())]))
+                        .unwrap_or_default(),
+                )
+                .build()
+                .expect("should build inventory")
+        };
+
+        // The component's own instances don't count against the capacity it can be scaled to
+        let inventory = with_capacity(Some("16"));
+        assert_eq!(
+            cap_at_host_capacity(&inventory, "echo", u32::MAX).expect("should clamp"),
+            10
+        );
+        assert_eq!(
+            cap_at_host_capacity(&inventory, "echo", 3).expect("should fit"),
+            3
+        );
+        assert_eq!(
+            cap_at_host_capacity(&inventory, "new", 100).expect("should clamp"),
+            6
+        );
+
+        let full = cap_at_host_capacity(&with_capacity(Some("10")), "new", 1)
+            .expect_err("full host should be rejected");
+        assert!(full.to_string().contains("has no capacity left"));
+        assert!(cap_at_host_capacity(&with_capacity(None), "echo", 5).is_err());
+        assert!(cap_at_host_capacity(&with_capacity(Some("lots")), "echo", 5).is_err());
+    }
+
+    #[test]
+    fn preview_reports_current_and_target_counts() {
+        let inventories = vec![
+            inventory(
+                "host1",
+                "quiet-dawn",
+                &[("hello", "ghcr.io/hello:0.1.0", 5)],
+            ),
+            inventory("host2", "bold-sky", &[("echo", "ghcr.io/echo:0.1.0", 1)]),
+        ];
+        let preview = preview_component_scale(&inventories, "hello", 10);
+        assert_eq!(
+            preview.hosts,
+            vec![
+                ComponentScalePreviewHost {
+                    host_id: "host1".into(),
+                    current: 5,
+                    target: 10,
+                },
+                ComponentScalePreviewHost {
+                    host_id: "host2".into(),
+                    current: 0,
+                    target: 10,
+                },
+            ]
+        );
+        assert_eq!(preview.current_total, 5);
+        assert_eq!(preview.target_total, 20);
+    }
+
+    #[test]
+    fn plan_only_includes_changed_counts() {
+        let inventories = vec![
+            inventory(
+                "host1",
+                "quiet-dawn",
+                &[("hello", "ghcr.io/hello:0.1.0", 5)],
+            ),
+            inventory("host2", "bold-sky", &[("echo", "ghcr.io/echo:0.1.0", 1)]),
+        ];
+        let desired: DesiredComponentCounts = serde_yaml::from_str(
+            r"
+ghcr.io/hello:0.1.0:
+  host1: 10
+  bold-sky: 2
+ghcr.io/echo:0.1.0:
+  host2: 1
+",
+        )
+        .expect("should parse desired counts");
+
+        // Desired counts are kept in sorted maps, so hosts are planned by key and `bold-sky` comes
+        // before `host1` regardless of the order in the file
+        let actions =
+            plan_component_scale(&desired, &inventories).expect("should plan scale actions");
+        assert_eq!(
+            actions,
+            vec![
+                ComponentScaleAction {
+                    host_id: "host2".into(),
+                    component_id: "ghcr_io_hello_0_1_0".into(),
+                    component_ref: "ghcr.io/hello:0.1.0".into(),
+                    current: 0,
+                    desired: 2,
+                },
+                ComponentScaleAction {
+                    host_id: "host1".into(),
+                    component_id: "hello".into(),
+                    component_ref: "ghcr.io/hello:0.1.0".into(),
+                    current: 5,
+                    desired: 10,
+                },
+            ]
+        );
+
+        let desired = DesiredComponentCounts::from([(
+            "ghcr.io/hello:0.1.0".to_string(),
+            BTreeMap::from([("unknown".to_string(), 1)]),
+        )]);
+        assert!(plan_component_scale(&desired, &inventories).is_err());
+    }
+
+    fn scale_event(ty: &str, data: serde_json::Value) -> Event {
+        EventBuilderV10::new()
+            .id("test")
+            .ty(format!("com.wasmcloud.lattice.{ty}"))
+            .source("host1")
+            .data("application/json", data)
+            .build()
+            .expect("failed to build event")
+    }
+
+    #[test]
+    fn scale_status_reports_counts_and_last_scale() {
+        let inventories = vec![
+            inventory(
+                "host1",
+                "quiet-dawn",
+                &[("hello", "ghcr.io/hello:0.1.0", 5)],
+            ),
+            inventory(
+                "host2",
+                "bold-sky",
+                &[
+                    ("hello", "ghcr.io/hello:0.1.0", 3),
+                    ("echo", "ghcr.io/echo:0.1.0", 1),
+                ],
+            ),
+        ];
+        let scaled = |max: u32| {
+            scale_event(
+                "component_scaled",
+                json!({"host_id": "host1", "image_ref": "ghcr.io/hello:0.1.0", "component_id": "hello", "max_instances": max}),
+            )
+        };
+
+        let status = component_scale_status("ghcr.io/hello:0.1.0", &inventories, &[]);
+        assert_eq!(status.hosts.len(), 2);
+        assert_eq!(status.total_instances, 8);
+        assert_eq!(status.last_scale, None);
+        assert_eq!(status.target_reached, None);
+
+        // Only the latest scale counts
+        let status =
+            component_scale_status("ghcr.io/hello:0.1.0", &inventories, &[scaled(2), scaled(5)]);
+        assert_eq!(status.last_scale.map(|s| s.max_instances), Some(5));
+        assert_eq!(status.target_reached, Some(true));
+
+        let status = component_scale_status("ghcr.io/hello:0.1.0", &inventories, &[scaled(10)]);
+        assert_eq!(status.target_reached, Some(false));
+
+        let failed = scale_event(
+            "component_scale_failed",
+            json!({"host_id": "host1", "image_ref": "ghcr.io/hello:0.1.0", "component_id": "hello", "max_instances": 5, "error": "out of memory"}),
+        );
+        let status = component_scale_status("ghcr.io/hello:0.1.0", &inventories, &[failed]);
+        assert_eq!(
+            status.last_scale.and_then(|s| s.error).as_deref(),
+            Some("out of memory")
+        );
+        assert_eq!(status.target_reached, Some(false));
+    }
+
+    #[tokio::test]
+    async fn scale_is_retried_while_host_is_already_scaling() {
+        let attempts = std::sync::atomic::AtomicU32::new(0);
+        send_scale_with_retry(Duration::from_millis(1), || async {
+            Ok(
+                match attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst) {
+                    0 => CtlResponse::error("component [hello] is already scaling"),
+                    _ => CtlResponse::success("scaled".to_string()),
+                },
+            )
+        })
+        .await
+        .expect("retry should succeed");
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        // Other rejections are terminal
+        let attempts = std::sync::atomic::AtomicU32::new(0);
+        let err = send_scale_with_retry(Duration::from_millis(1), || async {
+            attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(CtlResponse::error("invalid component reference"))
+        })
+        .await
+        .expect_err("rejection should fail");
+        assert_eq!(
+            err.to_string(),
+            "Operation failed: invalid component reference"
+        );
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        // A host that never settles is only retried a bounded number of times
+        let attempts = std::sync::atomic::AtomicU32::new(0);
+        send_scale_with_retry(Duration::from_millis(1), || async {
+            attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(CtlResponse::error("Already scaling"))
+        })
+        .await
+        .expect_err("should give up eventually");
+        assert_eq!(
+            attempts.load(std::sync::atomic::Ordering::SeqCst),
+            ALREADY_SCALING_RETRIES + 1
+        );
+    }
+
+    #[test]
+    fn scale_to_zero_keeps_running_annotations() {
+        let component = ComponentDescription::builder()
+            .id("echo".to_string())
+            .image_ref("ghcr.io/echo:0.1.0".to_string())
+            .max_instances(1)
+            .annotations(BTreeMap::from([
+                (OWNER_ANNOTATION.to_string(), "team-a".to_string()),
+                ("deployment".to_string(), "blue".to_string()),
+            ]))
+            .build()
+            .expect("should build component description");
+        let inventory = HostInventory::builder()
+            .host_id("host-a".into())
+            .friendly_name("a".into())
+            .version("1.0.0".into())
+            .uptime_human("t".into())
+            .uptime_seconds(100)
+            .components(vec![component])
+            .build()
+            .expect("should build inventory");
+
+        assert_eq!(
+            scale_to_zero_annotations(
+                Some(&inventory),
+                "echo",
+                Some(HashMap::from([(
+                    "deployment".to_string(),
+                    "green".to_string()
+                )])),
+            ),
+            BTreeMap::from([
+                (OWNER_ANNOTATION.to_string(), "team-a".to_string()),
+                ("deployment".to_string(), "green".to_string()),
+            ])
+        );
+        assert!(scale_to_zero_annotations(Some(&inventory), "other", None).is_empty());
+        assert!(scale_to_zero_annotations(None, "echo", None).is_empty());
+    }
+
+    #[test]
+    fn scale_is_scoped_to_annotations() {
+        let component = ComponentDescription::builder()
+            .id("echo".to_string())
+            .image_ref("ghcr.io/echo:0.1.0".to_string())
+            .max_instances(1)
+            .annotations(BTreeMap::from([
+                ("wasmcloud.dev/managed-by".to_string(), "wadm".to_string()),
+                ("deployment".to_string(), "blue".to_string()),
+            ]))
+            .build()
+            .expect("should build component description");
+        let inventory = HostInventory::builder()
+            .host_id("host-a".into())
+            .friendly_name("a".into())
+            .version("1.0.0".into())
+            .uptime_human("t".into())
+            .uptime_seconds(100)
+            .components(vec![component])
+            .build()
+            .expect("should build inventory");
+        let required = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<HashMap<_, _>>()
+        };
+
+        check_component_annotations(&inventory, "echo", &required(&[("deployment", "blue")]))
+            .expect("matching annotations should be accepted");
+        check_component_annotations(&inventory, "other", &required(&[]))
+            .expect("no required annotations should be accepted");
+        let err = check_component_annotations(
+            &inventory,
+            "echo",
+            &required(&[("deployment", "blue"), ("team", "search")]),
+        )
+        .expect_err("a missing annotation should be refused");
+        assert!(
+            err.to_string().contains("[deployment=blue, team=search]"),
+            "{err}"
+        );
+        check_component_annotations(&inventory, "echo", &required(&[("deployment", "green")]))
+            .expect_err("a different value should be refused");
+        check_component_annotations(&inventory, "other", &required(&[("deployment", "blue")]))
+            .expect_err("a component that isn't running can't match");
+        assert!(!annotations_match(
+            None,
+            &required(&[("deployment", "blue")])
+        ));
+    }
+
+    #[test]
+    fn scale_already_applied_is_detected() {
+        let inv = inventory("host-a", "a", &[("echo", "ghcr.io/echo:0.1.0", 3)]);
+        assert!(is_scaled_to(&inv, "echo", "ghcr.io/echo:0.1.0", 3));
+        assert!(!is_scaled_to(&inv, "echo", "ghcr.io/echo:0.1.0", 5));
+        assert!(!is_scaled_to(&inv, "echo", "ghcr.io/echo:0.2.0", 3));
+        assert!(!is_scaled_to(&inv, "other", "ghcr.io/other:0.1.0", 1));
+        assert!(is_scaled_to(&inv, "other", "ghcr.io/other:0.1.0", 0));
+    }
+
+    #[test]
+    fn manifest_host_selectors_are_resolved() {
+        let labeled = |host_id: &str, zone: &str, components: &[(&str, &str, u32)]| {
+            let base = inventory(host_id, &format!("{host_id}-name"), components);
+            HostInventory::builder()
+                .host_id(base.host_id().into())
+                .friendly_name(base.friendly_name().into())
+                .version("1.0.0".into())
+                .uptime_human("t".into())
+                .uptime_seconds(100)
+                .components(base.components().to_vec())
+                .labels(BTreeMap::from([("zone".to_string(), zone.to_string())]))
+                .build()
+                .expect("should build host inventory")
+        };
+        let inventories = vec![
+            labeled(
+                "host-a",
+                "east",
+                &[("running-echo", "ghcr.io/echo:0.1.0", 1)],
+            ),
+            labeled("host-b", "east", &[]),
+            labeled("host-c", "west", &[]),
+        ];
+        let entries: Vec<ScaleManifestEntry> = serde_yaml::from_str(
+            "
+- actor_ref: ghcr.io/echo:0.1.0
+  host_labels: {zone: east}
+  max_instances: 5
+- component_ref: ghcr.io/kv:0.1.0
+  component_id: kv
+  host: host-c-name
+  max_instances: 2
+  annotations: {team: storage}
+",
+        )
+        .expect("should parse manifest");
+
+        let rows = resolve_scale_manifest(&entries, &inventories).expect("should resolve");
+        assert_eq!(
+            rows.iter()
+                .map(|r| (r.host_id.as_str(), r.component_id.as_str(), r.max_instances))
+                .collect::<Vec<_>>(),
+            vec![
+                ("host-a", "ghcr_io_echo_0_1_0", 5),
+                ("host-b", "ghcr_io_echo_0_1_0", 5),
+                ("host-c", "kv", 2),
+            ]
+        );
+        assert_eq!(rows[2].annotations["team"], "storage");
+
+        let both = ScaleManifestEntry {
+            host: Some("host-a".to_string()),
+            ..entries[0].clone()
+        };
+        assert!(resolve_scale_manifest(&[both], &inventories).is_err());
+        let nowhere = ScaleManifestEntry {
+            host_labels: BTreeMap::from([("zone".to_string(), "north".to_string())]),
+            ..entries[0].clone()
+        };
+        assert!(resolve_scale_manifest(&[nowhere], &inventories).is_err());
+    }
+}
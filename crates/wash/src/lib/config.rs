@@ -1,9 +1,13 @@
 //! Common config constants and functions for loading, finding, and consuming configuration data
+use std::sync::atomic::Ordering;
+use std::time::Duration;
 use std::{fs, path::PathBuf};
 
 use anyhow::{Context, Result};
 use async_nats::Client;
 use tokio::io::AsyncReadExt;
+use tokio::task::JoinHandle;
+use tracing::debug;
 use wasmcloud_control_interface::{Client as CtlClient, ClientBuilder as CtlClientBuilder};
 
 use crate::lib::context::WashContext;
@@ -12,6 +16,7 @@ pub const WASH_DIR: &str = ".wash";
 
 pub const DEV_DIR: &str = "dev";
 pub const DOWNLOADS_DIR: &str = "downloads";
+pub const ROLLOUTS_DIR: &str = "rollouts";
 pub const WASMCLOUD_PID_FILE: &str = "wasmcloud.pid";
 pub const WADM_PID_FILE: &str = "wadm.pid";
 pub const DEFAULT_NATS_HOST: &str = "127.0.0.1";
@@ -47,6 +52,11 @@ pub fn downloads_dir() -> Result<PathBuf> {
     Ok(cfg_dir()?.join(DOWNLOADS_DIR))
 }
 
+/// The path to the directory of persisted provider rollouts
+pub fn rollouts_dir() -> Result<PathBuf> {
+    Ok(cfg_dir()?.join(ROLLOUTS_DIR))
+}
+
 /// The path to the running wasmCloud Host PID file for wash
 pub fn host_pid_file() -> Result<PathBuf> {
     Ok(downloads_dir()?.join(WASMCLOUD_PID_FILE))
@@ -91,6 +101,10 @@ pub struct WashConnectionOptions {
     /// Timeout length to await a control interface response, defaults to 2000 milliseconds
     pub timeout_ms: u64,
 
+    /// Close the NATS connection once no messages have been sent or received on it for this long.
+    /// Defaults to None, keeping the connection open until it is closed or dropped
+    pub idle_timeout_ms: Option<u64>,
+
     /// Wash context
     pub ctx: WashContext,
 }
@@ -128,6 +142,9 @@ impl WashConnectionOptions {
         )
         .await
         .context("Failed to create NATS client")?;
+        if let Some(idle_timeout_ms) = self.idle_timeout_ms {
+            close_when_idle(nc.clone(), Duration::from_millis(idle_timeout_ms));
+        }
 
         let mut builder = CtlClientBuilder::new(nc)
             .lattice(lattice)
@@ -170,6 +187,9 @@ impl WashConnectionOptions {
             ctl_tls_first,
         )
         .await?;
+        if let Some(idle_timeout_ms) = self.idle_timeout_ms {
+            close_when_idle(nc.clone(), Duration::from_millis(idle_timeout_ms));
+        }
 
         Ok(nc)
     }
@@ -183,6 +203,41 @@ impl WashConnectionOptions {
     }
 }
 
+/// Close the connection of a control client, flushing anything still pending, instead of leaving it
+/// open until the last clone of the client is dropped. Failures are only logged since the
+/// connection is being discarded anyway
+pub async fn close_ctl_client(client: &CtlClient) {
+    if let Err(e) = client.nats_client().drain().await {
+        debug!(?e, "failed to close control client connection");
+    }
+}
+
+/// Close the connection of `nc` once no messages have been sent or received on it for
+/// `idle_timeout`, so long-lived embedders don't accumulate stale connections
+pub fn close_when_idle(nc: Client, idle_timeout: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let traffic = |nc: &Client| {
+            let stats = nc.statistics();
+            (
+                stats.in_messages.load(Ordering::Relaxed),
+                stats.out_messages.load(Ordering::Relaxed),
+            )
+        };
+        let mut last = traffic(&nc);
+        loop {
+            tokio::time::sleep(idle_timeout).await;
+            let current = traffic(&nc);
+            if current == last {
+                debug!(?idle_timeout, "closing idle NATS connection");
+                // Fails if the connection was already closed, either way we're done
+                let _ = nc.drain().await;
+                return;
+            }
+            last = current;
+        }
+    })
+}
+
 /// Reads the content of a string if it is a valid file path, otherwise returning the string
 async fn extract_arg_value(arg: &str) -> Result<String> {
     match tokio::fs::File::open(arg).await {
@@ -1,7 +1,7 @@
 //! Utilities for working with and managing wit dependencies
 
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     path::{Path, PathBuf},
     str::FromStr,
 };
@@ -9,7 +9,7 @@ use std::{
 use anyhow::{bail, Context, Result};
 use async_compression::tokio::bufread::GzipDecoder;
 use futures::TryStreamExt as _;
-use semver::VersionReq;
+use semver::{Version, VersionReq};
 use url::Url;
 use walkdir::WalkDir;
 use wasm_pkg_client::{
@@ -331,6 +331,48 @@ impl WkgFetcher {
     }
 }
 
+/// Remove packages from a lock file, so that fetching dependencies resolves them to the newest
+/// versions their requirements allow instead of the locked versions. All packages are removed when
+/// `packages` is empty, otherwise packages are given as `namespace:name`
+pub fn unlock_packages(lock: &mut LockFile, packages: &[String]) -> Result<()> {
+    if packages.is_empty() {
+        lock.packages.clear();
+        return Ok(());
+    }
+    let package_refs = packages
+        .iter()
+        .map(|p| {
+            p.parse::<PackageRef>()
+                .with_context(|| format!("invalid package name [{p}]"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if let Some(missing) = package_refs
+        .iter()
+        .find(|r| !lock.packages.iter().any(|p| &p.name == *r))
+    {
+        bail!("package [{missing}] is not in the lock file");
+    }
+    lock.packages.retain(|p| !package_refs.contains(&p.name));
+    Ok(())
+}
+
+/// The versions each package in a lock file is locked to, by package name
+#[must_use]
+pub fn locked_versions(lock: &LockFile) -> BTreeMap<String, Vec<Version>> {
+    let mut versions = BTreeMap::<String, Vec<Version>>::new();
+    for package in &lock.packages {
+        versions
+            .entry(package.name.to_string())
+            .or_default()
+            .extend(package.versions.iter().map(|v| v.version.clone()));
+    }
+    for locked in versions.values_mut() {
+        locked.sort();
+        locked.dedup();
+    }
+    versions
+}
+
 async fn copy_dir(source: impl AsRef<Path>, destination: impl AsRef<Path>) -> anyhow::Result<()> {
     tokio::fs::create_dir_all(&destination).await?;
     let mut entries = tokio::fs::read_dir(source).await?;
@@ -403,3 +445,45 @@ async fn find_wit_folder_in_path(dir: impl AsRef<Path>) -> Result<PathBuf> {
     .await
     .context("failed to resolve folder with WIT in downloaded archive")
 }
+
+#[cfg(test)]
+mod test {
+    use wasm_pkg_core::lock::{LockedPackage, LockedPackageVersion};
+
+    use super::*;
+
+    fn locked(name: &str, version: &str) -> LockedPackage {
+        LockedPackage {
+            name: name.parse().expect("valid package name"),
+            registry: None,
+            versions: vec![LockedPackageVersion {
+                requirement: VersionReq::parse(&format!("^{version}")).expect("valid requirement"),
+                version: version.parse().expect("valid version"),
+                digest: "sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+                    .parse()
+                    .expect("valid digest"),
+            }],
+        }
+    }
+
+    #[tokio::test]
+    async fn named_packages_are_unlocked() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut lock = LockFile::new_with_path(
+            [locked("wasi:http", "0.2.0"), locked("wasi:io", "0.2.0")],
+            dir.path().join("wasmcloud.lock"),
+        )
+        .await?;
+        assert_eq!(locked_versions(&lock)["wasi:http"], [Version::new(0, 2, 0)]);
+
+        assert!(unlock_packages(&mut lock, &["wasi:clocks".into()]).is_err());
+        unlock_packages(&mut lock, &["wasi:http".into()])?;
+        assert_eq!(
+            locked_versions(&lock).into_keys().collect::<Vec<_>>(),
+            ["wasi:io"]
+        );
+        unlock_packages(&mut lock, &[])?;
+        assert!(lock.packages.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+//! Classes of command failure that scripts can branch on. Each class has its own process exit code
+//! and is reported as `error_kind` in JSON output; failures outside these classes exit with 1
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use serde::Serialize;
+
+use crate::lib::backoff::is_timeout;
+use crate::lib::provider::{ProviderStartError, ProviderStartFailureClass};
+
+/// Why a command failed, for failures that scripts may want to handle differently
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FailureKind {
+    /// No host responded to an auction, e.g. because none matched the constraints
+    AuctionEmpty,
+    /// A host received the command but refused it
+    AckRejected,
+    /// The command was sent but the event or response confirming it never arrived
+    EventTimeout,
+    /// The lattice could not be reached
+    ConnectionFailure,
+    /// A command acting on several providers, hosts or instances failed on at least one of them.
+    /// The outcome on each of them is reported with the error
+    TargetsFailed,
+}
+
+impl FailureKind {
+    /// The process exit code for this kind of failure. 1 is used for any other failure and 2 for
+    /// invalid arguments
+    #[must_use]
+    pub const fn exit_code(&self) -> i32 {
+        match self {
+            Self::AuctionEmpty => 3,
+            Self::AckRejected => 4,
+            Self::EventTimeout => 5,
+            Self::ConnectionFailure => 6,
+            Self::TargetsFailed => 7,
+        }
+    }
+
+    /// The identifier used for this kind in command output
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::AuctionEmpty => "auction-empty",
+            Self::AckRejected => "ack-rejected",
+            Self::EventTimeout => "event-timeout",
+            Self::ConnectionFailure => "connection-failure",
+            Self::TargetsFailed => "targets-failed",
+        }
+    }
+
+    /// Classify an error from its chain: a [`Failure`] anywhere in the chain decides the kind,
+    /// otherwise connection errors and timeouts are recognized from their source. Returns `None`
+    /// for errors outside the taxonomy
+    #[must_use]
+    pub fn of(err: &anyhow::Error) -> Option<Self> {
+        if let Some(failure) = err.chain().find_map(|e| e.downcast_ref::<Failure>()) {
+            return Some(failure.kind);
+        }
+        let connection_failed = err.chain().any(|e| {
+            e.downcast_ref::<async_nats::ConnectError>().is_some()
+                || e.downcast_ref::<std::io::Error>()
+                    .is_some_and(|e| e.kind() == std::io::ErrorKind::ConnectionRefused)
+        });
+        if connection_failed {
+            return Some(Self::ConnectionFailure);
+        }
+        let start_timed_out = err
+            .chain()
+            .find_map(|e| e.downcast_ref::<ProviderStartError>())
+            .is_some_and(|e| e.class == ProviderStartFailureClass::Timeout);
+        (start_timed_out || is_timeout(err)).then_some(Self::EventTimeout)
+    }
+}
+
+impl Display for FailureKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An error of a known [`FailureKind`]. Only the message is displayed, so tagging an error doesn't
+/// change how it is printed
+#[derive(Debug, thiserror::Error)]
+#[error("{message}")]
+pub struct Failure {
+    pub kind: FailureKind,
+    pub message: String,
+    /// Fields added to the structured output of the error, e.g. the outcome on each target
+    pub details: HashMap<String, serde_json::Value>,
+}
+
+impl Failure {
+    pub fn new(kind: FailureKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            details: HashMap::new(),
+        }
+    }
+
+    /// A failure that adds `details` to the structured output of the error, e.g. the fields the
+    /// command would have output had it succeeded
+    #[must_use]
+    pub fn with_details(
+        kind: FailureKind,
+        message: impl Into<String>,
+        details: HashMap<String, serde_json::Value>,
+    ) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            details,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Context;
+
+    use super::*;
+
+    #[test]
+    fn failures_are_classified_through_context() {
+        let err = anyhow::Error::from(Failure::new(
+            FailureKind::AuctionEmpty,
+            "No suitable hosts found for component ghcr.io/echo:0.1.0",
+        ))
+        .context("failed to start component");
+        assert_eq!(FailureKind::of(&err), Some(FailureKind::AuctionEmpty));
+        assert_eq!(
+            format!("{err:#}"),
+            "failed to start component: No suitable hosts found for component ghcr.io/echo:0.1.0"
+        );
+
+        let refused = Err::<(), _>(std::io::Error::from(std::io::ErrorKind::ConnectionRefused))
+            .context("Failed to connect to NATS")
+            .unwrap_err();
+        assert_eq!(
+            FailureKind::of(&refused),
+            Some(FailureKind::ConnectionFailure)
+        );
+
+        let timed_out = anyhow::Error::from(std::io::Error::from(std::io::ErrorKind::TimedOut));
+        assert_eq!(FailureKind::of(&timed_out), Some(FailureKind::EventTimeout));
+
+        assert_eq!(FailureKind::of(&anyhow::anyhow!("bad config")), None);
+
+        let codes = [
+            FailureKind::AuctionEmpty,
+            FailureKind::AckRejected,
+            FailureKind::EventTimeout,
+            FailureKind::ConnectionFailure,
+            FailureKind::TargetsFailed,
+        ]
+        .map(|kind| kind.exit_code());
+        assert!(codes.iter().all(|code| *code > 2));
+        assert_eq!(
+            codes.len(),
+            codes.iter().collect::<std::collections::HashSet<_>>().len()
+        );
+    }
+}
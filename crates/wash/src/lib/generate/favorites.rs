@@ -4,8 +4,9 @@
 //! A favorites file may be provided with the '--favorites' cli option, otherwise,
 //! a compiled-in set of defaults is used.
 //! The name of the template from the favorites file can be selected
-//! with the '--template-name' option. In silent mode, if no template-name
-//! is provided, the first entry of the applicable kind is selected.
+//! with the '--template-name' option, and the choices can be narrowed to the templates
+//! written in a language with the '--language' option. In silent mode, if no template-name
+//! is provided, the first entry of the applicable kind (and language) is selected.
 //!
 //! The favorites file should include settings for at least one component,
 //! interface, and provider. 'name' and 'description' are required,
@@ -20,6 +21,7 @@
 //! git = "optional github repository url for templates"
 //! subfolder = "optional, subdirectory. Only applicable with git"
 //! branch = "optional git branch. Only applicable with git"
+//! language = "optional language the template is written in, e.g. rust or go"
 //!
 //! [[component]]
 //! # settings for another component template, same fields as above
@@ -57,6 +59,7 @@ pub struct TemplateSource {
     pub(crate) git: Option<String>,
     pub(crate) branch: Option<String>,
     pub(crate) subfolder: Option<String>,
+    pub(crate) language: Option<String>,
 }
 
 /// Contents of favorites file
@@ -85,7 +88,7 @@ pub fn load_favorites(path: Option<&PathBuf>) -> Result<Favorites> {
     Ok(fv)
 }
 
-/// Picks one of the available templates for the project kind.
+/// Picks one of the available templates for the project kind, and language if one is given.
 /// If silent mode, picks the default, or the first entry if there is no default.
 /// If interactive, and there is more than one option, displays the choices
 /// to let the user pick one
@@ -94,10 +97,15 @@ pub fn pick_favorite(
     kind: &ProjectKind,
     silent: bool,
     fav_name: Option<&String>,
+    language: Option<&String>,
 ) -> Result<TemplateSource> {
     let mut favorites = load_favorites(fav_file)?;
     let fav = match favorites.templates.remove(&kind.to_string()) {
-        Some(mut type_favs) if !type_favs.is_empty() => {
+        Some(type_favs) if !type_favs.is_empty() => {
+            let mut type_favs = match language {
+                Some(language) => templates_in_language(type_favs, kind, language)?,
+                None => type_favs,
+            };
             if let Some(name) = &fav_name {
                 type_favs
                     .into_iter()
@@ -131,6 +139,37 @@ pub fn pick_favorite(
     Ok(fav)
 }
 
+/// The templates written in the given language, ignoring case. Fails, listing the languages
+/// there are templates for, if there are none
+fn templates_in_language(
+    templates: Vec<TemplateSource>,
+    kind: &ProjectKind,
+    language: &str,
+) -> Result<Vec<TemplateSource>> {
+    let mut available = templates
+        .iter()
+        .filter_map(|t| t.language.clone())
+        .collect::<Vec<_>>();
+    available.sort();
+    available.dedup();
+
+    let matching = templates
+        .into_iter()
+        .filter(|t| {
+            t.language
+                .as_deref()
+                .is_some_and(|l| l.eq_ignore_ascii_case(language))
+        })
+        .collect::<Vec<_>>();
+    if matching.is_empty() {
+        return Err(any_msg(
+            &format!("no {kind} template for the language '{language}'."),
+            &format!("Templates are available for: {}", available.join(", ")),
+        ));
+    }
+    Ok(matching)
+}
+
 /// Ask user to select one of the templates
 fn prompt_for_template(options: &[TemplateSource], prompt: &str) -> Result<usize> {
     let choices = options
@@ -146,3 +185,29 @@ fn prompt_for_template(options: &[TemplateSource], prompt: &str) -> Result<usize
     crate::lib::generate::interactive::prompt_for_choice(&entry, prompt)
         .map_err(|e| anyhow!("console IO error: {}", e))
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn templates_are_narrowed_to_a_language() {
+        let providers = || {
+            load_favorites(None)
+                .unwrap()
+                .templates
+                .remove("provider")
+                .expect("default favorites have providers")
+        };
+
+        let go = templates_in_language(providers(), &ProjectKind::Provider, "Go").unwrap();
+        assert_eq!(go.len(), 1);
+        assert_eq!(go[0].name, "custom-template-go");
+
+        let err = templates_in_language(providers(), &ProjectKind::Provider, "cobol")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("no provider template for the language 'cobol'"));
+        assert!(err.contains("go, rust"));
+    }
+}
@@ -75,6 +75,9 @@ pub struct Project {
     /// Template name - name of template to use
     pub template_name: Option<String>,
 
+    /// Only offer templates written in this language, e.g. rust or go
+    pub language: Option<String>,
+
     /// Don't run 'git init' on the new folder
     pub no_git_init: bool,
 
@@ -109,6 +112,7 @@ pub async fn generate_project(project: Project) -> Result<PathBuf> {
             &project.kind,
             project.silent,
             project.template_name.as_ref(),
+            project.language.as_ref(),
         )?;
         Project {
             path: fav.path.as_ref().map(PathBuf::from),
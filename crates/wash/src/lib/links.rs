@@ -0,0 +1,1355 @@
+//! An in-memory table of lattice links, grouped by the key the host uses to identify a link
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Display;
+
+use serde::Serialize;
+use wasmcloud_control_interface::Link;
+
+/// The identity of a link as far as the host is concerned: the source, the link name and the WIT
+/// namespace and package. Several [`Link`]s may share a key as long as they cover different
+/// interfaces.
+///
+/// This is a read-only view; keys are only ever constructed from links inserted into [`Links`].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LinkKey {
+    source_id: String,
+    name: String,
+    wit_namespace: String,
+    wit_package: String,
+}
+
+impl LinkKey {
+    /// The ID of the source component of the link
+    #[must_use]
+    pub fn source_id(&self) -> &str {
+        &self.source_id
+    }
+
+    /// The name of the link
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The WIT namespace of the link, e.g. `wasi` in `wasi:http/incoming-handler`
+    #[must_use]
+    pub fn wit_namespace(&self) -> &str {
+        &self.wit_namespace
+    }
+
+    /// The WIT package of the link, e.g. `http` in `wasi:http/incoming-handler`
+    #[must_use]
+    pub fn wit_package(&self) -> &str {
+        &self.wit_package
+    }
+}
+
+impl From<&Link> for LinkKey {
+    fn from(link: &Link) -> Self {
+        Self {
+            source_id: link.source_id().to_string(),
+            name: link.name().to_string(),
+            wit_namespace: link.wit_namespace().to_string(),
+            wit_package: link.wit_package().to_string(),
+        }
+    }
+}
+
+impl Display for LinkKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} -> {}:{} ({})",
+            self.source_id, self.wit_namespace, self.wit_package, self.name
+        )
+    }
+}
+
+/// Two links sharing a [`LinkKey`] whose interfaces overlap. The host only allows links under the
+/// same key if they cover disjoint sets of interfaces.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinkConflict {
+    /// The key shared by both links
+    pub key: LinkKey,
+    /// The interfaces claimed by both links
+    pub interfaces: Vec<String>,
+    /// The target of the link that was already present
+    pub existing_target: String,
+    /// The target of the link that conflicts with it
+    pub conflicting_target: String,
+}
+
+impl Display for LinkConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "link {} to [{}] overlaps with existing link to [{}] on interface(s) {}",
+            self.key,
+            self.conflicting_target,
+            self.existing_target,
+            self.interfaces.join(", ")
+        )
+    }
+}
+
+/// What putting a link into a [`Links`] table does, as returned by [`Links::put_outcome`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkPutOutcome {
+    /// No link with the same key and target exists yet
+    Created,
+    /// A link with the same key and target exists, but its interfaces or config differ
+    Updated,
+    /// An identical link is already present, so there is nothing to do
+    Unchanged,
+}
+
+impl Display for LinkPutOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Created => write!(f, "created"),
+            Self::Updated => write!(f, "updated"),
+            Self::Unchanged => write!(f, "unchanged"),
+        }
+    }
+}
+
+/// Error returned when building a [`Links`] table from links that conflict with each other. Every
+/// conflict found is reported, not just the first.
+#[derive(Debug)]
+pub struct LinksError {
+    pub conflicts: Vec<LinkConflict>,
+}
+
+impl Display for LinksError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "found {} conflicting link(s)", self.conflicts.len())?;
+        for conflict in &self.conflicts {
+            write!(f, "\n  {conflict}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for LinksError {}
+
+/// Error returned by [`Links::retarget`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RetargetError {
+    /// No link under the key covers only the given interfaces
+    NotFound {
+        key: LinkKey,
+        interfaces: Vec<String>,
+    },
+    /// The new target already has a link under the key. Retargeting onto it would leave two
+    /// links with the same key and target, which the host treats as one
+    TargetTaken { key: LinkKey, target: String },
+}
+
+impl Display for RetargetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound { key, interfaces } => write!(
+                f,
+                "no link {key} covers only interface(s) {}",
+                interfaces.join(", ")
+            ),
+            Self::TargetTaken { key, target } => {
+                write!(f, "link {key} already has a link to [{target}]")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RetargetError {}
+
+/// A link present in both tables of a [`LinksDiff`] whose interfaces or config differ
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct LinkChange {
+    pub before: Link,
+    pub after: Link,
+}
+
+impl Display for LinkChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "~ {} to [{}]",
+            LinkKey::from(&self.after),
+            self.after.target()
+        )?;
+        let fields = [
+            (
+                "interfaces",
+                self.before.interfaces(),
+                self.after.interfaces(),
+            ),
+            (
+                "source config",
+                self.before.source_config(),
+                self.after.source_config(),
+            ),
+            (
+                "target config",
+                self.before.target_config(),
+                self.after.target_config(),
+            ),
+        ];
+        for (field, before, after) in fields {
+            if before != after {
+                write!(
+                    f,
+                    "\n    {field}: [{}] => [{}]",
+                    before.join(", "),
+                    after.join(", ")
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The differences between two link tables, as returned by [`Links::diff`]. Links are matched by
+/// their [`LinkKey`] and target; a matched link whose interfaces or config differ is reported as
+/// changed rather than as a removal and an addition.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct LinksDiff {
+    /// Links only present in the other table
+    pub added: Vec<Link>,
+    /// Links only present in this table
+    pub removed: Vec<Link>,
+    pub changed: Vec<LinkChange>,
+}
+
+impl LinksDiff {
+    /// Whether the two tables hold the same links
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Human readable summary of the differences, meant for reviewing drift rather than parsing
+impl Display for LinksDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "No differences");
+        }
+        let mut sections = Vec::new();
+        if !self.added.is_empty() {
+            let lines = self
+                .added
+                .iter()
+                .map(|link| format!("  + {}", describe_link(link)));
+            sections.push(format!(
+                "Added ({}):\n{}",
+                self.added.len(),
+                lines.collect::<Vec<_>>().join("\n")
+            ));
+        }
+        if !self.removed.is_empty() {
+            let lines = self
+                .removed
+                .iter()
+                .map(|link| format!("  - {}", describe_link(link)));
+            sections.push(format!(
+                "Removed ({}):\n{}",
+                self.removed.len(),
+                lines.collect::<Vec<_>>().join("\n")
+            ));
+        }
+        if !self.changed.is_empty() {
+            let lines = self.changed.iter().map(|change| format!("  {change}"));
+            sections.push(format!(
+                "Changed ({}):\n{}",
+                self.changed.len(),
+                lines.collect::<Vec<_>>().join("\n")
+            ));
+        }
+        write!(f, "{}", sections.join("\n"))
+    }
+}
+
+fn describe_link(link: &Link) -> String {
+    format!(
+        "{} to [{}] on interface(s) {}",
+        LinkKey::from(link),
+        link.target(),
+        link.interfaces().join(", ")
+    )
+}
+
+/// The steps bringing a link table in line with a desired one, as returned by
+/// [`Links::apply_plan`]. Keys in `delete` are deleted first, then the links in `put` are put in
+/// order
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LinksApplyPlan {
+    /// The differences between the current and the desired table
+    pub diff: LinksDiff,
+    /// Keys to delete, as the host deletes every link under a key at once. The desired links under
+    /// these keys are part of `put`
+    pub delete: Vec<LinkKey>,
+    /// Links to put, in order
+    pub put: Vec<Link>,
+}
+
+impl LinksApplyPlan {
+    /// Whether applying the plan leaves the lattice untouched
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.delete.is_empty() && self.put.is_empty()
+    }
+}
+
+/// A table of [`Link`]s grouped by their [`LinkKey`]
+#[derive(Clone, Debug, Default)]
+pub struct Links {
+    inner: BTreeMap<LinkKey, Vec<Link>>,
+    /// Index from a link target to the keys holding at least one link to it, so lookups by target
+    /// don't scan the whole table. `inner` is the source of truth and this is updated alongside it
+    /// on every insert and removal
+    by_target: HashMap<String, BTreeSet<LinkKey>>,
+}
+
+impl Links {
+    /// Create an empty link table
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start building a validated link table
+    #[must_use]
+    pub fn builder() -> LinksBuilder {
+        LinksBuilder::default()
+    }
+
+    /// Add a link to the table under its key
+    pub fn insert(&mut self, link: Link) {
+        let key = LinkKey::from(&link);
+        self.by_target
+            .entry(link.target().to_string())
+            .or_default()
+            .insert(key.clone());
+        self.inner.entry(key).or_default().push(link);
+    }
+
+    /// Add a link to the table, unless its interfaces overlap with a link already stored under the
+    /// same key
+    pub fn try_insert(&mut self, link: Link) -> Result<(), Box<LinkConflict>> {
+        if let Some(conflict) = self.would_conflict(&link) {
+            return Err(Box::new(conflict));
+        }
+        self.insert(link);
+        Ok(())
+    }
+
+    /// Check whether inserting the given link would overlap with a link already stored under the
+    /// same key, returning the conflict if so
+    #[must_use]
+    pub fn would_conflict(&self, link: &Link) -> Option<LinkConflict> {
+        self.conflict_ignoring(link, None)
+    }
+
+    /// Work out whether putting the given link would create a new link, update the link stored
+    /// under the same key and target, or leave the table unchanged. A link that replaces the one
+    /// to its own target is only checked for overlaps against links to other targets
+    pub fn put_outcome(&self, link: &Link) -> Result<LinkPutOutcome, Box<LinkConflict>> {
+        let key = LinkKey::from(link);
+        let existing = self
+            .get(&key)
+            .iter()
+            .find(|existing| existing.target() == link.target());
+        if existing == Some(link) {
+            return Ok(LinkPutOutcome::Unchanged);
+        }
+        if let Some(conflict) = self.conflict_ignoring(link, Some(link.target())) {
+            return Err(Box::new(conflict));
+        }
+        Ok(if existing.is_some() {
+            LinkPutOutcome::Updated
+        } else {
+            LinkPutOutcome::Created
+        })
+    }
+
+    /// Put a link into the table, replacing the link stored under the same key and target if
+    /// there is one, and report what changed. The table is left as is on a conflict
+    pub fn put(&mut self, link: Link) -> Result<LinkPutOutcome, Box<LinkConflict>> {
+        let outcome = self.put_outcome(&link)?;
+        match outcome {
+            LinkPutOutcome::Unchanged => {}
+            LinkPutOutcome::Created => self.insert(link),
+            LinkPutOutcome::Updated => {
+                let key = LinkKey::from(&link);
+                if let Some(existing) = self
+                    .inner
+                    .get_mut(&key)
+                    .and_then(|links| links.iter_mut().find(|l| l.target() == link.target()))
+                {
+                    *existing = link;
+                }
+            }
+        }
+        Ok(outcome)
+    }
+
+    /// Point the links under `key` whose interfaces all lie in `interfaces` at `new_target`, e.g.
+    /// when the target component moves. Interfaces and config are kept as they are, so the links
+    /// under the key stay disjoint and sibling links covering other interfaces are untouched
+    pub fn retarget(
+        &mut self,
+        key: &LinkKey,
+        interfaces: &[String],
+        new_target: &str,
+    ) -> Result<(), RetargetError> {
+        let not_found = || RetargetError::NotFound {
+            key: key.clone(),
+            interfaces: interfaces.to_vec(),
+        };
+        let links = self.inner.get_mut(key).ok_or_else(not_found)?;
+        let matches = |link: &Link| {
+            link.target() != new_target && link.interfaces().iter().all(|i| interfaces.contains(i))
+        };
+        if !links.iter().any(matches) {
+            return Err(not_found());
+        }
+        if links.iter().any(|link| link.target() == new_target) {
+            return Err(RetargetError::TargetTaken {
+                key: key.clone(),
+                target: new_target.to_string(),
+            });
+        }
+
+        let mut old_targets = Vec::new();
+        for link in links.iter_mut().filter(|link| matches(link)) {
+            old_targets.push(link.target().to_string());
+            *link = Link::builder()
+                .source_id(link.source_id())
+                .target(new_target)
+                .name(link.name())
+                .wit_namespace(link.wit_namespace())
+                .wit_package(link.wit_package())
+                .interfaces(link.interfaces().clone())
+                .source_config(link.source_config().clone())
+                .target_config(link.target_config().clone())
+                .build()
+                .expect("a link rebuilt from a valid link should be valid");
+        }
+        self.by_target
+            .entry(new_target.to_string())
+            .or_default()
+            .insert(key.clone());
+        for target in old_targets {
+            self.unindex(key, &target);
+        }
+        Ok(())
+    }
+
+    /// Remove the given interfaces from the links stored under `key`, e.g. to delete part of a link
+    /// and keep the interfaces it shares a key with. Links left without interfaces are removed and
+    /// the others keep their target and config. Returns the links that were changed or removed, as
+    /// they were before, which is empty if no link under the key covers any of the interfaces
+    pub fn remove_interfaces(&mut self, key: &LinkKey, interfaces: &[String]) -> Vec<Link> {
+        let Some(links) = self.inner.get_mut(key) else {
+            return Vec::new();
+        };
+        let mut removed = Vec::new();
+        let mut kept = Vec::with_capacity(links.len());
+        for link in links.drain(..) {
+            if !link.interfaces().iter().any(|i| interfaces.contains(i)) {
+                kept.push(link);
+                continue;
+            }
+            let remaining = link
+                .interfaces()
+                .iter()
+                .filter(|i| !interfaces.contains(i))
+                .cloned()
+                .collect::<Vec<_>>();
+            if !remaining.is_empty() {
+                kept.push(
+                    Link::builder()
+                        .source_id(link.source_id())
+                        .target(link.target())
+                        .name(link.name())
+                        .wit_namespace(link.wit_namespace())
+                        .wit_package(link.wit_package())
+                        .interfaces(remaining)
+                        .source_config(link.source_config().clone())
+                        .target_config(link.target_config().clone())
+                        .build()
+                        .expect("a link rebuilt from a valid link should be valid"),
+                );
+            }
+            removed.push(link);
+        }
+        *links = kept;
+        if links.is_empty() {
+            self.inner.remove(key);
+        }
+        for link in &removed {
+            self.unindex(key, link.target());
+        }
+        removed
+    }
+
+    /// Find a link under the same key whose interfaces overlap with the given link, skipping links
+    /// to `skip_target`
+    fn conflict_ignoring(&self, link: &Link, skip_target: Option<&str>) -> Option<LinkConflict> {
+        let key = LinkKey::from(link);
+        self.get(&key)
+            .iter()
+            .filter(|existing| Some(existing.target()) != skip_target)
+            .find_map(|existing| {
+                let overlap = existing
+                    .interfaces()
+                    .iter()
+                    .filter(|i| link.interfaces().contains(i))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                (!overlap.is_empty()).then(|| LinkConflict {
+                    key: key.clone(),
+                    interfaces: overlap,
+                    existing_target: existing.target().to_string(),
+                    conflicting_target: link.target().to_string(),
+                })
+            })
+    }
+
+    /// Get all links stored under the given key
+    #[must_use]
+    pub fn get(&self, key: &LinkKey) -> &[Link] {
+        self.inner.get(key).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// The total number of links in the table
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inner.values().map(Vec::len).sum()
+    }
+
+    /// Whether the table contains no links
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Iterate over every link in the table, in key order
+    pub fn iter(&self) -> impl Iterator<Item = &Link> {
+        self.inner.values().flatten()
+    }
+
+    /// Iterate over every link with the given link name, whatever its source or WIT package, in
+    /// key order
+    pub fn iter_by_name<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Link> {
+        self.inner
+            .iter()
+            .filter(move |(key, _)| key.name == name)
+            .flat_map(|(_, links)| links)
+    }
+
+    /// Iterate over the distinct keys in the table, without touching the links stored under them
+    pub fn iter_keys(&self) -> impl Iterator<Item = &LinkKey> {
+        self.inner.keys()
+    }
+
+    /// An estimate of the heap memory held by the table, in bytes, for diagnosing hosts with very
+    /// large link tables.
+    ///
+    /// This counts the keys, the link vectors and the strings inside every link, plus a rough
+    /// per-entry allowance for the map's own nodes. It does not account for allocator overhead or
+    /// unused string capacity, so treat it as an order-of-magnitude figure rather than an exact
+    /// measurement.
+    #[must_use]
+    pub fn estimated_memory_bytes(&self) -> usize {
+        fn strings(values: &[String]) -> usize {
+            size_of_val(values) + values.iter().map(String::len).sum::<usize>()
+        }
+
+        // BTreeMap nodes hold up to 11 entries alongside some bookkeeping; a key/value pair plus a
+        // pointer's worth of overhead is close enough for an estimate
+        const NODE_OVERHEAD_BYTES: usize = size_of::<usize>();
+
+        fn key_bytes(key: &LinkKey) -> usize {
+            size_of::<LinkKey>()
+                + key.source_id.len()
+                + key.name.len()
+                + key.wit_namespace.len()
+                + key.wit_package.len()
+        }
+
+        let index_bytes = self
+            .by_target
+            .iter()
+            .map(|(target, keys)| {
+                size_of::<String>()
+                    + target.len()
+                    + keys
+                        .iter()
+                        .map(|key| key_bytes(key) + NODE_OVERHEAD_BYTES)
+                        .sum::<usize>()
+                    + NODE_OVERHEAD_BYTES
+            })
+            .sum::<usize>();
+
+        index_bytes
+            + self
+                .inner
+                .iter()
+                .map(|(key, links)| {
+                    let key_bytes = key_bytes(key);
+                    let links_bytes = size_of::<Vec<Link>>()
+                        + links.capacity() * size_of::<Link>()
+                        + links
+                            .iter()
+                            .map(|link| {
+                                link.source_id().len()
+                                    + link.target().len()
+                                    + link.name().len()
+                                    + link.wit_namespace().len()
+                                    + link.wit_package().len()
+                                    + strings(link.interfaces())
+                                    + strings(link.source_config())
+                                    + strings(link.target_config())
+                            })
+                            .sum::<usize>();
+                    key_bytes + links_bytes + NODE_OVERHEAD_BYTES
+                })
+                .sum::<usize>()
+    }
+
+    /// Remove and return every link whose source is the given component, in key order
+    pub fn drain_for_source(&mut self, source_id: &str) -> Vec<Link> {
+        let keys = self
+            .inner
+            .keys()
+            .filter(|k| k.source_id == source_id)
+            .cloned()
+            .collect::<Vec<_>>();
+        let drained = keys
+            .into_iter()
+            .filter_map(|k| self.inner.remove(&k).map(|links| (k, links)))
+            .collect::<Vec<_>>();
+        for (key, links) in &drained {
+            for link in links {
+                self.unindex(key, link.target());
+            }
+        }
+        drained.into_iter().flat_map(|(_, links)| links).collect()
+    }
+
+    /// Iterate over every link whose target is the given component or provider, in key order.
+    /// This only visits the keys that hold links to the target
+    pub fn iter_for_target<'a>(&'a self, target: &'a str) -> impl Iterator<Item = &'a Link> {
+        self.by_target
+            .get(target)
+            .into_iter()
+            .flatten()
+            .flat_map(|key| self.get(key))
+            .filter(move |link| link.target() == target)
+    }
+
+    /// Remove and return every link whose target is the given component or provider, in key
+    /// order. Other links stored under the same keys are kept
+    pub fn drain_for_target(&mut self, target: &str) -> Vec<Link> {
+        let Some(keys) = self.by_target.remove(target) else {
+            return Vec::new();
+        };
+        let mut drained = Vec::new();
+        for key in keys {
+            let Some(links) = self.inner.get_mut(&key) else {
+                continue;
+            };
+            let (matching, rest) = std::mem::take(links)
+                .into_iter()
+                .partition::<Vec<_>, _>(|link| link.target() == target);
+            drained.extend(matching);
+            if rest.is_empty() {
+                self.inner.remove(&key);
+            } else {
+                *links = rest;
+            }
+        }
+        drained
+    }
+
+    /// Keep only the links for which `keep` returns `true`
+    pub fn retain(&mut self, mut keep: impl FnMut(&Link) -> bool) {
+        let mut removed = Vec::new();
+        self.inner.retain(|key, links| {
+            links.retain(|link| {
+                let kept = keep(link);
+                if !kept {
+                    removed.push((key.clone(), link.target().to_string()));
+                }
+                kept
+            });
+            !links.is_empty()
+        });
+        for (key, target) in removed {
+            self.unindex(&key, &target);
+        }
+    }
+
+    /// Remove and return every link whose source or target isn't in `live_ids`, the IDs of the
+    /// components and providers currently running, in key order. Used to garbage collect links
+    /// left behind by components that are gone
+    pub fn prune_dead(&mut self, live_ids: &HashSet<String>) -> Vec<Link> {
+        let mut pruned = Vec::new();
+        self.retain(|link| {
+            let live = live_ids.contains(link.source_id()) && live_ids.contains(link.target());
+            if !live {
+                pruned.push(link.clone());
+            }
+            live
+        });
+        pruned
+    }
+
+    /// Clone out the links for which `filter` returns `true`, in key order, leaving the table as
+    /// is. The read-only counterpart of [`Links::retain`], e.g. to snapshot only managed links
+    pub fn export_where<F: Fn(&Link) -> bool>(&self, filter: F) -> Vec<Link> {
+        self.iter().filter(|link| filter(link)).cloned().collect()
+    }
+
+    /// Compare this table against `other`, e.g. a baseline snapshot against the links currently
+    /// in the lattice. Added links are those only in `other`, removed links those only in `self`
+    #[must_use]
+    pub fn diff(&self, other: &Links) -> LinksDiff {
+        let mut diff = LinksDiff::default();
+        for link in self.iter() {
+            let key = LinkKey::from(link);
+            match other.get(&key).iter().find(|l| l.target() == link.target()) {
+                None => diff.removed.push(link.clone()),
+                Some(after) if after != link => diff.changed.push(LinkChange {
+                    before: link.clone(),
+                    after: after.clone(),
+                }),
+                Some(_) => {}
+            }
+        }
+        diff.added = other
+            .iter()
+            .filter(|link| {
+                !self
+                    .get(&LinkKey::from(*link))
+                    .iter()
+                    .any(|l| l.target() == link.target())
+            })
+            .cloned()
+            .collect();
+        diff
+    }
+
+    /// Work out how to bring this table, e.g. the links in the lattice, in line with `desired`.
+    /// Missing and changed links are put, with changed links first so interfaces they give up are
+    /// free for new links. Links only in this table are deleted if `prune` is set and kept
+    /// otherwise. The plan is replayed on a copy of the table, so a desired link overlapping with
+    /// a link that is kept is reported as a conflict before anything is sent to the lattice
+    pub fn apply_plan(
+        &self,
+        desired: &Links,
+        prune: bool,
+    ) -> Result<LinksApplyPlan, Box<LinkConflict>> {
+        let diff = self.diff(desired);
+        let delete = if prune {
+            diff.removed
+                .iter()
+                .map(LinkKey::from)
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect::<Vec<_>>()
+        } else {
+            Vec::new()
+        };
+        let untouched = |link: &&Link| !delete.contains(&LinkKey::from(*link));
+        let mut put = delete
+            .iter()
+            .flat_map(|key| desired.get(key).iter().cloned())
+            .collect::<Vec<_>>();
+        put.extend(
+            diff.changed
+                .iter()
+                .map(|change| &change.after)
+                .filter(untouched)
+                .cloned(),
+        );
+        put.extend(diff.added.iter().filter(untouched).cloned());
+
+        let mut after = self.clone();
+        after.retain(|link| !delete.contains(&LinkKey::from(link)));
+        for link in &put {
+            after.put(link.clone())?;
+        }
+        Ok(LinksApplyPlan { diff, delete, put })
+    }
+
+    /// Drop `key` from the index of `target` once no link under the key points at the target
+    fn unindex(&mut self, key: &LinkKey, target: &str) {
+        if self.get(key).iter().any(|link| link.target() == target) {
+            return;
+        }
+        if let Some(keys) = self.by_target.get_mut(target) {
+            keys.remove(key);
+            if keys.is_empty() {
+                self.by_target.remove(target);
+            }
+        }
+    }
+}
+
+impl FromIterator<Link> for Links {
+    fn from_iter<T: IntoIterator<Item = Link>>(iter: T) -> Self {
+        let mut links = Self::new();
+        for link in iter {
+            links.insert(link);
+        }
+        links
+    }
+}
+
+/// Accumulates links and produces a validated [`Links`] table, reporting every conflict at once
+#[derive(Clone, Debug, Default)]
+pub struct LinksBuilder {
+    links: Vec<Link>,
+}
+
+impl LinksBuilder {
+    /// Add a single link
+    #[must_use]
+    pub fn link(mut self, link: Link) -> Self {
+        self.links.push(link);
+        self
+    }
+
+    /// Add several links
+    #[must_use]
+    pub fn links(mut self, links: impl IntoIterator<Item = Link>) -> Self {
+        self.links.extend(links);
+        self
+    }
+
+    /// Build the table, failing with all conflicts found if any links overlap
+    pub fn build(self) -> Result<Links, LinksError> {
+        let mut table = Links::new();
+        let mut conflicts = Vec::new();
+        for link in self.links {
+            if let Err(conflict) = table.try_insert(link) {
+                conflicts.push(*conflict);
+            }
+        }
+        if conflicts.is_empty() {
+            Ok(table)
+        } else {
+            Err(LinksError { conflicts })
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn link(source: &str, target: &str, package: &str, interfaces: &[&str]) -> Link {
+        Link::builder()
+            .source_id(source)
+            .target(target)
+            .name("default")
+            .wit_namespace("wasi")
+            .wit_package(package)
+            .interfaces(interfaces.iter().map(ToString::to_string).collect())
+            .build()
+            .expect("should be able to build link")
+    }
+
+    #[test]
+    fn iter_keys_yields_distinct_keys() {
+        let links = Links::from_iter([
+            link("echo", "kv-redis", "keyvalue", &["store"]),
+            link("echo", "kv-nats", "keyvalue", &["atomics"]),
+            link("echo", "httpclient", "http", &["outgoing-handler"]),
+            link("other", "kv-redis", "keyvalue", &["store"]),
+        ]);
+
+        assert_eq!(links.len(), 4);
+        let keys = links
+            .iter_keys()
+            .map(|k| (k.source_id(), k.name(), k.wit_namespace(), k.wit_package()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            vec![
+                ("echo", "default", "wasi", "http"),
+                ("echo", "default", "wasi", "keyvalue"),
+                ("other", "default", "wasi", "keyvalue"),
+            ]
+        );
+
+        let key = links
+            .iter_keys()
+            .find(|k| k.source_id() == "echo" && k.wit_package() == "keyvalue")
+            .expect("should find key");
+        assert_eq!(links.get(key).len(), 2);
+    }
+
+    #[test]
+    fn iter_by_name_matches_only_that_name() {
+        let named = |source: &str, target: &str, name: &str| {
+            Link::builder()
+                .source_id(source)
+                .target(target)
+                .name(name)
+                .wit_namespace("wasi")
+                .wit_package("keyvalue")
+                .interfaces(vec!["store".to_string()])
+                .build()
+                .expect("should be able to build link")
+        };
+        let links = Links::from_iter([
+            link("echo", "kv-redis", "keyvalue", &["store"]),
+            link("other", "httpclient", "http", &["outgoing-handler"]),
+            named("echo", "kv-vault", "secure"),
+            named("third", "kv-vault", "secure"),
+        ]);
+
+        assert_eq!(
+            links
+                .iter_by_name("default")
+                .map(Link::target)
+                .collect::<Vec<_>>(),
+            vec!["kv-redis", "httpclient"]
+        );
+        assert_eq!(
+            links
+                .iter_by_name("secure")
+                .map(Link::source_id)
+                .collect::<Vec<_>>(),
+            vec!["echo", "third"]
+        );
+        assert_eq!(links.iter_by_name("missing").count(), 0);
+    }
+
+    #[test]
+    fn export_where_leaves_the_table_unchanged() {
+        let tagged = |source: &str, target: &str| {
+            Link::builder()
+                .source_id(source)
+                .target(target)
+                .name("default")
+                .wit_namespace("wasi")
+                .wit_package("keyvalue")
+                .interfaces(vec!["store".to_string()])
+                .source_config(vec!["managed".to_string()])
+                .build()
+                .expect("should be able to build link")
+        };
+        let links = Links::from_iter([
+            link("echo", "kv-redis", "keyvalue", &["store"]),
+            tagged("echo", "kv-vault"),
+            link("other", "httpclient", "http", &["outgoing-handler"]),
+            tagged("third", "kv-vault"),
+        ]);
+        let before = links.iter().cloned().collect::<Vec<_>>();
+
+        let managed =
+            links.export_where(|link| link.source_config().iter().any(|c| c == "managed"));
+        assert_eq!(
+            managed.iter().map(Link::source_id).collect::<Vec<_>>(),
+            vec!["echo", "third"]
+        );
+        assert!(managed.iter().all(|link| link.target() == "kv-vault"));
+        assert!(links.export_where(|_| false).is_empty());
+
+        assert_eq!(links.iter().cloned().collect::<Vec<_>>(), before);
+        assert_eq!(links.iter_for_target("kv-vault").count(), 2);
+    }
+
+    #[test]
+    fn prune_dead_removes_only_links_to_dead_components() {
+        let mut links = Links::from_iter([
+            link("echo", "kv-redis", "keyvalue", &["store"]),
+            link("echo", "httpclient", "http", &["outgoing-handler"]),
+            link("gone", "kv-redis", "blobstore", &["blobstore"]),
+            link("other", "kv-redis", "keyvalue", &["store"]),
+        ]);
+        let live = HashSet::from(["echo", "other", "kv-redis"].map(String::from));
+
+        let pruned = links.prune_dead(&live);
+        assert_eq!(
+            pruned
+                .iter()
+                .map(|l| (l.source_id(), l.target()))
+                .collect::<Vec<_>>(),
+            vec![("echo", "httpclient"), ("gone", "kv-redis")]
+        );
+        assert_eq!(
+            links
+                .iter()
+                .map(|l| (l.source_id(), l.target()))
+                .collect::<Vec<_>>(),
+            vec![("echo", "kv-redis"), ("other", "kv-redis")]
+        );
+        assert_eq!(links.iter_for_target("httpclient").count(), 0);
+        assert_eq!(links.iter_for_target("kv-redis").count(), 2);
+        assert!(links.prune_dead(&live).is_empty());
+    }
+
+    #[test]
+    fn removing_interfaces_keeps_the_rest_of_the_key() {
+        let mut links = Links::from_iter([
+            link("echo", "kv-redis", "keyvalue", &["store", "atomics"]),
+            link("echo", "kv-nats", "keyvalue", &["batch"]),
+            link("echo", "httpclient", "http", &["outgoing-handler"]),
+        ]);
+        let key = LinkKey::from(&link("echo", "kv-redis", "keyvalue", &[]));
+
+        let removed = links.remove_interfaces(&key, &["atomics".to_string()]);
+        assert_eq!(
+            removed,
+            vec![link("echo", "kv-redis", "keyvalue", &["store", "atomics"])]
+        );
+        assert_eq!(
+            links.get(&key),
+            [
+                link("echo", "kv-redis", "keyvalue", &["store"]),
+                link("echo", "kv-nats", "keyvalue", &["batch"]),
+            ]
+        );
+
+        let removed = links.remove_interfaces(&key, &["batch".to_string()]);
+        assert_eq!(
+            removed,
+            vec![link("echo", "kv-nats", "keyvalue", &["batch"])]
+        );
+        assert_eq!(links.iter_for_target("kv-nats").count(), 0);
+        assert_eq!(links.len(), 2);
+
+        assert!(links
+            .remove_interfaces(&key, &["missing".to_string()])
+            .is_empty());
+        links.remove_interfaces(&key, &["store".to_string()]);
+        assert!(links.get(&key).is_empty());
+        assert_eq!(links.iter_keys().count(), 1);
+    }
+
+    #[test]
+    fn apply_plan_puts_missing_links_and_prunes_extras() {
+        let current = Links::from_iter([
+            link("echo", "kv-redis", "keyvalue", &["store", "atomics"]),
+            link("echo", "kv-nats", "keyvalue", &["batch"]),
+            link("echo", "httpclient", "http", &["outgoing-handler"]),
+        ]);
+        let desired = Links::from_iter([
+            link("echo", "kv-redis", "keyvalue", &["store"]),
+            link("echo", "kv-vault", "keyvalue", &["atomics"]),
+            link("echo", "httpclient", "http", &["outgoing-handler"]),
+            link("httpserver", "echo", "http", &["incoming-handler"]),
+        ]);
+        let keyvalue = LinkKey::from(&link("echo", "kv-redis", "keyvalue", &[]));
+
+        // Without pruning, the link to kv-nats stays and the new links are put around it
+        let plan = current.apply_plan(&desired, false).unwrap();
+        assert!(plan.delete.is_empty());
+        assert_eq!(
+            plan.put,
+            vec![
+                link("echo", "kv-redis", "keyvalue", &["store"]),
+                link("echo", "kv-vault", "keyvalue", &["atomics"]),
+                link("httpserver", "echo", "http", &["incoming-handler"]),
+            ]
+        );
+        assert_eq!(
+            plan.diff.removed,
+            vec![link("echo", "kv-nats", "keyvalue", &["batch"])]
+        );
+
+        // Pruning resets the whole key, so every desired link under it is put back
+        let plan = current.apply_plan(&desired, true).unwrap();
+        assert_eq!(plan.delete, vec![keyvalue]);
+        assert_eq!(
+            plan.put,
+            vec![
+                link("echo", "kv-redis", "keyvalue", &["store"]),
+                link("echo", "kv-vault", "keyvalue", &["atomics"]),
+                link("httpserver", "echo", "http", &["incoming-handler"]),
+            ]
+        );
+
+        // A desired link can't take interfaces from a link that is kept
+        let desired = Links::from_iter([link("echo", "kv-vault", "keyvalue", &["batch"])]);
+        let conflict = current.apply_plan(&desired, false).unwrap_err();
+        assert_eq!(conflict.existing_target, "kv-nats");
+        assert!(current.apply_plan(&desired, true).is_ok());
+
+        assert!(current.apply_plan(&current, true).unwrap().is_empty());
+    }
+
+    #[test]
+    fn drain_for_source_removes_only_that_source() {
+        let mut links = Links::from_iter([
+            link("echo", "kv-redis", "keyvalue", &["store"]),
+            link("echo", "httpclient", "http", &["outgoing-handler"]),
+            link("other", "kv-redis", "keyvalue", &["store"]),
+        ]);
+
+        let drained = links.drain_for_source("echo");
+        assert_eq!(
+            drained.iter().map(Link::target).collect::<Vec<_>>(),
+            vec!["httpclient", "kv-redis"]
+        );
+        assert_eq!(links.len(), 1);
+        assert_eq!(links.iter_keys().count(), 1);
+        assert!(links.drain_for_source("echo").is_empty());
+
+        links.drain_for_source("other");
+        assert!(links.is_empty());
+    }
+
+    #[test]
+    fn memory_estimate_grows_linearly() {
+        assert_eq!(Links::new().estimated_memory_bytes(), 0);
+
+        let table = |count: usize| {
+            Links::from_iter((0..count).map(|i| {
+                link(
+                    &format!("component-{i:05}"),
+                    "kv-redis",
+                    "keyvalue",
+                    &["store", "atomics"],
+                )
+            }))
+        };
+        let small = table(100).estimated_memory_bytes();
+        let large = table(1_000).estimated_memory_bytes();
+        assert!(small > 0);
+        // Every link here is the same size, so 10x the links should be close to 10x the memory
+        let ratio = large as f64 / small as f64;
+        assert!(
+            (9.0..=11.0).contains(&ratio),
+            "expected roughly linear growth, got a ratio of {ratio}"
+        );
+    }
+
+    /// Rebuild the target index from scratch and check it matches the one maintained incrementally
+    fn assert_index_consistent(links: &Links) {
+        let mut expected: HashMap<String, BTreeSet<LinkKey>> = HashMap::new();
+        for link in links.iter() {
+            expected
+                .entry(link.target().to_string())
+                .or_default()
+                .insert(LinkKey::from(link));
+        }
+        assert_eq!(links.by_target, expected);
+    }
+
+    #[test]
+    fn target_index_stays_consistent() {
+        let mut links = Links::from_iter([
+            link("echo", "kv-redis", "keyvalue", &["store"]),
+            link("echo", "kv-nats", "keyvalue", &["atomics"]),
+            link("echo", "httpclient", "http", &["outgoing-handler"]),
+            link("other", "kv-redis", "keyvalue", &["store"]),
+            link("third", "kv-redis", "keyvalue", &["store"]),
+        ]);
+        assert_index_consistent(&links);
+        assert_eq!(
+            links
+                .iter_for_target("kv-redis")
+                .map(Link::source_id)
+                .collect::<Vec<_>>(),
+            vec!["echo", "other", "third"]
+        );
+
+        links.insert(link("other", "kv-nats", "keyvalue", &["atomics"]));
+        assert_index_consistent(&links);
+
+        // Draining a target keeps other links under the same key
+        let drained = links.drain_for_target("kv-nats");
+        assert_eq!(drained.len(), 2);
+        assert_index_consistent(&links);
+        assert_eq!(links.iter_for_target("kv-nats").count(), 0);
+        assert_eq!(links.len(), 4);
+
+        links.drain_for_source("echo");
+        assert_index_consistent(&links);
+        assert_eq!(links.iter_for_target("httpclient").count(), 0);
+
+        links.retain(|link| link.source_id() != "third");
+        assert_index_consistent(&links);
+        assert_eq!(
+            links
+                .iter_for_target("kv-redis")
+                .map(Link::source_id)
+                .collect::<Vec<_>>(),
+            vec!["other"]
+        );
+
+        links.retain(|_| false);
+        assert!(links.is_empty());
+        assert_index_consistent(&links);
+        assert!(links.drain_for_target("kv-redis").is_empty());
+    }
+
+    #[test]
+    fn target_lookups_only_visit_matching_keys() {
+        // Many sources linked to a handful of targets, as on a host with a large link table
+        let links = Links::from_iter((0..10_000).map(|i| {
+            link(
+                &format!("component-{i:05}"),
+                &format!("provider-{}", i % 10),
+                "keyvalue",
+                &["store"],
+            )
+        }));
+        assert_index_consistent(&links);
+
+        // The index only holds the keys of the target's links, so that is all a lookup visits
+        for i in 0..10 {
+            let target = format!("provider-{i}");
+            assert_eq!(links.by_target[&target].len(), 1_000);
+            assert_eq!(links.iter_for_target(&target).count(), 1_000);
+        }
+        assert!(!links.by_target.contains_key("missing"));
+        assert_eq!(links.iter_for_target("missing").count(), 0);
+    }
+
+    #[test]
+    fn builder_reports_every_conflict() {
+        let err = Links::builder()
+            .link(link("echo", "kv-redis", "keyvalue", &["store", "atomics"]))
+            .link(link("echo", "kv-nats", "keyvalue", &["atomics"]))
+            .link(link("echo", "httpclient", "http", &["outgoing-handler"]))
+            .link(link("echo", "other-http", "http", &["outgoing-handler"]))
+            .link(link("echo", "kv-vault", "keyvalue", &["batch"]))
+            .build()
+            .expect_err("conflicting links should fail to build");
+
+        assert_eq!(err.conflicts.len(), 2);
+        assert_eq!(err.conflicts[0].key.wit_package(), "keyvalue");
+        assert_eq!(err.conflicts[0].interfaces, vec!["atomics"]);
+        assert_eq!(err.conflicts[0].existing_target, "kv-redis");
+        assert_eq!(err.conflicts[0].conflicting_target, "kv-nats");
+        assert_eq!(err.conflicts[1].key.wit_package(), "http");
+        assert_eq!(err.conflicts[1].conflicting_target, "other-http");
+
+        let links = Links::builder()
+            .link(link("echo", "kv-redis", "keyvalue", &["store"]))
+            .link(link("echo", "kv-nats", "keyvalue", &["atomics"]))
+            .build()
+            .expect("disjoint links should build");
+        assert_eq!(links.len(), 2);
+    }
+
+    #[test]
+    fn put_reports_created_updated_unchanged_and_conflicts() {
+        let mut links = Links::from_iter([link("echo", "kv-redis", "keyvalue", &["store"])]);
+
+        assert_eq!(
+            links.put(link("echo", "kv-redis", "keyvalue", &["store"])),
+            Ok(LinkPutOutcome::Unchanged)
+        );
+        assert_eq!(
+            links.put(link("echo", "httpclient", "http", &["outgoing-handler"])),
+            Ok(LinkPutOutcome::Created)
+        );
+        assert_eq!(
+            links.put(link("echo", "kv-redis", "keyvalue", &["store", "atomics"])),
+            Ok(LinkPutOutcome::Updated)
+        );
+        assert_eq!(links.len(), 2);
+        assert_eq!(
+            links.get(&LinkKey::from(&link("echo", "kv-redis", "keyvalue", &[])))[0].interfaces(),
+            &["store", "atomics"]
+        );
+
+        let conflict = links
+            .put(link("echo", "kv-nats", "keyvalue", &["atomics", "batch"]))
+            .expect_err("overlapping link to another target should conflict");
+        assert_eq!(conflict.existing_target, "kv-redis");
+        assert_eq!(conflict.interfaces, vec!["atomics".to_string()]);
+        assert_eq!(links.len(), 2);
+        assert_index_consistent(&links);
+    }
+
+    #[test]
+    fn retarget_moves_only_the_matching_link() {
+        let mut links = Links::from_iter([
+            link("echo", "kv-redis", "keyvalue", &["store"]),
+            link("echo", "kv-nats", "keyvalue", &["atomics"]),
+        ]);
+        let key = LinkKey::from(&link("echo", "kv-redis", "keyvalue", &[]));
+
+        links
+            .retarget(&key, &["store".to_string()], "kv-vault")
+            .expect("should retarget the store link");
+        assert_eq!(
+            links.get(&key),
+            &[
+                link("echo", "kv-vault", "keyvalue", &["store"]),
+                link("echo", "kv-nats", "keyvalue", &["atomics"]),
+            ]
+        );
+        assert_eq!(links.iter_for_target("kv-redis").count(), 0);
+        assert_eq!(links.iter_for_target("kv-vault").count(), 1);
+        assert_index_consistent(&links);
+
+        assert_eq!(
+            links.retarget(&key, &["batch".to_string()], "kv-vault"),
+            Err(RetargetError::NotFound {
+                key: key.clone(),
+                interfaces: vec!["batch".to_string()],
+            })
+        );
+        assert_eq!(
+            links.retarget(&key, &["atomics".to_string()], "kv-vault"),
+            Err(RetargetError::TargetTaken {
+                key: key.clone(),
+                target: "kv-vault".to_string(),
+            })
+        );
+        assert_index_consistent(&links);
+    }
+
+    #[test]
+    fn diff_reports_drift_from_baseline() {
+        let baseline = Links::from_iter([
+            link("echo", "kv-redis", "keyvalue", &["store"]),
+            link("echo", "httpclient", "http", &["outgoing-handler"]),
+            link("other", "kv-redis", "keyvalue", &["store"]),
+        ]);
+        let current = Links::from_iter([
+            link("echo", "kv-redis", "keyvalue", &["store", "atomics"]),
+            link("other", "kv-redis", "keyvalue", &["store"]),
+            link("other", "blobstore-fs", "blobstore", &["blobstore"]),
+        ]);
+
+        let diff = baseline.diff(&current);
+        assert_eq!(
+            diff.added.iter().map(Link::target).collect::<Vec<_>>(),
+            vec!["blobstore-fs"]
+        );
+        assert_eq!(
+            diff.removed.iter().map(Link::target).collect::<Vec<_>>(),
+            vec!["httpclient"]
+        );
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].before.interfaces(), &vec!["store"]);
+        assert_eq!(
+            diff.changed[0].after.interfaces(),
+            &vec!["store", "atomics"]
+        );
+
+        assert_eq!(
+            diff.to_string(),
+            "Added (1):\n  + other -> wasi:blobstore (default) to [blobstore-fs] on interface(s) blobstore\n\
+             Removed (1):\n  - echo -> wasi:http (default) to [httpclient] on interface(s) outgoing-handler\n\
+             Changed (1):\n  ~ echo -> wasi:keyvalue (default) to [kv-redis]\n    interfaces: [store] => [store, atomics]"
+        );
+
+        let same = baseline.diff(&baseline);
+        assert!(same.is_empty());
+        assert_eq!(same.to_string(), "No differences");
+    }
+}
@@ -20,6 +20,9 @@ use wasm_pkg_core::config::{Config as PackageConfig, Override};
 use wasmcloud_control_interface::RegistryCredential;
 use wasmcloud_core::{parse_wit_package_name, WitFunction, WitInterface, WitNamespace, WitPackage};
 
+mod workspace;
+pub use workspace::*;
+
 #[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum LanguageConfig {
@@ -65,9 +68,15 @@ pub struct ComponentConfig {
     /// File path `wash` can use to find the built artifact. Defaults to `./build/[name].wasm`
     pub build_artifact: Option<PathBuf>,
     /// Optional build override command to run instead of attempting to use the native language
-    /// toolchain to build. Keep in mind that `wash` expects for the built artifact to be located
-    /// under the `build` directory of the project root unless overridden by `build_artifact`.
+    /// toolchain to build, e.g. to build components in languages `wash` has no built-in support for.
+    /// Keep in mind that `wash` expects for the built artifact to be located under the `build`
+    /// directory of the project root unless overridden by `build_artifact`. An artifact that isn't
+    /// a component yet is turned into one according to `wasm_target`: the `wit_world` is embedded
+    /// into a core module, then it is adapted to WASI preview 2.
     pub build_command: Option<String>,
+    /// Environment variables to set for the `build_command`
+    #[serde(default)]
+    pub build_env: HashMap<String, String>,
     /// File path the built and signed component should be written to. Defaults to `./build/[name]_s.wasm`
     pub destination: Option<PathBuf>,
 }
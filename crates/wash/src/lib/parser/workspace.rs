@@ -0,0 +1,296 @@
+//! Workspaces of several wasmCloud projects, described by a `wasmcloud.toml` with a `[workspace]`
+//! table listing the directories of its members, each with its own `wasmcloud.toml`:
+//!
+//! ```toml
+//! [workspace]
+//! # Optional, defaults to the name of the workspace directory
+//! name = "petclinic"
+//! members = ["components/ui", "components/api", "providers/db"]
+//! ```
+//!
+//! Members can import the WIT packages of other members. Those imports are resolved from the WIT
+//! directory of the member that defines the package, and members are built after the members
+//! whose packages they import.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use wasm_pkg_core::config::Override;
+use wit_parser::UnresolvedPackageGroup;
+
+use super::{load_config, ProjectConfig};
+
+/// A workspace of wasmCloud projects
+#[derive(Debug, Clone)]
+pub struct WorkspaceConfig {
+    /// Name of the workspace, used to name its combined deployment manifest
+    pub name: String,
+    /// The directory of the workspace `wasmcloud.toml`
+    pub dir: PathBuf,
+    /// The members of the workspace, in the order they are listed
+    pub members: Vec<ProjectConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WorkspaceDotToml {
+    workspace: WorkspaceSpec,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WorkspaceSpec {
+    name: Option<String>,
+    members: Vec<PathBuf>,
+}
+
+/// The WIT package a member defines and the packages it imports, as `namespace:name`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemberWit {
+    pub package: Option<String>,
+    pub imports: HashSet<String>,
+}
+
+/// Load the workspace at the given path, a `wasmcloud.toml` file or the directory containing it,
+/// defaulting to the current directory. Returns `None` if the file describes a single project
+/// rather than a workspace
+pub async fn load_workspace_config(opt_path: Option<PathBuf>) -> Result<Option<WorkspaceConfig>> {
+    let path = match opt_path {
+        Some(p) => p,
+        None => std::env::current_dir().context("failed to get current directory")?,
+    };
+    let toml_path = if path.is_dir() {
+        path.join("wasmcloud.toml")
+    } else {
+        path
+    };
+    let Ok(contents) = tokio::fs::read_to_string(&toml_path).await else {
+        // Leave reporting a missing or unreadable file to loading it as a project
+        return Ok(None);
+    };
+    let value: toml::Value = toml::from_str(&contents)
+        .with_context(|| format!("failed to parse [{}]", toml_path.display()))?;
+    if value.get("workspace").is_none() {
+        return Ok(None);
+    }
+    let spec = value
+        .try_into::<WorkspaceDotToml>()
+        .with_context(|| format!("invalid workspace in [{}]", toml_path.display()))?
+        .workspace;
+    if spec.members.is_empty() {
+        bail!("workspace in [{}] has no members", toml_path.display());
+    }
+
+    let dir = toml_path
+        .parent()
+        .context("could not get parent path of wasmcloud.toml file")?
+        .canonicalize()
+        .context("failed to canonicalize workspace path")?;
+    let mut members = Vec::with_capacity(spec.members.len());
+    for member in &spec.members {
+        let config = load_config(Some(dir.join(member)), Some(true))
+            .await
+            .with_context(|| format!("failed to load workspace member [{}]", member.display()))?;
+        if let Some(other) = members
+            .iter()
+            .find(|m: &&ProjectConfig| m.common.name == config.common.name)
+        {
+            bail!(
+                "workspace members [{}] and [{}] are both named [{}]",
+                other.wasmcloud_toml_dir.display(),
+                config.wasmcloud_toml_dir.display(),
+                config.common.name
+            );
+        }
+        members.push(config);
+    }
+
+    let name = spec.name.unwrap_or_else(|| {
+        dir.file_name().map_or_else(
+            || "workspace".to_string(),
+            |n| n.to_string_lossy().to_string(),
+        )
+    });
+    Ok(Some(WorkspaceConfig { name, dir, members }))
+}
+
+/// The WIT package defined by the WIT directory of a member and the packages it imports. Members
+/// without a WIT directory define and import nothing
+pub fn member_wit(wit_dir: &Path) -> Result<MemberWit> {
+    if !wit_dir.is_dir() {
+        return Ok(MemberWit::default());
+    }
+    let group = UnresolvedPackageGroup::parse_dir(wit_dir)
+        .with_context(|| format!("failed to parse WIT in [{}]", wit_dir.display()))?;
+    let package_name = |name: &wit_parser::PackageName| format!("{}:{}", name.namespace, name.name);
+    Ok(MemberWit {
+        package: Some(package_name(&group.main.name)),
+        imports: group.main.foreign_deps.keys().map(package_name).collect(),
+    })
+}
+
+/// The order to build the members of a workspace in, as indexes into `wits`, so that each member
+/// comes after the members whose WIT packages it imports. Otherwise members keep their order
+pub fn workspace_build_order(names: &[&str], wits: &[MemberWit]) -> Result<Vec<usize>> {
+    let packages = wits
+        .iter()
+        .enumerate()
+        .filter_map(|(i, wit)| wit.package.as_deref().map(|p| (p, i)))
+        .collect::<HashMap<_, _>>();
+    let depends_on = wits
+        .iter()
+        .enumerate()
+        .map(|(i, wit)| {
+            wit.imports
+                .iter()
+                .filter_map(|import| packages.get(import.as_str()).copied())
+                .filter(|dep| *dep != i)
+                .collect::<HashSet<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut order = Vec::with_capacity(wits.len());
+    while order.len() < wits.len() {
+        let next = (0..wits.len())
+            .find(|i| !order.contains(i) && depends_on[*i].iter().all(|dep| order.contains(dep)));
+        let Some(next) = next else {
+            let cycle = (0..wits.len())
+                .filter(|i| !order.contains(i))
+                .map(|i| names[i])
+                .collect::<Vec<_>>();
+            bail!(
+                "workspace members [{}] import each other's WIT packages in a cycle",
+                cycle.join(", ")
+            );
+        };
+        order.push(next);
+    }
+    Ok(order)
+}
+
+/// Resolve the imports of members from the WIT packages of other members, by adding package
+/// overrides pointing at the WIT directory of the member that defines the package. Overrides the
+/// member already has for a package are kept
+pub fn link_member_wit(members: &mut [ProjectConfig], wits: &[MemberWit]) {
+    let packages = members
+        .iter()
+        .zip(wits)
+        .filter_map(|(member, wit)| {
+            wit.package
+                .clone()
+                .map(|p| (p, member.common.wit_dir.clone()))
+        })
+        .collect::<BTreeMap<_, _>>();
+    for (member, wit) in members.iter_mut().zip(wits) {
+        for (package, wit_dir) in &packages {
+            if !wit.imports.contains(package) || wit.package.as_ref() == Some(package) {
+                continue;
+            }
+            member
+                .package_config
+                .overrides
+                .get_or_insert_with(HashMap::new)
+                .entry(package.clone())
+                .or_insert_with(|| Override {
+                    path: Some(wit_dir.clone()),
+                    version: None,
+                });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const API_WIT: &str = "package petclinic:api;
+
+interface vets {
+    all: func() -> list<string>;
+}
+
+world api {
+    export vets;
+}
+";
+
+    const UI_WIT: &str = "package petclinic:ui;
+
+world ui {
+    import petclinic:api/vets;
+    import wasi:logging/logging;
+}
+";
+
+    const WASMCLOUD_TOML: &str = r#"
+name = "{name}"
+language = "rust"
+type = "component"
+version = "0.1.0"
+"#;
+
+    fn write_member(dir: &Path, name: &str, wit: &str) -> Result<()> {
+        let member = dir.join(name);
+        std::fs::create_dir_all(member.join("wit"))?;
+        std::fs::write(
+            member.join("wasmcloud.toml"),
+            WASMCLOUD_TOML.replace("{name}", name),
+        )?;
+        std::fs::write(member.join("wit/world.wit"), wit)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn members_are_built_after_the_members_they_import() -> Result<()> {
+        let workspace = tempfile::tempdir()?;
+        let dir = workspace.path();
+        write_member(dir, "ui", UI_WIT)?;
+        write_member(dir, "api", API_WIT)?;
+        std::fs::write(
+            dir.join("wasmcloud.toml"),
+            "[workspace]\nname = \"petclinic\"\nmembers = [\"ui\", \"api\"]\n",
+        )?;
+        write_member(dir, "single", API_WIT)?;
+        assert!(load_workspace_config(Some(dir.join("single")))
+            .await?
+            .is_none());
+
+        let mut ws = load_workspace_config(Some(dir.to_path_buf()))
+            .await?
+            .expect("should load a workspace");
+        assert_eq!(ws.name, "petclinic");
+        let names = ws
+            .members
+            .iter()
+            .map(|m| m.common.name.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["ui", "api"]);
+
+        let wits = ws
+            .members
+            .iter()
+            .map(|m| member_wit(&m.common.wit_dir))
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(wits[1].package.as_deref(), Some("petclinic:api"));
+        assert!(wits[0].imports.contains("wasi:logging"));
+
+        let names = names.iter().map(String::as_str).collect::<Vec<_>>();
+        assert_eq!(workspace_build_order(&names, &wits)?, [1, 0]);
+
+        link_member_wit(&mut ws.members, &wits);
+        let overrides = ws.members[0].package_config.overrides.clone().unwrap();
+        assert_eq!(
+            overrides["petclinic:api"].path.as_deref(),
+            Some(ws.members[1].common.wit_dir.as_path())
+        );
+        assert!(!overrides.contains_key("wasi:logging"));
+        assert!(ws.members[1].package_config.overrides.is_none());
+
+        // Members importing each other can't be built
+        let mut cyclic = wits.clone();
+        cyclic[1].imports.insert("petclinic:ui".to_string());
+        assert!(workspace_build_order(&names, &cyclic).is_err());
+        Ok(())
+    }
+}
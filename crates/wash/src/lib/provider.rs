@@ -0,0 +1,1403 @@
+//! Helpers for inspecting capability providers before they are started on a host
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Display;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use provider_archive::ProviderArchive;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tokio::time::Duration;
+use tracing::debug;
+use wascap::jwt::{validate_token, CapabilityProvider};
+use wasmcloud_control_interface::{Client as CtlClient, HostInventory, Link};
+
+use crate::lib::common::{boxed_err_to_anyhow, get_all_inventories};
+use crate::lib::registry::{fetch_oci_artifact_size, get_oci_artifact, OciPullOptions};
+
+/// The outcome of a successful provider signature verification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureVerification {
+    /// The public key of the account that signed the provider archive
+    pub issuer: String,
+    /// The public key of the provider itself (the subject of the embedded claims)
+    pub subject: String,
+}
+
+/// Fetch the raw bytes of a provider archive from a (possibly `file://` prefixed) path or an OCI
+/// reference
+pub async fn fetch_provider_archive(provider_ref: &str) -> Result<Vec<u8>> {
+    let url_or_file = provider_ref
+        .strip_prefix("file://")
+        .unwrap_or(provider_ref)
+        .to_string();
+    get_oci_artifact(
+        url_or_file,
+        None,
+        OciPullOptions {
+            allow_latest: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .with_context(|| format!("failed to fetch provider archive [{provider_ref}]"))
+}
+
+/// Verify that a provider archive carries validly signed claims issued by one of the
+/// `trusted_issuers` (account public keys).
+///
+/// Archives without embedded claims, with an invalid signature, or signed by an issuer outside
+/// the trust policy are rejected.
+pub async fn verify_provider_signature(
+    archive: &[u8],
+    trusted_issuers: &[String],
+) -> Result<SignatureVerification> {
+    let par = ProviderArchive::try_load(archive)
+        .await
+        .map_err(|e| anyhow!("{e}"))
+        .context("provider archive is unsigned or could not be loaded")?;
+    let token = par
+        .claims_token()
+        .context("provider archive does not contain signed claims")?;
+    let validation =
+        validate_token::<CapabilityProvider>(&token.jwt).context("invalid provider signature")?;
+    if validation.expired {
+        bail!("provider claims have expired");
+    }
+    if validation.cannot_use_yet {
+        bail!("provider claims are not valid yet");
+    }
+
+    let issuer = token.claims.issuer;
+    if !trusted_issuers.contains(&issuer) {
+        bail!("provider archive was signed by untrusted issuer [{issuer}]");
+    }
+    Ok(SignatureVerification {
+        issuer,
+        subject: token.claims.subject,
+    })
+}
+
+/// Extract the config JSON schema embedded in a provider archive's claims, if it has one
+pub async fn provider_config_schema(archive: &[u8]) -> Result<Option<serde_json::Value>> {
+    let par = ProviderArchive::try_load(archive)
+        .await
+        .map_err(|e| anyhow!("{e}"))
+        .context("failed to load provider archive")?;
+    Ok(par.schema())
+}
+
+/// Schema keywords that [`validate_provider_config`] checks, or that don't constrain values
+const CHECKED_SCHEMA_KEYWORDS: &[&str] = &[
+    "$schema",
+    "$id",
+    "title",
+    "description",
+    "default",
+    "examples",
+    "type",
+    "properties",
+    "required",
+    "additionalProperties",
+    "enum",
+];
+
+/// Check provider config values against the JSON schema embedded in the provider. Config values
+/// are always strings, so `type` is checked by whether the value parses as that type, with arrays
+/// and objects given as JSON. Only the `required`, `additionalProperties` and per-property `type`
+/// and `enum` keywords are checked. Every violation is reported, not just the first.
+///
+/// On success, returns the keywords of the schema that were not checked, so callers don't report
+/// more than was validated
+pub fn validate_provider_config(
+    schema: &serde_json::Value,
+    values: &BTreeMap<String, String>,
+) -> Result<BTreeSet<String>> {
+    let properties = schema
+        .get("properties")
+        .and_then(serde_json::Value::as_object);
+    let mut errors = Vec::new();
+    let unchecked = |value: Option<&serde_json::Value>| {
+        value
+            .and_then(serde_json::Value::as_object)
+            .into_iter()
+            .flat_map(|object| object.keys())
+            .filter(|keyword| !CHECKED_SCHEMA_KEYWORDS.contains(&keyword.as_str()))
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+    let mut unchecked_keywords: BTreeSet<String> = unchecked(Some(schema)).into_iter().collect();
+    if let Some(properties) = properties {
+        unchecked_keywords.extend(properties.values().flat_map(|p| unchecked(Some(p))));
+    }
+
+    for required in schema
+        .get("required")
+        .and_then(serde_json::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(serde_json::Value::as_str)
+    {
+        if !values.contains_key(required) {
+            errors.push(format!("missing required key [{required}]"));
+        }
+    }
+
+    let additional_allowed = schema
+        .get("additionalProperties")
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(true);
+    for (key, value) in values {
+        let Some(property) = properties.and_then(|p| p.get(key)) else {
+            if !additional_allowed {
+                errors.push(format!("unknown key [{key}]"));
+            }
+            continue;
+        };
+        if let Some(ty) = property.get("type").and_then(serde_json::Value::as_str) {
+            let json = || serde_json::from_str::<serde_json::Value>(value);
+            let matches = match ty {
+                "string" => true,
+                "integer" => value.parse::<i64>().is_ok(),
+                "number" => value.parse::<f64>().is_ok(),
+                "boolean" => value.parse::<bool>().is_ok(),
+                "array" => json().is_ok_and(|v| v.is_array()),
+                "object" => json().is_ok_and(|v| v.is_object()),
+                "null" => value == "null",
+                _ => {
+                    unchecked_keywords.insert(format!("type {ty}"));
+                    true
+                }
+            };
+            if !matches {
+                errors.push(format!(
+                    "value [{value}] of key [{key}] is not a valid {ty}"
+                ));
+            }
+        }
+        if let Some(allowed) = property.get("enum").and_then(serde_json::Value::as_array) {
+            // Non-string values like `1` or `true` in the enum match the same value as a string
+            let parsed = serde_json::from_str::<serde_json::Value>(value).ok();
+            if !allowed
+                .iter()
+                .any(|a| a.as_str() == Some(value.as_str()) || parsed.as_ref() == Some(a))
+            {
+                errors.push(format!(
+                    "value [{value}] of key [{key}] is not one of {}",
+                    serde_json::Value::Array(allowed.clone())
+                ));
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(unchecked_keywords)
+    } else {
+        bail!(
+            "config does not match the provider's schema:\n  {}",
+            errors.join("\n  ")
+        )
+    }
+}
+
+/// The imports and exports of a WIT world, by fully qualified name (e.g. `wasi:http/handler@0.2.0`)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorldInterfaces {
+    pub name: String,
+    pub imports: BTreeSet<String>,
+    pub exports: BTreeSet<String>,
+}
+
+impl WorldInterfaces {
+    fn from_world(resolve: &wit_parser::Resolve, world: wit_parser::WorldId) -> Self {
+        let world = &resolve.worlds[world];
+        // Types are pulled in along with their interfaces, so only interfaces and functions count
+        let names = |items: Vec<(&wit_parser::WorldKey, &wit_parser::WorldItem)>| {
+            items
+                .into_iter()
+                .filter(|(_, item)| !matches!(item, wit_parser::WorldItem::Type(_)))
+                .map(|(key, _)| resolve.name_world_key(key))
+                .collect()
+        };
+        Self {
+            name: world.name.clone(),
+            imports: names(world.imports.iter().collect()),
+            exports: names(world.exports.iter().collect()),
+        }
+    }
+}
+
+/// Extract the WIT worlds declared by a provider archive. Errors if the provider doesn't embed a
+/// WIT package
+pub async fn provider_worlds(archive: &[u8]) -> Result<Vec<WorldInterfaces>> {
+    let par = ProviderArchive::try_load(archive)
+        .await
+        .map_err(|e| anyhow!("{e}"))
+        .context("failed to load provider archive")?;
+    let wit = par
+        .wit_world()
+        .context("provider archive does not embed a WIT world")?;
+    let decoded =
+        wit_parser::decoding::decode(wit).context("failed to decode provider WIT package")?;
+    let resolve = decoded.resolve();
+    Ok(resolve.packages[decoded.package()]
+        .worlds
+        .values()
+        .map(|world| WorldInterfaces::from_world(resolve, *world))
+        .collect())
+}
+
+/// Load the world a host supports from a WIT file or directory, which must contain exactly one
+/// world
+pub fn load_host_world(path: &Path) -> Result<WorldInterfaces> {
+    let mut resolve = wit_parser::Resolve::default();
+    let (package, _) = resolve
+        .push_path(path)
+        .with_context(|| format!("failed to parse host WIT from `{}`", path.display()))?;
+    let worlds = &resolve.packages[package].worlds;
+    match worlds.values().collect::<Vec<_>>().as_slice() {
+        [world] => Ok(WorldInterfaces::from_world(&resolve, **world)),
+        _ => bail!(
+            "host WIT at `{}` must declare exactly one world, found {}",
+            path.display(),
+            worlds.len()
+        ),
+    }
+}
+
+/// Check that a provider world can run against the host world: everything the provider imports
+/// must be exported by the host, and everything the provider exports must be imported by the host.
+/// Every missing interface is reported, not just the first
+pub fn check_world_compatible(provider: &WorldInterfaces, host: &WorldInterfaces) -> Result<()> {
+    let missing_imports = provider
+        .imports
+        .difference(&host.exports)
+        .map(|name| format!("import [{name}] is not provided by the host"));
+    let missing_exports = provider
+        .exports
+        .difference(&host.imports)
+        .map(|name| format!("export [{name}] is not used by the host"));
+    let errors = missing_imports.chain(missing_exports).collect::<Vec<_>>();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        bail!(
+            "provider world [{}] is incompatible with host world [{}]:\n  {}",
+            provider.name,
+            host.name,
+            errors.join("\n  ")
+        )
+    }
+}
+
+/// A coarse category for why a provider failed to start, so automation can branch on it without
+/// parsing the host's error message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderStartFailureClass {
+    /// The provider archive could not be resolved or pulled
+    ImagePull,
+    /// The configuration supplied for the provider was missing or invalid
+    ConfigInvalid,
+    /// The host ran out of some resource (memory, disk, file handles, ...) while starting it
+    ResourceExhausted,
+    /// No start or failure event was received in time
+    Timeout,
+    /// Anything we couldn't categorize
+    Unknown,
+}
+
+impl ProviderStartFailureClass {
+    /// Classify the error reported in a `provider_start_failed` event
+    #[must_use]
+    pub fn classify(error: &str) -> Self {
+        let error = error.to_lowercase();
+        let contains_any = |needles: &[&str]| needles.iter().any(|n| error.contains(n));
+        if contains_any(&[
+            "failed to fetch provider",
+            "failed to parse provider reference",
+            "failed to pull",
+            "manifest unknown",
+            "unauthorized",
+            "no such file or directory",
+        ]) {
+            Self::ImagePull
+        } else if contains_any(&["config", "secret"]) {
+            Self::ConfigInvalid
+        } else if contains_any(&[
+            "out of memory",
+            "no space left",
+            "too many open files",
+            "resource temporarily unavailable",
+            "exhausted",
+        ]) {
+            Self::ResourceExhausted
+        } else if contains_any(&["timed out", "timeout", "deadline"]) {
+            Self::Timeout
+        } else {
+            Self::Unknown
+        }
+    }
+
+    /// The identifier used for this class in command output
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::ImagePull => "image_pull",
+            Self::ConfigInvalid => "config_invalid",
+            Self::ResourceExhausted => "resource_exhausted",
+            Self::Timeout => "timeout",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+impl Display for ProviderStartFailureClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The phase of a provider start that failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderStartPhase {
+    /// Resolving or pulling the provider archive before the start
+    Pull,
+    /// Launching the provider on the host
+    Launch,
+}
+
+impl Display for ProviderStartPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pull => f.write_str("pull"),
+            Self::Launch => f.write_str("launch"),
+        }
+    }
+}
+
+/// Error returned when a provider fails to start, carrying the phase that failed and the
+/// classified reason
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{message} (failure phase: {phase}, failure class: {class})")]
+pub struct ProviderStartError {
+    pub phase: ProviderStartPhase,
+    pub class: ProviderStartFailureClass,
+    pub message: String,
+}
+
+impl ProviderStartError {
+    /// Build an error from the failure reported by the host, classifying it from its message. A
+    /// failure the host already attributed to a phase (see [`Self::pull_failure`]) is kept as is,
+    /// anything else is a launch failure
+    #[must_use]
+    pub fn from_failure(err: &anyhow::Error) -> Self {
+        if let Some(err) = err.downcast_ref::<Self>() {
+            return err.clone();
+        }
+        let message = format!("{err:#}");
+        Self {
+            phase: ProviderStartPhase::Launch,
+            class: ProviderStartFailureClass::classify(&message),
+            message,
+        }
+    }
+
+    /// Build an error for a start the host reported as failing while it pulled the provider
+    #[must_use]
+    pub fn pull_failure(message: impl Into<String>) -> Self {
+        let message = message.into();
+        let class = if message.to_lowercase().contains("timed out") {
+            ProviderStartFailureClass::Timeout
+        } else {
+            ProviderStartFailureClass::ImagePull
+        };
+        Self {
+            phase: ProviderStartPhase::Pull,
+            class,
+            message,
+        }
+    }
+
+    /// Build an error for a start that never reported back
+    #[must_use]
+    pub fn timeout(err: &anyhow::Error) -> Self {
+        Self {
+            phase: ProviderStartPhase::Launch,
+            class: ProviderStartFailureClass::Timeout,
+            message: format!("{err:#}"),
+        }
+    }
+}
+
+/// Check that a `file://` provider reference points at an existing file before asking a host to
+/// start it, since the host only reports a missing file with an opaque error. Other references
+/// are not checked
+pub async fn check_local_provider_ref(provider_ref: &str) -> Result<(), ProviderStartError> {
+    let Some(path) = provider_ref.strip_prefix("file://") else {
+        return Ok(());
+    };
+    let problem = match tokio::fs::metadata(path).await {
+        Ok(metadata) if metadata.is_dir() => "it is a directory, not a provider archive",
+        Ok(_) => return Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => "no such file",
+        Err(_) => "it could not be read",
+    };
+    Err(ProviderStartError {
+        phase: ProviderStartPhase::Pull,
+        class: ProviderStartFailureClass::ImagePull,
+        message: format!("provider file not found: {path} ({problem})"),
+    })
+}
+
+/// Lower bound for a provider start timeout derived from the size of its image
+pub const MIN_SIZED_START_TIMEOUT: Duration = Duration::from_secs(15);
+/// Upper bound for a provider start timeout derived from the size of its image
+pub const MAX_SIZED_START_TIMEOUT: Duration = Duration::from_secs(300);
+/// Download rate assumed when sizing the start timeout. This is deliberately pessimistic, as the
+/// host may be pulling over a much slower link than the one wash runs on
+const ASSUMED_PULL_BYTES_PER_SEC: u64 = 1024 * 1024;
+/// Time allowed for the host to launch the provider once it has been pulled
+const PROVIDER_LAUNCH_ALLOWANCE: Duration = Duration::from_secs(10);
+
+/// Compute how long to wait for a provider to start given the size of its image, so small
+/// providers fail fast and large ones get time to download, within
+/// [`MIN_SIZED_START_TIMEOUT`]..=[`MAX_SIZED_START_TIMEOUT`]
+#[must_use]
+pub fn start_timeout_for_image_size(size_bytes: u64) -> Duration {
+    let pull = Duration::from_secs(size_bytes.div_ceil(ASSUMED_PULL_BYTES_PER_SEC));
+    (pull + PROVIDER_LAUNCH_ALLOWANCE).clamp(MIN_SIZED_START_TIMEOUT, MAX_SIZED_START_TIMEOUT)
+}
+
+/// A provider start timeout sized to the provider's image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizedStartTimeout {
+    /// Size of the image in bytes, from its manifest in the registry or the size of the local file
+    pub image_size: u64,
+    pub timeout: Duration,
+}
+
+/// How long to wait for the registry when fetching a provider manifest to size the start timeout.
+/// The start falls back to the fixed timeout rather than waiting on a slow registry
+pub const MANIFEST_FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Estimate the start timeout for a provider from the size of its image, read from its manifest in
+/// the registry or, for local files (`file://` refs or paths), from the file itself.
+///
+/// Returns `None` when the size can't be found, in which case the caller should fall back to a
+/// fixed timeout.
+pub async fn estimate_start_timeout(provider_ref: &str) -> Option<SizedStartTimeout> {
+    let path = provider_ref.strip_prefix("file://").unwrap_or(provider_ref);
+    let image_size = if provider_ref.starts_with("file://") || Path::new(path).exists() {
+        match tokio::fs::metadata(path).await {
+            Ok(metadata) => metadata.len(),
+            Err(err) => {
+                debug!(?err, provider_ref, "unable to read provider file size");
+                return None;
+            }
+        }
+    } else {
+        let reference = provider_ref.parse().ok()?;
+        let options = OciPullOptions {
+            allow_latest: true,
+            ..Default::default()
+        };
+        match tokio::time::timeout(
+            MANIFEST_FETCH_TIMEOUT,
+            fetch_oci_artifact_size(&reference, options),
+        )
+        .await
+        {
+            Ok(Ok(size)) => size,
+            Ok(Err(err)) => {
+                debug!(?err, provider_ref, "unable to estimate provider image size");
+                return None;
+            }
+            Err(_) => {
+                debug!(
+                    provider_ref,
+                    timeout = ?MANIFEST_FETCH_TIMEOUT,
+                    "timed out fetching provider image manifest"
+                );
+                return None;
+            }
+        }
+    };
+    Some(SizedStartTimeout {
+        image_size,
+        timeout: start_timeout_for_image_size(image_size),
+    })
+}
+
+/// Whether an error is (or wraps) a provider start failure caused by pulling the provider archive
+#[must_use]
+pub fn is_pull_failure(err: &anyhow::Error) -> bool {
+    err.chain()
+        .filter_map(|e| e.downcast_ref::<ProviderStartError>())
+        .any(|e| e.class == ProviderStartFailureClass::ImagePull)
+}
+
+/// Try starting a provider from each reference in turn, moving on to the next one only when the
+/// previous one failed to pull. Any other failure is returned immediately, since a mirror of the
+/// same provider would fail in the same way. Returns the reference that succeeded along with the
+/// start result.
+pub async fn start_with_fallback_refs<T, F, Fut>(
+    refs: impl IntoIterator<Item = String>,
+    mut start: F,
+) -> Result<(String, T)>
+where
+    F: FnMut(String) -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    let mut attempted = Vec::new();
+    let mut last_err = None;
+    for provider_ref in refs {
+        match start(provider_ref.clone()).await {
+            Ok(out) => return Ok((provider_ref, out)),
+            Err(err) if is_pull_failure(&err) => {
+                attempted.push(provider_ref);
+                last_err = Some(err);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    let err = last_err.context("no provider references to start")?;
+    Err(err.context(format!(
+        "failed to pull provider from any of [{}]",
+        attempted.join(", ")
+    )))
+}
+
+/// Prefix of the named config created from a provider config file
+const CONFIG_FILE_PREFIX: &str = "wash-config-";
+
+/// Format of a provider config file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFileFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl ConfigFileFormat {
+    /// Detect the format from the file extension. Files without a known extension are read as YAML,
+    /// which also covers JSON
+    #[must_use]
+    pub fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("json") => Self::Json,
+            Some("toml") => Self::Toml,
+            _ => Self::Yaml,
+        }
+    }
+}
+
+/// Parse provider config in the given format into config values. The config must be a map; since
+/// config values are strings, numbers and booleans are converted to their string form and nested
+/// maps or lists to JSON
+pub fn parse_provider_config(
+    contents: &str,
+    format: ConfigFileFormat,
+) -> Result<BTreeMap<String, String>> {
+    let parsed: serde_json::Value = match format {
+        ConfigFileFormat::Json => serde_json::from_str(contents)?,
+        ConfigFileFormat::Yaml => serde_yaml::from_str(contents)?,
+        ConfigFileFormat::Toml => toml::from_str(contents)?,
+    };
+    let serde_json::Value::Object(map) = parsed else {
+        bail!("config must be a map of keys to values");
+    };
+    map.into_iter()
+        .map(|(key, value)| {
+            let value = match value {
+                serde_json::Value::String(value) => value,
+                serde_json::Value::Null => bail!("config key [{key}] has no value"),
+                value @ (serde_json::Value::Bool(_) | serde_json::Value::Number(_)) => {
+                    value.to_string()
+                }
+                value => serde_json::to_string(&value)?,
+            };
+            Ok((key, value))
+        })
+        .collect()
+}
+
+/// Read a provider config file, a map of config values in JSON, YAML or TOML. See
+/// [`parse_provider_config`]
+pub async fn load_provider_config_file(path: &Path) -> Result<BTreeMap<String, String>> {
+    let contents = tokio::fs::read_to_string(path)
+        .await
+        .with_context(|| format!("failed to read config file [{}]", path.display()))?;
+    parse_provider_config(&contents, ConfigFileFormat::from_path(path))
+        .with_context(|| format!("failed to parse config file [{}]", path.display()))
+}
+
+/// The content-addressed name for a set of config values. Identical values always map to the
+/// same name, so a host can be pointed at config that was already sent by an earlier start.
+#[must_use]
+pub fn content_addressed_config_name(values: &BTreeMap<String, String>) -> String {
+    let mut digest = Sha256::new();
+    for (key, value) in values {
+        // Length prefixes keep `{"ab": "c"}` and `{"a": "bc"}` from hashing the same
+        digest.update((key.len() as u64).to_le_bytes());
+        digest.update(key);
+        digest.update((value.len() as u64).to_le_bytes());
+        digest.update(value);
+    }
+    let hash = format!("{:x}", digest.finalize());
+    format!("{CONFIG_FILE_PREFIX}{}", &hash[..16])
+}
+
+/// How provider config ended up stored in the lattice
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigUpload {
+    /// Identical config was already stored under its content-addressed name and was reused
+    Cached,
+    /// The config was sent in full
+    Sent,
+}
+
+/// Whether the config already stored under a content-addressed name matches the values we would
+/// send, in which case sending it again can be skipped
+#[must_use]
+pub fn is_cached_config(
+    existing: Option<&HashMap<String, String>>,
+    values: &BTreeMap<String, String>,
+) -> bool {
+    existing.is_some_and(|existing| {
+        existing.len() == values.len()
+            && values
+                .iter()
+                .all(|(k, v)| existing.get(k).is_some_and(|e| e == v))
+    })
+}
+
+/// Store provider config under its content-addressed name, returning the name to pass to the host.
+/// With `use_cache`, config that is already stored is referenced by name instead of being resent;
+/// any cache miss (or failure to look the config up) falls back to sending it in full.
+pub async fn put_provider_config(
+    client: &CtlClient,
+    values: BTreeMap<String, String>,
+    use_cache: bool,
+) -> Result<(String, ConfigUpload)> {
+    let name = content_addressed_config_name(&values);
+    if use_cache {
+        let existing = client
+            .get_config(&name)
+            .await
+            .ok()
+            .and_then(wasmcloud_control_interface::CtlResponse::into_data);
+        if is_cached_config(existing.as_ref(), &values) {
+            return Ok((name, ConfigUpload::Cached));
+        }
+    }
+    let ack = client
+        .put_config(&name, HashMap::from_iter(values))
+        .await
+        .map_err(boxed_err_to_anyhow)
+        .with_context(|| format!("failed to put provider config [{name}]"))?;
+    if !ack.succeeded() {
+        bail!("failed to put provider config [{name}]: {}", ack.message());
+    }
+    Ok((name, ConfigUpload::Sent))
+}
+
+/// Filters for finding providers by the WIT interfaces they serve. Unset parts match anything
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InterfaceQuery {
+    pub namespace: Option<String>,
+    pub package: Option<String>,
+    pub interface: Option<String>,
+}
+
+impl InterfaceQuery {
+    /// Build a query from a fully qualified interface (`wasi:keyvalue/store`) or a bare interface
+    /// name (`store`). Parts of a fully qualified interface override `namespace` and `package`
+    pub fn new(
+        interface: Option<&str>,
+        namespace: Option<String>,
+        package: Option<String>,
+    ) -> Result<Self> {
+        let Some(interface) = interface else {
+            return Ok(Self {
+                namespace,
+                package,
+                interface: None,
+            });
+        };
+        match interface.split_once(':') {
+            Some((ns, rest)) => {
+                let (pkg, iface) = rest.split_once('/').with_context(|| {
+                    format!(
+                        "interface [{interface}] must be in the form namespace:package/interface"
+                    )
+                })?;
+                if ns.is_empty() || pkg.is_empty() || iface.is_empty() {
+                    bail!(
+                        "interface [{interface}] must be in the form namespace:package/interface"
+                    );
+                }
+                Ok(Self {
+                    namespace: Some(ns.to_string()),
+                    package: Some(pkg.to_string()),
+                    interface: Some(iface.to_string()),
+                })
+            }
+            None => Ok(Self {
+                namespace,
+                package,
+                interface: Some(interface.to_string()),
+            }),
+        }
+    }
+
+    fn matches(&self, namespace: &str, package: &str, interface: &str) -> bool {
+        self.namespace.as_deref().is_none_or(|ns| ns == namespace)
+            && self.package.as_deref().is_none_or(|pkg| pkg == package)
+            && self.interface.as_deref().is_none_or(|i| i == interface)
+    }
+}
+
+/// A running provider and the interfaces it serves that matched a query
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ServingProvider {
+    pub host_id: String,
+    pub provider_id: String,
+    pub name: Option<String>,
+    pub image_ref: Option<String>,
+    /// Fully qualified interfaces, e.g. `wasi:keyvalue/store`
+    pub interfaces: Vec<String>,
+}
+
+/// Find the running providers that serve interfaces matching the query.
+///
+/// Providers don't advertise the interfaces they implement, so this relies on the links that
+/// target them: a provider serves an interface if a link to it carries that interface. Running
+/// providers that nothing links to yet won't be found.
+#[must_use]
+pub fn providers_serving(
+    inventories: &[HostInventory],
+    links: &[Link],
+    query: &InterfaceQuery,
+) -> Vec<ServingProvider> {
+    inventories
+        .iter()
+        .flat_map(|inv| {
+            inv.providers().iter().filter_map(|provider| {
+                let interfaces = links
+                    .iter()
+                    .filter(|link| link.target() == provider.id())
+                    .flat_map(|link| {
+                        link.interfaces()
+                            .iter()
+                            .filter(|i| query.matches(link.wit_namespace(), link.wit_package(), i))
+                            .map(|i| format!("{}:{}/{i}", link.wit_namespace(), link.wit_package()))
+                    })
+                    .collect::<BTreeSet<_>>();
+                (!interfaces.is_empty()).then(|| ServingProvider {
+                    host_id: inv.host_id().to_string(),
+                    provider_id: provider.id().to_string(),
+                    name: provider.name().map(ToString::to_string),
+                    image_ref: provider.image_ref().map(ToString::to_string),
+                    interfaces: interfaces.into_iter().collect(),
+                })
+            })
+        })
+        .collect()
+}
+
+/// Query the lattice for the running providers that serve interfaces matching the query. See
+/// [`providers_serving`]
+pub async fn find_providers_serving(
+    client: &CtlClient,
+    query: &InterfaceQuery,
+) -> Result<Vec<ServingProvider>> {
+    let inventories = get_all_inventories(client).await?;
+    let links = client
+        .get_links()
+        .await
+        .map_err(boxed_err_to_anyhow)?
+        .into_data()
+        .unwrap_or_default();
+    Ok(providers_serving(&inventories, &links, query))
+}
+
+#[cfg(test)]
+mod test {
+    use nkeys::KeyPair;
+    use tokio::io::AsyncReadExt;
+
+    use super::*;
+
+    async fn signed_archive(issuer: &KeyPair) -> Vec<u8> {
+        signed_archive_with(issuer, None, None).await
+    }
+
+    async fn signed_archive_with(
+        issuer: &KeyPair,
+        schema: Option<serde_json::Value>,
+        wit_world: Option<Vec<u8>>,
+    ) -> Vec<u8> {
+        let dir = tempfile::tempdir().expect("failed to create tempdir");
+        let path = dir.path().join("provider.par");
+        let mut par = ProviderArchive::new("test", "wasmCloud", Some(1), Some("0.1.0".into()));
+        par.add_library("x86_64-linux", b"not really a binary")
+            .expect("failed to add library");
+        if let Some(schema) = schema {
+            par.set_schema(schema).expect("failed to set schema");
+        }
+        if let Some(wit_world) = wit_world {
+            par.add_wit_world(&wit_world)
+                .expect("failed to add WIT world");
+        }
+        par.write(&path, issuer, &KeyPair::new_service(), false)
+            .await
+            .expect("failed to write archive");
+        let mut buf = Vec::new();
+        tokio::fs::File::open(&path)
+            .await
+            .expect("failed to open archive")
+            .read_to_end(&mut buf)
+            .await
+            .expect("failed to read archive");
+        buf
+    }
+
+    #[test]
+    fn classify_start_failures() {
+        for (error, class) in [
+            (
+                "failed to fetch provider: failed to pull ghcr.io/nope:0.1.0: manifest unknown",
+                ProviderStartFailureClass::ImagePull,
+            ),
+            (
+                "failed to parse provider reference: invalid reference format",
+                ProviderStartFailureClass::ImagePull,
+            ),
+            (
+                "failed to fetch config [http-settings]: config does not exist",
+                ProviderStartFailureClass::ConfigInvalid,
+            ),
+            (
+                "failed to spawn provider process: Too many open files (os error 24)",
+                ProviderStartFailureClass::ResourceExhausted,
+            ),
+            (
+                "failed to initialize provider: no space left on device",
+                ProviderStartFailureClass::ResourceExhausted,
+            ),
+            (
+                "provider did not respond to health check: timed out",
+                ProviderStartFailureClass::Timeout,
+            ),
+            (
+                "feature `builtin-http-server` is not enabled, denying start",
+                ProviderStartFailureClass::Unknown,
+            ),
+        ] {
+            assert_eq!(
+                ProviderStartFailureClass::classify(error),
+                class,
+                "wrong class for [{error}]"
+            );
+        }
+        assert_eq!(
+            serde_json::to_value(ProviderStartFailureClass::ConfigInvalid).unwrap(),
+            "config_invalid"
+        );
+    }
+
+    #[test]
+    fn start_failures_are_reported_as_launch_failures() {
+        let err = ProviderStartError::from_failure(&anyhow!("failed to initialize provider"));
+        assert_eq!(err.phase, ProviderStartPhase::Launch);
+        let err = ProviderStartError::timeout(&anyhow!("no start event received"));
+        assert_eq!(err.phase, ProviderStartPhase::Launch);
+        assert_eq!(err.class, ProviderStartFailureClass::Timeout);
+    }
+
+    #[tokio::test]
+    async fn fallback_ref_used_after_pull_failure() {
+        let pull_failure = || {
+            anyhow::Error::new(ProviderStartError {
+                phase: ProviderStartPhase::Launch,
+                class: ProviderStartFailureClass::ImagePull,
+                message: "failed to fetch provider: manifest unknown".into(),
+            })
+            .context("Failed starting provider")
+        };
+
+        let mut tried = Vec::new();
+        let (used, out) = start_with_fallback_refs(
+            [
+                "ghcr.io/primary:0.1.0".to_string(),
+                "mirror.io/primary:0.1.0".to_string(),
+            ],
+            |provider_ref| {
+                tried.push(provider_ref.clone());
+                let result = if provider_ref.starts_with("ghcr.io") {
+                    Err(pull_failure())
+                } else {
+                    Ok(provider_ref.len())
+                };
+                async move { result }
+            },
+        )
+        .await
+        .expect("fallback should succeed");
+        assert_eq!(used, "mirror.io/primary:0.1.0");
+        assert_eq!(out, used.len());
+        assert_eq!(tried.len(), 2);
+
+        // Non-pull failures aren't retried against the fallbacks
+        let mut tried = 0;
+        let err = start_with_fallback_refs(
+            [
+                "ghcr.io/primary:0.1.0".to_string(),
+                "mirror.io/primary:0.1.0".to_string(),
+            ],
+            |_| {
+                tried += 1;
+                async {
+                    Err::<(), _>(anyhow::Error::new(ProviderStartError::from_failure(
+                        &anyhow!("failed to fetch config [settings]"),
+                    )))
+                }
+            },
+        )
+        .await
+        .expect_err("config failures should not fall back");
+        assert_eq!(tried, 1);
+        assert!(!is_pull_failure(&err));
+
+        // Every reference failing to pull reports them all
+        let err = start_with_fallback_refs(
+            ["a.io/p:1".to_string(), "b.io/p:1".to_string()],
+            |_| async { Err::<(), _>(pull_failure()) },
+        )
+        .await
+        .expect_err("all pulls failed");
+        assert!(err.to_string().contains("a.io/p:1, b.io/p:1"));
+    }
+
+    #[test]
+    fn second_start_references_cached_config() {
+        let values = BTreeMap::from([
+            ("address".to_string(), "0.0.0.0:8080".to_string()),
+            ("cors".to_string(), "true".to_string()),
+        ]);
+        let first = content_addressed_config_name(&values);
+        assert!(first.starts_with(CONFIG_FILE_PREFIX));
+
+        // Nothing stored yet, so the first start has to send the config
+        assert!(!is_cached_config(None, &values));
+
+        // The second start with the same file resolves to the same name and finds it stored
+        let stored = HashMap::from_iter(values.clone());
+        assert_eq!(content_addressed_config_name(&values), first);
+        assert!(is_cached_config(Some(&stored), &values));
+
+        // Any change to the contents is a different name, and stale contents are a miss
+        let mut changed = values.clone();
+        changed.insert("cors".to_string(), "false".to_string());
+        assert_ne!(content_addressed_config_name(&changed), first);
+        assert!(!is_cached_config(Some(&stored), &changed));
+
+        let ab = BTreeMap::from([("ab".to_string(), "c".to_string())]);
+        let a = BTreeMap::from([("a".to_string(), "bc".to_string())]);
+        assert_ne!(
+            content_addressed_config_name(&ab),
+            content_addressed_config_name(&a)
+        );
+    }
+
+    #[tokio::test]
+    async fn trusted_signature_passes() {
+        let issuer = KeyPair::new_account();
+        let archive = signed_archive(&issuer).await;
+        let verification = verify_provider_signature(&archive, &[issuer.public_key()])
+            .await
+            .expect("signature should be trusted");
+        assert_eq!(verification.issuer, issuer.public_key());
+    }
+
+    #[tokio::test]
+    async fn config_is_validated_against_embedded_schema() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "port": {"type": "integer"},
+                "tls": {"type": "boolean"},
+                "mode": {"type": "string", "enum": ["fast", "safe"]},
+                "workers": {"type": "integer", "enum": [1, 2, 4], "minimum": 1},
+            },
+            "required": ["port"],
+            "additionalProperties": false,
+        });
+        let archive = signed_archive_with(&KeyPair::new_account(), Some(schema), None).await;
+        let schema = provider_config_schema(&archive)
+            .await
+            .expect("archive should load")
+            .expect("archive should embed a schema");
+
+        let good = BTreeMap::from([
+            ("port".to_string(), "8080".to_string()),
+            ("mode".to_string(), "safe".to_string()),
+        ]);
+        let unchecked = validate_provider_config(&schema, &good).expect("valid config should pass");
+        assert_eq!(unchecked, BTreeSet::from(["minimum".to_string()]));
+        let workers = BTreeMap::from([
+            ("port".to_string(), "8080".to_string()),
+            ("workers".to_string(), "4".to_string()),
+        ]);
+        validate_provider_config(&schema, &workers).expect("numeric enum values should match");
+
+        let bad = BTreeMap::from([
+            ("port".to_string(), "eighty".to_string()),
+            ("mode".to_string(), "reckless".to_string()),
+            ("colour".to_string(), "blue".to_string()),
+            ("workers".to_string(), "3".to_string()),
+        ]);
+        let err = validate_provider_config(&schema, &bad)
+            .expect_err("invalid config should be rejected")
+            .to_string();
+        assert!(err.contains("value [eighty] of key [port] is not a valid integer"));
+        assert!(err.contains("value [reckless] of key [mode] is not one of"));
+        assert!(err.contains("unknown key [colour]"));
+        assert!(err.contains("value [3] of key [workers] is not one of [1,2,4]"));
+        let missing = validate_provider_config(&schema, &BTreeMap::new())
+            .expect_err("missing required key should be rejected");
+        assert!(missing.to_string().contains("missing required key [port]"));
+
+        let plain = signed_archive(&KeyPair::new_account()).await;
+        assert!(provider_config_schema(&plain)
+            .await
+            .expect("archive should load")
+            .is_none());
+    }
+
+    #[test]
+    fn config_files_are_read_by_extension() {
+        let expected = BTreeMap::from([
+            ("port".to_string(), "8080".to_string()),
+            ("tls".to_string(), "true".to_string()),
+            ("mode".to_string(), "safe".to_string()),
+            ("hosts".to_string(), r#"["a","b"]"#.to_string()),
+        ]);
+        for (file, contents) in [
+            (
+                "config.json",
+                r#"{"port": 8080, "tls": true, "mode": "safe", "hosts": ["a", "b"]}"#,
+            ),
+            (
+                "config.yaml",
+                "port: 8080\ntls: true\nmode: safe\nhosts: [a, b]\n",
+            ),
+            (
+                "config.toml",
+                "port = 8080\ntls = true\nmode = \"safe\"\nhosts = [\"a\", \"b\"]\n",
+            ),
+        ] {
+            let format = ConfigFileFormat::from_path(Path::new(file));
+            let values = parse_provider_config(contents, format)
+                .unwrap_or_else(|e| panic!("failed to parse {file}: {e:#}"));
+            assert_eq!(values, expected, "{file}");
+        }
+
+        assert_eq!(
+            ConfigFileFormat::from_path(Path::new("config.YML")),
+            ConfigFileFormat::Yaml
+        );
+        assert!(parse_provider_config("[1, 2]", ConfigFileFormat::Json).is_err());
+        assert!(parse_provider_config("port: ~", ConfigFileFormat::Yaml).is_err());
+    }
+
+    const EXAMPLE_WIT: &str = r"
+package wasmcloud:example;
+
+interface logging {
+    log: func(message: string);
+}
+
+interface store {
+    get: func(key: string) -> option<string>;
+}
+
+interface keyvalue {
+    set: func(key: string, value: string);
+}
+";
+
+    /// Encode a provider WIT package the same way `wash par create --wit-dir` does
+    fn encoded_provider_wit(world: &str) -> Vec<u8> {
+        let mut resolve = wit_parser::Resolve::default();
+        let package = resolve
+            .push_str("provider.wit", &format!("{EXAMPLE_WIT}\n{world}"))
+            .expect("should parse provider WIT");
+        wit_component::encode(&resolve, package).expect("should encode provider WIT")
+    }
+
+    #[tokio::test]
+    async fn provider_world_is_checked_against_host_world() {
+        let dir = tempfile::tempdir().expect("should create temp dir");
+        let host_wit = dir.path().join("host.wit");
+        std::fs::write(
+            &host_wit,
+            format!("{EXAMPLE_WIT}\nworld host {{ export logging; import store; }}"),
+        )
+        .expect("should write host WIT");
+        let host = load_host_world(&host_wit).expect("should load host world");
+
+        let compatible = signed_archive_with(
+            &KeyPair::new_account(),
+            None,
+            Some(encoded_provider_wit(
+                "world provider { import logging; export store; }",
+            )),
+        )
+        .await;
+        let worlds = provider_worlds(&compatible)
+            .await
+            .expect("should read provider worlds");
+        assert_eq!(worlds.len(), 1);
+        check_world_compatible(&worlds[0], &host).expect("compatible world should pass");
+
+        let incompatible = signed_archive_with(
+            &KeyPair::new_account(),
+            None,
+            Some(encoded_provider_wit(
+                "world provider { import logging; import keyvalue; export store; export logging; }",
+            )),
+        )
+        .await;
+        let worlds = provider_worlds(&incompatible)
+            .await
+            .expect("should read provider worlds");
+        let err = check_world_compatible(&worlds[0], &host)
+            .expect_err("incompatible world should be rejected")
+            .to_string();
+        assert_eq!(
+            err,
+            "provider world [provider] is incompatible with host world [host]:\n  \
+             import [wasmcloud:example/keyvalue] is not provided by the host\n  \
+             export [wasmcloud:example/logging] is not used by the host"
+        );
+
+        let plain = signed_archive(&KeyPair::new_account()).await;
+        assert!(provider_worlds(&plain).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn local_provider_refs_must_point_at_a_file() {
+        let dir = tempfile::tempdir().expect("should create temp dir");
+        let archive = dir.path().join("provider.par.gz");
+        std::fs::write(&archive, b"archive").expect("should write archive");
+
+        check_local_provider_ref(&format!("file://{}", archive.display()))
+            .await
+            .expect("existing file should pass");
+        check_local_provider_ref("ghcr.io/wasmcloud/http-server:0.23.2")
+            .await
+            .expect("OCI references are not checked");
+
+        let missing = dir.path().join("missing.par.gz");
+        let err = check_local_provider_ref(&format!("file://{}", missing.display()))
+            .await
+            .expect_err("missing file should be rejected");
+        assert_eq!(
+            err.message,
+            format!(
+                "provider file not found: {} (no such file)",
+                missing.display()
+            )
+        );
+        assert_eq!(err.phase, ProviderStartPhase::Pull);
+        assert!(is_pull_failure(&anyhow::Error::new(err)));
+
+        let err = check_local_provider_ref(&format!("file://{}", dir.path().display()))
+            .await
+            .expect_err("directory should be rejected");
+        assert_eq!(
+            err.message,
+            format!(
+                "provider file not found: {} (it is a directory, not a provider archive)",
+                dir.path().display()
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn untrusted_signature_rejected() {
+        let archive = signed_archive(&KeyPair::new_account()).await;
+        let err = verify_provider_signature(&archive, &[KeyPair::new_account().public_key()])
+            .await
+            .expect_err("signature should not be trusted");
+        assert!(err.to_string().contains("untrusted issuer"));
+    }
+
+    #[tokio::test]
+    async fn unsigned_archive_rejected() {
+        let mut builder = tokio_tar::Builder::new(Vec::new());
+        let mut header = tokio_tar::Header::new_gnu();
+        let data = b"not really a binary";
+        header.set_size(data.len() as u64);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "x86_64-linux.bin", &data[..])
+            .await
+            .expect("failed to append to archive");
+        let buf = builder
+            .into_inner()
+            .await
+            .expect("failed to finish archive");
+        let err = verify_provider_signature(&buf, &[KeyPair::new_account().public_key()])
+            .await
+            .expect_err("unsigned archive should be rejected");
+        assert!(err.to_string().contains("unsigned"));
+    }
+
+    #[test]
+    fn providers_are_found_by_served_interface() {
+        let inventory = HostInventory::builder()
+            .host_id("host1".into())
+            .friendly_name("quiet-dawn".into())
+            .version("1.0.0".into())
+            .uptime_human("t".into())
+            .uptime_seconds(100)
+            .providers(
+                ["kv-redis", "http-server", "idle"]
+                    .into_iter()
+                    .map(|id| {
+                        wasmcloud_control_interface::ProviderDescription::builder()
+                            .id(id)
+                            .image_ref(&format!("ghcr.io/{id}:0.1.0"))
+                            .build()
+                            .expect("should build provider description")
+                    })
+                    .collect(),
+            )
+            .build()
+            .expect("should build host inventory");
+        let link = |target: &str, ns: &str, pkg: &str, interfaces: &[&str]| {
+            Link::builder()
+                .source_id("echo")
+                .target(target)
+                .name("default")
+                .wit_namespace(ns)
+                .wit_package(pkg)
+                .interfaces(interfaces.iter().map(ToString::to_string).collect())
+                .build()
+                .expect("should build link")
+        };
+        let links = vec![
+            link("kv-redis", "wasi", "keyvalue", &["store", "atomics"]),
+            link("http-server", "wasi", "http", &["incoming-handler"]),
+        ];
+        let find = |query: InterfaceQuery| {
+            providers_serving(std::slice::from_ref(&inventory), &links, &query)
+                .into_iter()
+                .map(|p| (p.provider_id, p.interfaces))
+                .collect::<Vec<_>>()
+        };
+
+        let query = InterfaceQuery::new(Some("wasi:keyvalue/store"), None, None).unwrap();
+        assert_eq!(
+            find(query),
+            vec![(
+                "kv-redis".to_string(),
+                vec!["wasi:keyvalue/store".to_string()]
+            )]
+        );
+
+        let query = InterfaceQuery::new(None, Some("wasi".into()), None).unwrap();
+        assert_eq!(
+            find(query),
+            vec![
+                (
+                    "kv-redis".to_string(),
+                    vec![
+                        "wasi:keyvalue/atomics".to_string(),
+                        "wasi:keyvalue/store".to_string()
+                    ]
+                ),
+                (
+                    "http-server".to_string(),
+                    vec!["wasi:http/incoming-handler".to_string()]
+                ),
+            ]
+        );
+
+        let query = InterfaceQuery::new(Some("store"), None, Some("blobstore".into())).unwrap();
+        assert!(find(query).is_empty());
+
+        for bad in ["wasi:keyvalue", "wasi:/store", ":keyvalue/store"] {
+            assert!(
+                InterfaceQuery::new(Some(bad), None, None).is_err(),
+                "[{bad}] should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn start_timeout_scales_with_image_size() {
+        use oci_client::manifest::{OciDescriptor, OciImageManifest};
+
+        let manifest = |layer_sizes: &[i64]| OciImageManifest {
+            layers: layer_sizes
+                .iter()
+                .map(|size| OciDescriptor {
+                    size: *size,
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let timeout = |layer_sizes: &[i64]| {
+            start_timeout_for_image_size(crate::lib::registry::manifest_layers_size(&manifest(
+                layer_sizes,
+            )))
+        };
+        const MIB: i64 = 1024 * 1024;
+
+        // Small images are held to the floor so they fail fast
+        assert_eq!(timeout(&[MIB]), MIN_SIZED_START_TIMEOUT);
+        // Sizes are summed across layers and scale the timeout
+        let medium = timeout(&[40 * MIB, 20 * MIB]);
+        assert_eq!(medium, Duration::from_secs(70));
+        assert!(timeout(&[120 * MIB]) > medium);
+        // Huge images are capped
+        assert_eq!(timeout(&[10_000 * MIB]), MAX_SIZED_START_TIMEOUT);
+    }
+
+    #[tokio::test]
+    async fn start_timeout_is_sized_to_local_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("provider.par.gz");
+        // Only the size matters, so a sparse file avoids writing it out
+        std::fs::File::create(&path)
+            .and_then(|file| file.set_len(40 * 1024 * 1024))
+            .unwrap();
+
+        let sized = estimate_start_timeout(&format!("file://{}", path.display()))
+            .await
+            .expect("local files should be sized");
+        assert_eq!(sized.image_size, 40 * 1024 * 1024);
+        assert_eq!(sized.timeout, Duration::from_secs(50));
+        assert_eq!(
+            estimate_start_timeout(path.to_str().unwrap()).await,
+            Some(sized)
+        );
+        assert!(
+            estimate_start_timeout(&format!("file://{}", dir.path().join("nope").display()))
+                .await
+                .is_none()
+        );
+    }
+}
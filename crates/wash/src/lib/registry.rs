@@ -131,21 +131,7 @@ pub async fn pull_oci_artifact(image_ref: &Reference, options: OciPullOptions) -
         }
     }
 
-    let client = Client::new(ClientConfig {
-        protocol: if options.insecure {
-            ClientProtocol::Http
-        } else {
-            ClientProtocol::Https
-        },
-        extra_root_certificates: tls::NATIVE_ROOTS_OCI.to_vec(),
-        accept_invalid_certificates: options.insecure_skip_tls_verify,
-        ..Default::default()
-    });
-
-    let auth = match (options.user, options.password) {
-        (Some(user), Some(password)) => RegistryAuth::Basic(user, password),
-        _ => RegistryAuth::Anonymous,
-    };
+    let (client, auth) = pull_client(&options);
 
     let image_data = client
         .pull(
@@ -181,6 +167,49 @@ pub async fn pull_oci_artifact(image_ref: &Reference, options: OciPullOptions) -
         .collect::<Vec<_>>())
 }
 
+/// Fetch only the manifest of an artifact and return the total size of its layers in bytes, e.g.
+/// to estimate how long pulling it will take
+pub async fn fetch_oci_artifact_size(
+    image_ref: &Reference,
+    options: OciPullOptions,
+) -> Result<u64> {
+    let (client, auth) = pull_client(&options);
+    let (manifest, _digest) = client
+        .pull_image_manifest(image_ref, &auth)
+        .await
+        .with_context(|| format!("failed to fetch manifest for [{image_ref}]"))?;
+    Ok(manifest_layers_size(&manifest))
+}
+
+/// The total size in bytes of the layers listed in a manifest
+#[must_use]
+pub fn manifest_layers_size(manifest: &OciImageManifest) -> u64 {
+    manifest
+        .layers
+        .iter()
+        .map(|layer| u64::try_from(layer.size).unwrap_or_default())
+        .sum()
+}
+
+fn pull_client(options: &OciPullOptions) -> (Client, RegistryAuth) {
+    let client = Client::new(ClientConfig {
+        protocol: if options.insecure {
+            ClientProtocol::Http
+        } else {
+            ClientProtocol::Https
+        },
+        extra_root_certificates: tls::NATIVE_ROOTS_OCI.to_vec(),
+        accept_invalid_certificates: options.insecure_skip_tls_verify,
+        ..Default::default()
+    });
+
+    let auth = match (&options.user, &options.password) {
+        (Some(user), Some(password)) => RegistryAuth::Basic(user.clone(), password.clone()),
+        _ => RegistryAuth::Anonymous,
+    };
+    (client, auth)
+}
+
 /// Pushes the artifact to the given repo and returns a tuple containing the tag (if one was set) and the digest
 pub async fn push_oci_artifact(
     url: String,
@@ -0,0 +1,208 @@
+//! Persisted state for progressive (canary) provider rollouts. A canary start records which hosts
+//! the provider was started on and which are still pending, so a later `wash rollout promote` can
+//! finish the rollout from a different invocation of wash.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use rand::{distr::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+
+use crate::lib::config::rollouts_dir;
+
+/// Length of the random suffix of a rollout handle
+const HANDLE_SUFFIX_LEN: usize = 6;
+
+/// A provider rollout that started on a canary subset of a host group
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rollout {
+    /// Handle used to refer to the rollout, e.g. when promoting it
+    pub handle: String,
+    pub provider_ref: String,
+    pub provider_id: String,
+    /// Flags of the original start that apply to every host, e.g. link names and config, passed
+    /// again when the provider is started on the pending hosts
+    pub start_args: Vec<String>,
+    /// Hosts the provider has been started on
+    pub started: Vec<String>,
+    /// Hosts the provider will be started on when the rollout is promoted
+    pub pending: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Rollout {
+    /// Create a new rollout with a fresh handle. The handle is derived from the provider ID, with
+    /// any character that can't be part of a handle replaced, so it can always be saved
+    #[must_use]
+    pub fn new(
+        provider_ref: &str,
+        provider_id: &str,
+        start_args: Vec<String>,
+        started: Vec<String>,
+        pending: Vec<String>,
+    ) -> Self {
+        let suffix: String = rand::rng()
+            .sample_iter(&Alphanumeric)
+            .take(HANDLE_SUFFIX_LEN)
+            .map(char::from)
+            .collect();
+        let prefix: String = provider_id
+            .chars()
+            .map(|c| if is_handle_char(c) { c } else { '-' })
+            .collect();
+        Self {
+            handle: format!("{prefix}-{}", suffix.to_lowercase()),
+            provider_ref: provider_ref.to_string(),
+            provider_id: provider_id.to_string(),
+            start_args,
+            started,
+            pending,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Whether the provider has been started on every host of the group
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Record that the provider was started on a pending host
+    pub fn mark_started(&mut self, host_id: &str) {
+        if let Some(pos) = self.pending.iter().position(|h| h == host_id) {
+            let host = self.pending.remove(pos);
+            self.started.push(host);
+        }
+    }
+
+    /// Persist the rollout in the default rollouts directory
+    pub fn save(&self) -> Result<()> {
+        self.save_in(&rollouts_dir()?)
+    }
+
+    /// Load a rollout by handle from the default rollouts directory
+    pub fn load(handle: &str) -> Result<Self> {
+        Self::load_from(&rollouts_dir()?, handle)
+    }
+
+    pub fn save_in(&self, dir: &Path) -> Result<()> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create directory `{}`", dir.display()))?;
+        let path = rollout_path(dir, &self.handle)?;
+        std::fs::write(&path, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("failed to write rollout to `{}`", path.display()))
+    }
+
+    pub fn load_from(dir: &Path, handle: &str) -> Result<Self> {
+        let path = rollout_path(dir, handle)?;
+        let contents = std::fs::read(&path)
+            .with_context(|| format!("no rollout found with handle [{handle}]"))?;
+        serde_json::from_slice(&contents)
+            .with_context(|| format!("failed to parse rollout from `{}`", path.display()))
+    }
+}
+
+fn is_handle_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '_'
+}
+
+fn rollout_path(dir: &Path, handle: &str) -> Result<PathBuf> {
+    if handle.is_empty() || !handle.chars().all(is_handle_char) {
+        bail!("invalid rollout handle [{handle}]");
+    }
+    Ok(dir.join(format!("{handle}.json")))
+}
+
+/// Parse a canary percentage such as `10%` or `10`
+pub fn parse_canary_percent(arg: &str) -> Result<u8> {
+    let percent = arg
+        .trim()
+        .trim_end_matches('%')
+        .parse::<u8>()
+        .with_context(|| format!("invalid canary percentage [{arg}]"))?;
+    if !(1..=100).contains(&percent) {
+        bail!("canary percentage must be between 1% and 100%, got {percent}%");
+    }
+    Ok(percent)
+}
+
+/// Split a host group into the canary hosts and the hosts left for promotion. The canary is
+/// `percent` of the group rounded up, so it always contains at least one host. Hosts are sorted
+/// first so the same group always yields the same canary.
+#[must_use]
+pub fn split_canary(mut hosts: Vec<String>, percent: u8) -> (Vec<String>, Vec<String>) {
+    hosts.sort();
+    hosts.dedup();
+    let count = (hosts.len() * usize::from(percent)).div_ceil(100);
+    let rest = hosts.split_off(count.min(hosts.len()));
+    (hosts, rest)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn hosts(n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("host-{i:02}")).collect()
+    }
+
+    #[test]
+    fn canary_affects_only_the_expected_fraction() {
+        let (canary, rest) = split_canary(hosts(20), 10);
+        assert_eq!(canary, vec!["host-00", "host-01"]);
+        assert_eq!(rest.len(), 18);
+
+        // Rounds up so a small group still gets a canary
+        let (canary, rest) = split_canary(hosts(3), 10);
+        assert_eq!((canary.len(), rest.len()), (1, 2));
+        let (canary, rest) = split_canary(hosts(5), 100);
+        assert_eq!((canary.len(), rest.len()), (5, 0));
+        let (canary, rest) = split_canary(Vec::new(), 50);
+        assert!(canary.is_empty() && rest.is_empty());
+
+        assert_eq!(parse_canary_percent("10%").expect("should parse"), 10);
+        assert_eq!(parse_canary_percent("25").expect("should parse"), 25);
+        assert!(parse_canary_percent("0%").is_err());
+        assert!(parse_canary_percent("150%").is_err());
+    }
+
+    #[test]
+    fn rollout_state_round_trips() {
+        let dir = tempfile::tempdir().expect("should create temp dir");
+        let (started, pending) = split_canary(hosts(4), 25);
+        let rollout = Rollout::new(
+            "ghcr.io/wasmcloud/http-server:0.1.0",
+            "http-server",
+            vec!["--config".to_string(), "server-config".to_string()],
+            started,
+            pending,
+        );
+        rollout.save_in(dir.path()).expect("should save rollout");
+
+        let mut loaded =
+            Rollout::load_from(dir.path(), &rollout.handle).expect("should load rollout");
+        assert_eq!(loaded, rollout);
+        assert!(!loaded.is_complete());
+
+        for host in loaded.pending.clone() {
+            loaded.mark_started(&host);
+        }
+        assert!(loaded.is_complete());
+        assert_eq!(loaded.started, hosts(4));
+
+        assert!(Rollout::load_from(dir.path(), "missing").is_err());
+        assert!(Rollout::load_from(dir.path(), "../escape").is_err());
+
+        // Provider IDs that aren't valid handles still yield a handle that can be saved
+        let rollout = Rollout::new(
+            "file:///tmp/p.par.gz",
+            "my.provider",
+            Vec::new(),
+            vec![],
+            vec![],
+        );
+        assert!(rollout.handle.starts_with("my-provider-"));
+        rollout.save_in(dir.path()).expect("should save rollout");
+    }
+}
@@ -1,6 +1,7 @@
 use std::task::Poll;
+use std::time::{Duration, Instant};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use chrono::{DateTime, Local};
 use futures::{Stream, StreamExt};
 use tracing::debug;
@@ -19,6 +20,90 @@ pub struct ObservedInvocation {
     /// The inner message that was received. We will attempt to parse the inner message from CBOR
     /// and JSON into a JSON string and fall back to the raw bytes if we are unable to do so
     pub message: ObservedMessage,
+    /// The subject the invoked entity answers on, if the invocation was made live
+    pub reply: Option<String>,
+}
+
+/// An observed invocation along with how it was answered
+#[derive(Debug)]
+pub struct InvocationTrace {
+    pub invocation: ObservedInvocation,
+    /// Time from the invocation being observed to its results being sent, if they were
+    pub latency: Option<Duration>,
+    /// Why the invocation is considered failed, if it is
+    pub error: Option<String>,
+}
+
+impl InvocationTrace {
+    #[must_use]
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// An observed invocation whose results are being waited for, see [`trace_invocation`]
+pub struct PendingTrace {
+    invocation: ObservedInvocation,
+    results: Result<async_nats::Subscriber>,
+    started: Instant,
+    timeout: Duration,
+}
+
+/// Start tracing an observed invocation. The invoked entity sends its results to the `results`
+/// subject under the reply subject of the invocation, which is subscribed to before this returns
+/// so results sent right after the invocation are not missed. Call this as soon as the invocation
+/// is observed
+pub async fn trace_invocation(
+    nats_client: &async_nats::Client,
+    invocation: ObservedInvocation,
+    timeout: Duration,
+) -> PendingTrace {
+    let started = Instant::now();
+    let results = match invocation.reply.as_deref() {
+        Some(reply) => nats_client
+            .subscribe(format!("{reply}.results"))
+            .await
+            .context("failed to subscribe to results"),
+        None => Err(anyhow::anyhow!("invocation has no reply subject")),
+    };
+    PendingTrace {
+        invocation,
+        results,
+        started,
+        timeout,
+    }
+}
+
+impl PendingTrace {
+    /// Wait for the results of the invocation. An invocation without results within the timeout
+    /// is reported as failed
+    pub async fn finish(self) -> InvocationTrace {
+        let Self {
+            invocation,
+            results,
+            started,
+            timeout,
+        } = self;
+        let results = async {
+            tokio::time::timeout(timeout, results?.next())
+                .await
+                .context("no results were sent before the timeout")?
+                .context("results subscription closed")
+        }
+        .await;
+        match results {
+            Ok(_) => InvocationTrace {
+                invocation,
+                latency: Some(started.elapsed()),
+                error: None,
+            },
+            Err(err) => InvocationTrace {
+                invocation,
+                latency: None,
+                error: Some(format!("{err:#}")),
+            },
+        }
+    }
 }
 
 /// A inner message that we've seen in an invocation message. This will either be a raw bytes or a
@@ -142,6 +227,7 @@ impl Stream for Spier {
                     to,
                     operation: operation.join("."),
                     message: ObservedMessage::parse(msg.payload.to_vec()),
+                    reply: msg.reply.map(|reply| reply.to_string()),
                 }))
             }
             Poll::Pending => Poll::Pending,
@@ -58,6 +58,8 @@
 use anyhow::Result;
 use tracing::debug;
 
+use crate::lib::backoff::Backoff;
+
 /// Wait for a server to come up, using default timeouts
 pub async fn wait_for_server(url: &str, service: &str) -> Result<()> {
     wait_for_server_with_timeout(url, service, std::time::Duration::from_secs(15)).await
@@ -69,18 +71,18 @@ pub async fn wait_for_server_with_timeout(
     service: &str,
     timeout: std::time::Duration,
 ) -> Result<()> {
-    tokio::time::timeout(timeout, async {
-        let mut wait_count = 1;
-        loop {
-            if tokio::net::TcpStream::connect(url).await.is_ok() {
-                break;
-            }
-            debug!("Waiting for {service} at {url} to come up, attempt {wait_count}. Will retry in 1 second.");
-            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-            wait_count += 1;
-        }
-    })
+    let mut wait_count = 1;
+    let connect = || {
+        debug!("Waiting for {service} at {url} to come up, attempt {wait_count}");
+        wait_count += 1;
+        tokio::net::TcpStream::connect(url)
+    };
+    tokio::time::timeout(
+        timeout,
+        Backoff::fixed(std::time::Duration::from_secs(1)).retry_forever(connect),
+    )
     .await
+    .map(|_| ())
     .map_err(|_| anyhow::anyhow!("Timed out waiting for {service} to start"))
 }
 
@@ -1,9 +1,16 @@
+use std::future::Future;
+
 use anyhow::{anyhow, bail, Result};
 use cloudevents::event::{AttributesReader, Event};
+use oci_client::Reference;
 use tokio::sync::mpsc::Receiver;
 use tokio::time::{Duration, Instant};
+use tracing::debug;
 
+use crate::lib::backoff::{Backoff, Polled};
 use crate::lib::component::ComponentScaledInfo;
+use crate::lib::failure::{Failure, FailureKind};
+use crate::lib::provider::ProviderStartError;
 
 /// Useful parts of a `CloudEvent` coming in from the wasmbus.
 #[derive(Debug, Clone)]
@@ -69,16 +76,20 @@ async fn find_event<T>(
     receiver: &mut Receiver<Event>,
     timeout: Duration,
     check_function: impl Fn(Event) -> Result<EventCheckOutcome<T>>,
+    mut on_event: impl FnMut(&Event),
 ) -> Result<FindEventOutcome<T>> {
     let start = Instant::now();
     loop {
         let elapsed = start.elapsed();
         if elapsed >= timeout {
-            bail!("Timeout waiting for event");
+            return Err(
+                Failure::new(FailureKind::EventTimeout, "Timeout waiting for event").into(),
+            );
         }
 
         match tokio::time::timeout(timeout - elapsed, receiver.recv()).await {
             Ok(Some(event)) => {
+                on_event(&event);
                 let outcome = check_function(event)?;
 
                 match outcome {
@@ -90,9 +101,13 @@ async fn find_event<T>(
                 }
             }
             Err(_e) => {
-                return Ok(FindEventOutcome::Failure(anyhow!(
-                    "Timed out waiting for applicable event, operation may have failed"
-                )))
+                return Ok(FindEventOutcome::Failure(
+                    Failure::new(
+                        FailureKind::EventTimeout,
+                        "Timed out waiting for applicable event, operation may have failed",
+                    )
+                    .into(),
+                ))
             }
             // Should only happen due to an internal failure with the events receiver
             Ok(None) => {
@@ -164,7 +179,7 @@ pub async fn wait_for_component_scaled_event(
         Ok(EventCheckOutcome::NotApplicable)
     };
 
-    let event = find_event(receiver, timeout, check_function).await?;
+    let event = find_event(receiver, timeout, check_function, |_| {}).await?;
     Ok(event)
 }
 
@@ -175,6 +190,56 @@ pub struct ProviderStartedInfo {
     pub provider_id: String,
 }
 
+/// How provider start events are matched against the provider that was asked to start
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProviderRefMatch {
+    /// Only events carrying exactly the submitted ref match
+    Exact,
+    /// Events also match if they carry the provider ID of the start request, or a ref naming the
+    /// same image once both are normalized, e.g. the digest the host resolved a submitted tag to
+    #[default]
+    Lenient,
+}
+
+/// The provider a start event is waited for
+#[derive(Clone, Debug)]
+pub struct ProviderStartTarget {
+    pub provider_ref: String,
+    pub provider_id: String,
+    pub ref_match: ProviderRefMatch,
+}
+
+impl ProviderStartTarget {
+    /// Whether an event for `event_ref` and, if the event has one, `event_provider_id` is about
+    /// this provider
+    #[must_use]
+    pub fn matches(&self, event_ref: &str, event_provider_id: Option<&str>) -> bool {
+        match self.ref_match {
+            ProviderRefMatch::Exact => event_ref == self.provider_ref,
+            ProviderRefMatch::Lenient => {
+                event_provider_id == Some(self.provider_id.as_str())
+                    || same_image(event_ref, &self.provider_ref)
+            }
+        }
+    }
+}
+
+/// Whether two refs name the same image: equal registry and repository, and equal tag and digest
+/// where both refs have one. Refs that aren't OCI references, e.g. `file://` paths, must be equal
+fn same_image(a: &str, b: &str) -> bool {
+    if a == b {
+        return true;
+    }
+    let (Ok(a), Ok(b)) = (a.parse::<Reference>(), b.parse::<Reference>()) else {
+        return false;
+    };
+    let compatible = |x: Option<&str>, y: Option<&str>| x.is_none() || y.is_none() || x == y;
+    a.registry() == b.registry()
+        && a.repository() == b.repository()
+        && compatible(a.tag(), b.tag())
+        && compatible(a.digest(), b.digest())
+}
+
 /// Uses the NATS receiver to read events being published to the wasmCloud lattice event subject, up until the given timeout duration.
 ///
 /// If the applicable provider start response event is found (either started or failed to start), the `Ok` variant of the `Result` will be returned,
@@ -186,6 +251,34 @@ pub async fn wait_for_provider_start_event(
     timeout: Duration,
     host_id: String,
     provider_ref: String,
+) -> Result<FindEventOutcome<ProviderStartedInfo>> {
+    let target = ProviderStartTarget {
+        provider_ref,
+        provider_id: String::new(),
+        ref_match: ProviderRefMatch::Exact,
+    };
+    wait_for_provider_start_event_matching(receiver, timeout, host_id, target).await
+}
+
+/// Same as [`wait_for_provider_start_event`], but events are matched against `target`, e.g. to
+/// also accept the digest a host resolved the submitted tag to
+pub async fn wait_for_provider_start_event_matching(
+    receiver: &mut Receiver<Event>,
+    timeout: Duration,
+    host_id: String,
+    target: ProviderStartTarget,
+) -> Result<FindEventOutcome<ProviderStartedInfo>> {
+    watch_for_provider_start_event(receiver, timeout, host_id, target, |_| {}).await
+}
+
+/// Same as [`wait_for_provider_start_event`], but every event received while waiting is passed to
+/// `on_event` before it is checked, so callers can stream intermediate events as they arrive.
+pub async fn watch_for_provider_start_event(
+    receiver: &mut Receiver<Event>,
+    timeout: Duration,
+    host_id: String,
+    target: ProviderStartTarget,
+    on_event: impl FnMut(&Event),
 ) -> Result<FindEventOutcome<ProviderStartedInfo>> {
     let check_function = move |event: Event| {
         let cloud_event = get_wasmbus_event_info(event)?;
@@ -197,13 +290,15 @@ pub async fn wait_for_provider_start_event(
         match cloud_event.event_type.as_str() {
             "com.wasmcloud.lattice.provider_started" => {
                 let image_ref = get_string_data_from_json(&cloud_event.data, "image_ref")?;
+                let provider_id = get_string_data_from_json(&cloud_event.data, "provider_id").ok();
 
-                if image_ref == provider_ref {
-                    let provider_id = get_string_data_from_json(&cloud_event.data, "provider_id")?;
+                if target.matches(&image_ref, provider_id.as_deref()) {
+                    let provider_id = provider_id
+                        .ok_or_else(|| anyhow!("No provider_id key found in json data"))?;
 
                     return Ok(EventCheckOutcome::Success(ProviderStartedInfo {
                         host_id: host_id.as_str().into(),
-                        provider_ref: provider_ref.as_str().into(),
+                        provider_ref: target.provider_ref.clone(),
                         provider_id,
                     }));
                 }
@@ -211,17 +306,22 @@ pub async fn wait_for_provider_start_event(
             "com.wasmcloud.lattice.provider_start_failed" => {
                 let returned_provider_ref =
                     get_string_data_from_json(&cloud_event.data, "provider_ref")?;
+                let provider_id = get_string_data_from_json(&cloud_event.data, "provider_id").ok();
 
-                if returned_provider_ref == provider_ref {
-                    let error = anyhow!(
-                        "{}",
-                        cloud_event
-                            .data
-                            .get("error")
-                            .ok_or_else(|| anyhow!("No error found in data"))?
-                            .as_str()
-                            .ok_or_else(|| anyhow!("error is not a string"))?
-                    );
+                if target.matches(&returned_provider_ref, provider_id.as_deref()) {
+                    let error = cloud_event
+                        .data
+                        .get("error")
+                        .ok_or_else(|| anyhow!("No error found in data"))?
+                        .as_str()
+                        .ok_or_else(|| anyhow!("error is not a string"))?;
+                    // Hosts that split the pull from the launch report the phase that failed
+                    let error = match get_string_data_from_json(&cloud_event.data, "phase") {
+                        Ok(phase) if phase == "pull" => {
+                            anyhow::Error::new(ProviderStartError::pull_failure(error))
+                        }
+                        _ => anyhow!("{error}"),
+                    };
 
                     return Ok(EventCheckOutcome::Failure(error));
                 }
@@ -232,10 +332,176 @@ pub async fn wait_for_provider_start_event(
         Ok(EventCheckOutcome::NotApplicable)
     };
 
-    let event = find_event(receiver, timeout, check_function).await?;
+    let event = find_event(receiver, timeout, check_function, on_event).await?;
     Ok(event)
 }
 
+/// Same as [`watch_for_provider_start_event`], but also calls `poll` every `poll_interval` as a
+/// second way of confirming the start, in case the started event is lost. `poll` returns the ID of
+/// the provider once the host reports it running (e.g. in its inventory), which counts as a
+/// successful start. Errors from `poll` are logged and polling continues.
+///
+/// Polling only ever confirms a start. A provider that never shows up in the inventory is still
+/// reported by the start failed event, which takes precedence over a poll finishing at the same
+/// time.
+pub async fn wait_for_provider_start_or_inventory<F, Fut>(
+    receiver: &mut Receiver<Event>,
+    timeout: Duration,
+    host_id: String,
+    target: ProviderStartTarget,
+    poll_interval: Duration,
+    on_event: impl FnMut(&Event),
+    mut poll: F,
+) -> Result<FindEventOutcome<ProviderStartedInfo>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Option<String>>>,
+{
+    let poll_inventory = async {
+        let polled = Backoff::fixed(poll_interval)
+            .poll(None, || {
+                let provider_id = poll();
+                async move {
+                    Ok(provider_id
+                        .await?
+                        .map_or(Polled::Pending(()), Polled::Ready))
+                }
+            })
+            .await;
+        match polled {
+            Polled::Ready(provider_id) => provider_id,
+            // Polling without a timeout only ends once the provider is found
+            Polled::Pending(_) => std::future::pending().await,
+        }
+    };
+
+    tokio::select! {
+        biased;
+        event = watch_for_provider_start_event(
+            receiver,
+            timeout,
+            host_id.clone(),
+            target.clone(),
+            on_event,
+        ) => event,
+        provider_id = poll_inventory => {
+            debug!(%provider_id, "provider start confirmed by polling");
+            Ok(FindEventOutcome::Success(ProviderStartedInfo {
+                host_id,
+                provider_ref: target.provider_ref,
+                provider_id,
+            }))
+        }
+    }
+}
+
+/// Poll until `pending` reports that none of a provider's links are outstanding, e.g. once the
+/// components it links to are running. Polling errors are logged and retried. If the timeout is
+/// reached, the `Err` variant lists the links that were still pending
+pub async fn wait_for_provider_links<F, Fut>(
+    timeout: Duration,
+    poll_interval: Duration,
+    pending: F,
+) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Vec<String>>>,
+{
+    let Err(outstanding) = poll_until_none_pending(timeout, poll_interval, pending).await else {
+        return Ok(());
+    };
+    bail!(
+        "Timed out after {}ms waiting for provider links, still pending:\n  {}",
+        timeout.as_millis(),
+        outstanding.join("\n  ")
+    );
+}
+
+/// Poll until `pending` reports that the host inventory reflects every label change, e.g. after
+/// putting or deleting host labels. Polling errors are logged and retried. If the timeout is
+/// reached, the `Err` variant lists the labels that were still pending
+pub async fn wait_for_host_labels<F, Fut>(
+    timeout: Duration,
+    poll_interval: Duration,
+    pending: F,
+) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Vec<String>>>,
+{
+    let Err(outstanding) = poll_until_none_pending(timeout, poll_interval, pending).await else {
+        return Ok(());
+    };
+    bail!(
+        "Timed out after {}ms waiting for the host to report its labels, still pending: {}",
+        timeout.as_millis(),
+        outstanding.join(", ")
+    );
+}
+
+/// Poll `pending` until it reports nothing outstanding. Returns `Ok` once that happens, or else
+/// the `Err` variant with what was still outstanding when the timeout was reached
+async fn poll_until_none_pending<F, Fut>(
+    timeout: Duration,
+    poll_interval: Duration,
+    mut pending: F,
+) -> std::result::Result<(), Vec<String>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Vec<String>>>,
+{
+    let polled = Backoff::fixed(poll_interval)
+        .poll(Some(timeout), || {
+            let pending = pending();
+            async move {
+                let pending = pending.await?;
+                Ok(if pending.is_empty() {
+                    Polled::Ready(())
+                } else {
+                    Polled::Pending(pending)
+                })
+            }
+        })
+        .await;
+    match polled {
+        Polled::Ready(()) => Ok(()),
+        Polled::Pending(outstanding) => Err(outstanding.unwrap_or_default()),
+    }
+}
+
+/// Poll until `scaled` reports that a component is at its requested scale, e.g. once the host
+/// inventory shows the new max instances. Polling errors are logged and retried. If the timeout is
+/// reached, the `Err` variant is returned
+pub async fn wait_for_component_scale<F, Fut>(
+    timeout: Duration,
+    poll_interval: Duration,
+    mut scaled: F,
+) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<bool>>,
+{
+    let polled = Backoff::fixed(poll_interval)
+        .poll(Some(timeout), || {
+            let scaled = scaled();
+            async move {
+                Ok(if scaled.await? {
+                    Polled::Ready(())
+                } else {
+                    Polled::Pending(())
+                })
+            }
+        })
+        .await;
+    if let Polled::Pending(_) = polled {
+        bail!(
+            "Timed out after {}ms waiting for the host to report the requested scale",
+            timeout.as_millis()
+        );
+    }
+    Ok(())
+}
+
 /// Information related to an provider stop
 pub struct ProviderStoppedInfo {
     pub host_id: String,
@@ -297,7 +563,7 @@ pub async fn wait_for_provider_stop_event(
         Ok(EventCheckOutcome::NotApplicable)
     };
 
-    let event = find_event(receiver, timeout, check_function).await?;
+    let event = find_event(receiver, timeout, check_function, |_| {}).await?;
     Ok(event)
 }
 
@@ -309,7 +575,11 @@ pub struct ComponentStoppedInfo {
 
 /// Uses the NATS receiver to read events being published to the wasmCloud lattice event subject, up until the given timeout duration.
 ///
-/// If the applicable stop component response event is found (either started or failed to start), the `Ok` variant of the `Result` will be returned,
+/// Components are stopped by scaling them to zero, so only a scaled event for the component that
+/// reports zero max instances counts as the stop. Unlike [`wait_for_component_scaled_event`], the
+/// component is matched by ID, as the same reference may run under other IDs on the host.
+///
+/// If the applicable stop component response event is found (either stopped or failed to stop), the `Ok` variant of the `Result` will be returned,
 /// with the `FindEventOutcome` enum containing the success or failure state of the event.
 ///
 /// If the timeout is reached or another error occurs, the `Err` variant of the `Result` will be returned.
@@ -329,8 +599,12 @@ pub async fn wait_for_component_stop_event(
         match cloud_event.event_type.as_str() {
             "com.wasmcloud.lattice.component_scaled" => {
                 let returned_component_id =
-                    get_string_data_from_json(&cloud_event.data, "public_key")?;
-                if returned_component_id == component_id {
+                    get_string_data_from_json(&cloud_event.data, "component_id")?;
+                let max_instances = cloud_event
+                    .data
+                    .get("max_instances")
+                    .and_then(serde_json::Value::as_u64);
+                if returned_component_id == component_id && max_instances == Some(0) {
                     return Ok(EventCheckOutcome::Success(ComponentStoppedInfo {
                         host_id: host_id.as_str().into(),
                         component_id: returned_component_id,
@@ -339,7 +613,7 @@ pub async fn wait_for_component_stop_event(
             }
             "com.wasmcloud.lattice.component_scale_failed" => {
                 let returned_component_id =
-                    get_string_data_from_json(&cloud_event.data, "public_key")?;
+                    get_string_data_from_json(&cloud_event.data, "component_id")?;
 
                 if returned_component_id == component_id {
                     let error = anyhow!(
@@ -361,6 +635,702 @@ pub async fn wait_for_component_stop_event(
         Ok(EventCheckOutcome::NotApplicable)
     };
 
-    let event = find_event(receiver, timeout, check_function).await?;
+    let event = find_event(receiver, timeout, check_function, |_| {}).await?;
     Ok(event)
 }
+
+/// Uses the NATS receiver to wait for a started provider to pass a health check, up until the given
+/// timeout duration.
+///
+/// Health check events received during the `warmup` window are not evaluated, so a provider that
+/// reports unhealthy while it initializes isn't treated as a failure. Once the warmup has elapsed,
+/// the first health check event for the provider decides the outcome. Because the host only
+/// publishes pass/fail events on transitions, a plain status event counts as healthy when the last
+/// transition seen (including during warmup) was a pass, and as unhealthy when it was a failure.
+///
+/// If the timeout is reached or another error occurs, the `Err` variant of the `Result` will be returned.
+pub async fn wait_for_provider_health(
+    receiver: &mut Receiver<Event>,
+    warmup: Duration,
+    timeout: Duration,
+    host_id: String,
+    provider_id: String,
+) -> Result<FindEventOutcome<()>> {
+    let is_provider_event = |event: &Event| {
+        *event.source() == host_id
+            && get_wasmbus_event_info(event.clone())
+                .and_then(|e| get_string_data_from_json(&e.data, "provider_id"))
+                .is_ok_and(|id| id == provider_id)
+    };
+
+    let mut last_transition = None;
+    watch_events(receiver, warmup, |event| {
+        if !is_provider_event(event) {
+            return;
+        }
+        match event.ty() {
+            "com.wasmcloud.lattice.health_check_passed" => last_transition = Some(true),
+            "com.wasmcloud.lattice.health_check_failed" => last_transition = Some(false),
+            _ => {}
+        }
+    })
+    .await;
+
+    let check_function = move |event: Event| {
+        if !is_provider_event(&event) {
+            return Ok(EventCheckOutcome::NotApplicable);
+        }
+        let healthy = match event.ty() {
+            "com.wasmcloud.lattice.health_check_passed" => Some(true),
+            "com.wasmcloud.lattice.health_check_failed" => Some(false),
+            "com.wasmcloud.lattice.health_check_status" => last_transition,
+            _ => None,
+        };
+        Ok(match healthy {
+            Some(true) => EventCheckOutcome::Success(()),
+            Some(false) => EventCheckOutcome::Failure(anyhow!("Provider failed its health check")),
+            None => EventCheckOutcome::NotApplicable,
+        })
+    };
+
+    find_event(receiver, timeout, check_function, |_| {}).await
+}
+
+/// Keeps reading events from the receiver for the given window, passing each one to `on_event`.
+///
+/// This is meant to be used after a terminal event has already been found, to keep observing
+/// follow-up events (e.g. health checks) for a while. Returns the number of events observed.
+pub async fn watch_events(
+    receiver: &mut Receiver<Event>,
+    window: Duration,
+    mut on_event: impl FnMut(&Event),
+) -> usize {
+    let deadline = Instant::now() + window;
+    let mut observed = 0;
+    while let Ok(Some(event)) = tokio::time::timeout_at(deadline, receiver.recv()).await {
+        on_event(&event);
+        observed += 1;
+    }
+    observed
+}
+
+/// Lattice events related to starting and scaling components and providers
+pub const START_AND_SCALE_EVENTS: [&str; 4] = [
+    "provider_started",
+    "provider_start_failed",
+    "component_scaled",
+    "component_scale_failed",
+];
+
+/// Narrows down lattice events to those about a given image reference, component and/or host
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    /// Only match events about this component or provider reference
+    pub image_ref: Option<String>,
+    /// Only match events emitted by this host
+    pub host_id: Option<String>,
+    /// Only match events about this component or provider ID
+    pub component_id: Option<String>,
+}
+
+impl EventFilter {
+    /// Whether the event passes the filter. Events are matched on the reference in their
+    /// `image_ref` or `provider_ref` data field, on the ID in their `component_id` or `provider_id`
+    /// field, and on their source (or `host_id` field) for the host
+    #[must_use]
+    pub fn matches(&self, event: &Event) -> bool {
+        let data = get_wasmbus_event_info(event.clone()).ok().map(|e| e.data);
+        let field = |key: &str| {
+            data.as_ref()
+                .and_then(|d| get_string_data_from_json(d, key).ok())
+        };
+        let ref_matches = self.image_ref.as_ref().is_none_or(|image_ref| {
+            field("image_ref")
+                .or_else(|| field("provider_ref"))
+                .is_some_and(|r| r == *image_ref)
+        });
+        let host_matches = self.host_id.as_ref().is_none_or(|host_id| {
+            *event.source() == **host_id || field("host_id").is_some_and(|h| h == *host_id)
+        });
+        let id_matches = self.component_id.as_ref().is_none_or(|id| {
+            field("component_id")
+                .or_else(|| field("provider_id"))
+                .is_some_and(|i| i == *id)
+        });
+        ref_matches && host_matches && id_matches
+    }
+}
+
+/// Collect every event passing the filter that arrives within the window
+pub async fn record_events(
+    receiver: &mut Receiver<Event>,
+    window: Duration,
+    filter: &EventFilter,
+) -> Vec<Event> {
+    let mut events = Vec::new();
+    watch_events(receiver, window, |event| {
+        if filter.matches(event) {
+            events.push(event.clone());
+        }
+    })
+    .await;
+    events
+}
+
+/// A component scale reported by a host in a `component_scaled` or `component_scale_failed` event
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ObservedComponentScale {
+    pub host_id: String,
+    pub component_id: String,
+    /// The max instances the component was scaled to
+    pub max_instances: u32,
+    /// Set if the host failed to scale the component
+    pub error: Option<String>,
+}
+
+/// Find the most recent scale of the given component reference among the events, which are
+/// expected to be in the order they were received
+#[must_use]
+pub fn last_component_scale(
+    events: &[Event],
+    component_ref: &str,
+) -> Option<ObservedComponentScale> {
+    events.iter().rev().find_map(|event| {
+        let info = get_wasmbus_event_info(event.clone()).ok()?;
+        let failed = match info.event_type.as_str() {
+            "com.wasmcloud.lattice.component_scaled" => false,
+            "com.wasmcloud.lattice.component_scale_failed" => true,
+            _ => return None,
+        };
+        if get_string_data_from_json(&info.data, "image_ref").ok()? != component_ref {
+            return None;
+        }
+        Some(ObservedComponentScale {
+            host_id: get_string_data_from_json(&info.data, "host_id").unwrap_or(info.source),
+            component_id: get_string_data_from_json(&info.data, "component_id").ok()?,
+            max_instances: info
+                .data
+                .get("max_instances")
+                .and_then(serde_json::Value::as_u64)
+                .and_then(|max| u32::try_from(max).ok())
+                .unwrap_or(u32::MAX),
+            error: failed.then(|| {
+                get_string_data_from_json(&info.data, "error")
+                    .unwrap_or_else(|_| "No error message in the event".to_string())
+            }),
+        })
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use cloudevents::{EventBuilder, EventBuilderV10};
+    use serde_json::json;
+
+    use super::*;
+    use crate::lib::provider::{ProviderStartFailureClass, ProviderStartPhase};
+
+    const HOST_ID: &str = "NCE7YHGI42RWEKBRDJZWXBEJJCFNE5YIWYMSTLGHQBEGFY55BKJ3EG3G";
+
+    fn event(ty: &str, data: serde_json::Value) -> Event {
+        EventBuilderV10::new()
+            .id("test")
+            .ty(format!("com.wasmcloud.lattice.{ty}"))
+            .source(HOST_ID)
+            .data("application/json", data)
+            .build()
+            .expect("failed to build event")
+    }
+
+    fn target(
+        provider_ref: &str,
+        provider_id: &str,
+        ref_match: ProviderRefMatch,
+    ) -> ProviderStartTarget {
+        ProviderStartTarget {
+            provider_ref: provider_ref.to_string(),
+            provider_id: provider_id.to_string(),
+            ref_match,
+        }
+    }
+
+    fn health_event(ty: &str) -> Event {
+        event(ty, json!({"host_id": HOST_ID, "provider_id": "provider"}))
+    }
+
+    #[tokio::test]
+    async fn health_is_not_evaluated_during_warmup() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        // Flapping while warming up must not fail the wait
+        tx.send(health_event("health_check_failed")).await.unwrap();
+        let sender = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            tx.send(health_event("health_check_passed")).await.unwrap();
+        });
+
+        let outcome = wait_for_provider_health(
+            &mut rx,
+            Duration::from_millis(150),
+            Duration::from_secs(2),
+            HOST_ID.to_string(),
+            "provider".to_string(),
+        )
+        .await
+        .expect("should find health event");
+        assert!(matches!(outcome, FindEventOutcome::Success(())));
+        sender.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn health_is_evaluated_after_warmup() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        tx.send(health_event("health_check_passed")).await.unwrap();
+        let sender = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            tx.send(health_event("health_check_failed")).await.unwrap();
+        });
+
+        let outcome = wait_for_provider_health(
+            &mut rx,
+            Duration::from_millis(150),
+            Duration::from_secs(2),
+            HOST_ID.to_string(),
+            "provider".to_string(),
+        )
+        .await
+        .expect("should find health event");
+        assert!(matches!(outcome, FindEventOutcome::Failure(_)));
+        sender.await.unwrap();
+
+        // A status event after warmup reflects the last transition seen during warmup
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        tx.send(health_event("health_check_passed")).await.unwrap();
+        let sender = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            tx.send(health_event("health_check_status")).await.unwrap();
+        });
+        let outcome = wait_for_provider_health(
+            &mut rx,
+            Duration::from_millis(150),
+            Duration::from_secs(2),
+            HOST_ID.to_string(),
+            "provider".to_string(),
+        )
+        .await
+        .expect("should find health event");
+        assert!(matches!(outcome, FindEventOutcome::Success(())));
+        sender.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn record_events_captures_filtered_events() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        for (ty, data) in [
+            (
+                "provider_started",
+                json!({"image_ref": "ghcr.io/kv:0.1.0", "provider_id": "kv"}),
+            ),
+            (
+                "provider_start_failed",
+                json!({"provider_ref": "ghcr.io/kv:0.1.0", "provider_id": "kv", "error": "nope"}),
+            ),
+            (
+                "component_scaled",
+                json!({"image_ref": "ghcr.io/hello:0.1.0", "component_id": "hello"}),
+            ),
+        ] {
+            tx.send(event(ty, data)).await.unwrap();
+        }
+
+        let filter = EventFilter {
+            image_ref: Some("ghcr.io/kv:0.1.0".to_string()),
+            host_id: Some(HOST_ID.to_string()),
+            ..Default::default()
+        };
+        let events = record_events(&mut rx, Duration::from_millis(100), &filter).await;
+        assert_eq!(
+            events
+                .iter()
+                .map(|e| e.ty().to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "com.wasmcloud.lattice.provider_started",
+                "com.wasmcloud.lattice.provider_start_failed"
+            ]
+        );
+        let json = serde_json::to_value(&events).expect("events should serialize");
+        assert_eq!(json[1]["data"]["error"], "nope");
+
+        let other_host = EventFilter {
+            host_id: Some("NOTTHEHOST".to_string()),
+            ..Default::default()
+        };
+        tx.send(event(
+            "component_scaled",
+            json!({"image_ref": "ghcr.io/hello:0.1.0"}),
+        ))
+        .await
+        .unwrap();
+        assert!(
+            record_events(&mut rx, Duration::from_millis(100), &other_host)
+                .await
+                .is_empty()
+        );
+
+        let by_component = EventFilter {
+            component_id: Some("hello".to_string()),
+            ..Default::default()
+        };
+        for id in ["hello", "goodbye"] {
+            tx.send(event(
+                "component_scaled",
+                json!({"image_ref": "ghcr.io/hello:0.1.0", "component_id": id}),
+            ))
+            .await
+            .unwrap();
+        }
+        let events = record_events(&mut rx, Duration::from_millis(100), &by_component).await;
+        assert_eq!(events.len(), 1);
+        assert!(by_component.matches(&events[0]));
+    }
+
+    #[tokio::test]
+    async fn provider_links_are_awaited_until_live() {
+        let mut polls = 0;
+        wait_for_provider_links(Duration::from_secs(1), Duration::from_millis(10), || {
+            polls += 1;
+            let pending = if polls < 3 {
+                vec!["default link to [echo]".to_string()]
+            } else {
+                Vec::new()
+            };
+            async move { Ok(pending) }
+        })
+        .await
+        .expect("links should become live");
+        assert_eq!(polls, 3);
+
+        let err = wait_for_provider_links(
+            Duration::from_millis(50),
+            Duration::from_millis(10),
+            || async { Ok(vec!["default link to [echo]".to_string()]) },
+        )
+        .await
+        .expect_err("pending links should time out");
+        assert!(err.to_string().contains("Timed out after 50ms"));
+        assert!(err.to_string().contains("default link to [echo]"));
+    }
+
+    #[tokio::test]
+    async fn component_stop_waits_for_scale_to_zero() {
+        let scaled = |component_id: &str, max_instances: u32| {
+            event(
+                "component_scaled",
+                json!({
+                    "image_ref": "ghcr.io/echo:0.1.0",
+                    "component_id": component_id,
+                    "max_instances": max_instances,
+                }),
+            )
+        };
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        // Neither a scale of the component to other counts nor a stop of another component with
+        // the same reference is the stop
+        tx.send(scaled("echo", 2)).await.unwrap();
+        tx.send(scaled("echo-copy", 0)).await.unwrap();
+        tx.send(scaled("echo", 0)).await.unwrap();
+        let outcome = wait_for_component_stop_event(
+            &mut rx,
+            Duration::from_secs(1),
+            HOST_ID.to_string(),
+            "echo".to_string(),
+        )
+        .await
+        .expect("wait should not error");
+        let FindEventOutcome::Success(info) = outcome else {
+            panic!("component should have been stopped");
+        };
+        assert_eq!(info.component_id, "echo");
+        assert_eq!(info.host_id, HOST_ID);
+        assert!(rx.try_recv().is_err(), "stop should be the last event read");
+
+        tx.send(scaled("echo", 2)).await.unwrap();
+        let outcome = wait_for_component_stop_event(
+            &mut rx,
+            Duration::from_millis(100),
+            HOST_ID.to_string(),
+            "echo".to_string(),
+        )
+        .await
+        .expect("wait should time out without error");
+        assert!(matches!(outcome, FindEventOutcome::Failure(_)));
+    }
+
+    #[tokio::test]
+    async fn component_scale_is_awaited_until_reported() {
+        let mut polls = 0;
+        wait_for_component_scale(Duration::from_secs(1), Duration::from_millis(10), || {
+            polls += 1;
+            let result = match polls {
+                1 => Err(anyhow::anyhow!("inventory request timed out")),
+                2 => Ok(false),
+                _ => Ok(true),
+            };
+            async move { result }
+        })
+        .await
+        .expect("scale should be reported");
+        assert_eq!(polls, 3);
+
+        let err = wait_for_component_scale(
+            Duration::from_millis(50),
+            Duration::from_millis(10),
+            || async { Ok(false) },
+        )
+        .await
+        .expect_err("scale that is never reported should time out");
+        assert!(err.to_string().contains("Timed out after 50ms"));
+    }
+
+    #[tokio::test]
+    async fn watch_continues_after_terminal_event() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        tx.send(event(
+            "provider_started",
+            json!({"image_ref": "ghcr.io/provider:v1", "provider_id": "provider"}),
+        ))
+        .await
+        .unwrap();
+
+        let mut seen = Vec::new();
+        let outcome = watch_for_provider_start_event(
+            &mut rx,
+            Duration::from_secs(1),
+            HOST_ID.to_string(),
+            target("ghcr.io/provider:v1", "provider", ProviderRefMatch::Exact),
+            |e| seen.push(e.ty().to_string()),
+        )
+        .await
+        .expect("should find start event");
+        assert!(matches!(outcome, FindEventOutcome::Success(_)));
+        assert_eq!(seen, vec!["com.wasmcloud.lattice.provider_started"]);
+
+        let sender = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            tx.send(event(
+                "health_check_passed",
+                json!({"provider_id": "provider"}),
+            ))
+            .await
+            .unwrap();
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            // This one arrives after the watch window and must not be observed
+            let _ = tx
+                .send(event(
+                    "health_check_status",
+                    json!({"provider_id": "provider"}),
+                ))
+                .await;
+        });
+
+        let observed = watch_events(&mut rx, Duration::from_millis(300), |e| {
+            seen.push(e.ty().to_string())
+        })
+        .await;
+        assert_eq!(observed, 1);
+        assert_eq!(
+            seen,
+            vec![
+                "com.wasmcloud.lattice.provider_started",
+                "com.wasmcloud.lattice.health_check_passed"
+            ]
+        );
+        sender.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn start_event_with_resolved_digest_matches_submitted_tag() {
+        const TAG_REF: &str = "ghcr.io/wasmcloud/http-server:0.23.2";
+        const DIGEST_REF: &str = "ghcr.io/wasmcloud/http-server@sha256:0fd9e2f4d1cd0ee1ec5f2e8aae50f9ff2bd0aa4be3bc5f3bd3ba49a8bd3a3c12";
+        let started = || {
+            // The host reports the digest it pulled and its own ID for the provider, so only the
+            // normalized ref can match
+            event(
+                "provider_started",
+                json!({"image_ref": DIGEST_REF, "provider_id": "resolved"}),
+            )
+        };
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        tx.send(started()).await.unwrap();
+        let outcome = wait_for_provider_start_event_matching(
+            &mut rx,
+            Duration::from_secs(1),
+            HOST_ID.to_string(),
+            target(TAG_REF, "http-server", ProviderRefMatch::Lenient),
+        )
+        .await
+        .expect("digest of the submitted tag should match");
+        let FindEventOutcome::Success(info) = outcome else {
+            panic!("provider should have started");
+        };
+        assert_eq!(info.provider_ref, TAG_REF);
+        assert_eq!(info.provider_id, "resolved");
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        tx.send(started()).await.unwrap();
+        let outcome = wait_for_provider_start_event_matching(
+            &mut rx,
+            Duration::from_millis(100),
+            HOST_ID.to_string(),
+            target(TAG_REF, "http-server", ProviderRefMatch::Exact),
+        )
+        .await
+        .expect("wait should time out without error");
+        assert!(
+            matches!(outcome, FindEventOutcome::Failure(_)),
+            "exact matching should miss the digest ref"
+        );
+
+        let lenient = target(TAG_REF, "http-server", ProviderRefMatch::Lenient);
+        assert!(lenient.matches("ghcr.io/other/provider:0.1.0", Some("http-server")));
+        assert!(!lenient.matches("ghcr.io/wasmcloud/http-server:0.24.0", None));
+        assert!(!lenient.matches("ghcr.io/wasmcloud/http-client@sha256:0fd9e2f4d1cd0ee1ec5f2e8aae50f9ff2bd0aa4be3bc5f3bd3ba49a8bd3a3c12", None));
+        drop(tx);
+    }
+
+    #[tokio::test]
+    async fn inventory_poll_confirms_start_when_event_is_lost() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        // The started event never arrives, only unrelated events do
+        tx.send(event(
+            "component_scaled",
+            json!({"image_ref": "ghcr.io/hello:0.1.0", "component_id": "hello"}),
+        ))
+        .await
+        .unwrap();
+
+        let polls = std::sync::atomic::AtomicU32::new(0);
+        let outcome = wait_for_provider_start_or_inventory(
+            &mut rx,
+            Duration::from_secs(5),
+            HOST_ID.to_string(),
+            target("ghcr.io/kv:0.1.0", "kv", ProviderRefMatch::default()),
+            Duration::from_millis(10),
+            |_| {},
+            || async {
+                // Not in the inventory until the third poll, and one poll fails outright
+                match polls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) {
+                    0 => Ok(None),
+                    1 => Err(anyhow!("inventory request timed out")),
+                    _ => Ok(Some("kv".to_string())),
+                }
+            },
+        )
+        .await
+        .expect("should confirm the start");
+        let FindEventOutcome::Success(info) = outcome else {
+            panic!("start should be confirmed by the inventory");
+        };
+        assert_eq!(info.provider_id, "kv");
+        assert_eq!(info.host_id, HOST_ID);
+        assert_eq!(polls.load(std::sync::atomic::Ordering::SeqCst), 3);
+        drop(tx);
+    }
+
+    #[tokio::test]
+    async fn start_failure_is_reported_while_polling_the_inventory() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        let polls = std::sync::atomic::AtomicU32::new(0);
+        let wait = wait_for_provider_start_or_inventory(
+            &mut rx,
+            Duration::from_secs(5),
+            HOST_ID.to_string(),
+            target("ghcr.io/kv:0.1.0", "kv", ProviderRefMatch::default()),
+            Duration::from_millis(10),
+            |_| {},
+            || async {
+                // The host never reports the provider as running
+                polls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Ok(None)
+            },
+        );
+        let send_failure = async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            tx.send(event(
+                "provider_start_failed",
+                json!({
+                    "provider_ref": "ghcr.io/kv:0.1.0",
+                    "provider_id": "kv",
+                    "error": "failed to launch provider",
+                }),
+            ))
+            .await
+            .unwrap();
+        };
+        let (outcome, ()) = tokio::join!(wait, send_failure);
+
+        let FindEventOutcome::Failure(err) = outcome.expect("the failure event should be found")
+        else {
+            panic!("the start should have failed");
+        };
+        assert_eq!(err.to_string(), "failed to launch provider");
+        assert!(polls.load(std::sync::atomic::Ordering::SeqCst) > 0);
+    }
+
+    #[tokio::test]
+    async fn pull_failures_are_reported_separately_from_launch_failures() {
+        let failed = |phase: Option<&str>, error: &str| {
+            let mut data = json!({
+                "provider_ref": "ghcr.io/kv:0.1.0",
+                "provider_id": "kv",
+                "error": error,
+            });
+            if let Some(phase) = phase {
+                data["phase"] = phase.into();
+            }
+            event("provider_start_failed", data)
+        };
+        let failure = |event: Event| async move {
+            let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+            tx.send(event).await.unwrap();
+            let outcome = wait_for_provider_start_event_matching(
+                &mut rx,
+                Duration::from_secs(1),
+                HOST_ID.to_string(),
+                target("ghcr.io/kv:0.1.0", "kv", ProviderRefMatch::default()),
+            )
+            .await
+            .expect("the failure event should be found");
+            let FindEventOutcome::Failure(err) = outcome else {
+                panic!("the start should have failed");
+            };
+            ProviderStartError::from_failure(&err)
+        };
+
+        let err = failure(failed(
+            Some("pull"),
+            "failed to fetch provider: timed out after 5000ms pulling provider",
+        ))
+        .await;
+        assert_eq!(err.phase, ProviderStartPhase::Pull);
+        assert_eq!(err.class, ProviderStartFailureClass::Timeout);
+
+        let err = failure(failed(
+            Some("pull"),
+            "failed to fetch provider: manifest unknown",
+        ))
+        .await;
+        assert_eq!(err.phase, ProviderStartPhase::Pull);
+        assert_eq!(err.class, ProviderStartFailureClass::ImagePull);
+
+        let err = failure(failed(Some("launch"), "failed to initialize provider")).await;
+        assert_eq!(err.phase, ProviderStartPhase::Launch);
+        assert_eq!(err.message, "failed to initialize provider");
+
+        // Hosts that don't report the phase have every failure reported as a launch failure
+        let err = failure(failed(None, "failed to fetch provider: manifest unknown")).await;
+        assert_eq!(err.phase, ProviderStartPhase::Launch);
+        assert_eq!(err.class, ProviderStartFailureClass::ImagePull);
+    }
+}
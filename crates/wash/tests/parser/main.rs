@@ -1,4 +1,8 @@
-use std::{collections::HashSet, fs, path::PathBuf};
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
 
 use claims::{assert_err, assert_ok};
 use semver::Version;
@@ -561,3 +565,29 @@ async fn separate_project_paths() {
         && target_path == PathBuf::from("./target")
     ));
 }
+
+#[tokio::test]
+async fn build_command_component() {
+    let result = load_config(
+        Some(PathBuf::from(
+            "./tests/parser/files/build_command_component.toml",
+        )),
+        None,
+    )
+    .await;
+    let config = assert_ok!(result);
+
+    assert_eq!(config.language, LanguageConfig::Other("python".into()));
+    assert!(matches!(
+        config.project_type,
+        TypeConfig::Component(ComponentConfig {
+            build_command: Some(build_command),
+            build_artifact: Some(build_artifact),
+            build_env,
+            ..
+        }) if build_command
+            == "componentize-py --wit-path wit --world hello componentize app -o build/app.wasm"
+            && build_artifact == Path::new("build/app.wasm")
+            && build_env == [("PYTHONPATH".into(), "src".into())].into()
+    ));
+}
@@ -1,6 +1,6 @@
 mod common;
 
-use common::TestWashInstance;
+use common::{TestWashInstance, PROVIDER_HTTPSERVER_OCI_REF};
 
 use anyhow::{bail, Context, Result};
 use serial_test::serial;
@@ -217,3 +217,65 @@ async fn integration_get_host_inventory_labels_sorted_serial() -> Result<()> {
     );
     Ok(())
 }
+
+#[tokio::test]
+#[serial]
+#[cfg_attr(not(can_reach_ghcr_io), ignore = "ghcr.io is not reachable")]
+async fn integration_get_providers_by_interface_serial() -> Result<()> {
+    let wash_instance = TestWashInstance::create().await?;
+    let nats_port = wash_instance.nats_port.to_string();
+
+    wash_instance
+        .start_provider(PROVIDER_HTTPSERVER_OCI_REF, "httpserver_by_interface")
+        .await?;
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wash"))
+        .args([
+            "link",
+            "put",
+            "echo",
+            "httpserver_by_interface",
+            "wasi",
+            "http",
+            "--interface",
+            "incoming-handler",
+            "--ctl-port",
+            &nats_port,
+        ])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .context("failed to put link")?;
+    assert!(output.status.success(), "put link");
+
+    let get_providers = |args: &[&str]| {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_wash"));
+        cmd.args(["get", "providers"])
+            .args(args)
+            .args(["--output", "json", "--ctl-port", &nats_port])
+            .kill_on_drop(true);
+        cmd
+    };
+
+    let output = get_providers(&["--interface", "wasi:http/incoming-handler"])
+        .output()
+        .await
+        .context("failed to get providers")?;
+    assert!(output.status.success(), "executed get providers");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let providers = json["providers"].as_array().context("missing providers")?;
+    assert_eq!(providers.len(), 1);
+    assert_eq!(providers[0]["provider_id"], "httpserver_by_interface");
+    assert_eq!(providers[0]["host_id"], wash_instance.host_id.as_str());
+    assert_eq!(providers[0]["interfaces"][0], "wasi:http/incoming-handler");
+
+    let output = get_providers(&["--namespace", "wasi", "--package", "keyvalue"])
+        .output()
+        .await
+        .context("failed to get providers")?;
+    assert!(output.status.success(), "executed filtered get providers");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["providers"].as_array().map(Vec::len), Some(0));
+
+    Ok(())
+}
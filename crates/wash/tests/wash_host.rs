@@ -0,0 +1,122 @@
+mod common;
+
+use common::{TestWashInstance, HELLO_OCI_REF, HTTP_JSONIFY_OCI_REF};
+
+use anyhow::{Context, Result};
+use serial_test::serial;
+use tokio::process::Command;
+use wash::lib::cli::output::GetHostInventoriesCommandOutput;
+
+async fn host_inventory(
+    wash_instance: &TestWashInstance,
+) -> Result<wasmcloud_control_interface::HostInventory> {
+    let output = Command::new(env!("CARGO_BIN_EXE_wash"))
+        .args([
+            "get",
+            "inventory",
+            "--output",
+            "json",
+            "--timeout-ms",
+            "2000",
+            "--ctl-port",
+            &wash_instance.nats_port.to_string(),
+        ])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .context("failed to get host inventory")?;
+    assert!(output.status.success(), "checked host inventory");
+    let cmd_output: GetHostInventoriesCommandOutput =
+        serde_json::from_slice(&output.stdout).context("failed to parse output")?;
+    cmd_output
+        .inventories
+        .into_iter()
+        .next()
+        .context("no inventory returned")
+}
+
+#[tokio::test]
+#[serial]
+#[cfg_attr(not(can_reach_ghcr_io), ignore = "ghcr.io is not reachable")]
+async fn integration_host_drain_serial() -> Result<()> {
+    let wash_instance = TestWashInstance::create().await?;
+    let nats_port = wash_instance.nats_port.to_string();
+
+    for (component_ref, component_id) in [
+        (HELLO_OCI_REF, "hello_component_id"),
+        (HTTP_JSONIFY_OCI_REF, "jsonify_component_id"),
+    ] {
+        let output = Command::new(env!("CARGO_BIN_EXE_wash"))
+            .args([
+                "scale",
+                "component",
+                "--host-id",
+                wash_instance.host_id.as_str(),
+                component_ref,
+                component_id,
+                "--max",
+                "2",
+                "--output",
+                "json",
+                "--timeout-ms",
+                "40000",
+                "--ctl-port",
+                &nats_port,
+            ])
+            .kill_on_drop(true)
+            .output()
+            .await
+            .context("failed to scale component")?;
+        assert!(output.status.success(), "executed scale");
+    }
+    assert_eq!(host_inventory(&wash_instance).await?.components().len(), 2);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wash"))
+        .args([
+            "host",
+            "drain",
+            wash_instance.host_id.as_str(),
+            "--cordon",
+            "--wait-timeout-ms",
+            "20000",
+            "--output",
+            "json",
+            "--ctl-port",
+            &nats_port,
+        ])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .context("failed to drain host")?;
+    assert!(output.status.success(), "executed drain");
+
+    let cmd_output: serde_json::Value =
+        serde_json::from_slice(&output.stdout).context("failed to parse output")?;
+    assert_eq!(cmd_output["partial"], false);
+    assert_eq!(cmd_output["cordoned"], true);
+    let drained = cmd_output["drained"]
+        .as_array()
+        .context("drained should be a list")?;
+    assert_eq!(drained.len(), 2);
+    assert!(drained
+        .iter()
+        .all(|step| step["kind"] == "component" && step["error"].is_null()));
+
+    // Scaling down is confirmed by an event, but give the inventory a moment to catch up
+    for retries in 0..5 {
+        let inventory = host_inventory(&wash_instance).await?;
+        if inventory.components().is_empty() {
+            assert_eq!(
+                inventory.labels().get("unschedulable").map(String::as_str),
+                Some("true")
+            );
+            return Ok(());
+        }
+        if retries == 4 {
+            panic!("host should be drained, found {:?}", inventory.components());
+        }
+        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+    }
+
+    Ok(())
+}
@@ -35,6 +35,7 @@ async fn integration_label_host_serial() -> Result<()> {
         cmd_output.processed,
         vec![(String::from("key1"), String::from("value1"))],
     );
+    assert!(cmd_output.waited, "waited for the host to report the label");
     Ok(())
 }
 
@@ -63,5 +64,6 @@ async fn integration_label_host_no_hostcore_serial() -> Result<()> {
 
     assert!(!cmd_output.deleted);
     assert!(cmd_output.processed.is_empty());
+    assert!(!cmd_output.waited);
     Ok(())
 }
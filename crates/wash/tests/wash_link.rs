@@ -134,3 +134,212 @@ async fn integration_link_del_all_serial() -> Result<()> {
 
     Ok(())
 }
+
+/// Ensure wash can delete every link from a single source
+#[tokio::test]
+#[serial]
+async fn integration_link_del_all_for_source_serial() -> Result<()> {
+    let wash = TestWashInstance::create().await?;
+    let nats_port = wash.nats_port.to_string();
+
+    const LINKS: [(&str, &str, &str, &str, &str); 3] = [
+        ("src", "dst", "wasi", "http", "incoming-handler"),
+        ("src", "dst", "wasmcloud", "messaging", "consumer"),
+        ("other", "dst", "wasi", "http", "incoming-handler"),
+    ];
+    for (src, dest, ns, pkg, iface) in LINKS {
+        let output = Command::new(env!("CARGO_BIN_EXE_wash"))
+            .args([
+                "link",
+                "put",
+                src,
+                dest,
+                ns,
+                pkg,
+                "--interface",
+                iface,
+                "--ctl-port",
+                &nats_port,
+            ])
+            .kill_on_drop(true)
+            .output()
+            .await
+            .context("failed to put link")?;
+        assert!(output.status.success(), "put link");
+    }
+
+    let delete = |dry_run: bool| {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_wash"));
+        cmd.args([
+            "link",
+            "delete",
+            "--all-for-source",
+            "src",
+            "--output",
+            "json",
+            "--ctl-port",
+            &nats_port,
+        ])
+        .kill_on_drop(true);
+        if dry_run {
+            cmd.arg("--dry-run");
+        }
+        cmd
+    };
+
+    let output = delete(true)
+        .output()
+        .await
+        .context("failed to dry-run delete")?;
+    assert!(output.status.success(), "dry-run delete succeeded");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["deleted"].as_array().map(Vec::len), Some(2));
+
+    let output = delete(false)
+        .output()
+        .await
+        .context("failed to delete links")?;
+    assert!(output.status.success(), "delete succeeded");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["deleted"].as_array().map(Vec::len), Some(2));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wash"))
+        .args([
+            "link",
+            "query",
+            "--output",
+            "json",
+            "--ctl-port",
+            &nats_port,
+        ])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .context("failed to query links")?;
+    let cmd_output: LinkQueryCommandOutput = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+        cmd_output.links.len(),
+        1,
+        "only the other source's link remains"
+    );
+    assert_eq!(cmd_output.links[0].source_id(), "other");
+
+    Ok(())
+}
+
+/// Ensure putting a link reports whether it was created, updated or already present
+#[tokio::test]
+#[serial]
+async fn integration_link_put_outcome_serial() -> Result<()> {
+    let wash = TestWashInstance::create().await?;
+    let nats_port = wash.nats_port.to_string();
+
+    let put = |target: &'static str, interfaces: &'static [&'static str]| {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_wash"));
+        cmd.args(["link", "put", "src", target, "wasi", "keyvalue"]);
+        for interface in interfaces {
+            cmd.args(["--interface", interface]);
+        }
+        cmd.args(["--output", "json", "--ctl-port", &nats_port])
+            .kill_on_drop(true);
+        cmd
+    };
+    let outcome = |output: std::process::Output| -> Result<serde_json::Value> {
+        assert!(output.status.success(), "put link");
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        Ok(json["outcome"].clone())
+    };
+
+    let output = put("kv-redis", &["store"]).output().await?;
+    assert_eq!(outcome(output)?, "created");
+    let output = put("kv-redis", &["store"]).output().await?;
+    assert_eq!(outcome(output)?, "unchanged");
+    let output = put("kv-redis", &["store", "atomics"]).output().await?;
+    assert_eq!(outcome(output)?, "updated");
+
+    let output = put("kv-nats", &["atomics"]).output().await?;
+    assert!(
+        !output.status.success(),
+        "overlapping link to another target should be rejected"
+    );
+
+    Ok(())
+}
+
+/// Ensure links can be applied from a file and exported back into one
+#[tokio::test]
+#[serial]
+async fn integration_link_apply_export_serial() -> Result<()> {
+    let wash = TestWashInstance::create().await?;
+    let nats_port = wash.nats_port.to_string();
+    let dir = tempfile::tempdir()?;
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wash"))
+        .args(["link", "put", "src", "kv-nats", "wasi", "keyvalue"])
+        .args(["--interface", "batch", "--ctl-port", &nats_port])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .context("failed to put link")?;
+    assert!(output.status.success(), "put link");
+
+    let file = dir.path().join("links.yaml");
+    tokio::fs::write(
+        &file,
+        r#"links:
+- source_id: src
+  target: kv-redis
+  wit_namespace: wasi
+  wit_package: keyvalue
+  interfaces: [store, atomics]
+"#,
+    )
+    .await?;
+    let apply = |file: &std::path::Path, prune: bool| {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_wash"));
+        cmd.args(["link", "apply"])
+            .arg(file)
+            .args(["--output", "json", "--ctl-port", &nats_port])
+            .kill_on_drop(true);
+        if prune {
+            cmd.arg("--prune");
+        }
+        cmd
+    };
+
+    let output = apply(&file, false).output().await?;
+    assert!(output.status.success(), "apply links");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["added"].as_array().map(Vec::len), Some(1));
+    assert_eq!(json["kept"].as_array().map(Vec::len), Some(1));
+
+    let output = apply(&file, true).output().await?;
+    assert!(output.status.success(), "apply links with prune");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["added"].as_array().map(Vec::len), Some(0));
+    assert_eq!(json["removed"].as_array().map(Vec::len), Some(1));
+
+    let exported = dir.path().join("exported.yaml");
+    let output = Command::new(env!("CARGO_BIN_EXE_wash"))
+        .args(["link", "export", "--file"])
+        .arg(&exported)
+        .args(["--ctl-port", &nats_port])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .context("failed to export links")?;
+    assert!(output.status.success(), "export links");
+    let links = wash::lib::cli::link::load_links_snapshot(&exported)?;
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].target(), "kv-redis");
+
+    // Applying the export is a no-op
+    let output = apply(&exported, true).output().await?;
+    assert!(output.status.success(), "apply exported links");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["added"].as_array().map(Vec::len), Some(0));
+    assert_eq!(json["removed"].as_array().map(Vec::len), Some(0));
+    assert_eq!(json["changed"].as_array().map(Vec::len), Some(0));
+
+    Ok(())
+}
@@ -27,6 +27,91 @@ fn integration_par_create_and_insert() {
 
     remove_dir_all(test_dir).unwrap();
 }
+
+#[test]
+fn integration_par_create_multi_target() {
+    const SUBFOLDER: &str = "par_create_multi_target";
+    const ISSUER: &str = "SAACTTUPKR55VUWUDK7GJ5SU5KGED455FR7BDO46RUVOTHUWKBLECLH2UU";
+    const SUBJECT: &str = "SVAOZUSBWWFL65P255DOHIETPTXUQMM5ETLSYPITI5G4K4HI6M2CDAPWAU";
+    let test_dir = test_dir_with_subfolder(SUBFOLDER);
+    let linux = test_dir_file(SUBFOLDER, "provider-linux");
+    let macos = test_dir_file(SUBFOLDER, "provider-macos");
+    File::create(&linux).unwrap().write_all(b"linux").unwrap();
+    File::create(&macos).unwrap().write_all(b"macos").unwrap();
+    let archive = test_dir_file(SUBFOLDER, "multi.par.gz");
+
+    let create = wash()
+        .args([
+            "par",
+            "create",
+            "-b",
+            &format!("x86_64-linux={}", linux.display()),
+            "-b",
+            &format!("darwin/arm64={}", macos.display()),
+            "-n",
+            "Multi",
+            "-v",
+            "TestRunner",
+            "--compress",
+            "--issuer",
+            ISSUER,
+            "--subject",
+            SUBJECT,
+            "--disable-keygen",
+            "--destination",
+            archive.to_str().unwrap(),
+            "-o",
+            "json",
+        ])
+        .output()
+        .expect("failed to create provider archive file");
+    assert!(create.status.success());
+
+    let inspect_created = wash()
+        .args(["par", "inspect", archive.to_str().unwrap(), "-o", "json"])
+        .output()
+        .expect("failed to inspect created provider archive file");
+    assert!(inspect_created.status.success());
+    let output = get_json_output(inspect_created).unwrap();
+    let mut targets = output["targets"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t.as_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+    targets.sort();
+    assert_eq!(targets, ["aarch64-macos", "x86_64-linux"]);
+
+    // The same target can't be given twice
+    let duplicate = wash()
+        .args([
+            "par",
+            "create",
+            "-a",
+            "x86_64-linux",
+            "-b",
+            linux.to_str().unwrap(),
+            "-b",
+            &format!("linux/amd64={}", macos.display()),
+            "-n",
+            "Multi",
+            "-v",
+            "TestRunner",
+            "--issuer",
+            ISSUER,
+            "--subject",
+            SUBJECT,
+            "--disable-keygen",
+            "--destination",
+            archive.to_str().unwrap(),
+        ])
+        .output()
+        .expect("failed to run par create");
+    assert!(!duplicate.status.success());
+
+    remove_dir_all(test_dir).unwrap();
+}
+
 #[test]
 #[cfg_attr(not(can_reach_ghcr_io), ignore = "ghcr.io is not reachable")]
 fn integration_par_inspect() {
@@ -0,0 +1,39 @@
+use anyhow::{Context, Result};
+use serial_test::serial;
+
+mod common;
+use common::TestWashInstance;
+
+#[tokio::test]
+#[serial]
+async fn integration_provider_logs_without_forwarding_serial() -> Result<()> {
+    let wash_instance = TestWashInstance::create().await?;
+
+    let output = wash_instance
+        .wash_cmd()
+        .args([
+            "provider",
+            "logs",
+            "not_running",
+            "--follow",
+            "--since",
+            "1m",
+            "--output",
+            "json",
+        ])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .context("failed to run wash provider logs")?;
+    assert!(
+        !output.status.success(),
+        "provider logs should fail without log forwarding"
+    );
+
+    let cmd_output: serde_json::Value =
+        serde_json::from_slice(&output.stderr).context("failed to parse output")?;
+    assert!(cmd_output["error"]
+        .as_str()
+        .is_some_and(|e| e.contains("Log forwarding not enabled")));
+    Ok(())
+}
@@ -84,3 +84,206 @@ async fn integration_scale_component_serial() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+#[serial]
+#[cfg_attr(not(can_reach_ghcr_io), ignore = "ghcr.io is not reachable")]
+async fn integration_scale_apply_serial() -> Result<()> {
+    let wash_instance = TestWashInstance::create().await?;
+    let nats_port = wash_instance.nats_port.to_string();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wash"))
+        .args([
+            "scale",
+            "component",
+            wash_instance.host_id.as_str(),
+            HELLO_OCI_REF,
+            "hello_component_id",
+            "--max",
+            "2",
+            "--output",
+            "json",
+            "--ctl-port",
+            &nats_port,
+        ])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .context("failed to scale component")?;
+    assert!(output.status.success(), "executed scale");
+
+    let counts_file = wash_instance.test_dir().join("counts.yaml");
+    tokio::fs::write(
+        &counts_file,
+        format!("{HELLO_OCI_REF}:\n  {}: 5\n", wash_instance.host_id),
+    )
+    .await?;
+
+    let apply = |dry_run: bool| {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_wash"));
+        cmd.args(["scale", "apply", "--file"])
+            .arg(&counts_file)
+            .args(["--output", "json", "--ctl-port", &nats_port])
+            .kill_on_drop(true);
+        if dry_run {
+            cmd.arg("--dry-run");
+        }
+        cmd
+    };
+
+    let output = apply(true)
+        .output()
+        .await
+        .context("failed to dry-run apply")?;
+    assert!(output.status.success(), "executed dry-run apply");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["actions"][0]["current"], 2);
+    assert_eq!(json["actions"][0]["desired"], 5);
+
+    let output = apply(false)
+        .output()
+        .await
+        .context("failed to apply counts")?;
+    assert!(output.status.success(), "executed apply");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wash"))
+        .args([
+            "get",
+            "inventory",
+            "--output",
+            "json",
+            "--ctl-port",
+            &nats_port,
+        ])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .context("failed to get host inventory")?;
+    let cmd_output: GetHostInventoriesCommandOutput =
+        serde_json::from_slice(&output.stdout).context("failed to parse output")?;
+    let components = cmd_output.inventories[0].components().clone();
+    assert_eq!(components.len(), 1);
+    assert_eq!(components[0].id(), "hello_component_id");
+    assert_eq!(components[0].max_instances(), 5);
+
+    // Once converged, applying again is a no-op
+    let output = apply(false)
+        .output()
+        .await
+        .context("failed to apply counts")?;
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["actions"].as_array().map(Vec::len), Some(0));
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+#[cfg_attr(not(can_reach_ghcr_io), ignore = "ghcr.io is not reachable")]
+async fn integration_scale_status_serial() -> Result<()> {
+    let wash_instance = TestWashInstance::create().await?;
+    let nats_port = wash_instance.nats_port.to_string();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wash"))
+        .args([
+            "scale",
+            "component",
+            wash_instance.host_id.as_str(),
+            HELLO_OCI_REF,
+            "hello_component_id",
+            "--max",
+            "3",
+            "--output",
+            "json",
+            "--ctl-port",
+            &nats_port,
+        ])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .context("failed to scale component")?;
+    assert!(output.status.success(), "executed scale");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wash"))
+        .args([
+            "scale",
+            "status",
+            HELLO_OCI_REF,
+            "--window",
+            "100ms",
+            "--output",
+            "json",
+            "--ctl-port",
+            &nats_port,
+        ])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .context("failed to get scale status")?;
+    assert!(output.status.success(), "executed scale status");
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["total_instances"], 3);
+    assert_eq!(json["hosts"][0]["host_id"], wash_instance.host_id.as_str());
+    assert_eq!(json["hosts"][0]["component_id"], "hello_component_id");
+    assert_eq!(json["hosts"][0]["max_instances"], 3);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+#[cfg_attr(not(can_reach_ghcr_io), ignore = "ghcr.io is not reachable")]
+async fn integration_scale_component_auction_serial() -> Result<()> {
+    let wash_instance = TestWashInstance::create().await?;
+    let nats_port = wash_instance.nats_port.to_string();
+
+    let scale = |constraint: Option<&str>| {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_wash"));
+        cmd.args([
+            "scale",
+            "component",
+            HELLO_OCI_REF,
+            "hello_component_id",
+            "--max",
+            "4",
+            "--wait-for-ready",
+            "--wait-timeout-ms",
+            "40000",
+            "--output",
+            "json",
+            "--ctl-port",
+            &nats_port,
+        ])
+        .kill_on_drop(true);
+        if let Some(constraint) = constraint {
+            cmd.args(["--constraint", constraint]);
+        }
+        cmd
+    };
+
+    // No host answers an auction it doesn't match the constraints of
+    let output = scale(Some("zone=nowhere"))
+        .output()
+        .await
+        .context("failed to scale component")?;
+    assert!(
+        !output.status.success(),
+        "no host should match the constraint"
+    );
+
+    // Without a host ID, the only host in the lattice wins the auction
+    let output = scale(None)
+        .output()
+        .await
+        .context("failed to scale component")?;
+    assert!(output.status.success(), "executed scale");
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["success"], true);
+    assert_eq!(json["host_id"], wash_instance.host_id.as_str());
+    assert_eq!(json["component_id"], "hello_component_id");
+    assert_eq!(json["max_instances"], 4);
+
+    Ok(())
+}
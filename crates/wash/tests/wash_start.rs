@@ -1,5 +1,9 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serial_test::serial;
+use tokio::process::Command;
+use wash::lib::cli::output::LinkQueryCommandOutput;
+use wash::lib::cli::CliConnectionOpts;
+use wash::lib::config::{close_ctl_client, WashConnectionOptions};
 
 mod common;
 use common::{TestWashInstance, HELLO_OCI_REF, PROVIDER_HTTPSERVER_OCI_REF};
@@ -37,3 +41,153 @@ async fn integration_start_stop_provider_serial() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+#[serial]
+async fn integration_start_provider_rolls_back_links_serial() -> Result<()> {
+    let wash_instance = TestWashInstance::create().await?;
+    let nats_port = wash_instance.nats_port.to_string();
+
+    let put_link = |source: &str, target: &str, interfaces: &[&str]| {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_wash"));
+        cmd.args(["link", "put", source, target, "wasi", "keyvalue"])
+            .args(interfaces.iter().flat_map(|i| ["--interface", i]))
+            .args(["--ctl-port", &nats_port])
+            .kill_on_drop(true);
+        cmd
+    };
+    // `echo` is linked exactly as the start requests, `other` is linked on more interfaces than
+    // the start requests and `fresh` only has a link to another target under the same key
+    for (source, target, interfaces) in [
+        ("echo", "missing_provider", &["store"][..]),
+        ("other", "missing_provider", &["atomics", "store"][..]),
+        ("fresh", "kv_provider", &["batch"][..]),
+    ] {
+        let output = put_link(source, target, interfaces)
+            .output()
+            .await
+            .context("failed to put link")?;
+        assert!(output.status.success(), "put link from {source}");
+    }
+
+    // The provider can't be fetched, so the start fails after the links have been put
+    let output = Command::new(env!("CARGO_BIN_EXE_wash"))
+        .args([
+            "start",
+            "provider",
+            "/does/not/exist/provider.par",
+            "missing_provider",
+            "--host-id",
+            &wash_instance.host_id,
+            "--link",
+            "echo=wasi:keyvalue/store",
+            "--link",
+            "other=wasi:keyvalue/atomics",
+            "--link",
+            "fresh=wasi:keyvalue/atomics",
+            "--output",
+            "json",
+            "--ctl-port",
+            &nats_port,
+        ])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .context("failed to start provider")?;
+    assert!(!output.status.success(), "provider start should fail");
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr)?;
+    assert_eq!(error["rolled_back_links"].as_array().map(Vec::len), Some(1));
+    assert_eq!(error["restored_links"].as_array().map(Vec::len), Some(1));
+    assert_eq!(
+        error["failed_link_rollbacks"].as_array().map(Vec::len),
+        Some(0)
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wash"))
+        .args([
+            "link",
+            "query",
+            "--output",
+            "json",
+            "--ctl-port",
+            &nats_port,
+        ])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .context("failed to query links")?;
+    let cmd_output: LinkQueryCommandOutput = serde_json::from_slice(&output.stdout)?;
+    let mut links = cmd_output
+        .links
+        .iter()
+        .map(|link| {
+            let mut interfaces = link.interfaces().clone();
+            interfaces.sort();
+            (link.source_id(), link.target(), interfaces)
+        })
+        .collect::<Vec<_>>();
+    links.sort();
+    assert_eq!(
+        links,
+        vec![
+            ("echo", "missing_provider", vec!["store".to_string()]),
+            ("fresh", "kv_provider", vec!["batch".to_string()]),
+            (
+                "other",
+                "missing_provider",
+                vec!["atomics".to_string(), "store".to_string()]
+            ),
+        ],
+        "only the links the start created should be rolled back"
+    );
+
+    Ok(())
+}
+
+/// Wait until the connection of `nc` refuses new subscriptions, which it does once it is closed
+async fn wait_for_closed(nc: &async_nats::Client) -> Result<()> {
+    tokio::time::timeout(std::time::Duration::from_secs(5), async {
+        while nc.subscribe("wash.test.closed").await.is_ok() {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .context("connection should have been closed")
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_ctl_client_is_closed_serial() -> Result<()> {
+    let wash_instance = TestWashInstance::create().await?;
+    let opts = CliConnectionOpts {
+        ctl_port: Some(wash_instance.nats_port.to_string()),
+        ..Default::default()
+    };
+
+    // Closed explicitly once a handler is done with it
+    let wco: WashConnectionOptions = opts.clone().try_into()?;
+    let client = wco.into_ctl_client(None).await?;
+    let nc = client.nats_client();
+    client
+        .get_hosts()
+        .await
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+    close_ctl_client(&client).await;
+    wait_for_closed(&nc).await?;
+
+    // Closed after going idle
+    let wco: WashConnectionOptions = CliConnectionOpts {
+        idle_timeout_ms: Some(200),
+        ..opts
+    }
+    .try_into()?;
+    let client = wco.into_ctl_client(None).await?;
+    let nc = client.nats_client();
+    client
+        .get_hosts()
+        .await
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+    wait_for_closed(&nc).await?;
+
+    Ok(())
+}
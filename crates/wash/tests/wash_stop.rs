@@ -82,3 +82,36 @@ async fn integration_stop_host_serial() -> Result<()> {
     wash_instance.stop_host().await?;
     Ok(())
 }
+
+#[tokio::test]
+#[serial]
+async fn integration_stop_host_dry_run_serial() -> Result<()> {
+    wait_for_no_hosts()
+        .await
+        .context("unexpected wasmcloud instance(s) running")?;
+    let wash_instance = TestWashInstance::create_with_extra_args(["--disable-wadm"]).await?;
+
+    let output = wash_instance
+        .wash_cmd()
+        .args([
+            "stop",
+            "host",
+            &wash_instance.host_id,
+            "--dry-run",
+            "--output",
+            "json",
+        ])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .context("failed to dry-run stop host")?;
+    assert!(output.status.success(), "dry-run stop host succeeded");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["dry_run"], true);
+    assert_eq!(json["host_id"], wash_instance.host_id.as_str());
+    assert_eq!(json["action"], "stop host");
+
+    // The host was left running, so it can still be stopped for real
+    wash_instance.stop_host().await?;
+    Ok(())
+}
@@ -394,6 +394,15 @@ struct Args {
     )]
     /// Determines whether capability provider auctions should be enabled (defaults to true)
     enable_provider_auction: Option<bool>,
+
+    /// Publish the output of binary providers on the lattice so it can be followed with `wash provider logs`.
+    /// Any lattice subscriber can read it, so only enable this when provider output holds no secrets
+    #[clap(
+        long = "forward-provider-logs",
+        default_value_t = false,
+        env = "WASMCLOUD_FORWARD_PROVIDER_LOGS"
+    )]
+    forward_provider_logs: bool,
 }
 
 const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
@@ -620,6 +629,7 @@ async fn main() -> anyhow::Result<()> {
             http_admin: args.http_admin,
             enable_component_auction: args.enable_component_auction.unwrap_or(true),
             enable_provider_auction: args.enable_provider_auction.unwrap_or(true),
+            forward_provider_logs: args.forward_provider_logs,
         })
         .await?;
     let (host, shutdown) = host_builder
@@ -0,0 +1,132 @@
+#![cfg(feature = "provider-http-server")]
+
+use core::time::Duration;
+
+use anyhow::Context as _;
+use tokio::time::timeout;
+use wasmcloud_test_util::host::WasmCloudTestHost;
+use wasmcloud_test_util::provider::{assert_start_provider, StartProviderArgs};
+
+pub mod common;
+use common::nats::start_nats;
+use common::providers;
+
+const LATTICE: &str = "default";
+
+#[tokio::test]
+async fn provider_logs_are_published_by_the_host() -> anyhow::Result<()> {
+    let (nats_server, nats_url, nats_client) = start_nats(None, true)
+        .await
+        .map(|res| (res.0, res.1, res.2.unwrap()))
+        .context("failed to start NATS")?;
+
+    let ctl_client = wasmcloud_control_interface::ClientBuilder::new(nats_client)
+        .lattice(LATTICE.to_string())
+        .build();
+    let host = WasmCloudTestHost::start_forwarding_provider_logs(&nats_url, LATTICE)
+        .await
+        .context("failed to start test host")?;
+    let hosts = ctl_client
+        .get_hosts()
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("failed to get hosts")?;
+    assert!(hosts
+        .iter()
+        .filter_map(|host| host.data())
+        .all(wasmcloud_control_interface::Host::provider_log_forwarding));
+    let host_id = host.host_key().public_key();
+
+    let rust_http_server = providers::rust_http_server().await;
+    let rust_http_server_id = rust_http_server.subject.public_key();
+
+    // Only lines written after subscribing are received, so subscribe before starting
+    let mut lines = ctl_client
+        .provider_logs_receiver(&rust_http_server_id, Some(&host_id))
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("failed to subscribe to provider logs")?;
+
+    assert_start_provider(StartProviderArgs {
+        client: &ctl_client,
+        host_id: &host_id,
+        provider_id: &rust_http_server_id,
+        provider_ref: rust_http_server.url().as_str(),
+        config: vec![],
+    })
+    .await
+    .context("failed to start provider")?;
+
+    let line = timeout(Duration::from_secs(30), lines.recv())
+        .await
+        .context("provider did not write a line in time")?
+        .context("provider log stream closed")?;
+    assert_eq!(line.host_id(), host_id);
+    assert_eq!(line.provider_id(), rust_http_server_id);
+    assert!(!line.line().is_empty());
+    assert!(line.seq() > 0);
+
+    // The host holds on to the lines it published
+    let history = ctl_client
+        .get_provider_log_history(&rust_http_server_id, &host_id, None)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("failed to get provider log history")?;
+    assert!(history.contains(&line));
+
+    nats_server.stop().await.context("failed to stop NATS")?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn provider_logs_are_not_published_by_default() -> anyhow::Result<()> {
+    let (nats_server, nats_url, nats_client) = start_nats(None, true)
+        .await
+        .map(|res| (res.0, res.1, res.2.unwrap()))
+        .context("failed to start NATS")?;
+
+    let ctl_client = wasmcloud_control_interface::ClientBuilder::new(nats_client)
+        .lattice(LATTICE.to_string())
+        .build();
+    let host = WasmCloudTestHost::start(&nats_url, LATTICE)
+        .await
+        .context("failed to start test host")?;
+    let host_id = host.host_key().public_key();
+
+    let hosts = ctl_client
+        .get_hosts()
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("failed to get hosts")?;
+    assert!(hosts
+        .iter()
+        .filter_map(|host| host.data())
+        .all(|host| !host.provider_log_forwarding()));
+
+    let rust_http_server = providers::rust_http_server().await;
+    let rust_http_server_id = rust_http_server.subject.public_key();
+
+    let mut lines = ctl_client
+        .provider_logs_receiver(&rust_http_server_id, Some(&host_id))
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("failed to subscribe to provider logs")?;
+
+    assert_start_provider(StartProviderArgs {
+        client: &ctl_client,
+        host_id: &host_id,
+        provider_id: &rust_http_server_id,
+        provider_ref: rust_http_server.url().as_str(),
+        config: vec![],
+    })
+    .await
+    .context("failed to start provider")?;
+
+    assert!(
+        timeout(Duration::from_secs(5), lines.recv()).await.is_err(),
+        "host published provider output without log forwarding enabled"
+    );
+
+    nats_server.stop().await.context("failed to stop NATS")?;
+    Ok(())
+}
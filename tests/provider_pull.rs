@@ -0,0 +1,89 @@
+use core::time::Duration;
+
+use anyhow::{ensure, Context as _};
+use tokio::time::timeout;
+use wasmcloud_control_interface::Client;
+use wasmcloud_test_util::host::WasmCloudTestHost;
+
+pub mod common;
+use common::nats::start_nats;
+
+const LATTICE: &str = "default";
+
+/// Start a provider and return the data of the `provider_start_failed` event the host publishes
+async fn start_failure(
+    ctl_client: &Client,
+    host_id: &str,
+    provider_ref: &str,
+    pull_timeout: Duration,
+) -> anyhow::Result<serde_json::Value> {
+    let mut events = ctl_client
+        .events_receiver(vec!["provider_start_failed".into()])
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("failed to subscribe to events")?;
+    let ack = ctl_client
+        .start_provider_with_pull_timeout(
+            host_id,
+            provider_ref,
+            "provider",
+            None,
+            vec![],
+            pull_timeout,
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("failed to start provider")?;
+    ensure!(ack.succeeded(), "start was not acknowledged");
+
+    let event = timeout(Duration::from_secs(30), events.recv())
+        .await
+        .context("no start failure in time")?
+        .context("event stream closed")?;
+    let mut event = serde_json::to_value(event).context("failed to serialize event")?;
+    Ok(event["data"].take())
+}
+
+#[tokio::test]
+async fn provider_pull_failures_are_reported_separately_from_launch_failures() -> anyhow::Result<()>
+{
+    let (nats_server, nats_url, nats_client) = start_nats(None, true)
+        .await
+        .map(|res| (res.0, res.1, res.2.unwrap()))
+        .context("failed to start NATS")?;
+
+    let ctl_client = wasmcloud_control_interface::ClientBuilder::new(nats_client)
+        .lattice(LATTICE.to_string())
+        .build();
+    let host = WasmCloudTestHost::start(&nats_url, LATTICE)
+        .await
+        .context("failed to start test host")?;
+    let host_id = host.host_key().public_key();
+
+    // Nothing listens on this port, so the pull fails
+    let failed = start_failure(
+        &ctl_client,
+        &host_id,
+        "localhost:1/wasmcloud/nope:0.1.0",
+        Duration::from_secs(10),
+    )
+    .await?;
+    assert_eq!(failed["phase"], "pull");
+    assert!(failed["error"]
+        .as_str()
+        .is_some_and(|error| error.starts_with("failed to fetch provider")));
+
+    // There is nothing to pull for a builtin, which then fails to launch
+    let failed = start_failure(
+        &ctl_client,
+        &host_id,
+        "wasmcloud+builtin://nope",
+        Duration::from_secs(10),
+    )
+    .await?;
+    assert_eq!(failed["phase"], "launch");
+    assert_eq!(failed["error"], "unknown builtin name: nope");
+
+    nats_server.stop().await.context("failed to stop NATS")?;
+    Ok(())
+}